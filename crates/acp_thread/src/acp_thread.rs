@@ -4711,6 +4711,8 @@ mod tests {
                     vec![],
                     0,
                     false,
+                    None,
+                    false,
                     0,
                     Some(completion_tx),
                     cx,
@@ -74,6 +74,11 @@ pub enum MentionUri {
         source: String,
         skill_file_path: PathBuf,
     },
+    ContextServerResource {
+        server_id: String,
+        uri: Url,
+        name: String,
+    },
 }
 
 impl MentionUri {
@@ -328,6 +333,18 @@ impl MentionUri {
                         source: source.context("missing skill source")?,
                         skill_file_path: skill_file_path.context("missing skill file path")?,
                     })
+                } else if path.starts_with("/agent/context-server-resource") {
+                    validate_query_params(&url, &["server", "uri", "name"])?;
+                    let server_id =
+                        query_param(&url, "server").context("Missing context server")?;
+                    let uri = query_param(&url, "uri").context("Missing resource URI")?;
+                    let uri = Url::parse(&uri).context("Parsing resource URI")?;
+                    let name = query_param(&url, "name").unwrap_or_else(|| uri.to_string());
+                    Ok(Self::ContextServerResource {
+                        server_id,
+                        uri,
+                        name,
+                    })
                 } else {
                     bail!("invalid zed url: {:?}", input);
                 }
@@ -371,6 +388,7 @@ impl MentionUri {
             } => selection_name(path.as_deref(), line_range),
             MentionUri::Fetch { url } => url.to_string(),
             MentionUri::Skill { name, .. } => name.clone(),
+            MentionUri::ContextServerResource { name, .. } => name.clone(),
         }
     }
 
@@ -434,6 +452,9 @@ impl MentionUri {
             MentionUri::Skill {
                 skill_file_path, ..
             } => Some(skill_file_path.to_string_lossy().into_owned().into()),
+            MentionUri::ContextServerResource { server_id, uri, .. } => {
+                Some(format!("{uri} ({server_id})").into())
+            }
             _ => None,
         }
     }
@@ -456,6 +477,7 @@ impl MentionUri {
             MentionUri::GitDiff { .. } => IconName::GitBranch.path().into(),
             MentionUri::MergeConflict { .. } => IconName::GitMergeConflict.path().into(),
             MentionUri::Skill { .. } => IconName::Sparkle.path().into(),
+            MentionUri::ContextServerResource { .. } => IconName::Server.path().into(),
         }
     }
 
@@ -587,6 +609,18 @@ impl MentionUri {
                     .append_pair("path", &skill_file_path.to_string_lossy());
                 url
             }
+            MentionUri::ContextServerResource {
+                server_id,
+                uri,
+                name,
+            } => {
+                let mut url = Url::parse("zed:///agent/context-server-resource").unwrap();
+                url.query_pairs_mut()
+                    .append_pair("server", server_id)
+                    .append_pair("uri", uri.as_str())
+                    .append_pair("name", name);
+                url
+            }
         }
     }
 }
@@ -890,6 +924,31 @@ mod tests {
         assert_eq!(parsed, skill_uri);
     }
 
+    #[test]
+    fn test_parse_context_server_resource_uri_round_trip() {
+        let mention = MentionUri::ContextServerResource {
+            server_id: "postgres".into(),
+            uri: Url::parse("postgres://db/users/schema?format=sql#columns").unwrap(),
+            name: "users & roles".into(),
+        };
+        let uri = mention.to_uri();
+        assert_eq!(uri.scheme(), "zed");
+        assert_eq!(
+            MentionUri::parse(uri.as_str(), PathStyle::local()).unwrap(),
+            mention
+        );
+        assert_eq!(mention.name(), "users & roles");
+
+        assert!(
+            MentionUri::parse(
+                "zed:///agent/context-server-resource?uri=note%3A1",
+                PathStyle::local()
+            )
+            .is_err(),
+            "The server is required to read the resource"
+        );
+    }
+
     #[test]
     fn test_parse_fetch_http_uri() {
         let http_uri = "http://example.com/path?query=value#fragment";
@@ -62,6 +62,9 @@ pub struct ActionLog {
     last_reject_undo: Option<LastRejectUndo>,
     /// Tracks the last time files were read by the agent, to detect external modifications
     file_read_times: HashMap<PathBuf, MTime>,
+    /// Commands tools ran outside of any buffer, in the order they finished, keeping the
+    /// last [`MAX_EXECUTED_COMMANDS`] of them
+    executed_commands: Vec<ExecutedCommand>,
 }
 
 impl ActionLog {
@@ -73,6 +76,7 @@ impl ActionLog {
             linked_action_log: None,
             last_reject_undo: None,
             file_read_times: HashMap::default(),
+            executed_commands: Vec::new(),
         }
     }
 
@@ -1039,6 +1043,42 @@ impl ActionLog {
             })
             .map(|(buffer, _)| buffer)
     }
+
+    /// Record a command a tool ran on the agent's behalf, such as a skill script.
+    pub fn command_executed(&mut self, command: ExecutedCommand, cx: &mut Context<Self>) {
+        if let Some(linked_action_log) = &self.linked_action_log {
+            linked_action_log.update(cx, |log, cx| {
+                log.command_executed(command.clone(), cx);
+            });
+        }
+        if self.executed_commands.len() == MAX_EXECUTED_COMMANDS {
+            self.executed_commands.remove(0);
+        }
+        self.executed_commands.push(command);
+        cx.notify();
+    }
+
+    /// Returns the most recent commands tools ran, in the order they finished.
+    pub fn executed_commands(&self) -> &[ExecutedCommand] {
+        &self.executed_commands
+    }
+}
+
+/// How many of the commands tools ran an action log remembers.
+const MAX_EXECUTED_COMMANDS: usize = 100;
+
+/// A command run by a tool, with the output it produced.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExecutedCommand {
+    /// What the command was run for, e.g. the skill whose script it is.
+    pub label: SharedString,
+    pub program: String,
+    pub args: Vec<String>,
+    pub cwd: Option<PathBuf>,
+    /// `None` when the command was killed by a signal or cancelled.
+    pub exit_code: Option<i32>,
+    /// Interleaved stdout and stderr, possibly truncated.
+    pub output: String,
 }
 
 #[derive(Default, Debug, Clone, Copy)]
@@ -3182,6 +3222,57 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_linked_action_log_command_executed(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({})).await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let parent_log = cx.new(|_| ActionLog::new(project.clone()));
+        let child_log =
+            cx.new(|_| ActionLog::new(project.clone()).with_linked_action_log(parent_log.clone()));
+
+        let command = ExecutedCommand {
+            label: "lint".into(),
+            program: "sh".into(),
+            args: vec!["scripts/lint.sh".into()],
+            cwd: Some(PathBuf::from(path!("/dir"))),
+            exit_code: Some(0),
+            output: "ok\n".into(),
+        };
+        child_log.update(cx, |log, cx| log.command_executed(command.clone(), cx));
+
+        child_log.read_with(cx, |log, _| {
+            assert_eq!(log.executed_commands(), [command.clone()]);
+        });
+        parent_log.read_with(cx, |log, _| {
+            assert_eq!(log.executed_commands(), [command.clone()]);
+        });
+
+        // Only the most recent commands are kept.
+        child_log.update(cx, |log, cx| {
+            for exit_code in 1..=MAX_EXECUTED_COMMANDS as i32 {
+                log.command_executed(
+                    ExecutedCommand {
+                        exit_code: Some(exit_code),
+                        ..command.clone()
+                    },
+                    cx,
+                );
+            }
+        });
+        parent_log.read_with(cx, |log, _| {
+            let commands = log.executed_commands();
+            assert_eq!(commands.len(), MAX_EXECUTED_COMMANDS);
+            assert_eq!(commands[0].exit_code, Some(1));
+            assert_eq!(
+                commands.last().unwrap().exit_code,
+                Some(MAX_EXECUTED_COMMANDS as i32)
+            );
+        });
+    }
+
     /// Simulates the subagent scenario: two child logs linked to the same parent, each
     /// editing a different file. The parent accumulates all edits while each child
     /// only sees its own.
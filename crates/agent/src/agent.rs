@@ -801,10 +801,16 @@ impl NativeAgent {
             // after the thread is constructed are still visible to the
             // model — without this, the catalog and tool would drift out
             // of sync until the session was reopened.
-            thread.add_tool(SkillTool::with_body_resolver(
+            let skill_tool = SkillTool::with_body_resolver(
                 skills_resolver_for_project(weak.clone(), project_id),
                 skill_body_resolver_for_project(project.clone(), self.fs.clone()),
-            ));
+            )
+            .with_scripts(
+                project.clone(),
+                thread.action_log().clone(),
+                self.fs.clone(),
+            );
+            thread.add_tool(skill_tool);
         });
 
         let subscriptions = vec![
@@ -3723,6 +3729,7 @@ mod internal_tests {
             skill_file_path: PathBuf::from(format!("/home/user/.agents/skills/{name}/SKILL.md")),
             load_warnings: Vec::new(),
             disable_model_invocation: false,
+            allowed_tools: Vec::new(),
             embedded_body: None,
         }
     }
@@ -3997,6 +4004,7 @@ mod internal_tests {
             skill_file_path: PathBuf::from(format!("/{worktree}/.agents/skills/{name}/SKILL.md")),
             load_warnings: Vec::new(),
             disable_model_invocation: false,
+            allowed_tools: Vec::new(),
             embedded_body: None,
         }
     }
@@ -4010,6 +4018,7 @@ mod internal_tests {
             skill_file_path: PathBuf::from(format!("/builtin/{name}/SKILL.md")),
             load_warnings: Vec::new(),
             disable_model_invocation: false,
+            allowed_tools: Vec::new(),
             embedded_body: Some("built-in body"),
         }
     }
@@ -4189,6 +4198,7 @@ mod internal_tests {
                 skill_file_path: PathBuf::from(format!("/skills/{name}/SKILL.md")),
                 load_warnings: Vec::new(),
                 disable_model_invocation: false,
+                allowed_tools: Vec::new(),
                 embedded_body: None,
             });
         }
@@ -4266,6 +4276,7 @@ mod internal_tests {
             skill_file_path: PathBuf::from("/skills/skill-01-first/SKILL.md"),
             load_warnings: Vec::new(),
             disable_model_invocation: false,
+            allowed_tools: Vec::new(),
             embedded_body: None,
         };
         let second = Skill {
@@ -4276,6 +4287,7 @@ mod internal_tests {
             skill_file_path: PathBuf::from("/skills/skill-02-overflows/SKILL.md"),
             load_warnings: Vec::new(),
             disable_model_invocation: false,
+            allowed_tools: Vec::new(),
             embedded_body: None,
         };
         let third = Skill {
@@ -4286,6 +4298,7 @@ mod internal_tests {
             skill_file_path: PathBuf::from("/skills/skill-03-would-fit/SKILL.md"),
             load_warnings: Vec::new(),
             disable_model_invocation: false,
+            allowed_tools: Vec::new(),
             embedded_body: None,
         };
 
@@ -4344,6 +4357,7 @@ mod internal_tests {
             skill_file_path: PathBuf::from("/skills/hidden-huge/SKILL.md"),
             load_warnings: Vec::new(),
             disable_model_invocation: true,
+            allowed_tools: Vec::new(),
             embedded_body: None,
         };
         let visible = Skill {
@@ -4354,6 +4368,7 @@ mod internal_tests {
             skill_file_path: PathBuf::from("/skills/visible/SKILL.md"),
             load_warnings: Vec::new(),
             disable_model_invocation: false,
+            allowed_tools: Vec::new(),
             embedded_body: None,
         };
 
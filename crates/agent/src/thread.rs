@@ -306,6 +306,7 @@ impl UserMessage {
         const MERGE_CONFLICT_TAG: &str = "<merge_conflicts>";
         const OPEN_SKILLS_TAG: &str =
             "<skills>\nThe user has attached the following agent skills:\n";
+        const OPEN_RESOURCES_TAG: &str = "<mcp_resources>";
 
         let mut file_context = OPEN_FILES_TAG.to_string();
         let mut directory_context = OPEN_DIRECTORIES_TAG.to_string();
@@ -318,6 +319,7 @@ impl UserMessage {
         let mut diffs_context = OPEN_DIFFS_TAG.to_string();
         let mut merge_conflict_context = MERGE_CONFLICT_TAG.to_string();
         let mut skills_context = OPEN_SKILLS_TAG.to_string();
+        let mut resources_context = OPEN_RESOURCES_TAG.to_string();
 
         for chunk in &*self.content {
             let chunk = match chunk {
@@ -439,6 +441,14 @@ impl UserMessage {
                             let label = format!("{} ({})", name, source);
                             write!(&mut skills_context, "\nSkill: {}\n{}\n", label, content).ok();
                         }
+                        MentionUri::ContextServerResource { server_id, uri, .. } => {
+                            write!(
+                                &mut resources_context,
+                                "\nResource {} from {}:\n{}\n",
+                                uri, server_id, content
+                            )
+                            .ok();
+                        }
                     }
 
                     language_model::MessageContent::Text(uri.as_link().to_string())
@@ -520,6 +530,13 @@ impl UserMessage {
                 .push(language_model::MessageContent::Text(skills_context));
         }
 
+        if resources_context.len() > OPEN_RESOURCES_TAG.len() {
+            resources_context.push_str("</mcp_resources>\n");
+            message
+                .content
+                .push(language_model::MessageContent::Text(resources_context));
+        }
+
         if merge_conflict_context.len() > MERGE_CONFLICT_TAG.len() {
             merge_conflict_context.push_str("</merge_conflicts>\n");
             message
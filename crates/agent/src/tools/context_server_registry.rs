@@ -2,12 +2,25 @@ use crate::{AgentToolOutput, AnyAgentTool, ToolCallEventStream, ToolInput};
 use agent_client_protocol::schema::v1 as acp;
 use anyhow::Result;
 use collections::{BTreeMap, HashMap};
-use context_server::{ContextServerId, client::NotificationSubscription};
-use futures::FutureExt as _;
-use gpui::{App, AppContext, AsyncApp, Context, Entity, EventEmitter, SharedString, Task};
+use context_server::{
+    ContextServerId,
+    client::NotificationSubscription,
+    protocol::{InitializedContextServerProtocol, ServerCapability},
+    types,
+};
+use futures::{
+    FutureExt as _,
+    future::{self, Shared},
+};
+use gpui::{
+    App, AppContext, AsyncApp, BackgroundExecutor, Context, Entity, EntityId, EventEmitter, Global,
+    SharedString, Task,
+};
 use language_model::{LanguageModelImage, LanguageModelImageExt, LanguageModelToolResultContent};
+use parking_lot::Mutex;
 use project::context_server_store::{ContextServerStatus, ContextServerStore};
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
+use url::Url;
 use util::ResultExt;
 
 /// Generates a tool ID for an MCP tool that can be used in settings.
@@ -507,6 +520,398 @@ pub fn get_prompt(
     })
 }
 
+#[derive(Clone)]
+pub struct ContextServerResource {
+    pub server_id: ContextServerId,
+    pub resource: types::Resource,
+}
+
+#[derive(Clone)]
+pub struct ContextServerResourceTemplate {
+    pub server_id: ContextServerId,
+    pub template: types::ResourceTemplate,
+}
+
+#[derive(Clone, Default)]
+pub struct ContextServerResources {
+    pub resources: Vec<ContextServerResource>,
+    pub templates: Vec<ContextServerResourceTemplate>,
+}
+
+/// The resources of every running context server, and the resources each
+/// of them has been asked to report changes to.
+#[derive(Default)]
+struct GlobalContextServerResources {
+    servers: HashMap<(EntityId, ContextServerId), ServerResources>,
+}
+
+impl Global for GlobalContextServerResources {}
+
+struct ServerResources {
+    protocol: Weak<InitializedContextServerProtocol>,
+    list: Shared<Task<Arc<ContextServerResources>>>,
+    /// How many [`ResourceSubscription`]s are held for each URI.
+    subscriptions: Arc<Mutex<HashMap<Url, usize>>>,
+    _list_changed_subscription: NotificationSubscription,
+}
+
+/// Returns the resource state of a running server, resetting it if the
+/// server has been restarted since it was last used.
+fn server_resources<'a>(
+    server_store: &Entity<ContextServerStore>,
+    server_id: &ContextServerId,
+    protocol: &Arc<InitializedContextServerProtocol>,
+    cx: &'a mut App,
+) -> &'a mut ServerResources {
+    let key = (server_store.entity_id(), server_id.clone());
+    let executor = cx.background_executor().clone();
+    let state = cx.default_global::<GlobalContextServerResources>();
+    state
+        .servers
+        .retain(|_, server| server.protocol.strong_count() > 0);
+
+    let is_current = state
+        .servers
+        .get(&key)
+        .is_some_and(|server| std::ptr::eq(server.protocol.as_ptr(), Arc::as_ptr(protocol)));
+    if !is_current {
+        let list_changed_subscription = protocol.on_notification(
+            "notifications/resources/list_changed",
+            Box::new({
+                let key = key.clone();
+                move |_params, cx: AsyncApp| {
+                    let key = key.clone();
+                    cx.spawn(async move |cx| {
+                        log::info!(
+                            "Received resources/list_changed notification for server {}",
+                            key.1
+                        );
+                        cx.update(|cx| {
+                            let executor = cx.background_executor().clone();
+                            let state = cx.default_global::<GlobalContextServerResources>();
+                            if let Some(server) = state.servers.get_mut(&key)
+                                && let Some(protocol) = server.protocol.upgrade()
+                            {
+                                server.list = load_resources(key.1.clone(), protocol, &executor);
+                            }
+                        })
+                    })
+                    .detach();
+                }
+            }),
+        );
+        state.servers.insert(
+            key.clone(),
+            ServerResources {
+                protocol: Arc::downgrade(protocol),
+                list: load_resources(server_id.clone(), protocol.clone(), &executor),
+                subscriptions: Arc::default(),
+                _list_changed_subscription: list_changed_subscription,
+            },
+        );
+    }
+
+    state
+        .servers
+        .get_mut(&key)
+        .expect("server resources were just inserted")
+}
+
+/// Fetches every page of a server's resources and resource templates.
+fn load_resources(
+    server_id: ContextServerId,
+    protocol: Arc<InitializedContextServerProtocol>,
+    executor: &BackgroundExecutor,
+) -> Shared<Task<Arc<ContextServerResources>>> {
+    executor
+        .spawn(async move {
+            let mut result = ContextServerResources::default();
+
+            let mut cursor = None;
+            loop {
+                let Some(response) = protocol
+                    .request::<types::requests::ResourcesList>(types::PaginatedParams {
+                        cursor: cursor.take(),
+                        meta: None,
+                    })
+                    .await
+                    .log_err()
+                else {
+                    break;
+                };
+                result
+                    .resources
+                    .extend(
+                        response
+                            .resources
+                            .into_iter()
+                            .map(|resource| ContextServerResource {
+                                server_id: server_id.clone(),
+                                resource,
+                            }),
+                    );
+                cursor = response.next_cursor;
+                if cursor.is_none() {
+                    break;
+                }
+            }
+
+            // Templates are optional, so servers without any may not
+            // implement the method at all.
+            let mut cursor = None;
+            loop {
+                let Ok(response) = protocol
+                    .request::<types::requests::ListResourceTemplates>(types::PaginatedParams {
+                        cursor: cursor.take(),
+                        meta: None,
+                    })
+                    .await
+                else {
+                    break;
+                };
+                result
+                    .templates
+                    .extend(response.resource_templates.into_iter().map(|template| {
+                        ContextServerResourceTemplate {
+                            server_id: server_id.clone(),
+                            template,
+                        }
+                    }));
+                cursor = response.next_cursor;
+                if cursor.is_none() {
+                    break;
+                }
+            }
+
+            Arc::new(result)
+        })
+        .shared()
+}
+
+/// Lists the resources and resource templates of every running context server
+/// that offers resources. Each server's list is fetched once, and again when
+/// the server reports that it changed.
+pub fn list_resources(
+    server_store: &Entity<ContextServerStore>,
+    cx: &mut App,
+) -> Task<ContextServerResources> {
+    let lists = server_store
+        .read(cx)
+        .running_servers()
+        .into_iter()
+        .filter_map(|server| {
+            let client = server.client()?;
+            client
+                .capable(ServerCapability::Resources)
+                .then(|| (server.id(), client))
+        })
+        .collect::<Vec<_>>()
+        .into_iter()
+        .map(|(server_id, client)| {
+            server_resources(server_store, &server_id, &client, cx)
+                .list
+                .clone()
+        })
+        .collect::<Vec<_>>();
+
+    cx.background_spawn(async move {
+        let mut result = ContextServerResources::default();
+        for list in future::join_all(lists).await {
+            result.resources.extend(list.resources.iter().cloned());
+            result.templates.extend(list.templates.iter().cloned());
+        }
+        result
+    })
+}
+
+pub fn read_resource(
+    server_store: &Entity<ContextServerStore>,
+    server_id: &ContextServerId,
+    uri: Url,
+    cx: &App,
+) -> Task<Result<types::ResourcesReadResponse>> {
+    let Some(server) = server_store.read(cx).get_running_server(server_id) else {
+        return Task::ready(Err(anyhow::anyhow!("Context server not found")));
+    };
+    let Some(protocol) = server.client() else {
+        return Task::ready(Err(anyhow::anyhow!("Context server not initialized")));
+    };
+
+    cx.background_spawn(async move {
+        protocol
+            .request::<types::requests::ResourcesRead>(types::ResourcesReadParams {
+                uri,
+                meta: None,
+            })
+            .await
+    })
+}
+
+/// Asks the server for values of a resource template's argument that start
+/// with `value`. `arguments` holds the values already chosen for the
+/// template's preceding arguments.
+pub fn complete_resource_template_argument(
+    server_store: &Entity<ContextServerStore>,
+    server_id: &ContextServerId,
+    uri_template: String,
+    argument_name: String,
+    value: String,
+    arguments: HashMap<String, String>,
+    cx: &App,
+) -> Task<Result<Vec<String>>> {
+    let Some(server) = server_store.read(cx).get_running_server(server_id) else {
+        return Task::ready(Err(anyhow::anyhow!("Context server not found")));
+    };
+    let Some(protocol) = server.client() else {
+        return Task::ready(Err(anyhow::anyhow!("Context server not initialized")));
+    };
+    if protocol.initialize.capabilities.completions.is_none() {
+        return Task::ready(Ok(Vec::new()));
+    }
+
+    cx.background_spawn(async move {
+        let response = protocol
+            .request::<types::requests::CompletionComplete>(types::CompletionCompleteParams {
+                reference: types::CompletionReference::Resource(types::ResourceReference {
+                    ty: types::PromptReferenceType::Resource,
+                    uri: uri_template,
+                }),
+                argument: types::CompletionArgument {
+                    name: argument_name,
+                    value,
+                },
+                context: (!arguments.is_empty()).then(|| types::CompletionContext {
+                    arguments: Some(arguments),
+                }),
+                meta: None,
+            })
+            .await?;
+
+        Ok(response.completion.values)
+    })
+}
+
+/// Keeps a context server notifying about changes to a resource. The server
+/// is asked to stop once every subscription to the resource is dropped.
+pub struct ResourceSubscription {
+    uri: Url,
+    protocol: Arc<InitializedContextServerProtocol>,
+    subscriptions: Arc<Mutex<HashMap<Url, usize>>>,
+    executor: BackgroundExecutor,
+    _notification_subscription: NotificationSubscription,
+}
+
+impl Drop for ResourceSubscription {
+    fn drop(&mut self) {
+        if release_subscription(&self.subscriptions, &self.uri) {
+            let protocol = self.protocol.clone();
+            let uri = self.uri.clone();
+            self.executor
+                .spawn(async move {
+                    protocol
+                        .request::<types::requests::ResourcesUnsubscribe>(
+                            types::ResourcesUnsubscribeParams { uri, meta: None },
+                        )
+                        .await
+                        .log_err();
+                })
+                .detach();
+        }
+    }
+}
+
+/// Counts a new holder of a subscription to `uri`, returning whether it's
+/// the first one.
+fn acquire_subscription(subscriptions: &Mutex<HashMap<Url, usize>>, uri: &Url) -> bool {
+    let mut subscriptions = subscriptions.lock();
+    let count = subscriptions.entry(uri.clone()).or_default();
+    *count += 1;
+    *count == 1
+}
+
+/// Forgets a holder of a subscription to `uri`, returning whether it was the
+/// last one.
+fn release_subscription(subscriptions: &Mutex<HashMap<Url, usize>>, uri: &Url) -> bool {
+    let mut subscriptions = subscriptions.lock();
+    let Some(count) = subscriptions.get_mut(uri) else {
+        return false;
+    };
+    *count -= 1;
+    if *count == 0 {
+        subscriptions.remove(uri);
+        true
+    } else {
+        false
+    }
+}
+
+/// Subscribes to changes to a resource, calling `on_update` each time the
+/// server reports one. Resolves to `None` if the server doesn't support
+/// subscriptions. Only the first subscription to a resource is sent to the
+/// server.
+pub fn subscribe_to_resource(
+    server_store: &Entity<ContextServerStore>,
+    server_id: &ContextServerId,
+    uri: Url,
+    mut on_update: impl 'static + Send + FnMut(AsyncApp),
+    cx: &mut App,
+) -> Task<Result<Option<ResourceSubscription>>> {
+    let Some(server) = server_store.read(cx).get_running_server(server_id) else {
+        return Task::ready(Err(anyhow::anyhow!("Context server not found")));
+    };
+    let Some(protocol) = server.client() else {
+        return Task::ready(Err(anyhow::anyhow!("Context server not initialized")));
+    };
+    let supports_subscriptions = protocol
+        .initialize
+        .capabilities
+        .resources
+        .as_ref()
+        .and_then(|resources| resources.subscribe)
+        .unwrap_or(false);
+    if !supports_subscriptions {
+        return Task::ready(Ok(None));
+    }
+
+    let subscriptions = server_resources(server_store, server_id, &protocol, cx)
+        .subscriptions
+        .clone();
+    let notification_subscription = protocol.on_notification(
+        "notifications/resources/updated",
+        Box::new({
+            let uri = uri.to_string();
+            move |params, cx| {
+                if let Ok(params) = serde_json::from_value::<types::ResourcesUpdatedParams>(params)
+                    && params.uri == uri
+                {
+                    on_update(cx);
+                }
+            }
+        }),
+    );
+    let is_first = acquire_subscription(&subscriptions, &uri);
+    let subscription = ResourceSubscription {
+        uri: uri.clone(),
+        protocol: protocol.clone(),
+        subscriptions,
+        executor: cx.background_executor().clone(),
+        _notification_subscription: notification_subscription,
+    };
+    if !is_first {
+        return Task::ready(Ok(Some(subscription)));
+    }
+
+    cx.background_spawn(async move {
+        protocol
+            .request::<types::requests::ResourcesSubscribe>(types::ResourcesSubscribeParams {
+                uri,
+                meta: None,
+            })
+            .await?;
+        Ok(Some(subscription))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -529,6 +934,25 @@ mod tests {
         assert_eq!(mcp_tool_id("my_server", "my_tool"), "mcp:my_server:my_tool");
     }
 
+    #[test]
+    fn test_resource_subscriptions_are_counted() {
+        let subscriptions = Mutex::new(HashMap::default());
+        let first = Url::parse("file:///first.txt").unwrap();
+        let second = Url::parse("file:///second.txt").unwrap();
+
+        assert!(acquire_subscription(&subscriptions, &first));
+        assert!(!acquire_subscription(&subscriptions, &first));
+        assert!(acquire_subscription(&subscriptions, &second));
+
+        assert!(!release_subscription(&subscriptions, &first));
+        assert!(release_subscription(&subscriptions, &first));
+        assert!(release_subscription(&subscriptions, &second));
+        assert!(subscriptions.lock().is_empty());
+
+        assert!(!release_subscription(&subscriptions, &first));
+        assert!(acquire_subscription(&subscriptions, &first));
+    }
+
     // Note: Tests for MCP tool ID collision with built-in tools and permission
     // decisions are in crates/agent/src/tool_permissions.rs to avoid duplication.
 }
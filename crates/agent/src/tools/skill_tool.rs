@@ -1,15 +1,51 @@
+use action_log::{ActionLog, ExecutedCommand};
 use agent_client_protocol::schema::v1 as acp;
-use agent_skills::Skill;
-use anyhow::Result;
-use gpui::{App, AsyncApp, SharedString, Task};
+use agent_skills::{Skill, SkillScriptPermissions, SkillSource};
+use anyhow::{Context as _, Result, anyhow};
+use fs::Fs;
+use futures::{
+    AsyncBufReadExt as _, AsyncRead, FutureExt as _, Stream, StreamExt as _, io::BufReader,
+};
+use gpui::{App, AppContext as _, AsyncApp, Entity, SharedString, Task};
+use http_proxy::{Allowlist, HostPattern};
 use language_model::LanguageModelToolResultContent;
+use project::{Project, WorktreeId};
+use sandbox::{
+    CommandAndArgs, GitSandboxPolicy, HostFilesystemLocation, Sandbox, SandboxFsPolicy,
+    SandboxNetPolicy, SandboxPolicy,
+};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use util::command::Stdio;
+use util::markdown::MarkdownCodeBlock;
 
+use crate::sandboxing::{sandbox_git_dirs, sandboxing_available_for_project};
 use crate::{AgentTool, ToolCallEventStream, ToolInput};
 
+/// How much of a script's output is kept for the model and the action log.
+const MAX_SCRIPT_OUTPUT_LEN: usize = 16 * 1024;
+
+/// Environment variables every skill script gets. Anything else has to be
+/// declared by the skill with `Env(<variable>)`.
+const SCRIPT_BASE_ENV_VARS: &[&str] = &["PATH", "HOME", "LANG", "TERM"];
+
+/// Upstream proxy settings, which the sandbox's network proxy reads and then
+/// replaces with its own address.
+const SCRIPT_PROXY_ENV_VARS: &[&str] = &[
+    "HTTPS_PROXY",
+    "https_proxy",
+    "ALL_PROXY",
+    "all_proxy",
+    "HTTP_PROXY",
+    "http_proxy",
+    "NO_PROXY",
+    "no_proxy",
+];
+
 /// XML-escape a string so a malicious skill author cannot break out of the
 /// `<skill_content>` envelope (or the `<available_skills>` catalog) by
 /// embedding closing tags or attribute terminators in their skill name,
@@ -78,10 +114,18 @@ pub fn render_skill_envelope(skill: &Skill, body: &str) -> String {
 }
 
 /// Retrieves the content and resources of a skill by name. Use this when a user's request matches a skill's description.
+///
+/// Once a skill is loaded, also use this to run the scripts it bundles in its `scripts/` directory, instead of running them in a terminal. Scripts run in a sandbox scoped to what the skill declares it needs.
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct SkillToolInput {
     /// The name of the skill to retrieve
     pub name: String,
+    /// A script bundled with the skill to run instead of retrieving the skill, relative to its `scripts/` directory (e.g. `extract.py`). Only run scripts the skill's instructions tell you to run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script: Option<String>,
+    /// Arguments passed to the script.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
 }
 
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -93,6 +137,12 @@ pub enum SkillToolOutput {
     Found {
         rendered: String,
     },
+    ScriptRun {
+        script: String,
+        /// `None` when the script was killed by a signal.
+        exit_code: Option<i32>,
+        output: String,
+    },
     Error {
         error: String,
     },
@@ -104,6 +154,30 @@ impl From<SkillToolOutput> for LanguageModelToolResultContent {
             SkillToolOutput::Found { rendered } => {
                 LanguageModelToolResultContent::Text(rendered.into())
             }
+            SkillToolOutput::ScriptRun {
+                script,
+                exit_code,
+                output,
+            } => {
+                let status = match exit_code {
+                    Some(code) => format!("exited with code {code}"),
+                    None => "was killed by a signal".to_string(),
+                };
+                let output = if output.is_empty() {
+                    "It printed no output.".to_string()
+                } else {
+                    format!(
+                        "Output:\n{}",
+                        MarkdownCodeBlock {
+                            tag: "",
+                            text: &output,
+                        }
+                    )
+                };
+                LanguageModelToolResultContent::Text(
+                    format!("Script `{script}` {status}. {output}").into(),
+                )
+            }
             SkillToolOutput::Error { error } => LanguageModelToolResultContent::Text(error.into()),
         }
     }
@@ -120,6 +194,15 @@ pub type SkillBodyResolver =
 pub struct SkillTool {
     skills: SkillsResolver,
     body_resolver: SkillBodyResolver,
+    scripts: Option<SkillScriptEnvironment>,
+}
+
+/// What the tool needs to run the scripts skills bundle. Without it the tool
+/// still loads skills, but refuses to run their scripts.
+struct SkillScriptEnvironment {
+    project: Entity<Project>,
+    action_log: Entity<ActionLog>,
+    fs: Arc<dyn Fs>,
 }
 
 impl SkillTool {
@@ -131,7 +214,365 @@ impl SkillTool {
         Self {
             skills: Arc::new(skills),
             body_resolver: Arc::new(body_resolver),
+            scripts: None,
+        }
+    }
+
+    /// Let the tool run the scripts skills bundle, in a sandbox rooted in one
+    /// of `project`'s worktrees, recording each run in `action_log`.
+    pub fn with_scripts(
+        mut self,
+        project: Entity<Project>,
+        action_log: Entity<ActionLog>,
+        fs: Arc<dyn Fs>,
+    ) -> Self {
+        self.scripts = Some(SkillScriptEnvironment {
+            project,
+            action_log,
+            fs,
+        });
+        self
+    }
+
+    /// Run `script` from `skill` in a sandbox derived from the skill's
+    /// `allowed-tools`, streaming its output into the tool call.
+    ///
+    /// Scripts never run unsandboxed: unlike the terminal tool, there is no
+    /// fallback to ambient permissions when the sandbox is unavailable, since
+    /// the point of running a script through here rather than a terminal is
+    /// that its reach is bounded by what the skill declared.
+    async fn run_script(
+        &self,
+        skill: Skill,
+        script: String,
+        args: Vec<String>,
+        event_stream: &ToolCallEventStream,
+        cx: &mut AsyncApp,
+    ) -> Result<SkillToolOutput> {
+        let Some(environment) = &self.scripts else {
+            anyhow::bail!("Running skill scripts isn't available in this thread");
+        };
+
+        let script_path = agent_skills::resolve_skill_script(&skill, &script)?;
+        if !environment.fs.is_file(&script_path).await {
+            let scripts = agent_skills::list_skill_scripts(environment.fs.as_ref(), &skill).await;
+            if scripts.is_empty() {
+                anyhow::bail!("Skill '{}' doesn't bundle any scripts", skill.name);
+            }
+            anyhow::bail!(
+                "Script '{script}' not found in skill '{}'. Available scripts: {}",
+                skill.name,
+                scripts.join(", ")
+            );
+        }
+
+        let permissions = skill.script_permissions()?;
+        let allowlist = permissions
+            .network_hosts
+            .iter()
+            .map(|host| {
+                HostPattern::parse(host).map_err(|error| {
+                    anyhow!(
+                        "Invalid host `{host}` in the allowed-tools of skill '{}': {error}",
+                        skill.name
+                    )
+                })
+            })
+            .collect::<Result<Allowlist>>()?;
+
+        let (cwd, git_dirs) = cx.update(|cx| {
+            let project = environment.project.read(cx);
+            // The Windows sandbox runs commands inside WSL, where the skill's
+            // host paths don't resolve.
+            if !cfg!(any(target_os = "macos", target_os = "linux"))
+                || !sandboxing_available_for_project(project, cx)
+            {
+                anyhow::bail!(
+                    "Skill scripts only run in a sandbox, which isn't available for this project"
+                );
+            }
+            let worktree = match &skill.source {
+                SkillSource::ProjectLocal { worktree_id, .. } => {
+                    project.worktree_for_id(WorktreeId::from_usize(worktree_id.0), cx)
+                }
+                SkillSource::BuiltIn | SkillSource::Global => project.visible_worktrees(cx).next(),
+            }
+            .context("Skill scripts run in a project worktree, but this project has none")?;
+            anyhow::Ok((
+                worktree.read(cx).abs_path().to_path_buf(),
+                sandbox_git_dirs(project, cx),
+            ))
+        })?;
+
+        let grants = script_grants(&permissions);
+        let authorize = cx.update(|cx| {
+            let mut title = format!("Run `{script}` from `{}` Skill", skill.name);
+            if !grants.is_empty() {
+                let grants = grants
+                    .iter()
+                    .map(|grant| format!("`{grant}`"))
+                    .collect::<Vec<_>>();
+                write!(title, " with {}", grants.join(", ")).unwrap();
+            }
+            // Every grant has to match an `always_allow` pattern too, so that
+            // allowing a script doesn't allow what its skill is granted later.
+            let context = crate::ToolPermissionContext::new(
+                Self::NAME,
+                std::iter::once(script_path.to_string_lossy().into_owned())
+                    .chain(grants)
+                    .collect(),
+            );
+            event_stream.authorize(title, context, cx)
+        });
+        authorize.await?;
+
+        let (program, mut program_args) = script_command(&script_path);
+        program_args.extend(args);
+        let command = CommandAndArgs {
+            program: program.clone(),
+            args: program_args.clone(),
+            env: script_env(&permissions, |name| std::env::var(name).ok()),
+            cwd: Some(cwd.clone()),
+        };
+        let write_paths: Vec<PathBuf> = permissions
+            .write_paths
+            .iter()
+            .map(|path| cwd.join(path))
+            .collect();
+        let (wrapped, sandbox) = cx
+            .background_spawn(async move {
+                let policy = script_sandbox_policy(&write_paths, &allowlist, git_dirs);
+                Sandbox::can_create(&policy).map_err(|error| {
+                    anyhow!("Couldn't create a sandbox for the script: {error:?}")
+                })?;
+                let mut sandbox = Sandbox::new(policy)?;
+                let wrapped = sandbox.wrap(&command).await?;
+                anyhow::Ok((wrapped, sandbox))
+            })
+            .await?;
+
+        let mut process = util::command::new_command(&wrapped.program);
+        process
+            .args(&wrapped.args)
+            .env_clear()
+            .envs(&wrapped.env)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        if let Some(cwd) = &wrapped.cwd {
+            process.current_dir(cwd);
+        }
+        let mut child = process
+            .spawn()
+            .with_context(|| format!("Failed to run script `{script}`"))?;
+        let stdout = child.stdout.take().context("Script has no stdout")?;
+        let stderr = child.stderr.take().context("Script has no stderr")?;
+
+        let mut output = ScriptOutput::default();
+        let status = {
+            let mut lines =
+                futures::stream::select(output_lines(stdout).boxed(), output_lines(stderr).boxed())
+                    .ready_chunks(64);
+            let output = &mut output;
+            let run = async {
+                while let Some(chunk) = lines.next().await {
+                    for line in chunk {
+                        output.push_line(&line?);
+                    }
+                    let markdown = MarkdownCodeBlock {
+                        tag: "",
+                        text: output.text.as_str(),
+                    }
+                    .to_string();
+                    event_stream.update_fields(acp::ToolCallUpdateFields::new().content(vec![
+                        acp::ToolCallContent::Content(acp::Content::new(markdown)),
+                    ]));
+                }
+                anyhow::Ok(child.status().await?)
+            };
+            futures::select! {
+                status = run.fuse() => Some(status?),
+                _ = event_stream.cancelled_by_user().fuse() => None,
+            }
+        };
+        if status.is_none() {
+            child.kill().ok();
+        }
+        // The sandbox's network proxy has to outlive the script.
+        drop(sandbox);
+
+        let exit_code = status.and_then(|status| status.code());
+        environment.action_log.update(cx, |log, cx| {
+            log.command_executed(
+                ExecutedCommand {
+                    label: format!("`{}` skill", skill.name).into(),
+                    program,
+                    args: program_args,
+                    cwd: Some(cwd),
+                    exit_code,
+                    output: output.text.clone(),
+                },
+                cx,
+            )
+        });
+
+        if status.is_none() {
+            anyhow::bail!("Script `{script}` cancelled by user");
+        }
+        Ok(SkillToolOutput::ScriptRun {
+            script,
+            exit_code,
+            output: output.text,
+        })
+    }
+}
+
+/// The program and arguments that run a script. Scripts in a language with
+/// a well-known interpreter run through it, so they don't need to be
+/// executable; anything else is executed directly.
+fn script_command(script_path: &Path) -> (String, Vec<String>) {
+    let script = script_path.to_string_lossy().into_owned();
+    let interpreter = match script_path
+        .extension()
+        .and_then(|extension| extension.to_str())
+    {
+        Some("sh") => Some("sh"),
+        Some("bash") => Some("bash"),
+        Some("py") => Some("python3"),
+        Some("js" | "mjs" | "cjs") => Some("node"),
+        Some("rb") => Some("ruby"),
+        _ => None,
+    };
+    match interpreter {
+        Some(interpreter) => (interpreter.to_string(), vec![script]),
+        None => (script, Vec::new()),
+    }
+}
+
+/// The sandbox a skill script runs in: writes only to the paths the skill
+/// declared, network only to the hosts it declared, and `.git` protected.
+///
+/// Like every other sandbox grant, a location that can't be captured (e.g. a
+/// write path that doesn't exist yet on Linux) is dropped — fail-closed.
+fn script_sandbox_policy(
+    write_paths: &[PathBuf],
+    allowlist: &Allowlist,
+    git_dirs: Vec<PathBuf>,
+) -> SandboxPolicy {
+    let network = if allowlist.is_deny_all() {
+        SandboxNetPolicy::Blocked
+    } else {
+        SandboxNetPolicy::Restricted {
+            allowed_domains: allowlist
+                .patterns()
+                .iter()
+                .map(|pattern| pattern.to_string())
+                .collect(),
         }
+    };
+    SandboxPolicy {
+        fs: SandboxFsPolicy::Restricted {
+            writable_paths: write_paths
+                .iter()
+                .filter_map(|path| HostFilesystemLocation::new(path).ok())
+                .collect(),
+        },
+        network,
+        git: GitSandboxPolicy::Denied {
+            git_dirs: git_dirs
+                .into_iter()
+                .filter_map(|path| HostFilesystemLocation::new(path).ok())
+                .collect(),
+        },
+    }
+}
+
+/// The environment a script runs with: a minimal base, the variables the
+/// skill declared, and the upstream proxy when it may reach the network.
+fn script_env(
+    permissions: &SkillScriptPermissions,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> HashMap<String, String> {
+    let proxy_vars = if permissions.network_hosts.is_empty() {
+        &[][..]
+    } else {
+        SCRIPT_PROXY_ENV_VARS
+    };
+    SCRIPT_BASE_ENV_VARS
+        .iter()
+        .chain(proxy_vars)
+        .copied()
+        .chain(permissions.env_vars.iter().map(String::as_str))
+        .filter_map(|name| Some((name.to_string(), lookup(name)?)))
+        .collect()
+}
+
+/// Reads the lines a script prints, replacing bytes that aren't valid UTF-8
+/// instead of failing on them.
+fn output_lines(output: impl AsyncRead + Unpin) -> impl Stream<Item = std::io::Result<String>> {
+    futures::stream::unfold(Some(BufReader::new(output)), |reader| async move {
+        let mut reader = reader?;
+        let mut line = Vec::new();
+        match reader.read_until(b'\n', &mut line).await {
+            Ok(0) => None,
+            Ok(_) => {
+                if line.last() == Some(&b'\n') {
+                    line.pop();
+                    if line.last() == Some(&b'\r') {
+                        line.pop();
+                    }
+                }
+                let line = String::from_utf8_lossy(&line).into_owned();
+                Some((Ok(line), Some(reader)))
+            }
+            Err(error) => Some((Err(error), None)),
+        }
+    })
+}
+
+/// The accesses a script is granted beyond its sandbox's defaults, written
+/// like the `allowed-tools` entries granting them.
+fn script_grants(permissions: &SkillScriptPermissions) -> Vec<String> {
+    permissions
+        .network_hosts
+        .iter()
+        .map(|host| format!("Network({host})"))
+        .chain(
+            permissions
+                .write_paths
+                .iter()
+                .map(|path| format!("Write({})", path.display())),
+        )
+        .chain(
+            permissions
+                .env_vars
+                .iter()
+                .map(|name| format!("Env({name})")),
+        )
+        .collect()
+}
+
+/// The output of a script, keeping at most `MAX_SCRIPT_OUTPUT_LEN` bytes of it.
+#[derive(Default)]
+struct ScriptOutput {
+    text: String,
+    truncated: bool,
+}
+
+impl ScriptOutput {
+    fn push_line(&mut self, line: &str) {
+        const TRUNCATED: &str = "[output truncated]";
+        if self.truncated {
+            return;
+        }
+        if self.text.len() + line.len() + 1 > MAX_SCRIPT_OUTPUT_LEN {
+            self.text.push_str(TRUNCATED);
+            self.truncated = true;
+            return;
+        }
+        self.text.push_str(line);
+        self.text.push('\n');
     }
 }
 
@@ -168,7 +609,7 @@ impl AgentTool for SkillTool {
         cx: &mut App,
     ) -> Task<Result<Self::Output, Self::Output>> {
         cx.spawn(async move |cx| {
-            let input = input.recv().await.map_err(|e| SkillToolOutput::Error {
+            let mut input = input.recv().await.map_err(|e| SkillToolOutput::Error {
                 error: e.to_string(),
             })?;
 
@@ -203,6 +644,16 @@ impl AgentTool for SkillTool {
                 (skill.clone(), path_string)
             };
 
+            if let Some(script) = input.script.take() {
+                let args = std::mem::take(&mut input.args);
+                return self
+                    .run_script(skill, script, args, &event_stream, cx)
+                    .await
+                    .map_err(|e| SkillToolOutput::Error {
+                        error: e.to_string(),
+                    });
+            }
+
             // For built-in skills the body is already in memory (compiled
             // into the binary). For user skills, read on demand from disk.
             let body = if let Some(embedded) = skill.embedded_body {
@@ -242,7 +693,7 @@ mod tests {
     use agent_skills::{SkillScopeId, SkillSource, parse_skill_frontmatter};
     use anyhow::Context as _;
     use fs::FakeFs;
-    use gpui::TestAppContext;
+    use gpui::{AppContext as _, TestAppContext};
     use project::Project;
     use serde_json::json;
     use settings::{Settings, SettingsStore};
@@ -570,7 +1021,7 @@ mod tests {
                 assert!(rendered.contains("<source>global</source>"));
                 assert!(!rendered.contains("<worktree>"));
             }
-            SkillToolOutput::Error { error } => panic!("expected Found, got: {error}"),
+            other => panic!("expected Found, got: {other:?}"),
         }
 
         // Test project-local skill
@@ -584,7 +1035,7 @@ mod tests {
                 assert!(rendered.contains("<source>project-local</source>"));
                 assert!(rendered.contains("<worktree>test</worktree>"));
             }
-            SkillToolOutput::Error { error } => panic!("expected Found, got: {error}"),
+            other => panic!("expected Found, got: {other:?}"),
         }
     }
 
@@ -812,4 +1263,203 @@ mod tests {
             "expected denial to surface as an error: {result:?}"
         );
     }
+
+    async fn script_test_tool(
+        allowed_tools: &str,
+        cx: &mut TestAppContext,
+    ) -> (Arc<SkillTool>, Entity<ActionLog>) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/skills",
+            json!({
+                "pdf": {
+                    "SKILL.md": "",
+                    "scripts": {
+                        "extract.py": "print('extracted')",
+                        "merge.sh": "echo merged"
+                    }
+                }
+            }),
+        )
+        .await;
+        fs.insert_tree("/test", json!({})).await;
+        let project = Project::test(fs.clone(), [Path::new("/test")], cx).await;
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+
+        let content = format!(
+            "---\nname: pdf\ndescription: Work with PDFs\nallowed-tools: {allowed_tools}\n---\n"
+        );
+        let skill = parse_skill_frontmatter(
+            Path::new("/skills/pdf/SKILL.md"),
+            &content,
+            SkillSource::Global,
+        )
+        .unwrap();
+        let bodies = vec![(skill.skill_file_path.clone(), String::new())];
+        let skills = Arc::new(vec![skill]);
+        let tool =
+            SkillTool::with_body_resolver(move |_cx| skills.clone(), stub_body_resolver(bodies))
+                .with_scripts(project, action_log.clone(), fs);
+        (Arc::new(tool), action_log)
+    }
+
+    async fn run_script_error(
+        tool: Arc<SkillTool>,
+        input: serde_json::Value,
+        cx: &mut TestAppContext,
+    ) -> String {
+        let (mut sender, tool_input) = ToolInput::<SkillToolInput>::test();
+        sender.send_full(input);
+        let (event_stream, _rx) = ToolCallEventStream::test();
+        let task = cx.update(|cx| tool.run(tool_input, event_stream, cx));
+        match task.await {
+            Err(SkillToolOutput::Error { error }) => error,
+            other => panic!("expected Error variant, got: {other:?}"),
+        }
+    }
+
+    #[gpui::test]
+    async fn test_skill_tool_script_not_found_lists_scripts(cx: &mut TestAppContext) {
+        init_test(cx);
+        let (tool, action_log) = script_test_tool("Read", cx).await;
+
+        let error = run_script_error(
+            tool.clone(),
+            json!({ "name": "pdf", "script": "missing.py" }),
+            cx,
+        )
+        .await;
+        assert!(error.contains("'missing.py' not found"), "{error}");
+        assert!(error.contains("extract.py, merge.sh"), "{error}");
+
+        let error =
+            run_script_error(tool, json!({ "name": "pdf", "script": "../SKILL.md" }), cx).await;
+        assert!(error.contains("without `..`"), "{error}");
+
+        action_log.read_with(cx, |log, _| assert!(log.executed_commands().is_empty()));
+    }
+
+    #[gpui::test]
+    async fn test_skill_tool_script_rejects_invalid_allowed_tools(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let (tool, _) = script_test_tool("Network(localhost)", cx).await;
+        let error =
+            run_script_error(tool, json!({ "name": "pdf", "script": "extract.py" }), cx).await;
+        assert!(error.contains("Invalid host"), "{error}");
+
+        let (tool, _) = script_test_tool("Write(../elsewhere)", cx).await;
+        let error = run_script_error(
+            tool,
+            json!({ "name": "pdf", "script": "scripts/merge.sh" }),
+            cx,
+        )
+        .await;
+        assert!(error.contains("Write(../elsewhere)"), "{error}");
+    }
+
+    #[gpui::test]
+    async fn test_skill_tool_script_requires_script_environment(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let (skill, body) = create_test_skill("my-skill", "A test skill", "# Body");
+        let bodies = vec![(skill.skill_file_path.clone(), body)];
+        let skills = Arc::new(vec![skill]);
+        let tool = Arc::new(SkillTool::with_body_resolver(
+            move |_cx| skills.clone(),
+            stub_body_resolver(bodies),
+        ));
+
+        let error = run_script_error(
+            tool,
+            json!({ "name": "my-skill", "script": "run.sh", "args": ["--verbose"] }),
+            cx,
+        )
+        .await;
+        assert!(error.contains("isn't available"), "{error}");
+    }
+
+    #[test]
+    fn test_script_command_picks_interpreter() {
+        assert_eq!(
+            script_command(Path::new("/skills/pdf/scripts/extract.py")),
+            (
+                "python3".to_string(),
+                vec!["/skills/pdf/scripts/extract.py".to_string()]
+            )
+        );
+        assert_eq!(
+            script_command(Path::new("/skills/pdf/scripts/run")),
+            ("/skills/pdf/scripts/run".to_string(), Vec::new())
+        );
+    }
+
+    #[test]
+    fn test_script_output_truncates_once() {
+        let mut output = ScriptOutput::default();
+        output.push_line("first");
+        assert_eq!(output.text, "first\n");
+
+        let long_line = "x".repeat(MAX_SCRIPT_OUTPUT_LEN);
+        output.push_line(&long_line);
+        output.push_line("dropped");
+        output.push_line(&long_line);
+        assert_eq!(output.text, "first\n[output truncated]");
+    }
+
+    #[test]
+    fn test_output_lines_replaces_invalid_utf8() {
+        let output = &b"first\r\nbad \xff byte\n\nlast"[..];
+        let lines = futures::executor::block_on(output_lines(output).collect::<Vec<_>>())
+            .into_iter()
+            .collect::<std::io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(lines, ["first", "bad \u{FFFD} byte", "", "last"]);
+    }
+
+    #[test]
+    fn test_script_grants() {
+        assert!(script_grants(&SkillScriptPermissions::default()).is_empty());
+        let permissions = SkillScriptPermissions {
+            network_hosts: vec!["api.github.com".to_string()],
+            write_paths: vec![PathBuf::from("out")],
+            env_vars: vec!["GITHUB_TOKEN".to_string()],
+        };
+        assert_eq!(
+            script_grants(&permissions),
+            ["Network(api.github.com)", "Write(out)", "Env(GITHUB_TOKEN)"]
+        );
+    }
+
+    #[test]
+    fn test_script_env_only_passes_allowed_variables() {
+        let lookup = |name: &str| match name {
+            "PATH" => Some("/usr/bin".to_string()),
+            "HOME" => Some("/home/me".to_string()),
+            "HTTPS_PROXY" => Some("http://proxy:3128".to_string()),
+            "GITHUB_TOKEN" => Some("token".to_string()),
+            "AWS_SECRET_ACCESS_KEY" => Some("secret".to_string()),
+            _ => None,
+        };
+        let mut permissions = SkillScriptPermissions::default();
+        assert_eq!(
+            script_env(&permissions, lookup),
+            HashMap::from_iter([
+                ("PATH".to_string(), "/usr/bin".to_string()),
+                ("HOME".to_string(), "/home/me".to_string()),
+            ])
+        );
+
+        permissions.env_vars = vec!["GITHUB_TOKEN".to_string()];
+        permissions.network_hosts = vec!["api.github.com".to_string()];
+        assert_eq!(
+            script_env(&permissions, lookup),
+            HashMap::from_iter([
+                ("PATH".to_string(), "/usr/bin".to_string()),
+                ("HOME".to_string(), "/home/me".to_string()),
+                ("HTTPS_PROXY".to_string(), "http://proxy:3128".to_string()),
+                ("GITHUB_TOKEN".to_string(), "token".to_string()),
+            ])
+        );
+    }
 }
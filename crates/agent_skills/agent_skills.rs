@@ -86,6 +86,10 @@ pub struct Skill {
     /// `skill` tool refuses to load it. The user can still invoke it as a
     /// slash command.
     pub disable_model_invocation: bool,
+    /// The `allowed-tools` frontmatter entries, as written. The skill tool
+    /// derives the sandbox its bundled scripts run in from these (see
+    /// [`Skill::script_permissions`]).
+    pub allowed_tools: Vec<String>,
     /// For built-in skills whose content is compiled into the binary,
     /// this holds the full SKILL.md body so the skill tool can serve it
     /// without a filesystem read.
@@ -208,6 +212,32 @@ pub struct SkillMetadata {
     pub description: String,
     #[serde(default, rename = "disable-model-invocation")]
     pub disable_model_invocation: bool,
+    #[serde(
+        default,
+        rename = "allowed-tools",
+        deserialize_with = "deserialize_allowed_tools",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub allowed_tools: Vec<String>,
+}
+
+/// The spec writes `allowed-tools` as a space-delimited string, but some
+/// skills in the wild use a YAML list instead; accept both.
+fn deserialize_allowed_tools<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum AllowedTools {
+        Delimited(String),
+        List(Vec<String>),
+    }
+
+    Ok(match AllowedTools::deserialize(deserializer)? {
+        AllowedTools::Delimited(tools) => tools.split_whitespace().map(str::to_string).collect(),
+        AllowedTools::List(tools) => tools,
+    })
 }
 
 /// Minimal skill info for system prompt.
@@ -283,6 +313,7 @@ pub fn parse_skill_frontmatter(
         skill_file_path: skill_file_path.to_path_buf(),
         load_warnings,
         disable_model_invocation: metadata.disable_model_invocation,
+        allowed_tools: metadata.allowed_tools,
         embedded_body: None,
     })
 }
@@ -694,6 +725,134 @@ pub fn read_skill_body_from_content(
     Ok(body.trim().to_string())
 }
 
+/// The directory inside a skill's directory that holds its bundled scripts.
+pub const SKILL_SCRIPTS_DIR_NAME: &str = "scripts";
+
+/// What a skill's bundled scripts may do when the skill tool runs them,
+/// declared through `Network(<host>)`, `Write(<path>)` and `Env(<variable>)`
+/// entries in the skill's `allowed-tools` field. Scripts of a skill declaring
+/// none of them run without network access, can't write outside of the
+/// platform's temporary directories, and only see a minimal environment.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SkillScriptPermissions {
+    /// Hosts scripts may connect to: exact hostnames (`api.github.com`) or
+    /// subdomain wildcards (`*.npmjs.org`). Validated by the caller.
+    pub network_hosts: Vec<String>,
+    /// Paths scripts may write to, relative to the directory they run in.
+    pub write_paths: Vec<PathBuf>,
+    /// Environment variables passed through to scripts, on top of a minimal
+    /// set like `PATH` and `HOME`.
+    pub env_vars: Vec<String>,
+}
+
+impl Skill {
+    /// Parse the script permissions out of `allowed-tools`. Entries naming
+    /// other tools are left for them; a malformed `Network(...)`,
+    /// `Write(...)` or `Env(...)` entry is an error, so a typo can't silently
+    /// widen or narrow what a script is allowed to do.
+    pub fn script_permissions(&self) -> Result<SkillScriptPermissions> {
+        let mut permissions = SkillScriptPermissions::default();
+        for entry in &self.allowed_tools {
+            if let Some(host) = permission_argument(entry, "Network")? {
+                permissions.network_hosts.push(host.to_string());
+            } else if let Some(path) = permission_argument(entry, "Write")? {
+                let path = Path::new(path);
+                if !is_plain_relative_path(path) {
+                    anyhow::bail!(
+                        "`{entry}` in allowed-tools must name a path relative to the project, \
+                         without `..`"
+                    );
+                }
+                permissions.write_paths.push(path.to_path_buf());
+            } else if let Some(name) = permission_argument(entry, "Env")? {
+                if !is_env_var_name(name) {
+                    anyhow::bail!("`{entry}` in allowed-tools must name an environment variable");
+                }
+                permissions.env_vars.push(name.to_string());
+            }
+        }
+        Ok(permissions)
+    }
+}
+
+/// Returns the argument of an `allowed-tools` entry of the form
+/// `<permission>(<argument>)`, or `None` for entries of other forms.
+fn permission_argument<'a>(entry: &'a str, permission: &str) -> Result<Option<&'a str>> {
+    let Some(rest) = entry.strip_prefix(permission) else {
+        return Ok(None);
+    };
+    let Some(argument) = rest.strip_prefix('(') else {
+        return Ok(None);
+    };
+    match argument.strip_suffix(')').map(str::trim) {
+        Some(argument) if !argument.is_empty() => Ok(Some(argument)),
+        _ => anyhow::bail!("`{entry}` in allowed-tools must be written as `{permission}(<value>)`"),
+    }
+}
+
+fn is_env_var_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|char| char.is_ascii_alphanumeric() || char == '_')
+}
+
+fn is_plain_relative_path(path: &Path) -> bool {
+    path.components().all(|component| {
+        matches!(
+            component,
+            std::path::Component::Normal(_) | std::path::Component::CurDir
+        )
+    })
+}
+
+/// Resolve the path of a script bundled with `skill`. `script` is relative
+/// to the skill's `scripts/` directory, and may repeat the `scripts/` prefix
+/// as SKILL.md bodies tend to spell it.
+pub fn resolve_skill_script(skill: &Skill, script: &str) -> Result<PathBuf> {
+    if skill.source == SkillSource::BuiltIn {
+        anyhow::bail!("Built-in skills don't bundle scripts");
+    }
+    let script = Path::new(script);
+    let script = script
+        .strip_prefix(SKILL_SCRIPTS_DIR_NAME)
+        .unwrap_or(script);
+    if script.as_os_str().is_empty() || !is_plain_relative_path(script) {
+        anyhow::bail!(
+            "Script paths must be relative to the skill's `{SKILL_SCRIPTS_DIR_NAME}` directory, \
+             without `..`"
+        );
+    }
+    Ok(skill
+        .directory_path
+        .join(SKILL_SCRIPTS_DIR_NAME)
+        .join(script))
+}
+
+/// List the scripts bundled with `skill`, relative to its `scripts/`
+/// directory. Like skill discovery, this only looks one level deep.
+pub async fn list_skill_scripts(fs: &dyn Fs, skill: &Skill) -> Vec<String> {
+    let scripts_dir = skill.directory_path.join(SKILL_SCRIPTS_DIR_NAME);
+    let Ok(mut entries) = fs.read_dir(&scripts_dir).await else {
+        return Vec::new();
+    };
+
+    let mut scripts = Vec::new();
+    while let Some(entry) = entries.next().await {
+        let Ok(entry_path) = entry else {
+            continue;
+        };
+        if fs.is_file(&entry_path).await
+            && let Some(name) = entry_path.file_name()
+        {
+            scripts.push(name.to_string_lossy().into_owned());
+        }
+    }
+    scripts.sort();
+    scripts
+}
+
 /// Content of the built-in `create-skill` SKILL.md, embedded at compile time.
 const CREATE_SKILL_CONTENT: &str = include_str!("builtin/create-skill/SKILL.md");
 
@@ -724,6 +883,7 @@ fn parse_builtin_skill(name: &str, content: &'static str) -> Result<Skill> {
         skill_file_path: synthetic_path,
         load_warnings: Vec::new(),
         disable_model_invocation: metadata.disable_model_invocation,
+        allowed_tools: metadata.allowed_tools,
         embedded_body: Some(body.trim()),
     })
 }
@@ -1829,6 +1989,7 @@ description: A skill with no body content
             skill_file_path: PathBuf::from("/skills/test-skill/SKILL.md"),
             load_warnings: Vec::new(),
             disable_model_invocation: false,
+            allowed_tools: Vec::new(),
             embedded_body: None,
         };
 
@@ -2008,6 +2169,156 @@ description: A skill with no body content
         assert!(body.is_empty(), "expected empty body, got: {body:?}");
     }
 
+    #[test]
+    fn test_parse_allowed_tools() {
+        let content = r#"---
+name: release-notes
+description: Draft release notes.
+allowed-tools: Read Network(api.github.com) Write(CHANGELOG.md) Env(GITHUB_TOKEN)
+---
+"#;
+        let skill = parse_skill_frontmatter(
+            Path::new("/skills/release-notes/SKILL.md"),
+            content,
+            SkillSource::Global,
+        )
+        .expect("should parse");
+        assert_eq!(
+            skill.allowed_tools,
+            [
+                "Read",
+                "Network(api.github.com)",
+                "Write(CHANGELOG.md)",
+                "Env(GITHUB_TOKEN)"
+            ]
+        );
+        assert_eq!(
+            skill.script_permissions().unwrap(),
+            SkillScriptPermissions {
+                network_hosts: vec!["api.github.com".to_string()],
+                write_paths: vec![PathBuf::from("CHANGELOG.md")],
+                env_vars: vec!["GITHUB_TOKEN".to_string()],
+            }
+        );
+
+        let content = r#"---
+name: release-notes
+description: Draft release notes.
+allowed-tools:
+  - Network(*.npmjs.org)
+---
+"#;
+        let skill = parse_skill_frontmatter(
+            Path::new("/skills/release-notes/SKILL.md"),
+            content,
+            SkillSource::Global,
+        )
+        .expect("should parse");
+        assert_eq!(
+            skill.script_permissions().unwrap().network_hosts,
+            ["*.npmjs.org"]
+        );
+    }
+
+    #[test]
+    fn test_script_permissions_reject_malformed_entries() {
+        let skill_with = |allowed_tools: &[&str]| Skill {
+            allowed_tools: allowed_tools.iter().map(|tool| tool.to_string()).collect(),
+            ..builtin_skills().remove(0)
+        };
+
+        assert!(skill_with(&["Network()"]).script_permissions().is_err());
+        assert!(
+            skill_with(&["Network(github.com"])
+                .script_permissions()
+                .is_err()
+        );
+        assert!(
+            skill_with(&["Write(../outside)"])
+                .script_permissions()
+                .is_err()
+        );
+        assert!(skill_with(&["Write(/etc)"]).script_permissions().is_err());
+        assert!(
+            skill_with(&["Env(1PASSWORD)"])
+                .script_permissions()
+                .is_err()
+        );
+        assert!(skill_with(&["Env(A=B)"]).script_permissions().is_err());
+        assert_eq!(
+            skill_with(&["NetworkAccess", "Bash(git:*)"])
+                .script_permissions()
+                .unwrap(),
+            SkillScriptPermissions::default()
+        );
+    }
+
+    #[test]
+    fn test_resolve_skill_script() {
+        let skill = parse_skill_frontmatter(
+            Path::new("/skills/pdf/SKILL.md"),
+            "---\nname: pdf\ndescription: Work with PDFs\n---\n",
+            SkillSource::Global,
+        )
+        .unwrap();
+
+        assert_eq!(
+            resolve_skill_script(&skill, "extract.py").unwrap(),
+            PathBuf::from("/skills/pdf/scripts/extract.py")
+        );
+        assert_eq!(
+            resolve_skill_script(&skill, "scripts/tools/merge.sh").unwrap(),
+            PathBuf::from("/skills/pdf/scripts/tools/merge.sh")
+        );
+        assert!(resolve_skill_script(&skill, "../SKILL.md").is_err());
+        assert!(resolve_skill_script(&skill, "/bin/sh").is_err());
+        assert!(resolve_skill_script(&skill, "scripts").is_err());
+        assert!(resolve_skill_script(&builtin_skills()[0], "anything.sh").is_err());
+    }
+
+    #[gpui::test]
+    async fn test_list_skill_scripts(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/skills",
+            serde_json::json!({
+                "pdf": {
+                    "SKILL.md": "---\nname: pdf\ndescription: Work with PDFs\n---\n",
+                    "scripts": {
+                        "merge.sh": "",
+                        "extract.py": "",
+                        "helpers": { "common.py": "" }
+                    }
+                },
+                "empty": {
+                    "SKILL.md": "---\nname: empty\ndescription: No scripts\n---\n"
+                }
+            }),
+        )
+        .await;
+
+        let skill = load_skill_frontmatter(
+            fs.clone(),
+            PathBuf::from("/skills/pdf/SKILL.md"),
+            SkillSource::Global,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            list_skill_scripts(fs.as_ref(), &skill).await,
+            ["extract.py", "merge.sh"]
+        );
+
+        let skill = load_skill_frontmatter(
+            fs.clone(),
+            PathBuf::from("/skills/empty/SKILL.md"),
+            SkillSource::Global,
+        )
+        .await
+        .unwrap();
+        assert!(list_skill_scripts(fs.as_ref(), &skill).await.is_empty());
+    }
+
     #[test]
     fn is_agents_skills_path_simple_positive() {
         assert!(is_agents_skills_path(Path::new(
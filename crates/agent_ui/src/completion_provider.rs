@@ -7,18 +7,23 @@ use std::sync::atomic::AtomicBool;
 use crate::DEFAULT_THREAD_TITLE;
 use crate::thread_metadata_store::{ThreadMetadata, ThreadMetadataStore};
 use acp_thread::MentionUri;
+use agent::{ContextServerResource, ContextServerResourceTemplate, ContextServerResources};
 use agent_client_protocol::schema::v1 as acp;
 use anyhow::Result;
+use collections::HashMap;
+use context_server::ContextServerId;
 use editor::{CompletionProvider, Editor, code_context_menus::COMPLETION_MENU_MAX_WIDTH};
 use futures::FutureExt as _;
 use fuzzy::{PathMatch, StringMatch, StringMatchCandidate};
 use gpui::{
-    App, BackgroundExecutor, Entity, Focusable, Hsla, SharedString, Task, WeakEntity, Window,
+    App, AsyncApp, BackgroundExecutor, Entity, Focusable, Hsla, SharedString, Task, WeakEntity,
+    Window,
 };
 use language::{Buffer, CodeLabel, CodeLabelBuilder, HighlightId};
 use lsp::CompletionContext;
 use multi_buffer::ToOffset as _;
 use ordered_float::OrderedFloat;
+use project::context_server_store::ContextServerStore;
 use project::lsp_store::{CompletionDocumentation, SymbolLocation};
 use project::{
     Completion, CompletionDisplayOptions, CompletionGroup, CompletionIntent, CompletionResponse,
@@ -32,6 +37,7 @@ use terminal_view::{TerminalView, terminal_panel::TerminalPanel};
 use text::{Anchor, ToOffset as _, ToPoint as _};
 use ui::IconName;
 use ui::prelude::*;
+use url::Url;
 use util::ResultExt as _;
 use util::paths::PathStyle;
 use util::rel_path::RelPath;
@@ -163,6 +169,7 @@ pub(crate) enum PromptContextType {
     Skill,
     Diagnostics,
     BranchDiff,
+    Resource,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -202,6 +209,7 @@ impl TryFrom<&str> for PromptContextType {
             "skill" => Ok(Self::Skill),
             "diagnostics" => Ok(Self::Diagnostics),
             "diff" => Ok(Self::BranchDiff),
+            "resource" => Ok(Self::Resource),
             _ => Err(format!("Invalid context picker mode: {}", value)),
         }
     }
@@ -217,6 +225,7 @@ impl PromptContextType {
             Self::Skill => "skill",
             Self::Diagnostics => "diagnostics",
             Self::BranchDiff => "branch diff",
+            Self::Resource => "resource",
         }
     }
 
@@ -229,6 +238,7 @@ impl PromptContextType {
             Self::Skill => "Skills",
             Self::Diagnostics => "Diagnostics",
             Self::BranchDiff => "Branch Diff",
+            Self::Resource => "MCP Resources",
         }
     }
 
@@ -241,6 +251,7 @@ impl PromptContextType {
             Self::Skill => IconName::Sparkle,
             Self::Diagnostics => IconName::Warning,
             Self::BranchDiff => IconName::GitBranch,
+            Self::Resource => IconName::Server,
         }
     }
 }
@@ -254,6 +265,7 @@ pub(crate) enum Match {
     Skill(AvailableSkill),
     Entry(EntryMatch),
     BranchDiff(BranchDiffMatch),
+    Resource(ResourceMatch),
 }
 
 #[derive(Debug, Clone)]
@@ -272,10 +284,26 @@ impl Match {
             Match::Skill(_) => 1.,
             Match::Fetch(_) => 1.,
             Match::BranchDiff(_) => 1.,
+            Match::Resource(_) => 1.,
         }
     }
 }
 
+pub struct ResourceMatch {
+    server_id: ContextServerId,
+    label: SharedString,
+    description: Option<SharedString>,
+    target: ResourceTarget,
+}
+
+enum ResourceTarget {
+    /// A resource that can be attached as is.
+    Resource(Url),
+    /// A resource template whose remaining arguments still have to be filled
+    /// in, continuing from this text.
+    Template(String),
+}
+
 #[derive(Debug, Clone)]
 pub struct SessionMatch {
     session_id: acp::SessionId,
@@ -755,6 +783,79 @@ impl<T: PromptCompletionProviderDelegate> PromptCompletionProvider<T> {
         })
     }
 
+    fn completion_for_resource(
+        resource: ResourceMatch,
+        source_range: Range<Anchor>,
+        source: Arc<T>,
+        editor: WeakEntity<Editor>,
+        mention_set: WeakEntity<MentionSet>,
+        workspace: Entity<Workspace>,
+        cx: &mut App,
+    ) -> Completion {
+        let source_highlight_id = cx
+            .theme()
+            .syntax()
+            .highlight_id("variable")
+            .map(HighlightId::new);
+        let name: Arc<str> = resource.label.as_ref().into();
+        let server_name = SharedString::from(resource.server_id.0.clone());
+        let label = build_slash_item_label(&name, Some(&server_name), source_highlight_id);
+        let documentation = resource
+            .description
+            .map(CompletionDocumentation::MultiLinePlainText);
+
+        match resource.target {
+            ResourceTarget::Resource(uri) => {
+                let uri = MentionUri::ContextServerResource {
+                    server_id: resource.server_id.0.to_string(),
+                    uri,
+                    name: resource.label.to_string(),
+                };
+                let new_text = format!("{} ", uri.as_link());
+                let new_text_len = new_text.len();
+                Completion {
+                    replace_range: source_range.clone(),
+                    new_text,
+                    label,
+                    documentation,
+                    insert_text_mode: None,
+                    source: project::CompletionSource::Custom,
+                    match_start: None,
+                    snippet_deduplication_key: None,
+                    icon_path: Some(uri.icon_path(cx)),
+                    icon_color: None,
+                    confirm: Some(confirm_completion_callback(
+                        resource.label,
+                        source_range.start,
+                        new_text_len - 1,
+                        uri,
+                        source,
+                        editor,
+                        mention_set,
+                        workspace,
+                    )),
+                    group: None,
+                }
+            }
+            ResourceTarget::Template(text) => Completion {
+                replace_range: source_range,
+                new_text: format!("@{} {}", PromptContextType::Resource.keyword(), text),
+                label,
+                documentation,
+                insert_text_mode: None,
+                source: project::CompletionSource::Custom,
+                match_start: None,
+                snippet_deduplication_key: None,
+                icon_path: Some(IconName::Server.path().into()),
+                icon_color: None,
+                // Keeps the completion menu open so the template's next
+                // argument can be picked.
+                confirm: Some(Arc::new(|_, _, _| true)),
+                group: None,
+            },
+        }
+    }
+
     pub(crate) fn completion_for_action(
         action: PromptContextAction,
         source_range: Range<Anchor>,
@@ -1089,6 +1190,15 @@ impl<T: PromptCompletionProviderDelegate> PromptCompletionProvider<T> {
 
             Some(PromptContextType::BranchDiff) => Task::ready(Vec::new()),
 
+            Some(PromptContextType::Resource) => {
+                let server_store = workspace.read(cx).project().read(cx).context_server_store();
+                let resources = agent::list_resources(&server_store, cx);
+                cx.spawn(async move |cx| {
+                    let resources = resources.await;
+                    search_resources(query, resources, server_store, cancellation_flag, cx).await
+                })
+            }
+
             None if query.is_empty() => {
                 let recent_task = self.recent_context_picker_entries(&workspace, cx);
                 let entries = self
@@ -1321,6 +1431,14 @@ impl<T: PromptCompletionProviderDelegate> PromptCompletionProvider<T> {
             entries.push(PromptContextEntry::Mode(PromptContextType::Fetch));
         }
 
+        if self
+            .source
+            .supports_context(PromptContextType::Resource, cx)
+            && has_context_server_resources(workspace, cx)
+        {
+            entries.push(PromptContextEntry::Mode(PromptContextType::Resource));
+        }
+
         if self
             .source
             .supports_context(PromptContextType::Diagnostics, cx)
@@ -1753,6 +1871,17 @@ impl<T: PromptCompletionProviderDelegate> CompletionProvider for PromptCompletio
                                             cx,
                                         ))
                                     }
+                                    Match::Resource(resource) => {
+                                        Some(Self::completion_for_resource(
+                                            resource,
+                                            source_range.clone(),
+                                            source.clone(),
+                                            editor.clone(),
+                                            mention_set.clone(),
+                                            workspace.clone(),
+                                            cx,
+                                        ))
+                                    }
                                 };
                                 if let Some(completion) = &mut completion {
                                     completion.group = group;
@@ -2411,6 +2540,279 @@ pub(crate) fn search_skills(
     })
 }
 
+fn has_context_server_resources(workspace: &Entity<Workspace>, cx: &App) -> bool {
+    workspace
+        .read(cx)
+        .project()
+        .read(cx)
+        .context_server_store()
+        .read(cx)
+        .running_servers()
+        .iter()
+        .any(|server| {
+            server.client().is_some_and(|client| {
+                client.capable(context_server::protocol::ServerCapability::Resources)
+            })
+        })
+}
+
+/// Matches the query against the servers' resources and resource templates.
+/// Queries that fill in a template are completed by the template's server.
+async fn search_resources(
+    query: String,
+    resources: ContextServerResources,
+    server_store: Entity<ContextServerStore>,
+    cancellation_flag: Arc<AtomicBool>,
+    cx: &mut AsyncApp,
+) -> Vec<Match> {
+    let mut matches = Vec::new();
+    let mut argument_completions = Vec::new();
+    let mut candidates = Vec::new();
+
+    for ContextServerResourceTemplate {
+        server_id,
+        template,
+    } in resources.templates
+    {
+        let label: SharedString = template.title.unwrap_or(template.name).into();
+        let description = template.description.map(SharedString::from);
+        match match_resource_template(&template.uri_template, &query) {
+            Some(ResourceTemplateMatch::Prefix { text }) => {
+                matches.push(Match::Resource(ResourceMatch {
+                    server_id,
+                    label,
+                    description,
+                    target: ResourceTarget::Template(text),
+                }));
+            }
+            Some(ResourceTemplateMatch::Expanded) => {
+                if let Ok(uri) = Url::parse(&query) {
+                    matches.push(Match::Resource(ResourceMatch {
+                        server_id,
+                        label: query.clone().into(),
+                        description: Some(label),
+                        target: ResourceTarget::Resource(uri),
+                    }));
+                }
+            }
+            Some(ResourceTemplateMatch::Argument {
+                name,
+                value,
+                prefix,
+                suffix,
+                arguments,
+                is_last,
+            }) => {
+                let values = cx.update(|cx| {
+                    agent::complete_resource_template_argument(
+                        &server_store,
+                        &server_id,
+                        template.uri_template,
+                        name,
+                        value.clone(),
+                        arguments,
+                        cx,
+                    )
+                });
+                argument_completions
+                    .push((server_id, label, prefix, suffix, value, is_last, values));
+            }
+            None => candidates.push(ResourceMatch {
+                server_id,
+                label,
+                description,
+                target: ResourceTarget::Template(
+                    template
+                        .uri_template
+                        .split('{')
+                        .next()
+                        .unwrap_or_default()
+                        .to_string(),
+                ),
+            }),
+        }
+    }
+
+    for (server_id, label, prefix, suffix, value, is_last, values) in argument_completions {
+        let mut values = values.await.log_err().unwrap_or_default();
+        // The last argument can be attached with whatever the user typed.
+        if is_last && !value.is_empty() && !values.contains(&value) {
+            values.insert(0, value);
+        }
+        for value in values {
+            let text = format!("{prefix}{value}{suffix}");
+            let target = if is_last {
+                let Ok(uri) = Url::parse(&text) else {
+                    continue;
+                };
+                ResourceTarget::Resource(uri)
+            } else {
+                ResourceTarget::Template(text.clone())
+            };
+            matches.push(Match::Resource(ResourceMatch {
+                server_id: server_id.clone(),
+                label: text.into(),
+                description: Some(label.clone()),
+                target,
+            }));
+        }
+    }
+
+    candidates.extend(resources.resources.into_iter().map(
+        |ContextServerResource {
+             server_id,
+             resource,
+         }| ResourceMatch {
+            server_id,
+            label: resource.title.unwrap_or(resource.name).into(),
+            description: resource.description.map(SharedString::from),
+            target: ResourceTarget::Resource(resource.uri),
+        },
+    ));
+
+    if query.is_empty() {
+        matches.extend(candidates.into_iter().map(Match::Resource));
+        return matches;
+    }
+
+    let string_candidates = candidates
+        .iter()
+        .enumerate()
+        .map(|(id, candidate)| {
+            let text = match &candidate.target {
+                ResourceTarget::Resource(uri) => format!("{} {}", candidate.label, uri),
+                ResourceTarget::Template(_) => candidate.label.to_string(),
+            };
+            StringMatchCandidate::new(id, &text)
+        })
+        .collect::<Vec<_>>();
+    let fuzzy_matches = fuzzy::match_strings(
+        &string_candidates,
+        &query,
+        false,
+        true,
+        100,
+        &cancellation_flag,
+        cx.background_executor().clone(),
+    )
+    .await;
+
+    let mut candidates = candidates.into_iter().map(Some).collect::<Vec<_>>();
+    matches.extend(
+        fuzzy_matches
+            .into_iter()
+            .filter_map(|mat| candidates[mat.candidate_id].take())
+            .map(Match::Resource),
+    );
+    matches
+}
+
+#[derive(Debug, PartialEq)]
+enum ResourceTemplateMatch {
+    /// The query is a prefix of the template's text up to its next argument,
+    /// which `text` holds.
+    Prefix { text: String },
+    /// The query ends while filling in the argument `name`.
+    Argument {
+        name: String,
+        value: String,
+        /// The query up to the argument's value.
+        prefix: String,
+        /// The template's text between this argument and the next one.
+        suffix: String,
+        /// The values of the arguments before this one.
+        arguments: HashMap<String, String>,
+        is_last: bool,
+    },
+    /// The query fills in all of the template's arguments.
+    Expanded,
+}
+
+enum UriTemplatePart<'a> {
+    Literal(&'a str),
+    Argument(&'a str),
+}
+
+/// Splits a URI template into its literal text and its arguments. Operators
+/// and modifiers of the argument expressions are dropped.
+fn parse_uri_template(template: &str) -> Vec<UriTemplatePart<'_>> {
+    let mut parts = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{')
+        && let Some(len) = rest[start..].find('}')
+    {
+        if start > 0 {
+            parts.push(UriTemplatePart::Literal(&rest[..start]));
+        }
+        let expression = &rest[start + 1..start + len];
+        let name = expression
+            .trim_start_matches(['+', '#', '.', '/', ';', '?', '&'])
+            .split(':')
+            .next()
+            .unwrap_or_default()
+            .trim_end_matches('*');
+        parts.push(UriTemplatePart::Argument(name));
+        rest = &rest[start + len + 1..];
+    }
+    if !rest.is_empty() {
+        parts.push(UriTemplatePart::Literal(rest));
+    }
+    parts
+}
+
+fn match_resource_template(template: &str, query: &str) -> Option<ResourceTemplateMatch> {
+    let parts = parse_uri_template(template);
+    let mut remaining = query;
+    let mut arguments = HashMap::default();
+
+    for (ix, part) in parts.iter().enumerate() {
+        let consumed = &query[..query.len() - remaining.len()];
+        match part {
+            UriTemplatePart::Literal(literal) => {
+                if let Some(rest) = remaining.strip_prefix(literal) {
+                    remaining = rest;
+                } else if literal.starts_with(remaining) {
+                    return Some(ResourceTemplateMatch::Prefix {
+                        text: format!("{consumed}{literal}"),
+                    });
+                } else {
+                    return None;
+                }
+            }
+            UriTemplatePart::Argument(name) => {
+                let suffix = match parts.get(ix + 1) {
+                    Some(UriTemplatePart::Literal(literal)) => *literal,
+                    _ => "",
+                };
+                let value_end = if suffix.is_empty() {
+                    None
+                } else {
+                    remaining.find(suffix)
+                };
+                let Some(value_end) = value_end else {
+                    let is_last = parts[ix + 1..]
+                        .iter()
+                        .all(|part| matches!(part, UriTemplatePart::Literal(_)));
+                    return Some(ResourceTemplateMatch::Argument {
+                        name: name.to_string(),
+                        value: remaining.to_string(),
+                        prefix: consumed.to_string(),
+                        suffix: suffix.to_string(),
+                        arguments,
+                        is_last,
+                    });
+                };
+                arguments.insert(name.to_string(), remaining[..value_end].to_string());
+                remaining = &remaining[value_end..];
+            }
+        }
+    }
+
+    remaining
+        .is_empty()
+        .then_some(ResourceTemplateMatch::Expanded)
+}
+
 pub struct SymbolMatch {
     pub symbol: Symbol,
 }
@@ -2904,6 +3306,62 @@ mod tests {
         assert_eq!(order, vec!["skill-a", "compact"]);
     }
 
+    #[test]
+    fn test_match_resource_template() {
+        let template = "log://{service}/{date}.txt";
+
+        assert_eq!(
+            match_resource_template(template, ""),
+            Some(ResourceTemplateMatch::Prefix {
+                text: "log://".into()
+            })
+        );
+        assert_eq!(match_resource_template(template, "logs"), None);
+        assert_eq!(
+            match_resource_template(template, "log://ap"),
+            Some(ResourceTemplateMatch::Argument {
+                name: "service".into(),
+                value: "ap".into(),
+                prefix: "log://".into(),
+                suffix: "/".into(),
+                arguments: HashMap::default(),
+                is_last: false,
+            })
+        );
+        assert_eq!(
+            match_resource_template(template, "log://api/2024"),
+            Some(ResourceTemplateMatch::Argument {
+                name: "date".into(),
+                value: "2024".into(),
+                prefix: "log://api/".into(),
+                suffix: ".txt".into(),
+                arguments: HashMap::from_iter([("service".into(), "api".into())]),
+                is_last: true,
+            })
+        );
+        assert_eq!(
+            match_resource_template(template, "log://api/2024-01-01.txt"),
+            Some(ResourceTemplateMatch::Expanded)
+        );
+        assert_eq!(
+            match_resource_template(template, "log://api/2024-01-01.txt.gz"),
+            None
+        );
+
+        // Operators and modifiers aren't part of the argument's name.
+        assert_eq!(
+            match_resource_template("file://{+path*}", "file:///src/ma"),
+            Some(ResourceTemplateMatch::Argument {
+                name: "path".into(),
+                value: "/src/ma".into(),
+                prefix: "file://".into(),
+                suffix: "".into(),
+                arguments: HashMap::default(),
+                is_last: true,
+            })
+        );
+    }
+
     #[test]
     fn test_mention_completion_parse() {
         let supported_modes = vec![PromptContextType::File, PromptContextType::Symbol];
@@ -0,0 +1,349 @@
+mod elicitation_modal;
+mod sampling_modal;
+
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use context_server::{
+    ContextServerId,
+    types::{self, CreateMessageRequest, CreateMessageResult},
+};
+use futures::{
+    Stream, StreamExt as _,
+    channel::{mpsc, oneshot},
+};
+use gpui::{App, AsyncApp, Context, Window};
+use language_model::{
+    LanguageModel, LanguageModelCompletionError, LanguageModelCompletionEvent,
+    LanguageModelRegistry, LanguageModelRequest, LanguageModelRequestMessage, Role, StopReason,
+};
+use project::context_server_store::ContextServerRequestEvent;
+use workspace::Workspace;
+
+use elicitation_modal::{ElicitationModal, ElicitationRequest};
+use sampling_modal::{SamplingModal, SamplingRequest};
+
+/// A rough number of bytes per token, used to enforce a server's `maxTokens`
+/// before the model has reported how many tokens it produced.
+const BYTES_PER_TOKEN: usize = 4;
+
+/// Answers the sampling and elicitation requests of the context servers
+/// running for each workspace's project.
+pub(crate) fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, window, cx| {
+        let Some(window) = window else {
+            return;
+        };
+
+        let context_server_store = workspace.project().read(cx).context_server_store();
+        cx.subscribe_in(
+            &context_server_store,
+            window,
+            |workspace, _, event: &ContextServerRequestEvent, window, cx| match event {
+                ContextServerRequestEvent::CreateMessage {
+                    server_id,
+                    params,
+                    sender,
+                } => create_message(
+                    workspace,
+                    server_id.clone(),
+                    params.clone(),
+                    sender.clone(),
+                    window,
+                    cx,
+                ),
+                ContextServerRequestEvent::Elicit {
+                    server_id,
+                    params,
+                    sender,
+                } => {
+                    let request = ElicitationRequest {
+                        server_id: server_id.clone(),
+                        params: params.clone(),
+                        sender: sender.clone(),
+                    };
+                    if let Some(modal) = workspace.active_modal::<ElicitationModal>(cx) {
+                        modal.update(cx, |modal, _| modal.push_request(request));
+                    } else {
+                        workspace.toggle_modal(window, cx, |window, cx| {
+                            ElicitationModal::new(request, window, cx)
+                        });
+                    }
+                }
+            },
+        )
+        .detach();
+    })
+    .detach();
+}
+
+/// Shows the user the whole request the server wants to send to their
+/// language model, and sends it if they allow it.
+fn create_message(
+    workspace: &mut Workspace,
+    server_id: ContextServerId,
+    params: Arc<CreateMessageRequest>,
+    mut sender: mpsc::Sender<Result<CreateMessageResult>>,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(model) = sampling_model(&params, cx) else {
+        sender
+            .try_send(Err(anyhow!("no language model is configured")))
+            .ok();
+        return;
+    };
+
+    let (answer, answer_rx) = oneshot::channel();
+    let request = SamplingRequest {
+        server_id,
+        model_name: model.name().0,
+        params: params.clone(),
+        answer,
+    };
+    if let Some(modal) = workspace.active_modal::<SamplingModal>(cx) {
+        modal.update(cx, |modal, _| modal.push_request(request));
+    } else {
+        workspace.toggle_modal(window, cx, |_, cx| SamplingModal::new(request, cx));
+    }
+
+    cx.spawn(async move |_, cx| {
+        let result = match answer_rx.await {
+            Ok(true) => sample(model, &params, cx).await,
+            _ => Err(anyhow!("the user denied the sampling request")),
+        };
+        sender.try_send(result).ok();
+    })
+    .detach();
+}
+
+/// Picks the first available model matching the server's hints, falling back
+/// to the user's default model.
+fn sampling_model(params: &CreateMessageRequest, cx: &App) -> Option<Arc<dyn LanguageModel>> {
+    let registry = LanguageModelRegistry::read_global(cx);
+    let hints = params
+        .model_preferences
+        .as_ref()
+        .and_then(|preferences| preferences.hints.as_ref())
+        .into_iter()
+        .flatten()
+        .filter_map(|hint| hint.name.as_deref());
+
+    for hint in hints {
+        if let Some(model) = registry
+            .available_models(cx)
+            .find(|model| model.id().0.contains(hint) || model.name().0.contains(hint))
+        {
+            return Some(model);
+        }
+    }
+
+    registry.default_model().map(|configured| configured.model)
+}
+
+async fn sample(
+    model: Arc<dyn LanguageModel>,
+    params: &CreateMessageRequest,
+    cx: &AsyncApp,
+) -> Result<CreateMessageResult> {
+    let request = sampling_request(params)?;
+    let events = model.stream_completion(request, cx).await?;
+    let (text, stop_reason) = collect_sampled_text(events, params.max_tokens).await?;
+
+    Ok(CreateMessageResult {
+        role: types::Role::Assistant,
+        content: types::MessageContent::Text {
+            text,
+            annotations: None,
+        },
+        model: model.id().0.to_string(),
+        stop_reason: Some(
+            match stop_reason {
+                StopReason::EndTurn => "endTurn",
+                StopReason::MaxTokens => "maxTokens",
+                StopReason::ToolUse => "toolUse",
+                StopReason::Refusal => "refusal",
+            }
+            .into(),
+        ),
+    })
+}
+
+/// Collects the text of a completion, stopping it once it reaches
+/// `max_tokens`, either by the model's reported usage or by an estimate from
+/// the length of the text, as not every model reports usage while streaming.
+async fn collect_sampled_text(
+    mut events: impl Stream<Item = Result<LanguageModelCompletionEvent, LanguageModelCompletionError>>
+    + Unpin,
+    max_tokens: u32,
+) -> Result<(String, StopReason)> {
+    let max_tokens = max_tokens as usize;
+    let mut text = String::new();
+    let mut output_tokens = 0;
+    while let Some(event) = events.next().await {
+        match event? {
+            LanguageModelCompletionEvent::Text(chunk) => text.push_str(&chunk),
+            LanguageModelCompletionEvent::UsageUpdate(usage) => {
+                output_tokens = usage.output_tokens as usize;
+            }
+            LanguageModelCompletionEvent::Stop(reason) => return Ok((text, reason)),
+            _ => {}
+        }
+
+        let max_len = max_tokens.saturating_mul(BYTES_PER_TOKEN);
+        if text.len() >= max_len {
+            text.truncate(text.floor_char_boundary(max_len));
+            return Ok((text, StopReason::MaxTokens));
+        }
+        if output_tokens >= max_tokens {
+            return Ok((text, StopReason::MaxTokens));
+        }
+    }
+
+    Ok((text, StopReason::EndTurn))
+}
+
+fn sampling_request(params: &CreateMessageRequest) -> Result<LanguageModelRequest> {
+    let mut messages = Vec::new();
+    if let Some(system_prompt) = &params.system_prompt {
+        messages.push(LanguageModelRequestMessage {
+            role: Role::System,
+            content: vec![system_prompt.clone().into()],
+            cache: false,
+            reasoning_details: None,
+        });
+    }
+    for message in &params.messages {
+        let types::MessageContent::Text { text, .. } = &message.content else {
+            return Err(anyhow!("only text messages can be sampled"));
+        };
+        messages.push(LanguageModelRequestMessage {
+            role: match message.role {
+                types::Role::User => Role::User,
+                types::Role::Assistant => Role::Assistant,
+            },
+            content: vec![text.clone().into()],
+            cache: false,
+            reasoning_details: None,
+        });
+    }
+
+    Ok(LanguageModelRequest {
+        messages,
+        stop: params.stop_sequences.clone().unwrap_or_default(),
+        temperature: params.temperature.map(|temperature| temperature as f32),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use language_model::{MessageContent, TokenUsage};
+
+    #[test]
+    fn test_sampling_request() {
+        let params: CreateMessageRequest = serde_json::from_value(serde_json::json!({
+            "messages": [
+                { "role": "user", "content": { "type": "text", "text": "Summarize the diff" } },
+                { "role": "assistant", "content": { "type": "text", "text": "Which diff?" } }
+            ],
+            "systemPrompt": "Be brief",
+            "temperature": 0.5,
+            "maxTokens": 100,
+            "stopSequences": ["END"]
+        }))
+        .unwrap();
+
+        let request = sampling_request(&params).unwrap();
+        assert_eq!(
+            request
+                .messages
+                .iter()
+                .map(|message| (message.role, message.content.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (Role::System, vec![MessageContent::from("Be brief")]),
+                (Role::User, vec![MessageContent::from("Summarize the diff")]),
+                (Role::Assistant, vec![MessageContent::from("Which diff?")]),
+            ]
+        );
+        assert_eq!(request.stop, vec!["END".to_string()]);
+        assert_eq!(request.temperature, Some(0.5));
+
+        let params: CreateMessageRequest = serde_json::from_value(serde_json::json!({
+            "messages": [
+                {
+                    "role": "user",
+                    "content": { "type": "image", "data": "", "mimeType": "image/png" }
+                }
+            ],
+            "maxTokens": 100
+        }))
+        .unwrap();
+        assert!(sampling_request(&params).is_err());
+    }
+
+    #[test]
+    fn test_collect_sampled_text() {
+        let collect = |events: Vec<LanguageModelCompletionEvent>, max_tokens| {
+            futures::executor::block_on(collect_sampled_text(
+                futures::stream::iter(events.into_iter().map(Ok)),
+                max_tokens,
+            ))
+            .unwrap()
+        };
+
+        assert_eq!(
+            collect(
+                vec![
+                    LanguageModelCompletionEvent::Text("Hello".into()),
+                    LanguageModelCompletionEvent::Text(" world".into()),
+                    LanguageModelCompletionEvent::Stop(StopReason::EndTurn),
+                ],
+                100
+            ),
+            ("Hello world".to_string(), StopReason::EndTurn)
+        );
+
+        // Without usage from the model, the text is cut off at the estimated limit.
+        assert_eq!(
+            collect(
+                vec![
+                    LanguageModelCompletionEvent::Text("one two three four".into()),
+                    LanguageModelCompletionEvent::Stop(StopReason::EndTurn),
+                ],
+                2
+            ),
+            ("one two ".to_string(), StopReason::MaxTokens)
+        );
+
+        // The model's reported usage stops the completion before the estimate does.
+        assert_eq!(
+            collect(
+                vec![
+                    LanguageModelCompletionEvent::Text("one two".into()),
+                    LanguageModelCompletionEvent::UsageUpdate(TokenUsage {
+                        output_tokens: 4,
+                        ..Default::default()
+                    }),
+                    LanguageModelCompletionEvent::Text(" three".into()),
+                    LanguageModelCompletionEvent::Stop(StopReason::EndTurn),
+                ],
+                4
+            ),
+            ("one two".to_string(), StopReason::MaxTokens)
+        );
+
+        assert_eq!(
+            collect(
+                vec![
+                    LanguageModelCompletionEvent::Text("Done".into()),
+                    LanguageModelCompletionEvent::Stop(StopReason::Refusal),
+                ],
+                100
+            ),
+            ("Done".to_string(), StopReason::Refusal)
+        );
+    }
+}
@@ -0,0 +1,679 @@
+use std::{collections::VecDeque, sync::Arc};
+
+use anyhow::{Result, anyhow};
+use context_server::{
+    ContextServerId,
+    types::{ElicitAction, ElicitParams, ElicitResult, PrimitiveSchema},
+};
+use futures::channel::mpsc;
+use gpui::{DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, ScrollHandle};
+use serde_json::{Map, Value};
+use ui::{
+    Banner, Checkbox, ContextMenu, ContextMenuEntry, DropdownMenu, DropdownStyle, IconPosition,
+    KeyBinding, Modal, ModalFooter, ModalHeader, Section, ToggleState, WithScrollbar, prelude::*,
+};
+use ui_input::InputField;
+use workspace::ModalView;
+
+pub(crate) struct ElicitationRequest {
+    pub server_id: ContextServerId,
+    pub params: Arc<ElicitParams>,
+    pub sender: mpsc::Sender<Result<ElicitResult>>,
+}
+
+impl ElicitationRequest {
+    /// Answers the request. Only the first response is sent to the server.
+    fn respond(&mut self, result: ElicitResult) {
+        self.sender.try_send(Ok(result)).ok();
+        self.sender.close_channel();
+    }
+}
+
+enum FieldInput {
+    Text {
+        input: Entity<InputField>,
+        min_length: Option<usize>,
+        max_length: Option<usize>,
+    },
+    Number {
+        input: Entity<InputField>,
+        integer: bool,
+        minimum: Option<f64>,
+        maximum: Option<f64>,
+    },
+    Boolean(ToggleState),
+    Choice {
+        options: Vec<String>,
+        labels: Vec<SharedString>,
+        selected: Option<usize>,
+    },
+}
+
+struct Field {
+    name: String,
+    label: SharedString,
+    description: Option<SharedString>,
+    required: bool,
+    input: FieldInput,
+}
+
+impl Field {
+    fn new(
+        name: &str,
+        schema: &PrimitiveSchema,
+        required: bool,
+        tab_index: isize,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Self {
+        let label: SharedString = schema.title().unwrap_or(name).to_string().into();
+        let text_input = |default: Option<String>, window: &mut Window, cx: &mut App| {
+            cx.new(|cx| {
+                let input = InputField::new(window, cx, "")
+                    .label(label.clone())
+                    .tab_index(tab_index)
+                    .tab_stop(true);
+                if let Some(default) = default {
+                    input.set_text(&default, window, cx);
+                }
+                input
+            })
+        };
+
+        let input = match schema {
+            PrimitiveSchema::String {
+                options: Some(options),
+                enum_names,
+                default,
+                ..
+            } => FieldInput::Choice {
+                labels: options
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, option)| {
+                        enum_names
+                            .as_ref()
+                            .and_then(|names| names.get(ix))
+                            .unwrap_or(option)
+                            .clone()
+                            .into()
+                    })
+                    .collect(),
+                selected: default
+                    .as_ref()
+                    .and_then(|default| options.iter().position(|option| option == default)),
+                options: options.clone(),
+            },
+            PrimitiveSchema::String {
+                min_length,
+                max_length,
+                default,
+                ..
+            } => FieldInput::Text {
+                input: text_input(default.clone(), window, cx),
+                min_length: *min_length,
+                max_length: *max_length,
+            },
+            PrimitiveSchema::Number {
+                minimum,
+                maximum,
+                default,
+                ..
+            } => FieldInput::Number {
+                input: text_input(default.map(|default| default.to_string()), window, cx),
+                integer: false,
+                minimum: *minimum,
+                maximum: *maximum,
+            },
+            PrimitiveSchema::Integer {
+                minimum,
+                maximum,
+                default,
+                ..
+            } => FieldInput::Number {
+                input: text_input(default.map(|default| default.to_string()), window, cx),
+                integer: true,
+                minimum: minimum.map(|minimum| minimum as f64),
+                maximum: maximum.map(|maximum| maximum as f64),
+            },
+            PrimitiveSchema::Boolean { default, .. } => {
+                FieldInput::Boolean(default.unwrap_or(false).into())
+            }
+        };
+
+        Self {
+            name: name.to_string(),
+            label,
+            description: schema
+                .description()
+                .map(|description| description.to_string().into()),
+            required,
+            input,
+        }
+    }
+
+    /// Returns the value the user entered, or `None` if the field was left empty.
+    fn value(&self, cx: &App) -> Result<Option<Value>> {
+        let label = &self.label;
+        match &self.input {
+            FieldInput::Text {
+                input,
+                min_length,
+                max_length,
+            } => {
+                let text = input.read(cx).text(cx);
+                if text.is_empty() {
+                    return Ok(None);
+                }
+                let length = text.chars().count();
+                if let Some(min_length) = min_length
+                    && length < *min_length
+                {
+                    return Err(anyhow!(
+                        "{label} must be at least {min_length} characters long"
+                    ));
+                }
+                if let Some(max_length) = max_length
+                    && length > *max_length
+                {
+                    return Err(anyhow!(
+                        "{label} must be at most {max_length} characters long"
+                    ));
+                }
+                Ok(Some(Value::String(text)))
+            }
+            FieldInput::Number {
+                input,
+                integer,
+                minimum,
+                maximum,
+            } => {
+                let text = input.read(cx).text(cx);
+                let text = text.trim();
+                if text.is_empty() {
+                    return Ok(None);
+                }
+                let (value, number) = if *integer {
+                    let number = text
+                        .parse::<i64>()
+                        .map_err(|_| anyhow!("{label} must be a whole number"))?;
+                    (Value::from(number), number as f64)
+                } else {
+                    let number = text
+                        .parse::<f64>()
+                        .map_err(|_| anyhow!("{label} must be a number"))?;
+                    (Value::from(number), number)
+                };
+                if let Some(minimum) = minimum
+                    && number < *minimum
+                {
+                    return Err(anyhow!("{label} must be at least {minimum}"));
+                }
+                if let Some(maximum) = maximum
+                    && number > *maximum
+                {
+                    return Err(anyhow!("{label} must be at most {maximum}"));
+                }
+                Ok(Some(value))
+            }
+            FieldInput::Boolean(state) => Ok(Some(Value::Bool(state.selected()))),
+            FieldInput::Choice {
+                options, selected, ..
+            } => Ok(selected.map(|ix| Value::String(options[ix].clone()))),
+        }
+    }
+}
+
+/// Asks the user for the input a context server requested, showing a form
+/// built from the schema the server sent. Requests that arrive while the
+/// modal is open are shown one after another.
+pub(crate) struct ElicitationModal {
+    request: ElicitationRequest,
+    queue: VecDeque<ElicitationRequest>,
+    fields: Vec<Field>,
+    last_error: Option<SharedString>,
+    focus_handle: FocusHandle,
+    scroll_handle: ScrollHandle,
+}
+
+impl ElicitationModal {
+    pub(crate) fn new(
+        request: ElicitationRequest,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        Self {
+            fields: Self::fields(&request.params, window, cx),
+            request,
+            queue: VecDeque::new(),
+            last_error: None,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: ScrollHandle::new(),
+        }
+    }
+
+    pub(crate) fn push_request(&mut self, request: ElicitationRequest) {
+        self.queue.push_back(request);
+    }
+
+    fn fields(params: &ElicitParams, window: &mut Window, cx: &mut App) -> Vec<Field> {
+        let schema = &params.requested_schema;
+        schema
+            .properties
+            .iter()
+            .enumerate()
+            .map(|(ix, (name, property))| {
+                let required = schema.required.contains(name);
+                Field::new(name, property, required, ix as isize + 1, window, cx)
+            })
+            .collect()
+    }
+
+    fn content(&self, cx: &App) -> Result<Map<String, Value>> {
+        let mut content = Map::new();
+        for field in &self.fields {
+            match field.value(cx)? {
+                Some(value) => {
+                    content.insert(field.name.clone(), value);
+                }
+                None if field.required => {
+                    return Err(anyhow!("{} is required", field.label));
+                }
+                None => {}
+            }
+        }
+        Ok(content)
+    }
+
+    /// Sends the response to the current request and moves on to the next one.
+    fn respond(&mut self, result: ElicitResult, window: &mut Window, cx: &mut Context<Self>) {
+        self.request.respond(result);
+
+        if let Some(request) = self.queue.pop_front() {
+            self.fields = Self::fields(&request.params, window, cx);
+            self.request = request;
+            self.last_error = None;
+            cx.notify();
+        } else {
+            cx.emit(DismissEvent);
+        }
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        match self.content(cx) {
+            Ok(content) => self.respond(
+                ElicitResult {
+                    action: ElicitAction::Accept,
+                    content: Some(content),
+                },
+                window,
+                cx,
+            ),
+            Err(error) => {
+                self.last_error = Some(error.to_string().into());
+                cx.notify();
+            }
+        }
+    }
+
+    fn decline(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.respond(
+            ElicitResult {
+                action: ElicitAction::Decline,
+                content: None,
+            },
+            window,
+            cx,
+        );
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn on_tab(&mut self, _: &menu::SelectNext, window: &mut Window, cx: &mut Context<Self>) {
+        window.focus_next(cx);
+    }
+
+    fn on_tab_prev(
+        &mut self,
+        _: &menu::SelectPrevious,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        window.focus_prev(cx);
+    }
+
+    fn render_field(
+        &self,
+        ix: usize,
+        field: &Field,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let label = if field.required {
+            format!("{} *", field.label).into()
+        } else {
+            field.label.clone()
+        };
+
+        let input = match &field.input {
+            FieldInput::Text { input, .. } | FieldInput::Number { input, .. } => {
+                input.clone().into_any_element()
+            }
+            FieldInput::Boolean(state) => Checkbox::new(("elicitation-field", ix), *state)
+                .label(label)
+                .on_click(cx.listener(move |this, checked, _window, cx| {
+                    if let Some(FieldInput::Boolean(state)) =
+                        this.fields.get_mut(ix).map(|field| &mut field.input)
+                    {
+                        *state = *checked;
+                        cx.notify();
+                    }
+                }))
+                .into_any_element(),
+            FieldInput::Choice {
+                labels, selected, ..
+            } => {
+                let weak_self = cx.weak_entity();
+                let menu_labels = labels.clone();
+                let selected = *selected;
+                let menu = ContextMenu::build(window, cx, move |mut menu, _window, _cx| {
+                    for (option_ix, option_label) in menu_labels.iter().enumerate() {
+                        let weak_self = weak_self.clone();
+                        menu.push_item(
+                            ContextMenuEntry::new(option_label.clone())
+                                .toggleable(IconPosition::End, selected == Some(option_ix))
+                                .handler(move |_window, cx| {
+                                    weak_self
+                                        .update(cx, |this, cx| {
+                                            if let Some(FieldInput::Choice { selected, .. }) = this
+                                                .fields
+                                                .get_mut(ix)
+                                                .map(|field| &mut field.input)
+                                            {
+                                                *selected = Some(option_ix);
+                                                cx.notify();
+                                            }
+                                        })
+                                        .ok();
+                                }),
+                        );
+                    }
+                    menu
+                });
+
+                v_flex()
+                    .gap_1()
+                    .child(Label::new(label.clone()).size(LabelSize::Small))
+                    .child(
+                        DropdownMenu::new(
+                            ("elicitation-field", ix),
+                            selected
+                                .and_then(|selected| labels.get(selected).cloned())
+                                .unwrap_or_else(|| "Select…".into()),
+                            menu,
+                        )
+                        .style(DropdownStyle::Outlined)
+                        .trigger_size(ButtonSize::Compact)
+                        .full_width(true)
+                        .aria_label(label),
+                    )
+                    .into_any_element()
+            }
+        };
+
+        v_flex()
+            .gap_0p5()
+            .child(input)
+            .when_some(field.description.clone(), |this, description| {
+                this.child(
+                    Label::new(description)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+            .into_any_element()
+    }
+
+    #[cfg(test)]
+    fn field_input(&self, name: &str) -> Option<Entity<InputField>> {
+        self.fields
+            .iter()
+            .find(|field| field.name == name)
+            .and_then(|field| match &field.input {
+                FieldInput::Text { input, .. } | FieldInput::Number { input, .. } => {
+                    Some(input.clone())
+                }
+                _ => None,
+            })
+    }
+}
+
+impl Drop for ElicitationModal {
+    fn drop(&mut self) {
+        for request in std::iter::once(&mut self.request).chain(self.queue.iter_mut()) {
+            request.respond(ElicitResult {
+                action: ElicitAction::Cancel,
+                content: None,
+            });
+        }
+    }
+}
+
+impl EventEmitter<DismissEvent> for ElicitationModal {}
+
+impl Focusable for ElicitationModal {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl ModalView for ElicitationModal {}
+
+impl Render for ElicitationModal {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let focus_handle = self.focus_handle(cx);
+        let fields = self
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(ix, field)| self.render_field(ix, field, window, cx))
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .id("elicitation-modal")
+            .key_context("ElicitationModal")
+            .w(rems(34.))
+            .elevation_3(cx)
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::on_tab))
+            .on_action(cx.listener(Self::on_tab_prev))
+            .capture_any_mouse_down(cx.listener(|this, _, window, cx| {
+                this.focus_handle(cx).focus(window, cx);
+            }))
+            .child(
+                Modal::new("elicitation", None)
+                    .header(
+                        ModalHeader::new()
+                            .headline(format!("{} Needs Your Input", self.request.server_id))
+                            .description(self.request.params.message.clone()),
+                    )
+                    .when_some(self.last_error.clone(), |this, error| {
+                        this.section(
+                            Section::new().child(
+                                Banner::new()
+                                    .severity(Severity::Warning)
+                                    .child(div().text_xs().child(error)),
+                            ),
+                        )
+                    })
+                    .child(
+                        div()
+                            .size_full()
+                            .vertical_scrollbar_for(&self.scroll_handle, window, cx)
+                            .child(
+                                v_flex()
+                                    .id("elicitation-fields")
+                                    .size_full()
+                                    .tab_group()
+                                    .max_h(rems_from_px(400.))
+                                    .pl_3()
+                                    .pr_4()
+                                    .pb_2()
+                                    .gap_2()
+                                    .overflow_y_scroll()
+                                    .track_scroll(&self.scroll_handle)
+                                    .children(fields),
+                            ),
+                    )
+                    .footer(
+                        ModalFooter::new().end_slot(
+                            h_flex()
+                                .gap_1()
+                                .child(Button::new("decline", "Decline").on_click(
+                                    cx.listener(|this, _event, window, cx| {
+                                        this.decline(window, cx)
+                                    }),
+                                ))
+                                .child(
+                                    Button::new("submit", "Submit")
+                                        .key_binding(
+                                            KeyBinding::for_action_in(
+                                                &menu::Confirm,
+                                                &focus_handle,
+                                                cx,
+                                            )
+                                            .map(|kb| kb.size(rems_from_px(12.))),
+                                        )
+                                        .on_click(cx.listener(|this, _event, window, cx| {
+                                            this.confirm(&menu::Confirm, window, cx)
+                                        })),
+                                ),
+                        ),
+                    ),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use context_server::types::ElicitationSchema;
+    use fs::{FakeFs, Fs};
+    use futures::StreamExt as _;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::Project;
+    use settings::SettingsStore;
+    use util::path;
+    use workspace::MultiWorkspace;
+
+    #[gpui::test]
+    async fn test_elicitation_form(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            theme_settings::init(theme::LoadThemes::JustBase, cx);
+            editor::init(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        cx.update(|cx| <dyn Fs>::set_global(fs.clone(), cx));
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        let (_, cx) =
+            cx.add_window_view(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+
+        let params: ElicitParams = serde_json::from_value(serde_json::json!({
+            "message": "Configure the deployment",
+            "requestedSchema": {
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string", "title": "Name", "minLength": 3 },
+                    "replicas": { "type": "integer", "minimum": 1, "default": 2 },
+                    "region": { "type": "string", "enum": ["eu", "us"], "default": "eu" },
+                    "dry_run": { "type": "boolean", "default": true }
+                },
+                "required": ["name"]
+            }
+        }))
+        .unwrap();
+        let (sender, mut receiver) = mpsc::channel(1);
+        let modal = cx.update(|window, cx| {
+            cx.new(|cx| {
+                ElicitationModal::new(
+                    ElicitationRequest {
+                        server_id: ContextServerId("deploy".into()),
+                        params: Arc::new(params),
+                        sender,
+                    },
+                    window,
+                    cx,
+                )
+            })
+        });
+        let set_name = |name: &str, cx: &mut VisualTestContext| {
+            modal.update_in(cx, |modal, window, cx| {
+                modal
+                    .field_input("name")
+                    .unwrap()
+                    .update(cx, |input, cx| input.set_text(name, window, cx));
+                modal.confirm(&menu::Confirm, window, cx);
+            });
+        };
+
+        set_name("", cx);
+        modal.read_with(cx, |modal, _| {
+            assert_eq!(modal.last_error.as_deref(), Some("Name is required"));
+        });
+        set_name("ab", cx);
+        modal.read_with(cx, |modal, _| {
+            assert_eq!(
+                modal.last_error.as_deref(),
+                Some("Name must be at least 3 characters long")
+            );
+        });
+
+        set_name("api", cx);
+        let result = receiver.next().await.unwrap().unwrap();
+        assert_eq!(result.action, ElicitAction::Accept);
+        assert_eq!(
+            Value::Object(result.content.unwrap()),
+            serde_json::json!({
+                "name": "api",
+                "replicas": 2,
+                "region": "eu",
+                "dry_run": true
+            })
+        );
+    }
+
+    #[gpui::test]
+    async fn test_dismissing_elicitation_cancels_it(cx: &mut TestAppContext) {
+        let (sender, mut receiver) = mpsc::channel(1);
+        let cx = cx.add_empty_window();
+        let modal = cx.update(|window, cx| {
+            cx.new(|cx| {
+                ElicitationModal::new(
+                    ElicitationRequest {
+                        server_id: ContextServerId("deploy".into()),
+                        params: Arc::new(ElicitParams {
+                            message: "Continue?".into(),
+                            requested_schema: ElicitationSchema {
+                                properties: Default::default(),
+                                required: Vec::new(),
+                            },
+                        }),
+                        sender,
+                    },
+                    window,
+                    cx,
+                )
+            })
+        });
+        drop(modal);
+        cx.update(|_, _| {});
+
+        let result = receiver.next().await.unwrap().unwrap();
+        assert_eq!(result.action, ElicitAction::Cancel);
+    }
+}
@@ -0,0 +1,198 @@
+use std::{collections::VecDeque, sync::Arc};
+
+use context_server::{
+    ContextServerId,
+    types::{self, CreateMessageRequest},
+};
+use futures::channel::oneshot;
+use gpui::{DismissEvent, EventEmitter, FocusHandle, Focusable, ScrollHandle};
+use ui::{KeyBinding, Modal, ModalFooter, ModalHeader, WithScrollbar, prelude::*};
+use workspace::ModalView;
+
+pub(crate) struct SamplingRequest {
+    pub server_id: ContextServerId,
+    pub model_name: SharedString,
+    pub params: Arc<CreateMessageRequest>,
+    pub answer: oneshot::Sender<bool>,
+}
+
+impl SamplingRequest {
+    /// Answers the request. Only the first answer is sent.
+    fn respond(&mut self, allow: bool) {
+        let (answer, _) = oneshot::channel();
+        std::mem::replace(&mut self.answer, answer).send(allow).ok();
+    }
+}
+
+/// Shows everything a context server wants to send to the user's language
+/// model, and asks whether to send it. Requests that arrive while the modal
+/// is open are shown one after another.
+pub(crate) struct SamplingModal {
+    request: SamplingRequest,
+    queue: VecDeque<SamplingRequest>,
+    focus_handle: FocusHandle,
+    scroll_handle: ScrollHandle,
+}
+
+impl SamplingModal {
+    pub(crate) fn new(request: SamplingRequest, cx: &mut Context<Self>) -> Self {
+        Self {
+            request,
+            queue: VecDeque::new(),
+            focus_handle: cx.focus_handle(),
+            scroll_handle: ScrollHandle::new(),
+        }
+    }
+
+    pub(crate) fn push_request(&mut self, request: SamplingRequest) {
+        self.queue.push_back(request);
+    }
+
+    /// Answers the current request and moves on to the next one. Requests
+    /// that are never answered are denied when the modal is dismissed.
+    fn respond(&mut self, allow: bool, cx: &mut Context<Self>) {
+        self.request.respond(allow);
+
+        if let Some(request) = self.queue.pop_front() {
+            self.request = request;
+            self.scroll_handle.scroll_to_item(0);
+            cx.notify();
+        } else {
+            cx.emit(DismissEvent);
+        }
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        self.respond(true, cx);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_message(
+        ix: usize,
+        role: &'static str,
+        text: SharedString,
+        cx: &App,
+    ) -> impl IntoElement {
+        v_flex()
+            .id(("sampling-message", ix))
+            .gap_0p5()
+            .p_2()
+            .rounded_sm()
+            .border_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(Label::new(role).size(LabelSize::Small).color(Color::Muted))
+            .child(div().text_sm().child(text))
+    }
+}
+
+impl EventEmitter<DismissEvent> for SamplingModal {}
+
+impl Focusable for SamplingModal {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl ModalView for SamplingModal {}
+
+impl Render for SamplingModal {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let focus_handle = self.focus_handle(cx);
+        let params = &self.request.params;
+        let messages = params
+            .system_prompt
+            .iter()
+            .map(|system_prompt| ("System", SharedString::from(system_prompt.clone())))
+            .chain(params.messages.iter().map(|message| {
+                let role = match message.role {
+                    types::Role::User => "User",
+                    types::Role::Assistant => "Assistant",
+                };
+                let text = match &message.content {
+                    types::MessageContent::Text { text, .. } => text.clone().into(),
+                    types::MessageContent::Image { mime_type, .. } => {
+                        format!("[{mime_type} image]").into()
+                    }
+                    types::MessageContent::Audio { mime_type, .. } => {
+                        format!("[{mime_type} audio]").into()
+                    }
+                    types::MessageContent::Resource { .. } => "[embedded resource]".into(),
+                };
+                (role, text)
+            }))
+            .enumerate()
+            .map(|(ix, (role, text))| Self::render_message(ix, role, text, cx))
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .id("sampling-modal")
+            .key_context("SamplingModal")
+            .w(rems(34.))
+            .elevation_3(cx)
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .capture_any_mouse_down(cx.listener(|this, _, window, cx| {
+                this.focus_handle(cx).focus(window, cx);
+            }))
+            .child(
+                Modal::new("sampling", None)
+                    .header(
+                        ModalHeader::new()
+                            .headline(format!(
+                                "{} Wants to Use {}",
+                                self.request.server_id, self.request.model_name
+                            ))
+                            .description(format!(
+                                "The server will receive the model's response, up to {} tokens.",
+                                params.max_tokens
+                            )),
+                    )
+                    .child(
+                        div()
+                            .size_full()
+                            .vertical_scrollbar_for(&self.scroll_handle, window, cx)
+                            .child(
+                                v_flex()
+                                    .id("sampling-messages")
+                                    .size_full()
+                                    .max_h(rems_from_px(400.))
+                                    .pl_3()
+                                    .pr_4()
+                                    .pb_2()
+                                    .gap_2()
+                                    .overflow_y_scroll()
+                                    .track_scroll(&self.scroll_handle)
+                                    .children(messages),
+                            ),
+                    )
+                    .footer(
+                        ModalFooter::new().end_slot(
+                            h_flex()
+                                .gap_1()
+                                .child(Button::new("deny", "Deny").on_click(
+                                    cx.listener(|this, _event, _window, cx| {
+                                        this.respond(false, cx)
+                                    }),
+                                ))
+                                .child(
+                                    Button::new("allow", "Allow")
+                                        .key_binding(
+                                            KeyBinding::for_action_in(
+                                                &menu::Confirm,
+                                                &focus_handle,
+                                                cx,
+                                            )
+                                            .map(|kb| kb.size(rems_from_px(12.))),
+                                        )
+                                        .on_click(cx.listener(|this, _event, window, cx| {
+                                            this.confirm(&menu::Confirm, window, cx)
+                                        })),
+                                ),
+                        ),
+                    ),
+            )
+    }
+}
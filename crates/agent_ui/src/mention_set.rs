@@ -1,10 +1,11 @@
 use crate::diagnostics::{DiagnosticsOptions, codeblock_fence_for_path, collect_diagnostics};
 use acp_thread::{MentionUri, selection_name};
-use agent::{ThreadStore, outline};
+use agent::{ResourceSubscription, ThreadStore, outline};
 use agent_client_protocol::schema::v1 as acp;
 use agent_servers::{AgentServer, AgentServerDelegate};
 use anyhow::{Context as _, Result, anyhow};
 use collections::{HashMap, HashSet};
+use context_server::{ContextServerId, types::ResourceContentsType};
 use editor::{
     Anchor, Editor, EditorSnapshot, FoldPlaceholder, ToOffset,
     display_map::{Crease, CreaseId, CreaseMetadata, FoldId},
@@ -34,6 +35,7 @@ use std::{
 };
 use text::OffsetRangeExt;
 use ui::{Disclosure, Toggleable, prelude::*};
+use url::Url;
 use util::{ResultExt, debug_panic, rel_path::RelPath};
 use workspace::{Workspace, notifications::NotifyResultExt as _};
 
@@ -62,6 +64,7 @@ pub struct MentionSet {
     thread_store: Option<Entity<ThreadStore>>,
     mentions: HashMap<CreaseId, (MentionUri, MentionTask)>,
     crease_entities: HashMap<CreaseId, Entity<LoadingContext>>,
+    resource_subscriptions: HashMap<CreaseId, ResourceSubscription>,
 }
 
 impl MentionSet {
@@ -71,6 +74,7 @@ impl MentionSet {
             thread_store,
             mentions: HashMap::default(),
             crease_entities: HashMap::default(),
+            resource_subscriptions: HashMap::default(),
         }
     }
 
@@ -106,6 +110,7 @@ impl MentionSet {
             if !crease.range().start.is_valid(snapshot.buffer_snapshot()) {
                 self.mentions.remove(&crease_id);
                 self.crease_entities.remove(&crease_id);
+                self.resource_subscriptions.remove(&crease_id);
             }
         }
     }
@@ -164,6 +169,9 @@ impl MentionSet {
             MentionUri::Selection { abs_path: None, .. } => Task::ready(Err(anyhow!(
                 "Untitled buffer selection mentions are not supported for paste"
             ))),
+            MentionUri::ContextServerResource { server_id, uri, .. } => {
+                self.confirm_mention_for_context_server_resource(server_id, uri, cx)
+            }
             MentionUri::PastedImage { .. }
             | MentionUri::TerminalSelection { .. }
             | MentionUri::MergeConflict { .. }
@@ -176,6 +184,7 @@ impl MentionSet {
     pub fn remove_mention(&mut self, crease_id: &CreaseId, cx: &mut App) {
         self.mentions.remove(crease_id);
         self.crease_entities.remove(crease_id);
+        self.resource_subscriptions.remove(crease_id);
         self.recompute_disambiguation(cx);
     }
 
@@ -208,11 +217,14 @@ impl MentionSet {
     pub fn set_mentions(&mut self, mentions: HashMap<CreaseId, (MentionUri, MentionTask)>) {
         self.crease_entities
             .retain(|id, _| mentions.contains_key(id));
+        self.resource_subscriptions
+            .retain(|id, _| mentions.contains_key(id));
         self.mentions = mentions;
     }
 
     pub fn clear(&mut self) -> impl Iterator<Item = (CreaseId, (MentionUri, MentionTask))> {
         self.crease_entities.clear();
+        self.resource_subscriptions.clear();
         self.mentions.drain()
     }
 
@@ -352,6 +364,15 @@ impl MentionSet {
                 debug_panic!("unexpected rule URI");
                 Task::ready(Err(anyhow!("unexpected rule URI")))
             }
+            MentionUri::ContextServerResource { server_id, uri, .. } => {
+                self.subscribe_to_context_server_resource(
+                    crease_id,
+                    server_id.clone(),
+                    uri.clone(),
+                    cx,
+                );
+                self.confirm_mention_for_context_server_resource(server_id, uri, cx)
+            }
         };
         let task = cx
             .spawn(async move |_, _| task.await.map_err(|e| e.to_string()))
@@ -375,6 +396,7 @@ impl MentionSet {
                     });
                     this.mentions.remove(&crease_id);
                     this.crease_entities.remove(&crease_id);
+                    this.resource_subscriptions.remove(&crease_id);
                 })
                 .ok();
             }
@@ -452,6 +474,101 @@ impl MentionSet {
         })
     }
 
+    fn confirm_mention_for_context_server_resource(
+        &self,
+        server_id: String,
+        uri: Url,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Mention>> {
+        let Some(project) = self.project.upgrade() else {
+            return Task::ready(Err(anyhow!("project not found")));
+        };
+        let server_store = project.read(cx).context_server_store();
+        let response =
+            agent::read_resource(&server_store, &ContextServerId(server_id.into()), uri, cx);
+        cx.background_spawn(async move {
+            let mut content = String::new();
+            for contents in response.await?.contents {
+                match contents {
+                    ResourceContentsType::Text(text) => {
+                        if !content.is_empty() {
+                            content.push('\n');
+                        }
+                        content.push_str(&text.text);
+                    }
+                    ResourceContentsType::Blob(blob) => {
+                        log::warn!("Skipping binary contents of resource {}", blob.uri);
+                    }
+                }
+            }
+            if content.is_empty() {
+                return Err(anyhow!("The resource has no text contents"));
+            }
+            Ok(Mention::Text {
+                content,
+                tracked_buffers: Vec::new(),
+            })
+        })
+    }
+
+    /// Re-reads a mentioned resource whenever its server reports a change, so
+    /// the message is sent with its latest contents.
+    fn subscribe_to_context_server_resource(
+        &mut self,
+        crease_id: CreaseId,
+        server_id: String,
+        uri: Url,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(project) = self.project.upgrade() else {
+            return;
+        };
+        let server_store = project.read(cx).context_server_store();
+        let this = cx.entity().downgrade();
+        let subscription = agent::subscribe_to_resource(
+            &server_store,
+            &ContextServerId(server_id.into()),
+            uri,
+            move |cx| {
+                let this = this.clone();
+                cx.spawn(async move |cx| {
+                    this.update(cx, |this, cx| {
+                        this.reload_context_server_resource(crease_id, cx)
+                    })
+                })
+                .detach();
+            },
+            cx,
+        );
+        cx.spawn(async move |this, cx| {
+            if let Some(subscription) = subscription.await.log_err().flatten() {
+                this.update(cx, |this, _| {
+                    if this.mentions.contains_key(&crease_id) {
+                        this.resource_subscriptions.insert(crease_id, subscription);
+                    }
+                })
+                .ok();
+            }
+        })
+        .detach();
+    }
+
+    fn reload_context_server_resource(&mut self, crease_id: CreaseId, cx: &mut Context<Self>) {
+        let Some((mention_uri, _)) = self.mentions.get(&crease_id) else {
+            return;
+        };
+        let MentionUri::ContextServerResource { server_id, uri, .. } = mention_uri.clone() else {
+            return;
+        };
+        let task = self.confirm_mention_for_context_server_resource(server_id, uri, cx);
+        let task = cx
+            .spawn(async move |_, _| task.await.map_err(|e| e.to_string()))
+            .shared();
+        if let Some((_, mention_task)) = self.mentions.get_mut(&crease_id) {
+            *mention_task = task;
+        }
+    }
+
     fn confirm_mention_for_symbol(
         &self,
         abs_path: PathBuf,
@@ -1422,7 +1539,7 @@ async fn fetch_url_content(http_client: Arc<HttpClientWithUrl>, url: String) ->
         Plaintext,
         Json,
     }
-    use html_to_markdown::{TagHandler, convert_html_to_markdown, markdown};
+    use html_to_markdown::{TagHandler, convert_webpage_to_markdown, markdown};
 
     let url = if !url.starts_with("https://") && !url.starts_with("http://") {
         format!("https://{url}")
@@ -1461,7 +1578,7 @@ async fn fetch_url_content(http_client: Arc<HttpClientWithUrl>, url: String) ->
 
     match content_type {
         ContentType::Html => {
-            let mut handlers: Vec<TagHandler> = vec![
+            let handlers: Vec<TagHandler> = vec![
                 Rc::new(RefCell::new(markdown::WebpageChromeRemover)),
                 Rc::new(RefCell::new(markdown::ParagraphHandler)),
                 Rc::new(RefCell::new(markdown::HeadingHandler)),
@@ -1469,18 +1586,8 @@ async fn fetch_url_content(http_client: Arc<HttpClientWithUrl>, url: String) ->
                 Rc::new(RefCell::new(markdown::TableHandler::new())),
                 Rc::new(RefCell::new(markdown::StyledTextHandler)),
             ];
-            if url.contains("wikipedia.org") {
-                use html_to_markdown::structure::wikipedia;
 
-                handlers.push(Rc::new(RefCell::new(wikipedia::WikipediaChromeRemover)));
-                handlers.push(Rc::new(RefCell::new(wikipedia::WikipediaInfoboxHandler)));
-                handlers.push(Rc::new(
-                    RefCell::new(wikipedia::WikipediaCodeHandler::new()),
-                ));
-            } else {
-                handlers.push(Rc::new(RefCell::new(markdown::CodeHandler)));
-            }
-            convert_html_to_markdown(&body[..], &mut handlers)
+            convert_webpage_to_markdown(&body[..], &url, handlers)
         }
         ContentType::Plaintext => Ok(std::str::from_utf8(&body)?.to_owned()),
         ContentType::Json => {
@@ -8,6 +8,7 @@
 )]
 
 mod completions;
+mod mcp;
 
 use crate::completions::Shell;
 
@@ -153,6 +154,10 @@ struct Args {
     /// by having Zed act like netcat communicating over a Unix socket.
     #[arg(long, hide = true)]
     askpass: Option<String>,
+    /// Run as a stdio MCP server that exposes the running Zed's editor and project tools.
+    /// Requires `mcp_server.enabled` to be set in Zed's settings.
+    #[arg(long)]
+    mcp: bool,
 }
 
 /// Parses a path containing a position (e.g. `path:line:column`)
@@ -517,6 +522,10 @@ fn run() -> Result<()> {
         paths::set_custom_data_dir(dir);
     }
 
+    if args.mcp {
+        return mcp::main();
+    }
+
     #[cfg(target_os = "linux")]
     let args = flatpak::set_bin_if_no_escape(args);
 
@@ -0,0 +1,27 @@
+use anyhow::{Context as _, Result};
+use std::io;
+
+/// Runs the CLI as a stdio MCP server by forwarding stdin and stdout to the
+/// socket on which the running Zed serves its editor and project tools.
+pub fn main() -> Result<()> {
+    let socket_path = paths::mcp_server_socket();
+    let stream = net::UnixStream::connect(socket_path).with_context(|| {
+        format!(
+            "connecting to {socket_path:?}. Is Zed running with `mcp_server.enabled` set to true?"
+        )
+    })?;
+
+    #[cfg(not(target_os = "windows"))]
+    let (mut incoming, mut outgoing) = (stream.try_clone()?, stream);
+    #[cfg(target_os = "windows")]
+    let (mut incoming, mut outgoing) = stream.into_split();
+
+    // Exit as soon as Zed closes the connection, even if stdin is still open.
+    std::thread::spawn(move || {
+        io::copy(&mut incoming, &mut io::stdout().lock()).ok();
+        std::process::exit(0);
+    });
+
+    io::copy(&mut io::stdin().lock(), &mut outgoing).context("forwarding stdin to Zed")?;
+    Ok(())
+}
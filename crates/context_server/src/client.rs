@@ -53,6 +53,7 @@ pub(crate) struct Client {
     name: Arc<str>,
     subscription_set: Arc<Mutex<NotificationSubscriptionSet>>,
     response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
+    request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
     #[allow(clippy::type_complexity)]
     #[allow(dead_code)]
     io_tasks: Mutex<Option<(Task<Option<()>>, Task<Option<()>>)>>,
@@ -109,7 +110,6 @@ struct AnyResponse<'a> {
 }
 
 #[derive(Serialize, Deserialize)]
-#[allow(dead_code)]
 pub(crate) struct Response<T> {
     pub jsonrpc: &'static str,
     pub id: RequestId,
@@ -122,7 +122,6 @@ pub(crate) struct Response<T> {
 pub(crate) enum CspResult<T> {
     #[serde(rename = "result")]
     Ok(Option<T>),
-    #[allow(dead_code)]
     Error(Option<Error>),
 }
 
@@ -211,12 +210,14 @@ impl Client {
             let response_handlers = response_handlers.clone();
             let request_handlers = request_handlers.clone();
             let transport = transport.clone();
+            let outbound_tx = outbound_tx.clone();
             async move |cx| {
                 Self::handle_input(
                     transport,
                     subscription_set,
                     request_handlers,
                     response_handlers,
+                    outbound_tx,
                     cx,
                 )
                 .log_err()
@@ -250,6 +251,7 @@ impl Client {
             server_id,
             subscription_set,
             response_handlers,
+            request_handlers,
             name: server_name,
             next_id: Default::default(),
             outbound_tx,
@@ -265,14 +267,15 @@ impl Client {
     /// Handles input from the server's stdout.
     ///
     /// This function continuously reads lines from the provided stdout stream,
-    /// parses them as JSON-RPC responses or notifications, and dispatches them
-    /// to the appropriate handlers. It processes both responses (which are matched
-    /// to pending requests) and notifications (which trigger registered handlers).
+    /// parses them as JSON-RPC requests, responses or notifications, and dispatches
+    /// them to the appropriate handlers. Requests from the server that no handler
+    /// was registered for are answered with a "method not found" error.
     async fn handle_input(
         transport: Arc<dyn Transport>,
         subscription_set: Arc<Mutex<NotificationSubscriptionSet>>,
         request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
         response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
+        outbound_tx: async_channel::Sender<String>,
         cx: &mut AsyncApp,
     ) -> anyhow::Result<()> {
         let mut receiver = transport.receive();
@@ -287,6 +290,16 @@ impl Client {
                         request.params.unwrap_or(RawValue::NULL),
                         cx.clone(),
                     );
+                } else {
+                    log::debug!("unhandled request from context server: {}", request.method);
+                    send_response::<()>(
+                        &outbound_tx,
+                        request.id,
+                        Err(Error {
+                            message: format!("method not found: {}", request.method),
+                            code: METHOD_NOT_FOUND,
+                        }),
+                    );
                 }
             } else if let Ok(response) = serde_json::from_str::<AnyResponse>(&message) {
                 if let Some(handlers) = response_handlers.lock().as_mut()
@@ -482,6 +495,54 @@ impl Client {
         self.transport.set_protocol_version(version);
     }
 
+    /// Registers a handler for requests of the given method sent by the context
+    /// server. The handler's result is sent back to the server as the response.
+    pub fn on_request<P, R, F, Fut>(&self, method: &'static str, mut f: F)
+    where
+        P: DeserializeOwned,
+        R: Serialize,
+        F: 'static + Send + FnMut(P, AsyncApp) -> Fut,
+        Fut: 'static + Future<Output = Result<R>>,
+    {
+        let outbound_tx = self.outbound_tx.clone();
+        self.request_handlers.lock().insert(
+            method,
+            Box::new(move |id, params, cx| {
+                let params = match serde_json::from_str::<P>(params.get()) {
+                    Ok(params) => params,
+                    Err(error) => {
+                        send_response::<()>(
+                            &outbound_tx,
+                            id,
+                            Err(Error {
+                                message: error.to_string(),
+                                code: INVALID_PARAMS,
+                            }),
+                        );
+                        return;
+                    }
+                };
+
+                let response = f(params, cx.clone());
+                let outbound_tx = outbound_tx.clone();
+                cx.foreground_executor()
+                    .spawn(async move {
+                        let response = response.await.map_err(|error| Error {
+                            message: format!("{error:#}"),
+                            code: INTERNAL_ERROR,
+                        });
+                        send_response(&outbound_tx, id, response);
+                    })
+                    .detach();
+            }),
+        );
+    }
+
+    /// Returns whether a handler is registered for requests of the given method.
+    pub fn handles_request(&self, method: &str) -> bool {
+        self.request_handlers.lock().contains_key(method)
+    }
+
     #[must_use]
     pub fn on_notification(
         &self,
@@ -497,6 +558,24 @@ impl Client {
     }
 }
 
+fn send_response<T: Serialize>(
+    outbound_tx: &async_channel::Sender<String>,
+    id: RequestId,
+    response: Result<T, Error>,
+) {
+    let value = match response {
+        Ok(result) => CspResult::Ok(Some(result)),
+        Err(error) => CspResult::Error(Some(error)),
+    };
+    let response = serde_json::to_string(&Response {
+        jsonrpc: JSON_RPC_VERSION,
+        id,
+        value,
+    })
+    .unwrap();
+    outbound_tx.try_send(response).log_err();
+}
+
 #[derive(Debug)]
 pub struct RequestCanceled;
 
@@ -16,7 +16,7 @@ use std::{fmt::Display, path::PathBuf};
 
 use anyhow::Result;
 use client::Client;
-use gpui::AsyncApp;
+use gpui::{AsyncApp, Task};
 use parking_lot::RwLock;
 pub use settings::ContextServerCommand;
 use url::Url;
@@ -37,11 +37,31 @@ enum ContextServerTransport {
     Custom(Arc<dyn crate::transport::Transport>),
 }
 
+/// Answers the requests a context server sends to Zed.
+pub trait ContextServerDelegate: 'static + Send + Sync {
+    /// Asks the user's language model for a completion (`sampling/createMessage`).
+    fn create_message(
+        &self,
+        server_id: ContextServerId,
+        params: types::CreateMessageRequest,
+        cx: AsyncApp,
+    ) -> Task<Result<types::CreateMessageResult>>;
+
+    /// Asks the user for structured input (`elicitation/create`).
+    fn elicit(
+        &self,
+        server_id: ContextServerId,
+        params: types::ElicitParams,
+        cx: AsyncApp,
+    ) -> Task<Result<types::ElicitResult>>;
+}
+
 pub struct ContextServer {
     id: ContextServerId,
     client: RwLock<Option<Arc<crate::protocol::InitializedContextServerProtocol>>>,
     configuration: ContextServerTransport,
     request_timeout: Option<Duration>,
+    delegate: RwLock<Option<Arc<dyn ContextServerDelegate>>>,
 }
 
 impl ContextServer {
@@ -58,6 +78,7 @@ impl ContextServer {
                 working_directory.map(|directory| directory.to_path_buf()),
             ),
             request_timeout: None,
+            delegate: RwLock::new(None),
         }
     }
 
@@ -95,6 +116,7 @@ impl ContextServer {
             client: RwLock::new(None),
             configuration: ContextServerTransport::Custom(transport),
             request_timeout,
+            delegate: RwLock::new(None),
         }
     }
 
@@ -106,6 +128,12 @@ impl ContextServer {
         self.client.read().clone()
     }
 
+    /// Sets the delegate that answers the server's sampling and elicitation
+    /// requests. It takes effect the next time the server is started.
+    pub fn set_delegate(&self, delegate: Arc<dyn ContextServerDelegate>) {
+        *self.delegate.write() = Some(delegate);
+    }
+
     pub async fn start(&self, cx: &AsyncApp) -> Result<()> {
         self.initialize(self.new_client(cx)?).await
     }
@@ -136,6 +164,18 @@ impl ContextServer {
     async fn initialize(&self, client: Client) -> Result<()> {
         log::debug!("starting context server {}", self.id);
         let protocol = crate::protocol::ModelContextProtocol::new(client);
+        let delegate = self.delegate.read().clone();
+        if let Some(delegate) = delegate {
+            protocol.on_request::<types::requests::CreateMessage, _, _>({
+                let delegate = delegate.clone();
+                let id = self.id();
+                move |params, cx| delegate.create_message(id.clone(), params, cx)
+            });
+            protocol.on_request::<types::requests::Elicit, _, _>({
+                let id = self.id();
+                move |params, cx| delegate.elicit(id.clone(), params, cx)
+            });
+        }
         let client_info = types::Implementation {
             name: "Zed".to_string(),
             title: None,
@@ -162,3 +202,113 @@ impl ContextServer {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::{FakeTransport, create_initialize_response};
+    use crate::types::{
+        CreateMessageRequest, CreateMessageResult, MessageContent, Role, SamplingMessage, requests,
+    };
+    use gpui::TestAppContext;
+
+    struct FakeDelegate;
+
+    impl ContextServerDelegate for FakeDelegate {
+        fn create_message(
+            &self,
+            server_id: ContextServerId,
+            params: CreateMessageRequest,
+            _cx: AsyncApp,
+        ) -> Task<Result<CreateMessageResult>> {
+            let prompt = match &params.messages[0].content {
+                MessageContent::Text { text, .. } => text.clone(),
+                _ => String::new(),
+            };
+            Task::ready(Ok(CreateMessageResult {
+                role: Role::Assistant,
+                content: MessageContent::Text {
+                    text: format!("{server_id}: {prompt}"),
+                    annotations: None,
+                },
+                model: "fake-model".into(),
+                stop_reason: Some("endTurn".into()),
+            }))
+        }
+
+        fn elicit(
+            &self,
+            _server_id: ContextServerId,
+            _params: types::ElicitParams,
+            _cx: AsyncApp,
+        ) -> Task<Result<types::ElicitResult>> {
+            Task::ready(Err(anyhow::anyhow!("the user is away")))
+        }
+    }
+
+    #[gpui::test]
+    async fn test_server_requests_are_answered_by_the_delegate(cx: &mut TestAppContext) {
+        let advertised_capabilities = Arc::new(parking_lot::Mutex::new(None));
+        let transport = Arc::new(
+            FakeTransport::new(cx.executor()).on_request::<requests::Initialize, _>({
+                let advertised_capabilities = advertised_capabilities.clone();
+                move |params| {
+                    *advertised_capabilities.lock() = Some((
+                        params.capabilities.sampling.is_some(),
+                        params.capabilities.elicitation.is_some(),
+                    ));
+                    async move { create_initialize_response("test".into()) }
+                }
+            }),
+        );
+
+        let server = ContextServer::new(ContextServerId("test".into()), transport.clone());
+        server.set_delegate(Arc::new(FakeDelegate));
+        server.start(&cx.to_async()).await.unwrap();
+        assert_eq!(*advertised_capabilities.lock(), Some((true, true)));
+
+        let response = transport
+            .request::<requests::CreateMessage>(CreateMessageRequest {
+                messages: vec![SamplingMessage {
+                    role: Role::User,
+                    content: MessageContent::Text {
+                        text: "hello".into(),
+                        annotations: None,
+                    },
+                }],
+                model_preferences: None,
+                system_prompt: None,
+                include_context: None,
+                temperature: None,
+                max_tokens: 100,
+                stop_sequences: None,
+                metadata: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(response.model, "fake-model");
+        assert!(matches!(
+            response.content,
+            MessageContent::Text { text, .. } if text == "test: hello"
+        ));
+
+        let error = transport
+            .request::<requests::Elicit>(types::ElicitParams {
+                message: "What is your name?".into(),
+                requested_schema: types::ElicitationSchema {
+                    properties: Default::default(),
+                    required: Vec::new(),
+                },
+            })
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("the user is away"));
+
+        // Requests without a handler are rejected rather than left unanswered.
+        let error = transport
+            .request::<requests::ListRoots>(())
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("method not found"));
+    }
+}
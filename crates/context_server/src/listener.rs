@@ -2,7 +2,7 @@ use ::serde::{Deserialize, Serialize};
 use anyhow::{Context as _, Result};
 use collections::HashMap;
 use futures::AsyncReadExt;
-use futures::stream::StreamExt;
+use futures::stream::{FuturesUnordered, StreamExt};
 use futures::{
     AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, FutureExt,
     channel::mpsc::{UnboundedReceiver, UnboundedSender, unbounded},
@@ -24,10 +24,11 @@ use util::ResultExt;
 
 use crate::{
     client::{CspResult, RequestId, Response},
+    protocol::ModelContextProtocol,
     types::{
-        CallToolParams, CallToolResponse, ListToolsResponse, Request, Tool, ToolAnnotations,
-        ToolResponseContent,
-        requests::{CallTool, ListTools},
+        CallToolParams, CallToolResponse, Implementation, InitializeResponse, ListToolsResponse,
+        Request, ServerCapabilities, Tool, ToolAnnotations, ToolResponseContent, ToolsCapabilities,
+        requests::{CallTool, Initialize, ListTools},
     },
 };
 
@@ -63,27 +64,105 @@ impl McpServer {
 
         cx.spawn(async move |cx| {
             let (temp_dir, socket_path, listener) = task.await?;
-            let tools = Rc::new(RefCell::new(HashMap::default()));
-            let handlers = Rc::new(RefCell::new(HashMap::default()));
-            let server_task = cx.spawn({
-                let tools = tools.clone();
-                let handlers = handlers.clone();
-                async move |cx| {
-                    while let Ok((stream, _)) = listener.accept().await {
-                        Self::serve_connection(stream, tools.clone(), handlers.clone(), cx);
-                    }
-                    drop(temp_dir)
+            Ok(Self::serve(listener, socket_path, temp_dir, cx))
+        })
+    }
+
+    /// Creates a server listening on a well-known socket path, so that clients
+    /// can connect to it without being told where it is.
+    ///
+    /// Fails if another process is already serving on that path. A stale socket
+    /// left behind by a previous process is replaced.
+    pub fn new_at(socket_path: PathBuf, cx: &AsyncApp) -> Task<Result<Self>> {
+        let task = cx.background_spawn({
+            let socket_path = socket_path.clone();
+            async move {
+                if UnixStream::connect(&socket_path).await.is_ok() {
+                    anyhow::bail!("another process is already serving on {socket_path:?}");
+                }
+                std::fs::remove_file(&socket_path).ok();
+                if let Some(parent) = socket_path.parent() {
+                    std::fs::create_dir_all(parent)?;
                 }
-            });
-            Ok(Self {
-                socket_path,
-                _server_task: server_task,
-                tools,
-                handlers,
-            })
+                let listener = UnixListener::bind(&socket_path).context("creating mcp socket")?;
+
+                anyhow::Ok((SocketFile(socket_path), listener))
+            }
+        });
+
+        cx.spawn(async move |cx| {
+            let (socket_file, listener) = task.await?;
+            Ok(Self::serve(listener, socket_path, socket_file, cx))
         })
     }
 
+    /// Accepts connections until the server is dropped, then closes them and
+    /// drops `socket_guard`.
+    fn serve(
+        listener: UnixListener,
+        socket_path: PathBuf,
+        socket_guard: impl 'static,
+        cx: &mut AsyncApp,
+    ) -> Self {
+        let tools = Rc::new(RefCell::new(HashMap::default()));
+        let handlers = Rc::new(RefCell::new(HashMap::default()));
+        let server_task = cx.spawn({
+            let tools = tools.clone();
+            let handlers = handlers.clone();
+            async move |cx| {
+                let _socket_guard = socket_guard;
+                let mut connections = FuturesUnordered::new();
+                loop {
+                    select_biased! {
+                        _ = connections.select_next_some() => {}
+                        connection = listener.accept().fuse() => {
+                            let Ok((stream, _)) = connection else {
+                                break;
+                            };
+                            connections.push(Self::serve_connection(
+                                stream,
+                                tools.clone(),
+                                handlers.clone(),
+                                cx,
+                            ));
+                        }
+                    }
+                }
+            }
+        });
+        let mut this = Self {
+            socket_path,
+            _server_task: server_task,
+            tools,
+            handlers,
+        };
+        this.handle_request::<Initialize>(|params, _| {
+            let mut supported_protocols = ModelContextProtocol::supported_protocols();
+            let protocol_version = if supported_protocols.contains(&params.protocol_version) {
+                params.protocol_version
+            } else {
+                supported_protocols.swap_remove(0)
+            };
+            Task::ready(Ok(InitializeResponse {
+                protocol_version,
+                capabilities: ServerCapabilities {
+                    tools: Some(ToolsCapabilities {
+                        list_changed: Some(false),
+                    }),
+                    ..Default::default()
+                },
+                server_info: Implementation {
+                    name: "zed".into(),
+                    title: Some("Zed".into()),
+                    version: env!("CARGO_PKG_VERSION").into(),
+                    description: None,
+                },
+                meta: None,
+            }))
+        });
+        this
+    }
+
     pub fn add_tool<T: McpServerTool + Clone + 'static>(&mut self, tool: T) {
         let mut settings = schemars::generate::SchemaSettings::draft07();
         settings.inline_subschemas = true;
@@ -115,10 +194,11 @@ impl McpServer {
             },
             handler: Box::new({
                 move |input_value, cx| {
-                    let input = match input_value {
-                        Some(input) => serde_json::from_value(input),
-                        None => serde_json::from_value(serde_json::Value::Null),
-                    };
+                    // Tool arguments are always an object, which clients may omit when empty.
+                    let input = serde_json::from_value(
+                        input_value
+                            .unwrap_or_else(|| serde_json::Value::Object(Default::default())),
+                    );
 
                     let tool = tool.clone();
                     match input {
@@ -197,20 +277,22 @@ impl McpServer {
         &self.socket_path
     }
 
+    /// Handles the connection's requests until it closes, or the returned task
+    /// is dropped.
     fn serve_connection(
         stream: UnixStream,
         tools: Rc<RefCell<HashMap<&'static str, RegisteredTool>>>,
         handlers: Rc<RefCell<HashMap<&'static str, RequestHandler>>>,
         cx: &mut AsyncApp,
-    ) {
+    ) -> Task<()> {
         let (read, write) = stream.split();
         let (incoming_tx, mut incoming_rx) = unbounded();
         let (outgoing_tx, outgoing_rx) = unbounded();
 
-        cx.background_spawn(Self::handle_io(outgoing_rx, incoming_tx, write, read))
-            .detach();
+        let io_task = cx.background_spawn(Self::handle_io(outgoing_rx, incoming_tx, write, read));
 
         cx.spawn(async move |cx| {
+            let _io_task = io_task;
             while let Some(request) = incoming_rx.next().await {
                 let Some(request_id) = request.id.clone() else {
                     continue;
@@ -239,7 +321,6 @@ impl McpServer {
                 }
             }
         })
-        .detach();
     }
 
     fn handle_list_tools(
@@ -422,6 +503,15 @@ pub trait McpServerTool {
     ) -> impl Future<Output = Result<ToolResponse<Self::Output>>>;
 }
 
+/// Removes the socket file created by [`McpServer::new_at`] when the server stops.
+struct SocketFile(PathBuf);
+
+impl Drop for SocketFile {
+    fn drop(&mut self) {
+        std::fs::remove_file(&self.0).ok();
+    }
+}
+
 #[derive(Debug)]
 pub struct ToolResponse<T> {
     pub content: Vec<ToolResponseContent>,
@@ -436,3 +526,95 @@ struct RawRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     params: Option<Box<serde_json::value::RawValue>>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{LATEST_PROTOCOL_VERSION, VERSION_2024_11_05};
+    use gpui::TestAppContext;
+
+    async fn start_server(socket_path: &Path, cx: &mut TestAppContext) -> Result<McpServer> {
+        let task = cx.update(|cx| McpServer::new_at(socket_path.to_path_buf(), &cx.to_async()));
+        task.await
+    }
+
+    async fn send_line(stream: &UnixStream, message: serde_json::Value) {
+        let mut writer = stream.clone();
+        writer
+            .write_all(format!("{message}\n").as_bytes())
+            .await
+            .unwrap();
+    }
+
+    #[gpui::test]
+    async fn test_new_at_replaces_stale_sockets(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        let temp_dir = tempfile::tempdir().unwrap();
+        let socket_path = temp_dir.path().join("mcp.sock");
+
+        // A socket left behind by a process that exited is replaced.
+        drop(std::os::unix::net::UnixListener::bind(&socket_path).unwrap());
+        assert!(socket_path.exists());
+        let server = start_server(&socket_path, cx).await.unwrap();
+        UnixStream::connect(&socket_path).await.unwrap();
+
+        // A socket that is still being served isn't taken over.
+        assert!(start_server(&socket_path, cx).await.is_err());
+        UnixStream::connect(&socket_path).await.unwrap();
+
+        drop(server);
+        cx.run_until_parked();
+        assert!(!socket_path.exists());
+    }
+
+    #[gpui::test]
+    async fn test_initialize(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        let temp_dir = tempfile::tempdir().unwrap();
+        let socket_path = temp_dir.path().join("mcp.sock");
+        let server = start_server(&socket_path, cx).await.unwrap();
+
+        let stream = UnixStream::connect(&socket_path).await.unwrap();
+        let mut reader = BufReader::new(stream.clone());
+        let mut initialize = async |id: u64, protocol_version: &str| {
+            send_line(
+                &stream,
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "method": Initialize::METHOD,
+                    "params": {
+                        "protocolVersion": protocol_version,
+                        "capabilities": {},
+                        "clientInfo": { "name": "test", "version": "1.0" },
+                    },
+                }),
+            )
+            .await;
+            let mut line = String::new();
+            reader.read_line(&mut line).await.unwrap();
+            serde_json::from_str::<serde_json::Value>(&line).unwrap()
+        };
+
+        // A supported protocol version is accepted as is.
+        let response = initialize(1, VERSION_2024_11_05).await;
+        assert_eq!(response["id"], 1);
+        assert_eq!(response["result"]["protocolVersion"], VERSION_2024_11_05);
+        assert_eq!(response["result"]["serverInfo"]["name"], "zed");
+        assert!(response["result"]["capabilities"]["tools"].is_object());
+
+        // Otherwise the server proposes the latest version it supports.
+        let response = initialize(2, "1999-01-01").await;
+        assert_eq!(response["id"], 2);
+        assert_eq!(
+            response["result"]["protocolVersion"],
+            LATEST_PROTOCOL_VERSION
+        );
+
+        // Connections are closed when the server is dropped.
+        drop(server);
+        cx.run_until_parked();
+        let mut line = String::new();
+        assert_eq!(reader.read_line(&mut line).await.unwrap(), 0);
+    }
+}
@@ -10,7 +10,7 @@ use std::time::Duration;
 use anyhow::Result;
 use futures::channel::oneshot;
 use gpui::AsyncApp;
-use serde_json::Value;
+use serde_json::{Value, json};
 
 use crate::client::{Client, NotificationSubscription};
 use crate::types::{self, Notification, Request};
@@ -24,7 +24,7 @@ impl ModelContextProtocol {
         Self { inner }
     }
 
-    fn supported_protocols() -> Vec<types::ProtocolVersion> {
+    pub(crate) fn supported_protocols() -> Vec<types::ProtocolVersion> {
         vec![
             types::ProtocolVersion(types::LATEST_PROTOCOL_VERSION.to_string()),
             types::ProtocolVersion(types::VERSION_2025_06_18.to_string()),
@@ -33,15 +33,29 @@ impl ModelContextProtocol {
         ]
     }
 
+    /// Registers a handler for a request the server may send to the client.
+    ///
+    /// Handlers must be registered before [`Self::initialize`], as the client
+    /// capabilities advertised to the server are derived from them.
+    pub fn on_request<T: Request, F, Fut>(&self, f: F)
+    where
+        F: 'static + Send + FnMut(T::Params, AsyncApp) -> Fut,
+        Fut: 'static + Future<Output = Result<T::Response>>,
+    {
+        self.inner.on_request(T::METHOD, f)
+    }
+
     pub async fn initialize(
         self,
         client_info: types::Implementation,
     ) -> Result<InitializedContextServerProtocol> {
+        let capability = |method| self.inner.handles_request(method).then(|| json!({}));
         let params = types::InitializeParams {
             protocol_version: types::ProtocolVersion(types::LATEST_PROTOCOL_VERSION.to_string()),
             capabilities: types::ClientCapabilities {
                 experimental: None,
-                sampling: None,
+                sampling: capability(types::requests::CreateMessage::METHOD),
+                elicitation: capability(types::requests::Elicit::METHOD),
                 roots: None,
             },
             meta: None,
@@ -1,8 +1,16 @@
-use anyhow::Context as _;
+use anyhow::{Context as _, anyhow};
 use collections::HashMap;
-use futures::{FutureExt, Stream, StreamExt as _, future::BoxFuture, lock::Mutex};
+use futures::{
+    FutureExt, Stream, StreamExt as _, channel::oneshot, future::BoxFuture, lock::Mutex,
+};
 use gpui::BackgroundExecutor;
-use std::{pin::Pin, sync::Arc};
+use std::{
+    pin::Pin,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering::SeqCst},
+    },
+};
 
 use crate::{
     transport::Transport,
@@ -22,7 +30,7 @@ pub fn create_fake_transport(
     )
 }
 
-fn create_initialize_response(server_name: String) -> InitializeResponse {
+pub fn create_initialize_response(server_name: String) -> InitializeResponse {
     InitializeResponse {
         protocol_version: ProtocolVersion(crate::types::LATEST_PROTOCOL_VERSION.to_string()),
         server_info: Implementation {
@@ -43,6 +51,8 @@ pub struct FakeTransport {
     >,
     tx: futures::channel::mpsc::UnboundedSender<String>,
     rx: Arc<Mutex<futures::channel::mpsc::UnboundedReceiver<String>>>,
+    pending_requests: parking_lot::Mutex<HashMap<String, oneshot::Sender<serde_json::Value>>>,
+    next_request_id: AtomicU64,
     executor: BackgroundExecutor,
 }
 
@@ -53,10 +63,43 @@ impl FakeTransport {
             request_handlers: Default::default(),
             tx,
             rx: Arc::new(Mutex::new(rx)),
+            pending_requests: Default::default(),
+            next_request_id: AtomicU64::new(0),
             executor,
         }
     }
 
+    /// Sends a request from the fake server to the client, resolving to the
+    /// client's response.
+    pub fn request<T: crate::types::Request>(
+        &self,
+        params: T::Params,
+    ) -> impl Future<Output = anyhow::Result<T::Response>> + use<T> {
+        let id = format!("fake-server-{}", self.next_request_id.fetch_add(1, SeqCst));
+        let (tx, rx) = oneshot::channel();
+        self.pending_requests.lock().insert(id.clone(), tx);
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": T::METHOD,
+            "params": params,
+        });
+        let sent = self.tx.unbounded_send(request.to_string());
+
+        async move {
+            sent.context("sending a request")?;
+            let mut response = rx.await.context("the client dropped the request")?;
+            if let Some(error) = response.get("error") {
+                return Err(anyhow!("{error}"));
+            }
+            let result = response
+                .get_mut("result")
+                .map(serde_json::Value::take)
+                .unwrap_or_default();
+            Ok(serde_json::from_value(result)?)
+        }
+    }
+
     pub fn on_request<T, Fut>(
         mut self,
         handler: impl 'static + Send + Sync + Fn(T::Params) -> Fut,
@@ -103,6 +146,10 @@ impl Transport for FakeTransport {
                 } else {
                     log::debug!("No handler registered for MCP request '{method}'");
                 }
+            } else if let Some(id) = msg.get("id").and_then(|id| id.as_str())
+                && let Some(tx) = self.pending_requests.lock().remove(id)
+            {
+                tx.send(msg).ok();
             }
         }
         Ok(())
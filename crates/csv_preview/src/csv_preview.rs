@@ -1,29 +1,59 @@
 use editor::{Editor, EditorEvent};
 use feature_flags::{FeatureFlag, FeatureFlagAppExt as _, PresenceFlag, register_feature_flag};
 use gpui::{
-    AppContext, Entity, EventEmitter, FocusHandle, Focusable, ListAlignment, Task, actions,
+    AppContext, Entity, EventEmitter, FocusHandle, Focusable, ListAlignment, Pixels, Point,
+    Subscription, Task, actions,
 };
 use std::{
     collections::HashMap,
     time::{Duration, Instant},
 };
 
-use crate::table_data_engine::TableDataEngine;
+use crate::table_data_engine::{MappingChanges, TableDataEngine};
 use ui::{
-    AbsoluteLength, ResizableColumnsState, SharedString, TableInteractionState,
+    AbsoluteLength, ContextMenu, ResizableColumnsState, SharedString, TableInteractionState,
     TableResizeBehavior, prelude::*,
 };
 use workspace::{Item, SplitDirection, Workspace};
 
-use crate::{parser::EditorState, settings::CsvPreviewSettings, types::TableLikeContent};
+use crate::{
+    editing::CellEditor,
+    parser::EditorState,
+    settings::CsvPreviewSettings,
+    types::{DisplayCellId, TableLikeContent},
+};
 
+mod editing;
+mod filtering;
 mod parser;
 mod renderer;
 mod settings;
 mod table_data_engine;
 mod types;
 
-actions!(csv, [OpenPreview, OpenPreviewToTheSide]);
+actions!(
+    csv,
+    [
+        OpenPreview,
+        OpenPreviewToTheSide,
+        /// Starts editing the selected cell.
+        EditCell,
+        /// Inserts an empty row above the selected cell.
+        InsertRowAbove,
+        /// Inserts an empty row below the selected cell.
+        InsertRowBelow,
+        /// Deletes the row of the selected cell.
+        DeleteRow,
+        /// Inserts an empty column to the left of the selected cell.
+        InsertColumnLeft,
+        /// Inserts an empty column to the right of the selected cell.
+        InsertColumnRight,
+        /// Deletes the column of the selected cell.
+        DeleteColumn,
+        /// Pastes tab or delimiter separated text, starting at the selected cell.
+        PasteCells
+    ]
+);
 
 pub struct TabularDataPreviewFeatureFlag;
 
@@ -47,6 +77,19 @@ pub struct CsvPreviewView {
     pub(crate) list_state: gpui::ListState,
     /// Time when the last parsing operation ended, used for smart debouncing
     pub(crate) last_parse_end_time: Option<std::time::Instant>,
+    pub(crate) selected_cell: Option<DisplayCellId>,
+    pub(crate) cell_editor: Option<CellEditor>,
+    pub(crate) context_menu: Option<(Entity<ContextMenu>, Point<Pixels>, Subscription)>,
+    pub(crate) query_editor: Entity<Editor>,
+    pub(crate) query_error: Option<SharedString>,
+    /// Error of the last column filter that couldn't be parsed
+    pub(crate) filter_error: Option<SharedString>,
+    pub(crate) query_task: Option<Task<()>>,
+    /// Sorting and filtering running in the background
+    mapping_task: Option<Task<()>>,
+    /// Parts of the mapping that `mapping_task` recomputes
+    pending_mapping_changes: MappingChanges,
+    _query_editor_subscription: Subscription,
 }
 
 pub fn init(cx: &mut App) {
@@ -90,7 +133,7 @@ impl CsvPreviewView {
                         .and_then(|item| item.act_as::<Editor>(cx))
                         .filter(|editor| Self::is_csv_file(editor, cx))
                     {
-                        let csv_preview = Self::new(&editor, cx);
+                        let csv_preview = Self::new(&editor, window, cx);
                         workspace.active_pane().update(cx, |pane, cx| {
                             let existing = pane
                                 .items_of_type::<CsvPreviewView>()
@@ -111,7 +154,7 @@ impl CsvPreviewView {
                             .and_then(|item| item.act_as::<Editor>(cx))
                             .filter(|editor| Self::is_csv_file(editor, cx))
                         {
-                            let csv_preview = Self::new(&editor, cx);
+                            let csv_preview = Self::new(&editor, window, cx);
                             let pane = workspace
                                 .find_pane_in_direction(SplitDirection::Right, cx)
                                 .unwrap_or_else(|| {
@@ -148,7 +191,11 @@ impl CsvPreviewView {
         });
     }
 
-    fn new(editor: &Entity<Editor>, cx: &mut Context<Workspace>) -> Entity<Self> {
+    fn new(
+        editor: &Entity<Editor>,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
         let contents = TableLikeContent::default();
         let table_interaction_state = cx.new(|cx| {
             TableInteractionState::new(cx).with_custom_scrollbar(ui::Scrollbars::for_settings::<
@@ -169,6 +216,24 @@ impl CsvPreviewView {
                 },
             );
 
+            let query_editor = cx.new(|cx| {
+                let mut editor = Editor::single_line(window, cx);
+                editor.set_placeholder_text(
+                    "Filter rows, e.g. price > 10 and region = \"EU\"",
+                    window,
+                    cx,
+                );
+                editor
+            });
+            let query_editor_subscription = cx.subscribe(
+                &query_editor,
+                |this: &mut CsvPreviewView, _, event: &EditorEvent, cx| {
+                    if let EditorEvent::BufferEdited = event {
+                        this.schedule_query_update(cx);
+                    }
+                },
+            );
+
             let mut view = CsvPreviewView {
                 focus_handle: cx.focus_handle(),
                 active_editor_state: EditorState {
@@ -183,6 +248,16 @@ impl CsvPreviewView {
                     .measure_all(),
                 settings: CsvPreviewSettings::default(),
                 last_parse_end_time: None,
+                selected_cell: None,
+                cell_editor: None,
+                context_menu: None,
+                query_editor,
+                query_error: None,
+                filter_error: None,
+                query_task: None,
+                mapping_task: None,
+                pending_mapping_changes: MappingChanges::default(),
+                _query_editor_subscription: query_editor_subscription,
                 engine: TableDataEngine::default(),
             };
 
@@ -194,19 +269,56 @@ impl CsvPreviewView {
     pub(crate) fn editor_state(&self) -> &EditorState {
         &self.active_editor_state
     }
-    pub(crate) fn apply_sort(&mut self) {
-        self.performance_metrics.record("Sort", || {
-            self.engine.apply_sort();
-        });
+    pub(crate) fn apply_sort(&mut self, cx: &mut Context<Self>) {
+        self.update_mapping(MappingChanges::SORTING, cx);
+    }
+
+    pub(crate) fn apply_filter(&mut self, cx: &mut Context<Self>) {
+        self.update_mapping(MappingChanges::FILTERING, cx);
     }
 
     /// Update ordered indices when ordering or content changes
-    pub(crate) fn apply_filter_sort(&mut self) {
-        self.performance_metrics.record("Filter&sort", || {
-            self.engine.calculate_d2d_mapping();
-        });
+    pub(crate) fn apply_filter_sort(&mut self, cx: &mut Context<Self>) {
+        self.update_mapping(MappingChanges::ALL, cx);
+    }
+
+    /// Recomputes sorting and/or filtering in the background, together with whatever the
+    /// replaced task was still computing, and shows the result once it's done.
+    fn update_mapping(&mut self, changes: MappingChanges, cx: &mut Context<Self>) {
+        let changes = self.pending_mapping_changes.union(changes);
+        self.pending_mapping_changes = changes;
+        let metric = match changes {
+            MappingChanges::SORTING => "Sort",
+            MappingChanges::FILTERING => "Filter",
+            _ => "Filter&sort",
+        };
+        let job = self.engine.mapping_job(changes);
+        self.mapping_task = Some(cx.spawn(async move |this, cx| {
+            let (update, duration) = cx
+                .background_spawn(async move {
+                    let start_time = Instant::now();
+                    let update = job.run();
+                    (update, start_time.elapsed())
+                })
+                .await;
+            this.update(cx, |this, cx| {
+                this.performance_metrics
+                    .timings
+                    .insert(metric, (duration, Instant::now()));
+                this.engine.apply_mapping_update(update);
+                this.pending_mapping_changes = MappingChanges::default();
+                this.mapping_task = None;
+                if changes.filtering {
+                    this.sync_list_state();
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
 
-        // Update list state with filtered row count
+    /// Update list state with filtered row count
+    fn sync_list_state(&mut self) {
         let visible_rows = self.engine.d2d_mapping().visible_row_count();
         self.list_state =
             gpui::ListState::new(visible_rows, ListAlignment::Top, px(100.)).measure_all();
@@ -233,7 +345,7 @@ impl CsvPreviewView {
                     .read(cx)
                     .file()
                     .and_then(|file| file.path().extension())
-                    .map(|ext| ext.eq_ignore_ascii_case("csv"))
+                    .map(|ext| ext.eq_ignore_ascii_case("csv") || ext.eq_ignore_ascii_case("tsv"))
             })
             .unwrap_or(false)
     }
@@ -0,0 +1,343 @@
+//! In-grid editing of the previewed table.
+//!
+//! Nothing is edited in the parsed table itself: every change is turned into edits of the
+//! source buffer (see [`crate::table_data_engine::editing`]), which then gets reparsed. That
+//! way undo, collaboration and saving behave as if the text had been edited by hand.
+
+use editor::{Editor, EditorEvent, SelectAll};
+use gpui::{DismissEvent, Entity, Pixels, Point, Subscription};
+use text::BufferSnapshot;
+use ui::{ContextMenu, prelude::*};
+
+use crate::{
+    CsvPreviewView, DeleteColumn, DeleteRow, EditCell, InsertColumnLeft, InsertColumnRight,
+    InsertRowAbove, InsertRowBelow, PasteCells,
+    table_data_engine::editing::BufferEdit,
+    types::{AnyColumn, DataRow, DisplayCellId, DisplayRow, TableLikeContent},
+};
+
+/// What an open cell editor is going to change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EditTarget {
+    Header(AnyColumn),
+    Cell(DisplayCellId),
+    /// Filter of a column rather than anything in the source
+    ColumnFilter(AnyColumn),
+}
+
+/// Single line editor shown in place of the cell being edited.
+pub(crate) struct CellEditor {
+    pub target: EditTarget,
+    pub editor: Entity<Editor>,
+    _subscription: Subscription,
+}
+
+impl CsvPreviewView {
+    pub(crate) fn select_cell(
+        &mut self,
+        cell: DisplayCellId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.selected_cell = Some(cell);
+        window.focus(&self.focus_handle, cx);
+        cx.notify();
+    }
+
+    fn selected_data_row(&self) -> Option<DataRow> {
+        let cell = self.selected_cell?;
+        self.engine.d2d_mapping().get_data_row(cell.row)
+    }
+
+    fn target_value(&self, target: EditTarget) -> SharedString {
+        if let EditTarget::ColumnFilter(col) = target {
+            return self
+                .engine
+                .applied_filters
+                .get(&col)
+                .map(|filter| filter.to_string().into())
+                .unwrap_or_default();
+        }
+        let cell = match target {
+            EditTarget::Header(col) => self.engine.contents.headers.get(col),
+            EditTarget::Cell(cell) => self
+                .engine
+                .d2d_mapping()
+                .get_data_row(cell.row)
+                .and_then(|row| self.engine.contents.get_row(row))
+                .and_then(|row| row.get(cell.col)),
+            EditTarget::ColumnFilter(_) => None,
+        };
+        cell.and_then(|cell| cell.display_value().cloned())
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn start_editing(
+        &mut self,
+        target: EditTarget,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self
+            .cell_editor
+            .as_ref()
+            .is_some_and(|cell_editor| cell_editor.target == target)
+        {
+            return;
+        }
+        self.commit_cell_edit(cx);
+
+        let value = self.target_value(target);
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_text(value, window, cx);
+            editor.select_all(&SelectAll, window, cx);
+            editor
+        });
+        let subscription = cx.subscribe_in(
+            &editor,
+            window,
+            |this, _, event: &EditorEvent, _window, cx| {
+                if let EditorEvent::Blurred = event {
+                    this.commit_cell_edit(cx);
+                }
+            },
+        );
+        window.focus(&editor.focus_handle(cx), cx);
+
+        if let EditTarget::Cell(cell) = target {
+            self.selected_cell = Some(cell);
+        }
+        self.cell_editor = Some(CellEditor {
+            target,
+            editor,
+            _subscription: subscription,
+        });
+        cx.notify();
+    }
+
+    /// Writes the value of the open cell editor, if any, into the source buffer.
+    pub(crate) fn commit_cell_edit(&mut self, cx: &mut Context<Self>) {
+        let Some(cell_editor) = self.cell_editor.take() else {
+            return;
+        };
+        let value = cell_editor.editor.read(cx).text(cx);
+        let row = match cell_editor.target {
+            EditTarget::ColumnFilter(col) => {
+                self.set_column_filter_from_text(col, &value, cx);
+                None
+            }
+            EditTarget::Header(col) => Some((None, col)),
+            EditTarget::Cell(cell) => self
+                .engine
+                .d2d_mapping()
+                .get_data_row(cell.row)
+                .map(|row| (Some(row), cell.col)),
+        };
+        if let Some((row, col)) = row {
+            self.edit_source(
+                |contents, snapshot| contents.set_cell(row, col, &value, snapshot),
+                cx,
+            );
+        }
+        cx.notify();
+    }
+
+    pub(crate) fn confirm_cell_edit(
+        &mut self,
+        _: &menu::Confirm,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.commit_cell_edit(cx);
+        window.focus(&self.focus_handle, cx);
+    }
+
+    pub(crate) fn cancel_cell_edit(
+        &mut self,
+        _: &menu::Cancel,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.cell_editor.take().is_some() {
+            window.focus(&self.focus_handle, cx);
+            cx.notify();
+        } else {
+            cx.propagate();
+        }
+    }
+
+    /// Applies edits computed against the current buffer snapshot to the source buffer.
+    fn edit_source(
+        &mut self,
+        edits: impl FnOnce(&TableLikeContent, &BufferSnapshot) -> Vec<BufferEdit>,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(buffer) = self
+            .editor_state()
+            .editor
+            .read(cx)
+            .buffer()
+            .read(cx)
+            .as_singleton()
+        else {
+            return;
+        };
+        if buffer.read(cx).read_only() {
+            return;
+        }
+        // Cells are anchored, so they resolve correctly even if the buffer changed
+        // after the last parse.
+        let snapshot = buffer.read(cx).text_snapshot();
+        let edits = edits(&self.engine.contents, &snapshot);
+        if edits.is_empty() {
+            return;
+        }
+        buffer.update(cx, |buffer, cx| {
+            buffer.edit(edits, None, cx);
+        });
+        self.parse_csv_from_active_editor(false, cx);
+    }
+
+    pub(crate) fn edit_cell(&mut self, _: &EditCell, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(cell) = self.selected_cell {
+            self.start_editing(EditTarget::Cell(cell), window, cx);
+        }
+    }
+
+    pub(crate) fn insert_row_above(
+        &mut self,
+        _: &InsertRowAbove,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(row) = self.selected_data_row() else {
+            return;
+        };
+        let after = row.checked_sub(1).map(DataRow);
+        self.edit_source(
+            |contents, snapshot| contents.insert_row(after, snapshot),
+            cx,
+        );
+    }
+
+    pub(crate) fn insert_row_below(
+        &mut self,
+        _: &InsertRowBelow,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(row) = self.selected_data_row() else {
+            return;
+        };
+        self.edit_source(
+            |contents, snapshot| contents.insert_row(Some(row), snapshot),
+            cx,
+        );
+    }
+
+    pub(crate) fn delete_row(&mut self, _: &DeleteRow, _: &mut Window, cx: &mut Context<Self>) {
+        let Some(row) = self.selected_data_row() else {
+            return;
+        };
+        self.edit_source(|contents, snapshot| contents.delete_row(row, snapshot), cx);
+    }
+
+    pub(crate) fn insert_column_left(
+        &mut self,
+        _: &InsertColumnLeft,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(cell) = self.selected_cell else {
+            return;
+        };
+        self.edit_source(
+            |contents, snapshot| contents.insert_column(cell.col, snapshot),
+            cx,
+        );
+    }
+
+    pub(crate) fn insert_column_right(
+        &mut self,
+        _: &InsertColumnRight,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(cell) = self.selected_cell else {
+            return;
+        };
+        let col = AnyColumn(*cell.col + 1);
+        self.edit_source(
+            |contents, snapshot| contents.insert_column(col, snapshot),
+            cx,
+        );
+    }
+
+    pub(crate) fn delete_column(
+        &mut self,
+        _: &DeleteColumn,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(cell) = self.selected_cell else {
+            return;
+        };
+        self.edit_source(
+            |contents, snapshot| contents.delete_column(cell.col, snapshot),
+            cx,
+        );
+    }
+
+    /// Pastes the clipboard with its top-left cell at the selected cell. Rows are
+    /// pasted in display order, so a sorted table is filled the way it looks.
+    pub(crate) fn paste_cells(&mut self, _: &PasteCells, _: &mut Window, cx: &mut Context<Self>) {
+        let Some(cell) = self.selected_cell else {
+            return;
+        };
+        let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) else {
+            return;
+        };
+        let mapping = self.engine.d2d_mapping();
+        let rows = (*cell.row..)
+            .map_while(|row| mapping.get_data_row(DisplayRow(row)))
+            .take(text.lines().count())
+            .collect::<Vec<_>>();
+        self.edit_source(
+            |contents, snapshot| contents.paste(rows, cell.col, &text, snapshot),
+            cx,
+        );
+    }
+
+    pub(crate) fn deploy_cell_context_menu(
+        &mut self,
+        cell: DisplayCellId,
+        position: Point<Pixels>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.commit_cell_edit(cx);
+        self.select_cell(cell, window, cx);
+        let context_menu = ContextMenu::build(window, cx, |menu, _, _| {
+            menu.context(self.focus_handle.clone())
+                .action("Edit Cell", Box::new(EditCell))
+                .separator()
+                .action("Insert Row Above", Box::new(InsertRowAbove))
+                .action("Insert Row Below", Box::new(InsertRowBelow))
+                .action("Delete Row", Box::new(DeleteRow))
+                .separator()
+                .action("Insert Column Left", Box::new(InsertColumnLeft))
+                .action("Insert Column Right", Box::new(InsertColumnRight))
+                .action("Delete Column", Box::new(DeleteColumn))
+                .separator()
+                .action("Paste", Box::new(PasteCells))
+        });
+        window.focus(&context_menu.focus_handle(cx), cx);
+        let subscription = cx.subscribe(&context_menu, |this, _, _: &DismissEvent, cx| {
+            this.context_menu.take();
+            cx.notify();
+        });
+        self.context_menu = Some((context_menu, position, subscription));
+        cx.notify();
+    }
+}
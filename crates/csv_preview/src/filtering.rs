@@ -0,0 +1,117 @@
+//! Column filters and the query bar of the preview.
+
+use std::time::Duration;
+
+use ui::prelude::*;
+
+use crate::{
+    CsvPreviewView,
+    table_data_engine::{filtering::ColumnFilter, query::Query},
+    types::AnyColumn,
+};
+
+/// Delay between typing into the query bar and filtering, so that large tables
+/// aren't filtered on every keystroke.
+pub(crate) const QUERY_DEBOUNCE: Duration = Duration::from_millis(250);
+
+impl CsvPreviewView {
+    /// Parses the query bar's text into the query applied by the engine. Needs to be
+    /// rerun whenever the headers change, as columns are resolved by name.
+    pub(crate) fn compile_query(&mut self, cx: &mut Context<Self>) {
+        let text = self.query_editor.read(cx).text(cx);
+        if text.trim().is_empty() {
+            self.engine.applied_query = None;
+            self.query_error = None;
+            return;
+        }
+
+        let column_names = self
+            .engine
+            .contents
+            .headers
+            .as_slice()
+            .iter()
+            .map(|cell| cell.display_value().map_or("", |value| value.as_str()))
+            .collect::<Vec<_>>();
+        match Query::parse(&text, &column_names) {
+            Ok(query) => {
+                self.engine.applied_query = Some(query);
+                self.query_error = None;
+            }
+            Err(error) => {
+                self.engine.applied_query = None;
+                self.query_error = Some(format!("{error:#}").into());
+            }
+        }
+    }
+
+    pub(crate) fn schedule_query_update(&mut self, cx: &mut Context<Self>) {
+        self.query_task = Some(cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(QUERY_DEBOUNCE).await;
+            this.update(cx, |this, cx| this.update_query(cx)).ok();
+        }));
+    }
+
+    fn update_query(&mut self, cx: &mut Context<Self>) {
+        self.query_task = None;
+        self.compile_query(cx);
+        self.apply_filter(cx);
+        cx.notify();
+    }
+
+    pub(crate) fn confirm_query(
+        &mut self,
+        _: &menu::Confirm,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.update_query(cx);
+        window.focus(&self.focus_handle, cx);
+    }
+
+    pub(crate) fn set_column_filter(
+        &mut self,
+        col: AnyColumn,
+        filter: Option<ColumnFilter>,
+        cx: &mut Context<Self>,
+    ) {
+        match filter {
+            Some(filter) => self.engine.applied_filters.insert(col, filter),
+            None => self.engine.applied_filters.remove(&col),
+        };
+        self.filter_error = None;
+        self.apply_filter(cx);
+        cx.notify();
+    }
+
+    pub(crate) fn set_column_filter_from_text(
+        &mut self,
+        col: AnyColumn,
+        text: &str,
+        cx: &mut Context<Self>,
+    ) {
+        match ColumnFilter::parse(text) {
+            Ok(filter) => self.set_column_filter(col, filter, cx),
+            Err(error) => {
+                self.filter_error = Some(format!("Column {}: {error:#}", *col + 1).into());
+                cx.notify();
+            }
+        }
+    }
+
+    pub(crate) fn clear_filters(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.engine.applied_filters.clear();
+        self.filter_error = None;
+        self.query_editor.update(cx, |editor, cx| {
+            editor.set_text("", window, cx);
+        });
+        self.query_task = None;
+        self.compile_query(cx);
+        self.apply_filter(cx);
+        cx.notify();
+    }
+
+    pub(crate) fn has_filters(&self) -> bool {
+        !self.engine.applied_filters.is_empty() || self.engine.applied_query.is_some()
+    }
+}
@@ -1,11 +1,15 @@
 use crate::{
     CsvPreviewView,
+    table_data_engine::statistics::compute_column_statistics,
     types::TableLikeContent,
     types::{LineNumber, TableCell},
 };
 use editor::Editor;
 use gpui::{AppContext, Context, Entity, Subscription, Task};
-use std::time::{Duration, Instant};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use text::BufferSnapshot;
 use ui::{SharedString, table_row::TableRow};
 
@@ -55,21 +59,28 @@ impl CsvPreviewView {
             }
 
             let buffer_snapshot = view.update(cx, |_, cx| {
-                editor
-                    .read(cx)
-                    .buffer()
-                    .read(cx)
-                    .as_singleton()
-                    .map(|b| b.read(cx).text_snapshot())
+                editor.read(cx).buffer().read(cx).as_singleton().map(|b| {
+                    let buffer = b.read(cx);
+                    let extension = buffer
+                        .file()
+                        .and_then(|file| file.path().extension())
+                        .map(|extension| extension.to_ascii_lowercase());
+                    (buffer.text_snapshot(), extension)
+                })
             })?;
 
-            let Some(buffer_snapshot) = buffer_snapshot else {
+            let Some((buffer_snapshot, extension)) = buffer_snapshot else {
                 return Ok(());
             };
 
             let instant = Instant::now();
-            let parsed_csv = cx
-                .background_spawn(async move { from_buffer(&buffer_snapshot) })
+            let (parsed_csv, column_statistics) = cx
+                .background_spawn(async move {
+                    let delimiter = detect_delimiter(&buffer_snapshot, extension.as_deref());
+                    let parsed_csv = from_buffer(&buffer_snapshot, delimiter);
+                    let column_statistics = compute_column_statistics(&parsed_csv);
+                    (parsed_csv, column_statistics)
+                })
                 .await;
             let parse_duration = instant.elapsed();
             let parse_end_time: Instant = Instant::now();
@@ -81,32 +92,69 @@ impl CsvPreviewView {
 
                 log::debug!("Parsed {} rows", parsed_csv.rows.len());
                 view.engine.contents = parsed_csv;
+                view.engine.column_statistics = column_statistics;
                 view.sync_column_widths(cx);
                 view.last_parse_end_time = Some(parse_end_time);
 
-                view.apply_filter_sort();
+                // Column names might have changed.
+                view.compile_query(cx);
+                view.apply_filter_sort(cx);
                 cx.notify();
             })
         })
     }
 }
 
-pub fn from_buffer(buffer_snapshot: &BufferSnapshot) -> TableLikeContent {
+/// Picks the field delimiter: tabs for `.tsv` files, otherwise whichever of
+/// `,`, `;`, `|` and tab occurs most often on the first line, preferring commas.
+pub(crate) fn detect_delimiter(buffer_snapshot: &BufferSnapshot, extension: Option<&str>) -> char {
+    if extension == Some("tsv") {
+        return '\t';
+    }
+
+    let first_line = buffer_snapshot
+        .chars_at(0)
+        .take_while(|ch| *ch != '\n')
+        .take(64 * 1024);
+    let mut counts = [(',', 0), (';', 0), ('|', 0), ('\t', 0)];
+    for ch in first_line {
+        if let Some((_, count)) = counts.iter_mut().find(|(delimiter, _)| *delimiter == ch) {
+            *count += 1;
+        }
+    }
+    counts
+        .into_iter()
+        .rev()
+        .max_by_key(|(_, count)| *count)
+        .filter(|(_, count)| *count > 0)
+        .map_or(',', |(delimiter, _)| delimiter)
+}
+
+pub fn from_buffer(buffer_snapshot: &BufferSnapshot, delimiter: char) -> TableLikeContent {
     let text = buffer_snapshot.text();
 
     if text.trim().is_empty() {
-        return TableLikeContent::default();
+        return TableLikeContent {
+            delimiter,
+            ..TableLikeContent::default()
+        };
     }
 
-    let (parsed_cells_with_positions, line_numbers) = parse_csv_with_positions(&text);
+    let (parsed_cells_with_positions, line_numbers) = parse_csv_with_positions(&text, delimiter);
     if parsed_cells_with_positions.is_empty() {
-        return TableLikeContent::default();
+        return TableLikeContent {
+            delimiter,
+            ..TableLikeContent::default()
+        };
     }
     let raw_headers = parsed_cells_with_positions[0].clone();
 
     // Calculating the longest row, as CSV might have less headers than max row width
     let Some(max_number_of_cols) = parsed_cells_with_positions.iter().map(|r| r.len()).max() else {
-        return TableLikeContent::default();
+        return TableLikeContent {
+            delimiter,
+            ..TableLikeContent::default()
+        };
     };
 
     // Convert to TableCell objects with buffer positions
@@ -122,15 +170,27 @@ pub fn from_buffer(buffer_snapshot: &BufferSnapshot) -> TableLikeContent {
 
     TableLikeContent {
         headers,
-        rows,
+        rows: Arc::new(rows),
         line_numbers: row_line_numbers,
         number_of_cols: max_number_of_cols,
+        delimiter,
+    }
+}
+
+/// A row is skipped when its line is blank. Rows of empty fields, like `,,`,
+/// are kept so that rows inserted from the table stay visible.
+fn is_blank_row(row: &[(SharedString, std::ops::Range<usize>)], text: &str) -> bool {
+    match row {
+        [] => true,
+        [(_, range)] => text[range.clone()].trim().is_empty(),
+        _ => false,
     }
 }
 
 /// Parse CSV and track byte positions for each cell
-fn parse_csv_with_positions(
+pub(crate) fn parse_csv_with_positions(
     text: &str,
+    delimiter: char,
 ) -> (
     Vec<Vec<(SharedString, std::ops::Range<usize>)>>,
     Vec<LineNumber>,
@@ -170,7 +230,7 @@ fn parse_csv_with_positions(
                     }
                 }
             }
-            ',' if !in_quotes => {
+            ch if ch == delimiter && !in_quotes => {
                 // Field separator
                 let field_end_offset = current_offset;
                 if current_field.is_empty() && !in_quotes {
@@ -188,9 +248,6 @@ fn parse_csv_with_positions(
                 if !in_quotes {
                     // Row separator (only when not inside quotes)
                     let field_end_offset = current_offset;
-                    if current_field.is_empty() && current_row.is_empty() {
-                        field_start_offset = 0;
-                    }
                     current_row.push((
                         current_field.clone().into(),
                         field_start_offset..field_end_offset,
@@ -198,9 +255,7 @@ fn parse_csv_with_positions(
                     current_field.clear();
 
                     // Only add non-empty rows
-                    if !current_row.is_empty()
-                        && !current_row.iter().all(|(field, _)| field.trim().is_empty())
-                    {
+                    if !is_blank_row(&current_row, text) {
                         rows.push(current_row);
                         // Add line number info for this row
                         let line_info = if row_start_line == current_line - 1 {
@@ -236,9 +291,7 @@ fn parse_csv_with_positions(
                         current_field.clear();
 
                         // Only add non-empty rows
-                        if !current_row.is_empty()
-                            && !current_row.iter().all(|(field, _)| field.trim().is_empty())
-                        {
+                        if !is_blank_row(&current_row, text) {
                             rows.push(current_row);
                             // Add line number info for this row
                             let line_info = if row_start_line == current_line - 1 {
@@ -276,7 +329,7 @@ fn parse_csv_with_positions(
             field_start_offset..field_end_offset,
         ));
     }
-    if !current_row.is_empty() && !current_row.iter().all(|(field, _)| field.trim().is_empty()) {
+    if !is_blank_row(&current_row, text) {
         rows.push(current_row);
         // Add line number info for the last row
         let line_info = if row_start_line == current_line {
@@ -421,7 +474,7 @@ Jane,"Simple name""#;
     #[test]
     fn test_csv_parsing_quote_offset_handling() {
         let csv_data = r#"first,"se,cond",third"#;
-        let (parsed_cells, _) = parse_csv_with_positions(csv_data);
+        let (parsed_cells, _) = parse_csv_with_positions(csv_data, ',');
 
         assert_eq!(parsed_cells.len(), 1); // One row
         assert_eq!(parsed_cells[0].len(), 3); // Three cells
@@ -447,7 +500,7 @@ Jane,"Simple name""#;
         let csv_data = r#"id,"name with spaces","description, with commas",status
 1,"John Doe","A person with ""quotes"" and, commas",active
 2,"Jane Smith","Simple description",inactive"#;
-        let (parsed_cells, _) = parse_csv_with_positions(csv_data);
+        let (parsed_cells, _) = parse_csv_with_positions(csv_data, ',');
 
         assert_eq!(parsed_cells.len(), 3); // header + 2 rows
 
@@ -505,6 +558,6 @@ impl TableLikeContent {
         let buffer_id = BufferId::new(1).unwrap();
         let buffer = Buffer::new(ReplicaId::LOCAL, buffer_id, text);
         let snapshot = buffer.snapshot();
-        from_buffer(snapshot)
+        from_buffer(snapshot, detect_delimiter(snapshot, None))
     }
 }
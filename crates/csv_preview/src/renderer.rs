@@ -1,6 +1,7 @@
 #[cfg(feature = "dev-tools")]
 mod performance_metrics_overlay;
 mod preview_view;
+mod query_bar;
 mod render_table;
 mod row_identifiers;
 mod settings;
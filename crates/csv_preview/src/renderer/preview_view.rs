@@ -1,5 +1,6 @@
 use std::time::Instant;
 
+use gpui::{anchored, deferred};
 use ui::{div, prelude::*};
 
 use crate::CsvPreviewView;
@@ -14,7 +15,19 @@ impl Render for CsvPreviewView {
             .p_4()
             .bg(theme.colors().editor_background)
             .track_focus(&self.focus_handle)
+            .key_context("CsvPreview")
+            .on_action(cx.listener(Self::edit_cell))
+            .on_action(cx.listener(Self::insert_row_above))
+            .on_action(cx.listener(Self::insert_row_below))
+            .on_action(cx.listener(Self::delete_row))
+            .on_action(cx.listener(Self::insert_column_left))
+            .on_action(cx.listener(Self::insert_column_right))
+            .on_action(cx.listener(Self::delete_column))
+            .on_action(cx.listener(Self::paste_cells))
+            .on_action(cx.listener(Self::confirm_cell_edit))
+            .on_action(cx.listener(Self::cancel_cell_edit))
             .child(self.render_settings_panel(window, cx))
+            .child(self.render_query_bar(cx))
             .child({
                 if self.engine.contents.number_of_cols == 0 {
                     div()
@@ -42,7 +55,16 @@ impl Render for CsvPreviewView {
             .relative()
             .w_full()
             .h_full()
-            .child(table_with_settings);
+            .child(table_with_settings)
+            .children(self.context_menu.as_ref().map(|(menu, position, _)| {
+                deferred(
+                    anchored()
+                        .position(*position)
+                        .anchor(gpui::Anchor::TopLeft)
+                        .child(menu.clone()),
+                )
+                .with_priority(1)
+            }));
 
         #[cfg(feature = "dev-tools")]
         let show_perf_metrics_overlay = self.settings.show_perf_metrics_overlay;
@@ -0,0 +1,57 @@
+use ui::{Tooltip, prelude::*};
+
+use crate::CsvPreviewView;
+
+impl CsvPreviewView {
+    /// Render query input with the number of matching rows and any filter errors
+    pub(crate) fn render_query_bar(&self, cx: &mut Context<Self>) -> AnyElement {
+        let visible_rows = self.engine.d2d_mapping().visible_row_count();
+        let total_rows = self.engine.contents.rows.len();
+        let error = self
+            .query_error
+            .clone()
+            .or_else(|| self.filter_error.clone());
+
+        h_flex()
+            .gap_2()
+            .p_2()
+            .items_center()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .on_action(cx.listener(Self::confirm_query))
+            .child(
+                Icon::new(IconName::Filter)
+                    .size(IconSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .font_buffer(cx)
+                    .child(self.query_editor.clone()),
+            )
+            .when_some(error, |bar, error| {
+                bar.child(Label::new(error).size(LabelSize::Small).color(Color::Error))
+            })
+            .child(
+                Label::new(if visible_rows == total_rows {
+                    format!("{total_rows} rows")
+                } else {
+                    format!("{visible_rows} of {total_rows} rows")
+                })
+                .size(LabelSize::Small)
+                .color(Color::Muted),
+            )
+            .when(self.has_filters(), |bar| {
+                bar.child(
+                    Button::new("clear-filters", "Clear Filters")
+                        .size(ButtonSize::Compact)
+                        .tooltip(Tooltip::text("Clear the query and all column filters"))
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.clear_filters(window, cx);
+                        })),
+                )
+            })
+            .into_any_element()
+    }
+}
@@ -17,7 +17,11 @@ impl CsvPreviewView {
         current_widths: &Entity<ResizableColumnsState>,
         cx: &mut Context<Self>,
     ) -> AnyElement {
-        self.create_table_inner(self.engine.contents.rows.len(), current_widths, cx)
+        self.create_table_inner(
+            self.engine.d2d_mapping().visible_row_count(),
+            current_widths,
+            cx,
+        )
     }
 
     fn create_table_inner(
@@ -130,7 +134,7 @@ impl CsvPreviewView {
             let display_cell_id = DisplayCellId::new(display_row, col);
 
             let cell = div().size_full().whitespace_nowrap().text_ellipsis().child(
-                CsvPreviewView::create_selectable_cell(
+                this.create_selectable_cell(
                     display_cell_id,
                     cell_content,
                     this.settings.vertical_alignment,
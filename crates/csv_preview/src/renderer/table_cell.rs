@@ -1,20 +1,60 @@
 //! Table Cell Rendering
 
-use gpui::{AnyElement, ElementId};
+use gpui::{AnyElement, ClickEvent, ElementId, MouseButton, MouseDownEvent};
 use ui::{SharedString, Tooltip, div, prelude::*};
 
-use crate::{CsvPreviewView, settings::VerticalAlignment, types::DisplayCellId};
+use crate::{
+    CsvPreviewView, editing::EditTarget, settings::VerticalAlignment, types::DisplayCellId,
+};
 
 impl CsvPreviewView {
     /// Create selectable table cell with mouse event handlers.
+    /// Click selects the cell, double click edits it and right click opens the context menu.
     pub fn create_selectable_cell(
+        &self,
         display_cell_id: DisplayCellId,
         cell_content: SharedString,
         vertical_alignment: VerticalAlignment,
         cx: &Context<CsvPreviewView>,
     ) -> AnyElement {
+        let target = EditTarget::Cell(display_cell_id);
+        if let Some(cell_editor) = self
+            .cell_editor
+            .as_ref()
+            .filter(|cell_editor| cell_editor.target == target)
+        {
+            return div()
+                .size_full()
+                .px_1()
+                .border_1()
+                .border_color(cx.theme().colors().border_focused)
+                .bg(cx.theme().colors().editor_background)
+                .child(cell_editor.editor.clone())
+                .into_any_element();
+        }
+
+        let is_selected = self.selected_cell == Some(display_cell_id);
         create_table_cell(display_cell_id, cell_content, vertical_alignment, cx)
-            // Mouse events handlers will be here
+            .when(is_selected, |div| {
+                div.bg(cx.theme().colors().element_selected)
+                    .border_1()
+                    .border_color(cx.theme().colors().border_focused)
+            })
+            .on_click(cx.listener(move |this, event: &ClickEvent, window, cx| {
+                if event.click_count() > 1 {
+                    this.start_editing(target, window, cx);
+                } else {
+                    this.commit_cell_edit(cx);
+                    this.select_cell(display_cell_id, window, cx);
+                }
+            }))
+            .on_mouse_down(
+                MouseButton::Right,
+                cx.listener(move |this, event: &MouseDownEvent, window, cx| {
+                    this.deploy_cell_context_menu(display_cell_id, event.position, window, cx);
+                    cx.stop_propagation();
+                }),
+            )
             .into_any_element()
     }
 }
@@ -1,88 +1,218 @@
-use gpui::ElementId;
-use ui::{Tooltip, prelude::*};
+use gpui::{ClickEvent, ElementId};
+use ui::{ContextMenu, PopoverMenu, Tooltip, prelude::*};
 
 use crate::{
     CsvPreviewView,
-    table_data_engine::sorting_by_column::{AppliedSorting, SortDirection},
+    editing::EditTarget,
+    table_data_engine::{
+        filtering::ColumnFilter,
+        sorting_by_column::{SortDirection, toggle_sorting},
+    },
     types::AnyColumn,
 };
 
 impl CsvPreviewView {
-    /// Create header for data, which is orderable with text on the left and sort button on the right
+    /// Create header for data, which is orderable with text on the left and filter/sort buttons on the right.
+    /// Column statistics and the active filter are shown below.
     pub(crate) fn create_header_element_with_sort_button(
         &self,
         header_text: SharedString,
         cx: &mut Context<'_, CsvPreviewView>,
         col_idx: AnyColumn,
     ) -> AnyElement {
+        let target = EditTarget::Header(col_idx);
+        let editor_for = |target| {
+            self.cell_editor
+                .as_ref()
+                .filter(|cell_editor| cell_editor.target == target)
+                .map(|cell_editor| cell_editor.editor.clone())
+        };
+        let header_editor = editor_for(target);
+        let filter_editor = editor_for(EditTarget::ColumnFilter(col_idx));
+
         // CSV data columns: text + filter/sort buttons
-        h_flex()
+        let header = h_flex()
+            .id(ElementId::NamedInteger(
+                "csv-header".into(),
+                col_idx.get() as u64,
+            ))
             .justify_between()
             .items_center()
             .w_full()
             .font_buffer(cx)
-            .child(div().child(header_text))
-            .child(h_flex().gap_1().child(self.create_sort_button(cx, col_idx)))
+            .map(|header| match header_editor {
+                Some(editor) => header.child(div().flex_1().child(editor)),
+                None => header.child(div().child(header_text)),
+            })
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(self.create_filter_button(cx, col_idx))
+                    .child(self.create_sort_button(cx, col_idx)),
+            )
+            .on_click(cx.listener(move |this, event: &ClickEvent, window, cx| {
+                if event.click_count() > 1 {
+                    this.start_editing(target, window, cx);
+                }
+            }));
+
+        let filter = self.engine.applied_filters.get(&col_idx);
+        v_flex()
+            .w_full()
+            .child(header)
+            .children(self.create_column_statistics(col_idx))
+            .map(|column| match (filter_editor, filter) {
+                (Some(editor), _) => column.child(div().w_full().font_buffer(cx).child(editor)),
+                (None, Some(filter)) => column.child(
+                    Label::new(format!("Filter: {filter}"))
+                        .size(LabelSize::XSmall)
+                        .color(Color::Accent)
+                        .truncate(),
+                ),
+                (None, None) => column,
+            })
             .into_any_element()
     }
 
+    fn create_column_statistics(&self, col_idx: AnyColumn) -> Option<AnyElement> {
+        let statistics = self.engine.column_statistics.get(*col_idx)?;
+        let mut details = format!(
+            "Distinct values: {}\nEmpty values: {}",
+            statistics.distinct, statistics.nulls
+        );
+        if let Some((min, max)) = statistics.min.as_ref().zip(statistics.max.as_ref()) {
+            details.push_str(&format!("\nMin: {min}\nMax: {max}"));
+        }
+
+        Some(
+            div()
+                .id(ElementId::NamedInteger(
+                    "column-statistics".into(),
+                    col_idx.get() as u64,
+                ))
+                .tooltip(Tooltip::text(details))
+                .child(
+                    Label::new(format!(
+                        "{} distinct · {} empty",
+                        statistics.distinct, statistics.nulls
+                    ))
+                    .size(LabelSize::XSmall)
+                    .color(Color::Muted)
+                    .truncate(),
+                )
+                .into_any_element(),
+        )
+    }
+
+    fn create_filter_button(
+        &self,
+        cx: &mut Context<'_, CsvPreviewView>,
+        col_idx: AnyColumn,
+    ) -> impl IntoElement {
+        let view = cx.entity().downgrade();
+        let is_filtered = self.engine.applied_filters.contains_key(&col_idx);
+
+        PopoverMenu::new(ElementId::NamedInteger(
+            "filter-menu".into(),
+            col_idx.get() as u64,
+        ))
+        .trigger_with_tooltip(
+            IconButton::new(
+                ElementId::NamedInteger("filter-button".into(), col_idx.get() as u64),
+                IconName::Filter,
+            )
+            .icon_size(IconSize::Small)
+            .toggle_state(is_filtered),
+            Tooltip::text("Filter Column"),
+        )
+        .anchor(gpui::Anchor::TopRight)
+        .menu(move |window, cx| {
+            let set_filter = {
+                let view = view.clone();
+                move |filter: fn() -> Option<ColumnFilter>| {
+                    let view = view.clone();
+                    move |_: &mut Window, cx: &mut App| {
+                        view.update(cx, |this, cx| this.set_column_filter(col_idx, filter(), cx))
+                            .ok();
+                    }
+                }
+            };
+            Some(ContextMenu::build(window, cx, |menu, _, _| {
+                menu.entry("Filter…", None, {
+                    let view = view.clone();
+                    move |window, cx| {
+                        let view = view.clone();
+                        // Wait for the menu to return focus before focusing the filter input.
+                        window.defer(cx, move |window, cx| {
+                            view.update(cx, |this, cx| {
+                                this.start_editing(EditTarget::ColumnFilter(col_idx), window, cx)
+                            })
+                            .ok();
+                        });
+                    }
+                })
+                .entry("Only Empty", None, set_filter(|| Some(ColumnFilter::Empty)))
+                .entry(
+                    "Only Non-Empty",
+                    None,
+                    set_filter(|| Some(ColumnFilter::NonEmpty)),
+                )
+                .when(is_filtered, |menu| {
+                    menu.separator()
+                        .entry("Clear Filter", None, set_filter(|| None))
+                })
+            }))
+        })
+    }
+
     fn create_sort_button(
         &self,
         cx: &mut Context<'_, CsvPreviewView>,
         col_idx: AnyColumn,
     ) -> Button {
+        let sortings = &self.engine.applied_sorting;
+        let position = sortings
+            .iter()
+            .position(|sorting| sorting.col_idx == col_idx);
+        let direction = position.map(|ix| sortings[ix].direction);
+        let arrow = match direction {
+            Some(SortDirection::Asc) => "↓",
+            Some(SortDirection::Desc) => "↑",
+            None => "↕", // Unsorted/available for sorting
+        };
+        let label = match position {
+            // Show the priority of the column when sorting by several
+            Some(ix) if sortings.len() > 1 => format!("{arrow}{}", ix + 1),
+            _ => arrow.to_string(),
+        };
+
         let sort_btn = Button::new(
             ElementId::NamedInteger("sort-button".into(), col_idx.get() as u64),
-            match self.engine.applied_sorting {
-                Some(ordering) if ordering.col_idx == col_idx => match ordering.direction {
-                    SortDirection::Asc => "↓",
-                    SortDirection::Desc => "↑",
-                },
-                _ => "↕", // Unsorted/available for sorting
-            },
+            label,
         )
         .size(ButtonSize::Compact)
-        .style(
-            if self
-                .engine
-                .applied_sorting
-                .is_some_and(|o| o.col_idx == col_idx)
-            {
-                ButtonStyle::Filled
-            } else {
-                ButtonStyle::Subtle
-            },
-        )
-        .tooltip(Tooltip::text(match self.engine.applied_sorting {
-            Some(ordering) if ordering.col_idx == col_idx => match ordering.direction {
-                SortDirection::Asc => "Sorted A-Z. Click to sort Z-A",
-                SortDirection::Desc => "Sorted Z-A. Click to disable sorting",
-            },
-            _ => "Not sorted. Click to sort A-Z",
+        .style(if direction.is_some() {
+            ButtonStyle::Filled
+        } else {
+            ButtonStyle::Subtle
+        })
+        .tooltip(Tooltip::text(match direction {
+            Some(SortDirection::Asc) => {
+                "Sorted A-Z. Click to sort Z-A. Shift-click to sort by several columns"
+            }
+            Some(SortDirection::Desc) => {
+                "Sorted Z-A. Click to disable sorting. Shift-click to sort by several columns"
+            }
+            None => "Not sorted. Click to sort A-Z. Shift-click to sort by several columns",
         }))
-        .on_click(cx.listener(move |this, _event, _window, cx| {
-            let new_sorting = match this.engine.applied_sorting {
-                Some(ordering) if ordering.col_idx == col_idx => {
-                    // Same column clicked - cycle through states
-                    match ordering.direction {
-                        SortDirection::Asc => Some(AppliedSorting {
-                            col_idx,
-                            direction: SortDirection::Desc,
-                        }),
-                        SortDirection::Desc => None, // Clear sorting
-                    }
-                }
-                _ => {
-                    // Different column or no sorting - start with ascending
-                    Some(AppliedSorting {
-                        col_idx,
-                        direction: SortDirection::Asc,
-                    })
-                }
-            };
-
-            this.engine.applied_sorting = new_sorting;
-            this.apply_sort();
+        .on_click(cx.listener(move |this, event: &ClickEvent, _window, cx| {
+            toggle_sorting(
+                &mut this.engine.applied_sorting,
+                col_idx,
+                event.modifiers().shift,
+            );
+            this.apply_sort(cx);
+            cx.stop_propagation();
             cx.notify();
         }));
         sort_btn
@@ -4,23 +4,41 @@
 //! - `DisplayCellId` - indices of data after applied transformations like sorting/filtering, which is used to render cell on the screen
 //!
 //! It's designed to contain core logic of operations without relying on `CsvPreviewView`, context or window handles.
+//! Sorting and filtering are computed by a [`MappingJob`], which can run on a background thread.
 
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+};
 
 use ui::table_row::TableRow;
 
 use crate::{
-    table_data_engine::sorting_by_column::{AppliedSorting, sort_data_rows},
-    types::{DataRow, DisplayRow, TableCell, TableLikeContent},
+    table_data_engine::{
+        filtering::{ColumnFilter, filter_data_rows},
+        query::Query,
+        sorting_by_column::{AppliedSorting, sort_data_rows},
+        statistics::ColumnStatistics,
+    },
+    types::{AnyColumn, DataRow, DisplayRow, TableCell, TableLikeContent},
 };
 
+pub mod editing;
+pub mod filtering;
+pub mod query;
 pub mod sorting_by_column;
+pub mod statistics;
 
 #[derive(Default)]
 pub(crate) struct TableDataEngine {
-    pub applied_sorting: Option<AppliedSorting>,
+    /// Sort keys, the most significant first
+    pub applied_sorting: Vec<AppliedSorting>,
+    pub applied_filters: BTreeMap<AnyColumn, ColumnFilter>,
+    pub applied_query: Option<Query>,
     d2d_mapping: DisplayToDataMapping,
     pub contents: TableLikeContent,
+    /// Computed along with `contents`, one per column
+    pub column_statistics: Vec<ColumnStatistics>,
 }
 
 impl TableDataEngine {
@@ -28,27 +46,122 @@ impl TableDataEngine {
         &self.d2d_mapping
     }
 
-    pub(crate) fn apply_sort(&mut self) {
-        self.d2d_mapping
-            .apply_sorting(self.applied_sorting, &self.contents.rows);
-        self.d2d_mapping.merge_mappings();
+    /// Captures the current sorting, filters and rows, to recompute the given parts of
+    /// the display to data mapping
+    pub(crate) fn mapping_job(&self, changes: MappingChanges) -> MappingJob {
+        MappingJob {
+            rows: self.contents.rows.clone(),
+            sorting: changes.sorting.then(|| self.applied_sorting.clone()),
+            filtering: changes
+                .filtering
+                .then(|| (self.applied_filters.clone(), self.applied_query.clone())),
+        }
     }
 
-    /// Applies sorting and filtering to the data and produces display to data mapping
-    pub(crate) fn calculate_d2d_mapping(&mut self) {
-        self.d2d_mapping
-            .apply_sorting(self.applied_sorting, &self.contents.rows);
+    /// Applies the result of a [`MappingJob`] and produces display to data mapping
+    pub(crate) fn apply_mapping_update(&mut self, update: MappingUpdate) {
+        if let Some(sorted_rows) = update.sorted_rows {
+            self.d2d_mapping.sorted_rows = sorted_rows;
+        }
+        if let Some(filtered_out_rows) = update.filtered_out_rows {
+            self.d2d_mapping.filtered_out_rows = filtered_out_rows;
+        }
         self.d2d_mapping.merge_mappings();
     }
 }
 
+/// Parts of the display to data mapping that need to be recomputed
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct MappingChanges {
+    pub sorting: bool,
+    pub filtering: bool,
+}
+
+impl MappingChanges {
+    pub(crate) const SORTING: Self = Self {
+        sorting: true,
+        filtering: false,
+    };
+    pub(crate) const FILTERING: Self = Self {
+        sorting: false,
+        filtering: true,
+    };
+    pub(crate) const ALL: Self = Self {
+        sorting: true,
+        filtering: true,
+    };
+
+    pub(crate) fn union(self, other: Self) -> Self {
+        Self {
+            sorting: self.sorting || other.sorting,
+            filtering: self.filtering || other.filtering,
+        }
+    }
+}
+
+/// Sorting and filtering detached from the engine, so that it can be computed off the main thread
+pub(crate) struct MappingJob {
+    rows: Arc<Vec<TableRow<TableCell>>>,
+    sorting: Option<Vec<AppliedSorting>>,
+    filtering: Option<(BTreeMap<AnyColumn, ColumnFilter>, Option<Query>)>,
+}
+
+/// Result of a [`MappingJob`], with `None` for the parts that weren't recomputed
+pub(crate) struct MappingUpdate {
+    sorted_rows: Option<Vec<DataRow>>,
+    filtered_out_rows: Option<HashSet<DataRow>>,
+}
+
+impl MappingJob {
+    pub(crate) fn run(self) -> MappingUpdate {
+        MappingUpdate {
+            sorted_rows: self
+                .sorting
+                .map(|sortings| compute_sorting(&sortings, &self.rows)),
+            filtered_out_rows: self
+                .filtering
+                .map(|(filters, query)| filter_data_rows(&self.rows, &filters, query.as_ref())),
+        }
+    }
+}
+
+/// All rows sorted, regardless of applied filtering
+fn compute_sorting(sortings: &[AppliedSorting], rows: &[TableRow<TableCell>]) -> Vec<DataRow> {
+    let data_rows: Vec<DataRow> = (0..rows.len()).map(DataRow).collect();
+
+    if sortings.is_empty() {
+        data_rows
+    } else {
+        sort_data_rows(rows, data_rows, sortings)
+    }
+}
+
+/// Value of a cell as displayed, with missing cells treated as empty.
+pub(crate) fn cell_value(row: &TableRow<TableCell>, col: AnyColumn) -> &str {
+    row.get(col)
+        .and_then(|cell| cell.display_value())
+        .map_or("", |value| value.as_str())
+}
+
+/// Parses a cell value as a number, ignoring surrounding whitespace.
+pub(crate) fn parse_number(value: &str) -> Option<f64> {
+    value
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|number| number.is_finite())
+}
+
 /// Relation of Display (rendered) rows to Data (src) rows with applied transformations
 /// Transformations applied:
-/// - sorting by column
+/// - sorting by columns
+/// - filtering by columns and query
 #[derive(Debug, Default)]
 pub struct DisplayToDataMapping {
     /// All rows sorted, regardless of applied filtering. Applied every time sorting changes
     pub sorted_rows: Vec<DataRow>,
+    /// Rows hidden by filters or the query. Applied every time filtering changes
+    pub filtered_out_rows: HashSet<DataRow>,
     /// Filtered and sorted rows. Computed cheaply from `sorted_mapping` and `filtered_out_rows`
     pub mapping: Arc<HashMap<DisplayRow, DataRow>>,
 }
@@ -64,27 +177,49 @@ impl DisplayToDataMapping {
         self.mapping.len()
     }
 
-    /// Computes sorting
-    fn apply_sorting(&mut self, sorting: Option<AppliedSorting>, rows: &[TableRow<TableCell>]) {
-        let data_rows: Vec<DataRow> = (0..rows.len()).map(DataRow).collect();
-
-        let sorted_rows = if let Some(sorting) = sorting {
-            sort_data_rows(&rows, data_rows, sorting)
-        } else {
-            data_rows
-        };
-
-        self.sorted_rows = sorted_rows;
-    }
-
     /// Take pre-computed sorting and filtering results, and apply them to the mapping
     fn merge_mappings(&mut self) {
         self.mapping = Arc::new(
             self.sorted_rows
                 .iter()
+                .filter(|row| !self.filtered_out_rows.contains(row))
                 .enumerate()
                 .map(|(display, data)| (DisplayRow(display), *data))
                 .collect(),
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table_data_engine::sorting_by_column::SortDirection;
+
+    #[test]
+    fn test_mapping_job() {
+        let mut engine = TableDataEngine {
+            contents: TableLikeContent::from_str("name,price\na,3\nb,1\nc,2\n".to_string()),
+            ..Default::default()
+        };
+        engine.apply_mapping_update(engine.mapping_job(MappingChanges::ALL).run());
+        assert_eq!(engine.d2d_mapping().visible_row_count(), 3);
+
+        engine.applied_sorting = vec![AppliedSorting {
+            col_idx: AnyColumn(1),
+            direction: SortDirection::Asc,
+        }];
+        let sort_job = engine.mapping_job(MappingChanges::SORTING);
+        engine
+            .applied_filters
+            .insert(AnyColumn(1), ColumnFilter::parse("> 1").unwrap().unwrap());
+        let filter_job = engine.mapping_job(MappingChanges::FILTERING);
+
+        // Results can be applied in any order, each only replacing its own part
+        engine.apply_mapping_update(filter_job.run());
+        engine.apply_mapping_update(sort_job.run());
+        let data_rows = (0..engine.d2d_mapping().visible_row_count())
+            .map(|row| engine.d2d_mapping().get_data_row(DisplayRow(row)).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(data_rows, vec![DataRow(2), DataRow(0)]);
+    }
+}
@@ -0,0 +1,436 @@
+//! Translates table edits (cell values, inserted/removed rows and columns, pasted ranges)
+//! into minimal edits of the source buffer, so undo, collaboration and saving work as for
+//! any other edit.
+//!
+//! Cell positions come from the anchors recorded while parsing, and are resolved against the
+//! snapshot the edits are going to be applied to.
+
+use std::{borrow::Cow, ops::Range};
+
+use text::{BufferSnapshot, ToOffset as _};
+use ui::table_row::TableRow;
+
+use crate::{
+    parser::parse_csv_with_positions,
+    types::{AnyColumn, DataRow, TableCell, TableLikeContent},
+};
+
+/// Byte range in the source buffer and the text to replace it with.
+pub type BufferEdit = (Range<usize>, String);
+
+/// An empty field spelled out, for rows that would otherwise be blank lines,
+/// which aren't rows.
+const QUOTED_EMPTY_FIELD: &str = "\"\"";
+
+/// Quotes `value` if it can't be written verbatim between two delimiters.
+pub fn escape_field(value: &str, delimiter: char) -> Cow<'_, str> {
+    if value.contains([delimiter, '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+fn cell_range(cell: &TableCell, snapshot: &BufferSnapshot) -> Option<Range<usize>> {
+    match cell {
+        TableCell::Real { position, .. } => {
+            Some(position.start.to_offset(snapshot)..position.end.to_offset(snapshot))
+        }
+        TableCell::Virtual => None,
+    }
+}
+
+/// Number of cells actually present in the source; the rest of the row is padding.
+fn real_cell_count(row: &TableRow<TableCell>) -> usize {
+    row.as_slice()
+        .iter()
+        .take_while(|cell| matches!(cell, TableCell::Real { .. }))
+        .count()
+}
+
+/// Range from the start of the row's first cell to the end of its last one.
+fn row_range(row: &TableRow<TableCell>, snapshot: &BufferSnapshot) -> Option<Range<usize>> {
+    let real_cells = real_cell_count(row);
+    let start = cell_range(row.get(0usize)?, snapshot)?.start;
+    let end = cell_range(row.get(real_cells.checked_sub(1)?)?, snapshot)?.end;
+    Some(start..end)
+}
+
+/// Text of a row with `number_of_cols` empty fields.
+fn empty_row(number_of_cols: usize, delimiter: char) -> String {
+    if number_of_cols <= 1 {
+        QUOTED_EMPTY_FIELD.to_string()
+    } else {
+        delimiter.to_string().repeat(number_of_cols - 1)
+    }
+}
+
+/// Sorts edits and merges the ones that touch, e.g. a cell replacement and an
+/// insertion right after it, so they can be applied as one batch.
+fn normalize_edits(mut edits: Vec<BufferEdit>) -> Vec<BufferEdit> {
+    edits.sort_by_key(|(range, _)| (range.start, range.end));
+    let mut normalized: Vec<BufferEdit> = Vec::with_capacity(edits.len());
+    for (range, text) in edits {
+        if let Some((previous_range, previous_text)) = normalized.last_mut()
+            && previous_range.end == range.start
+        {
+            previous_range.end = range.end;
+            previous_text.push_str(&text);
+        } else {
+            normalized.push((range, text));
+        }
+    }
+    normalized
+}
+
+/// Sets consecutive cells of `row`, starting at `start_col`. Cells past the end of
+/// the row in the source are created by appending delimiters.
+pub fn set_cells<'a>(
+    row: &TableRow<TableCell>,
+    start_col: AnyColumn,
+    values: impl IntoIterator<Item = &'a str>,
+    delimiter: char,
+    snapshot: &BufferSnapshot,
+) -> Vec<BufferEdit> {
+    let Some(row_end) = row_range(row, snapshot).map(|range| range.end) else {
+        return Vec::new();
+    };
+    let real_cells = real_cell_count(row);
+
+    let mut edits = Vec::new();
+    let mut appended = String::new();
+    let mut next_appended_col = real_cells;
+    for (offset, value) in values.into_iter().enumerate() {
+        let col = *start_col + offset;
+        let escaped = if value.is_empty() && real_cells == 1 && col == 0 {
+            Cow::Borrowed(QUOTED_EMPTY_FIELD)
+        } else {
+            escape_field(value, delimiter)
+        };
+        if col < real_cells {
+            let Some(range) = cell_range(row.expect_get(col), snapshot) else {
+                continue;
+            };
+            if snapshot.text_for_range(range.clone()).collect::<Cow<str>>() != escaped {
+                edits.push((range, escaped.into_owned()));
+            }
+        } else {
+            while next_appended_col < col {
+                appended.push(delimiter);
+                next_appended_col += 1;
+            }
+            appended.push(delimiter);
+            appended.push_str(&escaped);
+            next_appended_col += 1;
+        }
+    }
+    if !appended.is_empty() {
+        edits.push((row_end..row_end, appended));
+    }
+    edits
+}
+
+impl TableLikeContent {
+    /// The header row when `row` is `None`, otherwise the given data row.
+    fn row_or_header(&self, row: Option<DataRow>) -> Option<&TableRow<TableCell>> {
+        match row {
+            Some(row) => self.get_row(row),
+            None => Some(&self.headers),
+        }
+    }
+
+    /// Sets one cell; `row` is `None` for the header.
+    pub fn set_cell(
+        &self,
+        row: Option<DataRow>,
+        col: AnyColumn,
+        value: &str,
+        snapshot: &BufferSnapshot,
+    ) -> Vec<BufferEdit> {
+        let Some(table_row) = self.row_or_header(row) else {
+            return Vec::new();
+        };
+        set_cells(table_row, col, [value], self.delimiter, snapshot)
+    }
+
+    /// Inserts an empty row after `after`, or before the first data row when `after` is `None`.
+    pub fn insert_row(&self, after: Option<DataRow>, snapshot: &BufferSnapshot) -> Vec<BufferEdit> {
+        let Some(end) = self
+            .row_or_header(after)
+            .and_then(|row| row_range(row, snapshot))
+            .map(|range| range.end)
+        else {
+            return Vec::new();
+        };
+        let row = empty_row(self.number_of_cols, self.delimiter);
+        vec![(end..end, format!("\n{row}"))]
+    }
+
+    /// Removes a data row together with one of the line breaks around it.
+    pub fn delete_row(&self, row: DataRow, snapshot: &BufferSnapshot) -> Vec<BufferEdit> {
+        let Some(range) = self
+            .get_row(row)
+            .and_then(|table_row| row_range(table_row, snapshot))
+        else {
+            return Vec::new();
+        };
+        let range = if snapshot.chars_at(range.end).next() == Some('\n') {
+            range.start..range.end + 1
+        } else if snapshot.reversed_chars_at(range.start).next() == Some('\n') {
+            range.start - 1..range.end
+        } else {
+            range
+        };
+        vec![(range, String::new())]
+    }
+
+    fn all_rows(&self) -> impl Iterator<Item = &TableRow<TableCell>> {
+        std::iter::once(&self.headers).chain(self.rows.iter())
+    }
+
+    /// Inserts an empty column so that it ends up at index `at`.
+    pub fn insert_column(&self, at: AnyColumn, snapshot: &BufferSnapshot) -> Vec<BufferEdit> {
+        let delimiter = self.delimiter.to_string();
+        let edits = self
+            .all_rows()
+            .filter_map(|row| {
+                let real_cells = real_cell_count(row);
+                if *at < real_cells {
+                    let start = cell_range(row.expect_get(at), snapshot)?.start;
+                    Some((start..start, delimiter.clone()))
+                } else if *at == real_cells {
+                    let end = row_range(row, snapshot)?.end;
+                    Some((end..end, delimiter.clone()))
+                } else {
+                    // The row is already padded past the new column.
+                    None
+                }
+            })
+            .collect();
+        normalize_edits(edits)
+    }
+
+    /// Removes a column along with the delimiter separating it from its neighbour.
+    pub fn delete_column(&self, col: AnyColumn, snapshot: &BufferSnapshot) -> Vec<BufferEdit> {
+        let edits = self
+            .all_rows()
+            .filter_map(|row| {
+                let real_cells = real_cell_count(row);
+                if *col >= real_cells {
+                    return None;
+                }
+                let range = cell_range(row.expect_get(col), snapshot)?;
+                if real_cells == 1 {
+                    return Some((range, QUOTED_EMPTY_FIELD.to_string()));
+                }
+                let range = if *col > 0 {
+                    cell_range(row.expect_get(*col - 1), snapshot)?.end..range.end
+                } else {
+                    range.start..cell_range(row.expect_get(1usize), snapshot)?.start
+                };
+                Some((range, String::new()))
+            })
+            .collect();
+        normalize_edits(edits)
+    }
+
+    /// Pastes a rectangular range, given as delimited text, with its top-left cell at
+    /// `start_col` of the first row in `rows`. Pasted rows past the end of `rows` are
+    /// appended to the end of the table.
+    ///
+    /// Text copied from spreadsheets is tab separated, so tabs are used as the delimiter
+    /// of the pasted text whenever it has any.
+    pub fn paste(
+        &self,
+        rows: impl IntoIterator<Item = DataRow>,
+        start_col: AnyColumn,
+        text: &str,
+        snapshot: &BufferSnapshot,
+    ) -> Vec<BufferEdit> {
+        let pasted_delimiter = if text.contains('\t') {
+            '\t'
+        } else {
+            self.delimiter
+        };
+        let (pasted_rows, _) = parse_csv_with_positions(text, pasted_delimiter);
+
+        let mut edits = Vec::new();
+        let mut rows = rows.into_iter();
+        let mut appended_rows = String::new();
+        for pasted_row in &pasted_rows {
+            let values = pasted_row.iter().map(|(value, _)| value.as_str());
+            if let Some(table_row) = rows.next().and_then(|row| self.get_row(row)) {
+                edits.extend(set_cells(
+                    table_row,
+                    start_col,
+                    values,
+                    self.delimiter,
+                    snapshot,
+                ));
+            } else {
+                let fields = std::iter::repeat_n(Cow::Borrowed(""), *start_col)
+                    .chain(values.map(|value| escape_field(value, self.delimiter)))
+                    .collect::<Vec<_>>();
+                appended_rows.push('\n');
+                if fields.len() == 1 && fields[0].is_empty() {
+                    appended_rows.push_str(&empty_row(1, self.delimiter));
+                } else {
+                    appended_rows.push_str(&fields.join(&self.delimiter.to_string()));
+                }
+            }
+        }
+
+        if !appended_rows.is_empty()
+            && let Some(end) = self
+                .rows
+                .last()
+                .or(Some(&self.headers))
+                .and_then(|row| row_range(row, snapshot))
+                .map(|range| range.end)
+        {
+            edits.push((end..end, appended_rows));
+        }
+        normalize_edits(edits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use text::{Buffer, BufferId, ReplicaId};
+
+    fn apply(
+        text: &str,
+        edit: impl FnOnce(&TableLikeContent, &BufferSnapshot) -> Vec<BufferEdit>,
+    ) -> String {
+        let buffer = Buffer::new(
+            ReplicaId::LOCAL,
+            BufferId::new(1).unwrap(),
+            text.to_string(),
+        );
+        let snapshot = buffer.snapshot();
+        let content =
+            crate::parser::from_buffer(snapshot, crate::parser::detect_delimiter(snapshot, None));
+        let mut result = text.to_string();
+        for (range, new_text) in edit(&content, snapshot).into_iter().rev() {
+            result.replace_range(range, &new_text);
+        }
+        result
+    }
+
+    #[test]
+    fn test_set_cell_escapes_values() {
+        let text = "name,note\nJane,\"a, b\"\nJohn\n";
+        assert_eq!(
+            apply(text, |content, snapshot| content.set_cell(
+                Some(DataRow(0)),
+                AnyColumn(1),
+                "plain",
+                snapshot
+            )),
+            "name,note\nJane,plain\nJohn\n"
+        );
+        assert_eq!(
+            apply(text, |content, snapshot| content.set_cell(
+                None,
+                AnyColumn(0),
+                "say \"hi\"",
+                snapshot
+            )),
+            "\"say \"\"hi\"\"\",note\nJane,\"a, b\"\nJohn\n"
+        );
+        // Setting a padded cell creates it.
+        assert_eq!(
+            apply(text, |content, snapshot| content.set_cell(
+                Some(DataRow(1)),
+                AnyColumn(1),
+                "x",
+                snapshot
+            )),
+            "name,note\nJane,\"a, b\"\nJohn,x\n"
+        );
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let text = "a,b,c\n1,2,3\n4,5,6";
+        assert_eq!(
+            apply(text, |content, snapshot| content.insert_row(None, snapshot)),
+            "a,b,c\n,,\n1,2,3\n4,5,6"
+        );
+        assert_eq!(
+            apply(text, |content, snapshot| content
+                .insert_row(Some(DataRow(1)), snapshot)),
+            "a,b,c\n1,2,3\n4,5,6\n,,"
+        );
+        assert_eq!(
+            apply(text, |content, snapshot| content
+                .delete_row(DataRow(0), snapshot)),
+            "a,b,c\n4,5,6"
+        );
+        assert_eq!(
+            apply(text, |content, snapshot| content
+                .delete_row(DataRow(1), snapshot)),
+            "a,b,c\n1,2,3"
+        );
+        assert_eq!(
+            apply(text, |content, snapshot| content
+                .insert_column(AnyColumn(1), snapshot)),
+            "a,,b,c\n1,,2,3\n4,,5,6"
+        );
+        assert_eq!(
+            apply(text, |content, snapshot| content
+                .insert_column(AnyColumn(3), snapshot)),
+            "a,b,c,\n1,2,3,\n4,5,6,"
+        );
+        assert_eq!(
+            apply(text, |content, snapshot| content
+                .delete_column(AnyColumn(0), snapshot)),
+            "b,c\n2,3\n5,6"
+        );
+        assert_eq!(
+            apply(text, |content, snapshot| content
+                .delete_column(AnyColumn(2), snapshot)),
+            "a,b\n1,2\n4,5"
+        );
+    }
+
+    #[test]
+    fn test_single_column_rows_are_kept() {
+        let text = "name\nJane\nJohn";
+        assert_eq!(
+            apply(text, |content, snapshot| content.set_cell(
+                Some(DataRow(0)),
+                AnyColumn(0),
+                "",
+                snapshot
+            )),
+            "name\n\"\"\nJohn"
+        );
+        assert_eq!(
+            apply(text, |content, snapshot| content
+                .delete_column(AnyColumn(0), snapshot)),
+            "\"\"\n\"\"\n\"\""
+        );
+        // Rows that are only padded past their first cell keep it too.
+        assert_eq!(
+            apply("a,b\n1\n2,3", |content, snapshot| content
+                .delete_column(AnyColumn(0), snapshot)),
+            "b\n\"\"\n3"
+        );
+    }
+
+    #[test]
+    fn test_paste_range() {
+        let text = "a\tb\n1\t2\n";
+        assert_eq!(
+            apply(text, |content, snapshot| content.paste(
+                [DataRow(0)],
+                AnyColumn(1),
+                "x\ty\nz\tw",
+                snapshot
+            )),
+            "a\tb\n1\tx\ty\n\tz\tw\n"
+        );
+    }
+}
@@ -0,0 +1,244 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt,
+    ops::Bound,
+};
+
+use anyhow::Context as _;
+use regex::{Regex, RegexBuilder};
+use ui::table_row::TableRow;
+
+use crate::{
+    table_data_engine::{cell_value, parse_number, query::Query},
+    types::{AnyColumn, DataRow, TableCell},
+};
+
+/// Condition the values of a column have to meet for their row to be displayed.
+#[derive(Debug, Clone)]
+pub enum ColumnFilter {
+    /// Case-insensitive substring match
+    Contains(String),
+    Regex(Regex),
+    /// Numbers within the bounds. Values that aren't numbers never match.
+    NumericRange {
+        min: Bound<f64>,
+        max: Bound<f64>,
+    },
+    /// Empty or missing values
+    Empty,
+    NonEmpty,
+}
+
+impl ColumnFilter {
+    /// Parses the text of a column filter input:
+    /// - `/pattern/` matches a regex,
+    /// - `10..20`, `10..`, `..20`, `> 10`, `>= 10`, `< 20` and `<= 20` match numeric ranges,
+    /// - `(empty)` and `(non-empty)` match empty and non-empty values,
+    /// - anything else matches values containing the text.
+    ///
+    /// Returns `None` for blank input.
+    pub fn parse(text: &str) -> anyhow::Result<Option<Self>> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Ok(None);
+        }
+        if text.eq_ignore_ascii_case("(empty)") {
+            return Ok(Some(Self::Empty));
+        }
+        if text.eq_ignore_ascii_case("(non-empty)") {
+            return Ok(Some(Self::NonEmpty));
+        }
+        if let Some(pattern) = text
+            .strip_prefix('/')
+            .and_then(|text| text.strip_suffix('/'))
+        {
+            return Self::regex(pattern).map(Some);
+        }
+        if let Some(range) = parse_numeric_range(text)? {
+            return Ok(Some(range));
+        }
+        Ok(Some(Self::Contains(text.to_string())))
+    }
+
+    pub fn regex(pattern: &str) -> anyhow::Result<Self> {
+        let regex = RegexBuilder::new(pattern)
+            .size_limit(1 << 20)
+            .build()
+            .with_context(|| format!("invalid regex `{pattern}`"))?;
+        Ok(Self::Regex(regex))
+    }
+
+    pub fn matches(&self, value: &str) -> bool {
+        match self {
+            Self::Contains(text) => contains_ignoring_case(value, text),
+            Self::Regex(regex) => regex.is_match(value),
+            Self::NumericRange { min, max } => parse_number(value).is_some_and(|number| {
+                let above_min = match min {
+                    Bound::Included(min) => number >= *min,
+                    Bound::Excluded(min) => number > *min,
+                    Bound::Unbounded => true,
+                };
+                let below_max = match max {
+                    Bound::Included(max) => number <= *max,
+                    Bound::Excluded(max) => number < *max,
+                    Bound::Unbounded => true,
+                };
+                above_min && below_max
+            }),
+            Self::Empty => value.trim().is_empty(),
+            Self::NonEmpty => !value.trim().is_empty(),
+        }
+    }
+}
+
+/// Formats the filter the way [`ColumnFilter::parse`] accepts it.
+impl fmt::Display for ColumnFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Contains(text) => write!(f, "{text}"),
+            Self::Regex(regex) => write!(f, "/{}/", regex.as_str()),
+            Self::NumericRange { min, max } => match (min, max) {
+                (Bound::Included(min), Bound::Included(max)) => write!(f, "{min}..{max}"),
+                (Bound::Included(min), Bound::Unbounded) => write!(f, ">= {min}"),
+                (Bound::Excluded(min), Bound::Unbounded) => write!(f, "> {min}"),
+                (Bound::Unbounded, Bound::Included(max)) => write!(f, "<= {max}"),
+                (Bound::Unbounded, Bound::Excluded(max)) => write!(f, "< {max}"),
+                (Bound::Unbounded, Bound::Unbounded) => write!(f, ".."),
+                (min, max) => write!(f, "{min:?}..{max:?}"),
+            },
+            Self::Empty => write!(f, "(empty)"),
+            Self::NonEmpty => write!(f, "(non-empty)"),
+        }
+    }
+}
+
+fn parse_numeric_range(text: &str) -> anyhow::Result<Option<ColumnFilter>> {
+    let number = |text: &str| {
+        parse_number(text).with_context(|| format!("`{}` is not a number", text.trim()))
+    };
+
+    for (prefix, bound) in [
+        (">=", Bound::Included as fn(f64) -> Bound<f64>),
+        ("<=", Bound::Included),
+        (">", Bound::Excluded),
+        ("<", Bound::Excluded),
+    ] {
+        if let Some(rest) = text.strip_prefix(prefix) {
+            let bound = bound(number(rest)?);
+            return Ok(Some(if prefix.starts_with('>') {
+                ColumnFilter::NumericRange {
+                    min: bound,
+                    max: Bound::Unbounded,
+                }
+            } else {
+                ColumnFilter::NumericRange {
+                    min: Bound::Unbounded,
+                    max: bound,
+                }
+            }));
+        }
+    }
+
+    let Some((min, max)) = text.split_once("..") else {
+        return Ok(None);
+    };
+    let (min, max) = (min.trim(), max.trim());
+    // Only treat it as a range when both sides look numeric, so that e.g. `a..b` is still
+    // searched for as text.
+    let is_bound = |text: &str| text.is_empty() || parse_number(text).is_some();
+    if !is_bound(min) || !is_bound(max) {
+        return Ok(None);
+    }
+    let bound = |text: &str| parse_number(text).map_or(Bound::Unbounded, Bound::Included);
+    Ok(Some(ColumnFilter::NumericRange {
+        min: bound(min),
+        max: bound(max),
+    }))
+}
+
+fn contains_ignoring_case(haystack: &str, needle: &str) -> bool {
+    if needle.is_empty() {
+        true
+    } else if needle.is_ascii() {
+        // Avoids allocating lowercase copies of every value when filtering large files.
+        haystack
+            .as_bytes()
+            .windows(needle.len())
+            .any(|window| window.eq_ignore_ascii_case(needle.as_bytes()))
+    } else {
+        haystack.to_lowercase().contains(&needle.to_lowercase())
+    }
+}
+
+/// Returns rows that don't pass either the column filters or the query.
+pub fn filter_data_rows(
+    content_rows: &[TableRow<TableCell>],
+    filters: &BTreeMap<AnyColumn, ColumnFilter>,
+    query: Option<&Query>,
+) -> HashSet<DataRow> {
+    if filters.is_empty() && query.is_none() {
+        return HashSet::default();
+    }
+
+    content_rows
+        .iter()
+        .enumerate()
+        .filter(|(_, row)| {
+            let passes_filters = filters
+                .iter()
+                .all(|(col, filter)| filter.matches(cell_value(row, *col)));
+            !passes_filters || query.is_some_and(|query| !query.matches(row))
+        })
+        .map(|(ix, _)| DataRow(ix))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> ColumnFilter {
+        ColumnFilter::parse(text).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_parse_filters() {
+        assert!(ColumnFilter::parse("  ").unwrap().is_none());
+        assert!(matches!(parse("(empty)"), ColumnFilter::Empty));
+        assert!(matches!(parse("(Non-Empty)"), ColumnFilter::NonEmpty));
+        assert!(matches!(parse("/^a+$/"), ColumnFilter::Regex(_)));
+        assert!(ColumnFilter::parse("/(/").is_err());
+        assert!(ColumnFilter::parse("> ten").is_err());
+        assert!(matches!(parse("a..b"), ColumnFilter::Contains(_)));
+
+        for text in [
+            "1..2.5", ">= 3", "> 3", "<= 3", "< 3", "(empty)", "/x/", "eu",
+        ] {
+            assert_eq!(parse(text).to_string(), text);
+        }
+    }
+
+    #[test]
+    fn test_filter_matches() {
+        let range = parse("10..20");
+        assert!(range.matches("10"));
+        assert!(range.matches(" 20 "));
+        assert!(!range.matches("20.5"));
+        assert!(!range.matches("abc"));
+
+        let greater = parse("> 10");
+        assert!(!greater.matches("10"));
+        assert!(greater.matches("10.01"));
+        assert!(parse("..0").matches("-3"));
+
+        let contains = parse("Eu");
+        assert!(contains.matches("EUROPE"));
+        assert!(contains.matches("Neu"));
+        assert!(!contains.matches("US"));
+        assert!(parse("ÄPFEL").matches("äpfel"));
+
+        assert!(parse("(empty)").matches(" "));
+        assert!(!parse("(non-empty)").matches(""));
+        assert!(parse("/^\\d{3}$/").matches("123"));
+    }
+}
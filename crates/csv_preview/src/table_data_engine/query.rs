@@ -0,0 +1,444 @@
+//! Small query language for slicing the table, e.g. `price > 10 and region = "EU"`.
+//!
+//! ```text
+//! query      := and ("or" and)*
+//! and        := unary ("and" unary)*
+//! unary      := "not" unary | "(" query ")" | condition
+//! condition  := column ("=" | "!=" | "<" | "<=" | ">" | ">=") value
+//!             | column ("contains" | "matches") value
+//!             | column "is" ["not"] "empty"
+//! column     := word | `quoted name`
+//! value      := number | "quoted text" | 'quoted text' | word
+//! ```
+//!
+//! Keywords are case-insensitive. Comparisons with a number are numeric, and rows whose value
+//! isn't a number only match `!=`. Comparisons with text are case-sensitive.
+
+use std::{cmp::Ordering, iter::Peekable, ops::Range, str::CharIndices};
+
+use anyhow::{Context as _, anyhow, bail};
+use ui::table_row::TableRow;
+
+use crate::{
+    table_data_engine::{cell_value, filtering::ColumnFilter, parse_number},
+    types::{AnyColumn, TableCell},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+}
+
+impl CompareOp {
+    fn accepts(self, ordering: Ordering) -> bool {
+        match self {
+            Self::Eq => ordering.is_eq(),
+            Self::NotEq => ordering.is_ne(),
+            Self::Lt => ordering.is_lt(),
+            Self::LtEq => ordering.is_le(),
+            Self::Gt => ordering.is_gt(),
+            Self::GtEq => ordering.is_ge(),
+        }
+    }
+}
+
+/// Value a column is compared against.
+#[derive(Debug, Clone)]
+pub struct Literal {
+    pub text: String,
+    /// Set for unquoted numbers, which are compared numerically.
+    pub number: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Query {
+    And(Box<Query>, Box<Query>),
+    Or(Box<Query>, Box<Query>),
+    Not(Box<Query>),
+    Compare {
+        column: AnyColumn,
+        op: CompareOp,
+        value: Literal,
+    },
+    Filter {
+        column: AnyColumn,
+        filter: ColumnFilter,
+    },
+}
+
+impl Query {
+    /// Parses a query, resolving column names against `column_names`: exact matches are
+    /// preferred over case-insensitive ones.
+    pub fn parse(text: &str, column_names: &[&str]) -> anyhow::Result<Self> {
+        let tokens = tokenize(text)?;
+        let mut parser = Parser {
+            tokens: tokens.into_iter().peekable(),
+            column_names,
+            end: text.len(),
+        };
+        let query = parser.parse_or()?;
+        if let Some(token) = parser.tokens.next() {
+            bail!(
+                "unexpected {} at {}",
+                token.kind.describe(),
+                token.range.start + 1
+            );
+        }
+        Ok(query)
+    }
+
+    pub fn matches(&self, row: &TableRow<TableCell>) -> bool {
+        match self {
+            Self::And(left, right) => left.matches(row) && right.matches(row),
+            Self::Or(left, right) => left.matches(row) || right.matches(row),
+            Self::Not(query) => !query.matches(row),
+            Self::Compare { column, op, value } => {
+                let cell = cell_value(row, *column);
+                match value.number {
+                    Some(number) => match parse_number(cell) {
+                        Some(cell) => cell
+                            .partial_cmp(&number)
+                            .is_some_and(|ordering| op.accepts(ordering)),
+                        None => *op == CompareOp::NotEq,
+                    },
+                    None => op.accepts(cell.cmp(value.text.as_str())),
+                }
+            }
+            Self::Filter { column, filter } => filter.matches(cell_value(row, *column)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    /// Unquoted word, which can be a keyword, a column name or a value
+    Word(String),
+    /// Quoted with single or double quotes
+    Text(String),
+    /// Quoted with backticks
+    Column(String),
+    Op(CompareOp),
+    OpenParen,
+    CloseParen,
+}
+
+impl TokenKind {
+    fn describe(&self) -> String {
+        match self {
+            Self::Word(word) => format!("`{word}`"),
+            Self::Text(text) => format!("\"{text}\""),
+            Self::Column(column) => format!("`{column}`"),
+            Self::Op(_) => "operator".to_string(),
+            Self::OpenParen => "`(`".to_string(),
+            Self::CloseParen => "`)`".to_string(),
+        }
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self, Self::Word(word) if word.eq_ignore_ascii_case(keyword))
+    }
+}
+
+#[derive(Debug)]
+struct Token {
+    kind: TokenKind,
+    range: Range<usize>,
+}
+
+fn tokenize(text: &str) -> anyhow::Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, ch)) = chars.next() {
+        let kind = match ch {
+            ch if ch.is_whitespace() => continue,
+            '(' => TokenKind::OpenParen,
+            ')' => TokenKind::CloseParen,
+            '=' => {
+                chars.next_if(|(_, ch)| *ch == '=');
+                TokenKind::Op(CompareOp::Eq)
+            }
+            '!' => {
+                chars
+                    .next_if(|(_, ch)| *ch == '=')
+                    .with_context(|| format!("expected `!=` at {}", start + 1))?;
+                TokenKind::Op(CompareOp::NotEq)
+            }
+            '<' => {
+                if chars.next_if(|(_, ch)| *ch == '=').is_some() {
+                    TokenKind::Op(CompareOp::LtEq)
+                } else if chars.next_if(|(_, ch)| *ch == '>').is_some() {
+                    TokenKind::Op(CompareOp::NotEq)
+                } else {
+                    TokenKind::Op(CompareOp::Lt)
+                }
+            }
+            '>' => {
+                if chars.next_if(|(_, ch)| *ch == '=').is_some() {
+                    TokenKind::Op(CompareOp::GtEq)
+                } else {
+                    TokenKind::Op(CompareOp::Gt)
+                }
+            }
+            '"' | '\'' => TokenKind::Text(take_quoted(&mut chars, ch, start)?),
+            '`' => TokenKind::Column(take_quoted(&mut chars, ch, start)?),
+            _ => {
+                let mut word = ch.to_string();
+                while let Some((_, ch)) = chars.next_if(|(_, ch)| !is_word_boundary(*ch)) {
+                    word.push(ch);
+                }
+                TokenKind::Word(word)
+            }
+        };
+        let end = chars.peek().map_or(text.len(), |(offset, _)| *offset);
+        tokens.push(Token {
+            kind,
+            range: start..end,
+        });
+    }
+    Ok(tokens)
+}
+
+fn is_word_boundary(ch: char) -> bool {
+    ch.is_whitespace() || matches!(ch, '(' | ')' | '=' | '!' | '<' | '>' | '"' | '\'' | '`')
+}
+
+/// Reads up to the closing `quote`, which is escaped by doubling it.
+fn take_quoted(
+    chars: &mut Peekable<CharIndices<'_>>,
+    quote: char,
+    start: usize,
+) -> anyhow::Result<String> {
+    let mut text = String::new();
+    loop {
+        match chars.next() {
+            Some((_, ch)) if ch == quote => {
+                if chars.next_if(|(_, ch)| *ch == quote).is_some() {
+                    text.push(quote);
+                } else {
+                    return Ok(text);
+                }
+            }
+            Some((_, ch)) => text.push(ch),
+            None => bail!("unclosed {quote} at {}", start + 1),
+        }
+    }
+}
+
+struct Parser<'a> {
+    tokens: Peekable<std::vec::IntoIter<Token>>,
+    column_names: &'a [&'a str],
+    end: usize,
+}
+
+impl Parser<'_> {
+    fn next_if_keyword(&mut self, keyword: &str) -> bool {
+        self.tokens
+            .next_if(|token| token.kind.is_keyword(keyword))
+            .is_some()
+    }
+
+    fn expect(&mut self, expected: &str) -> anyhow::Result<Token> {
+        self.tokens
+            .next()
+            .with_context(|| format!("expected {expected} at {}", self.end + 1))
+    }
+
+    fn parse_or(&mut self) -> anyhow::Result<Query> {
+        let mut query = self.parse_and()?;
+        while self.next_if_keyword("or") {
+            query = Query::Or(Box::new(query), Box::new(self.parse_and()?));
+        }
+        Ok(query)
+    }
+
+    fn parse_and(&mut self) -> anyhow::Result<Query> {
+        let mut query = self.parse_unary()?;
+        while self.next_if_keyword("and") {
+            query = Query::And(Box::new(query), Box::new(self.parse_unary()?));
+        }
+        Ok(query)
+    }
+
+    fn parse_unary(&mut self) -> anyhow::Result<Query> {
+        if self.next_if_keyword("not") {
+            return Ok(Query::Not(Box::new(self.parse_unary()?)));
+        }
+        if self
+            .tokens
+            .next_if(|token| token.kind == TokenKind::OpenParen)
+            .is_some()
+        {
+            let query = self.parse_or()?;
+            let token = self.expect("`)`")?;
+            if token.kind != TokenKind::CloseParen {
+                bail!(
+                    "expected `)` but found {} at {}",
+                    token.kind.describe(),
+                    token.range.start + 1
+                );
+            }
+            return Ok(query);
+        }
+        self.parse_condition()
+    }
+
+    fn parse_condition(&mut self) -> anyhow::Result<Query> {
+        let token = self.expect("a column name")?;
+        let column = match &token.kind {
+            TokenKind::Word(name) | TokenKind::Column(name) => self.resolve_column(name),
+            kind => Err(anyhow!(
+                "expected a column name but found {}",
+                kind.describe()
+            )),
+        }
+        .with_context(|| format!("at {}", token.range.start + 1))?;
+
+        let token = self.expect("an operator")?;
+        match token.kind {
+            TokenKind::Op(op) => Ok(Query::Compare {
+                column,
+                op,
+                value: self.parse_value()?,
+            }),
+            kind if kind.is_keyword("contains") => Ok(Query::Filter {
+                column,
+                filter: ColumnFilter::Contains(self.parse_value()?.text),
+            }),
+            kind if kind.is_keyword("matches") => Ok(Query::Filter {
+                column,
+                filter: ColumnFilter::regex(&self.parse_value()?.text)?,
+            }),
+            kind if kind.is_keyword("is") => {
+                let negated = self.next_if_keyword("not");
+                let token = self.expect("`empty`")?;
+                if !token.kind.is_keyword("empty") {
+                    bail!(
+                        "expected `empty` but found {} at {}",
+                        token.kind.describe(),
+                        token.range.start + 1
+                    );
+                }
+                Ok(Query::Filter {
+                    column,
+                    filter: if negated {
+                        ColumnFilter::NonEmpty
+                    } else {
+                        ColumnFilter::Empty
+                    },
+                })
+            }
+            kind => bail!(
+                "expected an operator but found {} at {}",
+                kind.describe(),
+                token.range.start + 1
+            ),
+        }
+    }
+
+    fn parse_value(&mut self) -> anyhow::Result<Literal> {
+        let token = self.expect("a value")?;
+        match token.kind {
+            TokenKind::Text(text) => Ok(Literal { text, number: None }),
+            TokenKind::Word(word) => Ok(Literal {
+                number: parse_number(&word),
+                text: word,
+            }),
+            kind => bail!(
+                "expected a value but found {} at {}",
+                kind.describe(),
+                token.range.start + 1
+            ),
+        }
+    }
+
+    fn resolve_column(&self, name: &str) -> anyhow::Result<AnyColumn> {
+        self.column_names
+            .iter()
+            .position(|column| *column == name)
+            .or_else(|| {
+                self.column_names
+                    .iter()
+                    .position(|column| column.eq_ignore_ascii_case(name))
+            })
+            .map(AnyColumn)
+            .with_context(|| format!("unknown column `{name}`"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TableLikeContent;
+
+    const CSV: &str = "name,price,region,unit price\n\
+        apple,12,EU,1\n\
+        banana,8,US,2\n\
+        cherry,,EU,3\n\
+        date,30,APAC,\n";
+
+    fn matching_names(query: &str) -> Vec<String> {
+        let content = TableLikeContent::from_str(CSV.to_string());
+        let column_names = content
+            .headers
+            .as_slice()
+            .iter()
+            .map(|cell| cell.display_value().map_or("", |value| value.as_str()))
+            .collect::<Vec<_>>();
+        let query = Query::parse(query, &column_names).unwrap();
+        content
+            .rows
+            .iter()
+            .filter(|row| query.matches(row))
+            .map(|row| cell_value(row, AnyColumn(0)).to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_query_matching() {
+        assert_eq!(matching_names(r#"price > 10 and region = "EU""#), ["apple"]);
+        assert_eq!(
+            matching_names("price >= 8 and price <= 12"),
+            ["apple", "banana"]
+        );
+        assert_eq!(
+            matching_names("PRICE < 10 or not (region = EU)"),
+            ["banana", "date"]
+        );
+        assert_eq!(matching_names("price is empty"), ["cherry"]);
+        assert_eq!(
+            matching_names("`unit price` is not empty and name contains AN"),
+            ["banana"]
+        );
+        assert_eq!(
+            matching_names("name matches '^[a-c]' and region != 'EU'"),
+            ["banana"]
+        );
+        assert_eq!(matching_names("price != 12"), ["banana", "cherry", "date"]);
+    }
+
+    #[test]
+    fn test_query_errors() {
+        let columns = ["name", "price"];
+        for (query, error) in [
+            ("", "expected a column name at 1"),
+            ("weight > 1", "unknown column `weight`"),
+            ("price >", "expected a value at 8"),
+            ("price 10", "expected an operator but found `10` at 7"),
+            ("(price > 1", "expected `)` at 11"),
+            ("name = \"x", "unclosed \" at 8"),
+            ("price > 1 name", "unexpected `name` at 11"),
+            ("price ! 1", "expected `!=` at 7"),
+        ] {
+            let actual = format!("{:#}", Query::parse(query, &columns).unwrap_err());
+            assert!(
+                actual.contains(error),
+                "query {query:?}: expected error containing {error:?}, got {actual:?}"
+            );
+        }
+    }
+}
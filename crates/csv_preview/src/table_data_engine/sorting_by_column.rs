@@ -1,6 +1,11 @@
+use std::cmp::Ordering;
+
 use ui::table_row::TableRow;
 
-use crate::types::{AnyColumn, DataRow, TableCell};
+use crate::{
+    table_data_engine::{cell_value, parse_number},
+    types::{AnyColumn, DataRow, TableCell},
+};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum SortDirection {
@@ -17,33 +22,142 @@ pub struct AppliedSorting {
     pub direction: SortDirection,
 }
 
+/// Cycles sorting of a column through ascending, descending and unsorted.
+///
+/// Without `additive`, the column becomes the only sort key. With it, the column is added
+/// as the least significant key, or cycled in place if it's already sorted.
+pub fn toggle_sorting(sortings: &mut Vec<AppliedSorting>, col_idx: AnyColumn, additive: bool) {
+    let existing = sortings
+        .iter()
+        .position(|sorting| sorting.col_idx == col_idx);
+    let next_direction = match existing.map(|ix| sortings[ix].direction) {
+        None => Some(SortDirection::Asc),
+        Some(SortDirection::Asc) => Some(SortDirection::Desc),
+        Some(SortDirection::Desc) => None,
+    };
+
+    if !additive {
+        // Clicking the only sorted column cycles it, clicking another one starts over.
+        let direction = if sortings.len() == 1 {
+            next_direction
+        } else {
+            Some(SortDirection::Asc)
+        };
+        sortings.clear();
+        sortings.extend(direction.map(|direction| AppliedSorting { col_idx, direction }));
+        return;
+    }
+
+    match (existing, next_direction) {
+        (Some(ix), Some(direction)) => sortings[ix].direction = direction,
+        (Some(ix), None) => {
+            sortings.remove(ix);
+        }
+        (None, direction) => {
+            sortings.extend(direction.map(|direction| AppliedSorting { col_idx, direction }))
+        }
+    }
+}
+
+/// Orders values numerically if both are numbers, as text otherwise. Empty values go last.
+fn compare_values(a: &str, b: &str) -> Ordering {
+    match (a.trim().is_empty(), b.trim().is_empty()) {
+        (true, true) => return Ordering::Equal,
+        (true, false) => return Ordering::Greater,
+        (false, true) => return Ordering::Less,
+        (false, false) => {}
+    }
+    match (parse_number(a), parse_number(b)) {
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        _ => a.cmp(b),
+    }
+}
+
+/// Sorts rows by the given columns, the first one being the most significant.
 pub fn sort_data_rows(
     content_rows: &[TableRow<TableCell>],
     mut data_row_ids: Vec<DataRow>,
-    sorting: AppliedSorting,
+    sortings: &[AppliedSorting],
 ) -> Vec<DataRow> {
     data_row_ids.sort_by(|&a, &b| {
         let row_a = &content_rows[*a];
         let row_b = &content_rows[*b];
 
-        // TODO: Decide how to handle nulls (on top or on bottom)
-        let val_a = row_a
-            .get(sorting.col_idx)
-            .and_then(|tc| tc.display_value())
-            .map(|tc| tc.as_str())
-            .unwrap_or("");
-        let val_b = row_b
-            .get(sorting.col_idx)
-            .and_then(|tc| tc.display_value())
-            .map(|tc| tc.as_str())
-            .unwrap_or("");
-
-        let cmp = val_a.cmp(val_b);
-        match sorting.direction {
-            SortDirection::Asc => cmp,
-            SortDirection::Desc => cmp.reverse(),
-        }
+        sortings
+            .iter()
+            .map(|sorting| {
+                let cmp = compare_values(
+                    cell_value(row_a, sorting.col_idx),
+                    cell_value(row_b, sorting.col_idx),
+                );
+                match sorting.direction {
+                    SortDirection::Asc => cmp,
+                    SortDirection::Desc => cmp.reverse(),
+                }
+            })
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
     });
 
     data_row_ids
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TableLikeContent;
+
+    #[test]
+    fn test_sort_by_multiple_columns() {
+        let contents =
+            TableLikeContent::from_str("region,price\nEU,10\nUS,9\nEU,9\n,1\nUS,100\n".to_string());
+        let rows = (0..contents.rows.len()).map(DataRow).collect();
+        let sorted = sort_data_rows(
+            &contents.rows,
+            rows,
+            &[
+                AppliedSorting {
+                    col_idx: AnyColumn(0),
+                    direction: SortDirection::Asc,
+                },
+                AppliedSorting {
+                    col_idx: AnyColumn(1),
+                    direction: SortDirection::Desc,
+                },
+            ],
+        );
+        assert_eq!(
+            sorted.into_iter().map(|row| *row).collect::<Vec<_>>(),
+            [0, 2, 4, 1, 3]
+        );
+    }
+
+    #[test]
+    fn test_toggle_sorting() {
+        let columns = |sortings: &[AppliedSorting]| {
+            sortings
+                .iter()
+                .map(|sorting| (*sorting.col_idx, sorting.direction))
+                .collect::<Vec<_>>()
+        };
+        let mut sortings = Vec::new();
+
+        toggle_sorting(&mut sortings, AnyColumn(0), false);
+        assert_eq!(columns(&sortings), [(0, SortDirection::Asc)]);
+        toggle_sorting(&mut sortings, AnyColumn(1), true);
+        toggle_sorting(&mut sortings, AnyColumn(1), true);
+        assert_eq!(
+            columns(&sortings),
+            [(0, SortDirection::Asc), (1, SortDirection::Desc)]
+        );
+        toggle_sorting(&mut sortings, AnyColumn(0), true);
+        toggle_sorting(&mut sortings, AnyColumn(0), true);
+        assert_eq!(columns(&sortings), [(1, SortDirection::Desc)]);
+
+        toggle_sorting(&mut sortings, AnyColumn(2), false);
+        assert_eq!(columns(&sortings), [(2, SortDirection::Asc)]);
+        toggle_sorting(&mut sortings, AnyColumn(2), false);
+        toggle_sorting(&mut sortings, AnyColumn(2), false);
+        assert!(sortings.is_empty());
+    }
+}
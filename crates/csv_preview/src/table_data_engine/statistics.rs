@@ -0,0 +1,115 @@
+use std::collections::HashSet;
+
+use ui::SharedString;
+
+use crate::{
+    table_data_engine::{cell_value, parse_number},
+    types::{AnyColumn, TableLikeContent},
+};
+
+/// Summary of a column's values over all rows, shown in the table header.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ColumnStatistics {
+    /// Number of distinct non-empty values
+    pub distinct: usize,
+    /// Number of empty or missing values
+    pub nulls: usize,
+    /// Smallest non-empty value. Compared as numbers if all values are numbers, as text otherwise.
+    pub min: Option<SharedString>,
+    /// Largest non-empty value, compared the same way as `min`.
+    pub max: Option<SharedString>,
+}
+
+/// Computes statistics of every column. Meant to run in the background, as it goes over
+/// every cell of the table.
+pub fn compute_column_statistics(contents: &TableLikeContent) -> Vec<ColumnStatistics> {
+    (0..contents.number_of_cols)
+        .map(|col| column_statistics(contents, AnyColumn(col)))
+        .collect()
+}
+
+fn column_statistics(contents: &TableLikeContent, col: AnyColumn) -> ColumnStatistics {
+    let mut distinct = HashSet::new();
+    let mut nulls = 0;
+    let mut all_numeric = true;
+    let mut numeric_min: Option<(f64, &str)> = None;
+    let mut numeric_max: Option<(f64, &str)> = None;
+    let mut text_min: Option<&str> = None;
+    let mut text_max: Option<&str> = None;
+
+    for row in contents.rows.iter() {
+        let value = cell_value(row, col);
+        if value.trim().is_empty() {
+            nulls += 1;
+            continue;
+        }
+        distinct.insert(value);
+
+        text_min = Some(text_min.map_or(value, |min| min.min(value)));
+        text_max = Some(text_max.map_or(value, |max| max.max(value)));
+        if all_numeric {
+            match parse_number(value) {
+                Some(number) => {
+                    if numeric_min.is_none_or(|(min, _)| number < min) {
+                        numeric_min = Some((number, value));
+                    }
+                    if numeric_max.is_none_or(|(max, _)| number > max) {
+                        numeric_max = Some((number, value));
+                    }
+                }
+                None => all_numeric = false,
+            }
+        }
+    }
+
+    let (min, max) = if all_numeric {
+        (
+            numeric_min.map(|(_, value)| value),
+            numeric_max.map(|(_, value)| value),
+        )
+    } else {
+        (text_min, text_max)
+    };
+    ColumnStatistics {
+        distinct: distinct.len(),
+        nulls,
+        min: min.map(|value| SharedString::new(value.trim())),
+        max: max.map(|value| SharedString::new(value.trim())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_statistics() {
+        let contents = TableLikeContent::from_str(
+            "name,price,region\nb,10,EU\na,9.5,\nc,100,EU\na\n".to_string(),
+        );
+        let statistics = compute_column_statistics(&contents);
+        assert_eq!(
+            statistics,
+            [
+                ColumnStatistics {
+                    distinct: 3,
+                    nulls: 0,
+                    min: Some("a".into()),
+                    max: Some("c".into()),
+                },
+                ColumnStatistics {
+                    distinct: 3,
+                    nulls: 1,
+                    min: Some("9.5".into()),
+                    max: Some("100".into()),
+                },
+                ColumnStatistics {
+                    distinct: 1,
+                    nulls: 2,
+                    min: Some("EU".into()),
+                    max: Some("EU".into()),
+                },
+            ]
+        );
+    }
+}
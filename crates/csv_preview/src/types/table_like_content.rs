@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use ui::table_row::TableRow;
 
 use crate::types::{DataRow, LineNumber, TableCell};
@@ -9,9 +11,12 @@ pub struct TableLikeContent {
     /// Defines table width used to validate `TableRow` on creation
     pub number_of_cols: usize,
     pub headers: TableRow<TableCell>,
-    pub rows: Vec<TableRow<TableCell>>,
+    /// Shared with sorting and filtering running in the background
+    pub rows: Arc<Vec<TableRow<TableCell>>>,
     /// Follows the same indices as `rows`
     pub line_numbers: Vec<LineNumber>,
+    /// Character separating fields, e.g. `,` for CSV and `\t` for TSV.
+    pub delimiter: char,
 }
 
 impl Default for TableLikeContent {
@@ -19,8 +24,9 @@ impl Default for TableLikeContent {
         Self {
             number_of_cols: 0,
             headers: TableRow::<TableCell>::from_vec(vec![], 0),
-            rows: vec![],
+            rows: Arc::default(),
             line_numbers: vec![],
+            delimiter: ',',
         }
     }
 }
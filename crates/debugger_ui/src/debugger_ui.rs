@@ -49,6 +49,14 @@ actions!(
         StepOut,
         /// Steps back to the previous statement.
         StepBack,
+        /// Steps over the current instruction.
+        StepOverInstruction,
+        /// Steps into the current instruction.
+        StepIntoInstruction,
+        /// Steps into one of the calls on the current line, picking which when there are several.
+        StepIntoTarget,
+        /// Shows the details of the exception the program stopped on.
+        ShowExceptionInfo,
         /// Stops the debugging session.
         Stop,
         /// Toggles whether to ignore all breakpoints.
@@ -159,6 +167,15 @@ pub fn init(cx: &mut App) {
 
                 let caps = running_state.capabilities(cx);
                 let supports_step_back = caps.supports_step_back.unwrap_or_default();
+                let supports_stepping_granularity =
+                    caps.supports_stepping_granularity.unwrap_or_default();
+                let supports_step_in_targets =
+                    caps.supports_step_in_targets_request.unwrap_or_default();
+                let exception_thread = caps
+                    .supports_exception_info_request
+                    .unwrap_or_default()
+                    .then(|| running_state.session().read(cx).exception_thread())
+                    .flatten();
                 let supports_detach = running_state.session().read(cx).is_attached();
                 let status = running_state.thread_status(cx);
 
@@ -196,6 +213,42 @@ pub fn init(cx: &mut App) {
                             active_item.update(cx, |item, cx| item.step_back(cx)).ok();
                         })
                     })
+                    .when(supports_stepping_granularity, |div| {
+                        div.on_action({
+                            let active_item = active_item.clone();
+                            move |_: &StepOverInstruction, _, cx| {
+                                active_item
+                                    .update(cx, |item, cx| item.step_over_instruction(cx))
+                                    .ok();
+                            }
+                        })
+                        .on_action({
+                            let active_item = active_item.clone();
+                            move |_: &StepIntoInstruction, _, cx| {
+                                active_item
+                                    .update(cx, |item, cx| item.step_in_instruction(cx))
+                                    .ok();
+                            }
+                        })
+                    })
+                    .when(supports_step_in_targets, |div| {
+                        let active_item = active_item.clone();
+                        div.on_action(move |_: &StepIntoTarget, window, cx| {
+                            active_item
+                                .update(cx, |item, cx| item.step_into_target(window, cx))
+                                .ok();
+                        })
+                    })
+                    .when_some(exception_thread, |div, exception_thread| {
+                        let active_item = active_item.clone();
+                        div.on_action(move |_: &ShowExceptionInfo, window, cx| {
+                            active_item
+                                .update(cx, |item, cx| {
+                                    item.show_exception_info(exception_thread, window, cx)
+                                })
+                                .ok();
+                        })
+                    })
                     .on_action({
                         let active_item = active_item.clone();
                         move |_: &Continue, _, cx| {
@@ -347,6 +400,54 @@ pub fn init(cx: &mut App) {
                         },
                     );
 
+                    let supports_goto_targets = session
+                        .capabilities()
+                        .supports_goto_targets_request
+                        .unwrap_or_default();
+                    window.on_action_when(
+                        session.any_stopped_thread() && supports_goto_targets,
+                        TypeId::of::<editor::actions::SetNextStatement>(),
+                        {
+                            let editor = editor.clone();
+                            let active_session = active_session.clone();
+                            move |_, phase, _, cx| {
+                                if phase != DispatchPhase::Bubble {
+                                    return;
+                                }
+                                maybe!({
+                                    let (buffer, position) = editor
+                                        .update(cx, |editor, cx| {
+                                            let cursor_point: language::Point = editor
+                                                .selections
+                                                .newest(&editor.display_snapshot(cx))
+                                                .head();
+
+                                            editor
+                                                .buffer()
+                                                .read(cx)
+                                                .point_to_buffer_point(cursor_point, cx)
+                                        })
+                                        .ok()??;
+
+                                    let path =
+                                debugger::breakpoint_store::BreakpointStore::abs_path_from_buffer(
+                                    &buffer, cx,
+                                )?;
+
+                                    active_session.update(cx, |session, cx| {
+                                        session.running_state().update(cx, |state, cx| {
+                                            state
+                                                .set_next_statement(path, position.row, cx)
+                                                .detach_and_log_err(cx);
+                                        });
+                                    });
+
+                                    Some(())
+                                });
+                            }
+                        },
+                    );
+
                     window.on_action(
                         TypeId::of::<editor::actions::EvaluateSelectedText>(),
                         move |_, _, window, cx| {
@@ -1,13 +1,16 @@
 pub(crate) mod breakpoint_list;
 pub(crate) mod console;
+pub(crate) mod disassembly_view;
+pub(crate) mod exception_info_modal;
 pub(crate) mod loaded_source_list;
 pub(crate) mod memory_view;
 pub(crate) mod module_list;
 pub mod stack_frame_list;
+pub(crate) mod step_in_targets_modal;
 pub mod variable_list;
 use std::{
     any::Any,
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{Arc, LazyLock},
     time::Duration,
 };
@@ -17,7 +20,10 @@ use crate::{
     attach_modal::{AttachModal, ModalIntent},
     new_process_modal::resolve_path,
     persistence::{self, DebuggerPaneItem, SerializedLayout},
-    session::running::memory_view::MemoryView,
+    session::running::{
+        disassembly_view::DisassemblyView, exception_info_modal::ExceptionInfoModal,
+        memory_view::MemoryView, step_in_targets_modal::StepInTargetsModal,
+    },
 };
 
 use anyhow::{Context as _, Result, anyhow, bail};
@@ -25,7 +31,7 @@ use breakpoint_list::BreakpointList;
 use collections::{HashMap, IndexMap};
 use console::Console;
 use dap::{
-    Capabilities, DapRegistry, RunInTerminalRequestArguments, Thread,
+    Capabilities, DapRegistry, RunInTerminalRequestArguments, SteppingGranularity, Thread,
     adapters::{DebugAdapterName, DebugTaskDefinition},
     client::SessionId,
     debugger_settings::DebuggerSettings,
@@ -45,7 +51,7 @@ use project::{
 use rpc::proto::ViewId;
 use serde_json::Value;
 use settings::Settings;
-use stack_frame_list::StackFrameList;
+use stack_frame_list::{StackFrameList, StackFrameListEvent};
 use task::{
     BuildTaskDefinition, DebugScenario, SharedTaskContext, Shell, ShellBuilder, SpawnInTerminal,
     TaskContext, ZedDebugConfig, substitute_variables_in_str,
@@ -89,6 +95,7 @@ pub struct RunningState {
     pub(crate) scenario: Option<DebugScenario>,
     pub(crate) scenario_context: Option<DebugScenarioContext>,
     memory_view: Entity<MemoryView>,
+    disassembly_view: Entity<DisassemblyView>,
 }
 
 impl RunningState {
@@ -803,6 +810,15 @@ impl RunningState {
 
         let module_list = cx.new(|cx| ModuleList::new(session.clone(), workspace.clone(), cx));
 
+        let disassembly_view = cx.new(|cx| {
+            DisassemblyView::new(
+                session.clone(),
+                stack_frame_list.clone(),
+                workspace.clone(),
+                cx,
+            )
+        });
+
         let loaded_source_list = cx.new(|cx| LoadedSourceList::new(session.clone(), cx));
 
         let console = cx.new(|cx| {
@@ -835,6 +851,27 @@ impl RunningState {
                 }
             }),
             cx.observe(&module_list, |_, _, cx| cx.notify()),
+            cx.subscribe_in(
+                &stack_frame_list,
+                window,
+                |this, stack_frame_list, event, window, cx| {
+                    if let StackFrameListEvent::SelectedStackFrameChanged(_) = event {
+                        // Frames without a source can only be shown as instructions.
+                        let has_only_instructions = stack_frame_list
+                            .read(cx)
+                            .opened_stack_frame()
+                            .is_some_and(|stack_frame| {
+                                StackFrameList::abs_path_from_stack_frame(stack_frame).is_none()
+                                    && stack_frame.instruction_pointer_reference.is_some()
+                            });
+                        if has_only_instructions
+                            && DebuggerPaneItem::Disassembly.is_supported(&this.capabilities(cx))
+                        {
+                            this.activate_item(DebuggerPaneItem::Disassembly, window, cx);
+                        }
+                    }
+                },
+            ),
             cx.subscribe_in(&session, window, |this, _, event, window, cx| {
                 match event {
                     SessionEvent::Stopped(thread_id) => {
@@ -850,6 +887,14 @@ impl RunningState {
                         if let Some(thread_id) = thread_id {
                             this.select_thread(*thread_id, window, cx);
                         }
+                        if let Some(exception_thread) = this.session.read(cx).exception_thread()
+                            && this
+                                .capabilities(cx)
+                                .supports_exception_info_request
+                                .unwrap_or_default()
+                        {
+                            this.show_exception_info(exception_thread, window, cx);
+                        }
                         if let Some(panel) = panel {
                             let id = this.session_id;
                             window.defer(cx, move |window, cx| {
@@ -912,6 +957,7 @@ impl RunningState {
                 &loaded_source_list,
                 &debug_terminal,
                 &memory_view,
+                &disassembly_view,
                 &mut pane_close_subscriptions,
                 window,
                 cx,
@@ -941,6 +987,7 @@ impl RunningState {
 
         Self {
             memory_view,
+            disassembly_view,
             session,
             workspace,
             project: weak_project,
@@ -1312,6 +1359,7 @@ impl RunningState {
             show_command: false,
             show_rerun: false,
             save: task::SaveStrategy::default(),
+            problem_matchers: Vec::new(),
         };
 
         let workspace = self.workspace.clone();
@@ -1415,6 +1463,14 @@ impl RunningState {
                 host_pane,
                 cx,
             )),
+            DebuggerPaneItem::Disassembly => Box::new(SubView::new(
+                self.disassembly_view.focus_handle(cx),
+                self.disassembly_view.clone().into(),
+                item_kind,
+                running_state,
+                host_pane,
+                cx,
+            )),
         }
     }
 
@@ -1611,6 +1667,10 @@ impl RunningState {
         &self.module_list
     }
 
+    pub(crate) fn disassembly_view(&self) -> &Entity<DisassemblyView> {
+        &self.disassembly_view
+    }
+
     pub(crate) fn activate_item(
         &mut self,
         item: DebuggerPaneItem,
@@ -1729,6 +1789,114 @@ impl RunningState {
         });
     }
 
+    /// Steps into one of the calls on the current line, asking which when there are several.
+    pub(crate) fn step_into_target(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(thread_id) = self.thread_id else {
+            return;
+        };
+        let Some(frame_id) = self.selected_stack_frame_id(cx) else {
+            return;
+        };
+
+        let granularity = DebuggerSettings::get_global(cx).stepping_granularity;
+        let session = self.session.clone();
+        let workspace = self.workspace.clone();
+        let targets = session.update(cx, |session, cx| session.step_in_targets(frame_id, cx));
+        cx.spawn_in(window, async move |_, cx| {
+            let mut targets = targets.await.unwrap_or_default();
+            if targets.len() > 1 {
+                workspace
+                    .update_in(cx, |workspace, window, cx| {
+                        workspace.toggle_modal(window, cx, |window, cx| {
+                            StepInTargetsModal::new(session, thread_id, targets, window, cx)
+                        });
+                    })
+                    .log_err();
+            } else if let Some(target) = targets.pop() {
+                session.update(cx, |session, cx| {
+                    session.step_in_target(thread_id, target.id, cx);
+                });
+            } else {
+                session.update(cx, |session, cx| {
+                    session.step_in(thread_id, granularity, cx);
+                });
+            }
+        })
+        .detach();
+    }
+
+    /// Moves execution to the zero-based `row` of `path`, without running the code in between.
+    pub(crate) fn set_next_statement(
+        &mut self,
+        path: Arc<Path>,
+        row: u32,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let Some(thread_id) = self.thread_id else {
+            return Task::ready(Err(anyhow!("No thread is selected")));
+        };
+
+        let session = self.session.clone();
+        let targets = session.update(cx, |session, cx| session.goto_targets(path, row, cx));
+        cx.spawn(async move |_, cx| {
+            let target = targets
+                .await
+                .and_then(|targets| targets.into_iter().next())
+                .context("Execution can't be moved to this line")?;
+            session.update(cx, |session, cx| session.goto(thread_id, target.id, cx));
+            Ok(())
+        })
+    }
+
+    /// Shows the details of the exception `thread_id` stopped on.
+    pub(crate) fn show_exception_info(
+        &mut self,
+        thread_id: ThreadId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let info = self
+            .session
+            .update(cx, |session, cx| session.exception_info(thread_id, cx));
+        let workspace = self.workspace.clone();
+        cx.spawn_in(window, async move |_, cx| {
+            let Some(info) = info.await else {
+                return;
+            };
+            workspace
+                .update_in(cx, |workspace, window, cx| {
+                    if let Some(modal) = workspace.active_modal::<ExceptionInfoModal>(cx) {
+                        modal.update(cx, |modal, cx| modal.set_info(info, cx));
+                    } else {
+                        workspace
+                            .toggle_modal(window, cx, |_, cx| ExceptionInfoModal::new(info, cx));
+                    }
+                })
+                .log_err();
+        })
+        .detach();
+    }
+
+    pub(crate) fn step_over_instruction(&mut self, cx: &mut Context<Self>) {
+        let Some(thread_id) = self.thread_id else {
+            return;
+        };
+
+        self.session().update(cx, |state, cx| {
+            state.step_over(thread_id, SteppingGranularity::Instruction, cx);
+        });
+    }
+
+    pub(crate) fn step_in_instruction(&mut self, cx: &mut Context<Self>) {
+        let Some(thread_id) = self.thread_id else {
+            return;
+        };
+
+        self.session().update(cx, |state, cx| {
+            state.step_in(thread_id, SteppingGranularity::Instruction, cx);
+        });
+    }
+
     pub(crate) fn step_out(&mut self, cx: &mut Context<Self>) {
         let Some(thread_id) = self.thread_id else {
             return;
@@ -0,0 +1,725 @@
+use std::{ops::Range, path::Path, sync::Arc};
+
+use collections::HashMap;
+use dap::{DisassembledInstruction, DisassembledInstructionPresentationHint};
+use gpui::{
+    AnyElement, Entity, FocusHandle, Focusable, ListHorizontalSizingBehavior, ScrollStrategy,
+    Subscription, Task, UniformListScrollHandle, WeakEntity, actions, uniform_list,
+};
+use project::debugger::session::{Session, SessionEvent};
+use ui::{Tooltip, WithScrollbar, prelude::*};
+use workspace::Workspace;
+
+use crate::{
+    StepIntoInstruction, StepOverInstruction,
+    session::running::stack_frame_list::{StackFrameList, StackFrameListEvent},
+};
+
+actions!(
+    debugger,
+    [
+        /// Toggles a breakpoint on the selected instruction in the disassembly view.
+        ToggleInstructionBreakpoint
+    ]
+);
+
+/// How many instructions are disassembled around the instruction pointer, and each time the
+/// list is scrolled to either end.
+const INSTRUCTIONS_PER_FETCH: u64 = 100;
+
+/// A row of the disassembly view.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum DisassemblyRow {
+    /// The line of source the instructions below it were compiled from.
+    Source {
+        path: Arc<Path>,
+        /// The one-based line number.
+        line: u64,
+    },
+    /// An index into the disassembled instructions.
+    Instruction(usize),
+}
+
+#[derive(Clone, Debug)]
+struct Instruction {
+    address: u64,
+    dap: DisassembledInstruction,
+}
+
+pub(crate) struct DisassemblyView {
+    session: Entity<Session>,
+    stack_frame_list: Entity<StackFrameList>,
+    workspace: WeakEntity<Workspace>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    /// The instructions disassembled so far, ordered by address.
+    instructions: Vec<Instruction>,
+    rows: Vec<DisassemblyRow>,
+    instruction_pointer: Option<u64>,
+    selected_ix: Option<usize>,
+    show_source: bool,
+    /// The lines of the source files shown, `None` for those that couldn't be read.
+    source_lines: HashMap<Arc<Path>, Option<Arc<[SharedString]>>>,
+    /// Whether earlier or later instructions can still be disassembled.
+    can_fetch_before: bool,
+    can_fetch_after: bool,
+    fetch_task: Option<Task<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl DisassemblyView {
+    pub(crate) fn new(
+        session: Entity<Session>,
+        stack_frame_list: Entity<StackFrameList>,
+        workspace: WeakEntity<Workspace>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let _subscriptions = vec![
+            cx.subscribe(&stack_frame_list, |this, _, event, cx| match event {
+                StackFrameListEvent::SelectedStackFrameChanged(_)
+                | StackFrameListEvent::BuiltEntries => this.sync_to_stack_frame(cx),
+            }),
+            cx.subscribe(&session, |this, _, event, cx| match event {
+                SessionEvent::Stopped(_) | SessionEvent::HistoricSnapshotSelected => {
+                    this.instruction_pointer = None;
+                    cx.notify();
+                }
+                _ => {}
+            }),
+            cx.observe(&session, |_, _, cx| cx.notify()),
+        ];
+
+        let mut this = Self {
+            session,
+            stack_frame_list,
+            workspace,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            instructions: Vec::new(),
+            rows: Vec::new(),
+            instruction_pointer: None,
+            selected_ix: None,
+            show_source: true,
+            source_lines: HashMap::default(),
+            can_fetch_before: true,
+            can_fetch_after: true,
+            fetch_task: None,
+            _subscriptions,
+        };
+        this.sync_to_stack_frame(cx);
+        this
+    }
+
+    /// Shows the instructions around the instruction pointer of the selected stack frame.
+    fn sync_to_stack_frame(&mut self, cx: &mut Context<Self>) {
+        let Some(memory_reference) = self
+            .stack_frame_list
+            .read(cx)
+            .opened_stack_frame()
+            .and_then(|stack_frame| stack_frame.instruction_pointer_reference.clone())
+        else {
+            return;
+        };
+        let Some(address) = parse_address(&memory_reference) else {
+            return;
+        };
+        if self.instruction_pointer == Some(address) && self.fetch_task.is_none() {
+            return;
+        }
+        self.instruction_pointer = Some(address);
+
+        if self.instruction_ix(address).is_some() {
+            self.reveal_instruction_pointer();
+            cx.notify();
+            return;
+        }
+
+        let disassemble = self.session.update(cx, |session, cx| {
+            session.disassemble(
+                memory_reference,
+                -(INSTRUCTIONS_PER_FETCH as i64 / 2),
+                INSTRUCTIONS_PER_FETCH,
+                cx,
+            )
+        });
+        self.fetch_task = Some(cx.spawn(async move |this, cx| {
+            let instructions = disassemble.await.unwrap_or_default();
+            this.update(cx, |this, cx| {
+                this.instructions = parse_instructions(instructions);
+                this.can_fetch_before = true;
+                this.can_fetch_after = true;
+                this.selected_ix = None;
+                this.rebuild_rows(cx);
+                this.reveal_instruction_pointer();
+                this.fetch_task = None;
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    /// Disassembles the instructions before the first one shown, or after the last one.
+    fn fetch_more(&mut self, before: bool, cx: &mut Context<Self>) {
+        if self.fetch_task.is_some() {
+            return;
+        }
+        let edge = if before {
+            self.instructions.first()
+        } else {
+            self.instructions.last()
+        };
+        let Some(edge) = edge else {
+            return;
+        };
+        let memory_reference = format!("0x{:X}", edge.address);
+        let instruction_offset = if before {
+            -(INSTRUCTIONS_PER_FETCH as i64)
+        } else {
+            1
+        };
+        let disassemble = self.session.update(cx, |session, cx| {
+            session.disassemble(
+                memory_reference,
+                instruction_offset,
+                INSTRUCTIONS_PER_FETCH,
+                cx,
+            )
+        });
+        self.fetch_task = Some(cx.spawn(async move |this, cx| {
+            let fetched = parse_instructions(disassemble.await.unwrap_or_default());
+            this.update(cx, |this, cx| {
+                this.fetch_task = None;
+                let added = if before {
+                    let first = this.instructions.first().map(|i| i.address);
+                    let fetched = fetched
+                        .into_iter()
+                        .filter(|instruction| Some(instruction.address) < first)
+                        .collect::<Vec<_>>();
+                    let added = fetched.len();
+                    this.instructions.splice(0..0, fetched);
+                    this.can_fetch_before = added > 0;
+                    added
+                } else {
+                    let last = this.instructions.last().map(|i| i.address);
+                    let fetched = fetched
+                        .into_iter()
+                        .filter(|instruction| Some(instruction.address) > last)
+                        .collect::<Vec<_>>();
+                    let added = fetched.len();
+                    this.instructions.extend(fetched);
+                    this.can_fetch_after = added > 0;
+                    added
+                };
+                if added > 0 {
+                    let previous_row_count = this.rows.len();
+                    this.rebuild_rows(cx);
+                    if before {
+                        // Keep the rows that were shown in place.
+                        let inserted = this.rows.len() - previous_row_count;
+                        this.selected_ix = this.selected_ix.map(|ix| ix + inserted);
+                        this.scroll_handle
+                            .scroll_to_item_strict(inserted, ScrollStrategy::Top);
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    fn rebuild_rows(&mut self, cx: &mut Context<Self>) {
+        self.rows = interleave_source(
+            self.instructions.iter().map(|instruction| &instruction.dap),
+            self.show_source,
+        );
+        if self.show_source {
+            self.load_source_lines(cx);
+        }
+    }
+
+    /// Reads the source files the instructions were compiled from, to show their lines.
+    fn load_source_lines(&mut self, cx: &mut Context<Self>) {
+        let Some(project) = self
+            .workspace
+            .read_with(cx, |workspace, _| workspace.project().clone())
+            .ok()
+        else {
+            return;
+        };
+        if !project.read(cx).is_local() {
+            return;
+        }
+        let fs = project.read(cx).fs().clone();
+        for row in &self.rows {
+            let DisassemblyRow::Source { path, .. } = row else {
+                continue;
+            };
+            if self.source_lines.contains_key(path) {
+                continue;
+            }
+            self.source_lines.insert(path.clone(), None);
+            let path = path.clone();
+            let fs = fs.clone();
+            cx.spawn(async move |this, cx| {
+                let Ok(text) = fs.load(&path).await else {
+                    return;
+                };
+                let lines = text
+                    .lines()
+                    .map(|line| SharedString::from(line.trim_end().to_string()))
+                    .collect::<Arc<[_]>>();
+                this.update(cx, |this, cx| {
+                    this.source_lines.insert(path, Some(lines));
+                    cx.notify();
+                })
+                .ok();
+            })
+            .detach();
+        }
+    }
+
+    fn instruction_ix(&self, address: u64) -> Option<usize> {
+        self.instructions
+            .binary_search_by_key(&address, |instruction| instruction.address)
+            .ok()
+    }
+
+    fn reveal_instruction_pointer(&mut self) {
+        let Some(instruction_ix) = self
+            .instruction_pointer
+            .and_then(|address| self.instruction_ix(address))
+        else {
+            return;
+        };
+        if let Some(row_ix) = self
+            .rows
+            .iter()
+            .position(|row| *row == DisassemblyRow::Instruction(instruction_ix))
+        {
+            self.scroll_handle
+                .scroll_to_item(row_ix, ScrollStrategy::Center);
+        }
+    }
+
+    fn toggle_source(&mut self, cx: &mut Context<Self>) {
+        self.show_source = !self.show_source;
+        let selected_row = self.selected_ix.and_then(|ix| self.rows.get(ix).cloned());
+        self.rebuild_rows(cx);
+        self.selected_ix = selected_row.and_then(|row| self.rows.iter().position(|r| *r == row));
+        self.reveal_instruction_pointer();
+        cx.notify();
+    }
+
+    fn toggle_instruction_breakpoint(
+        &mut self,
+        _: &ToggleInstructionBreakpoint,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(DisassemblyRow::Instruction(instruction_ix)) =
+            self.selected_ix.and_then(|ix| self.rows.get(ix))
+        else {
+            return;
+        };
+        let address = self.instructions[*instruction_ix].address;
+        self.toggle_breakpoint_at(address, cx);
+    }
+
+    fn toggle_breakpoint_at(&mut self, address: u64, cx: &mut Context<Self>) {
+        if !self
+            .session
+            .read(cx)
+            .capabilities()
+            .supports_instruction_breakpoints
+            .unwrap_or_default()
+        {
+            return;
+        }
+        self.session.update(cx, |session, cx| {
+            session.toggle_instruction_breakpoint(address, cx);
+        });
+        cx.notify();
+    }
+
+    fn select_ix(&mut self, ix: Option<usize>, cx: &mut Context<Self>) {
+        self.selected_ix = ix;
+        if let Some(ix) = ix {
+            self.scroll_handle
+                .scroll_to_item(ix, ScrollStrategy::Center);
+        }
+        cx.notify();
+    }
+
+    fn select_next(&mut self, _: &menu::SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        let ix = match self.selected_ix {
+            _ if self.rows.is_empty() => None,
+            None => Some(0),
+            Some(ix) => Some((ix + 1).min(self.rows.len() - 1)),
+        };
+        self.select_ix(ix, cx);
+    }
+
+    fn select_previous(
+        &mut self,
+        _: &menu::SelectPrevious,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let ix = match self.selected_ix {
+            _ if self.rows.is_empty() => None,
+            None => Some(self.rows.len() - 1),
+            Some(ix) => Some(ix.saturating_sub(1)),
+        };
+        self.select_ix(ix, cx);
+    }
+
+    #[cfg(test)]
+    pub(crate) fn rows(&self) -> Vec<String> {
+        self.rows
+            .iter()
+            .map(|row| match row {
+                DisassemblyRow::Source { path, line } => {
+                    format!("{}:{line}", path.display())
+                }
+                DisassemblyRow::Instruction(ix) => {
+                    let instruction = &self.instructions[*ix];
+                    let marker = if Some(instruction.address) == self.instruction_pointer {
+                        "> "
+                    } else {
+                        "  "
+                    };
+                    format!(
+                        "{marker}{} {}",
+                        instruction.dap.address, instruction.dap.instruction
+                    )
+                }
+            })
+            .collect()
+    }
+
+    fn render_row(&self, ix: usize, cx: &mut Context<Self>) -> AnyElement {
+        let is_selected = self.selected_ix == Some(ix);
+        match &self.rows[ix] {
+            DisassemblyRow::Source { path, line } => {
+                let text = self
+                    .source_lines
+                    .get(path)
+                    .and_then(|lines| lines.as_ref()?.get(line.checked_sub(1)? as usize).cloned());
+                let location = format!(
+                    "{}:{line}",
+                    path.file_name()
+                        .map(|name| name.to_string_lossy())
+                        .unwrap_or_else(|| path.to_string_lossy())
+                );
+                h_flex()
+                    .id(("disassembly-source", ix))
+                    .w_full()
+                    .gap_2()
+                    .pl_6()
+                    .pt_1()
+                    .when(is_selected, |this| {
+                        this.bg(cx.theme().colors().element_selected)
+                    })
+                    .on_click(cx.listener(move |this, _, _, cx| this.select_ix(Some(ix), cx)))
+                    .child(
+                        Label::new(location)
+                            .size(LabelSize::Small)
+                            .color(Color::Accent),
+                    )
+                    .when_some(text, |this, text| {
+                        this.child(
+                            Label::new(text)
+                                .buffer_font(cx)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .single_line(),
+                        )
+                    })
+                    .into_any_element()
+            }
+            DisassemblyRow::Instruction(instruction_ix) => {
+                let instruction = &self.instructions[*instruction_ix];
+                let address = instruction.address;
+                let is_instruction_pointer = self.instruction_pointer == Some(address);
+                let has_breakpoint = self.session.read(cx).has_instruction_breakpoint(address);
+                let is_invalid = instruction.dap.presentation_hint
+                    == Some(DisassembledInstructionPresentationHint::Invalid);
+
+                h_flex()
+                    .id(("disassembly-instruction", ix))
+                    .w_full()
+                    .gap_2()
+                    .group("disassembly-instruction")
+                    .when(is_instruction_pointer, |this| {
+                        this.bg(cx.theme().colors().editor_debugger_active_line_background)
+                    })
+                    .when(is_selected, |this| {
+                        this.bg(cx.theme().colors().element_selected)
+                    })
+                    .on_click(cx.listener(move |this, _, _, cx| this.select_ix(Some(ix), cx)))
+                    .child(
+                        div()
+                            .id(("disassembly-breakpoint", ix))
+                            .w_4()
+                            .flex_none()
+                            .cursor_pointer()
+                            .when(!has_breakpoint, |this| {
+                                this.visible_on_hover("disassembly-instruction")
+                            })
+                            .child(
+                                Icon::new(IconName::DebugBreakpoint)
+                                    .size(IconSize::XSmall)
+                                    .color(if has_breakpoint {
+                                        Color::Debugger
+                                    } else {
+                                        Color::Muted
+                                    }),
+                            )
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                cx.stop_propagation();
+                                this.toggle_breakpoint_at(address, cx);
+                            }))
+                            .tooltip(Tooltip::text("Toggle Instruction Breakpoint")),
+                    )
+                    .child(
+                        div()
+                            .w_3()
+                            .flex_none()
+                            .when(is_instruction_pointer, |this| {
+                                this.child(
+                                    Icon::new(IconName::ArrowRight)
+                                        .size(IconSize::XSmall)
+                                        .color(Color::Debugger),
+                                )
+                            }),
+                    )
+                    .child(
+                        Label::new(instruction.dap.address.clone())
+                            .buffer_font(cx)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .when_some(instruction.dap.instruction_bytes.clone(), |this, bytes| {
+                        this.child(
+                            div().w_40().flex_none().child(
+                                Label::new(bytes)
+                                    .buffer_font(cx)
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted)
+                                    .single_line(),
+                            ),
+                        )
+                    })
+                    .child(
+                        Label::new(instruction.dap.instruction.clone())
+                            .buffer_font(cx)
+                            .size(LabelSize::Small)
+                            .when(is_invalid, |this| this.color(Color::Muted)),
+                    )
+                    .when_some(instruction.dap.symbol.clone(), |this, symbol| {
+                        this.child(
+                            Label::new(format!("<{symbol}>"))
+                                .buffer_font(cx)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    })
+                    .into_any_element()
+            }
+        }
+    }
+
+    fn render_toolbar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let session = self.session.read(cx);
+        let supports_stepping_granularity = session
+            .capabilities()
+            .supports_stepping_granularity
+            .unwrap_or_default();
+        let is_stopped = self
+            .stack_frame_list
+            .read(cx)
+            .opened_stack_frame()
+            .is_some()
+            && !session.is_terminated();
+        let can_step = supports_stepping_granularity && is_stopped;
+        let focus_handle = self.focus_handle.clone();
+
+        h_flex()
+            .w_full()
+            .gap_1()
+            .px_1()
+            .pb_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                IconButton::new("disassembly-step-over", IconName::DebugStepOver)
+                    .icon_size(IconSize::Small)
+                    .disabled(!can_step)
+                    .on_click(|_, window, cx| {
+                        window.dispatch_action(StepOverInstruction.boxed_clone(), cx)
+                    })
+                    .tooltip({
+                        let focus_handle = focus_handle.clone();
+                        move |_window, cx| {
+                            Tooltip::for_action_in(
+                                "Step Over Instruction",
+                                &StepOverInstruction,
+                                &focus_handle,
+                                cx,
+                            )
+                        }
+                    }),
+            )
+            .child(
+                IconButton::new("disassembly-step-into", IconName::DebugStepInto)
+                    .icon_size(IconSize::Small)
+                    .disabled(!can_step)
+                    .on_click(|_, window, cx| {
+                        window.dispatch_action(StepIntoInstruction.boxed_clone(), cx)
+                    })
+                    .tooltip(move |_window, cx| {
+                        Tooltip::for_action_in(
+                            "Step Into Instruction",
+                            &StepIntoInstruction,
+                            &focus_handle,
+                            cx,
+                        )
+                    }),
+            )
+            .child(div().flex_1())
+            .child(
+                IconButton::new("disassembly-toggle-source", IconName::FileCode)
+                    .icon_size(IconSize::Small)
+                    .toggle_state(self.show_source)
+                    .on_click(cx.listener(|this, _, _, cx| this.toggle_source(cx)))
+                    .tooltip(Tooltip::text("Show Source Lines")),
+            )
+    }
+
+    fn render_list(&mut self, cx: &mut Context<Self>) -> impl IntoElement {
+        uniform_list(
+            "disassembly-view",
+            self.rows.len(),
+            cx.processor(|this, range: Range<usize>, _window, cx| {
+                if range.start == 0 && this.can_fetch_before {
+                    this.fetch_more(true, cx);
+                } else if range.end == this.rows.len() && this.can_fetch_after {
+                    this.fetch_more(false, cx);
+                }
+                range.map(|ix| this.render_row(ix, cx)).collect()
+            }),
+        )
+        .track_scroll(&self.scroll_handle)
+        .with_horizontal_sizing_behavior(ListHorizontalSizingBehavior::Unconstrained)
+        .size_full()
+    }
+}
+
+impl Focusable for DisassemblyView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for DisassemblyView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let is_running = self
+            .stack_frame_list
+            .read(cx)
+            .opened_stack_frame()
+            .is_none();
+
+        v_flex()
+            .track_focus(&self.focus_handle)
+            .key_context("DisassemblyView")
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::toggle_instruction_breakpoint))
+            .size_full()
+            .p_1()
+            .child(self.render_toolbar(cx))
+            .map(|this| {
+                if self.rows.is_empty() {
+                    this.child(
+                        h_flex().size_full().justify_center().child(
+                            Label::new(if is_running {
+                                "Pause the program to see its instructions"
+                            } else {
+                                "No instructions to show for this stack frame"
+                            })
+                            .color(Color::Muted),
+                        ),
+                    )
+                } else {
+                    this.child(
+                        div()
+                            .size_full()
+                            .child(self.render_list(cx))
+                            .vertical_scrollbar_for(&self.scroll_handle, window, cx),
+                    )
+                }
+            })
+    }
+}
+
+/// Parses a memory reference, which adapters send as a hex or decimal address.
+pub(crate) fn parse_address(memory_reference: &str) -> Option<u64> {
+    let memory_reference = memory_reference.trim();
+    match memory_reference
+        .strip_prefix("0x")
+        .or_else(|| memory_reference.strip_prefix("0X"))
+    {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => memory_reference.parse().ok(),
+    }
+}
+
+fn parse_instructions(instructions: Vec<DisassembledInstruction>) -> Vec<Instruction> {
+    let mut instructions = instructions
+        .into_iter()
+        .filter_map(|dap| {
+            Some(Instruction {
+                address: parse_address(&dap.address)?,
+                dap,
+            })
+        })
+        .collect::<Vec<_>>();
+    instructions.sort_by_key(|instruction| instruction.address);
+    instructions.dedup_by_key(|instruction| instruction.address);
+    instructions
+}
+
+/// Lays out the instructions, preceding each run of instructions compiled from the same line of
+/// source with that line. Adapters omit the location of an instruction when it's the same as the
+/// one before.
+pub(crate) fn interleave_source<'a>(
+    instructions: impl IntoIterator<Item = &'a DisassembledInstruction>,
+    show_source: bool,
+) -> Vec<DisassemblyRow> {
+    let mut rows = Vec::new();
+    let mut current_path: Option<Arc<Path>> = None;
+    let mut current_line = None;
+    for (ix, instruction) in instructions.into_iter().enumerate() {
+        if show_source {
+            if let Some(path) = instruction
+                .location
+                .as_ref()
+                .and_then(|source| source.path.as_deref())
+                && current_path.as_deref() != Some(Path::new(path))
+            {
+                current_path = Some(Arc::from(Path::new(path)));
+                current_line = None;
+            }
+            if let Some(line) = instruction.line
+                && current_line != Some(line)
+            {
+                current_line = Some(line);
+                if let Some(path) = current_path.clone() {
+                    rows.push(DisassemblyRow::Source { path, line });
+                }
+            }
+        }
+        rows.push(DisassemblyRow::Instruction(ix));
+    }
+    rows
+}
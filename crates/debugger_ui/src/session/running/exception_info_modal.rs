@@ -0,0 +1,157 @@
+use dap::{ExceptionBreakMode, ExceptionDetails, ExceptionInfoResponse};
+use gpui::{DismissEvent, EventEmitter, FocusHandle, Focusable, FontWeight};
+use ui::prelude::*;
+use workspace::ModalView;
+
+/// Shows the details of the exception a thread stopped on.
+pub(crate) struct ExceptionInfoModal {
+    info: ExceptionInfoResponse,
+    focus_handle: FocusHandle,
+}
+
+impl ExceptionInfoModal {
+    pub(crate) fn new(info: ExceptionInfoResponse, cx: &mut Context<Self>) -> Self {
+        Self {
+            info,
+            focus_handle: cx.focus_handle(),
+        }
+    }
+
+    pub(crate) fn set_info(&mut self, info: ExceptionInfoResponse, cx: &mut Context<Self>) {
+        self.info = info;
+        cx.notify();
+    }
+
+    #[cfg(test)]
+    pub(crate) fn info(&self) -> &ExceptionInfoResponse {
+        &self.info
+    }
+
+    fn dismiss(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_details(
+        details: &ExceptionDetails,
+        depth: usize,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let type_name = details
+            .full_type_name
+            .clone()
+            .or_else(|| details.type_name.clone());
+
+        v_flex()
+            .gap_1()
+            .when(depth > 0, |this| {
+                this.pl_3()
+                    .border_l_1()
+                    .border_color(cx.theme().colors().border_variant)
+            })
+            .when_some(type_name, |this, type_name| {
+                this.child(
+                    Label::new(type_name)
+                        .buffer_font(cx)
+                        .weight(FontWeight::SEMIBOLD),
+                )
+            })
+            .when_some(details.message.clone(), |this, message| {
+                this.child(Label::new(message))
+            })
+            .when_some(details.stack_trace.clone(), |this, stack_trace| {
+                this.child(
+                    div()
+                        .id(("exception-stack-trace", depth))
+                        .max_h(rems(16.))
+                        .overflow_y_scroll()
+                        .p_1()
+                        .rounded_sm()
+                        .bg(cx.theme().colors().editor_background)
+                        .child(
+                            Label::new(stack_trace)
+                                .buffer_font(cx)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                )
+            })
+            .children(
+                details
+                    .inner_exception
+                    .iter()
+                    .flatten()
+                    .map(|inner| {
+                        v_flex()
+                            .gap_1()
+                            .child(
+                                Label::new("Inner Exception")
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                            .child(Self::render_details(inner, depth + 1, cx))
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .into_any_element()
+    }
+}
+
+fn break_mode_label(break_mode: &ExceptionBreakMode) -> &'static str {
+    match break_mode {
+        ExceptionBreakMode::Never => "Never breaks",
+        ExceptionBreakMode::Always => "Always breaks",
+        ExceptionBreakMode::Unhandled => "Breaks when unhandled",
+        ExceptionBreakMode::UserUnhandled => "Breaks when unhandled by user code",
+    }
+}
+
+impl EventEmitter<DismissEvent> for ExceptionInfoModal {}
+impl ModalView for ExceptionInfoModal {}
+
+impl Focusable for ExceptionInfoModal {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for ExceptionInfoModal {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let details = self.info.details.clone();
+
+        v_flex()
+            .key_context("ExceptionInfoModal")
+            .on_action(cx.listener(Self::dismiss))
+            .track_focus(&self.focus_handle)
+            .elevation_3(cx)
+            .w(rems(34.))
+            .p_4()
+            .gap_3()
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Icon::new(IconName::Warning)
+                            .size(IconSize::Small)
+                            .color(Color::Error),
+                    )
+                    .child(Label::new("Exception Thrown").size(LabelSize::Large))
+                    .child(div().flex_1())
+                    .child(
+                        Label::new(break_mode_label(&self.info.break_mode))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+            )
+            .child(
+                Label::new(self.info.exception_id.clone())
+                    .buffer_font(cx)
+                    .color(Color::Error),
+            )
+            .when_some(self.info.description.clone(), |this, description| {
+                this.child(Label::new(description))
+            })
+            .when_some(details, |this, details| {
+                this.child(Self::render_details(&details, 0, cx))
+            })
+    }
+}
@@ -1,21 +1,27 @@
 use std::{
     cell::{LazyCell, RefCell, RefMut},
     fmt::Write,
-    ops::RangeInclusive,
+    ops::{Range, RangeInclusive},
     rc::Rc,
     sync::{Arc, LazyLock},
     time::Duration,
 };
 
+use anyhow::Result;
 use editor::{Editor, EditorElement, EditorStyle};
 use gpui::{
     Action, Along, AppContext, Axis, DismissEvent, DragMoveEvent, Empty, Entity, FocusHandle,
     Focusable, ListHorizontalSizingBehavior, MouseButton, Point, ScrollStrategy, ScrollWheelEvent,
-    Subscription, Task, TextStyle, UniformList, UniformListScrollHandle, WeakEntity, actions,
-    anchored, deferred, uniform_list,
+    Subscription, Task, TaskExt, TextStyle, UniformList, UniformListScrollHandle, WeakEntity,
+    actions, anchored, deferred, uniform_list,
 };
+use hex_editor::{ByteSource, HexDocument, HexEditor};
 use notifications::status_toast::StatusToast;
-use project::debugger::{MemoryCell, dap_command::DataBreakpointContext, session::Session};
+use project::debugger::{
+    MemoryCell,
+    dap_command::DataBreakpointContext,
+    session::{Session, SessionEvent},
+};
 use settings::Settings;
 use theme_settings::ThemeSettings;
 use ui::{
@@ -26,7 +32,7 @@ use workspace::Workspace;
 
 use crate::{ToggleDataBreakpoint, session::running::stack_frame_list::StackFrameList};
 
-actions!(debugger, [GoToSelectedAddress]);
+actions!(debugger, [GoToSelectedAddress, OpenMemoryInHexEditor]);
 
 pub(crate) struct MemoryView {
     workspace: WeakEntity<Workspace>,
@@ -505,7 +511,8 @@ impl MemoryView {
                     let range = drag.memory_range();
 
                     if let Ok(as_hex) = hex::decode(text) {
-                        this.write_memory(*range.start(), &as_hex, cx);
+                        this.write_memory(*range.start(), &as_hex, cx)
+                            .detach_and_log_err(cx);
                     }
                 });
                 self.change_query_bar_mode(false, window, cx);
@@ -608,6 +615,45 @@ impl MemoryView {
         self.jump_to_query_bar_address(cx);
     }
 
+    /// Open the memory at the selection, or at the top of the view, in a hex editor tab.
+    fn open_in_hex_editor(
+        &mut self,
+        _: &OpenMemoryInHexEditor,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let address = {
+            let view_state = self.view_state();
+            match &view_state.selection {
+                Some(SelectedMemoryRange::DragComplete(drag)) => *drag.memory_range().start(),
+                _ => view_state.base_row * view_state.line_width.width as u64,
+            }
+        };
+        let session = self.session.clone();
+        let title = match session.read(cx).label() {
+            Some(label) => format!("Memory: {label}").into(),
+            None => "Memory".into(),
+        };
+        let source = Rc::new(SessionMemorySource {
+            session: session.clone(),
+            title,
+        });
+        _ = self.workspace.update(cx, |workspace, cx| {
+            let hex_editor = cx.new(|cx| {
+                cx.subscribe(&session, |hex_editor: &mut HexEditor, _, event, cx| {
+                    if matches!(event, SessionEvent::Stopped(_) | SessionEvent::Variables) {
+                        hex_editor.reload_bytes(cx);
+                    }
+                })
+                .detach();
+                let mut hex_editor = HexEditor::new(source, window, cx);
+                hex_editor.go_to_offset(address, cx);
+                hex_editor
+            });
+            workspace.add_item_to_active_pane(Box::new(hex_editor), None, true, window, cx);
+        });
+    }
+
     fn deploy_memory_context_menu(
         &mut self,
         range: RangeInclusive<u64>,
@@ -633,6 +679,7 @@ impl MemoryView {
                 "Go To Selected Address",
                 GoToSelectedAddress.boxed_clone(),
             );
+            menu = menu.action("Open in Hex Editor", OpenMemoryInHexEditor.boxed_clone());
 
             if supports_data_breakpoints {
                 menu = menu.action_disabled_when(
@@ -663,6 +710,60 @@ impl MemoryView {
     }
 }
 
+/// The address space of a debuggee, edited by overwriting bytes in place.
+struct SessionMemorySource {
+    session: Entity<Session>,
+    title: SharedString,
+}
+
+impl ByteSource for SessionMemorySource {
+    fn title(&self) -> SharedString {
+        self.title.clone()
+    }
+
+    fn len(&self) -> Option<u64> {
+        None
+    }
+
+    fn read(&self, range: Range<u64>, cx: &mut App) -> Task<Result<Vec<Option<u8>>>> {
+        let cells = self.session.update(cx, |session, cx| {
+            session.read_memory_bytes(range.start, range.end - range.start, cx)
+        });
+        cx.background_spawn(async move { Ok(cells.await.into_iter().map(|cell| cell.0).collect()) })
+    }
+
+    fn can_resize(&self) -> bool {
+        false
+    }
+
+    fn save(&self, document: &HexDocument, cx: &mut App) -> Task<Result<u64>> {
+        let supports_write_memory = self
+            .session
+            .read(cx)
+            .capabilities()
+            .supports_write_memory_request
+            .unwrap_or_default();
+        if !supports_write_memory {
+            let adapter_name = self.session.read(cx).adapter();
+            return Task::ready(Err(anyhow::anyhow!(
+                "Debug Adapter `{adapter_name}` does not support writing to memory"
+            )));
+        }
+        let writes = self.session.update(cx, |session, cx| {
+            document
+                .patches()
+                .into_iter()
+                .map(|(address, bytes)| session.write_memory(address, &bytes, cx))
+                .collect::<Vec<_>>()
+        });
+        let len = document.len();
+        cx.background_spawn(async move {
+            futures::future::try_join_all(writes).await?;
+            Ok(len)
+        })
+    }
+}
+
 #[derive(Clone)]
 struct ViewWidth {
     width: u8,
@@ -863,6 +964,7 @@ impl Render for MemoryView {
             .id("Memory-view")
             .on_action(cx.listener(Self::cancel))
             .on_action(cx.listener(Self::go_to_address))
+            .on_action(cx.listener(Self::open_in_hex_editor))
             .p_1()
             .on_action(cx.listener(Self::confirm))
             .on_action(cx.listener(Self::toggle_data_breakpoint))
@@ -219,6 +219,22 @@ impl StackFrameList {
         self.opened_stack_frame_id
     }
 
+    /// The stack frame last opened, whether or not it has a source.
+    pub(crate) fn opened_stack_frame(&self) -> Option<&dap::StackFrame> {
+        self.stack_frame(self.opened_stack_frame_id?)
+    }
+
+    fn stack_frame(&self, stack_frame_id: StackFrameId) -> Option<&dap::StackFrame> {
+        self.entries
+            .iter()
+            .flat_map(|entry| match entry {
+                StackFrameEntry::Label(stack_frame) => std::slice::from_ref(stack_frame),
+                StackFrameEntry::Normal(stack_frame) => std::slice::from_ref(stack_frame),
+                StackFrameEntry::Collapsed(stack_frames) => stack_frames.as_slice(),
+            })
+            .find(|stack_frame| stack_frame.id == stack_frame_id)
+    }
+
     pub(super) fn schedule_refresh(
         &mut self,
         select_first: bool,
@@ -370,17 +386,7 @@ impl StackFrameList {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Task<Result<()>> {
-        let Some(stack_frame) = self
-            .entries
-            .iter()
-            .flat_map(|entry| match entry {
-                StackFrameEntry::Label(stack_frame) => std::slice::from_ref(stack_frame),
-                StackFrameEntry::Normal(stack_frame) => std::slice::from_ref(stack_frame),
-                StackFrameEntry::Collapsed(stack_frames) => stack_frames.as_slice(),
-            })
-            .find(|stack_frame| stack_frame.id == stack_frame_id)
-            .cloned()
-        else {
+        let Some(stack_frame) = self.stack_frame(stack_frame_id).cloned() else {
             return Task::ready(Err(anyhow!("No stack frame for ID")));
         };
         self.go_to_stack_frame_inner(stack_frame, window, cx)
@@ -394,13 +400,14 @@ impl StackFrameList {
     ) -> Task<Result<()>> {
         let stack_frame_id = stack_frame.id;
         self.opened_stack_frame_id = Some(stack_frame_id);
+        // Frames without a source are still selected, so that they can be shown as disassembly.
+        cx.emit(StackFrameListEvent::SelectedStackFrameChanged(
+            stack_frame_id,
+        ));
         let Some(abs_path) = Self::abs_path_from_stack_frame(&stack_frame) else {
             return Task::ready(Err(anyhow!("Project path not found")));
         };
         let row = stack_frame.line.saturating_sub(1) as u32;
-        cx.emit(StackFrameListEvent::SelectedStackFrameChanged(
-            stack_frame_id,
-        ));
         cx.spawn_in(window, async move |this, cx| {
             let (worktree, relative_path) = this
                 .update(cx, |this, cx| {
@@ -0,0 +1,180 @@
+use std::sync::Arc;
+
+use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{DismissEvent, Entity, EventEmitter, Focusable, Subscription, Task};
+use picker::{Picker, PickerDelegate};
+use project::debugger::session::{Session, ThreadId};
+use ui::{ListItem, ListItemSpacing, prelude::*};
+use workspace::ModalView;
+
+/// Lets the user pick which of the calls on the current line to step into.
+pub(crate) struct StepInTargetsModal {
+    picker: Entity<Picker<StepInTargetsDelegate>>,
+    _subscription: Subscription,
+}
+
+impl StepInTargetsModal {
+    pub(crate) fn new(
+        session: Entity<Session>,
+        thread_id: ThreadId,
+        targets: Vec<dap::StepInTarget>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = StepInTargetsDelegate {
+            session,
+            thread_id,
+            targets: targets.into(),
+            matches: Vec::new(),
+            selected_index: 0,
+        };
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        Self {
+            _subscription: cx.subscribe(&picker, |_, _, _, cx| cx.emit(DismissEvent)),
+            picker,
+        }
+    }
+}
+
+impl Render for StepInTargetsModal {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("StepInTargetsModal")
+            .track_focus(&self.focus_handle(cx))
+            .w(rems(34.))
+            .child(self.picker.clone())
+    }
+}
+
+impl EventEmitter<DismissEvent> for StepInTargetsModal {}
+
+impl Focusable for StepInTargetsModal {
+    fn focus_handle(&self, cx: &App) -> gpui::FocusHandle {
+        self.picker.read(cx).focus_handle(cx)
+    }
+}
+
+impl ModalView for StepInTargetsModal {}
+
+pub(crate) struct StepInTargetsDelegate {
+    session: Entity<Session>,
+    thread_id: ThreadId,
+    targets: Arc<[dap::StepInTarget]>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl PickerDelegate for StepInTargetsDelegate {
+    type ListItem = ListItem;
+
+    fn name() -> &'static str {
+        "step in targets"
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut Window, _: &mut Context<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Select the call to step into".into()
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let candidates = self
+            .targets
+            .iter()
+            .enumerate()
+            .map(|(id, target)| StringMatchCandidate::new(id, &target.label))
+            .collect::<Vec<_>>();
+        cx.spawn(async move |this, cx| {
+            let matches = fuzzy::match_strings(
+                &candidates,
+                &query,
+                true,
+                true,
+                100,
+                &Default::default(),
+                cx.background_executor().clone(),
+            )
+            .await;
+
+            this.update(cx, |this, _| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+            })
+            .ok();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(target) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|string_match| self.targets.get(string_match.candidate_id))
+        else {
+            return;
+        };
+
+        let (thread_id, target_id) = (self.thread_id, target.id);
+        self.session.update(cx, |session, cx| {
+            session.step_in_target(thread_id, target_id, cx);
+        });
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let string_match = self.matches.get(ix)?;
+        let target = self.targets.get(string_match.candidate_id)?;
+        let location = target.line.map(|line| match target.column {
+            Some(column) => format!("{line}:{column}"),
+            None => line.to_string(),
+        });
+
+        Some(
+            ListItem::new(("step-in-target", ix))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(
+                    h_flex()
+                        .w_full()
+                        .gap_2()
+                        .justify_between()
+                        .child(Label::new(target.label.clone()).single_line())
+                        .when_some(location, |this, location| {
+                            this.child(
+                                Label::new(location)
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                        }),
+                ),
+        )
+    }
+}
@@ -0,0 +1,210 @@
+use crate::{
+    persistence::DebuggerPaneItem,
+    tests::{active_debug_session_panel, init_test, init_test_workspace, start_debug_session},
+};
+use dap::{
+    DisassembledInstruction, StackFrame, StoppedEvent,
+    requests::{
+        Disassemble, Initialize, Next, Scopes, SetInstructionBreakpoints, StackTrace, Threads,
+    },
+};
+use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
+use parking_lot::Mutex;
+use project::{FakeFs, Project};
+use std::sync::Arc;
+use util::path;
+
+fn instruction(
+    address: &str,
+    instruction: &str,
+    location: Option<&str>,
+    line: Option<u64>,
+) -> DisassembledInstruction {
+    DisassembledInstruction {
+        address: address.into(),
+        instruction_bytes: None,
+        instruction: instruction.into(),
+        symbol: None,
+        location: location.map(|path| dap::Source {
+            name: None,
+            path: Some(path.into()),
+            source_reference: None,
+            presentation_hint: None,
+            origin: None,
+            sources: None,
+            adapter_data: None,
+            checksums: None,
+        }),
+        line,
+        column: None,
+        end_line: None,
+        end_column: None,
+        presentation_hint: None,
+    }
+}
+
+#[gpui::test]
+async fn test_disassembly_view(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |client| {
+        client.on_request::<Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_disassemble_request: Some(true),
+                supports_instruction_breakpoints: Some(true),
+                supports_stepping_granularity: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![dap::Thread {
+                id: 1,
+                name: "Thread 1".into(),
+            }],
+        })
+    });
+    client.on_request::<Scopes, _>(move |_, _| Ok(dap::ScopesResponse { scopes: vec![] }));
+    client.on_request::<StackTrace, _>(move |_, _| {
+        Ok(dap::StackTraceResponse {
+            stack_frames: vec![StackFrame {
+                id: 1,
+                name: "main".into(),
+                source: None,
+                line: 0,
+                column: 0,
+                end_line: None,
+                end_column: None,
+                can_restart: None,
+                instruction_pointer_reference: Some("0x1008".into()),
+                module_id: None,
+                presentation_hint: None,
+            }],
+            total_frames: None,
+        })
+    });
+
+    let main_path = path!("/project/src/main.c");
+    let instructions = vec![
+        instruction("0x1000", "push rbp", Some(main_path), Some(3)),
+        instruction("0x1004", "mov rbp, rsp", None, None),
+        instruction("0x1008", "call foo", None, Some(4)),
+        instruction("0x100c", "ret", None, Some(4)),
+    ];
+    let disassembled_references = Arc::new(Mutex::new(Vec::new()));
+    client.on_request::<Disassemble, _>({
+        let disassembled_references = disassembled_references.clone();
+        move |_, args| {
+            disassembled_references
+                .lock()
+                .push((args.memory_reference, args.instruction_offset));
+            Ok(dap::DisassembleResponse {
+                instructions: instructions.clone(),
+            })
+        }
+    });
+
+    client
+        .fake_event(dap::messages::Events::Stopped(StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        }))
+        .await;
+
+    cx.run_until_parked();
+
+    assert_eq!(
+        disassembled_references.lock().first().cloned(),
+        Some(("0x1008".to_string(), Some(-50))),
+        "The instructions around the instruction pointer should be disassembled"
+    );
+
+    let running_state = active_debug_session_panel(workspace, cx)
+        .update(cx, |item, _| item.running_state().clone());
+
+    // The frame has no source, so the disassembly is brought up to show it.
+    running_state.update(cx, |running_state, cx| {
+        assert_eq!(
+            running_state
+                .pane_items_status(cx)
+                .get(&DebuggerPaneItem::Disassembly),
+            Some(&true)
+        );
+    });
+
+    let disassembly_view = running_state.update(cx, |running_state, _| {
+        running_state.disassembly_view().clone()
+    });
+    disassembly_view.update(cx, |view, _| {
+        assert_eq!(
+            view.rows(),
+            vec![
+                format!("{main_path}:3"),
+                "  0x1000 push rbp".to_string(),
+                "  0x1004 mov rbp, rsp".to_string(),
+                format!("{main_path}:4"),
+                "> 0x1008 call foo".to_string(),
+                "  0x100c ret".to_string(),
+            ]
+        );
+    });
+
+    let instruction_breakpoints = Arc::new(Mutex::new(Vec::new()));
+    client.on_request::<SetInstructionBreakpoints, _>({
+        let instruction_breakpoints = instruction_breakpoints.clone();
+        move |_, args| {
+            *instruction_breakpoints.lock() = args
+                .breakpoints
+                .into_iter()
+                .map(|breakpoint| breakpoint.instruction_reference)
+                .collect::<Vec<_>>();
+            Ok(dap::SetInstructionBreakpointsResponse {
+                breakpoints: Vec::new(),
+            })
+        }
+    });
+
+    session.update(cx, |session, cx| {
+        session.toggle_instruction_breakpoint(0x100c, cx);
+    });
+    cx.run_until_parked();
+    assert_eq!(*instruction_breakpoints.lock(), vec!["0x100C".to_string()]);
+
+    session.update(cx, |session, cx| {
+        session.toggle_instruction_breakpoint(0x100c, cx);
+    });
+    cx.run_until_parked();
+    assert!(instruction_breakpoints.lock().is_empty());
+
+    let step_granularity = Arc::new(Mutex::new(None));
+    client.on_request::<Next, _>({
+        let step_granularity = step_granularity.clone();
+        move |_, args| {
+            *step_granularity.lock() = args.granularity;
+            Ok(())
+        }
+    });
+
+    running_state.update(cx, |running_state, cx| {
+        running_state.step_over_instruction(cx);
+    });
+    cx.run_until_parked();
+    assert_eq!(
+        *step_granularity.lock(),
+        Some(dap::SteppingGranularity::Instruction)
+    );
+}
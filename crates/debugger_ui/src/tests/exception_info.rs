@@ -0,0 +1,173 @@
+use crate::{
+    session::running::exception_info_modal::ExceptionInfoModal,
+    tests::{init_test, init_test_workspace, start_debug_session},
+};
+use dap::{
+    StackFrame, StoppedEvent,
+    requests::{ExceptionInfo, Initialize, Next, Scopes, StackTrace, Threads},
+};
+use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
+use parking_lot::Mutex;
+use project::{FakeFs, Project, debugger::session::ThreadId};
+use std::sync::Arc;
+use util::path;
+
+#[gpui::test]
+async fn test_exception_info_is_shown_when_stopped_on_exception(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |client| {
+        client.on_request::<Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_exception_info_request: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![dap::Thread {
+                id: 1,
+                name: "Thread 1".into(),
+            }],
+        })
+    });
+    client.on_request::<Scopes, _>(move |_, _| Ok(dap::ScopesResponse { scopes: vec![] }));
+    client.on_request::<StackTrace, _>(move |_, _| {
+        Ok(dap::StackTraceResponse {
+            stack_frames: vec![StackFrame {
+                id: 1,
+                name: "main".into(),
+                source: None,
+                line: 0,
+                column: 0,
+                end_line: None,
+                end_column: None,
+                can_restart: None,
+                instruction_pointer_reference: None,
+                module_id: None,
+                presentation_hint: None,
+            }],
+            total_frames: None,
+        })
+    });
+
+    let exception_info_requested_for = Arc::new(Mutex::new(Vec::new()));
+    client.on_request::<ExceptionInfo, _>({
+        let exception_info_requested_for = exception_info_requested_for.clone();
+        move |_, args| {
+            exception_info_requested_for.lock().push(args.thread_id);
+            Ok(dap::ExceptionInfoResponse {
+                exception_id: "System.InvalidOperationException".into(),
+                description: Some("Sequence contains no elements".into()),
+                break_mode: dap::ExceptionBreakMode::Unhandled,
+                details: Some(dap::ExceptionDetails {
+                    message: Some("Sequence contains no elements".into()),
+                    type_name: Some("InvalidOperationException".into()),
+                    full_type_name: Some("System.InvalidOperationException".into()),
+                    evaluate_name: None,
+                    stack_trace: Some("   at Program.Main()".into()),
+                    inner_exception: Some(vec![dap::ExceptionDetails {
+                        message: Some("The list was empty".into()),
+                        type_name: Some("ArgumentException".into()),
+                        full_type_name: None,
+                        evaluate_name: None,
+                        stack_trace: None,
+                        inner_exception: None,
+                    }]),
+                }),
+            })
+        }
+    });
+
+    let stop = |reason| {
+        dap::messages::Events::Stopped(StoppedEvent {
+            reason,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        })
+    };
+    let active_modal = |cx: &mut VisualTestContext| {
+        workspace
+            .update(cx, |multi, _, cx| {
+                multi
+                    .workspace()
+                    .read(cx)
+                    .active_modal::<ExceptionInfoModal>(cx)
+            })
+            .unwrap()
+    };
+
+    client
+        .fake_event(stop(dap::StoppedEventReason::Pause))
+        .await;
+    cx.run_until_parked();
+
+    assert!(exception_info_requested_for.lock().is_empty());
+    assert!(active_modal(cx).is_none());
+    session.update(cx, |session, _| {
+        assert_eq!(session.exception_thread(), None)
+    });
+
+    client
+        .fake_event(stop(dap::StoppedEventReason::Exception))
+        .await;
+    cx.run_until_parked();
+
+    assert_eq!(*exception_info_requested_for.lock(), vec![1]);
+    session.update(cx, |session, _| {
+        assert_eq!(session.exception_thread(), Some(ThreadId(1)))
+    });
+    let modal = active_modal(cx).expect("The exception should be shown");
+    modal.update(cx, |modal, _| {
+        let info = modal.info();
+        assert_eq!(info.exception_id, "System.InvalidOperationException");
+        let inner = info
+            .details
+            .as_ref()
+            .and_then(|details| details.inner_exception.as_ref())
+            .expect("The inner exception should be kept");
+        assert_eq!(inner[0].message.as_deref(), Some("The list was empty"));
+    });
+
+    client
+        .fake_event(dap::messages::Events::Continued(dap::ContinuedEvent {
+            thread_id: 1,
+            all_threads_continued: Some(true),
+        }))
+        .await;
+    cx.run_until_parked();
+
+    session.update(cx, |session, _| {
+        assert_eq!(session.exception_thread(), None)
+    });
+
+    client
+        .fake_event(stop(dap::StoppedEventReason::Exception))
+        .await;
+    cx.run_until_parked();
+    session.update(cx, |session, _| {
+        assert_eq!(session.exception_thread(), Some(ThreadId(1)))
+    });
+
+    client.on_request::<Next, _>(move |_, _| Ok(()));
+    session.update(cx, |session, cx| {
+        session.step_over(ThreadId(1), dap::SteppingGranularity::Line, cx);
+        assert_eq!(session.exception_thread(), None)
+    });
+}
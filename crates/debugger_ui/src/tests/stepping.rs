@@ -0,0 +1,250 @@
+use crate::{
+    session::running::step_in_targets_modal::StepInTargetsModal,
+    tests::{active_debug_session_panel, init_test, init_test_workspace, start_debug_session},
+};
+use dap::{
+    Source, StackFrame, StoppedEvent,
+    requests::{Goto, GotoTargets, Initialize, Scopes, StackTrace, StepIn, StepInTargets, Threads},
+};
+use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
+use menu::Confirm;
+use parking_lot::Mutex;
+use project::{FakeFs, Project, debugger::session::Session};
+use serde_json::json;
+use std::{path::Path, sync::Arc};
+use util::path;
+
+/// Answers the requests made when the session stops, with a single frame in `main.rs`.
+fn stop_in_main(
+    session: &gpui::Entity<Session>,
+    cx: &mut VisualTestContext,
+) -> Arc<dap::client::DebugAdapterClient> {
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![dap::Thread {
+                id: 1,
+                name: "Thread 1".into(),
+            }],
+        })
+    });
+    client.on_request::<Scopes, _>(move |_, _| Ok(dap::ScopesResponse { scopes: vec![] }));
+    client.on_request::<StackTrace, _>(move |_, _| {
+        Ok(dap::StackTraceResponse {
+            stack_frames: vec![StackFrame {
+                id: 1,
+                name: "main".into(),
+                source: Some(Source {
+                    name: Some("main.rs".into()),
+                    path: Some(path!("/project/main.rs").into()),
+                    source_reference: None,
+                    presentation_hint: None,
+                    origin: None,
+                    sources: None,
+                    adapter_data: None,
+                    checksums: None,
+                }),
+                line: 2,
+                column: 1,
+                end_line: None,
+                end_column: None,
+                can_restart: None,
+                instruction_pointer_reference: None,
+                module_id: None,
+                presentation_hint: None,
+            }],
+            total_frames: None,
+        })
+    });
+
+    client
+}
+
+async fn stop_thread(client: &dap::client::DebugAdapterClient) {
+    client
+        .fake_event(dap::messages::Events::Stopped(StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        }))
+        .await;
+}
+
+fn step_in_target(id: u64, label: &str) -> dap::StepInTarget {
+    dap::StepInTarget {
+        id,
+        label: label.into(),
+        line: Some(2),
+        column: None,
+        end_line: None,
+        end_column: None,
+    }
+}
+
+#[gpui::test]
+async fn test_step_into_target(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.rs": "fn main() {\n    foo(bar());\n}",
+        }),
+    )
+    .await;
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |client| {
+        client.on_request::<Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_step_in_targets_request: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .unwrap();
+    let client = stop_in_main(&session, cx);
+
+    let targets_requested_for = Arc::new(Mutex::new(None));
+    client.on_request::<StepInTargets, _>({
+        let targets_requested_for = targets_requested_for.clone();
+        move |_, args| {
+            *targets_requested_for.lock() = Some(args.frame_id);
+            Ok(dap::StepInTargetsResponse {
+                targets: vec![step_in_target(10, "bar()"), step_in_target(11, "foo()")],
+            })
+        }
+    });
+    let stepped_into = Arc::new(Mutex::new(None));
+    client.on_request::<StepIn, _>({
+        let stepped_into = stepped_into.clone();
+        move |_, args| {
+            *stepped_into.lock() = Some(args.target_id);
+            Ok(())
+        }
+    });
+
+    stop_thread(&client).await;
+    cx.run_until_parked();
+
+    let running_state = active_debug_session_panel(workspace, cx)
+        .update(cx, |item, _| item.running_state().clone());
+    running_state.update_in(cx, |running_state, window, cx| {
+        running_state.step_into_target(window, cx);
+    });
+    cx.run_until_parked();
+
+    assert_eq!(*targets_requested_for.lock(), Some(1));
+    assert_eq!(
+        *stepped_into.lock(),
+        None,
+        "The user should be asked which call to step into"
+    );
+    let modal = workspace
+        .update(cx, |multi, _, cx| {
+            multi
+                .workspace()
+                .read(cx)
+                .active_modal::<StepInTargetsModal>(cx)
+        })
+        .unwrap();
+    assert!(modal.is_some(), "The step in targets should be shown");
+
+    // The targets are listed in the order the adapter sent them.
+    cx.dispatch_action(Confirm);
+    cx.run_until_parked();
+
+    assert_eq!(*stepped_into.lock(), Some(Some(10)));
+    workspace
+        .update(cx, |multi, _, cx| {
+            assert!(
+                multi
+                    .workspace()
+                    .read(cx)
+                    .active_modal::<StepInTargetsModal>(cx)
+                    .is_none()
+            );
+        })
+        .unwrap();
+}
+
+#[gpui::test]
+async fn test_set_next_statement(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.rs": "fn main() {\n    foo();\n    bar();\n}",
+        }),
+    )
+    .await;
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |client| {
+        client.on_request::<Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_goto_targets_request: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .unwrap();
+    let client = stop_in_main(&session, cx);
+
+    let goto_targets_requested_for = Arc::new(Mutex::new(None));
+    client.on_request::<GotoTargets, _>({
+        let goto_targets_requested_for = goto_targets_requested_for.clone();
+        move |_, args| {
+            *goto_targets_requested_for.lock() = Some((args.source.path, args.line));
+            Ok(dap::GotoTargetsResponse {
+                targets: vec![dap::GotoTarget {
+                    id: 7,
+                    label: "bar();".into(),
+                    line: args.line,
+                    column: None,
+                    end_line: None,
+                    end_column: None,
+                    instruction_pointer_reference: None,
+                }],
+            })
+        }
+    });
+    let went_to = Arc::new(Mutex::new(None));
+    client.on_request::<Goto, _>({
+        let went_to = went_to.clone();
+        move |_, args| {
+            *went_to.lock() = Some((args.thread_id, args.target_id));
+            Ok(())
+        }
+    });
+
+    stop_thread(&client).await;
+    cx.run_until_parked();
+
+    let running_state = active_debug_session_panel(workspace, cx)
+        .update(cx, |item, _| item.running_state().clone());
+    let task = running_state.update(cx, |running_state, cx| {
+        running_state.set_next_statement(Path::new(path!("/project/main.rs")).into(), 2, cx)
+    });
+    cx.run_until_parked();
+    task.await.unwrap();
+
+    assert_eq!(
+        *goto_targets_requested_for.lock(),
+        Some((Some(path!("/project/main.rs").to_string()), 3)),
+        "Lines are one-based in the debug adapter protocol"
+    );
+    assert_eq!(*went_to.lock(), Some((1, 7)));
+}
@@ -192,6 +192,32 @@ pub struct SelectDownByLines {
     pub(super) lines: u32,
 }
 
+/// Moves to an earlier state in the undo tree, regardless of which branch it is on.
+#[derive(PartialEq, Clone, Deserialize, Default, JsonSchema, Action)]
+#[action(namespace = editor)]
+#[serde(deny_unknown_fields)]
+pub struct UndoEarlier {
+    /// The number of states to move back by. Defaults to 1.
+    #[serde(default)]
+    pub steps: Option<usize>,
+    /// Moves back to the state from this many seconds earlier instead.
+    #[serde(default)]
+    pub seconds: Option<u64>,
+}
+
+/// Moves to a later state in the undo tree, regardless of which branch it is on.
+#[derive(PartialEq, Clone, Deserialize, Default, JsonSchema, Action)]
+#[action(namespace = editor)]
+#[serde(deny_unknown_fields)]
+pub struct UndoLater {
+    /// The number of states to move forward by. Defaults to 1.
+    #[serde(default)]
+    pub steps: Option<usize>,
+    /// Moves forward to the state from this many seconds later instead.
+    #[serde(default)]
+    pub seconds: Option<u64>,
+}
+
 /// Expands all excerpts with selections.
 #[derive(PartialEq, Clone, Deserialize, Default, JsonSchema, Action)]
 #[action(namespace = editor)]
@@ -383,6 +409,8 @@ actions!(
     [
         /// Runs program execution to the current cursor position.
         RunToCursor,
+        /// Moves program execution to the current cursor position, without running the code in between.
+        SetNextStatement,
         /// Evaluates the selected text in the debugger context.
         EvaluateSelectedText
     ]
@@ -921,6 +949,10 @@ actions!(
         Undo,
         /// Undoes the last selection change.
         UndoSelection,
+        /// Switches to the next sibling branch in the undo tree.
+        UndoTreeNextBranch,
+        /// Switches to the previous sibling branch in the undo tree.
+        UndoTreePreviousBranch,
         /// Unfolds all folded regions.
         UnfoldAll,
         /// Unfolds lines at cursor.
@@ -70,6 +70,7 @@ mod markdown_actions;
 mod navigation;
 mod rewrap;
 mod selection;
+mod undo_history;
 
 pub(crate) use actions::*;
 pub use clipboard::ClipboardSelection;
@@ -179,7 +180,7 @@ use language::{
     DiagnosticEntryRef, DiffOptions, EditPredictionsMode, EditPreview, HighlightedText, IndentKind,
     IndentSize, Language, LanguageAwareStyling, LanguageName, LanguageRegistry, LanguageScope,
     LocalFile, OffsetRangeExt, OutlineItem, Point, Selection, SelectionGoal, TextObject,
-    TransactionId, TreeSitterOptions, WordsQuery,
+    TransactionId, TreeSitterOptions, UndoTreeTarget, WordsQuery,
     language_settings::{
         self, AllLanguageSettings, LanguageSettings, LspInsertMode, RewrapBehavior,
         WordsCompletionMode, all_language_settings,
@@ -1140,6 +1141,8 @@ pub struct Editor {
     _scroll_cursor_center_top_bottom_task: Task<()>,
     serialize_selections: Task<()>,
     serialize_folds: Task<()>,
+    serialize_undo_history: Task<()>,
+    load_undo_history: Task<()>,
     minimap: Option<Entity<Self>>,
     pub change_list: ChangeList,
     inline_value_cache: InlineValueCache,
@@ -2362,6 +2365,8 @@ impl Editor {
             toggle_fold_multiple_buffers: Task::ready(()),
             serialize_selections: Task::ready(()),
             serialize_folds: Task::ready(()),
+            serialize_undo_history: Task::ready(()),
+            load_undo_history: Task::ready(()),
             text_style_refinement: None,
             load_diff_task: load_uncommitted_diff,
             temporary_diff_override: false,
@@ -4132,6 +4137,7 @@ impl Editor {
         let has_bookmark = bookmark.as_ref().is_some();
 
         let run_to_cursor = window.is_action_available(&RunToCursor, cx);
+        let set_next_statement = window.is_action_available(&SetNextStatement, cx);
 
         let toggle_state_entry: Option<(&str, Box<dyn Action>)> =
             breakpoint.as_ref().map(|bp| match bp.1.state {
@@ -4173,8 +4179,33 @@ impl Editor {
                             window.dispatch_action(Box::new(RunToCursor), cx);
                         },
                     )
-                    .separator()
                 })
+                .when(set_next_statement, |this| {
+                    let weak_editor = weak_editor.clone();
+                    this.entry(
+                        "Set Next Statement",
+                        Some(SetNextStatement.boxed_clone()),
+                        move |window, cx| {
+                            weak_editor
+                                .update(cx, |editor, cx| {
+                                    editor.change_selections(
+                                        SelectionEffects::no_scroll(),
+                                        window,
+                                        cx,
+                                        |s| {
+                                            s.select_ranges(
+                                                [Point::new(row, 0)..Point::new(row, 0)],
+                                            )
+                                        },
+                                    );
+                                })
+                                .ok();
+
+                            window.dispatch_action(Box::new(SetNextStatement), cx);
+                        },
+                    )
+                })
+                .when(run_to_cursor || set_next_statement, |this| this.separator())
                 .when_some(toggle_state_entry, |this, (msg, action)| {
                     this.entry(msg, Some(action), {
                         let weak_editor = weak_editor.clone();
@@ -7562,6 +7593,100 @@ impl Editor {
         }
     }
 
+    pub fn undo_tree_next_branch(
+        &mut self,
+        _: &UndoTreeNextBranch,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.travel_undo_tree(UndoTreeTarget::NextBranch, window, cx);
+    }
+
+    pub fn undo_tree_previous_branch(
+        &mut self,
+        _: &UndoTreePreviousBranch,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.travel_undo_tree(UndoTreeTarget::PreviousBranch, window, cx);
+    }
+
+    pub fn undo_earlier(
+        &mut self,
+        action: &UndoEarlier,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let target = match action.seconds {
+            Some(seconds) => UndoTreeTarget::Earlier(Duration::from_secs(seconds)),
+            None => UndoTreeTarget::Steps(-undo_tree_steps(action.steps)),
+        };
+        self.travel_undo_tree(target, window, cx);
+    }
+
+    pub fn undo_later(&mut self, action: &UndoLater, window: &mut Window, cx: &mut Context<Self>) {
+        let target = match action.seconds {
+            Some(seconds) => UndoTreeTarget::Later(Duration::from_secs(seconds)),
+            None => UndoTreeTarget::Steps(undo_tree_steps(action.steps)),
+        };
+        self.travel_undo_tree(target, window, cx);
+    }
+
+    /// Moves the buffer to a different state in its undo tree, restoring the
+    /// selections that were active in that state. Returns whether anything changed.
+    pub fn travel_undo_tree(
+        &mut self,
+        target: UndoTreeTarget,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if self.read_only(cx) {
+            return false;
+        }
+
+        let Some(travel) = self
+            .buffer
+            .update(cx, |buffer, cx| buffer.travel_undo_tree(target, cx))
+        else {
+            return false;
+        };
+
+        let selections = if let Some(transaction_id) = travel.redone.last() {
+            self.selection_history
+                .transaction(*transaction_id)
+                .and_then(|(_, after)| after.clone())
+        } else if let Some(transaction_id) = travel.undone.last() {
+            self.selection_history
+                .transaction(*transaction_id)
+                .map(|(before, _)| before.clone())
+        } else {
+            None
+        };
+        if let Some(selections) = selections {
+            self.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+                s.select_anchors(selections.to_vec());
+            });
+        }
+        self.request_autoscroll(Autoscroll::fit(), cx);
+        self.unmark_text(window, cx);
+        self.refresh_edit_prediction(
+            true,
+            false,
+            EditPredictionRequestTrigger::BufferEdit,
+            window,
+            cx,
+        );
+        if let Some(transaction_id) = travel.redone.last().or(travel.undone.last()) {
+            cx.emit(EditorEvent::Edited {
+                transaction_id: *transaction_id,
+            });
+        }
+        for transaction_id in travel.undone {
+            cx.emit(EditorEvent::TransactionUndone { transaction_id });
+        }
+        true
+    }
+
     pub fn finalize_last_transaction(&mut self, cx: &mut Context<Self>) {
         self.buffer
             .update(cx, |buffer, cx| buffer.finalize_last_transaction(cx));
@@ -9608,7 +9733,10 @@ impl Editor {
                 cx.notify();
             }
             multi_buffer::Event::DirtyChanged => cx.emit(EditorEvent::DirtyChanged),
-            multi_buffer::Event::Saved => cx.emit(EditorEvent::Saved),
+            multi_buffer::Event::Saved => {
+                self.serialize_undo_history(cx);
+                cx.emit(EditorEvent::Saved);
+            }
             multi_buffer::Event::FileHandleChanged => {
                 cx.emit(EditorEvent::TitleChanged);
                 cx.emit(EditorEvent::FileHandleChanged);
@@ -12406,3 +12534,9 @@ pub fn multibuffer_context_lines(cx: &App) -> u32 {
         .unwrap_or(2)
         .min(32)
 }
+
+/// Converts a step count into a signed offset, clamping counts too large to
+/// represent so that they travel as far as possible.
+fn undo_tree_steps(steps: Option<usize>) -> isize {
+    isize::try_from(steps.unwrap_or(1)).unwrap_or(isize::MAX)
+}
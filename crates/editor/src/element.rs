@@ -608,6 +608,10 @@ impl EditorElement {
             register_action(editor, window, Editor::paste);
             register_action(editor, window, Editor::undo);
             register_action(editor, window, Editor::redo);
+            register_action(editor, window, Editor::undo_tree_next_branch);
+            register_action(editor, window, Editor::undo_tree_previous_branch);
+            register_action(editor, window, Editor::undo_earlier);
+            register_action(editor, window, Editor::undo_later);
             register_action(editor, window, Editor::toggle_comments);
             register_action(editor, window, Editor::toggle_block_comments);
             register_action(editor, window, Editor::toggle_markdown_block_quote);
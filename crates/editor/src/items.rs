@@ -1108,14 +1108,15 @@ impl Item for Editor {
             .next()
             .is_some();
 
-        if !has_folds {
-            if let Some(workspace_id) = workspace.database_id()
-                && let Some(file_path) = self.buffer().read(cx).as_singleton().and_then(|buffer| {
-                    project::File::from_dyn(buffer.read(cx).file()).map(|file| file.abs_path(cx))
-                })
-            {
-                self.load_folds_from_db(workspace_id, file_path, window, cx);
+        if let Some(workspace_id) = workspace.database_id()
+            && let Some(file_path) = self.buffer().read(cx).as_singleton().and_then(|buffer| {
+                project::File::from_dyn(buffer.read(cx).file()).map(|file| file.abs_path(cx))
+            })
+        {
+            if !has_folds {
+                self.load_folds_from_db(workspace_id, file_path.clone(), window, cx);
             }
+            self.load_undo_history_from_db(workspace_id, file_path, cx);
         }
     }
 
@@ -126,6 +126,13 @@ impl Domain for EditorDb {
     //   start_fingerprint: Option<String>,
     //   end_fingerprint: Option<String>,
     // )
+    //
+    // file_undo_histories(
+    //   workspace_id: usize,
+    //   path: PathBuf,
+    //   content_hash: String,
+    //   history: String,
+    // )
 
     const MIGRATIONS: &[&str] = &[
         sql! (
@@ -223,6 +230,20 @@ impl Domain for EditorDb {
                 PRIMARY KEY(workspace_id, path, start)
             );
         ),
+        // Undo history persistence: store each file's undo tree along with a hash
+        // of the content it applies to, so it can be dropped if the file changes.
+        sql! (
+            CREATE TABLE file_undo_histories (
+                workspace_id INTEGER NOT NULL,
+                path TEXT NOT NULL,
+                content_hash TEXT NOT NULL,
+                history TEXT NOT NULL,
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                    ON DELETE CASCADE
+                    ON UPDATE CASCADE,
+                PRIMARY KEY(workspace_id, path)
+            );
+        ),
     ];
 }
 
@@ -319,6 +340,39 @@ impl EditorDb {
         }
     }
 
+    // Returns the content hash and the serialized undo history
+    query! {
+        pub fn get_file_undo_history(
+            workspace_id: WorkspaceId,
+            path: &Path
+        ) -> Result<Option<(String, String)>> {
+            SELECT content_hash, history
+            FROM file_undo_histories
+            WHERE workspace_id = ?1 AND path = ?2
+        }
+    }
+
+    query! {
+        pub async fn save_file_undo_history(
+            workspace_id: WorkspaceId,
+            path: PathBuf,
+            content_hash: String,
+            history: String
+        ) -> Result<()> {
+            INSERT OR REPLACE INTO file_undo_histories
+                (workspace_id, path, content_hash, history)
+            VALUES
+                (?1, ?2, ?3, ?4)
+        }
+    }
+
+    query! {
+        pub async fn delete_file_undo_history(workspace_id: WorkspaceId, path: PathBuf) -> Result<()> {
+            DELETE FROM file_undo_histories
+            WHERE workspace_id = ?1 AND path = ?2
+        }
+    }
+
     pub async fn save_editor_selections(
         &self,
         editor_id: ItemId,
@@ -614,4 +668,55 @@ mod tests {
         assert_eq!(retrieved_a[0].0, 10); // file_a's fold
         assert_eq!(retrieved_b[0].0, 30); // file_b's fold
     }
+
+    #[gpui::test]
+    async fn test_save_and_get_file_undo_history(cx: &mut gpui::TestAppContext) {
+        let db = cx.update(|cx| workspace::WorkspaceDb::global(cx));
+        let workspace_id = db.next_id().await.unwrap();
+        let editor_db = cx.update(|cx| EditorDb::global(cx));
+        let file_path = PathBuf::from("/tmp/test_file_undo_history.rs");
+
+        assert_eq!(
+            editor_db
+                .get_file_undo_history(workspace_id, &file_path)
+                .unwrap(),
+            None
+        );
+
+        editor_db
+            .save_file_undo_history(
+                workspace_id,
+                file_path.clone(),
+                "hash-1".to_string(),
+                "history-1".to_string(),
+            )
+            .await
+            .unwrap();
+        editor_db
+            .save_file_undo_history(
+                workspace_id,
+                file_path.clone(),
+                "hash-2".to_string(),
+                "history-2".to_string(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            editor_db
+                .get_file_undo_history(workspace_id, &file_path)
+                .unwrap(),
+            Some(("hash-2".to_string(), "history-2".to_string()))
+        );
+
+        editor_db
+            .delete_file_undo_history(workspace_id, file_path.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            editor_db
+                .get_file_undo_history(workspace_id, &file_path)
+                .unwrap(),
+            None
+        );
+    }
 }
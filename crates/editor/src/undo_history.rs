@@ -0,0 +1,130 @@
+use super::*;
+use language::SerializedUndoHistory;
+use sha2::{Digest as _, Sha256};
+
+impl Editor {
+    /// Persists the undo history of the editor's file along with a hash of
+    /// its saved content, so it can be restored when the file is reopened.
+    ///
+    /// Copying the history takes time proportional to its size, so saves in
+    /// quick succession (e.g. with autosave) only persist it once.
+    pub(super) fn serialize_undo_history(&mut self, cx: &mut Context<Self>) {
+        if !self.mode.is_full() {
+            return;
+        }
+        let db = EditorDb::global(cx);
+        self.serialize_undo_history = cx.spawn(async move |editor, cx| {
+            cx.background_executor()
+                .timer(SERIALIZATION_THROTTLE_TIME)
+                .await;
+            let Some((workspace_id, file_path, history, text)) = editor
+                .update(cx, |editor, cx| {
+                    let workspace_id = editor.workspace_serialization_id(cx)?;
+                    let buffer = editor.buffer.read(cx).as_singleton()?;
+                    let buffer = buffer.read(cx);
+                    // The history has to match the file's content. If it was
+                    // edited since, the next save persists the history.
+                    if buffer.is_dirty() {
+                        return None;
+                    }
+                    let file_path =
+                        project::File::from_dyn(buffer.file()).map(|file| file.abs_path(cx))?;
+                    Some((
+                        workspace_id,
+                        file_path,
+                        buffer.undo_history_snapshot(),
+                        buffer.as_rope().clone(),
+                    ))
+                })
+                .ok()
+                .flatten()
+            else {
+                return;
+            };
+
+            cx.background_spawn(async move {
+                match history.serialize() {
+                    Some(history) => {
+                        let history = serde_json::to_string(&history)?;
+                        db.save_file_undo_history(
+                            workspace_id,
+                            file_path,
+                            content_hash(&text),
+                            history,
+                        )
+                        .await
+                    }
+                    None => db.delete_file_undo_history(workspace_id, file_path).await,
+                }
+            })
+            .await
+            .with_context(|| format!("persisting undo history for workspace {workspace_id:?}"))
+            .log_err();
+        });
+    }
+
+    /// Restores the undo history persisted for the editor's file, dropping it
+    /// if the file was changed since the history was stored.
+    pub(super) fn load_undo_history_from_db(
+        &mut self,
+        workspace_id: WorkspaceId,
+        file_path: PathBuf,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.mode.is_full() {
+            return;
+        }
+        let Some(buffer) = self.buffer.read(cx).as_singleton() else {
+            return;
+        };
+        if buffer.read(cx).is_dirty() || !buffer.read(cx).undo_tree().is_empty() {
+            return;
+        }
+        // The restored history isn't sent to other replicas of the buffer, so
+        // it can't have any.
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let is_replicated = |project: &Project| !project.is_local() || project.is_shared();
+        if is_replicated(project.read(cx)) {
+            return;
+        }
+
+        let db = EditorDb::global(cx);
+        let Some((stored_hash, history)) = db
+            .get_file_undo_history(workspace_id, &file_path)
+            .log_err()
+            .flatten()
+        else {
+            return;
+        };
+
+        let text = buffer.read(cx).as_rope().clone();
+        self.load_undo_history = cx.spawn(async move |_, cx| {
+            let (content_hash, history) = cx
+                .background_spawn(async move {
+                    let history = serde_json::from_str::<SerializedUndoHistory>(&history);
+                    (content_hash(&text), history)
+                })
+                .await;
+            let restored = content_hash == stored_hash
+                && !project.read_with(cx, |project, _| is_replicated(project))
+                && history.log_err().is_some_and(|history| {
+                    buffer.update(cx, |buffer, cx| buffer.restore_undo_history(&history, cx))
+                });
+            if !restored {
+                db.delete_file_undo_history(workspace_id, file_path)
+                    .await
+                    .log_err();
+            }
+        });
+    }
+}
+
+fn content_hash(text: &Rope) -> String {
+    let mut hasher = Sha256::new();
+    for chunk in text.chunks() {
+        hasher.update(chunk.as_bytes());
+    }
+    format!("{:x}", hasher.finalize())
+}
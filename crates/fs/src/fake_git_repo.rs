@@ -11,11 +11,12 @@ use git::{
     Oid, RunHook,
     blame::Blame,
     repository::{
-        AskPassDelegate, Branch, CommitData, CommitDataReader, CommitDetails, CommitOptions,
-        CreateWorktreeTarget, FetchOptions, FileHistoryChangedFileSets, GRAPH_CHUNK_SIZE,
-        GitRepository, GitRepositoryCheckpoint, InitialGraphCommitData, LogOrder, LogSource,
-        PushOptions, RefEdit, Remote, RepoPath, ResetMode, SearchCommitArgs, Worktree,
-        commit_hash_search_query,
+        AskPassDelegate, BisectMark, BisectState, Branch, CommitData, CommitDataReader,
+        CommitDetails, CommitOptions, CreateWorktreeTarget, FetchOptions,
+        FileHistoryChangedFileSets, GRAPH_CHUNK_SIZE, GitRepository, GitRepositoryCheckpoint,
+        InitialGraphCommitData, LogOrder, LogSource, MergeStages, PushOptions, RebaseAction,
+        RebaseControl, RebaseProgress, RebaseTodoEntry, RefEdit, Remote, RepoPath, ResetMode,
+        SearchCommitArgs, SequencerOperation, Worktree, commit_hash_search_query,
     },
     stash::GitStash,
     status::{
@@ -49,6 +50,28 @@ pub struct FakeCommitSnapshot {
     pub sha: String,
 }
 
+#[derive(Debug, Clone)]
+pub struct FakeRebaseState {
+    pub original_head: String,
+    pub todo: Vec<RebaseTodoEntry>,
+    /// The number of todo entries that have been processed.
+    pub done: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct FakeSequencerState {
+    pub operation: SequencerOperation,
+    pub original_head: String,
+    /// The commits that have yet to be applied.
+    pub remaining: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FakeBisectState {
+    pub state: BisectState,
+    pub original_head: String,
+}
+
 #[derive(Debug, Clone)]
 pub enum FakeCommitDataEntry {
     Success(CommitData),
@@ -60,6 +83,7 @@ pub struct FakeGitRepositoryState {
     pub commit_history: Vec<FakeCommitSnapshot>,
     pub event_emitter: async_channel::Sender<PathBuf>,
     pub unmerged_paths: HashMap<RepoPath, UnmergedStatus>,
+    pub merge_stages: HashMap<RepoPath, MergeStages>,
     pub head_contents: HashMap<RepoPath, String>,
     pub index_contents: HashMap<RepoPath, String>,
     // everything in commit contents is in oids
@@ -79,6 +103,11 @@ pub struct FakeGitRepositoryState {
     pub graph_commits: Vec<Arc<InitialGraphCommitData>>,
     pub commit_data: HashMap<Oid, FakeCommitDataEntry>,
     pub stash_entries: GitStash,
+    pub rebase: Option<FakeRebaseState>,
+    pub sequencer: Option<FakeSequencerState>,
+    pub bisect: Option<FakeBisectState>,
+    /// Commits that stop a rebase, cherry-pick or revert with conflicts when applied.
+    pub conflicting_commits: HashSet<String>,
 }
 
 impl FakeGitRepositoryState {
@@ -88,6 +117,7 @@ impl FakeGitRepositoryState {
             head_contents: Default::default(),
             index_contents: Default::default(),
             unmerged_paths: Default::default(),
+            merge_stages: Default::default(),
             blames: Default::default(),
             current_branch_name: Default::default(),
             branches: Default::default(),
@@ -104,6 +134,10 @@ impl FakeGitRepositoryState {
             commit_data: Default::default(),
             commit_history: Vec::new(),
             stash_entries: Default::default(),
+            rebase: None,
+            sequencer: None,
+            bisect: None,
+            conflicting_commits: Default::default(),
         }
     }
 }
@@ -138,6 +172,103 @@ impl FakeGitRepository {
         })
     }
 
+    /// Replays the remaining todo entries of the rebase in progress, stopping
+    /// at the first one that conflicts.
+    fn advance_rebase(state: &mut FakeGitRepositoryState) {
+        state.refs.remove("REBASE_HEAD");
+        let Some(rebase) = state.rebase.as_mut() else {
+            return;
+        };
+        while let Some(entry) = rebase.todo.get(rebase.done) {
+            rebase.done += 1;
+            if entry.action == RebaseAction::Drop {
+                continue;
+            }
+            if state.conflicting_commits.contains(entry.sha.as_ref()) {
+                state
+                    .refs
+                    .insert("REBASE_HEAD".into(), entry.sha.to_string());
+                return;
+            }
+            state
+                .refs
+                .insert("HEAD".into(), format!("rebased-{}", entry.sha));
+        }
+        state.rebase = None;
+    }
+
+    /// Applies the remaining commits of the cherry-pick or revert in progress,
+    /// stopping at the first one that conflicts.
+    fn advance_sequencer(state: &mut FakeGitRepositoryState) {
+        let Some(sequencer) = state.sequencer.as_mut() else {
+            return;
+        };
+        let operation = sequencer.operation;
+        state.refs.remove(operation.head_ref());
+        while !sequencer.remaining.is_empty() {
+            let sha = sequencer.remaining.remove(0);
+            if state.conflicting_commits.contains(&sha) {
+                state.refs.insert(operation.head_ref().into(), sha);
+                return;
+            }
+            state
+                .refs
+                .insert("HEAD".into(), format!("{}-{sha}", operation.command()));
+        }
+        state.sequencer = None;
+    }
+
+    fn start_sequencer(
+        &self,
+        operation: SequencerOperation,
+        commits: Vec<String>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            anyhow::ensure!(
+                state.sequencer.is_none(),
+                "a cherry-pick or revert is already in progress"
+            );
+            state.sequencer = Some(FakeSequencerState {
+                operation,
+                original_head: state.refs.get("HEAD").cloned().unwrap_or_default(),
+                remaining: commits,
+            });
+            Self::advance_sequencer(state);
+            Ok(())
+        })
+    }
+
+    /// Checks out the middle of the remaining graph commits, or records the
+    /// first bad commit once it is the only one left.
+    fn advance_bisect(state: &mut FakeGitRepositoryState) {
+        let Some(bisect) = state.bisect.as_mut() else {
+            return;
+        };
+        if bisect.state.bad.is_none() || bisect.state.good.is_empty() {
+            return;
+        }
+        let remaining = bisect
+            .state
+            .remaining_commits(state.graph_commits.iter().map(|commit| commit.as_ref()));
+        let remaining = state
+            .graph_commits
+            .iter()
+            .map(|commit| commit.sha)
+            .filter(|sha| remaining.contains(sha) && !bisect.state.skipped.contains(sha))
+            .collect::<Vec<_>>();
+        match remaining.as_slice() {
+            [] => {}
+            [first_bad] => {
+                bisect.state.first_bad = Some(*first_bad);
+                state.refs.insert("HEAD".into(), first_bad.to_string());
+            }
+            remaining => {
+                let next = remaining[remaining.len() / 2];
+                state.refs.insert("HEAD".into(), next.to_string());
+            }
+        }
+    }
+
     /// Scans `.git/worktrees/*/gitdir` to find the admin entry directory for a
     /// worktree at the given checkout path. Used when the working tree directory
     /// has already been deleted and we can't read its `.git` pointer file.
@@ -184,6 +315,22 @@ impl GitRepository for FakeGitRepository {
         self.executor.spawn(async move { fut.await.ok() }).boxed()
     }
 
+    fn load_committed_bytes(&self, path: RepoPath) -> BoxFuture<'_, Option<Vec<u8>>> {
+        let fut = self.load_committed_text(path);
+        async move { fut.await.map(String::into_bytes) }.boxed()
+    }
+
+    fn load_merge_stages(&self, path: RepoPath) -> BoxFuture<'_, Result<MergeStages>> {
+        self.with_state_async(false, move |state| {
+            state
+                .merge_stages
+                .get(&path)
+                .cloned()
+                .context("path is not conflicted")
+        })
+        .boxed()
+    }
+
     fn load_commit_template(&self) -> BoxFuture<'_, Result<Option<GitCommitTemplate>>> {
         async { Ok(None) }.boxed()
     }
@@ -318,6 +465,11 @@ impl GitRepository for FakeGitRepository {
                     state.head_contents = snapshot.head_contents;
                     state.index_contents = state.head_contents.clone();
                 }
+                ResetMode::Hard => {
+                    state.head_contents = snapshot.head_contents;
+                    state.index_contents = state.head_contents.clone();
+                    state.unmerged_paths.clear();
+                }
             }
 
             state.refs.insert("HEAD".into(), snapshot.sha);
@@ -334,6 +486,230 @@ impl GitRepository for FakeGitRepository {
         unimplemented!()
     }
 
+    fn rebase_todo(&self, base: String) -> BoxFuture<'_, Result<Vec<RebaseTodoEntry>>> {
+        self.with_state_async(false, move |state| {
+            let commits_by_sha = state
+                .graph_commits
+                .iter()
+                .map(|commit| (commit.sha.to_string(), commit))
+                .collect::<HashMap<_, _>>();
+            let mut sha = state.refs.get("HEAD").cloned().context("no HEAD")?;
+            let mut todo = Vec::new();
+            while sha != base {
+                let commit = commits_by_sha
+                    .get(&sha)
+                    .with_context(|| format!("{base} is not an ancestor of HEAD"))?;
+                let (subject, original_message) = match state.commit_data.get(&commit.sha) {
+                    Some(FakeCommitDataEntry::Success(data)) => {
+                        (data.subject.clone(), data.message.clone())
+                    }
+                    _ => Default::default(),
+                };
+                todo.push(RebaseTodoEntry {
+                    action: RebaseAction::Pick,
+                    sha: sha.clone().into(),
+                    subject,
+                    original_message,
+                    message: None,
+                });
+                sha = commit
+                    .parents
+                    .first()
+                    .with_context(|| format!("{base} is not an ancestor of HEAD"))?
+                    .to_string();
+            }
+            todo.reverse();
+            Ok(todo)
+        })
+    }
+
+    fn rebase(
+        &self,
+        base: String,
+        todo: Vec<RebaseTodoEntry>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            anyhow::ensure!(state.rebase.is_none(), "a rebase is already in progress");
+            let original_head = state.refs.get("HEAD").cloned().unwrap_or_default();
+            state.refs.insert("HEAD".into(), base);
+            state.rebase = Some(FakeRebaseState {
+                original_head,
+                todo,
+                done: 0,
+            });
+            Self::advance_rebase(state);
+            Ok(())
+        })
+    }
+
+    fn rebase_control(
+        &self,
+        control: RebaseControl,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            let rebase = state.rebase.as_ref().context("no rebase in progress")?;
+            match control {
+                RebaseControl::Continue => {
+                    anyhow::ensure!(
+                        state.unmerged_paths.is_empty(),
+                        "you must resolve all conflicts before continuing"
+                    );
+                    if let Some(sha) = state.refs.get("REBASE_HEAD").cloned() {
+                        state.refs.insert("HEAD".into(), format!("rebased-{sha}"));
+                    }
+                    Self::advance_rebase(state);
+                }
+                RebaseControl::Skip => {
+                    state.unmerged_paths.clear();
+                    Self::advance_rebase(state);
+                }
+                RebaseControl::Abort => {
+                    state
+                        .refs
+                        .insert("HEAD".into(), rebase.original_head.clone());
+                    state.refs.remove("REBASE_HEAD");
+                    state.unmerged_paths.clear();
+                    state.rebase = None;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn rebase_progress(&self) -> BoxFuture<'_, Option<RebaseProgress>> {
+        self.with_state_async(false, |state| {
+            Ok(state.rebase.as_ref().map(|rebase| RebaseProgress {
+                current: rebase.done,
+                total: rebase.todo.len(),
+            }))
+        })
+        .map(|result| result.ok().flatten())
+        .boxed()
+    }
+
+    fn cherry_pick(
+        &self,
+        commits: Vec<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.start_sequencer(SequencerOperation::CherryPick, commits)
+    }
+
+    fn revert(
+        &self,
+        commit: String,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.start_sequencer(SequencerOperation::Revert, vec![commit])
+    }
+
+    fn sequencer_control(
+        &self,
+        operation: SequencerOperation,
+        control: RebaseControl,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            let sequencer = state
+                .sequencer
+                .as_ref()
+                .filter(|sequencer| sequencer.operation == operation)
+                .with_context(|| format!("no {} in progress", operation.command()))?;
+            match control {
+                RebaseControl::Continue => {
+                    anyhow::ensure!(
+                        state.unmerged_paths.is_empty(),
+                        "you must resolve all conflicts before continuing"
+                    );
+                    if let Some(sha) = state.refs.get(operation.head_ref()).cloned() {
+                        state
+                            .refs
+                            .insert("HEAD".into(), format!("{}-{sha}", operation.command()));
+                    }
+                    Self::advance_sequencer(state);
+                }
+                RebaseControl::Skip => {
+                    state.unmerged_paths.clear();
+                    Self::advance_sequencer(state);
+                }
+                RebaseControl::Abort => {
+                    state
+                        .refs
+                        .insert("HEAD".into(), sequencer.original_head.clone());
+                    state.refs.remove(operation.head_ref());
+                    state.unmerged_paths.clear();
+                    state.sequencer = None;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn bisect_start(
+        &self,
+        bad: String,
+        good: Vec<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            anyhow::ensure!(state.bisect.is_none(), "a bisect is already in progress");
+            state.bisect = Some(FakeBisectState {
+                state: BisectState {
+                    bad: Some(bad.parse()?),
+                    good: good.iter().map(|sha| sha.parse()).collect::<Result<_>>()?,
+                    ..Default::default()
+                },
+                original_head: state.refs.get("HEAD").cloned().unwrap_or_default(),
+            });
+            Self::advance_bisect(state);
+            Ok(())
+        })
+    }
+
+    fn bisect_mark(
+        &self,
+        mark: BisectMark,
+        commit: Option<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            let commit = commit
+                .or_else(|| state.refs.get("HEAD").cloned())
+                .context("no commit to mark")?;
+            let sha = commit.parse()?;
+            let bisect = &mut state
+                .bisect
+                .as_mut()
+                .context("no bisect in progress")?
+                .state;
+            match mark {
+                BisectMark::Good => bisect.good.push(sha),
+                BisectMark::Bad => bisect.bad = Some(sha),
+                BisectMark::Skip => bisect.skipped.push(sha),
+            }
+            Self::advance_bisect(state);
+            Ok(())
+        })
+    }
+
+    fn bisect_reset(&self, _env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, |state| {
+            let bisect = state.bisect.take().context("no bisect in progress")?;
+            state.refs.insert("HEAD".into(), bisect.original_head);
+            Ok(())
+        })
+    }
+
+    fn bisect_state(&self) -> BoxFuture<'_, Option<BisectState>> {
+        self.with_state_async(false, |state| {
+            Ok(state.bisect.as_ref().map(|bisect| bisect.state.clone()))
+        })
+        .map(|result| result.ok().flatten())
+        .boxed()
+    }
+
     fn path(&self) -> PathBuf {
         self.repository_dir_path.clone()
     }
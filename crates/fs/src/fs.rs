@@ -132,6 +132,10 @@ pub trait Fs: Send + Sync {
         Ok(String::from_utf8(self.load_bytes(path).await?)?)
     }
     async fn load_bytes(&self, path: &Path) -> Result<Vec<u8>>;
+    /// Reads up to `len` bytes starting at `offset`, returning fewer when the file ends before.
+    async fn read_range(&self, path: &Path, offset: u64, len: usize) -> Result<Vec<u8>>;
+    /// Overwrites the bytes starting at each offset, leaving the rest of the file as is.
+    async fn write_ranges(&self, path: &Path, ranges: Vec<(u64, Arc<[u8]>)>) -> Result<()>;
     async fn atomic_write(&self, path: PathBuf, text: String) -> Result<()>;
     async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()>;
     async fn write(&self, path: &Path, content: &[u8]) -> Result<()>;
@@ -854,6 +858,42 @@ impl Fs for RealFs {
         Ok(bytes)
     }
 
+    async fn read_range(&self, path: &Path, offset: u64, len: usize) -> Result<Vec<u8>> {
+        use std::io::{Read as _, Seek as _, SeekFrom};
+
+        let path = path.to_path_buf();
+        self.executor
+            .spawn(async move {
+                let mut file = std::fs::File::open(&path)
+                    .with_context(|| format!("Failed to open file {}", path.display()))?;
+                file.seek(SeekFrom::Start(offset))?;
+                let mut bytes = Vec::with_capacity(len);
+                file.take(len as u64).read_to_end(&mut bytes)?;
+                Ok(bytes)
+            })
+            .await
+    }
+
+    async fn write_ranges(&self, path: &Path, ranges: Vec<(u64, Arc<[u8]>)>) -> Result<()> {
+        use std::io::{Seek as _, SeekFrom};
+
+        let path = path.to_path_buf();
+        self.executor
+            .spawn(async move {
+                let mut file = std::fs::OpenOptions::new()
+                    .write(true)
+                    .open(&path)
+                    .with_context(|| format!("Failed to open file {}", path.display()))?;
+                for (offset, bytes) in ranges {
+                    file.seek(SeekFrom::Start(offset))?;
+                    file.write_all(&bytes)?;
+                }
+                file.sync_all()?;
+                Ok(())
+            })
+            .await
+    }
+
     #[cfg(not(target_os = "windows"))]
     async fn atomic_write(&self, path: PathBuf, data: String) -> Result<()> {
         smol::unblock(move || {
@@ -2270,6 +2310,13 @@ impl FakeFs {
         .unwrap();
     }
 
+    pub fn set_conflicting_commits(&self, dot_git: &Path, shas: impl IntoIterator<Item = String>) {
+        self.with_git_state(dot_git, true, |state| {
+            state.conflicting_commits = shas.into_iter().collect();
+        })
+        .unwrap();
+    }
+
     pub fn set_graph_error(&self, dot_git: &Path, error: Option<String>) {
         self.with_git_state(dot_git, true, |state| {
             state.simulated_graph_error = error;
@@ -2988,6 +3035,27 @@ impl Fs for FakeFs {
         self.load_internal(path).await
     }
 
+    async fn read_range(&self, path: &Path, offset: u64, len: usize) -> Result<Vec<u8>> {
+        let content = self.load_internal(path).await?;
+        let start = (offset as usize).min(content.len());
+        let end = start.saturating_add(len).min(content.len());
+        Ok(content[start..end].to_vec())
+    }
+
+    async fn write_ranges(&self, path: &Path, ranges: Vec<(u64, Arc<[u8]>)>) -> Result<()> {
+        let mut content = self.load_internal(path).await?;
+        for (offset, bytes) in ranges {
+            let start = offset as usize;
+            let end = start + bytes.len();
+            if content.len() < end {
+                content.resize(end, 0);
+            }
+            content[start..end].copy_from_slice(&bytes);
+        }
+        self.write_file_internal(normalize_path(path), content, false)?;
+        Ok(())
+    }
+
     async fn atomic_write(&self, path: PathBuf, data: String) -> Result<()> {
         self.simulate_random_delay().await;
         let path = normalize_path(path.as_path());
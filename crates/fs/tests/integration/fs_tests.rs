@@ -458,6 +458,35 @@ async fn test_realfs_atomic_write_non_existing_file(executor: BackgroundExecutor
     assert_eq!(content, "Hello");
 }
 
+#[gpui::test]
+async fn test_realfs_read_range(executor: BackgroundExecutor) {
+    let fs = RealFs::new(None, executor);
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("file.bin");
+    std::fs::write(&file, [0u8, 1, 2, 3, 4, 5, 6, 7]).unwrap();
+
+    assert_eq!(fs.read_range(&file, 2, 3).await.unwrap(), [2, 3, 4]);
+    assert_eq!(fs.read_range(&file, 6, 10).await.unwrap(), [6, 7]);
+    assert!(fs.read_range(&file, 20, 4).await.unwrap().is_empty());
+    assert!(
+        fs.read_range(&temp_dir.path().join("missing.bin"), 0, 4)
+            .await
+            .is_err()
+    );
+}
+
+#[gpui::test]
+async fn test_fake_fs_read_range(executor: BackgroundExecutor) {
+    let fs = FakeFs::new(executor);
+    fs.insert_file(path!("/root/file.bin"), vec![0, 1, 2, 3, 4, 5, 6, 7])
+        .await;
+
+    let file = Path::new(path!("/root/file.bin"));
+    assert_eq!(fs.read_range(file, 2, 3).await.unwrap(), [2, 3, 4]);
+    assert_eq!(fs.read_range(file, 6, 10).await.unwrap(), [6, 7]);
+    assert!(fs.read_range(file, 20, 4).await.unwrap().is_empty());
+}
+
 #[gpui::test]
 #[cfg(target_os = "windows")]
 async fn test_realfs_canonicalize(executor: BackgroundExecutor) {
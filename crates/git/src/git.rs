@@ -82,6 +82,34 @@ actions!(
         TrashUntrackedFiles,
         /// Undoes the last commit, keeping changes in the working directory.
         Uncommit,
+        /// Continues the rebase in progress after resolving conflicts.
+        ContinueRebase,
+        /// Skips the commit that stopped the rebase in progress.
+        SkipRebase,
+        /// Aborts the rebase in progress, restoring the original branch.
+        AbortRebase,
+        /// Continues the cherry-pick in progress after resolving conflicts.
+        ContinueCherryPick,
+        /// Skips the commit that stopped the cherry-pick in progress.
+        SkipCherryPick,
+        /// Aborts the cherry-pick in progress, restoring the original branch.
+        AbortCherryPick,
+        /// Continues the revert in progress after resolving conflicts.
+        ContinueRevert,
+        /// Skips the commit that stopped the revert in progress.
+        SkipRevert,
+        /// Aborts the revert in progress, restoring the original branch.
+        AbortRevert,
+        /// Marks the current checkout as good while bisecting.
+        BisectGood,
+        /// Marks the current checkout as bad while bisecting.
+        BisectBad,
+        /// Skips testing the current checkout while bisecting.
+        BisectSkip,
+        /// Tests each commit with a task until the first bad commit is found.
+        BisectRun,
+        /// Ends the bisect in progress, returning to the commit it started from.
+        BisectReset,
         /// Pushes commits to the remote repository.
         Push,
         /// Pushes commits to a specific remote branch.
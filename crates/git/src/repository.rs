@@ -583,6 +583,174 @@ pub enum ResetMode {
     /// Reset the branch pointer and index, leave worktree unchanged (this makes it look as though things that were
     /// committed are now unstaged).
     Mixed,
+    /// Reset the branch pointer, index and worktree, discarding all uncommitted changes.
+    Hard,
+}
+
+/// What an interactive rebase does with a commit.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum RebaseAction {
+    /// Use the commit as is.
+    Pick,
+    /// Use the commit, but replace its message.
+    Reword,
+    /// Meld the commit into the previous one, combining their messages.
+    Squash,
+    /// Meld the commit into the previous one, discarding its message.
+    Fixup,
+    /// Remove the commit.
+    Drop,
+}
+
+impl RebaseAction {
+    pub const ALL: [Self; 5] = [
+        Self::Pick,
+        Self::Reword,
+        Self::Squash,
+        Self::Fixup,
+        Self::Drop,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pick => "pick",
+            Self::Reword => "reword",
+            Self::Squash => "squash",
+            Self::Fixup => "fixup",
+            Self::Drop => "drop",
+        }
+    }
+
+    /// Whether the commit is melded into the one before it.
+    pub fn melds_into_previous(&self) -> bool {
+        matches!(self, Self::Squash | Self::Fixup)
+    }
+}
+
+/// A line of an interactive rebase's todo list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RebaseTodoEntry {
+    pub action: RebaseAction,
+    pub sha: SharedString,
+    pub subject: SharedString,
+    /// The full message of the commit before the rebase.
+    pub original_message: SharedString,
+    /// The new message of the commit, used when rewording it.
+    pub message: Option<SharedString>,
+}
+
+/// Controls a rebase that stopped, e.g. because of conflicts.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum RebaseControl {
+    /// Commit the resolved changes and resume the rebase.
+    Continue,
+    /// Skip the commit that stopped the rebase and resume.
+    Skip,
+    /// Stop the rebase and restore the branch to its original state.
+    Abort,
+}
+
+/// A git command that applies commits one at a time and can stop because of
+/// conflicts, to be resumed with [`RebaseControl`].
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum SequencerOperation {
+    CherryPick,
+    Revert,
+}
+
+impl SequencerOperation {
+    pub fn command(&self) -> &'static str {
+        match self {
+            Self::CherryPick => "cherry-pick",
+            Self::Revert => "revert",
+        }
+    }
+
+    /// The ref git writes while the operation is stopped at a commit.
+    pub fn head_ref(&self) -> &'static str {
+        match self {
+            Self::CherryPick => "CHERRY_PICK_HEAD",
+            Self::Revert => "REVERT_HEAD",
+        }
+    }
+}
+
+/// How a commit is marked while bisecting.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum BisectMark {
+    Good,
+    Bad,
+    Skip,
+}
+
+impl BisectMark {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Good => "good",
+            Self::Bad => "bad",
+            Self::Skip => "skip",
+        }
+    }
+}
+
+/// The commits marked so far in an in-progress bisect.
+#[derive(Debug, Clone, Default, Hash, PartialEq, Eq)]
+pub struct BisectState {
+    pub bad: Option<Oid>,
+    pub good: Vec<Oid>,
+    pub skipped: Vec<Oid>,
+    /// The commit that introduced the regression, once the bisect has
+    /// narrowed the range down to it.
+    pub first_bad: Option<Oid>,
+}
+
+impl BisectState {
+    /// Returns the commits that may still have introduced the regression:
+    /// those reachable from the bad commit but not from any good one, the bad
+    /// commit included. `commits` must list children before their parents,
+    /// as `git log` does.
+    pub fn remaining_commits<'a>(
+        &self,
+        commits: impl IntoIterator<Item = &'a InitialGraphCommitData>,
+    ) -> HashSet<Oid> {
+        let Some(bad) = self.bad else {
+            return HashSet::default();
+        };
+        if let Some(first_bad) = self.first_bad {
+            return HashSet::from_iter([first_bad]);
+        }
+
+        let mut reachable_from_bad = HashSet::from_iter([bad]);
+        let mut reachable_from_good = HashSet::from_iter(self.good.iter().copied());
+        let mut remaining = HashSet::default();
+        for commit in commits {
+            if reachable_from_good.contains(&commit.sha) {
+                reachable_from_good.extend(commit.parents.iter().copied());
+            } else if reachable_from_bad.contains(&commit.sha) {
+                reachable_from_bad.extend(commit.parents.iter().copied());
+                remaining.insert(commit.sha);
+            }
+        }
+        remaining
+    }
+}
+
+/// The versions of a conflicted file stored in the index while a merge is
+/// in progress. A version is `None` when the file doesn't exist in it, e.g.
+/// because it was added on only one side.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MergeStages {
+    pub base: Option<String>,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+}
+
+/// How far along an in-progress rebase is.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct RebaseProgress {
+    /// The 1-based index of the todo entry the rebase stopped at.
+    pub current: usize,
+    pub total: usize,
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
@@ -807,6 +975,12 @@ pub trait GitRepository: Send + Sync {
     ///
     /// Also returns `None` for symlinks.
     fn load_committed_text(&self, path: RepoPath) -> BoxFuture<'_, Option<String>>;
+
+    /// Returns the raw bytes of an entry in the repository's HEAD, for files that aren't text, such as images.
+    fn load_committed_bytes(&self, path: RepoPath) -> BoxFuture<'_, Option<Vec<u8>>>;
+
+    /// Returns the base, ours and theirs versions of a conflicted path, from stages 1 to 3 of the index.
+    fn load_merge_stages(&self, path: RepoPath) -> BoxFuture<'_, Result<MergeStages>>;
     fn load_blob_content(&self, oid: Oid) -> BoxFuture<'_, Result<String>>;
 
     fn set_index_text(
@@ -918,6 +1092,75 @@ pub trait GitRepository: Send + Sync {
         env: Arc<HashMap<String, String>>,
     ) -> BoxFuture<'_, Result<()>>;
 
+    /// Returns the commits an interactive rebase onto `base` would replay,
+    /// oldest first, as `pick` entries.
+    fn rebase_todo(&self, base: String) -> BoxFuture<'_, Result<Vec<RebaseTodoEntry>>>;
+
+    /// Replays the given todo list onto `base`. Stopping because of conflicts
+    /// is not an error; the rebase is left in progress to be resolved.
+    fn rebase(
+        &self,
+        base: String,
+        todo: Vec<RebaseTodoEntry>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    fn rebase_control(
+        &self,
+        control: RebaseControl,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Returns the progress of the interactive rebase in progress, if any.
+    fn rebase_progress(&self) -> BoxFuture<'_, Option<RebaseProgress>>;
+
+    /// Applies the changes of the given commits or commit ranges onto `HEAD`.
+    /// Stopping because of conflicts is not an error.
+    fn cherry_pick(
+        &self,
+        commits: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Creates a commit undoing the changes of the given commit. Stopping
+    /// because of conflicts is not an error.
+    fn revert(
+        &self,
+        commit: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    fn sequencer_control(
+        &self,
+        operation: SequencerOperation,
+        control: RebaseControl,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Starts bisecting between the given bad and good commits, checking out
+    /// the first commit to test.
+    fn bisect_start(
+        &self,
+        bad: String,
+        good: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Marks the given commit, or `HEAD` if none is given, and checks out the
+    /// next commit to test.
+    fn bisect_mark(
+        &self,
+        mark: BisectMark,
+        commit: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Ends the bisect and checks out the commit it started from.
+    fn bisect_reset(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>>;
+
+    /// Returns the state of the bisect in progress, if any.
+    fn bisect_state(&self) -> BoxFuture<'_, Option<BisectState>>;
+
     fn show(&self, commit: String) -> BoxFuture<'_, Result<CommitDetails>>;
 
     fn load_commit(&self, commit: String, cx: AsyncApp) -> BoxFuture<'_, Result<CommitDiff>>;
@@ -1539,6 +1782,7 @@ impl GitRepository for RealGitRepository {
             let mode_flag = match mode {
                 ResetMode::Mixed => "--mixed",
                 ResetMode::Soft => "--soft",
+                ResetMode::Hard => "--hard",
             };
 
             let output = git
@@ -1585,12 +1829,382 @@ impl GitRepository for RealGitRepository {
         .boxed()
     }
 
+    fn rebase_todo(&self, base: String) -> BoxFuture<'_, Result<Vec<RebaseTodoEntry>>> {
+        let git = self.git_binary_in_worktree();
+        self.executor
+            .spawn(async move {
+                let git = git?;
+                validate_rev(&base)?;
+                let output = git
+                    .run(&[
+                        "log",
+                        "--reverse",
+                        "--no-merges",
+                        "-z",
+                        "--format=%H%x00%s%x00%B",
+                        &format!("{base}..HEAD"),
+                    ])
+                    .await?;
+                Ok(parse_rebase_todo(&output))
+            })
+            .boxed()
+    }
+
+    fn rebase(
+        &self,
+        base: String,
+        todo: Vec<RebaseTodoEntry>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git = self.git_binary_in_worktree();
+        let git_binary_path = self.any_git_binary_path.clone();
+        let git_dir = self.path();
+        async move {
+            let git = git?;
+            validate_rev(&base)?;
+            // Each entry becomes a line of the todo list, so anything but a
+            // commit hash could add lines, e.g. `exec` ones.
+            for entry in &todo {
+                entry
+                    .sha
+                    .parse::<Oid>()
+                    .with_context(|| format!("invalid commit `{}` in rebase todo", entry.sha))?;
+            }
+            let messages_dir = git_dir.join(REBASE_MESSAGES_DIR);
+            let mut todo_text = String::new();
+            for (ix, entry) in todo.iter().enumerate() {
+                match (entry.action, &entry.message) {
+                    (RebaseAction::Reword, Some(message)) => {
+                        // Rewording through git's own editor would block, so the
+                        // commit is picked and then amended with the new message.
+                        let message_path = messages_dir.join(ix.to_string());
+                        smol::fs::create_dir_all(&messages_dir).await?;
+                        smol::fs::write(&message_path, message.as_bytes()).await?;
+                        todo_text.push_str(&format!(
+                            "pick {}\nexec {} commit --amend --only --no-verify --allow-empty -F {}\n",
+                            entry.sha,
+                            shell_quote(&git_binary_path.to_string_lossy()),
+                            shell_quote(&message_path.to_string_lossy()),
+                        ));
+                    }
+                    (action, _) => {
+                        todo_text.push_str(&format!("{} {}\n", action.as_str(), entry.sha));
+                    }
+                }
+            }
+            let todo_path = git_dir.join(REBASE_TODO_FILE);
+            smol::fs::write(&todo_path, todo_text).await?;
+
+            let output = git
+                .build_command(&["rebase", "--interactive", "--end-of-options", &base])
+                .envs(env.iter())
+                // Git appends the path of its todo list to the sequence editor
+                // command, so this replaces that list with ours.
+                .env("GIT_SEQUENCE_EDITOR", "cat \"$ZED_REBASE_TODO\" >")
+                .env("ZED_REBASE_TODO", &todo_path)
+                .env("GIT_EDITOR", "true")
+                .output()
+                .await;
+            smol::fs::remove_file(&todo_path).await.log_err();
+            let output = output?;
+
+            if !output.status.success() {
+                anyhow::ensure!(
+                    read_rebase_progress(&git_dir).await.is_some(),
+                    "Failed to rebase:\n{}",
+                    String::from_utf8_lossy(&output.stderr),
+                );
+            } else {
+                smol::fs::remove_dir_all(&messages_dir).await.ok();
+            }
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn rebase_control(
+        &self,
+        control: RebaseControl,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git = self.git_binary_in_worktree();
+        let git_dir = self.path();
+        async move {
+            let git = git?;
+            let flag = match control {
+                RebaseControl::Continue => "--continue",
+                RebaseControl::Skip => "--skip",
+                RebaseControl::Abort => "--abort",
+            };
+            let progress_before = read_rebase_progress(&git_dir).await;
+            let output = git
+                .build_command(&["rebase", flag])
+                .envs(env.iter())
+                .env("GIT_EDITOR", "true")
+                .output()
+                .await?;
+            let progress_after = read_rebase_progress(&git_dir).await;
+
+            // Stopping at a later commit because of new conflicts isn't a failure.
+            let stopped_at_later_commit = progress_before
+                .zip(progress_after)
+                .is_some_and(|(before, after)| after.current > before.current);
+            anyhow::ensure!(
+                output.status.success() || stopped_at_later_commit,
+                "Failed to {} rebase:\n{}",
+                flag.trim_start_matches('-'),
+                String::from_utf8_lossy(&output.stderr),
+            );
+            if progress_after.is_none() {
+                smol::fs::remove_dir_all(git_dir.join(REBASE_MESSAGES_DIR))
+                    .await
+                    .ok();
+            }
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn rebase_progress(&self) -> BoxFuture<'_, Option<RebaseProgress>> {
+        let git_dir = self.path();
+        self.executor
+            .spawn(async move { read_rebase_progress(&git_dir).await })
+            .boxed()
+    }
+
+    fn cherry_pick(
+        &self,
+        commits: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git = self.git_binary_in_worktree();
+        let git_dir = self.path();
+        async move {
+            let git = git?;
+            for commit in &commits {
+                validate_rev(commit)?;
+            }
+            let output = git
+                .build_command(&["cherry-pick", "--end-of-options"])
+                .args(&commits)
+                .envs(env.iter())
+                .env("GIT_EDITOR", "true")
+                .output()
+                .await?;
+            anyhow::ensure!(
+                output.status.success()
+                    || read_sequencer_head(&git_dir, SequencerOperation::CherryPick)
+                        .await
+                        .is_some(),
+                "Failed to cherry-pick:\n{}",
+                String::from_utf8_lossy(&output.stderr),
+            );
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn revert(
+        &self,
+        commit: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git = self.git_binary_in_worktree();
+        let git_dir = self.path();
+        async move {
+            let git = git?;
+            validate_rev(&commit)?;
+            let output = git
+                .build_command(&["revert", "--no-edit", "--end-of-options", &commit])
+                .envs(env.iter())
+                .output()
+                .await?;
+            anyhow::ensure!(
+                output.status.success()
+                    || read_sequencer_head(&git_dir, SequencerOperation::Revert)
+                        .await
+                        .is_some(),
+                "Failed to revert:\n{}",
+                String::from_utf8_lossy(&output.stderr),
+            );
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn sequencer_control(
+        &self,
+        operation: SequencerOperation,
+        control: RebaseControl,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git = self.git_binary_in_worktree();
+        let git_dir = self.path();
+        async move {
+            let git = git?;
+            let flag = match control {
+                RebaseControl::Continue => "--continue",
+                RebaseControl::Skip => "--skip",
+                RebaseControl::Abort => "--abort",
+            };
+            let head_before = read_sequencer_head(&git_dir, operation).await;
+            let output = git
+                .build_command(&[operation.command(), flag])
+                .envs(env.iter())
+                .env("GIT_EDITOR", "true")
+                .output()
+                .await?;
+            let head_after = read_sequencer_head(&git_dir, operation).await;
+
+            // Stopping at another commit because of new conflicts isn't a failure.
+            let stopped_at_other_commit = head_after.is_some() && head_after != head_before;
+            anyhow::ensure!(
+                output.status.success() || stopped_at_other_commit,
+                "Failed to {} {}:\n{}",
+                flag.trim_start_matches('-'),
+                operation.command(),
+                String::from_utf8_lossy(&output.stderr),
+            );
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn bisect_start(
+        &self,
+        bad: String,
+        good: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git = self.git_binary_in_worktree();
+        async move {
+            let git = git?;
+            // `git bisect` doesn't accept `--end-of-options`, so it's only
+            // given the hashes the revisions resolve to.
+            let bad = resolve_commit(&git, &bad).await?;
+            let mut good_commits = Vec::with_capacity(good.len());
+            for good in &good {
+                good_commits.push(resolve_commit(&git, good).await?);
+            }
+            let output = git
+                .build_command(&["bisect", "start", &bad])
+                .args(&good_commits)
+                .envs(env.iter())
+                .output()
+                .await?;
+            anyhow::ensure!(
+                output.status.success(),
+                "Failed to start bisect:\n{}",
+                String::from_utf8_lossy(&output.stderr),
+            );
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn bisect_mark(
+        &self,
+        mark: BisectMark,
+        commit: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git = self.git_binary_in_worktree();
+        async move {
+            let git = git?;
+            let commit = match commit {
+                Some(commit) => Some(resolve_commit(&git, &commit).await?),
+                None => None,
+            };
+            let output = git
+                .build_command(&["bisect", mark.as_str()])
+                .args(commit)
+                .envs(env.iter())
+                .output()
+                .await?;
+            anyhow::ensure!(
+                output.status.success(),
+                "Failed to mark commit as {}:\n{}",
+                mark.as_str(),
+                String::from_utf8_lossy(&output.stderr),
+            );
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn bisect_reset(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        let git = self.git_binary_in_worktree();
+        async move {
+            let git = git?;
+            let output = git
+                .build_command(&["bisect", "reset"])
+                .envs(env.iter())
+                .output()
+                .await?;
+            anyhow::ensure!(
+                output.status.success(),
+                "Failed to reset bisect:\n{}",
+                String::from_utf8_lossy(&output.stderr),
+            );
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn bisect_state(&self) -> BoxFuture<'_, Option<BisectState>> {
+        let git = self.git_binary_in_worktree();
+        let git_dir = self.path();
+        self.executor
+            .spawn(async move {
+                if smol::fs::metadata(git_dir.join("BISECT_START"))
+                    .await
+                    .is_err()
+                {
+                    return None;
+                }
+                let refs = git
+                    .log_err()?
+                    .run(&[
+                        "for-each-ref",
+                        "--format=%(refname) %(objectname)",
+                        "refs/bisect/",
+                    ])
+                    .await
+                    .log_err()?;
+                let log = smol::fs::read_to_string(git_dir.join("BISECT_LOG"))
+                    .await
+                    .unwrap_or_default();
+                Some(parse_bisect_state(&refs, &log))
+            })
+            .boxed()
+    }
+
     fn load_index_text(&self, path: RepoPath) -> BoxFuture<'_, Option<String>> {
         let git_binary = self.git_binary();
         let path_str = format!(":{}", path.as_unix_str());
         self.executor
             .spawn(async move {
-                let git = git_binary;
+                let git = git_binary;
+                let output = git
+                    .build_command(&["show", &path_str])
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::piped())
+                    .output()
+                    .await
+                    .log_err()?;
+                if !output.status.success() {
+                    return None;
+                }
+                String::from_utf8(output.stdout).ok()
+            })
+            .boxed()
+    }
+
+    fn load_committed_text(&self, path: RepoPath) -> BoxFuture<'_, Option<String>> {
+        let git = self.git_binary();
+        let path_str = format!("HEAD:{}", path.as_unix_str());
+        self.executor
+            .spawn(async move {
                 let output = git
                     .build_command(&["show", &path_str])
                     .stdout(Stdio::piped())
@@ -1606,7 +2220,7 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
-    fn load_committed_text(&self, path: RepoPath) -> BoxFuture<'_, Option<String>> {
+    fn load_committed_bytes(&self, path: RepoPath) -> BoxFuture<'_, Option<Vec<u8>>> {
         let git = self.git_binary();
         let path_str = format!("HEAD:{}", path.as_unix_str());
         self.executor
@@ -1618,10 +2232,35 @@ impl GitRepository for RealGitRepository {
                     .output()
                     .await
                     .log_err()?;
-                if !output.status.success() {
-                    return None;
+                output.status.success().then_some(output.stdout)
+            })
+            .boxed()
+    }
+
+    fn load_merge_stages(&self, path: RepoPath) -> BoxFuture<'_, Result<MergeStages>> {
+        let git = self.git_binary();
+        self.executor
+            .spawn(async move {
+                let mut stages = [None, None, None];
+                for (stage, text) in stages.iter_mut().enumerate() {
+                    let path_str = format!(":{}:{}", stage + 1, path.as_unix_str());
+                    let output = git
+                        .build_command(&["show", &path_str])
+                        .stdout(Stdio::piped())
+                        .stderr(Stdio::piped())
+                        .output()
+                        .await?;
+                    if output.status.success() {
+                        *text = Some(String::from_utf8(output.stdout)?);
+                    }
                 }
-                String::from_utf8(output.stdout).ok()
+                let [base, ours, theirs] = stages;
+                anyhow::ensure!(
+                    ours.is_some() || theirs.is_some(),
+                    "{} is not conflicted",
+                    path.as_unix_str()
+                );
+                Ok(MergeStages { base, ours, theirs })
             })
             .boxed()
     }
@@ -3421,6 +4060,108 @@ fn parse_initial_graph_output<'a>(
         .collect()
 }
 
+/// The todo list handed to git by the sequence editor, relative to the git directory.
+const REBASE_TODO_FILE: &str = "ZED_REBASE_TODO";
+/// Where the new messages of reworded commits are kept while rebasing,
+/// relative to the git directory.
+const REBASE_MESSAGES_DIR: &str = "zed-rebase-messages";
+
+/// Rejects revisions that git would parse as an option, or that aren't a
+/// single argument.
+pub fn validate_rev(rev: &str) -> Result<()> {
+    anyhow::ensure!(
+        !rev.is_empty()
+            && !rev.starts_with('-')
+            && !rev.chars().any(|c| c.is_whitespace() || c.is_control()),
+        "invalid revision `{rev}`"
+    );
+    Ok(())
+}
+
+/// Returns the hash of the commit a revision names, for commands that can't
+/// be told where their options end.
+async fn resolve_commit(git: &GitBinary, rev: &str) -> Result<String> {
+    validate_rev(rev)?;
+    git.run(&[
+        "rev-parse",
+        "--verify",
+        "--end-of-options",
+        &format!("{rev}^{{commit}}"),
+    ])
+    .await
+    .with_context(|| format!("invalid revision `{rev}`"))
+}
+
+fn parse_rebase_todo(output: &str) -> Vec<RebaseTodoEntry> {
+    // Messages can span lines, so each field is terminated by a NUL instead.
+    let fields = output.split('\0').collect::<Vec<_>>();
+    fields
+        .chunks_exact(3)
+        .map(|entry| RebaseTodoEntry {
+            action: RebaseAction::Pick,
+            sha: entry[0].to_string().into(),
+            subject: entry[1].to_string().into(),
+            original_message: entry[2].trim_end().to_string().into(),
+            message: None,
+        })
+        .collect()
+}
+
+async fn read_rebase_progress(git_dir: &Path) -> Option<RebaseProgress> {
+    let rebase_dir = git_dir.join(crate::REBASE_MERGE_DIR);
+    let current = smol::fs::read_to_string(rebase_dir.join("msgnum"))
+        .await
+        .ok()?;
+    let total = smol::fs::read_to_string(rebase_dir.join("end"))
+        .await
+        .ok()?;
+    Some(RebaseProgress {
+        current: current.trim().parse().ok()?,
+        total: total.trim().parse().ok()?,
+    })
+}
+
+async fn read_sequencer_head(git_dir: &Path, operation: SequencerOperation) -> Option<String> {
+    let head = smol::fs::read_to_string(git_dir.join(operation.head_ref()))
+        .await
+        .ok()?;
+    Some(head.trim().to_string())
+}
+
+/// Reads the marked commits from the output of `git for-each-ref refs/bisect/`,
+/// and the first bad commit from the bisect log once it has been found.
+fn parse_bisect_state(refs: &str, log: &str) -> BisectState {
+    let mut state = BisectState::default();
+    for line in refs.lines() {
+        let Some((ref_name, sha)) = line.split_once(' ') else {
+            continue;
+        };
+        let Some(sha) = sha.parse().log_err() else {
+            continue;
+        };
+        let Some(ref_name) = ref_name.strip_prefix("refs/bisect/") else {
+            continue;
+        };
+        if ref_name == "bad" {
+            state.bad = Some(sha);
+        } else if ref_name.starts_with("good-") {
+            state.good.push(sha);
+        } else if ref_name.starts_with("skip-") {
+            state.skipped.push(sha);
+        }
+    }
+    state.first_bad = log.lines().rev().find_map(|line| {
+        let sha = line.strip_prefix("# first bad commit: [")?;
+        sha.get(..sha.find(']')?)?.parse().ok()
+    });
+    state
+}
+
+/// Quotes an argument for the POSIX shell git uses to run `exec` todo lines.
+fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
 fn git_status_args(path_prefixes: &[RepoPath]) -> Vec<OsString> {
     let mut args = vec![
         OsString::from("status"),
@@ -5319,6 +6060,433 @@ mod tests {
         assert!(repo.check_for_pushed_commit().await.unwrap().is_empty());
     }
 
+    #[gpui::test]
+    async fn test_interactive_rebase(cx: &mut TestAppContext) {
+        disable_git_global_config();
+        cx.executor().allow_parking();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_dir = temp_dir.path().join("repo");
+        git_init_repo(&repo_dir);
+        let repo = RealGitRepository::new(
+            &repo_dir.join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(test_commit_envs());
+
+        git_command(&repo_dir, ["commit", "--allow-empty", "-m", "Base"]);
+        let base = repo.head_sha().await.unwrap();
+        for name in ["a", "b", "c"] {
+            fs::write(repo_dir.join(name), name).unwrap();
+            git_command(&repo_dir, ["add", name]);
+            git_command(
+                &repo_dir,
+                ["commit", "-m", &format!("Add {name}"), "-m", "Details"],
+            );
+        }
+
+        let todo = repo.rebase_todo(base.clone()).await.unwrap();
+        assert_eq!(todo[0].original_message.as_ref(), "Add a\n\nDetails");
+        assert_eq!(
+            todo.iter()
+                .map(|entry| (entry.action, entry.subject.as_ref()))
+                .collect::<Vec<_>>(),
+            [
+                (RebaseAction::Pick, "Add a"),
+                (RebaseAction::Pick, "Add b"),
+                (RebaseAction::Pick, "Add c"),
+            ]
+        );
+
+        // Entries and bases that would inject commands or options are rejected.
+        let injected = RebaseTodoEntry {
+            sha: format!("{}\nexec touch injected", todo[0].sha).into(),
+            ..todo[0].clone()
+        };
+        assert!(
+            repo.rebase(base.clone(), vec![injected], env.clone())
+                .await
+                .is_err()
+        );
+        assert!(
+            repo.rebase("--exec=touch injected".into(), todo.clone(), env.clone())
+                .await
+                .is_err()
+        );
+        assert!(repo.rebase_todo("--output=injected".into()).await.is_err());
+        assert!(!repo_dir.join("injected").exists());
+
+        // Reword the first commit, fold the last one into it and drop the second.
+        let [add_a, add_b, add_c] = <[RebaseTodoEntry; 3]>::try_from(todo).unwrap();
+        let todo = vec![
+            RebaseTodoEntry {
+                action: RebaseAction::Reword,
+                message: Some("Add a and c".into()),
+                ..add_a
+            },
+            RebaseTodoEntry {
+                action: RebaseAction::Fixup,
+                ..add_c
+            },
+            RebaseTodoEntry {
+                action: RebaseAction::Drop,
+                ..add_b
+            },
+        ];
+        repo.rebase(base.clone(), todo, env.clone()).await.unwrap();
+        assert_eq!(repo.rebase_progress().await, None);
+        assert_eq!(
+            repo.rebase_todo(base.clone())
+                .await
+                .unwrap()
+                .iter()
+                .map(|entry| entry.subject.as_ref())
+                .collect::<Vec<_>>(),
+            ["Add a and c"]
+        );
+        assert!(repo_dir.join("a").exists());
+        assert!(!repo_dir.join("b").exists());
+        assert!(repo_dir.join("c").exists());
+
+        // Reordering commits that touch the same lines stops with conflicts.
+        for content in ["first", "second"] {
+            fs::write(repo_dir.join("a"), content).unwrap();
+            git_command(&repo_dir, ["commit", "-am", content]);
+        }
+        let mut todo = repo.rebase_todo(base.clone()).await.unwrap();
+        todo.swap(1, 2);
+        repo.rebase(base.clone(), todo, env.clone()).await.unwrap();
+        assert_eq!(
+            repo.rebase_progress().await,
+            Some(RebaseProgress {
+                current: 2,
+                total: 3
+            })
+        );
+
+        // Continuing without resolving the conflicts fails.
+        assert!(
+            repo.rebase_control(RebaseControl::Continue, env.clone())
+                .await
+                .is_err()
+        );
+
+        repo.rebase_control(RebaseControl::Abort, env.clone())
+            .await
+            .unwrap();
+        assert_eq!(repo.rebase_progress().await, None);
+        assert_eq!(
+            repo.rebase_todo(base)
+                .await
+                .unwrap()
+                .iter()
+                .map(|entry| entry.subject.as_ref())
+                .collect::<Vec<_>>(),
+            ["Add a and c", "first", "second"]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_cherry_pick_revert_and_reset(cx: &mut TestAppContext) {
+        disable_git_global_config();
+        cx.executor().allow_parking();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_dir = temp_dir.path().join("repo");
+        git_init_repo(&repo_dir);
+        let repo = RealGitRepository::new(
+            &repo_dir.join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(test_commit_envs());
+        let subjects = async |base: &str| {
+            repo.rebase_todo(base.to_string())
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.subject.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        fs::write(repo_dir.join("file"), "base").unwrap();
+        git_command(&repo_dir, ["add", "file"]);
+        git_command(&repo_dir, ["commit", "-m", "Base"]);
+        let base = repo.head_sha().await.unwrap();
+        git_command(&repo_dir, ["checkout", "-b", "topic"]);
+        fs::write(repo_dir.join("file"), "topic").unwrap();
+        git_command(&repo_dir, ["commit", "-am", "Change file"]);
+        for name in ["a", "b"] {
+            fs::write(repo_dir.join(name), name).unwrap();
+            git_command(&repo_dir, ["add", name]);
+            git_command(&repo_dir, ["commit", "-m", &format!("Add {name}")]);
+        }
+        git_command(&repo_dir, ["checkout", "-"]);
+
+        // Cherry-picking a range of commits.
+        repo.cherry_pick(vec!["topic~2..topic".into()], env.clone())
+            .await
+            .unwrap();
+        assert_eq!(subjects(&base).await, ["Add a", "Add b"]);
+
+        // Reverting a commit.
+        repo.revert("HEAD~1".into(), env.clone()).await.unwrap();
+        assert_eq!(
+            subjects(&base).await,
+            ["Add a", "Add b", "Revert \"Add a\""]
+        );
+        assert!(!repo_dir.join("a").exists());
+
+        // Revisions that would be parsed as options are rejected.
+        assert!(
+            repo.cherry_pick(vec!["--no-commit".into()], env.clone())
+                .await
+                .is_err()
+        );
+        assert!(
+            repo.cherry_pick(vec!["HEAD~1".into(), "-n".into()], env.clone())
+                .await
+                .is_err()
+        );
+        assert!(
+            repo.revert("--no-commit".into(), env.clone())
+                .await
+                .is_err()
+        );
+        assert_eq!(
+            subjects(&base).await,
+            ["Add a", "Add b", "Revert \"Add a\""]
+        );
+
+        // Resetting hard discards the commits and the worktree changes.
+        fs::write(repo_dir.join("b"), "modified").unwrap();
+        repo.reset(base.clone(), ResetMode::Hard, env.clone())
+            .await
+            .unwrap();
+        assert!(subjects(&base).await.is_empty());
+        assert!(!repo_dir.join("b").exists());
+
+        // Cherry-picking a conflicting commit stops until the conflicts are resolved.
+        fs::write(repo_dir.join("file"), "main").unwrap();
+        git_command(&repo_dir, ["commit", "-am", "Change file on main"]);
+        repo.cherry_pick(vec!["topic~2".into(), "topic~1".into()], env.clone())
+            .await
+            .unwrap();
+        assert_eq!(subjects(&base).await, ["Change file on main"]);
+        assert!(
+            repo.sequencer_control(
+                SequencerOperation::CherryPick,
+                RebaseControl::Continue,
+                env.clone()
+            )
+            .await
+            .is_err()
+        );
+        fs::write(repo_dir.join("file"), "resolved").unwrap();
+        git_command(&repo_dir, ["add", "file"]);
+        repo.sequencer_control(
+            SequencerOperation::CherryPick,
+            RebaseControl::Continue,
+            env.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            subjects(&base).await,
+            ["Change file on main", "Change file", "Add a"]
+        );
+        assert!(!repo_dir.join(".git/CHERRY_PICK_HEAD").exists());
+    }
+
+    #[gpui::test]
+    async fn test_bisect(cx: &mut TestAppContext) {
+        disable_git_global_config();
+        cx.executor().allow_parking();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_dir = temp_dir.path().join("repo");
+        git_init_repo(&repo_dir);
+        let repo = RealGitRepository::new(
+            &repo_dir.join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(test_commit_envs());
+
+        for version in 1..=6 {
+            fs::write(repo_dir.join("version"), version.to_string()).unwrap();
+            git_command(&repo_dir, ["add", "version"]);
+            git_command(&repo_dir, ["commit", "-m", &format!("Version {version}")]);
+        }
+        let shas = repo
+            .revparse_batch(vec!["HEAD".into(), "HEAD~2".into(), "HEAD~5".into()])
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|sha| sha.unwrap().parse::<Oid>().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(repo.bisect_state().await, None);
+
+        // Revisions that would be parsed as options are rejected.
+        assert!(
+            repo.bisect_start(
+                "--no-checkout".into(),
+                vec![shas[2].to_string()],
+                env.clone()
+            )
+            .await
+            .is_err()
+        );
+        assert!(
+            repo.bisect_start(
+                shas[0].to_string(),
+                vec!["--first-parent".into()],
+                env.clone()
+            )
+            .await
+            .is_err()
+        );
+        assert_eq!(repo.bisect_state().await, None);
+
+        repo.bisect_start("HEAD".into(), vec![shas[2].to_string()], env.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            repo.bisect_state().await,
+            Some(BisectState {
+                bad: Some(shas[0]),
+                good: vec![shas[2]],
+                skipped: Vec::new(),
+                first_bad: None,
+            })
+        );
+
+        // Version 4 introduced the regression.
+        git_command(
+            &repo_dir,
+            ["bisect", "run", "sh", "-c", "test $(cat version) -lt 4"],
+        );
+        let state = repo.bisect_state().await.unwrap();
+        assert_eq!(state.first_bad, Some(shas[1]));
+
+        assert!(
+            repo.bisect_mark(BisectMark::Skip, Some("--term-good=x".into()), env.clone())
+                .await
+                .is_err()
+        );
+
+        // Marking the found commit as good contradicts the bisect.
+        assert!(
+            repo.bisect_mark(BisectMark::Good, None, env.clone())
+                .await
+                .is_err()
+        );
+
+        repo.bisect_reset(env.clone()).await.unwrap();
+        assert_eq!(repo.bisect_state().await, None);
+        assert_eq!(fs::read_to_string(repo_dir.join("version")).unwrap(), "6");
+    }
+
+    #[gpui::test]
+    async fn test_load_merge_stages(cx: &mut TestAppContext) {
+        disable_git_global_config();
+        cx.executor().allow_parking();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_dir = temp_dir.path().join("repo");
+        git_init_repo(&repo_dir);
+        let repo = RealGitRepository::new(
+            &repo_dir.join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(test_commit_envs());
+
+        fs::write(repo_dir.join("file"), "base\n").unwrap();
+        git_command(&repo_dir, ["add", "file"]);
+        git_command(&repo_dir, ["commit", "-m", "Base"]);
+        git_command(&repo_dir, ["checkout", "-b", "topic"]);
+        fs::write(repo_dir.join("file"), "theirs\n").unwrap();
+        fs::write(repo_dir.join("added"), "added\n").unwrap();
+        git_command(&repo_dir, ["add", "added"]);
+        git_command(&repo_dir, ["commit", "-am", "Change file on topic"]);
+        git_command(&repo_dir, ["checkout", "-"]);
+        fs::write(repo_dir.join("file"), "ours\n").unwrap();
+        fs::write(repo_dir.join("added"), "also added\n").unwrap();
+        git_command(&repo_dir, ["add", "added"]);
+        git_command(&repo_dir, ["commit", "-am", "Change file on main"]);
+
+        assert!(repo.load_merge_stages(repo_path("file")).await.is_err());
+        repo.cherry_pick(vec!["topic".into()], env).await.unwrap();
+        assert_eq!(
+            repo.load_merge_stages(repo_path("file")).await.unwrap(),
+            MergeStages {
+                base: Some("base\n".into()),
+                ours: Some("ours\n".into()),
+                theirs: Some("theirs\n".into()),
+            }
+        );
+        // Added on both sides, so there's no common version.
+        assert_eq!(
+            repo.load_merge_stages(repo_path("added")).await.unwrap(),
+            MergeStages {
+                base: None,
+                ours: Some("also added\n".into()),
+                theirs: Some("added\n".into()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_bisect_remaining_commits() {
+        let sha = |n: u8| Oid::from_bytes(&[n; 20]).unwrap();
+        let commit = |n: u8, parents: &[u8]| InitialGraphCommitData {
+            sha: sha(n),
+            parents: parents.iter().map(|parent| sha(*parent)).collect(),
+            ref_names: Vec::new(),
+        };
+        // 6 merges 5 into 4; 5 branched off from 2.
+        let commits = [
+            commit(6, &[4, 5]),
+            commit(5, &[2]),
+            commit(4, &[3]),
+            commit(3, &[2]),
+            commit(2, &[1]),
+            commit(1, &[]),
+        ];
+
+        let mut state = BisectState {
+            bad: Some(sha(6)),
+            good: vec![sha(3)],
+            ..Default::default()
+        };
+        assert_eq!(
+            state.remaining_commits(&commits),
+            HashSet::from_iter([sha(6), sha(5), sha(4)])
+        );
+
+        state.good.push(sha(5));
+        assert_eq!(
+            state.remaining_commits(&commits),
+            HashSet::from_iter([sha(6), sha(4)])
+        );
+
+        state.first_bad = Some(sha(4));
+        assert_eq!(
+            state.remaining_commits(&commits),
+            HashSet::from_iter([sha(4)])
+        );
+    }
+
     #[test]
     fn test_original_repo_path_from_common_dir() {
         // Normal repo: common_dir is <work_dir>/.git
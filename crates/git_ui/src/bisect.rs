@@ -0,0 +1,315 @@
+//! Guided `git bisect`: commits are marked from the git graph and the status
+//! bar, and a task can be used to test each commit automatically.
+
+use anyhow::anyhow;
+use git::{
+    BisectBad, BisectGood, BisectReset, BisectRun, BisectSkip, Oid,
+    repository::{BisectMark, BisectState},
+};
+use gpui::{
+    Action, Anchor, App, Context, Empty, Entity, IntoElement, ParentElement, Render, Subscription,
+    WeakEntity, Window,
+};
+use project::{
+    Project,
+    git_store::{GitStoreEvent, Repository, RepositoryEvent},
+};
+use task::{SpawnInTerminal, TaskContext, TaskId};
+use ui::{ButtonLike, ContextMenu, PopoverMenu, Tooltip, prelude::*};
+use util::shell_builder::ShellBuilder;
+use workspace::{HideStatusItem, StatusItemView, Workspace, item::ItemHandle};
+
+use crate::{commit_operations::report_failure, commit_view::CommitView, git_panel, picker_prompt};
+
+pub(crate) fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &BisectGood, _, cx| {
+        mark_active_checkout(workspace, BisectMark::Good, cx);
+    });
+    workspace.register_action(|workspace, _: &BisectBad, _, cx| {
+        mark_active_checkout(workspace, BisectMark::Bad, cx);
+    });
+    workspace.register_action(|workspace, _: &BisectSkip, _, cx| {
+        mark_active_checkout(workspace, BisectMark::Skip, cx);
+    });
+    workspace.register_action(|workspace, _: &BisectReset, _, cx| {
+        let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+            return;
+        };
+        let task = repository.update(cx, |repository, cx| repository.bisect_reset(cx));
+        report_failure(task, "bisect reset", workspace.weak_handle(), cx);
+    });
+    workspace.register_action(|workspace, _: &BisectRun, window, cx| {
+        run_task(workspace, window, cx);
+    });
+}
+
+/// Starts bisecting between the given bad and good commits.
+pub(crate) fn start(
+    repository: Entity<Repository>,
+    bad: Oid,
+    good: Oid,
+    workspace: WeakEntity<Workspace>,
+    cx: &mut App,
+) {
+    telemetry::event!("Git Bisect Started");
+    let task = repository.update(cx, |repository, cx| {
+        repository.bisect_start(bad.to_string(), vec![good.to_string()], cx)
+    });
+    report_failure(task, "bisect start", workspace, cx);
+}
+
+/// Marks the given commit, or the current checkout if none is given.
+pub(crate) fn mark(
+    repository: Entity<Repository>,
+    mark: BisectMark,
+    commit: Option<Oid>,
+    workspace: WeakEntity<Workspace>,
+    cx: &mut App,
+) {
+    let task = repository.update(cx, |repository, cx| {
+        repository.bisect_mark(mark, commit.map(|sha| sha.to_string()), cx)
+    });
+    let action = match mark {
+        BisectMark::Good => "bisect good",
+        BisectMark::Bad => "bisect bad",
+        BisectMark::Skip => "bisect skip",
+    };
+    report_failure(task, action, workspace, cx);
+}
+
+fn mark_active_checkout(workspace: &mut Workspace, bisect_mark: BisectMark, cx: &mut App) {
+    let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+        return;
+    };
+    mark(repository, bisect_mark, None, workspace.weak_handle(), cx);
+}
+
+/// Asks for a task to test each commit with, and lets `git bisect run` mark
+/// commits by the task's exit code. It runs in a terminal from the repository
+/// root, so its output is visible and it can be stopped like any other task.
+fn run_task(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+    let project = workspace.project().read(cx);
+    let Some(repository) = project.active_repository(cx) else {
+        return;
+    };
+    let Some(inventory) = project.task_store().read(cx).task_inventory().cloned() else {
+        return;
+    };
+    let repository_path = repository.read(cx).work_directory_abs_path.to_path_buf();
+    let worktree_id = project
+        .find_worktree(&repository_path, cx)
+        .map(|(worktree, _)| worktree.read(cx).id());
+    let is_windows = project.path_style(cx).is_windows();
+    let templates = inventory.read(cx).list_tasks(None, None, worktree_id, cx);
+    let task_context = TaskContext {
+        cwd: Some(repository_path.clone()),
+        ..TaskContext::default()
+    };
+
+    let workspace_handle = workspace.weak_handle();
+    cx.spawn_in(window, async move |_, cx| {
+        let tasks = templates
+            .await
+            .into_iter()
+            .filter_map(|(source_kind, template)| {
+                template.resolve_task(&source_kind.to_id_base(), &task_context)
+            })
+            .collect::<Vec<_>>();
+        if tasks.is_empty() {
+            if let Some(workspace) = workspace_handle.upgrade() {
+                cx.update(|_, cx| {
+                    git_panel::show_error_toast(
+                        workspace,
+                        "bisect run",
+                        anyhow!("No tasks are available to test commits with"),
+                        cx,
+                    )
+                })?;
+            }
+            return anyhow::Ok(());
+        }
+
+        let labels = tasks
+            .iter()
+            .map(|task| task.resolved_label.clone().into())
+            .collect();
+        let selection = cx.update(|window, cx| {
+            picker_prompt::prompt(
+                "Select a task that fails on bad commits…",
+                labels,
+                workspace_handle.clone(),
+                window,
+                cx,
+            )
+        })?;
+        let Some(task) = selection.await.and_then(|ix| tasks.into_iter().nth(ix)) else {
+            return Ok(());
+        };
+
+        let spawn = task.resolved;
+        let (program, args) = ShellBuilder::new(&spawn.shell, is_windows)
+            .non_interactive()
+            .build(spawn.command.clone(), &spawn.args);
+        let label = format!("git bisect run: {}", spawn.label);
+        let args = ["bisect".to_string(), "run".to_string(), program]
+            .into_iter()
+            .chain(args)
+            .collect::<Vec<_>>();
+        let spawn = SpawnInTerminal {
+            id: TaskId(format!("git-bisect-run-{}", spawn.id.0)),
+            full_label: label.clone(),
+            label,
+            command_label: format!("git {}", args.join(" ")),
+            command: Some("git".to_string()),
+            args,
+            cwd: Some(repository_path),
+            show_rerun: false,
+            ..spawn
+        };
+        workspace_handle.update_in(cx, |workspace, window, cx| {
+            telemetry::event!("Git Bisect Run");
+            workspace.spawn_in_terminal(spawn, window, cx).detach();
+        })?;
+        Ok(())
+    })
+    .detach_and_log_err(cx);
+}
+
+/// A status bar item shown while bisecting the active repository, to mark
+/// the current checkout and end the bisect.
+pub struct BisectStatus {
+    project: Entity<Project>,
+    workspace: WeakEntity<Workspace>,
+    _subscription: Subscription,
+}
+
+impl BisectStatus {
+    pub fn new(workspace: &Workspace, cx: &mut Context<Self>) -> Self {
+        let project = workspace.project().clone();
+        let git_store = project.read(cx).git_store().clone();
+        let subscription = cx.subscribe(&git_store, |_, _, event, cx| {
+            if matches!(
+                event,
+                GitStoreEvent::ActiveRepositoryChanged(_)
+                    | GitStoreEvent::RepositoryUpdated(
+                        _,
+                        RepositoryEvent::StatusesChanged | RepositoryEvent::HeadChanged,
+                        _
+                    )
+            ) {
+                cx.notify();
+            }
+        });
+
+        Self {
+            project,
+            workspace: workspace.weak_handle(),
+            _subscription: subscription,
+        }
+    }
+
+    fn render_menu(
+        repository: WeakEntity<Repository>,
+        state: &BisectState,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Entity<ContextMenu> {
+        let first_bad = state.first_bad;
+        ContextMenu::build(window, cx, move |menu, _, _| {
+            menu.map(|menu| match first_bad {
+                Some(first_bad) => menu.entry("View First Bad Commit", None, {
+                    let repository = repository.clone();
+                    let workspace = workspace.clone();
+                    move |window, cx| {
+                        CommitView::open(
+                            first_bad.to_string(),
+                            repository.clone(),
+                            workspace.clone(),
+                            None,
+                            None,
+                            window,
+                            cx,
+                        );
+                    }
+                }),
+                None => menu
+                    .action("Mark as Good", BisectGood.boxed_clone())
+                    .action("Mark as Bad", BisectBad.boxed_clone())
+                    .action("Skip", BisectSkip.boxed_clone())
+                    .separator()
+                    .action("Run Task on Each Commit…", BisectRun.boxed_clone()),
+            })
+            .separator()
+            .action("End Bisect", BisectReset.boxed_clone())
+        })
+    }
+}
+
+impl Render for BisectStatus {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let Some(repository) = self.project.read(cx).active_repository(cx) else {
+            return Empty.into_any_element();
+        };
+        let Some(state) = repository.read(cx).merge.bisect.clone() else {
+            return Empty.into_any_element();
+        };
+
+        let (label, tooltip): (SharedString, _) = match state.first_bad {
+            Some(first_bad) => (
+                format!("First Bad Commit: {}", first_bad.display_short()).into(),
+                "Bisect Finished",
+            ),
+            None => match &repository.read(cx).head_commit {
+                Some(head) => (
+                    format!("Bisecting at {}", head.sha.get(..7).unwrap_or(&head.sha)).into(),
+                    "Mark the Current Checkout",
+                ),
+                None => ("Bisecting".into(), "Mark the Current Checkout"),
+            },
+        };
+
+        let repository = repository.downgrade();
+        let workspace = self.workspace.clone();
+        PopoverMenu::new("bisect-status-menu")
+            .trigger_with_tooltip(
+                ButtonLike::new("bisect-status").child(
+                    h_flex()
+                        .gap_1()
+                        .child(
+                            Icon::new(IconName::Crosshair)
+                                .size(IconSize::Small)
+                                .color(Color::Muted),
+                        )
+                        .child(Label::new(label).size(LabelSize::Small)),
+                ),
+                Tooltip::text(tooltip),
+            )
+            .menu(move |window, cx| {
+                Some(Self::render_menu(
+                    repository.clone(),
+                    &state,
+                    workspace.clone(),
+                    window,
+                    cx,
+                ))
+            })
+            .anchor(Anchor::BottomLeft)
+            .into_any_element()
+    }
+}
+
+impl StatusItemView for BisectStatus {
+    fn set_active_pane_item(
+        &mut self,
+        _: Option<&dyn ItemHandle>,
+        _window: &mut Window,
+        _: &mut Context<Self>,
+    ) {
+    }
+
+    fn hide_setting(&self, _: &App) -> Option<HideStatusItem> {
+        // Only shown while a bisect is in progress.
+        None
+    }
+}
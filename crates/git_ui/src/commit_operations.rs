@@ -0,0 +1,91 @@
+//! Operations that apply or undo existing commits, shared by the git graph
+//! and the commit view. Cherry-picks and reverts that stop because of
+//! conflicts are left in progress, to be resolved through the conflict view.
+
+use futures::channel::oneshot;
+use git::repository::ResetMode;
+use gpui::{App, Entity, PromptLevel, WeakEntity, Window};
+use project::git_store::Repository;
+use workspace::Workspace;
+
+use crate::git_panel::show_error_toast;
+
+pub(crate) fn cherry_pick(
+    repository: Entity<Repository>,
+    commits: Vec<String>,
+    workspace: WeakEntity<Workspace>,
+    cx: &mut App,
+) {
+    telemetry::event!("Git Cherry-Picked", commit_count = commits.len());
+    let task = repository.update(cx, |repository, cx| repository.cherry_pick(commits, cx));
+    report_failure(task, "cherry-pick", workspace, cx);
+}
+
+pub(crate) fn revert(
+    repository: Entity<Repository>,
+    commit: String,
+    workspace: WeakEntity<Workspace>,
+    cx: &mut App,
+) {
+    telemetry::event!("Git Reverted");
+    let task = repository.update(cx, |repository, cx| repository.revert(commit, cx));
+    report_failure(task, "revert", workspace, cx);
+}
+
+/// Resets the current branch to the given commit, asking for confirmation
+/// first when the reset discards uncommitted changes.
+pub(crate) fn reset(
+    repository: Entity<Repository>,
+    commit: String,
+    mode: ResetMode,
+    workspace: WeakEntity<Workspace>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let confirmation = match mode {
+        ResetMode::Hard => {
+            let short_sha = commit.get(..7).unwrap_or(&commit);
+            Some(window.prompt(
+                PromptLevel::Warning,
+                &format!("Hard reset to {short_sha}?"),
+                Some("All uncommitted changes will be lost."),
+                &["Reset", "Cancel"],
+                cx,
+            ))
+        }
+        ResetMode::Soft | ResetMode::Mixed => None,
+    };
+
+    window
+        .spawn(cx, async move |cx| {
+            if let Some(confirmation) = confirmation
+                && confirmation.await != Ok(0)
+            {
+                return;
+            }
+            cx.update(|_, cx| {
+                let task =
+                    repository.update(cx, |repository, cx| repository.reset(commit, mode, cx));
+                report_failure(task, "reset", workspace, cx);
+            })
+            .ok();
+        })
+        .detach();
+}
+
+pub(crate) fn report_failure(
+    task: oneshot::Receiver<anyhow::Result<()>>,
+    action: &'static str,
+    workspace: WeakEntity<Workspace>,
+    cx: &mut App,
+) {
+    cx.spawn(async move |cx| {
+        let result = task.await.map_err(anyhow::Error::from).and_then(|r| r);
+        if let Err(error) = result
+            && let Some(workspace) = workspace.upgrade()
+        {
+            cx.update(|cx| show_error_toast(workspace, action, error, cx));
+        }
+    })
+    .detach();
+}
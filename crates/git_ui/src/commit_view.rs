@@ -6,15 +6,15 @@ use editor::{
     hover_markdown_style, multibuffer_context_lines,
 };
 use futures_lite::future::yield_now;
-use git::repository::{CommitDetails, CommitDiff, RepoPath, is_binary_content};
+use git::repository::{CommitDetails, CommitDiff, RepoPath, ResetMode, is_binary_content};
 use git::status::{FileStatus, StatusCode, TrackedStatus};
 use git::{
     BuildCommitPermalinkParams, GitHostingProviderRegistry, GitRemote, ParsedGitRemote,
     parse_git_remote_url,
 };
 use gpui::{
-    AnyElement, App, AppContext as _, AsyncWindowContext, ClipboardItem, Context, Entity,
-    EventEmitter, FocusHandle, Focusable, InteractiveElement, IntoElement, ParentElement,
+    Action, Anchor, AnyElement, App, AppContext as _, AsyncWindowContext, ClipboardItem, Context,
+    Entity, EventEmitter, FocusHandle, Focusable, InteractiveElement, IntoElement, ParentElement,
     PromptLevel, Render, ScrollHandle, StatefulInteractiveElement as _, Styled, Task, WeakEntity,
     Window, actions,
 };
@@ -33,7 +33,9 @@ use std::{
     sync::Arc,
 };
 use theme::ActiveTheme;
-use ui::{ContextMenu, DiffStat, Disclosure, Divider, Tooltip, WithScrollbar, prelude::*};
+use ui::{
+    ContextMenu, DiffStat, Disclosure, Divider, PopoverMenu, Tooltip, WithScrollbar, prelude::*,
+};
 use util::{ResultExt, paths::PathStyle, rel_path::RelPath, truncate_and_trailoff};
 use workspace::item::TabTooltipContent;
 use workspace::{
@@ -45,6 +47,7 @@ use workspace::{
     searchable::SearchableItemHandle,
 };
 
+use crate::commit_operations;
 use crate::commit_tooltip::CommitAvatar;
 use crate::git_panel::GitPanel;
 
@@ -55,6 +58,10 @@ actions!(
         PopCurrentStash,
         DropCurrentStash,
         OpenFileAtHead,
+        /// Applies the changes of the commit in the active commit view onto the current branch.
+        CherryPickCurrentCommit,
+        /// Creates a commit undoing the changes of the commit in the active commit view.
+        RevertCurrentCommit,
     ]
 );
 
@@ -551,6 +558,35 @@ impl CommitView {
         self.open_file_at_head(&file, window, cx);
     }
 
+    fn cherry_pick(&mut self, _: &CherryPickCurrentCommit, _: &mut Window, cx: &mut Context<Self>) {
+        commit_operations::cherry_pick(
+            self.repository.clone(),
+            vec![self.commit.sha.to_string()],
+            self.workspace.clone(),
+            cx,
+        );
+    }
+
+    fn revert(&mut self, _: &RevertCurrentCommit, _: &mut Window, cx: &mut Context<Self>) {
+        commit_operations::revert(
+            self.repository.clone(),
+            self.commit.sha.to_string(),
+            self.workspace.clone(),
+            cx,
+        );
+    }
+
+    fn reset(&mut self, mode: ResetMode, window: &mut Window, cx: &mut Context<Self>) {
+        commit_operations::reset(
+            self.repository.clone(),
+            self.commit.sha.to_string(),
+            mode,
+            self.workspace.clone(),
+            window,
+            cx,
+        );
+    }
+
     fn render_header(&self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let commit = &self.commit;
         let author_name = commit.author_name.clone();
@@ -1239,6 +1275,10 @@ impl Render for CommitView {
         v_flex()
             .key_context(if is_stash { "StashDiff" } else { "CommitDiff" })
             .on_action(cx.listener(Self::open_file_at_head_action))
+            .when(!is_stash, |this| {
+                this.on_action(cx.listener(Self::cherry_pick))
+                    .on_action(cx.listener(Self::revert))
+            })
             .size_full()
             .bg(cx.theme().colors().editor_background)
             .child(self.render_header(window, cx))
@@ -1343,7 +1383,52 @@ impl Render for CommitViewToolbar {
                         .tooltip(Tooltip::text(format!("View on {}", provider_name)))
                         .on_click(move |_, _, cx| cx.open_url(&url))
                 }))
+                .child(Self::render_commit_actions_menu(commit_view.clone()))
+            })
+    }
+}
+
+impl CommitViewToolbar {
+    fn render_commit_actions_menu(commit_view: Entity<CommitView>) -> impl IntoElement {
+        PopoverMenu::new("commit-actions-menu")
+            .trigger_with_tooltip(
+                IconButton::new("commit-actions-menu-trigger", IconName::Ellipsis)
+                    .icon_size(IconSize::Small),
+                Tooltip::text("Commit Actions"),
+            )
+            .menu(move |window, cx| {
+                let focus_handle = commit_view.focus_handle(cx);
+                let commit_view = commit_view.clone();
+                Some(ContextMenu::build(window, cx, move |menu, window, _| {
+                    menu.context(focus_handle)
+                        .action("Cherry-Pick", CherryPickCurrentCommit.boxed_clone())
+                        .action("Revert", RevertCurrentCommit.boxed_clone())
+                        .separator()
+                        .header("Reset to This Commit")
+                        .entry(
+                            "Soft",
+                            None,
+                            window.handler_for(&commit_view, |this, window, cx| {
+                                this.reset(ResetMode::Soft, window, cx)
+                            }),
+                        )
+                        .entry(
+                            "Mixed",
+                            None,
+                            window.handler_for(&commit_view, |this, window, cx| {
+                                this.reset(ResetMode::Mixed, window, cx)
+                            }),
+                        )
+                        .entry(
+                            "Hard",
+                            None,
+                            window.handler_for(&commit_view, |this, window, cx| {
+                                this.reset(ResetMode::Hard, window, cx)
+                            }),
+                        )
+                }))
             })
+            .anchor(Anchor::TopRight)
     }
 }
 
@@ -5,14 +5,15 @@ use editor::{
     Editor, MultiBuffer, RowHighlightOptions,
     display_map::{BlockContext, BlockPlacement, BlockProperties, BlockStyle, CustomBlockId},
 };
+use git::repository::SequencerOperation;
 use gpui::{
-    App, ClickEvent, Context, Empty, Entity, InteractiveElement as _, ParentElement as _,
+    Action, App, ClickEvent, Context, Empty, Entity, InteractiveElement as _, ParentElement as _,
     Subscription, Task, WeakEntity,
 };
 use language::{Anchor, Buffer, BufferId};
 use project::{
     ConflictRegion, ConflictSet, ConflictSetUpdate, Project,
-    git_store::{GitStore, GitStoreEvent, RepositoryEvent},
+    git_store::{GitStore, GitStoreEvent, RepositoryEvent, RepositorySnapshot},
 };
 use settings::Settings;
 use std::{ops::Range, sync::Arc};
@@ -23,6 +24,8 @@ use zed_actions::agent::{
     ConflictContent, ResolveConflictedFilesWithAgent, ResolveConflictsWithAgent,
 };
 
+use crate::merge_editor;
+
 pub(crate) struct ConflictAddon {
     buffers: HashMap<BufferId, BufferConflicts>,
 }
@@ -231,6 +234,15 @@ fn conflicts_updated(
         editor.remove_blocks(removed_block_ids, None, cx);
     }
 
+    let stopped_operation = editor.project().and_then(|project| {
+        let (repository, _) = project
+            .read(cx)
+            .git_store()
+            .read(cx)
+            .repository_and_path_for_buffer_id(buffer_id, cx)?;
+        StoppedOperation::for_repository(&repository.read(cx).snapshot())
+    });
+
     // Add new highlights and blocks
     let editor_handle = cx.weak_entity();
     let new_conflicts = &conflict_set.conflicts[event.new_range.clone()];
@@ -249,7 +261,9 @@ fn conflicts_updated(
             style: BlockStyle::Sticky,
             render: Arc::new({
                 let conflict = conflict.clone();
-                move |cx| render_conflict_buttons(&conflict, editor_handle.clone(), cx)
+                move |cx| {
+                    render_conflict_buttons(&conflict, stopped_operation, editor_handle.clone(), cx)
+                }
             }),
             priority: 0,
         })
@@ -317,8 +331,59 @@ fn update_conflict_highlighting(
     Some(())
 }
 
+/// A git operation that stopped because of conflicts, which can be resumed
+/// or abandoned once they are resolved.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum StoppedOperation {
+    Rebase,
+    CherryPick,
+    Revert,
+}
+
+impl StoppedOperation {
+    fn for_repository(snapshot: &RepositorySnapshot) -> Option<Self> {
+        if snapshot.merge.rebase.is_some() {
+            return Some(Self::Rebase);
+        }
+        match snapshot.merge.sequencer? {
+            SequencerOperation::CherryPick => Some(Self::CherryPick),
+            SequencerOperation::Revert => Some(Self::Revert),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Rebase => "Rebase",
+            Self::CherryPick => "Cherry-Pick",
+            Self::Revert => "Revert",
+        }
+    }
+
+    /// The actions that continue, skip and abort the operation.
+    fn actions(&self) -> [Box<dyn Action>; 3] {
+        match self {
+            Self::Rebase => [
+                git::ContinueRebase.boxed_clone(),
+                git::SkipRebase.boxed_clone(),
+                git::AbortRebase.boxed_clone(),
+            ],
+            Self::CherryPick => [
+                git::ContinueCherryPick.boxed_clone(),
+                git::SkipCherryPick.boxed_clone(),
+                git::AbortCherryPick.boxed_clone(),
+            ],
+            Self::Revert => [
+                git::ContinueRevert.boxed_clone(),
+                git::SkipRevert.boxed_clone(),
+                git::AbortRevert.boxed_clone(),
+            ],
+        }
+    }
+}
+
 fn render_conflict_buttons(
     conflict: &ConflictRegion,
+    stopped_operation: Option<StoppedOperation>,
     editor: WeakEntity<Editor>,
     cx: &mut BlockContext,
 ) -> AnyElement {
@@ -388,6 +453,56 @@ fn render_conflict_buttons(
                     }
                 }),
         )
+        .child(
+            Button::new("open-merge-editor", "Open Merge Editor")
+                .label_size(LabelSize::Small)
+                .on_click({
+                    let editor = editor.clone();
+                    let buffer_id = conflict.ours.end.buffer_id;
+                    move |_, window, cx| {
+                        let Some(editor) = editor.upgrade() else {
+                            return;
+                        };
+                        let Some(buffer) = editor.read(cx).buffer().read(cx).buffer(buffer_id)
+                        else {
+                            return;
+                        };
+                        if let Some(workspace) = editor.read(cx).workspace() {
+                            workspace.update(cx, |workspace, cx| {
+                                merge_editor::open_for_buffer(buffer, workspace, window, cx)
+                            });
+                        }
+                    }
+                }),
+        )
+        .when_some(stopped_operation, |this, operation| {
+            let [continue_action, skip_action, abort_action] = operation.actions();
+            this.child(Divider::vertical())
+                .child(
+                    Button::new(
+                        "continue-operation",
+                        format!("Continue {}", operation.name()),
+                    )
+                    .label_size(LabelSize::Small)
+                    .on_click(move |_, window, cx| {
+                        window.dispatch_action(continue_action.boxed_clone(), cx)
+                    }),
+                )
+                .child(
+                    Button::new("skip-operation", "Skip Commit")
+                        .label_size(LabelSize::Small)
+                        .on_click(move |_, window, cx| {
+                            window.dispatch_action(skip_action.boxed_clone(), cx)
+                        }),
+                )
+                .child(
+                    Button::new("abort-operation", format!("Abort {}", operation.name()))
+                        .label_size(LabelSize::Small)
+                        .on_click(move |_, window, cx| {
+                            window.dispatch_action(abort_action.boxed_clone(), cx)
+                        }),
+                )
+        })
         .when(is_ai_enabled, |this| {
             this.child(Divider::vertical()).child(
                 Button::new("resolve-with-agent", "Resolve with Agent")
@@ -1,9 +1,11 @@
 use crate::{
+    bisect, commit_operations,
     commit_tooltip::{CommitAvatar, CommitDetails, CommitTooltip},
     commit_view::CommitView,
     git_status_icon,
+    interactive_rebase::InteractiveRebase,
 };
-use collections::{BTreeMap, HashMap, IndexSet};
+use collections::{BTreeMap, HashMap, HashSet, IndexSet};
 use editor::Editor;
 use file_icons::FileIcons;
 use git::{
@@ -11,8 +13,8 @@ use git::{
     commit::ParsedCommitMessage,
     parse_git_remote_url,
     repository::{
-        CommitDiff, CommitFile, InitialGraphCommitData, LogOrder, LogSource, RepoPath,
-        SearchCommitArgs,
+        BisectMark, BisectState, CommitDiff, CommitFile, InitialGraphCommitData, LogOrder,
+        LogSource, RepoPath, ResetMode, SearchCommitArgs,
     },
     status::{FileStatus, StatusCode, TrackedStatus},
 };
@@ -616,6 +618,12 @@ actions!(
         ScrollDown,
         /// Toggles the selected commit's changed files between flat and tree views.
         ToggleChangedFilesView,
+        /// Interactively rebases the current branch onto the selected commit.
+        RebaseOntoCommit,
+        /// Applies the changes of the selected commit onto the current branch.
+        CherryPickCommit,
+        /// Creates a commit undoing the changes of the selected commit.
+        RevertCommit,
     ]
 );
 
@@ -824,6 +832,14 @@ struct CommitEntry {
 
 type ActiveLaneIdx = usize;
 
+/// The rows of the commits still in range of the bisect in progress, cached
+/// for the bisect state and number of loaded commits they were computed for.
+struct BisectHighlights {
+    state: BisectState,
+    commit_count: usize,
+    rows: Rc<HashSet<usize>>,
+}
+
 enum AllCommitCount {
     NotLoaded,
     Loading(usize),
@@ -1333,6 +1349,11 @@ pub struct GitGraph {
     column_widths: Entity<RedistributableColumnsState>,
     selected_entry_idx: Option<usize>,
     hovered_entry_idx: Option<usize>,
+    /// The commit marked as the oldest end of a range to cherry-pick.
+    cherry_pick_range_start: Option<Oid>,
+    /// The commit marked as bad, to start a bisect once a good one is picked.
+    bisect_bad_commit: Option<Oid>,
+    bisect_highlights: Option<BisectHighlights>,
     graph_canvas_bounds: Rc<Cell<Option<Bounds<Pixels>>>>,
     log_source: LogSource,
     log_order: LogOrder,
@@ -1354,6 +1375,7 @@ impl GitGraph {
         self.search_state.selected_index = None;
         self.search_state.state.next_state();
         self.context_menu = None;
+        self.bisect_highlights = None;
         cx.emit(ItemEvent::Edit);
         cx.notify();
     }
@@ -1556,6 +1578,9 @@ impl GitGraph {
             column_widths,
             selected_entry_idx: None,
             hovered_entry_idx: None,
+            cherry_pick_range_start: None,
+            bisect_bad_commit: None,
+            bisect_highlights: None,
             graph_canvas_bounds: Rc::new(Cell::new(None)),
             selected_commit_diff: None,
             selected_commit_diff_stats: None,
@@ -2318,6 +2343,183 @@ impl GitGraph {
         );
     }
 
+    fn open_interactive_rebase(
+        &mut self,
+        entry_index: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(commit_entry) = self.graph_data.commits.get(entry_index) else {
+            return;
+        };
+        let Some(repository) = self.get_repository(cx) else {
+            return;
+        };
+        let base = SharedString::from(commit_entry.data.sha.to_string());
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.toggle_modal(window, cx, |window, cx| {
+                    InteractiveRebase::new(repository, base, window, cx)
+                });
+            })
+            .ok();
+    }
+
+    fn cherry_pick(&mut self, entry_index: usize, cx: &mut Context<Self>) {
+        let Some(commit) = self.graph_data.commits.get(entry_index) else {
+            return;
+        };
+        let Some(repository) = self.get_repository(cx) else {
+            return;
+        };
+        commit_operations::cherry_pick(
+            repository,
+            vec![commit.data.sha.to_string()],
+            self.workspace.clone(),
+            cx,
+        );
+    }
+
+    /// Cherry-picks the commits between the marked range start and the given
+    /// entry, both included, oldest first.
+    fn cherry_pick_range(&mut self, range_start: Oid, entry_index: usize, cx: &mut Context<Self>) {
+        let Some(start_index) = self
+            .graph_data
+            .commits
+            .iter()
+            .position(|commit| commit.data.sha == range_start)
+        else {
+            return;
+        };
+        let Some(repository) = self.get_repository(cx) else {
+            return;
+        };
+        // Commits are listed newest first.
+        let range =
+            &self.graph_data.commits[start_index.min(entry_index)..=start_index.max(entry_index)];
+        let (Some(newest), Some(oldest)) = (range.first(), range.last()) else {
+            return;
+        };
+        let revisions = if oldest.data.parents.is_empty() {
+            // A root commit has no parent to start the range from, so the
+            // newest commit's ancestors within the range are listed instead.
+            let mut ancestors = HashSet::from_iter([newest.data.sha]);
+            let mut revisions = Vec::new();
+            for commit in range {
+                if ancestors.contains(&commit.data.sha) {
+                    ancestors.extend(commit.data.parents.iter().copied());
+                    revisions.push(commit.data.sha.to_string());
+                }
+            }
+            revisions.reverse();
+            revisions
+        } else {
+            vec![format!("{}^..{}", oldest.data.sha, newest.data.sha)]
+        };
+        self.cherry_pick_range_start = None;
+        commit_operations::cherry_pick(repository, revisions, self.workspace.clone(), cx);
+    }
+
+    /// Returns the rows of the commits the bisect in progress has narrowed
+    /// down to, and the color to highlight them with.
+    fn bisect_highlights(&mut self, cx: &mut Context<Self>) -> Option<(Rc<HashSet<usize>>, Hsla)> {
+        let state = self.get_repository(cx)?.read(cx).merge.bisect.clone()?;
+        let commit_count = self.graph_data.commits.len();
+        let is_stale = self.bisect_highlights.as_ref().is_none_or(|highlights| {
+            highlights.state != state || highlights.commit_count != commit_count
+        });
+        if is_stale {
+            let remaining =
+                state.remaining_commits(self.graph_data.commits.iter().map(|commit| &*commit.data));
+            let rows = self
+                .graph_data
+                .commits
+                .iter()
+                .enumerate()
+                .filter(|(_, commit)| remaining.contains(&commit.data.sha))
+                .map(|(row, _)| row)
+                .collect();
+            self.bisect_highlights = Some(BisectHighlights {
+                state,
+                commit_count,
+                rows: Rc::new(rows),
+            });
+        }
+
+        let highlights = self.bisect_highlights.as_ref()?;
+        let color = if highlights.state.first_bad.is_some() {
+            cx.theme().status().error_background
+        } else {
+            cx.theme().status().warning_background
+        };
+        Some((highlights.rows.clone(), color.opacity(0.25)))
+    }
+
+    fn bisect_mark(&mut self, entry_index: usize, mark: BisectMark, cx: &mut Context<Self>) {
+        let Some(commit) = self.graph_data.commits.get(entry_index) else {
+            return;
+        };
+        let Some(repository) = self.get_repository(cx) else {
+            return;
+        };
+        bisect::mark(
+            repository,
+            mark,
+            Some(commit.data.sha),
+            self.workspace.clone(),
+            cx,
+        );
+    }
+
+    fn start_bisect(&mut self, bad: Oid, good_entry_index: usize, cx: &mut Context<Self>) {
+        let Some(good) = self.graph_data.commits.get(good_entry_index) else {
+            return;
+        };
+        let Some(repository) = self.get_repository(cx) else {
+            return;
+        };
+        self.bisect_bad_commit = None;
+        bisect::start(repository, bad, good.data.sha, self.workspace.clone(), cx);
+    }
+
+    fn revert(&mut self, entry_index: usize, cx: &mut Context<Self>) {
+        let Some(commit) = self.graph_data.commits.get(entry_index) else {
+            return;
+        };
+        let Some(repository) = self.get_repository(cx) else {
+            return;
+        };
+        commit_operations::revert(
+            repository,
+            commit.data.sha.to_string(),
+            self.workspace.clone(),
+            cx,
+        );
+    }
+
+    fn reset(
+        &mut self,
+        entry_index: usize,
+        mode: ResetMode,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(commit) = self.graph_data.commits.get(entry_index) else {
+            return;
+        };
+        let Some(repository) = self.get_repository(cx) else {
+            return;
+        };
+        commit_operations::reset(
+            repository,
+            commit.data.sha.to_string(),
+            mode,
+            self.workspace.clone(),
+            window,
+            cx,
+        );
+    }
+
     fn copy_commit_sha(&mut self, entry_index: usize, cx: &mut Context<Self>) {
         let Some(commit) = self.graph_data.commits.get(entry_index) else {
             return;
@@ -2486,6 +2688,11 @@ impl GitGraph {
 
         let focus_handle = self.focus_handle.clone();
         let git_graph = cx.entity();
+        let range_start = self.cherry_pick_range_start;
+        let bisect_bad_commit = self.bisect_bad_commit;
+        let is_bisecting = self
+            .get_repository(cx)
+            .is_some_and(|repository| repository.read(cx).merge.bisect.is_some());
         let context_menu = ContextMenu::build(window, cx, |context_menu, window, _| {
             context_menu
                 .context(focus_handle)
@@ -2556,6 +2763,111 @@ impl GitGraph {
                         }
                     })
                 })
+                .separator()
+                .entry(
+                    "Cherry-Pick",
+                    Some(CherryPickCommit.boxed_clone()),
+                    window.handler_for(&git_graph, move |this, _window, cx| {
+                        this.cherry_pick(index, cx);
+                    }),
+                )
+                .map(|menu| match range_start {
+                    Some(range_start) if range_start != sha => menu.entry(
+                        format!("Cherry-Pick Range from {}", range_start.display_short()),
+                        None,
+                        window.handler_for(&git_graph, move |this, _window, cx| {
+                            this.cherry_pick_range(range_start, index, cx);
+                        }),
+                    ),
+                    _ => menu.entry(
+                        "Mark as Start of Cherry-Pick Range",
+                        None,
+                        window.handler_for(&git_graph, move |this, _window, cx| {
+                            this.cherry_pick_range_start = Some(sha);
+                            cx.notify();
+                        }),
+                    ),
+                })
+                .entry(
+                    "Revert",
+                    Some(RevertCommit.boxed_clone()),
+                    window.handler_for(&git_graph, move |this, _window, cx| {
+                        this.revert(index, cx);
+                    }),
+                )
+                .submenu("Reset to Here", {
+                    let git_graph = git_graph.clone();
+                    move |menu, window, _| {
+                        [
+                            ("Soft", ResetMode::Soft),
+                            ("Mixed", ResetMode::Mixed),
+                            ("Hard", ResetMode::Hard),
+                        ]
+                        .into_iter()
+                        .fold(menu, |menu, (label, mode)| {
+                            menu.entry(
+                                label,
+                                None,
+                                window.handler_for(&git_graph, move |this, window, cx| {
+                                    this.reset(index, mode, window, cx);
+                                }),
+                            )
+                        })
+                    }
+                })
+                .entry(
+                    "Interactive Rebase onto Here",
+                    Some(RebaseOntoCommit.boxed_clone()),
+                    window.handler_for(&git_graph, move |this, window, cx| {
+                        this.open_interactive_rebase(index, window, cx);
+                    }),
+                )
+                .submenu("Bisect", {
+                    let git_graph = git_graph.clone();
+                    move |menu, window, _| {
+                        if is_bisecting {
+                            return [
+                                ("Mark as Good", BisectMark::Good),
+                                ("Mark as Bad", BisectMark::Bad),
+                                ("Skip", BisectMark::Skip),
+                            ]
+                            .into_iter()
+                            .fold(menu, |menu, (label, mark)| {
+                                menu.entry(
+                                    label,
+                                    None,
+                                    window.handler_for(&git_graph, move |this, _window, cx| {
+                                        this.bisect_mark(index, mark, cx);
+                                    }),
+                                )
+                            });
+                        }
+
+                        menu.entry(
+                            "Mark as Bad to Start Bisect",
+                            None,
+                            window.handler_for(&git_graph, move |this, _window, cx| {
+                                this.bisect_bad_commit = Some(sha);
+                                cx.notify();
+                            }),
+                        )
+                        .when_some(
+                            bisect_bad_commit.filter(|bad| *bad != sha),
+                            |menu, bad| {
+                                menu.entry(
+                                    format!(
+                                        "Start Bisect as Good, with {} as Bad",
+                                        bad.display_short()
+                                    ),
+                                    None,
+                                    window.handler_for(&git_graph, move |this, _window, cx| {
+                                        this.start_bisect(bad, index, cx);
+                                    }),
+                                )
+                            },
+                        )
+                    }
+                })
                 .map(|mut menu| {
                     menu = menu.separator().header("Custom Commands");
 
@@ -3240,7 +3552,12 @@ impl GitGraph {
             .into_any_element()
     }
 
-    fn render_graph_canvas(&self, window: &Window, cx: &mut Context<GitGraph>) -> impl IntoElement {
+    fn render_graph_canvas(
+        &self,
+        bisect_highlights: Option<(Rc<HashSet<usize>>, Hsla)>,
+        window: &Window,
+        cx: &mut Context<GitGraph>,
+    ) -> impl IntoElement {
         let row_height = Self::row_height(window, cx);
         let visible_row_count = self.visible_row_count(window, cx);
         let table_state = self.table_interaction_state.read(cx);
@@ -3311,26 +3628,31 @@ impl GitGraph {
                         let is_selected = selected_entry_idx == Some(absolute_row_idx);
                         let is_context_menu_target =
                             context_menu_entry_idx == Some(absolute_row_idx);
+                        let bisect_bg = bisect_highlights
+                            .as_ref()
+                            .filter(|(rows, _)| rows.contains(&absolute_row_idx))
+                            .map(|(_, color)| *color);
+
+                        let bg_color = if is_selected || is_context_menu_target {
+                            selected_bg
+                        } else if is_hovered {
+                            hover_bg
+                        } else if let Some(bisect_bg) = bisect_bg {
+                            bisect_bg
+                        } else {
+                            continue;
+                        };
 
-                        if is_hovered || is_selected || is_context_menu_target {
-                            let row_y = bounds.origin.y + visible_row_idx as f32 * row_height
-                                - vertical_scroll_offset;
-
-                            let row_bounds = Bounds::new(
-                                point(bounds.origin.x, row_y),
-                                gpui::Size {
-                                    width: bounds.size.width,
-                                    height: row_height,
-                                },
-                            );
-
-                            let bg_color = if is_selected || is_context_menu_target {
-                                selected_bg
-                            } else {
-                                hover_bg
-                            };
-                            window.paint_quad(gpui::fill(row_bounds, bg_color));
-                        }
+                        let row_y = bounds.origin.y + visible_row_idx as f32 * row_height
+                            - vertical_scroll_offset;
+                        let row_bounds = Bounds::new(
+                            point(bounds.origin.x, row_y),
+                            gpui::Size {
+                                width: bounds.size.width,
+                                height: row_height,
+                            },
+                        );
+                        window.paint_quad(gpui::fill(row_bounds, bg_color));
                     }
 
                     for (row_idx, row) in rows.into_iter().enumerate() {
@@ -3734,6 +4056,7 @@ impl Render for GitGraph {
             self.search(query, cx);
         }
         let (commit_count, is_loading) = self.commit_count_and_loading_state(cx);
+        let bisect_highlights = self.bisect_highlights(cx);
 
         let error = self.get_repository(cx).and_then(|repo| {
             repo.read(cx)
@@ -3841,11 +4164,11 @@ impl Render for GitGraph {
                                 .size_full()
                                 .overflow_hidden()
                                 .cursor_pointer()
-                                .child(
-                                    div()
-                                        .size_full()
-                                        .child(self.render_graph_canvas(window, cx)),
-                                )
+                                .child(div().size_full().child(self.render_graph_canvas(
+                                    bisect_highlights.clone(),
+                                    window,
+                                    cx,
+                                )))
                                 .on_scroll_wheel(cx.listener(Self::handle_graph_scroll))
                                 .on_mouse_move(cx.listener(Self::handle_graph_mouse_move))
                                 .on_click(cx.listener(Self::handle_graph_click))
@@ -3884,8 +4207,14 @@ impl Render for GitGraph {
                                         cx.theme().colors().element_hover
                                     };
 
+                                    let bisect_bg = bisect_highlights
+                                        .as_ref()
+                                        .filter(|(rows, _)| rows.contains(&index))
+                                        .map(|(_, color)| *color);
+
                                     row.h(row_height)
                                         .cursor_pointer()
+                                        .when_some(bisect_bg, |row, bisect_bg| row.bg(bisect_bg))
                                         .when(is_selected || is_context_menu_target, |row| {
                                             row.bg(selected_bg)
                                         })
@@ -4006,6 +4335,21 @@ impl Render for GitGraph {
                 this.open_selected_commit_view(window, cx);
             }))
             .on_action(cx.listener(Self::copy_selected_commit_sha))
+            .on_action(cx.listener(|this, _: &RebaseOntoCommit, window, cx| {
+                if let Some(selected_entry_index) = this.selected_entry_idx {
+                    this.open_interactive_rebase(selected_entry_index, window, cx);
+                }
+            }))
+            .on_action(cx.listener(|this, _: &CherryPickCommit, _window, cx| {
+                if let Some(selected_entry_index) = this.selected_entry_idx {
+                    this.cherry_pick(selected_entry_index, cx);
+                }
+            }))
+            .on_action(cx.listener(|this, _: &RevertCommit, _window, cx| {
+                if let Some(selected_entry_index) = this.selected_entry_idx {
+                    this.revert(selected_entry_index, cx);
+                }
+            }))
             .on_action(cx.listener(Self::copy_selected_commit_tag))
             .on_action(cx.listener(Self::cancel))
             .on_action(cx.listener(|this, _: &FocusSearch, window, cx| {
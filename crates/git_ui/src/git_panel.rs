@@ -24,8 +24,9 @@ use git::Oid;
 use git::commit::ParsedCommitMessage;
 use git::repository::{
     Branch, CommitData, CommitDetails, CommitOptions, CommitSummary, DiffType, FetchOptions,
-    GitCommitTemplate, GitCommitter, LogOrder, LogSource, PushOptions, Remote, RemoteCommandOutput,
-    ResetMode, Upstream, UpstreamTracking, UpstreamTrackingStatus, get_git_committer,
+    GitCommitTemplate, GitCommitter, LogOrder, LogSource, PushOptions, RebaseControl, Remote,
+    RemoteCommandOutput, ResetMode, SequencerOperation, Upstream, UpstreamTracking,
+    UpstreamTrackingStatus, get_git_committer,
 };
 use git::stash::GitStash;
 use git::status::{DiffStat, StageStatus};
@@ -2401,6 +2402,55 @@ impl GitPanel {
         .detach();
     }
 
+    pub(crate) fn sequencer_control(
+        &mut self,
+        operation: SequencerOperation,
+        control: RebaseControl,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(active_repository) = self.active_repository.clone() else {
+            return;
+        };
+
+        cx.spawn(async move |this, cx| {
+            let result = active_repository
+                .update(cx, |repo, cx| {
+                    repo.sequencer_control(operation, control, cx)
+                })
+                .await;
+            this.update(cx, |this, cx| {
+                match result {
+                    Ok(Ok(())) => {}
+                    Ok(Err(e)) => this.show_error_toast(operation.command(), e, cx),
+                    Err(e) => this.show_error_toast(operation.command(), e.into(), cx),
+                }
+                cx.notify();
+            })
+        })
+        .detach();
+    }
+
+    pub(crate) fn rebase_control(&mut self, control: RebaseControl, cx: &mut Context<Self>) {
+        let Some(active_repository) = self.active_repository.clone() else {
+            return;
+        };
+
+        cx.spawn(async move |this, cx| {
+            let result = active_repository
+                .update(cx, |repo, cx| repo.rebase_control(control, cx))
+                .await;
+            this.update(cx, |this, cx| {
+                match result {
+                    Ok(Ok(())) => {}
+                    Ok(Err(e)) => this.show_error_toast("rebase", e, cx),
+                    Err(e) => this.show_error_toast("rebase", e.into(), cx),
+                }
+                cx.notify();
+            })
+        })
+        .detach();
+    }
+
     pub fn commit_message_buffer(&self, cx: &App) -> Entity<Buffer> {
         self.commit_editor
             .read(cx)
@@ -12,12 +12,15 @@ mod blame_ui;
 pub mod clone;
 
 use git::{
-    repository::{Branch, CommitDetails, Upstream, UpstreamTracking, UpstreamTrackingStatus},
+    repository::{
+        Branch, CommitDetails, RebaseControl, SequencerOperation, Upstream, UpstreamTracking,
+        UpstreamTrackingStatus,
+    },
     status::{FileStatus, StatusCode, UnmergedStatus, UnmergedStatusCode},
 };
 use gpui::{
-    App, ClipboardItem, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
-    SharedString, Subscription, Task, TaskExt, WeakEntity, Window,
+    Action, App, ClipboardItem, Context, DismissEvent, Entity, EventEmitter, FocusHandle,
+    Focusable, SharedString, Subscription, Task, TaskExt, WeakEntity, Window,
 };
 use menu::{Cancel, Confirm};
 use project::git_store::Repository;
@@ -38,8 +41,10 @@ use crate::{
 };
 
 mod askpass_modal;
+mod bisect;
 pub mod branch_picker;
 mod commit_modal;
+mod commit_operations;
 pub mod commit_tooltip;
 pub mod commit_view;
 mod conflict_view;
@@ -50,6 +55,8 @@ pub mod git_panel;
 mod git_panel_settings;
 pub mod git_picker;
 mod git_runtime_diagnostics;
+pub mod interactive_rebase;
+pub mod merge_editor;
 pub mod multi_diff_view;
 pub mod picker_prompt;
 pub mod project_diff;
@@ -62,6 +69,7 @@ pub mod worktree_names;
 pub mod worktree_picker;
 pub mod worktree_service;
 
+pub use bisect::BisectStatus;
 pub use blame_ui::GitBlameStatus;
 pub use conflict_view::MergeConflictIndicator;
 
@@ -95,6 +103,8 @@ pub fn init(cx: &mut App) {
         git_panel::register(workspace);
         repository_selector::register(workspace);
         git_picker::register(workspace);
+        bisect::register(workspace);
+        merge_editor::register(workspace);
 
         workspace.register_action(
             |workspace, action: &zed_actions::CreateWorktree, window, cx| {
@@ -273,6 +283,60 @@ pub fn init(cx: &mut App) {
                 panel.uncommit(window, cx);
             })
         });
+        workspace.register_action(|workspace, _: &git::ContinueRebase, _window, cx| {
+            let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
+                return;
+            };
+            panel.update(cx, |panel, cx| {
+                panel.rebase_control(RebaseControl::Continue, cx);
+            });
+        });
+        workspace.register_action(|workspace, _: &git::SkipRebase, _window, cx| {
+            let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
+                return;
+            };
+            panel.update(cx, |panel, cx| {
+                panel.rebase_control(RebaseControl::Skip, cx);
+            });
+        });
+        workspace.register_action(|workspace, _: &git::AbortRebase, _window, cx| {
+            let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
+                return;
+            };
+            panel.update(cx, |panel, cx| {
+                panel.rebase_control(RebaseControl::Abort, cx);
+            });
+        });
+        register_sequencer_control::<git::ContinueCherryPick>(
+            workspace,
+            SequencerOperation::CherryPick,
+            RebaseControl::Continue,
+        );
+        register_sequencer_control::<git::SkipCherryPick>(
+            workspace,
+            SequencerOperation::CherryPick,
+            RebaseControl::Skip,
+        );
+        register_sequencer_control::<git::AbortCherryPick>(
+            workspace,
+            SequencerOperation::CherryPick,
+            RebaseControl::Abort,
+        );
+        register_sequencer_control::<git::ContinueRevert>(
+            workspace,
+            SequencerOperation::Revert,
+            RebaseControl::Continue,
+        );
+        register_sequencer_control::<git::SkipRevert>(
+            workspace,
+            SequencerOperation::Revert,
+            RebaseControl::Skip,
+        );
+        register_sequencer_control::<git::AbortRevert>(
+            workspace,
+            SequencerOperation::Revert,
+            RebaseControl::Abort,
+        );
         workspace.register_action(|workspace, _action: &git::Init, window, cx| {
             let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
                 return;
@@ -369,6 +433,21 @@ fn file_diff_entry(
         })
 }
 
+fn register_sequencer_control<A: Action>(
+    workspace: &mut Workspace,
+    operation: SequencerOperation,
+    control: RebaseControl,
+) {
+    workspace.register_action(move |workspace, _: &A, _window, cx| {
+        let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
+            return;
+        };
+        panel.update(cx, |panel, cx| {
+            panel.sequencer_control(operation, control, cx);
+        });
+    });
+}
+
 fn open_modified_files(
     workspace: &mut Workspace,
     window: &mut Window,
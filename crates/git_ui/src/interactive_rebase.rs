@@ -0,0 +1,508 @@
+use std::ops::Range;
+
+use editor::Editor;
+use git::repository::{RebaseAction, RebaseTodoEntry};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, KeyContext,
+    ListSizingBehavior, Render, ScrollStrategy, SharedString, Task, UniformListScrollHandle,
+    Window, actions, uniform_list,
+};
+use project::git_store::Repository;
+use ui::{ListItem, ListItemSpacing, prelude::*};
+use workspace::ModalView;
+
+actions!(
+    interactive_rebase,
+    [
+        /// Moves the selected commit up in the rebase todo list.
+        MoveCommitUp,
+        /// Moves the selected commit down in the rebase todo list.
+        MoveCommitDown,
+        /// Keeps the selected commit as is.
+        PickCommit,
+        /// Keeps the selected commit and edits its message.
+        RewordCommit,
+        /// Melds the selected commit into the previous one, combining their messages.
+        SquashCommit,
+        /// Melds the selected commit into the previous one, discarding its message.
+        FixupCommit,
+        /// Removes the selected commit.
+        DropCommit,
+    ]
+);
+
+/// A modal for reordering, rewording, squashing and dropping the commits
+/// between a base commit and `HEAD` before running an interactive rebase.
+pub struct InteractiveRebase {
+    repository: Entity<Repository>,
+    base: SharedString,
+    entries: Option<Vec<RebaseTodoEntry>>,
+    selected_index: usize,
+    message_editor: Entity<Editor>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    error: Option<SharedString>,
+    pending_rebase: Option<Task<()>>,
+}
+
+impl InteractiveRebase {
+    pub fn new(
+        repository: Entity<Repository>,
+        base: SharedString,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let message_editor = cx.new(|cx| {
+            let mut editor = Editor::auto_height(1, 4, window, cx);
+            editor.set_placeholder_text("Commit message…", window, cx);
+            editor
+        });
+        cx.subscribe(&message_editor, |this, editor, event, cx| {
+            if let editor::EditorEvent::BufferEdited = event
+                && let Some(entry) = this.selected_entry_mut()
+                && entry.action == RebaseAction::Reword
+            {
+                // Git keeps the original message of commits that weren't
+                // given a new one.
+                let text = editor.read(cx).text(cx);
+                entry.message = (text != entry.original_message.as_ref()).then(|| text.into());
+            }
+        })
+        .detach();
+
+        let todo = repository.update(cx, |repository, _| repository.rebase_todo(base.to_string()));
+        cx.spawn_in(window, async move |this, cx| {
+            let result = todo.await;
+            this.update_in(cx, |this, window, cx| {
+                match result {
+                    Ok(Ok(entries)) => {
+                        this.selected_index = entries.len().saturating_sub(1);
+                        this.entries = Some(entries);
+                        this.sync_message_editor(window, cx);
+                    }
+                    Ok(Err(error)) => this.error = Some(error.to_string().into()),
+                    Err(error) => this.error = Some(error.to_string().into()),
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+
+        let focus_handle = cx.focus_handle();
+        window.focus(&focus_handle, cx);
+
+        Self {
+            repository,
+            base,
+            entries: None,
+            selected_index: 0,
+            message_editor,
+            focus_handle,
+            scroll_handle: UniformListScrollHandle::new(),
+            error: None,
+            pending_rebase: None,
+        }
+    }
+
+    fn selected_entry_mut(&mut self) -> Option<&mut RebaseTodoEntry> {
+        self.entries.as_mut()?.get_mut(self.selected_index)
+    }
+
+    fn sync_message_editor(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self
+            .entries
+            .as_ref()
+            .and_then(|entries| entries.get(self.selected_index))
+        else {
+            return;
+        };
+        let message = entry
+            .message
+            .clone()
+            .unwrap_or_else(|| entry.original_message.clone());
+        self.message_editor.update(cx, |editor, cx| {
+            editor.set_text(message, window, cx);
+        });
+    }
+
+    fn select(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        self.selected_index = index;
+        self.scroll_handle
+            .scroll_to_item(index, ScrollStrategy::Center);
+        self.sync_message_editor(window, cx);
+        cx.notify();
+    }
+
+    fn select_previous(
+        &mut self,
+        _: &menu::SelectPrevious,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.selected_index > 0 {
+            self.select(self.selected_index - 1, window, cx);
+        }
+    }
+
+    fn select_next(&mut self, _: &menu::SelectNext, window: &mut Window, cx: &mut Context<Self>) {
+        let count = self.entries.as_ref().map_or(0, Vec::len);
+        if self.selected_index + 1 < count {
+            self.select(self.selected_index + 1, window, cx);
+        }
+    }
+
+    fn move_selected(&mut self, up: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entries) = self.entries.as_mut() else {
+            return;
+        };
+        let index = self.selected_index;
+        let target = if up {
+            index.checked_sub(1)
+        } else {
+            Some(index + 1).filter(|target| *target < entries.len())
+        };
+        if let Some(target) = target {
+            entries.swap(index, target);
+            self.error = None;
+            self.select(target, window, cx);
+        }
+    }
+
+    fn set_action(
+        &mut self,
+        index: usize,
+        action: RebaseAction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(entry) = self
+            .entries
+            .as_mut()
+            .and_then(|entries| entries.get_mut(index))
+        else {
+            return;
+        };
+        entry.action = action;
+        if action != RebaseAction::Reword {
+            entry.message = None;
+        }
+        self.error = None;
+        if index == self.selected_index {
+            self.sync_message_editor(window, cx);
+            if action == RebaseAction::Reword {
+                window.focus(&self.message_editor.focus_handle(cx), cx);
+            }
+        }
+        cx.notify();
+    }
+
+    fn set_selected_action(
+        &mut self,
+        action: RebaseAction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.set_action(self.selected_index, action, window, cx);
+    }
+
+    fn cycle_action(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self.entries.as_ref().and_then(|entries| entries.get(index)) else {
+            return;
+        };
+        let position = RebaseAction::ALL
+            .iter()
+            .position(|action| *action == entry.action)
+            .unwrap_or(0);
+        let action = RebaseAction::ALL[(position + 1) % RebaseAction::ALL.len()];
+        self.selected_index = index;
+        self.set_action(index, action, window, cx);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if self.pending_rebase.is_some() {
+            return;
+        }
+        let Some(entries) = self.entries.clone() else {
+            return;
+        };
+        if let Err(error) = validate_todo(&entries) {
+            self.error = Some(error);
+            cx.notify();
+            return;
+        }
+
+        let rebase = self.repository.update(cx, |repository, cx| {
+            repository.rebase(self.base.to_string(), entries, cx)
+        });
+        self.pending_rebase = Some(cx.spawn_in(window, async move |this, cx| {
+            let result = rebase.await;
+            this.update(cx, |this, cx| {
+                this.pending_rebase = None;
+                match result {
+                    Ok(Ok(())) => cx.emit(DismissEvent),
+                    Ok(Err(error)) => this.error = Some(error.to_string().into()),
+                    Err(error) => this.error = Some(error.to_string().into()),
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn dispatch_context(&self, window: &Window, cx: &Context<Self>) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("InteractiveRebase");
+        let identifier = if self.message_editor.focus_handle(cx).is_focused(window) {
+            "editing"
+        } else {
+            "not_editing"
+        };
+        dispatch_context.add(identifier);
+        dispatch_context
+    }
+
+    fn render_entries(&self, range: Range<usize>, cx: &mut Context<Self>) -> Vec<AnyElement> {
+        let Some(entries) = self.entries.as_ref() else {
+            return Vec::new();
+        };
+        range
+            .filter_map(|index| {
+                let entry = entries.get(index)?;
+                let is_dropped = entry.action == RebaseAction::Drop;
+                let subject = match (&entry.action, &entry.message) {
+                    (RebaseAction::Reword, Some(message)) => message
+                        .lines()
+                        .next()
+                        .unwrap_or_default()
+                        .to_string()
+                        .into(),
+                    _ => entry.subject.clone(),
+                };
+                Some(
+                    ListItem::new(index)
+                        .inset(true)
+                        .spacing(ListItemSpacing::Sparse)
+                        .toggle_state(index == self.selected_index)
+                        .start_slot(
+                            Button::new(("rebase-action", index), entry.action.as_str())
+                                .label_size(LabelSize::Small)
+                                .width(rems(4.5))
+                                .on_click(cx.listener(move |this, _, window, cx| {
+                                    this.cycle_action(index, window, cx);
+                                })),
+                        )
+                        .child(
+                            h_flex()
+                                .w_full()
+                                .gap_2()
+                                .child(
+                                    Label::new(entry.sha.chars().take(7).collect::<String>())
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted)
+                                        .buffer_font(cx),
+                                )
+                                .child(Label::new(subject).truncate().when(is_dropped, |label| {
+                                    label.strikethrough().color(Color::Disabled)
+                                })),
+                        )
+                        .on_click(cx.listener(move |this, _, window, cx| {
+                            window.focus(&this.focus_handle, cx);
+                            this.select(index, window, cx);
+                        }))
+                        .into_any_element(),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Checks that the todo list can be applied, i.e. that the first commit that
+/// is kept doesn't have to be melded into a previous one.
+fn validate_todo(entries: &[RebaseTodoEntry]) -> Result<(), SharedString> {
+    if entries.is_empty() {
+        return Err("There are no commits to rebase".into());
+    }
+    let first_kept = entries
+        .iter()
+        .find(|entry| entry.action != RebaseAction::Drop);
+    if let Some(entry) = first_kept
+        && entry.action.melds_into_previous()
+    {
+        return Err(format!(
+            "Cannot {} the first commit, as there is no previous commit to meld it into",
+            entry.action.as_str()
+        )
+        .into());
+    }
+    Ok(())
+}
+
+impl ModalView for InteractiveRebase {}
+
+impl EventEmitter<DismissEvent> for InteractiveRebase {}
+
+impl Focusable for InteractiveRebase {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for InteractiveRebase {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let entry_count = self.entries.as_ref().map_or(0, Vec::len);
+        let is_rewording = self
+            .entries
+            .as_ref()
+            .and_then(|entries| entries.get(self.selected_index))
+            .is_some_and(|entry| entry.action == RebaseAction::Reword);
+        let base = self.base.chars().take(7).collect::<String>();
+
+        v_flex()
+            .key_context(self.dispatch_context(window, cx))
+            .track_focus(&self.focus_handle)
+            .elevation_3(cx)
+            .w(rems(34.))
+            .overflow_hidden()
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(|this, _: &MoveCommitUp, window, cx| {
+                this.move_selected(true, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &MoveCommitDown, window, cx| {
+                this.move_selected(false, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &PickCommit, window, cx| {
+                this.set_selected_action(RebaseAction::Pick, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &RewordCommit, window, cx| {
+                this.set_selected_action(RebaseAction::Reword, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &SquashCommit, window, cx| {
+                this.set_selected_action(RebaseAction::Squash, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &FixupCommit, window, cx| {
+                this.set_selected_action(RebaseAction::Fixup, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &DropCommit, window, cx| {
+                this.set_selected_action(RebaseAction::Drop, window, cx)
+            }))
+            .child(
+                h_flex()
+                    .w_full()
+                    .p_2()
+                    .gap_1()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(Label::new("Interactive Rebase"))
+                    .child(
+                        Label::new(format!("onto {base}"))
+                            .color(Color::Muted)
+                            .buffer_font(cx),
+                    ),
+            )
+            .child(v_flex().w_full().py_1().map(|this| {
+                match &self.entries {
+                    None => this.child(
+                        h_flex()
+                            .px_2()
+                            .child(Label::new("Loading commits…").color(Color::Muted)),
+                    ),
+                    Some(_) => this.child(
+                        uniform_list(
+                            "interactive-rebase-entries",
+                            entry_count,
+                            cx.processor(|this, range, _window, cx| this.render_entries(range, cx)),
+                        )
+                        .with_sizing_behavior(ListSizingBehavior::Infer)
+                        .max_h(rems(24.))
+                        .track_scroll(&self.scroll_handle),
+                    ),
+                }
+            }))
+            .when(is_rewording, |this| {
+                this.child(
+                    div()
+                        .w_full()
+                        .p_2()
+                        .border_t_1()
+                        .border_color(cx.theme().colors().border_variant)
+                        .child(self.message_editor.clone()),
+                )
+            })
+            .child(
+                h_flex()
+                    .w_full()
+                    .p_2()
+                    .gap_1()
+                    .justify_between()
+                    .border_t_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .bg(cx.theme().colors().editor_background)
+                    .map(|this| match &self.error {
+                        Some(error) => this.child(
+                            Label::new(error.clone())
+                                .size(LabelSize::Small)
+                                .color(Color::Error),
+                        ),
+                        None => this.child(
+                            Label::new("Commits are applied from top to bottom.")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                    })
+                    .child(
+                        Button::new("start-rebase", "Rebase")
+                            .disabled(entry_count == 0 || self.pending_rebase.is_some())
+                            .on_click(|_, window, cx| {
+                                window.dispatch_action(menu::Confirm.boxed_clone(), cx)
+                            }),
+                    ),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(action: RebaseAction) -> RebaseTodoEntry {
+        RebaseTodoEntry {
+            action,
+            sha: "0123456789abcdef".into(),
+            subject: "subject".into(),
+            original_message: "subject\n\nbody".into(),
+            message: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_todo() {
+        assert!(validate_todo(&[]).is_err());
+        assert!(validate_todo(&[entry(RebaseAction::Pick), entry(RebaseAction::Squash)]).is_ok());
+        assert!(validate_todo(&[entry(RebaseAction::Fixup), entry(RebaseAction::Pick)]).is_err());
+        assert!(
+            validate_todo(&[
+                entry(RebaseAction::Drop),
+                entry(RebaseAction::Squash),
+                entry(RebaseAction::Pick),
+            ])
+            .is_err()
+        );
+        assert!(
+            validate_todo(&[
+                entry(RebaseAction::Drop),
+                entry(RebaseAction::Reword),
+                entry(RebaseAction::Fixup),
+            ])
+            .is_ok()
+        );
+    }
+}
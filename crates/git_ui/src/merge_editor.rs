@@ -0,0 +1,836 @@
+//! A three-way merge editor for a conflicted file: the common base and the
+//! two sides being merged are shown above the result, which is the file
+//! itself with its conflict markers.
+
+use std::{
+    any::{Any, TypeId},
+    ops::Range,
+    sync::Arc,
+};
+
+use anyhow::Result;
+use buffer_diff::BufferDiff;
+use editor::{Editor, EditorEvent, MultiBuffer, RenderDiffHunkControlsFn, RowHighlightOptions};
+use git::repository::{MergeStages, RepoPath};
+use gpui::{
+    Action, AnyElement, App, AsyncWindowContext, Context, Empty, Entity, EventEmitter, FocusHandle,
+    Focusable, IntoElement, PromptLevel, Render, SharedString, Subscription, Task, WeakEntity,
+    Window, actions,
+};
+use language::{Buffer, Capability, Point, ToPoint as _};
+use project::{ConflictRegion, ConflictSet, Project, ProjectPath, git_store::Repository};
+use ui::{Tooltip, prelude::*};
+use workspace::{
+    Item, ItemHandle as _, ItemNavHistory, SaveIntent, Workspace,
+    item::{ItemEvent, SaveOptions, TabContentParams},
+    notifications::DetachAndPromptErr,
+    searchable::SearchableItemHandle,
+};
+
+actions!(
+    git,
+    [
+        /// Opens the three-way merge editor for the conflicted file in the active editor.
+        OpenMergeEditor,
+    ]
+);
+
+actions!(
+    merge_editor,
+    [
+        /// Resolves every remaining conflict with our side.
+        AcceptAllOurs,
+        /// Resolves every remaining conflict with their side.
+        AcceptAllTheirs,
+        /// Saves the file and stages it, marking its conflicts as resolved.
+        MarkResolved,
+    ]
+);
+
+pub(crate) fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &OpenMergeEditor, window, cx| {
+        let Some(buffer) = workspace
+            .active_item_as::<Editor>(cx)
+            .and_then(|editor| editor.read(cx).active_buffer(cx))
+        else {
+            return;
+        };
+        open_for_buffer(buffer, workspace, window, cx);
+    });
+}
+
+/// Opens the merge editor for a conflicted buffer, or activates the one
+/// already open for it.
+pub(crate) fn open_for_buffer(
+    buffer: Entity<Buffer>,
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    if let Some(existing) = workspace
+        .items_of_type::<MergeEditor>(cx)
+        .find(|merge_editor| merge_editor.read(cx).result_buffer == buffer)
+    {
+        workspace.activate_item(&existing, true, true, window, cx);
+        return;
+    }
+
+    let buffer_id = buffer.read(cx).remote_id();
+    let Some((repository, repo_path)) = workspace
+        .project()
+        .read(cx)
+        .git_store()
+        .read(cx)
+        .repository_and_path_for_buffer_id(buffer_id, cx)
+    else {
+        return;
+    };
+    MergeEditor::open(
+        repository,
+        repo_path,
+        buffer,
+        workspace.weak_handle(),
+        window,
+        cx,
+    )
+    .detach_and_prompt_err("Failed to open merge editor", window, cx, |error, _, _| {
+        Some(error.to_string())
+    });
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MergeSide {
+    Base,
+    Ours,
+    Theirs,
+}
+
+/// The rows each side of a conflict spans in the corresponding version.
+#[derive(Clone, Debug, Default)]
+struct ConflictLocation {
+    base: Option<Range<u32>>,
+    ours: Option<Range<u32>>,
+    theirs: Option<Range<u32>>,
+}
+
+impl ConflictLocation {
+    fn rows(&self, side: MergeSide) -> Option<&Range<u32>> {
+        match side {
+            MergeSide::Base => self.base.as_ref(),
+            MergeSide::Ours => self.ours.as_ref(),
+            MergeSide::Theirs => self.theirs.as_ref(),
+        }
+    }
+}
+
+enum MergeEditorBaseConflict {}
+
+pub struct MergeEditor {
+    repository: Entity<Repository>,
+    repo_path: RepoPath,
+    project: Entity<Project>,
+    workspace: WeakEntity<Workspace>,
+    result_buffer: Entity<Buffer>,
+    conflict_set: Entity<ConflictSet>,
+    base_editor: Entity<Editor>,
+    ours_editor: Entity<Editor>,
+    theirs_editor: Entity<Editor>,
+    result_editor: Entity<Editor>,
+    conflict_locations: Vec<ConflictLocation>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl MergeEditor {
+    pub fn open(
+        repository: Entity<Repository>,
+        repo_path: RepoPath,
+        result_buffer: Entity<Buffer>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        window.spawn(cx, async move |cx| {
+            let project = workspace.update(cx, |workspace, _| workspace.project().clone())?;
+            let stages = repository
+                .update(cx, |repository, _| {
+                    repository.load_merge_stages(repo_path.clone())
+                })
+                .await??;
+            let conflict_set = project
+                .update(cx, |project, cx| {
+                    project.git_store().update(cx, |git_store, cx| {
+                        git_store.open_conflict_set(result_buffer.clone(), cx)
+                    })
+                })
+                .await;
+            let MergeStages { base, ours, theirs } = stages;
+            let base_buffer = build_version_buffer(base.clone(), &result_buffer, cx)?;
+            let ours_buffer = build_version_buffer(ours, &result_buffer, cx)?;
+            let theirs_buffer = build_version_buffer(theirs, &result_buffer, cx)?;
+            let ours_diff = build_side_diff(base.clone(), &ours_buffer, cx).await?;
+            let theirs_diff = build_side_diff(base, &theirs_buffer, cx).await?;
+
+            workspace.update_in(cx, |workspace, window, cx| {
+                let merge_editor = cx.new(|cx| {
+                    MergeEditor::new(
+                        repository,
+                        repo_path,
+                        project,
+                        workspace.weak_handle(),
+                        result_buffer,
+                        conflict_set,
+                        base_buffer,
+                        (ours_buffer, ours_diff),
+                        (theirs_buffer, theirs_diff),
+                        window,
+                        cx,
+                    )
+                });
+                workspace.add_item_to_active_pane(
+                    Box::new(merge_editor.clone()),
+                    None,
+                    true,
+                    window,
+                    cx,
+                );
+                merge_editor
+            })
+        })
+    }
+
+    fn new(
+        repository: Entity<Repository>,
+        repo_path: RepoPath,
+        project: Entity<Project>,
+        workspace: WeakEntity<Workspace>,
+        result_buffer: Entity<Buffer>,
+        conflict_set: Entity<ConflictSet>,
+        base_buffer: Entity<Buffer>,
+        (ours_buffer, ours_diff): (Entity<Buffer>, Entity<BufferDiff>),
+        (theirs_buffer, theirs_diff): (Entity<Buffer>, Entity<BufferDiff>),
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let merge_editor = cx.weak_entity();
+        let base_editor = cx.new(|cx| {
+            let multibuffer = cx.new(|cx| MultiBuffer::singleton(base_buffer, cx));
+            let mut editor =
+                Editor::for_multibuffer(multibuffer, Some(project.clone()), window, cx);
+            editor.set_read_only(true);
+            editor
+        });
+        let side_editor = |buffer: Entity<Buffer>,
+                           diff: Entity<BufferDiff>,
+                           side: MergeSide,
+                           window: &mut Window,
+                           cx: &mut Context<Self>| {
+            cx.new(|cx| {
+                let multibuffer = cx.new(|cx| {
+                    let mut multibuffer = MultiBuffer::singleton(buffer, cx);
+                    multibuffer.add_diff(diff, cx);
+                    multibuffer
+                });
+                let mut editor =
+                    Editor::for_multibuffer(multibuffer, Some(project.clone()), window, cx);
+                editor.set_read_only(true);
+                editor.start_temporary_diff_override();
+                editor.set_expand_all_diff_hunks(cx);
+                editor.set_render_diff_hunks_as_unstaged(true, cx);
+                editor.set_render_diff_hunk_controls(
+                    accept_hunk_controls(merge_editor.clone(), side),
+                    cx,
+                );
+                editor
+            })
+        };
+        let ours_editor = side_editor(ours_buffer, ours_diff, MergeSide::Ours, window, cx);
+        let theirs_editor = side_editor(theirs_buffer, theirs_diff, MergeSide::Theirs, window, cx);
+        let result_editor = cx
+            .new(|cx| Editor::for_buffer(result_buffer.clone(), Some(project.clone()), window, cx));
+
+        let subscriptions = vec![
+            cx.subscribe(&result_editor, |_, _, event: &EditorEvent, cx| {
+                cx.emit(event.clone());
+            }),
+            cx.subscribe(&conflict_set, |this, _, _, cx| {
+                this.update_conflict_locations(cx);
+            }),
+        ];
+
+        let mut this = Self {
+            repository,
+            repo_path,
+            project,
+            workspace,
+            result_buffer,
+            conflict_set,
+            base_editor,
+            ours_editor,
+            theirs_editor,
+            result_editor,
+            conflict_locations: Vec::new(),
+            _subscriptions: subscriptions,
+        };
+        this.update_conflict_locations(cx);
+        this
+    }
+
+    fn conflicts(&self, cx: &App) -> Arc<[ConflictRegion]> {
+        self.conflict_set.read(cx).snapshot().conflicts
+    }
+
+    fn side_editor(&self, side: MergeSide) -> &Entity<Editor> {
+        match side {
+            MergeSide::Base => &self.base_editor,
+            MergeSide::Ours => &self.ours_editor,
+            MergeSide::Theirs => &self.theirs_editor,
+        }
+    }
+
+    /// Finds where each conflict's sides are in the base, ours and theirs
+    /// versions, and highlights the conflicting part of the base.
+    fn update_conflict_locations(&mut self, cx: &mut Context<Self>) {
+        let conflicts = self.conflicts(cx);
+        let result = self.result_buffer.read(cx).snapshot();
+        let side_text = |range: &Range<language::Anchor>| {
+            result.text_for_range(range.clone()).collect::<String>()
+        };
+
+        let mut locations = vec![ConflictLocation::default(); conflicts.len()];
+        for side in [MergeSide::Base, MergeSide::Ours, MergeSide::Theirs] {
+            let Some(buffer) = self
+                .side_editor(side)
+                .read(cx)
+                .buffer()
+                .read(cx)
+                .as_singleton()
+            else {
+                continue;
+            };
+            let version = buffer.read(cx).snapshot();
+            let sides = conflicts.iter().map(|conflict| match side {
+                MergeSide::Base => conflict.base.as_ref().map(side_text),
+                MergeSide::Ours => Some(side_text(&conflict.ours)),
+                MergeSide::Theirs => Some(side_text(&conflict.theirs)),
+            });
+            for (location, range) in locations
+                .iter_mut()
+                .zip(locate_sides(sides, &version.text()))
+            {
+                let rows = range.map(|range| {
+                    range.start.to_point(&version).row..range.end.to_point(&version).row
+                });
+                match side {
+                    MergeSide::Base => location.base = rows,
+                    MergeSide::Ours => location.ours = rows,
+                    MergeSide::Theirs => location.theirs = rows,
+                }
+            }
+        }
+
+        self.base_editor.update(cx, |editor, cx| {
+            editor.clear_row_highlights::<MergeEditorBaseConflict>();
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            for rows in locations
+                .iter()
+                .filter_map(|location| location.base.clone())
+            {
+                let start = snapshot.anchor_before(Point::new(rows.start, 0));
+                let end = snapshot.anchor_before(Point::new(rows.end, 0));
+                editor.highlight_rows::<MergeEditorBaseConflict>(
+                    start..end,
+                    |cx| cx.theme().colors().version_control_conflict_marker_theirs,
+                    RowHighlightOptions::default(),
+                    cx,
+                );
+            }
+        });
+        self.conflict_locations = locations;
+        cx.notify();
+    }
+
+    /// Returns the conflict that the hunk spanning the given rows of a side
+    /// belongs to, or `None` if the hunk was merged without conflicts.
+    fn conflict_for_rows(&self, side: MergeSide, rows: Range<u32>) -> Option<usize> {
+        self.conflict_locations.iter().position(|location| {
+            location
+                .rows(side)
+                .is_some_and(|conflict| conflict.start <= rows.end && rows.start <= conflict.end)
+        })
+    }
+
+    fn accept(&mut self, conflict_ix: usize, side: MergeSide, cx: &mut Context<Self>) {
+        let Some(conflict) = self.conflicts(cx).get(conflict_ix).cloned() else {
+            return;
+        };
+        let kept = match side {
+            MergeSide::Base => conflict.base.clone().into_iter().collect(),
+            MergeSide::Ours => vec![conflict.ours.clone()],
+            MergeSide::Theirs => vec![conflict.theirs.clone()],
+        };
+        conflict.resolve(self.result_buffer.clone(), &kept, cx);
+    }
+
+    fn accept_all(&mut self, side: MergeSide, cx: &mut Context<Self>) {
+        for conflict_ix in (0..self.conflicts(cx).len()).rev() {
+            self.accept(conflict_ix, side, cx);
+        }
+    }
+
+    fn mark_resolved(&mut self, _: &MarkResolved, window: &mut Window, cx: &mut Context<Self>) {
+        // The conflict set is reparsed asynchronously after edits, so check the
+        // result's current text rather than the last parse.
+        let unresolved = ConflictSet::parse(&self.result_buffer.read(cx).text_snapshot())
+            .conflicts
+            .len();
+        if unresolved > 0 {
+            let message = if unresolved == 1 {
+                "Resolve the remaining conflict before marking the file as resolved".to_string()
+            } else {
+                format!(
+                    "Resolve the {unresolved} remaining conflicts before marking the file as resolved"
+                )
+            };
+            let prompt = window.prompt(PromptLevel::Warning, &message, None, &["OK"], cx);
+            cx.spawn(async move |_, _| {
+                prompt.await.ok();
+            })
+            .detach();
+            return;
+        }
+
+        let save = self.project.update(cx, |project, cx| {
+            project.save_buffer(self.result_buffer.clone(), cx)
+        });
+        let repository = self.repository.clone();
+        let repo_path = self.repo_path.clone();
+        let workspace = self.workspace.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            save.await?;
+            repository
+                .update(cx, |repository, cx| {
+                    repository.stage_entries(vec![repo_path], cx)
+                })
+                .await?;
+            let Some(this) = this.upgrade() else {
+                return Ok(());
+            };
+            workspace.update_in(cx, |workspace, window, cx| {
+                if let Some(pane) = workspace.pane_for(&this) {
+                    pane.update(cx, |pane, cx| {
+                        pane.close_item_by_id(this.entity_id(), SaveIntent::Skip, window, cx)
+                    })
+                    .detach_and_log_err(cx);
+                }
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to mark as resolved", window, cx, |error, _, _| {
+            Some(error.to_string())
+        });
+    }
+
+    fn render_pane(
+        &self,
+        label: impl Into<SharedString>,
+        editor: &Entity<Editor>,
+        cx: &App,
+    ) -> impl IntoElement {
+        v_flex()
+            .flex_1()
+            .min_w_0()
+            .h_full()
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(Label::new(label).size(LabelSize::Small).color(Color::Muted)),
+            )
+            .child(div().flex_1().min_h_0().child(editor.clone()))
+    }
+
+    fn render_header(&self, conflicts: &[ConflictRegion], cx: &App) -> impl IntoElement {
+        let remaining = conflicts.len();
+        let status = match remaining {
+            0 => "All conflicts resolved".to_string(),
+            1 => "1 conflict remaining".to_string(),
+            _ => format!("{remaining} conflicts remaining"),
+        };
+        let (ours_name, theirs_name) = branch_names(conflicts);
+
+        h_flex()
+            .justify_between()
+            .px_2()
+            .py_1()
+            .gap_2()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(Label::new(status).size(LabelSize::Small))
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        Button::new("accept-all-ours", format!("Accept All {ours_name}"))
+                            .label_size(LabelSize::Small)
+                            .disabled(remaining == 0)
+                            .on_click(|_, window, cx| {
+                                window.dispatch_action(AcceptAllOurs.boxed_clone(), cx)
+                            }),
+                    )
+                    .child(
+                        Button::new("accept-all-theirs", format!("Accept All {theirs_name}"))
+                            .label_size(LabelSize::Small)
+                            .disabled(remaining == 0)
+                            .on_click(|_, window, cx| {
+                                window.dispatch_action(AcceptAllTheirs.boxed_clone(), cx)
+                            }),
+                    )
+                    .child(
+                        Button::new("mark-resolved", "Mark as Resolved")
+                            .label_size(LabelSize::Small)
+                            .style(ButtonStyle::Filled)
+                            .disabled(remaining > 0)
+                            .when(remaining > 0, |button| {
+                                button.tooltip(Tooltip::text("Resolve all conflicts first"))
+                            })
+                            .on_click(|_, window, cx| {
+                                window.dispatch_action(MarkResolved.boxed_clone(), cx)
+                            }),
+                    ),
+            )
+    }
+}
+
+/// Finds each side of a conflict in the full version it comes from,
+/// searching after the previous side so that repeated text maps to the right
+/// conflict. Returns the byte range of each side found.
+fn locate_sides(
+    sides: impl IntoIterator<Item = Option<String>>,
+    version: &str,
+) -> Vec<Option<Range<usize>>> {
+    let mut offset = 0;
+    sides
+        .into_iter()
+        .map(|side| {
+            let side = side?;
+            let start = offset + version[offset..].find(&side)?;
+            offset = start + side.len();
+            Some(start..offset)
+        })
+        .collect()
+}
+
+fn branch_names(conflicts: &[ConflictRegion]) -> (SharedString, SharedString) {
+    conflicts.first().map_or_else(
+        || ("Ours".into(), "Theirs".into()),
+        |conflict| {
+            (
+                conflict.ours_branch_name.clone(),
+                conflict.theirs_branch_name.clone(),
+            )
+        },
+    )
+}
+
+fn build_version_buffer(
+    text: Option<String>,
+    result_buffer: &Entity<Buffer>,
+    cx: &mut AsyncWindowContext,
+) -> Result<Entity<Buffer>> {
+    cx.update(|_, cx| {
+        let language = result_buffer.read(cx).language().cloned();
+        cx.new(|cx| {
+            let mut buffer = Buffer::local(text.unwrap_or_default(), cx);
+            buffer.set_language(language, cx);
+            buffer.set_capability(Capability::ReadOnly, cx);
+            buffer
+        })
+    })
+}
+
+/// Diffs one side of the merge against the base, so that the side's changes
+/// are shown with the base lines they replace.
+async fn build_side_diff(
+    base: Option<String>,
+    buffer: &Entity<Buffer>,
+    cx: &mut AsyncWindowContext,
+) -> Result<Entity<BufferDiff>> {
+    let (snapshot, language_registry) = cx.update(|_, cx| {
+        let buffer = buffer.read(cx);
+        (buffer.snapshot(), buffer.language_registry())
+    })?;
+    let diff = cx.new(|cx| {
+        BufferDiff::new(
+            &snapshot.text,
+            snapshot.language().cloned(),
+            language_registry,
+            cx,
+        )
+    });
+    diff.update(cx, |diff, cx| {
+        diff.set_base_text(
+            base.map(|base| Arc::from(base.as_str())),
+            snapshot.text.clone(),
+            cx,
+        )
+    })
+    .await;
+    Ok(diff)
+}
+
+/// Renders an accept button on the hunks of a side that conflict with the
+/// other side, and nothing on those that were merged cleanly.
+fn accept_hunk_controls(
+    merge_editor: WeakEntity<MergeEditor>,
+    side: MergeSide,
+) -> RenderDiffHunkControlsFn {
+    Arc::new(
+        move |row, _status, hunk_range, _is_created_file, line_height, editor, _window, cx| {
+            let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+            let Some(rows) = hunk_rows(&snapshot, hunk_range) else {
+                return Empty.into_any_element();
+            };
+            let Some(merge_editor) = merge_editor.upgrade() else {
+                return Empty.into_any_element();
+            };
+            let Some(conflict_ix) = merge_editor.read(cx).conflict_for_rows(side, rows) else {
+                return Empty.into_any_element();
+            };
+            let (ours_name, theirs_name) = branch_names(&merge_editor.read(cx).conflicts(cx));
+            let label = match side {
+                MergeSide::Base => "Accept Base".into(),
+                MergeSide::Ours => format!("Accept {ours_name}"),
+                MergeSide::Theirs => format!("Accept {theirs_name}"),
+            };
+
+            h_flex()
+                .h(line_height)
+                .mr_0p5()
+                .px_0p5()
+                .pb_1()
+                .border_x_1()
+                .border_b_1()
+                .border_color(cx.theme().colors().border)
+                .rounded_b_md()
+                .bg(cx.theme().colors().editor_background)
+                .block_mouse_except_scroll()
+                .shadow_md()
+                .child(
+                    Button::new(("accept-hunk", row as u64), label)
+                        .label_size(LabelSize::Small)
+                        .on_click(move |_, _, cx| {
+                            merge_editor.update(cx, |merge_editor, cx| {
+                                merge_editor.accept(conflict_ix, side, cx);
+                            });
+                        }),
+                )
+                .into_any_element()
+        },
+    )
+}
+
+/// Returns the rows of the side's buffer that a hunk spans.
+fn hunk_rows(
+    snapshot: &editor::MultiBufferSnapshot,
+    hunk_range: Range<editor::Anchor>,
+) -> Option<Range<u32>> {
+    let (start, buffer) = snapshot.anchor_to_buffer_anchor(hunk_range.start)?;
+    let start = start.to_point(buffer).row;
+    let (end, buffer) = snapshot.anchor_to_buffer_anchor(hunk_range.end)?;
+    Some(start..end.to_point(buffer).row)
+}
+
+impl EventEmitter<EditorEvent> for MergeEditor {}
+
+impl Focusable for MergeEditor {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.result_editor.focus_handle(cx)
+    }
+}
+
+impl Item for MergeEditor {
+    type Event = EditorEvent;
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::GitMergeConflict).color(Color::Muted))
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        Label::new(self.tab_content_text(params.detail.unwrap_or_default(), cx))
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        let file_name = self
+            .repo_path
+            .file_name()
+            .map(ToString::to_string)
+            .unwrap_or_default();
+        format!("Merge {file_name}").into()
+    }
+
+    fn tab_tooltip_text(&self, _cx: &App) -> Option<SharedString> {
+        Some(format!("Merge {}", self.repo_path.as_unix_str()).into())
+    }
+
+    fn to_item_events(event: &EditorEvent, f: &mut dyn FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Merge Editor Opened")
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.result_editor.deactivated(window, cx);
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<gpui::AnyEntity> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.clone().into())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.result_editor.clone().into())
+        } else {
+            None
+        }
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>, _: &App) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.result_editor.clone()))
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        self.result_editor.for_each_project_item(cx, f)
+    }
+
+    fn active_project_path(&self, cx: &App) -> Option<ProjectPath> {
+        self.result_editor.read(cx).active_project_path(cx)
+    }
+
+    fn set_nav_history(
+        &mut self,
+        nav_history: ItemNavHistory,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.result_editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn navigate(
+        &mut self,
+        data: Arc<dyn Any + Send>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.result_editor
+            .update(cx, |editor, cx| editor.navigate(data, window, cx))
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.result_editor.update(cx, |editor, cx| {
+            editor.added_to_workspace(workspace, window, cx)
+        });
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.result_buffer.read(cx).is_dirty()
+    }
+
+    fn can_save(&self, cx: &App) -> bool {
+        self.result_editor.read(cx).can_save(cx)
+    }
+
+    fn save(
+        &mut self,
+        options: SaveOptions,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.result_editor.save(options, project, window, cx)
+    }
+}
+
+impl Render for MergeEditor {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let conflicts = self.conflicts(cx);
+        let (ours_name, theirs_name) = branch_names(&conflicts);
+        let border_color = cx.theme().colors().border;
+
+        v_flex()
+            .key_context("MergeEditor")
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .on_action(
+                cx.listener(|this, _: &AcceptAllOurs, _, cx| this.accept_all(MergeSide::Ours, cx)),
+            )
+            .on_action(cx.listener(|this, _: &AcceptAllTheirs, _, cx| {
+                this.accept_all(MergeSide::Theirs, cx)
+            }))
+            .on_action(cx.listener(Self::mark_resolved))
+            .child(self.render_header(&conflicts, cx))
+            .child(
+                h_flex()
+                    .flex_1()
+                    .min_h_0()
+                    .border_b_1()
+                    .border_color(border_color)
+                    .child(self.render_pane("Base", &self.base_editor, cx))
+                    .child(div().h_full().w_px().bg(border_color))
+                    .child(self.render_pane(ours_name, &self.ours_editor, cx))
+                    .child(div().h_full().w_px().bg(border_color))
+                    .child(self.render_pane(theirs_name, &self.theirs_editor, cx)),
+            )
+            .child(self.render_pane("Result", &self.result_editor, cx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locate_sides() {
+        let version = "a\nb\nb\nc\n";
+        assert_eq!(
+            locate_sides(
+                [
+                    Some("b\n".into()),
+                    None,
+                    Some("b\n".into()),
+                    Some("d\n".into())
+                ],
+                version
+            ),
+            [Some(2..4), None, Some(4..6), None]
+        );
+        // Sides that were deleted are found where the previous side ended.
+        assert_eq!(
+            locate_sides([Some("a\n".into()), Some(String::new())], version),
+            [Some(0..2), Some(2..2)]
+        );
+    }
+}
@@ -2,6 +2,7 @@ use crate::{
     AssetSource, DevicePixels, IsZero, RenderImage, Result, SharedString, Size,
     swap_rgba_pa_to_bgra,
 };
+use anyhow::Context as _;
 use image::Frame;
 use resvg::tiny_skia::Pixmap;
 use smallvec::SmallVec;
@@ -194,6 +195,25 @@ impl SvgRenderer {
         })
     }
 
+    /// Renders the given bytes into a PNG-encoded image, e.g. to copy or export an SVG as an image.
+    pub fn render_png(&self, bytes: &[u8], scale_factor: f32) -> Result<Vec<u8>> {
+        let pixmap = self.render_pixmap(bytes, SvgSize::ScaleFactor(scale_factor))?;
+        let pixels = pixmap
+            .pixels()
+            .iter()
+            .flat_map(|pixel| {
+                let color = pixel.demultiply();
+                [color.red(), color.green(), color.blue(), color.alpha()]
+            })
+            .collect();
+        let image = image::RgbaImage::from_raw(pixmap.width(), pixmap.height(), pixels)
+            .context("pixmap size doesn't match its pixels")?;
+
+        let mut png = Vec::new();
+        image.write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)?;
+        Ok(png)
+    }
+
     pub(crate) fn render_alpha_mask(
         &self,
         params: &RenderSvgParams,
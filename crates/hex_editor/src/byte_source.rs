@@ -0,0 +1,388 @@
+use std::{
+    io,
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use fs::{Fs, RemoveOptions, RenameOptions};
+use futures::{StreamExt as _, TryStreamExt as _, stream};
+use gpui::{App, SharedString, Task};
+
+use crate::hex_document::{HexDocument, Piece};
+
+/// The bytes a hex editor shows and edits, such as a file or a debuggee's memory.
+pub trait ByteSource: 'static {
+    /// What the tab of the hex editor is titled.
+    fn title(&self) -> SharedString;
+
+    /// The path of the file the bytes are read from, if they are.
+    fn abs_path(&self) -> Option<&Path> {
+        None
+    }
+
+    /// The number of bytes, or `None` for address spaces without a known end.
+    fn len(&self) -> Option<u64>;
+
+    /// Looks up the number of bytes again, after the source was changed elsewhere.
+    fn reload_len(&self, _cx: &mut App) -> Task<Result<Option<u64>>> {
+        Task::ready(Ok(self.len()))
+    }
+
+    /// Reads the bytes at `range`, those that can't be read being `None`.
+    fn read(&self, range: Range<u64>, cx: &mut App) -> Task<Result<Vec<Option<u8>>>>;
+
+    /// Whether bytes can be inserted and deleted, rather than only overwritten.
+    fn can_resize(&self) -> bool;
+
+    /// Writes the edited bytes back, returning the number of bytes there are now.
+    fn save(&self, document: &HexDocument, cx: &mut App) -> Task<Result<u64>>;
+}
+
+/// A file, read a page at a time so that files of any size can be shown.
+pub struct FileByteSource {
+    fs: Arc<dyn Fs>,
+    abs_path: PathBuf,
+    /// Updated by saves and reloads, which finish in the background.
+    len: Arc<AtomicU64>,
+}
+
+impl FileByteSource {
+    pub async fn open(fs: Arc<dyn Fs>, abs_path: PathBuf) -> Result<Self> {
+        let metadata = fs
+            .metadata(&abs_path)
+            .await?
+            .with_context(|| format!("{} does not exist", abs_path.display()))?;
+        anyhow::ensure!(!metadata.is_dir, "{} is a directory", abs_path.display());
+        Ok(Self {
+            fs,
+            abs_path,
+            len: Arc::new(AtomicU64::new(metadata.len)),
+        })
+    }
+
+    /// Writes the document to a file next to the original, reading the original a chunk at a
+    /// time, then moves it over the original.
+    async fn write_through_temp_file(
+        fs: &Arc<dyn Fs>,
+        abs_path: &Path,
+        pieces: Vec<Piece>,
+    ) -> Result<()> {
+        let file_name = abs_path
+            .file_name()
+            .with_context(|| format!("{} is not a file", abs_path.display()))?;
+        let temp_path =
+            abs_path.with_file_name(format!(".{}.zed-save", file_name.to_string_lossy()));
+
+        let chunks = pieces.into_iter().flat_map(|piece| match piece {
+            Piece::Original(range) => (range.start..range.end)
+                .step_by(SAVE_CHUNK_SIZE as usize)
+                .map(|start| Piece::Original(start..range.end.min(start + SAVE_CHUNK_SIZE)))
+                .collect::<Vec<_>>(),
+            Piece::Added(bytes) => vec![Piece::Added(bytes)],
+        });
+        let mut contents = stream::iter(chunks)
+            .then(|chunk| {
+                let fs = fs.clone();
+                let abs_path = abs_path.to_path_buf();
+                async move {
+                    let bytes = match chunk {
+                        Piece::Original(range) => {
+                            let bytes = fs
+                                .read_range(
+                                    &abs_path,
+                                    range.start,
+                                    (range.end - range.start) as usize,
+                                )
+                                .await
+                                .map_err(io::Error::other)?;
+                            if bytes.len() as u64 != range.end - range.start {
+                                return Err(io::Error::other(format!(
+                                    "{} was changed while it was being saved",
+                                    abs_path.display()
+                                )));
+                            }
+                            bytes
+                        }
+                        Piece::Added(bytes) => bytes.to_vec(),
+                    };
+                    Ok(bytes)
+                }
+            })
+            .boxed()
+            .into_async_read();
+
+        let result = async {
+            fs.create_file_with(&temp_path, std::pin::Pin::new(&mut contents))
+                .await?;
+            fs.rename(
+                &temp_path,
+                abs_path,
+                RenameOptions {
+                    overwrite: true,
+                    ..RenameOptions::default()
+                },
+            )
+            .await
+        }
+        .await;
+        if result.is_err() {
+            fs.remove_file(
+                &temp_path,
+                RemoveOptions {
+                    ignore_if_not_exists: true,
+                    ..RemoveOptions::default()
+                },
+            )
+            .await
+            .ok();
+        }
+        result
+    }
+}
+
+impl ByteSource for FileByteSource {
+    fn title(&self) -> SharedString {
+        self.abs_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned().into())
+            .unwrap_or_else(|| self.abs_path.to_string_lossy().into_owned().into())
+    }
+
+    fn abs_path(&self) -> Option<&Path> {
+        Some(&self.abs_path)
+    }
+
+    fn len(&self) -> Option<u64> {
+        Some(self.len.load(Ordering::Acquire))
+    }
+
+    fn reload_len(&self, cx: &mut App) -> Task<Result<Option<u64>>> {
+        let fs = self.fs.clone();
+        let abs_path = self.abs_path.clone();
+        let len = self.len.clone();
+        cx.background_spawn(async move {
+            let metadata = fs
+                .metadata(&abs_path)
+                .await?
+                .with_context(|| format!("{} does not exist", abs_path.display()))?;
+            len.store(metadata.len, Ordering::Release);
+            Ok(Some(metadata.len))
+        })
+    }
+
+    fn read(&self, range: Range<u64>, cx: &mut App) -> Task<Result<Vec<Option<u8>>>> {
+        let fs = self.fs.clone();
+        let abs_path = self.abs_path.clone();
+        cx.background_spawn(async move {
+            let bytes = fs
+                .read_range(&abs_path, range.start, (range.end - range.start) as usize)
+                .await?;
+            Ok(bytes.into_iter().map(Some).collect())
+        })
+    }
+
+    fn can_resize(&self) -> bool {
+        true
+    }
+
+    fn save(&self, document: &HexDocument, cx: &mut App) -> Task<Result<u64>> {
+        let fs = self.fs.clone();
+        let abs_path = self.abs_path.clone();
+        let len = self.len.clone();
+        let new_len = document.len();
+        // Bytes that were only overwritten can be written in place, without touching the rest.
+        let patches = document.preserves_offsets().then(|| document.patches());
+        let pieces = document.pieces().to_vec();
+        cx.background_spawn(async move {
+            match patches {
+                Some(patches) => fs.write_ranges(&abs_path, patches).await?,
+                None => Self::write_through_temp_file(&fs, &abs_path, pieces).await?,
+            }
+            len.store(new_len, Ordering::Release);
+            Ok(new_len)
+        })
+    }
+}
+
+/// The number of bytes read from a source at once.
+pub const PAGE_SIZE: u64 = 4096;
+/// The number of bytes of a file read at once while saving it.
+const SAVE_CHUNK_SIZE: u64 = 1024 * 1024;
+/// How many pages are kept around before those furthest from what's shown are dropped.
+const MAX_CACHED_PAGES: usize = 1024;
+
+enum CachedPage {
+    Loading,
+    Loaded(Arc<[Option<u8>]>),
+}
+
+/// The pages of a source read so far.
+#[derive(Default)]
+pub struct PageCache {
+    pages: HashMap<u64, CachedPage>,
+}
+
+impl PageCache {
+    /// Returns the bytes at `range` of the source, `None` for those not read yet, and starts
+    /// loading the pages that aren't.
+    pub fn read(&mut self, range: Range<u64>, mut load_page: impl FnMut(u64)) -> Vec<Option<u8>> {
+        let mut bytes = Vec::with_capacity((range.end - range.start) as usize);
+        let mut offset = range.start;
+        while offset < range.end {
+            let page = offset / PAGE_SIZE;
+            let page_start = page * PAGE_SIZE;
+            let end = range.end.min(page_start + PAGE_SIZE);
+            match self.pages.get(&page) {
+                Some(CachedPage::Loaded(contents)) => bytes.extend((offset..end).map(|offset| {
+                    contents
+                        .get((offset - page_start) as usize)
+                        .copied()
+                        .flatten()
+                })),
+                Some(CachedPage::Loading) => bytes.extend((offset..end).map(|_| None)),
+                None => {
+                    self.pages.insert(page, CachedPage::Loading);
+                    load_page(page);
+                    bytes.extend((offset..end).map(|_| None));
+                }
+            }
+            offset = end;
+        }
+        bytes
+    }
+
+    pub fn insert(&mut self, page: u64, contents: Arc<[Option<u8>]>) {
+        self.pages.insert(page, CachedPage::Loaded(contents));
+    }
+
+    /// Drops pages far from `range` once there are too many.
+    pub fn evict_outside(&mut self, range: Range<u64>) {
+        if self.pages.len() <= MAX_CACHED_PAGES {
+            return;
+        }
+        let first_page = range.start / PAGE_SIZE;
+        let last_page = range.end.div_ceil(PAGE_SIZE);
+        let keep_around = MAX_CACHED_PAGES as u64 / 4;
+        self.pages.retain(|page, contents| {
+            matches!(contents, CachedPage::Loading)
+                || (first_page.saturating_sub(keep_around)..last_page.saturating_add(keep_around))
+                    .contains(page)
+        });
+    }
+
+    pub fn clear(&mut self) {
+        self.pages.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_save_file(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_file(path!("/file.bin"), b"0123456789".to_vec())
+            .await;
+        let source = FileByteSource::open(fs.clone(), PathBuf::from(path!("/file.bin")))
+            .await
+            .unwrap();
+
+        // Overwritten bytes are saved in place.
+        let mut document = HexDocument::new(10);
+        document.overwrite(2, b"ab");
+        document.overwrite(8, b"cd");
+        assert!(document.preserves_offsets());
+        let len = cx.update(|cx| source.save(&document, cx)).await.unwrap();
+        assert_eq!(len, 10);
+        assert_eq!(
+            fs.read_file_sync(path!("/file.bin")).unwrap(),
+            b"01ab4567cd"
+        );
+
+        // Inserting and deleting bytes rewrites the file through a temporary one.
+        let mut document = HexDocument::new(10);
+        document.insert(0, b"xyz");
+        document.delete(7..10);
+        assert!(!document.preserves_offsets());
+        let len = cx.update(|cx| source.save(&document, cx)).await.unwrap();
+        assert_eq!(len, 10);
+        assert_eq!(
+            fs.read_file_sync(path!("/file.bin")).unwrap(),
+            b"xyz01ab4cd"
+        );
+        assert_eq!(
+            fs.files_with_contents(Path::new(path!("/")))
+                .into_iter()
+                .map(|(path, _)| path)
+                .collect::<Vec<_>>(),
+            [PathBuf::from(path!("/file.bin"))]
+        );
+
+        // Saving a shorter document updates the length of the source.
+        document.reset(10);
+        document.delete(0..4);
+        assert_eq!(cx.update(|cx| source.save(&document, cx)).await.unwrap(), 6);
+        assert_eq!(source.len(), Some(6));
+
+        // Changes made elsewhere are picked up when reloading.
+        fs.insert_file(path!("/file.bin"), b"0123".to_vec()).await;
+        assert_eq!(
+            cx.update(|cx| source.reload_len(cx)).await.unwrap(),
+            Some(4)
+        );
+        assert_eq!(source.len(), Some(4));
+    }
+
+    #[test]
+    fn test_page_cache() {
+        let mut cache = PageCache::default();
+        let mut requested = Vec::new();
+
+        let bytes = cache.read(PAGE_SIZE - 2..PAGE_SIZE + 2, |page| requested.push(page));
+        assert_eq!(bytes, [None; 4]);
+        assert_eq!(requested, [0, 1]);
+
+        // Pages being loaded aren't requested again.
+        requested.clear();
+        cache.read(0..PAGE_SIZE * 2, |page| requested.push(page));
+        assert!(requested.is_empty());
+
+        cache.insert(0, (0..PAGE_SIZE).map(|offset| Some(offset as u8)).collect());
+        // The last page of a source can be shorter than the others.
+        cache.insert(1, vec![Some(0xAA), None].into());
+        let bytes = cache.read(PAGE_SIZE - 2..PAGE_SIZE + 3, |page| requested.push(page));
+        assert_eq!(bytes, [Some(0xFE), Some(0xFF), Some(0xAA), None, None]);
+        assert!(requested.is_empty());
+    }
+
+    #[test]
+    fn test_page_cache_eviction() {
+        let mut cache = PageCache::default();
+        for page in 0..MAX_CACHED_PAGES as u64 + 10 {
+            cache.insert(page, Arc::from(&[][..]));
+        }
+        let shown = 10 * PAGE_SIZE..12 * PAGE_SIZE;
+        cache.evict_outside(shown.clone());
+        assert!(cache.pages.len() < MAX_CACHED_PAGES);
+        assert!(cache.pages.contains_key(&10));
+
+        let mut requested = Vec::new();
+        cache.read(shown, |page| requested.push(page));
+        assert!(requested.is_empty());
+        cache.read(
+            (MAX_CACHED_PAGES as u64 + 5) * PAGE_SIZE..(MAX_CACHED_PAGES as u64 + 6) * PAGE_SIZE,
+            |page| requested.push(page),
+        );
+        assert_eq!(requested, [MAX_CACHED_PAGES as u64 + 5]);
+    }
+}
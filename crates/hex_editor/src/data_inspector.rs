@@ -0,0 +1,201 @@
+/// The byte order the data inspector reads values in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Endianness {
+    #[default]
+    Little,
+    Big,
+}
+
+impl Endianness {
+    pub fn toggle(self) -> Self {
+        match self {
+            Endianness::Little => Endianness::Big,
+            Endianness::Big => Endianness::Little,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Endianness::Little => "Little Endian",
+            Endianness::Big => "Big Endian",
+        }
+    }
+}
+
+/// A value the bytes at the cursor can be read as.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InspectedValue {
+    pub label: &'static str,
+    /// The value, or `None` when there aren't enough readable bytes at the cursor.
+    pub value: Option<String>,
+}
+
+/// Reads the bytes at the cursor as the integers and floats they could be.
+pub fn inspect(bytes: &[Option<u8>], endianness: Endianness) -> Vec<InspectedValue> {
+    fn read<const N: usize>(bytes: &[Option<u8>], endianness: Endianness) -> Option<[u8; N]> {
+        let mut value = [0; N];
+        for (byte, read) in value.iter_mut().zip(bytes.get(..N)?) {
+            *byte = (*read)?;
+        }
+        if endianness == Endianness::Little {
+            value.reverse();
+        }
+        Some(value)
+    }
+
+    let binary = bytes
+        .first()
+        .copied()
+        .flatten()
+        .map(|byte| format!("{byte:08b}"));
+    let ascii = bytes.first().copied().flatten().map(|byte| {
+        if byte.is_ascii_graphic() || byte == b' ' {
+            format!("'{}'", byte as char)
+        } else {
+            format!("'\\x{byte:02x}'")
+        }
+    });
+    let utf8 =
+        utf8_char(bytes).map(|character| format!("{character:?} (U+{:04X})", character as u32));
+
+    vec![
+        InspectedValue {
+            label: "Binary",
+            value: binary,
+        },
+        InspectedValue {
+            label: "ASCII",
+            value: ascii,
+        },
+        InspectedValue {
+            label: "UTF-8",
+            value: utf8,
+        },
+        InspectedValue {
+            label: "u8",
+            value: read::<1>(bytes, endianness).map(|b| u8::from_be_bytes(b).to_string()),
+        },
+        InspectedValue {
+            label: "i8",
+            value: read::<1>(bytes, endianness).map(|b| i8::from_be_bytes(b).to_string()),
+        },
+        InspectedValue {
+            label: "u16",
+            value: read::<2>(bytes, endianness).map(|b| u16::from_be_bytes(b).to_string()),
+        },
+        InspectedValue {
+            label: "i16",
+            value: read::<2>(bytes, endianness).map(|b| i16::from_be_bytes(b).to_string()),
+        },
+        InspectedValue {
+            label: "u32",
+            value: read::<4>(bytes, endianness).map(|b| u32::from_be_bytes(b).to_string()),
+        },
+        InspectedValue {
+            label: "i32",
+            value: read::<4>(bytes, endianness).map(|b| i32::from_be_bytes(b).to_string()),
+        },
+        InspectedValue {
+            label: "u64",
+            value: read::<8>(bytes, endianness).map(|b| u64::from_be_bytes(b).to_string()),
+        },
+        InspectedValue {
+            label: "i64",
+            value: read::<8>(bytes, endianness).map(|b| i64::from_be_bytes(b).to_string()),
+        },
+        InspectedValue {
+            label: "f32",
+            value: read::<4>(bytes, endianness).map(|b| f32::from_be_bytes(b).to_string()),
+        },
+        InspectedValue {
+            label: "f64",
+            value: read::<8>(bytes, endianness).map(|b| f64::from_be_bytes(b).to_string()),
+        },
+    ]
+}
+
+/// Decodes the UTF-8 character starting at the first byte, if it starts one.
+fn utf8_char(bytes: &[Option<u8>]) -> Option<char> {
+    let first = bytes.first().copied().flatten()?;
+    let len = match first.leading_ones() {
+        0 => 1,
+        2..=4 => first.leading_ones() as usize,
+        _ => return None,
+    };
+    let encoded = bytes
+        .get(..len)?
+        .iter()
+        .copied()
+        .collect::<Option<Vec<u8>>>()?;
+    std::str::from_utf8(&encoded).ok()?.chars().next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(values: &[InspectedValue], label: &str) -> Option<String> {
+        values
+            .iter()
+            .find(|value| value.label == label)
+            .and_then(|value| value.value.clone())
+    }
+
+    fn readable(bytes: &[u8]) -> Vec<Option<u8>> {
+        bytes.iter().copied().map(Some).collect()
+    }
+
+    #[test]
+    fn test_inspect_integers() {
+        let bytes = readable(&[0xFE, 0xFF, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00]);
+
+        let little = inspect(&bytes, Endianness::Little);
+        assert_eq!(value(&little, "u8").as_deref(), Some("254"));
+        assert_eq!(value(&little, "i8").as_deref(), Some("-2"));
+        assert_eq!(value(&little, "u16").as_deref(), Some("65534"));
+        assert_eq!(value(&little, "i16").as_deref(), Some("-2"));
+        assert_eq!(value(&little, "u32").as_deref(), Some("65534"));
+        assert_eq!(value(&little, "u64").as_deref(), Some("4295032830"));
+        assert_eq!(value(&little, "Binary").as_deref(), Some("11111110"));
+
+        let big = inspect(&bytes, Endianness::Big);
+        assert_eq!(value(&big, "u16").as_deref(), Some("65279"));
+        assert_eq!(value(&big, "i32").as_deref(), Some("-16842752"));
+    }
+
+    #[test]
+    fn test_inspect_floats() {
+        let bytes = readable(&1.5f32.to_le_bytes());
+        let values = inspect(&bytes, Endianness::Little);
+        assert_eq!(value(&values, "f32").as_deref(), Some("1.5"));
+        assert_eq!(value(&values, "f64"), None);
+
+        let bytes = readable(&(-0.25f64).to_be_bytes());
+        let values = inspect(&bytes, Endianness::Big);
+        assert_eq!(value(&values, "f64").as_deref(), Some("-0.25"));
+    }
+
+    #[test]
+    fn test_inspect_characters() {
+        let values = inspect(&readable("é!".as_bytes()), Endianness::Little);
+        assert_eq!(value(&values, "UTF-8").as_deref(), Some("'é' (U+00E9)"));
+        assert_eq!(value(&values, "ASCII").as_deref(), Some("'\\xc3'"));
+
+        let values = inspect(&readable(b"A"), Endianness::Little);
+        assert_eq!(value(&values, "ASCII").as_deref(), Some("'A'"));
+        assert_eq!(value(&values, "UTF-8").as_deref(), Some("'A' (U+0041)"));
+    }
+
+    #[test]
+    fn test_inspect_unreadable_bytes() {
+        let values = inspect(&[Some(1), None, Some(2), Some(3)], Endianness::Little);
+        assert_eq!(value(&values, "u8").as_deref(), Some("1"));
+        assert_eq!(value(&values, "u16"), None);
+        assert_eq!(value(&values, "u32"), None);
+        assert!(
+            inspect(&[], Endianness::Big)
+                .iter()
+                .all(|value| value.value.is_none())
+        );
+    }
+}
@@ -0,0 +1,434 @@
+use std::{ops::Range, sync::Arc};
+
+/// A run of a document's bytes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Piece {
+    /// Bytes of the source at this range of it.
+    Original(Range<u64>),
+    /// Bytes typed or pasted in.
+    Added(Arc<[u8]>),
+}
+
+impl Piece {
+    pub fn len(&self) -> u64 {
+        match self {
+            Piece::Original(range) => range.end - range.start,
+            Piece::Added(bytes) => bytes.len() as u64,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the part of the piece at `range`, relative to the piece's start.
+    fn slice(&self, range: Range<u64>) -> Piece {
+        match self {
+            Piece::Original(original) => {
+                Piece::Original(original.start + range.start..original.start + range.end)
+            }
+            Piece::Added(bytes) => {
+                Piece::Added(bytes[range.start as usize..range.end as usize].into())
+            }
+        }
+    }
+}
+
+/// The bytes of a source with the unsaved edits applied.
+///
+/// Edits are kept as a piece table over the source's bytes, so that any file can be edited
+/// without reading more of it than is shown.
+#[derive(Clone, Debug)]
+pub struct HexDocument {
+    pieces: Vec<Piece>,
+    original_len: u64,
+    undo_stack: Vec<Vec<Piece>>,
+    redo_stack: Vec<Vec<Piece>>,
+}
+
+impl HexDocument {
+    pub fn new(original_len: u64) -> Self {
+        Self {
+            pieces: Self::original_pieces(original_len),
+            original_len,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+        }
+    }
+
+    fn original_pieces(original_len: u64) -> Vec<Piece> {
+        if original_len == 0 {
+            Vec::new()
+        } else {
+            vec![Piece::Original(0..original_len)]
+        }
+    }
+
+    pub fn len(&self) -> u64 {
+        self.pieces.iter().map(Piece::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pieces.is_empty()
+    }
+
+    pub fn original_len(&self) -> u64 {
+        self.original_len
+    }
+
+    pub fn pieces(&self) -> &[Piece] {
+        &self.pieces
+    }
+
+    pub fn is_edited(&self) -> bool {
+        self.pieces != Self::original_pieces(self.original_len)
+    }
+
+    /// Whether every byte of the source is still at its original offset, which is all that
+    /// sources that can't grow or shrink, such as a debuggee's memory, can save.
+    pub fn preserves_offsets(&self) -> bool {
+        let mut offset = 0;
+        for piece in &self.pieces {
+            if let Piece::Original(range) = piece
+                && range.start != offset
+            {
+                return false;
+            }
+            offset += piece.len();
+        }
+        offset == self.original_len
+    }
+
+    /// The added bytes, with the offsets they are at.
+    pub fn patches(&self) -> Vec<(u64, Arc<[u8]>)> {
+        let mut offset = 0;
+        let mut patches = Vec::new();
+        for piece in &self.pieces {
+            if let Piece::Added(bytes) = piece {
+                patches.push((offset, bytes.clone()));
+            }
+            offset += piece.len();
+        }
+        patches
+    }
+
+    /// Returns the pieces making up `range` of the document.
+    pub fn slice(&self, range: Range<u64>) -> Vec<Piece> {
+        let mut slice = Vec::new();
+        let mut offset = 0;
+        for piece in &self.pieces {
+            let piece_range = offset..offset + piece.len();
+            offset = piece_range.end;
+            if piece_range.end <= range.start {
+                continue;
+            }
+            if piece_range.start >= range.end {
+                break;
+            }
+            let start = range.start.max(piece_range.start) - piece_range.start;
+            let end = range.end.min(piece_range.end) - piece_range.start;
+            if start < end {
+                slice.push(piece.slice(start..end));
+            }
+        }
+        slice
+    }
+
+    /// Whether the byte at `offset` was typed or pasted in.
+    pub fn is_added(&self, offset: u64) -> bool {
+        matches!(
+            self.slice(offset..offset.saturating_add(1)).first(),
+            Some(Piece::Added(_))
+        )
+    }
+
+    /// Replaces the bytes from `offset` on, appending those past the end of the document.
+    pub fn overwrite(&mut self, offset: u64, bytes: &[u8]) {
+        let offset = offset.min(self.len());
+        let end = offset.saturating_add(bytes.len() as u64).min(self.len());
+        self.replace(offset..end, bytes);
+    }
+
+    pub fn insert(&mut self, offset: u64, bytes: &[u8]) {
+        let offset = offset.min(self.len());
+        self.replace(offset..offset, bytes);
+    }
+
+    pub fn delete(&mut self, range: Range<u64>) {
+        let len = self.len();
+        self.replace(range.start.min(len)..range.end.min(len), &[]);
+    }
+
+    fn replace(&mut self, range: Range<u64>, bytes: &[u8]) {
+        if range.is_empty() && bytes.is_empty() {
+            return;
+        }
+        let len = self.len();
+        let mut pieces = self.slice(0..range.start);
+        if !bytes.is_empty() {
+            pieces.push(Piece::Added(bytes.into()));
+        }
+        pieces.extend(self.slice(range.end..len));
+
+        let previous = std::mem::replace(&mut self.pieces, merge_pieces(pieces));
+        self.undo_stack.push(previous);
+        self.redo_stack.clear();
+    }
+
+    /// Makes undoing the last edit undo the one before it as well, as the two halves of a byte
+    /// typed in hex are.
+    pub fn merge_last_edits(&mut self) {
+        if self.undo_stack.len() >= 2 {
+            self.undo_stack.pop();
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    pub fn undo(&mut self) -> bool {
+        let Some(pieces) = self.undo_stack.pop() else {
+            return false;
+        };
+        self.redo_stack
+            .push(std::mem::replace(&mut self.pieces, pieces));
+        true
+    }
+
+    pub fn redo(&mut self) -> bool {
+        let Some(pieces) = self.redo_stack.pop() else {
+            return false;
+        };
+        self.undo_stack
+            .push(std::mem::replace(&mut self.pieces, pieces));
+        true
+    }
+
+    /// Discards the edits, keeping the length the source has after saving or reloading them.
+    pub fn reset(&mut self, original_len: u64) {
+        *self = Self::new(original_len);
+    }
+}
+
+/// Joins adjacent pieces that continue each other, so that undoing and comparing stays cheap.
+fn merge_pieces(pieces: Vec<Piece>) -> Vec<Piece> {
+    let mut merged: Vec<Piece> = Vec::with_capacity(pieces.len());
+    for piece in pieces {
+        if piece.is_empty() {
+            continue;
+        }
+        match (merged.last_mut(), piece) {
+            (Some(Piece::Original(last)), Piece::Original(range)) if last.end == range.start => {
+                last.end = range.end;
+            }
+            (Some(Piece::Added(last)), Piece::Added(bytes)) => {
+                *last = last.iter().chain(bytes.iter()).copied().collect();
+            }
+            (_, piece) => merged.push(piece),
+        }
+    }
+    merged
+}
+
+/// Parses a byte pattern written in hex, such as `DE AD be ef` or `0xCAFE`.
+pub fn parse_hex_pattern(text: &str) -> Option<Vec<u8>> {
+    let digits = text
+        .split_whitespace()
+        .map(|word| {
+            word.strip_prefix("0x")
+                .or_else(|| word.strip_prefix("0X"))
+                .unwrap_or(word)
+        })
+        .collect::<String>();
+    if digits.is_empty() || digits.len() % 2 != 0 {
+        return None;
+    }
+    hex::decode(digits).ok()
+}
+
+/// Parses an offset, in decimal or, prefixed with `0x`, in hex.
+pub fn parse_offset(text: &str) -> Option<u64> {
+    parse_int::parse::<u64>(text.trim()).ok()
+}
+
+/// Returns where `pattern` first starts in `haystack`, ignoring the bytes that couldn't be read.
+pub fn find_pattern(haystack: &[Option<u8>], pattern: &[u8]) -> Option<usize> {
+    if pattern.is_empty() {
+        return None;
+    }
+    haystack.windows(pattern.len()).position(|window| {
+        window
+            .iter()
+            .zip(pattern)
+            .all(|(byte, expected)| *byte == Some(*expected))
+    })
+}
+
+/// Returns where `pattern` last starts in `haystack`, ignoring the bytes that couldn't be read.
+pub fn rfind_pattern(haystack: &[Option<u8>], pattern: &[u8]) -> Option<usize> {
+    if pattern.is_empty() {
+        return None;
+    }
+    haystack.windows(pattern.len()).rposition(|window| {
+        window
+            .iter()
+            .zip(pattern)
+            .all(|(byte, expected)| *byte == Some(*expected))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn added(bytes: &[u8]) -> Piece {
+        Piece::Added(bytes.into())
+    }
+
+    /// Reads the document, using the offsets as the source's bytes.
+    fn contents(document: &HexDocument) -> Vec<u8> {
+        document
+            .pieces()
+            .iter()
+            .flat_map(|piece| match piece {
+                Piece::Original(range) => range.clone().map(|offset| offset as u8).collect(),
+                Piece::Added(bytes) => bytes.to_vec(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_overwrite() {
+        let mut document = HexDocument::new(8);
+        document.overwrite(2, &[0xAA, 0xBB]);
+        assert_eq!(contents(&document), [0, 1, 0xAA, 0xBB, 4, 5, 6, 7]);
+        assert!(document.is_edited());
+        assert!(document.preserves_offsets());
+        assert!(document.is_added(3));
+        assert!(!document.is_added(4));
+
+        // Overwriting right after the previous edit continues it.
+        document.overwrite(4, &[0xCC]);
+        assert_eq!(
+            document.pieces(),
+            [
+                Piece::Original(0..2),
+                added(&[0xAA, 0xBB, 0xCC]),
+                Piece::Original(5..8)
+            ]
+        );
+        assert_eq!(
+            document.patches(),
+            [(2, Arc::from(&[0xAA, 0xBB, 0xCC][..]))]
+        );
+
+        // Writing past the end appends.
+        document.overwrite(7, &[0xDD, 0xEE]);
+        assert_eq!(document.len(), 9);
+        assert_eq!(contents(&document)[7..], [0xDD, 0xEE]);
+        assert!(!document.preserves_offsets());
+    }
+
+    #[test]
+    fn test_insert_and_delete() {
+        let mut document = HexDocument::new(6);
+        document.insert(3, &[0xAA]);
+        assert_eq!(contents(&document), [0, 1, 2, 0xAA, 3, 4, 5]);
+        assert!(!document.preserves_offsets());
+
+        document.delete(0..2);
+        assert_eq!(contents(&document), [2, 0xAA, 3, 4, 5]);
+        document.delete(1..2);
+        assert_eq!(contents(&document), [2, 3, 4, 5]);
+        assert_eq!(document.pieces(), [Piece::Original(2..6)]);
+
+        document.delete(0..100);
+        assert!(document.is_empty());
+        document.insert(10, &[1, 2]);
+        assert_eq!(contents(&document), [1, 2]);
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let mut document = HexDocument::new(4);
+        document.overwrite(0, &[0xAA]);
+        document.insert(4, &[0xBB]);
+        assert_eq!(contents(&document), [0xAA, 1, 2, 3, 0xBB]);
+
+        assert!(document.undo());
+        assert_eq!(contents(&document), [0xAA, 1, 2, 3]);
+        assert!(document.undo());
+        assert!(!document.is_edited());
+        assert!(!document.undo());
+
+        assert!(document.redo());
+        assert_eq!(contents(&document), [0xAA, 1, 2, 3]);
+
+        // Editing drops what could be redone.
+        document.delete(3..4);
+        assert!(!document.can_redo());
+        assert_eq!(contents(&document), [0xAA, 1, 2]);
+
+        document.overwrite(0, &[0x10]);
+        document.overwrite(0, &[0x12]);
+        document.merge_last_edits();
+        assert_eq!(contents(&document), [0x12, 1, 2]);
+        assert!(document.undo());
+        assert_eq!(contents(&document), [0xAA, 1, 2]);
+
+        document.reset(3);
+        assert!(!document.is_edited());
+        assert!(!document.can_undo());
+    }
+
+    #[test]
+    fn test_slice() {
+        let mut document = HexDocument::new(10);
+        document.overwrite(4, &[0xAA, 0xBB]);
+        assert_eq!(
+            document.slice(3..7),
+            [
+                Piece::Original(3..4),
+                added(&[0xAA, 0xBB]),
+                Piece::Original(6..7)
+            ]
+        );
+        assert_eq!(document.slice(5..5), []);
+        assert_eq!(document.slice(8..20), [Piece::Original(8..10)]);
+    }
+
+    #[test]
+    fn test_parse_hex_pattern() {
+        assert_eq!(
+            parse_hex_pattern("DE AD be ef"),
+            Some(vec![0xDE, 0xAD, 0xBE, 0xEF])
+        );
+        assert_eq!(parse_hex_pattern("0xCAFE"), Some(vec![0xCA, 0xFE]));
+        assert_eq!(parse_hex_pattern("ABC"), None);
+        assert_eq!(parse_hex_pattern("zz"), None);
+        assert_eq!(parse_hex_pattern(" "), None);
+    }
+
+    #[test]
+    fn test_parse_offset() {
+        assert_eq!(parse_offset("1024"), Some(1024));
+        assert_eq!(parse_offset(" 0x1F "), Some(31));
+        assert_eq!(parse_offset("nope"), None);
+    }
+
+    #[test]
+    fn test_find_pattern() {
+        let haystack = [Some(1), None, Some(2), Some(3), Some(1), Some(2), Some(3)];
+        assert_eq!(find_pattern(&haystack, &[2, 3]), Some(2));
+        assert_eq!(rfind_pattern(&haystack, &[2, 3]), Some(5));
+        // Unreadable bytes never match.
+        assert_eq!(find_pattern(&haystack, &[1, 2]), Some(4));
+        assert_eq!(find_pattern(&haystack, &[4]), None);
+        assert_eq!(find_pattern(&haystack, &[]), None);
+    }
+}
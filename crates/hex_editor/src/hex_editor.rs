@@ -0,0 +1,1245 @@
+mod byte_source;
+mod data_inspector;
+mod hex_document;
+
+use std::{
+    ops::{Range, RangeInclusive},
+    path::PathBuf,
+    rc::Rc,
+    sync::LazyLock,
+};
+
+use anyhow::Result;
+use editor::Editor;
+use gpui::{
+    AnyElement, App, AsyncWindowContext, ClickEvent, Context, Entity, EventEmitter, FocusHandle,
+    Focusable, KeyDownEvent, MouseButton, MouseDownEvent, ScrollStrategy, SharedString, Task,
+    UniformListScrollHandle, WeakEntity, Window, actions, uniform_list,
+};
+use project::Project;
+use ui::{Tooltip, prelude::*};
+use util::ResultExt as _;
+use workspace::{
+    Workspace,
+    invalid_item_view::InvalidItemView,
+    item::{Item, ItemBufferKind, ItemEvent, SaveOptions},
+    notifications::DetachAndPromptErr as _,
+};
+use zed_actions::hex_editor::OpenInHexEditor;
+
+pub use crate::byte_source::{ByteSource, FileByteSource};
+use crate::byte_source::{PAGE_SIZE, PageCache};
+use crate::data_inspector::Endianness;
+pub use crate::hex_document::{HexDocument, Piece};
+
+actions!(
+    hex_editor,
+    [
+        /// Move the cursor one byte to the left.
+        MoveLeft,
+        /// Move the cursor one byte to the right.
+        MoveRight,
+        /// Move the cursor one row up.
+        MoveUp,
+        /// Move the cursor one row down.
+        MoveDown,
+        /// Move the cursor one page up.
+        MovePageUp,
+        /// Move the cursor one page down.
+        MovePageDown,
+        /// Move the cursor to the start of its row.
+        MoveToRowStart,
+        /// Move the cursor to the end of its row.
+        MoveToRowEnd,
+        /// Move the cursor to the first byte.
+        MoveToStart,
+        /// Move the cursor to the last byte.
+        MoveToEnd,
+        /// Extend the selection one byte to the left.
+        SelectLeft,
+        /// Extend the selection one byte to the right.
+        SelectRight,
+        /// Extend the selection one row up.
+        SelectUp,
+        /// Extend the selection one row down.
+        SelectDown,
+        /// Delete the selected bytes, or the byte under the cursor.
+        Delete,
+        /// Delete the selected bytes, or the byte before the cursor.
+        Backspace,
+        /// Switch between inserting and overwriting bytes.
+        ToggleInsertMode,
+        /// Move the cursor between the hex and the ASCII column.
+        SwitchColumn,
+        /// Switch the byte order the data inspector reads values in.
+        ToggleEndianness,
+        /// Move the cursor to an offset.
+        GoToOffset,
+        /// Search for a sequence of bytes written in hex.
+        FindBytes,
+        /// Search for text.
+        FindText,
+        /// Select the next match of the search.
+        FindNext,
+        /// Select the previous match of the search.
+        FindPrevious,
+        /// Undo the last edit.
+        Undo,
+        /// Redo the last undone edit.
+        Redo,
+    ]
+);
+
+const BYTES_PER_ROW: u64 = 16;
+/// How many rows moving the cursor a page moves it by.
+const PAGE_ROWS: u64 = 32;
+/// How many rows the list holds at once. The rows of larger sources are shown a window at a
+/// time, as the list can't scroll through more of them precisely.
+const WINDOW_ROWS: u64 = 1 << 16;
+const SEARCH_CHUNK_LEN: u64 = 1 << 20;
+/// How far searching an address space without a known end goes from the cursor.
+const MAX_UNBOUNDED_SEARCH_LEN: u64 = 64 << 20;
+
+static HEX_BYTES: LazyLock<[SharedString; 256]> =
+    LazyLock::new(|| std::array::from_fn(|byte| SharedString::from(format!("{byte:02X}"))));
+static UNKNOWN_BYTE: SharedString = SharedString::new_static("??");
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum EditMode {
+    #[default]
+    Overwrite,
+    Insert,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Column {
+    #[default]
+    Hex,
+    Ascii,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum QueryMode {
+    GoToOffset,
+    FindBytes,
+    FindText,
+}
+
+impl QueryMode {
+    fn placeholder(self) -> &'static str {
+        match self {
+            QueryMode::GoToOffset => "Go to offset, such as 4096 or 0x1000…",
+            QueryMode::FindBytes => "Find bytes, such as DE AD BE EF…",
+            QueryMode::FindText => "Find text…",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SearchDirection {
+    Forward,
+    Backward,
+}
+
+pub enum HexEditorEvent {
+    DirtyChanged,
+}
+
+/// Shows the bytes of a [`ByteSource`] as hex and ASCII, and edits them.
+pub struct HexEditor {
+    source: Rc<dyn ByteSource>,
+    document: HexDocument,
+    pages: PageCache,
+    /// Bumped whenever the cached pages are dropped, so that reads started before are ignored.
+    pages_generation: usize,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    /// The row at the top of the list.
+    base_row: u64,
+    cursor: u64,
+    selection_anchor: Option<u64>,
+    /// Whether the next hex digit typed goes into the low half of the byte at the cursor.
+    editing_low_nibble: bool,
+    edit_mode: EditMode,
+    column: Column,
+    endianness: Endianness,
+    query_editor: Entity<Editor>,
+    query_mode: Option<QueryMode>,
+    search_pattern: Option<Vec<u8>>,
+    search_task: Option<Task<()>>,
+    saving: bool,
+    status: Option<SharedString>,
+}
+
+impl HexEditor {
+    pub fn new(source: Rc<dyn ByteSource>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let query_editor = cx.new(|cx| Editor::single_line(window, cx));
+        Self {
+            document: HexDocument::new(source.len().unwrap_or(u64::MAX)),
+            source,
+            pages: PageCache::default(),
+            pages_generation: 0,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            base_row: 0,
+            cursor: 0,
+            selection_anchor: None,
+            editing_low_nibble: false,
+            edit_mode: EditMode::default(),
+            column: Column::default(),
+            endianness: Endianness::default(),
+            query_editor,
+            query_mode: None,
+            search_pattern: None,
+            search_task: None,
+            saving: false,
+            status: None,
+        }
+    }
+
+    /// Opens a local file in a hex editor, or activates the one it's already open in.
+    pub fn open_file(
+        workspace: &mut Workspace,
+        abs_path: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Task<Result<Entity<HexEditor>>> {
+        let existing = workspace
+            .items_of_type::<HexEditor>(cx)
+            .find(|editor| editor.read(cx).source.abs_path() == Some(abs_path.as_path()));
+        if let Some(existing) = existing {
+            workspace.activate_item(&existing, true, true, window, cx);
+            return Task::ready(Ok(existing));
+        }
+
+        let fs = workspace.app_state().fs.clone();
+        cx.spawn_in(window, async move |workspace, cx| {
+            let source = FileByteSource::open(fs, abs_path.clone()).await?;
+            workspace.update_in(cx, |workspace, window, cx| {
+                let hex_editor = cx.new(|cx| HexEditor::new(Rc::new(source), window, cx));
+                // Take the place of the view saying the file couldn't be opened.
+                let invalid_item = workspace
+                    .active_pane()
+                    .read(cx)
+                    .items_of_type::<InvalidItemView>()
+                    .find(|item| item.read(cx).abs_path.as_ref() == abs_path.as_path());
+                workspace.active_pane().update(cx, |pane, cx| {
+                    let index = invalid_item
+                        .as_ref()
+                        .and_then(|item| pane.index_for_item(item));
+                    pane.add_item(Box::new(hex_editor.clone()), true, true, index, window, cx);
+                    if let Some(invalid_item) = invalid_item {
+                        pane.remove_item(invalid_item.item_id(), false, false, window, cx);
+                    }
+                });
+                hex_editor
+            })
+        })
+    }
+
+    fn register(workspace: &mut Workspace, _window: &mut Window, _cx: &mut Context<Workspace>) {
+        workspace.register_action(|workspace, _: &OpenInHexEditor, window, cx| {
+            let project = workspace.project().read(cx);
+            if !project.is_local() {
+                return;
+            }
+            let Some(active_item) = workspace.active_item(cx) else {
+                return;
+            };
+            let abs_path = if let Some(invalid_item) = active_item.downcast::<InvalidItemView>() {
+                Some(invalid_item.read(cx).abs_path.to_path_buf())
+            } else {
+                active_item
+                    .project_path(cx)
+                    .and_then(|project_path| project.absolute_path(&project_path, cx))
+            };
+            let Some(abs_path) = abs_path else {
+                return;
+            };
+            Self::open_file(workspace, abs_path, window, cx).detach_and_prompt_err(
+                "Failed to open the file in a hex editor",
+                window,
+                cx,
+                |_, _, _| None,
+            );
+        });
+    }
+
+    pub fn document(&self) -> &HexDocument {
+        &self.document
+    }
+
+    pub fn cursor(&self) -> u64 {
+        self.cursor
+    }
+
+    /// Moves the cursor to `offset` and scrolls it into view.
+    pub fn go_to_offset(&mut self, offset: u64, cx: &mut Context<Self>) {
+        self.move_cursor(offset, false, cx);
+    }
+
+    /// Drops the bytes read so far, to show what the source holds now, keeping the edits.
+    pub fn reload_bytes(&mut self, cx: &mut Context<Self>) {
+        self.pages.clear();
+        self.pages_generation += 1;
+        cx.notify();
+    }
+
+    fn can_resize(&self) -> bool {
+        self.source.can_resize()
+    }
+
+    /// The last offset the cursor can be at, which is past the last byte when bytes can be appended.
+    fn max_cursor(&self) -> u64 {
+        let len = self.document.len();
+        if self.can_resize() {
+            len
+        } else {
+            len.saturating_sub(1)
+        }
+    }
+
+    fn row_count(&self) -> u64 {
+        self.max_cursor() / BYTES_PER_ROW + 1
+    }
+
+    fn offset_digits(&self) -> usize {
+        if self.document.len() > u32::MAX as u64 {
+            16
+        } else {
+            8
+        }
+    }
+
+    fn selection(&self) -> Option<RangeInclusive<u64>> {
+        let anchor = self.selection_anchor?;
+        Some(anchor.min(self.cursor)..=anchor.max(self.cursor))
+    }
+
+    /// Returns the bytes at `range` of the document, `None` for those not read yet.
+    fn read(&mut self, range: Range<u64>, cx: &mut Context<Self>) -> Vec<Option<u8>> {
+        let mut bytes = Vec::with_capacity((range.end - range.start) as usize);
+        let mut pages_to_load = Vec::new();
+        for piece in self.document.slice(range) {
+            match piece {
+                Piece::Original(range) => {
+                    bytes.extend(self.pages.read(range, |page| pages_to_load.push(page)))
+                }
+                Piece::Added(added) => bytes.extend(added.iter().copied().map(Some)),
+            }
+        }
+        for page in pages_to_load {
+            self.load_page(page, cx);
+        }
+        bytes
+    }
+
+    fn load_page(&mut self, page: u64, cx: &mut Context<Self>) {
+        let start = page * PAGE_SIZE;
+        let end = start
+            .saturating_add(PAGE_SIZE)
+            .min(self.document.original_len());
+        let generation = self.pages_generation;
+        let read = self.source.read(start..end, cx);
+        cx.spawn(async move |this, cx| {
+            let contents = read.await.log_err().unwrap_or_default();
+            this.update(cx, |this, cx| {
+                if this.pages_generation == generation {
+                    this.pages.insert(page, contents.into());
+                    cx.notify();
+                }
+            })
+        })
+        .detach();
+    }
+
+    /// Reads `range` of the document from the source, for searching it.
+    fn read_document(&self, range: Range<u64>, cx: &mut App) -> Task<Result<Vec<Option<u8>>>> {
+        let reads = self
+            .document
+            .slice(range)
+            .into_iter()
+            .map(|piece| match piece {
+                Piece::Original(range) => self.source.read(range, cx),
+                Piece::Added(bytes) => Task::ready(Ok(bytes.iter().copied().map(Some).collect())),
+            })
+            .collect::<Vec<_>>();
+        cx.background_spawn(async move {
+            let mut bytes = Vec::new();
+            for read in reads {
+                bytes.extend(read.await?);
+            }
+            Ok(bytes)
+        })
+    }
+
+    fn move_cursor(&mut self, offset: u64, selecting: bool, cx: &mut Context<Self>) {
+        if selecting {
+            self.selection_anchor.get_or_insert(self.cursor);
+        } else {
+            self.selection_anchor = None;
+        }
+        self.cursor = offset.min(self.max_cursor());
+        self.editing_low_nibble = false;
+        self.reveal_cursor();
+        cx.notify();
+    }
+
+    fn reveal_cursor(&mut self) {
+        let row = self.cursor / BYTES_PER_ROW;
+        if row < self.base_row || row >= self.base_row + WINDOW_ROWS {
+            let last_base_row = self.row_count().saturating_sub(WINDOW_ROWS);
+            self.base_row = row.saturating_sub(WINDOW_ROWS / 2).min(last_base_row);
+        }
+        self.scroll_handle
+            .scroll_to_item((row - self.base_row) as usize, ScrollStrategy::Nearest);
+    }
+
+    fn move_by(&mut self, delta: i64, selecting: bool, cx: &mut Context<Self>) {
+        let offset = self.cursor.saturating_add_signed(delta);
+        self.move_cursor(offset, selecting, cx);
+    }
+
+    fn move_left(&mut self, _: &MoveLeft, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(-1, false, cx);
+    }
+
+    fn move_right(&mut self, _: &MoveRight, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(1, false, cx);
+    }
+
+    fn move_up(&mut self, _: &MoveUp, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(-(BYTES_PER_ROW as i64), false, cx);
+    }
+
+    fn move_down(&mut self, _: &MoveDown, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(BYTES_PER_ROW as i64, false, cx);
+    }
+
+    fn move_page_up(&mut self, _: &MovePageUp, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(-((BYTES_PER_ROW * PAGE_ROWS) as i64), false, cx);
+    }
+
+    fn move_page_down(&mut self, _: &MovePageDown, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by((BYTES_PER_ROW * PAGE_ROWS) as i64, false, cx);
+    }
+
+    fn move_to_row_start(&mut self, _: &MoveToRowStart, _: &mut Window, cx: &mut Context<Self>) {
+        let offset = self.cursor - self.cursor % BYTES_PER_ROW;
+        self.move_cursor(offset, false, cx);
+    }
+
+    fn move_to_row_end(&mut self, _: &MoveToRowEnd, _: &mut Window, cx: &mut Context<Self>) {
+        let offset = self.cursor - self.cursor % BYTES_PER_ROW + BYTES_PER_ROW - 1;
+        self.move_cursor(offset, false, cx);
+    }
+
+    fn move_to_start(&mut self, _: &MoveToStart, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor(0, false, cx);
+    }
+
+    fn move_to_end(&mut self, _: &MoveToEnd, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor(u64::MAX, false, cx);
+    }
+
+    fn select_left(&mut self, _: &SelectLeft, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(-1, true, cx);
+    }
+
+    fn select_right(&mut self, _: &SelectRight, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(1, true, cx);
+    }
+
+    fn select_up(&mut self, _: &SelectUp, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(-(BYTES_PER_ROW as i64), true, cx);
+    }
+
+    fn select_down(&mut self, _: &SelectDown, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(BYTES_PER_ROW as i64, true, cx);
+    }
+
+    fn edited(&mut self, cx: &mut Context<Self>) {
+        self.status = None;
+        self.reveal_cursor();
+        cx.emit(HexEditorEvent::DirtyChanged);
+        cx.notify();
+    }
+
+    /// Deletes the selected bytes, returning whether there were any.
+    fn delete_selection(&mut self) -> bool {
+        let Some(selection) = self.selection() else {
+            return false;
+        };
+        self.document
+            .delete(*selection.start()..selection.end().saturating_add(1));
+        self.cursor = *selection.start();
+        self.selection_anchor = None;
+        true
+    }
+
+    fn delete(&mut self, _: &Delete, _: &mut Window, cx: &mut Context<Self>) {
+        if self.saving || !self.can_resize() {
+            return;
+        }
+        if !self.delete_selection() {
+            self.document
+                .delete(self.cursor..self.cursor.saturating_add(1));
+        }
+        self.cursor = self.cursor.min(self.max_cursor());
+        self.editing_low_nibble = false;
+        self.edited(cx);
+    }
+
+    fn backspace(&mut self, _: &Backspace, _: &mut Window, cx: &mut Context<Self>) {
+        if self.saving || !self.can_resize() {
+            return;
+        }
+        if !self.delete_selection() {
+            if self.cursor == 0 {
+                return;
+            }
+            self.cursor -= 1;
+            self.document
+                .delete(self.cursor..self.cursor.saturating_add(1));
+        }
+        self.editing_low_nibble = false;
+        self.edited(cx);
+    }
+
+    fn toggle_insert_mode(&mut self, _: &ToggleInsertMode, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.can_resize() {
+            return;
+        }
+        self.edit_mode = match self.edit_mode {
+            EditMode::Overwrite => EditMode::Insert,
+            EditMode::Insert => EditMode::Overwrite,
+        };
+        self.editing_low_nibble = false;
+        cx.notify();
+    }
+
+    fn switch_column(&mut self, _: &SwitchColumn, _: &mut Window, cx: &mut Context<Self>) {
+        self.column = match self.column {
+            Column::Hex => Column::Ascii,
+            Column::Ascii => Column::Hex,
+        };
+        self.editing_low_nibble = false;
+        cx.notify();
+    }
+
+    fn toggle_endianness(&mut self, _: &ToggleEndianness, _: &mut Window, cx: &mut Context<Self>) {
+        self.endianness = self.endianness.toggle();
+        cx.notify();
+    }
+
+    fn undo(&mut self, _: &Undo, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.saving && self.document.undo() {
+            self.selection_anchor = None;
+            self.editing_low_nibble = false;
+            self.cursor = self.cursor.min(self.max_cursor());
+            self.edited(cx);
+        }
+    }
+
+    fn redo(&mut self, _: &Redo, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.saving && self.document.redo() {
+            self.selection_anchor = None;
+            self.editing_low_nibble = false;
+            self.cursor = self.cursor.min(self.max_cursor());
+            self.edited(cx);
+        }
+    }
+
+    fn key_down(&mut self, event: &KeyDownEvent, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.focus_handle.is_focused(window) || self.saving {
+            return;
+        }
+        let modifiers = &event.keystroke.modifiers;
+        if modifiers.control || modifiers.alt || modifiers.platform || modifiers.function {
+            return;
+        }
+        let Some(character) = event
+            .keystroke
+            .key_char
+            .as_deref()
+            .and_then(|key_char| key_char.chars().next())
+        else {
+            return;
+        };
+        match self.column {
+            Column::Hex => {
+                let Some(digit) = character.to_digit(16) else {
+                    return;
+                };
+                self.type_nibble(digit as u8, cx);
+            }
+            Column::Ascii => {
+                if !character.is_ascii_graphic() && character != ' ' {
+                    return;
+                }
+                self.type_byte(character as u8);
+            }
+        }
+        self.edited(cx);
+        cx.stop_propagation();
+    }
+
+    fn type_nibble(&mut self, digit: u8, cx: &mut Context<Self>) {
+        if !self.editing_low_nibble {
+            if self.edit_mode == EditMode::Insert {
+                self.delete_selection();
+                self.document.insert(self.cursor, &[digit << 4]);
+            } else {
+                self.selection_anchor = None;
+                let current = self.byte_at(self.cursor, cx).unwrap_or(0);
+                self.document
+                    .overwrite(self.cursor, &[digit << 4 | current & 0x0F]);
+            }
+            self.editing_low_nibble = true;
+        } else {
+            let current = self.byte_at(self.cursor, cx).unwrap_or(0);
+            self.document
+                .overwrite(self.cursor, &[current & 0xF0 | digit]);
+            self.document.merge_last_edits();
+            self.editing_low_nibble = false;
+            self.cursor = (self.cursor + 1).min(self.max_cursor());
+        }
+    }
+
+    fn type_byte(&mut self, byte: u8) {
+        if self.edit_mode == EditMode::Insert {
+            self.delete_selection();
+            self.document.insert(self.cursor, &[byte]);
+        } else {
+            self.selection_anchor = None;
+            self.document.overwrite(self.cursor, &[byte]);
+        }
+        self.editing_low_nibble = false;
+        self.cursor = (self.cursor + 1).min(self.max_cursor());
+    }
+
+    fn byte_at(&mut self, offset: u64, cx: &mut Context<Self>) -> Option<u8> {
+        self.read(offset..offset.saturating_add(1), cx)
+            .first()
+            .copied()
+            .flatten()
+    }
+
+    fn handle_cell_click(
+        &mut self,
+        offset: u64,
+        column: Column,
+        event: &MouseDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.column = column;
+        self.move_cursor(offset, event.modifiers.shift, cx);
+        window.focus(&self.focus_handle, cx);
+    }
+
+    fn deploy_query(&mut self, mode: QueryMode, window: &mut Window, cx: &mut Context<Self>) {
+        self.query_mode = Some(mode);
+        self.query_editor.update(cx, |editor, cx| {
+            editor.set_placeholder_text(mode.placeholder(), window, cx);
+            editor.select_all(&Default::default(), window, cx);
+        });
+        window.focus(&self.query_editor.focus_handle(cx), cx);
+        cx.notify();
+    }
+
+    fn go_to_offset_action(&mut self, _: &GoToOffset, window: &mut Window, cx: &mut Context<Self>) {
+        self.deploy_query(QueryMode::GoToOffset, window, cx);
+    }
+
+    fn find_bytes(&mut self, _: &FindBytes, window: &mut Window, cx: &mut Context<Self>) {
+        self.deploy_query(QueryMode::FindBytes, window, cx);
+    }
+
+    fn find_text(&mut self, _: &FindText, window: &mut Window, cx: &mut Context<Self>) {
+        self.deploy_query(QueryMode::FindText, window, cx);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(mode) = self.query_mode else {
+            return;
+        };
+        if !self.query_editor.focus_handle(cx).is_focused(window) {
+            return;
+        }
+        let query = self.query_editor.read(cx).text(cx);
+        match mode {
+            QueryMode::GoToOffset => {
+                let Some(offset) = hex_document::parse_offset(&query) else {
+                    self.status = Some("Not a valid offset".into());
+                    cx.notify();
+                    return;
+                };
+                self.go_to_offset(offset, cx);
+            }
+            QueryMode::FindBytes | QueryMode::FindText => {
+                let pattern = if mode == QueryMode::FindBytes {
+                    hex_document::parse_hex_pattern(&query)
+                } else {
+                    Some(query.into_bytes()).filter(|bytes| !bytes.is_empty())
+                };
+                let Some(pattern) = pattern else {
+                    self.status = Some("Not a valid search".into());
+                    cx.notify();
+                    return;
+                };
+                self.search_pattern = Some(pattern);
+                // Starting on the cursor finds a match right at it, unlike looking for the next one.
+                self.search(self.cursor, SearchDirection::Forward, window, cx);
+            }
+        }
+        self.query_mode = None;
+        window.focus(&self.focus_handle, cx);
+        cx.notify();
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        if self.query_mode.take().is_some() {
+            window.focus(&self.focus_handle, cx);
+        } else {
+            self.selection_anchor = None;
+            self.search_task = None;
+            self.status = None;
+        }
+        cx.notify();
+    }
+
+    fn find_next(&mut self, _: &FindNext, window: &mut Window, cx: &mut Context<Self>) {
+        self.search(
+            self.cursor.saturating_add(1),
+            SearchDirection::Forward,
+            window,
+            cx,
+        );
+    }
+
+    fn find_previous(&mut self, _: &FindPrevious, window: &mut Window, cx: &mut Context<Self>) {
+        let start = self
+            .selection()
+            .map_or(self.cursor, |selection| *selection.start());
+        self.search(start, SearchDirection::Backward, window, cx);
+    }
+
+    /// Searches for the pattern starting at `from` going forward, or before `from` going
+    /// backward, wrapping around the end of sources of a known length.
+    fn search(
+        &mut self,
+        from: u64,
+        direction: SearchDirection,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(pattern) = self.search_pattern.clone() else {
+            return;
+        };
+        let len = self.document.len();
+        let ranges = if self.source.len().is_none() {
+            match direction {
+                SearchDirection::Forward => {
+                    vec![from..from.saturating_add(MAX_UNBOUNDED_SEARCH_LEN)]
+                }
+                SearchDirection::Backward => {
+                    vec![from.saturating_sub(MAX_UNBOUNDED_SEARCH_LEN)..from]
+                }
+            }
+        } else {
+            let from = from.min(len);
+            match direction {
+                SearchDirection::Forward => vec![from..len, 0..from],
+                SearchDirection::Backward => vec![0..from, from..len],
+            }
+        };
+
+        self.status = Some("Searching…".into());
+        cx.notify();
+        self.search_task = Some(cx.spawn_in(window, async move |this, cx| {
+            let mut found = Ok(None);
+            for range in ranges {
+                found = search_range(&this, &pattern, range, direction, cx).await;
+                if !matches!(found, Ok(None)) {
+                    break;
+                }
+            }
+            this.update(cx, |this, cx| {
+                match found {
+                    Ok(Some(offset)) => {
+                        this.move_cursor(offset + pattern.len() as u64 - 1, false, cx);
+                        this.selection_anchor = Some(offset);
+                        this.status = None;
+                    }
+                    Ok(None) => this.status = Some("No matches".into()),
+                    Err(error) => this.status = Some(format!("Search failed: {error}").into()),
+                }
+                this.search_task = None;
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    fn render_rows(&mut self, range: Range<usize>, cx: &mut Context<Self>) -> Vec<AnyElement> {
+        let first_offset = (self.base_row + range.start as u64) * BYTES_PER_ROW;
+        let end_offset = (self.base_row + range.end as u64)
+            .saturating_mul(BYTES_PER_ROW)
+            .min(self.document.len());
+        let bytes = self.read(first_offset.min(end_offset)..end_offset, cx);
+        self.pages.evict_outside(first_offset..end_offset);
+
+        let selection = self.selection();
+        let digits = self.offset_digits();
+        let entity = cx.entity();
+        range
+            .map(|ix| {
+                let row_offset = (self.base_row + ix as u64) * BYTES_PER_ROW;
+                let start = ((row_offset - first_offset) as usize).min(bytes.len());
+                let end = (start + BYTES_PER_ROW as usize).min(bytes.len());
+                self.render_row(
+                    row_offset,
+                    &bytes[start..end],
+                    selection.as_ref(),
+                    digits,
+                    &entity,
+                    cx,
+                )
+            })
+            .collect()
+    }
+
+    fn render_row(
+        &self,
+        row_offset: u64,
+        bytes: &[Option<u8>],
+        selection: Option<&RangeInclusive<u64>>,
+        digits: usize,
+        entity: &Entity<Self>,
+        cx: &App,
+    ) -> AnyElement {
+        let colors = cx.theme().colors();
+        // Past the last byte, the cursor can still be put where bytes are appended.
+        let cells = (0..BYTES_PER_ROW)
+            .map(|ix| {
+                let offset = row_offset + ix;
+                let byte = bytes.get(ix as usize).copied();
+                let is_cursor = offset == self.cursor;
+                let is_selected = selection.is_some_and(|selection| selection.contains(&offset));
+                let is_added = byte.is_some() && self.document.is_added(offset);
+                (offset, byte, is_cursor, is_selected, is_added)
+            })
+            .filter(|(offset, byte, ..)| byte.is_some() || *offset <= self.max_cursor())
+            .collect::<Vec<_>>();
+
+        let cell = |id: (&'static str, u64),
+                    column: Column,
+                    text: SharedString,
+                    color: Color,
+                    (offset, is_cursor, is_selected): (u64, bool, bool)| {
+            let entity = entity.clone();
+            div()
+                .id(id)
+                .px_0p5()
+                .rounded_xs()
+                .when(is_selected, |this| {
+                    this.bg(Color::Selected.color(cx).opacity(0.2))
+                })
+                .when(is_cursor, |this| {
+                    if column == self.column {
+                        this.bg(colors.element_selected)
+                    } else {
+                        this.border_1().border_color(colors.border_focused)
+                    }
+                })
+                .child(
+                    Label::new(text)
+                        .buffer_font(cx)
+                        .size(LabelSize::Small)
+                        .color(color),
+                )
+                .on_mouse_down(MouseButton::Left, move |event, window, cx| {
+                    entity.update(cx, |this, cx| {
+                        this.handle_cell_click(offset, column, event, window, cx)
+                    });
+                    cx.stop_propagation();
+                })
+        };
+
+        h_flex()
+            .id(("hex-row", row_offset))
+            .gap_x_2()
+            .child(
+                div()
+                    .px_1()
+                    .border_r_1()
+                    .border_color(colors.border_variant)
+                    .child(
+                        Label::new(format!("{row_offset:0digits$X}"))
+                            .buffer_font(cx)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+            )
+            .child(h_flex().children(cells.iter().map(
+                |&(offset, byte, is_cursor, is_selected, is_added)| {
+                    let (text, color) = match byte {
+                        Some(Some(byte)) => (
+                            HEX_BYTES[byte as usize].clone(),
+                            if is_added {
+                                Color::Modified
+                            } else {
+                                Color::Default
+                            },
+                        ),
+                        Some(None) => (UNKNOWN_BYTE.clone(), Color::Muted),
+                        None => ("  ".into(), Color::Default),
+                    };
+                    cell(
+                        ("hex-cell", offset),
+                        Column::Hex,
+                        text,
+                        color,
+                        (offset, is_cursor, is_selected),
+                    )
+                    .when(offset % BYTES_PER_ROW == BYTES_PER_ROW / 2 - 1, |this| {
+                        this.mr_2()
+                    })
+                },
+            )))
+            .child(
+                h_flex()
+                    .px_1()
+                    .border_l_1()
+                    .border_color(colors.border_variant)
+                    .children(cells.iter().map(
+                        |&(offset, byte, is_cursor, is_selected, is_added)| {
+                            let (text, color) = match byte {
+                                Some(Some(byte)) if byte.is_ascii_graphic() || byte == b' ' => (
+                                    SharedString::from((byte as char).to_string()),
+                                    if is_added {
+                                        Color::Modified
+                                    } else {
+                                        Color::Default
+                                    },
+                                ),
+                                Some(Some(_)) => ("·".into(), Color::Muted),
+                                Some(None) => ("?".into(), Color::Muted),
+                                None => (" ".into(), Color::Default),
+                            };
+                            cell(
+                                ("ascii-cell", offset),
+                                Column::Ascii,
+                                text,
+                                color,
+                                (offset, is_cursor, is_selected),
+                            )
+                        },
+                    )),
+            )
+            .into_any_element()
+    }
+
+    fn render_inspector(&mut self, cx: &mut Context<Self>) -> impl IntoElement {
+        let bytes = self.read(self.cursor..self.cursor.saturating_add(8), cx);
+        let values = data_inspector::inspect(&bytes, self.endianness);
+        v_flex()
+            .w_64()
+            .flex_none()
+            .p_2()
+            .gap_1()
+            .border_l_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(Label::new("Data Inspector").size(LabelSize::Small))
+                    .child(
+                        Button::new("toggle-endianness", self.endianness.label())
+                            .label_size(LabelSize::Small)
+                            .tooltip(Tooltip::for_action_title(
+                                "Switch Byte Order",
+                                &ToggleEndianness,
+                            ))
+                            .on_click(cx.listener(|this, _: &ClickEvent, window, cx| {
+                                this.toggle_endianness(&ToggleEndianness, window, cx)
+                            })),
+                    ),
+            )
+            .children(values.into_iter().map(|value| {
+                h_flex()
+                    .justify_between()
+                    .gap_2()
+                    .child(
+                        Label::new(value.label)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        Label::new(value.value.unwrap_or_else(|| "—".to_string()))
+                            .buffer_font(cx)
+                            .size(LabelSize::Small)
+                            .single_line()
+                            .truncate(),
+                    )
+            }))
+    }
+
+    fn render_status_bar(&self, cx: &Context<Self>) -> impl IntoElement {
+        let digits = self.offset_digits();
+        let mut position = format!("Offset 0x{:0digits$X}", self.cursor);
+        if let Some(selection) = self.selection() {
+            let len = selection.end() - selection.start() + 1;
+            position.push_str(&format!(" · {len} bytes selected"));
+        }
+        h_flex()
+            .px_2()
+            .py_0p5()
+            .gap_3()
+            .border_t_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(Label::new(position).size(LabelSize::Small))
+            .when(self.can_resize(), |this| {
+                this.child(
+                    Label::new(match self.edit_mode {
+                        EditMode::Overwrite => "Overwrite",
+                        EditMode::Insert => "Insert",
+                    })
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+                )
+            })
+            .when_some(self.status.clone(), |this, status| {
+                this.child(
+                    Label::new(status)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+    }
+}
+
+/// Searches for matches of `pattern` starting in `range` of the document, a chunk at a time.
+async fn search_range(
+    this: &WeakEntity<HexEditor>,
+    pattern: &[u8],
+    range: Range<u64>,
+    direction: SearchDirection,
+    cx: &mut AsyncWindowContext,
+) -> Result<Option<u64>> {
+    let overlap = pattern.len() as u64 - 1;
+    let mut remaining = range;
+    while !remaining.is_empty() {
+        let chunk = match direction {
+            SearchDirection::Forward => {
+                remaining.start
+                    ..remaining
+                        .end
+                        .min(remaining.start.saturating_add(SEARCH_CHUNK_LEN))
+            }
+            SearchDirection::Backward => {
+                remaining
+                    .start
+                    .max(remaining.end.saturating_sub(SEARCH_CHUNK_LEN))
+                    ..remaining.end
+            }
+        };
+        let read = this.update(cx, |this, cx| {
+            let end = chunk.end.saturating_add(overlap).min(this.document.len());
+            this.read_document(chunk.start..end, cx)
+        })?;
+        let bytes = read.await?;
+        let found = match direction {
+            SearchDirection::Forward => hex_document::find_pattern(&bytes, pattern),
+            SearchDirection::Backward => hex_document::rfind_pattern(&bytes, pattern),
+        };
+        if let Some(position) = found {
+            return Ok(Some(chunk.start + position as u64));
+        }
+        match direction {
+            SearchDirection::Forward => remaining.start = chunk.end,
+            SearchDirection::Backward => remaining.end = chunk.start,
+        }
+    }
+    Ok(None)
+}
+
+impl EventEmitter<HexEditorEvent> for HexEditor {}
+
+impl Focusable for HexEditor {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for HexEditor {
+    type Event = HexEditorEvent;
+
+    fn to_item_events(event: &Self::Event, f: &mut dyn FnMut(ItemEvent)) {
+        match event {
+            HexEditorEvent::DirtyChanged => f(ItemEvent::UpdateTab),
+        }
+    }
+
+    fn tab_content_text(&self, _: usize, _: &App) -> SharedString {
+        self.source.title()
+    }
+
+    fn tab_tooltip_text(&self, _: &App) -> Option<SharedString> {
+        self.source
+            .abs_path()
+            .map(|abs_path| abs_path.to_string_lossy().into_owned().into())
+    }
+
+    fn tab_icon(&self, _: &Window, _: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::Binary))
+    }
+
+    fn buffer_kind(&self, _: &App) -> ItemBufferKind {
+        ItemBufferKind::Singleton
+    }
+
+    fn is_dirty(&self, _: &App) -> bool {
+        self.document.is_edited()
+    }
+
+    fn can_save(&self, _: &App) -> bool {
+        !self.saving
+    }
+
+    fn save(
+        &mut self,
+        _options: SaveOptions,
+        _project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        if !self.document.is_edited() {
+            return Task::ready(Ok(()));
+        }
+        let save = self.source.save(&self.document, cx);
+        self.saving = true;
+        cx.spawn_in(window, async move |this, cx| {
+            let result = save.await;
+            this.update(cx, |this, cx| {
+                this.saving = false;
+                let len = result.as_ref().ok().copied();
+                if let Some(len) = len {
+                    this.document.reset(len);
+                    this.cursor = this.cursor.min(this.max_cursor());
+                    this.selection_anchor = None;
+                    this.reload_bytes(cx);
+                    cx.emit(HexEditorEvent::DirtyChanged);
+                }
+            })?;
+            result.map(|_| ())
+        })
+    }
+
+    fn reload(
+        &mut self,
+        _project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let len = self.source.reload_len(cx);
+        cx.spawn_in(window, async move |this, cx| {
+            let len = len.await?;
+            this.update(cx, |this, cx| {
+                this.document.reset(len.unwrap_or(u64::MAX));
+                this.cursor = this.cursor.min(this.max_cursor());
+                this.selection_anchor = None;
+                this.editing_low_nibble = false;
+                this.reload_bytes(cx);
+                cx.emit(HexEditorEvent::DirtyChanged);
+            })
+        })
+    }
+}
+
+impl Render for HexEditor {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let row_count = (self.row_count() - self.base_row).min(WINDOW_ROWS) as usize;
+        v_flex()
+            .key_context("HexEditor")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .on_key_down(cx.listener(Self::key_down))
+            .on_action(cx.listener(Self::move_left))
+            .on_action(cx.listener(Self::move_right))
+            .on_action(cx.listener(Self::move_up))
+            .on_action(cx.listener(Self::move_down))
+            .on_action(cx.listener(Self::move_page_up))
+            .on_action(cx.listener(Self::move_page_down))
+            .on_action(cx.listener(Self::move_to_row_start))
+            .on_action(cx.listener(Self::move_to_row_end))
+            .on_action(cx.listener(Self::move_to_start))
+            .on_action(cx.listener(Self::move_to_end))
+            .on_action(cx.listener(Self::select_left))
+            .on_action(cx.listener(Self::select_right))
+            .on_action(cx.listener(Self::select_up))
+            .on_action(cx.listener(Self::select_down))
+            .on_action(cx.listener(Self::delete))
+            .on_action(cx.listener(Self::backspace))
+            .on_action(cx.listener(Self::toggle_insert_mode))
+            .on_action(cx.listener(Self::switch_column))
+            .on_action(cx.listener(Self::toggle_endianness))
+            .on_action(cx.listener(Self::go_to_offset_action))
+            .on_action(cx.listener(Self::find_bytes))
+            .on_action(cx.listener(Self::find_text))
+            .on_action(cx.listener(Self::find_next))
+            .on_action(cx.listener(Self::find_previous))
+            .on_action(cx.listener(Self::undo))
+            .on_action(cx.listener(Self::redo))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .when(self.query_mode.is_some(), |this| {
+                this.child(
+                    h_flex()
+                        .px_2()
+                        .py_1()
+                        .border_b_1()
+                        .border_color(cx.theme().colors().border_variant)
+                        .child(self.query_editor.clone()),
+                )
+            })
+            .child(
+                h_flex()
+                    .flex_1()
+                    .min_h_0()
+                    .items_start()
+                    .child(
+                        div()
+                            .flex_1()
+                            .h_full()
+                            .p_1()
+                            .child(
+                                uniform_list(
+                                    "hex-editor-rows",
+                                    row_count,
+                                    cx.processor(|this, range: Range<usize>, _, cx| {
+                                        this.render_rows(range, cx)
+                                    }),
+                                )
+                                .size_full()
+                                .track_scroll(&self.scroll_handle),
+                            )
+                            .vertical_scrollbar_for(&self.scroll_handle, window, cx),
+                    )
+                    .child(self.render_inspector(cx)),
+            )
+            .child(self.render_status_bar(cx))
+    }
+}
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, window, cx| {
+        let Some(window) = window else {
+            return;
+        };
+        HexEditor::register(workspace, window, cx);
+    })
+    .detach();
+}
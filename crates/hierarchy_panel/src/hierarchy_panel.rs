@@ -0,0 +1,754 @@
+mod hierarchy_panel_settings;
+
+use std::{ops::Range, sync::Arc};
+
+use collections::HashMap;
+use editor::{Editor, SelectionEffects, scroll::Autoscroll};
+use gpui::{
+    Action, App, AsyncWindowContext, ClickEvent, Context, Entity, EventEmitter, FocusHandle,
+    Focusable, KeyContext, Pixels, Render, ScrollStrategy, SharedString, Task,
+    UniformListScrollHandle, WeakEntity, Window, actions, uniform_list,
+};
+use language::{Anchor, Buffer, Point, ToPoint as _};
+use menu::{Confirm, SelectFirst, SelectLast, SelectNext, SelectPrevious};
+use project::{Fs, HierarchyCall, HierarchyItem, Location, Project};
+use settings::Settings;
+use ui::{ListItem, WithScrollbar, prelude::*};
+use util::ResultExt as _;
+use workspace::{
+    Workspace,
+    dock::{DockPosition, Panel, PanelEvent},
+};
+
+use crate::hierarchy_panel_settings::HierarchyPanelSettings;
+
+actions!(
+    hierarchy_panel,
+    [
+        /// Toggles focus on the hierarchy panel.
+        ToggleFocus,
+        /// Shows the callers of the symbol under the cursor.
+        ShowIncomingCalls,
+        /// Shows the calls made by the symbol under the cursor.
+        ShowOutgoingCalls,
+        /// Shows the supertypes of the type under the cursor.
+        ShowSupertypes,
+        /// Shows the subtypes of the type under the cursor.
+        ShowSubtypes,
+        /// Expands the selected entry, loading its children if needed.
+        ExpandSelectedEntry,
+        /// Collapses the selected entry, or selects its parent.
+        CollapseSelectedEntry,
+    ]
+);
+
+const HIERARCHY_PANEL_KEY: &str = "HierarchyPanel";
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
+            workspace.toggle_panel_focus::<HierarchyPanel>(window, cx);
+        });
+        workspace.register_action(|workspace, _: &ShowIncomingCalls, window, cx| {
+            show_hierarchy(workspace, HierarchyKind::IncomingCalls, window, cx);
+        });
+        workspace.register_action(|workspace, _: &ShowOutgoingCalls, window, cx| {
+            show_hierarchy(workspace, HierarchyKind::OutgoingCalls, window, cx);
+        });
+        workspace.register_action(|workspace, _: &ShowSupertypes, window, cx| {
+            show_hierarchy(workspace, HierarchyKind::Supertypes, window, cx);
+        });
+        workspace.register_action(|workspace, _: &ShowSubtypes, window, cx| {
+            show_hierarchy(workspace, HierarchyKind::Subtypes, window, cx);
+        });
+    })
+    .detach();
+}
+
+fn show_hierarchy(
+    workspace: &mut Workspace,
+    kind: HierarchyKind,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+        return;
+    };
+    let Some((buffer, position)) = ({
+        let editor = editor.read(cx);
+        let head = editor.selections.newest_anchor().head();
+        editor.buffer().read(cx).text_anchor_for_position(head, cx)
+    }) else {
+        return;
+    };
+    if let Some(panel) = workspace.focus_panel::<HierarchyPanel>(window, cx) {
+        panel.update(cx, |panel, cx| panel.show(kind, buffer, position, cx));
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HierarchyKind {
+    IncomingCalls,
+    OutgoingCalls,
+    Supertypes,
+    Subtypes,
+}
+
+impl HierarchyKind {
+    fn title(self) -> &'static str {
+        match self {
+            Self::IncomingCalls => "Incoming Calls",
+            Self::OutgoingCalls => "Outgoing Calls",
+            Self::Supertypes => "Supertypes",
+            Self::Subtypes => "Subtypes",
+        }
+    }
+
+    fn is_call_hierarchy(self) -> bool {
+        matches!(self, Self::IncomingCalls | Self::OutgoingCalls)
+    }
+
+    /// The other direction of the same hierarchy.
+    fn reversed(self) -> Self {
+        match self {
+            Self::IncomingCalls => Self::OutgoingCalls,
+            Self::OutgoingCalls => Self::IncomingCalls,
+            Self::Supertypes => Self::Subtypes,
+            Self::Subtypes => Self::Supertypes,
+        }
+    }
+}
+
+/// A panel showing the call hierarchy or type hierarchy of a symbol as a tree,
+/// where each level is requested from the language server when expanded.
+pub struct HierarchyPanel {
+    fs: Arc<dyn Fs>,
+    project: Entity<Project>,
+    workspace: WeakEntity<Workspace>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    kind: HierarchyKind,
+    status: Status,
+    root_items: Vec<HierarchyItem>,
+    roots: Vec<usize>,
+    nodes: Vec<HierarchyNode>,
+    entries: Vec<HierarchyEntry>,
+    selected_ix: Option<usize>,
+    prepare_task: Option<Task<()>>,
+    children_tasks: HashMap<usize, Task<()>>,
+}
+
+enum Status {
+    Idle,
+    Loading,
+    Loaded,
+    Failed(SharedString),
+}
+
+struct HierarchyNode {
+    item: HierarchyItem,
+    /// Where this node's item calls its parent, or is called by it.
+    call_sites: Vec<Location>,
+    parent: Option<usize>,
+    depth: usize,
+    /// `None` until the children have been requested from the language server.
+    children: Option<Vec<usize>>,
+    is_expanded: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum HierarchyEntry {
+    Node(usize),
+    CallSite { node_ix: usize, call_site_ix: usize },
+}
+
+impl HierarchyPanel {
+    pub async fn load(
+        workspace: WeakEntity<Workspace>,
+        mut cx: AsyncWindowContext,
+    ) -> anyhow::Result<Entity<Self>> {
+        workspace.update_in(&mut cx, |workspace, _, cx| {
+            let fs = workspace.app_state().fs.clone();
+            let project = workspace.project().clone();
+            let workspace = workspace.weak_handle();
+            cx.new(|cx| Self {
+                fs,
+                project,
+                workspace,
+                focus_handle: cx.focus_handle(),
+                scroll_handle: UniformListScrollHandle::new(),
+                kind: HierarchyKind::IncomingCalls,
+                status: Status::Idle,
+                root_items: Vec::new(),
+                roots: Vec::new(),
+                nodes: Vec::new(),
+                entries: Vec::new(),
+                selected_ix: None,
+                prepare_task: None,
+                children_tasks: HashMap::default(),
+            })
+        })
+    }
+
+    /// Shows the hierarchy of the symbol at the given position.
+    pub fn show(
+        &mut self,
+        kind: HierarchyKind,
+        buffer: Entity<Buffer>,
+        position: Anchor,
+        cx: &mut Context<Self>,
+    ) {
+        let prepare = self.project.update(cx, |project, cx| {
+            if kind.is_call_hierarchy() {
+                project.prepare_call_hierarchy(&buffer, position, cx)
+            } else {
+                project.prepare_type_hierarchy(&buffer, position, cx)
+            }
+        });
+        self.kind = kind;
+        self.status = Status::Loading;
+        self.set_root_items(Vec::new(), cx);
+        self.prepare_task = Some(cx.spawn(async move |this, cx| {
+            let items = prepare.await;
+            this.update(cx, |this, cx| match items {
+                Ok(items) => {
+                    this.status = Status::Loaded;
+                    this.set_root_items(items, cx);
+                }
+                Err(error) => {
+                    this.status = Status::Failed(error.to_string().into());
+                    cx.notify();
+                }
+            })
+            .ok();
+        }));
+    }
+
+    fn set_kind(&mut self, kind: HierarchyKind, cx: &mut Context<Self>) {
+        if self.kind == kind {
+            return;
+        }
+        self.kind = kind;
+        self.set_root_items(self.root_items.clone(), cx);
+    }
+
+    fn set_root_items(&mut self, items: Vec<HierarchyItem>, cx: &mut Context<Self>) {
+        self.nodes.clear();
+        self.roots.clear();
+        self.children_tasks.clear();
+        self.entries.clear();
+        self.selected_ix = None;
+        for item in &items {
+            self.roots.push(self.nodes.len());
+            self.nodes.push(HierarchyNode {
+                item: item.clone(),
+                call_sites: Vec::new(),
+                parent: None,
+                depth: 0,
+                children: None,
+                is_expanded: false,
+            });
+        }
+        self.root_items = items;
+        for root_ix in self.roots.clone() {
+            self.expand(root_ix, cx);
+        }
+        self.update_entries();
+        if !self.entries.is_empty() {
+            self.selected_ix = Some(0);
+        }
+        cx.notify();
+    }
+
+    fn expand(&mut self, node_ix: usize, cx: &mut Context<Self>) {
+        let Some(node) = self.nodes.get_mut(node_ix) else {
+            return;
+        };
+        node.is_expanded = true;
+        if node.children.is_some() || self.children_tasks.contains_key(&node_ix) {
+            return;
+        }
+
+        let item = node.item.clone();
+        let kind = self.kind;
+        let (calls, types) = self.project.update(cx, |project, cx| match kind {
+            HierarchyKind::IncomingCalls => (Some(project.incoming_calls(&item, cx)), None),
+            HierarchyKind::OutgoingCalls => (Some(project.outgoing_calls(&item, cx)), None),
+            HierarchyKind::Supertypes => (None, Some(project.supertypes(&item, cx))),
+            HierarchyKind::Subtypes => (None, Some(project.subtypes(&item, cx))),
+        });
+        let task = cx.spawn(async move |this, cx| {
+            let children = match (calls, types) {
+                (Some(calls), _) => calls.await,
+                // Related types have no call sites.
+                (None, Some(types)) => types.await.map(|items| {
+                    items
+                        .into_iter()
+                        .map(|item| HierarchyCall {
+                            item,
+                            call_sites: Vec::new(),
+                        })
+                        .collect()
+                }),
+                (None, None) => Ok(Vec::new()),
+            };
+            this.update(cx, |this, cx| {
+                this.children_tasks.remove(&node_ix);
+                let children = children.log_err().unwrap_or_default();
+                this.insert_children(node_ix, children);
+                this.update_entries();
+                cx.notify();
+            })
+            .ok();
+        });
+        self.children_tasks.insert(node_ix, task);
+    }
+
+    fn insert_children(&mut self, node_ix: usize, children: Vec<HierarchyCall>) {
+        let depth = self.nodes[node_ix].depth + 1;
+        let mut child_ixs = Vec::with_capacity(children.len());
+        for child in children {
+            child_ixs.push(self.nodes.len());
+            self.nodes.push(HierarchyNode {
+                item: child.item,
+                call_sites: child.call_sites,
+                parent: Some(node_ix),
+                depth,
+                children: None,
+                is_expanded: false,
+            });
+        }
+        self.nodes[node_ix].children = Some(child_ixs);
+    }
+
+    fn toggle_expanded(&mut self, node_ix: usize, cx: &mut Context<Self>) {
+        let Some(node) = self.nodes.get_mut(node_ix) else {
+            return;
+        };
+        if node.is_expanded {
+            node.is_expanded = false;
+        } else {
+            self.expand(node_ix, cx);
+        }
+        self.update_entries();
+        cx.notify();
+    }
+
+    /// Lists the expanded nodes depth-first, with each node's call sites
+    /// before its children.
+    fn update_entries(&mut self) {
+        let selected_entry = self
+            .selected_ix
+            .and_then(|ix| self.entries.get(ix))
+            .copied();
+        self.entries.clear();
+        let mut stack = self.roots.iter().rev().copied().collect::<Vec<_>>();
+        while let Some(node_ix) = stack.pop() {
+            self.entries.push(HierarchyEntry::Node(node_ix));
+            let node = &self.nodes[node_ix];
+            if !node.is_expanded {
+                continue;
+            }
+            self.entries
+                .extend(
+                    (0..node.call_sites.len()).map(|call_site_ix| HierarchyEntry::CallSite {
+                        node_ix,
+                        call_site_ix,
+                    }),
+                );
+            if let Some(children) = &node.children {
+                stack.extend(children.iter().rev());
+            }
+        }
+        self.selected_ix = selected_entry
+            .and_then(|selected_entry| {
+                self.entries
+                    .iter()
+                    .position(|entry| *entry == selected_entry)
+            })
+            .or_else(|| {
+                // The selected call site was collapsed, so select its node.
+                let HierarchyEntry::CallSite { node_ix, .. } = selected_entry? else {
+                    return None;
+                };
+                self.entries
+                    .iter()
+                    .position(|entry| *entry == HierarchyEntry::Node(node_ix))
+            });
+    }
+
+    fn select_ix(&mut self, ix: usize, cx: &mut Context<Self>) {
+        if ix >= self.entries.len() {
+            return;
+        }
+        self.selected_ix = Some(ix);
+        self.scroll_handle
+            .scroll_to_item(ix, ScrollStrategy::Nearest);
+        cx.notify();
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        let ix = self.selected_ix.map_or(0, |ix| ix + 1);
+        self.select_ix(ix, cx);
+    }
+
+    fn select_previous(&mut self, _: &SelectPrevious, _: &mut Window, cx: &mut Context<Self>) {
+        let ix = self.selected_ix.map_or(0, |ix| ix.saturating_sub(1));
+        self.select_ix(ix, cx);
+    }
+
+    fn select_first(&mut self, _: &SelectFirst, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_ix(0, cx);
+    }
+
+    fn select_last(&mut self, _: &SelectLast, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.entries.len().checked_sub(1) {
+            self.select_ix(ix, cx);
+        }
+    }
+
+    fn expand_selected_entry(
+        &mut self,
+        _: &ExpandSelectedEntry,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(selected_ix) = self.selected_ix else {
+            return;
+        };
+        let Some(HierarchyEntry::Node(node_ix)) = self.entries.get(selected_ix).copied() else {
+            return;
+        };
+        if self.nodes[node_ix].is_expanded {
+            self.select_ix(selected_ix + 1, cx);
+        } else {
+            self.toggle_expanded(node_ix, cx);
+        }
+    }
+
+    fn collapse_selected_entry(
+        &mut self,
+        _: &CollapseSelectedEntry,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(entry) = self
+            .selected_ix
+            .and_then(|ix| self.entries.get(ix))
+            .copied()
+        else {
+            return;
+        };
+        let parent_ix = match entry {
+            HierarchyEntry::Node(node_ix) if self.nodes[node_ix].is_expanded => {
+                self.toggle_expanded(node_ix, cx);
+                return;
+            }
+            HierarchyEntry::Node(node_ix) => self.nodes[node_ix].parent,
+            HierarchyEntry::CallSite { node_ix, .. } => Some(node_ix),
+        };
+        if let Some(ix) = parent_ix.and_then(|parent_ix| {
+            self.entries
+                .iter()
+                .position(|entry| *entry == HierarchyEntry::Node(parent_ix))
+        }) {
+            self.select_ix(ix, cx);
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.selected_ix {
+            self.open_entry(ix, true, window, cx);
+        }
+    }
+
+    /// Opens the symbol of a node, or the location of a call site.
+    fn open_entry(&mut self, ix: usize, focus: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(location) = self.entries.get(ix).and_then(|entry| match *entry {
+            HierarchyEntry::Node(node_ix) => Some(self.nodes[node_ix].item.location.clone()),
+            HierarchyEntry::CallSite {
+                node_ix,
+                call_site_ix,
+            } => self.nodes[node_ix].call_sites.get(call_site_ix).cloned(),
+        }) else {
+            return;
+        };
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        workspace.update(cx, |workspace, cx| {
+            let pane = workspace.active_pane().clone();
+            let editor = workspace.open_project_item::<Editor>(
+                pane,
+                location.buffer.clone(),
+                true,
+                focus,
+                true,
+                true,
+                window,
+                cx,
+            );
+            editor.update(cx, |editor, cx| {
+                let snapshot = editor.buffer().read(cx).snapshot(cx);
+                let (Some(start), Some(end)) = (
+                    snapshot.anchor_in_buffer(location.range.start),
+                    snapshot.anchor_in_buffer(location.range.end),
+                ) else {
+                    return;
+                };
+                editor.change_selections(
+                    SelectionEffects::scroll(Autoscroll::center()),
+                    window,
+                    cx,
+                    |selections| selections.select_ranges([start..end]),
+                );
+            });
+        });
+    }
+
+    fn dispatch_context(&self) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("HierarchyPanel");
+        dispatch_context.add("menu");
+        dispatch_context
+    }
+
+    fn render_header(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let reversed = self.kind.reversed();
+        h_flex()
+            .h_8()
+            .px_2()
+            .gap_2()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(Label::new(self.kind.title()).size(LabelSize::Small))
+            .when(!self.root_items.is_empty(), |header| {
+                header.child(
+                    Button::new("reverse-hierarchy", format!("Show {}", reversed.title()))
+                        .label_size(LabelSize::Small)
+                        .on_click(cx.listener(move |this, _, _, cx| this.set_kind(reversed, cx))),
+                )
+            })
+    }
+
+    fn render_entries(&self, range: Range<usize>, cx: &mut Context<Self>) -> Vec<AnyElement> {
+        range
+            .filter_map(|ix| {
+                let entry = *self.entries.get(ix)?;
+                let item = match entry {
+                    HierarchyEntry::Node(node_ix) => self.render_node(ix, node_ix, cx),
+                    HierarchyEntry::CallSite {
+                        node_ix,
+                        call_site_ix,
+                    } => self.render_call_site(ix, node_ix, call_site_ix, cx)?,
+                };
+                Some(
+                    item.toggle_state(self.selected_ix == Some(ix))
+                        .on_click(cx.listener(move |this, event: &ClickEvent, window, cx| {
+                            this.select_ix(ix, cx);
+                            this.open_entry(ix, event.click_count() > 1, window, cx);
+                        }))
+                        .into_any_element(),
+                )
+            })
+            .collect()
+    }
+
+    fn render_node(&self, ix: usize, node_ix: usize, cx: &mut Context<Self>) -> ListItem {
+        let node = &self.nodes[node_ix];
+        let is_loading = self.children_tasks.contains_key(&node_ix);
+        let is_leaf = node
+            .children
+            .as_ref()
+            .is_some_and(|children| children.is_empty())
+            && node.call_sites.is_empty();
+        let buffer = node.item.location.buffer.read(cx);
+        let position = format!(
+            "{}:{}",
+            buffer.file().map_or("untitled", |file| file.file_name(cx)),
+            node.item.location.range.start.to_point(buffer).row + 1
+        );
+
+        ListItem::new(ix)
+            .indent_level(node.depth)
+            .indent_step_size(px(12.))
+            .spacing(ui::ListItemSpacing::Sparse)
+            .toggle((!is_leaf).then_some(node.is_expanded))
+            .on_toggle(cx.listener(move |this, _, _, cx| this.toggle_expanded(node_ix, cx)))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(Label::new(node.item.name.clone()))
+                    .children(node.item.detail.clone().map(|detail| {
+                        Label::new(detail)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .truncate()
+                    })),
+            )
+            .end_slot(
+                Label::new(if is_loading {
+                    "Loading…".to_string()
+                } else {
+                    position
+                })
+                .size(LabelSize::Small)
+                .color(Color::Muted),
+            )
+    }
+
+    fn render_call_site(
+        &self,
+        ix: usize,
+        node_ix: usize,
+        call_site_ix: usize,
+        cx: &mut Context<Self>,
+    ) -> Option<ListItem> {
+        let node = &self.nodes[node_ix];
+        let call_site = node.call_sites.get(call_site_ix)?;
+        let buffer = call_site.buffer.read(cx);
+        let row = call_site.range.start.to_point(buffer).row;
+        let line = buffer
+            .text_for_range(Point::new(row, 0)..Point::new(row, buffer.line_len(row)))
+            .collect::<String>();
+
+        Some(
+            ListItem::new(ix)
+                .indent_level(node.depth + 1)
+                .indent_step_size(px(12.))
+                .spacing(ui::ListItemSpacing::Sparse)
+                .child(
+                    Label::new(line.trim().to_string())
+                        .buffer_font(cx)
+                        .size(LabelSize::Small)
+                        .truncate(),
+                )
+                .end_slot(
+                    Label::new(format!("{}", row + 1))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                ),
+        )
+    }
+
+    fn render_placeholder(&self, message: SharedString) -> AnyElement {
+        v_flex()
+            .size_full()
+            .p_4()
+            .items_center()
+            .justify_center()
+            .child(Label::new(message).color(Color::Muted))
+            .into_any_element()
+    }
+}
+
+impl Render for HierarchyPanel {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let content = match &self.status {
+            Status::Idle => self.render_placeholder(
+                "Show the call or type hierarchy of a symbol from the editor".into(),
+            ),
+            Status::Loading => self.render_placeholder("Loading…".into()),
+            Status::Failed(error) => self.render_placeholder(error.clone()),
+            Status::Loaded if self.entries.is_empty() => {
+                self.render_placeholder("No symbol found at the cursor".into())
+            }
+            Status::Loaded => v_flex()
+                .size_full()
+                .child(self.render_header(cx))
+                .child(
+                    div()
+                        .flex_1()
+                        .min_h_0()
+                        .child(
+                            uniform_list(
+                                "hierarchy-entries",
+                                self.entries.len(),
+                                cx.processor(|this, range: Range<usize>, _, cx| {
+                                    this.render_entries(range, cx)
+                                }),
+                            )
+                            .size_full()
+                            .track_scroll(&self.scroll_handle),
+                        )
+                        .vertical_scrollbar_for(&self.scroll_handle, window, cx),
+                )
+                .into_any_element(),
+        };
+
+        v_flex()
+            .id("hierarchy-panel")
+            .size_full()
+            .key_context(self.dispatch_context())
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::expand_selected_entry))
+            .on_action(cx.listener(Self::collapse_selected_entry))
+            .on_action(cx.listener(Self::confirm))
+            .child(content)
+    }
+}
+
+impl Focusable for HierarchyPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for HierarchyPanel {}
+
+impl Panel for HierarchyPanel {
+    fn persistent_name() -> &'static str {
+        "Hierarchy Panel"
+    }
+
+    fn panel_key() -> &'static str {
+        HIERARCHY_PANEL_KEY
+    }
+
+    fn position(&self, _: &Window, cx: &App) -> DockPosition {
+        HierarchyPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        settings::update_settings_file(self.fs.clone(), cx, move |settings, _| {
+            settings.hierarchy_panel.get_or_insert_default().dock = Some(position.into())
+        });
+    }
+
+    fn default_size(&self, _: &Window, cx: &App) -> Pixels {
+        HierarchyPanelSettings::get_global(cx).default_width
+    }
+
+    fn icon(&self, _: &Window, cx: &App) -> Option<IconName> {
+        HierarchyPanelSettings::get_global(cx)
+            .button
+            .then_some(IconName::FileTree)
+    }
+
+    fn icon_tooltip(&self, _: &Window, _: &App) -> Option<&'static str> {
+        Some("Hierarchy Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn activation_priority(&self) -> u32 {
+        9
+    }
+
+    fn hide_button_setting(&self, _: &App) -> Option<workspace::HideStatusItem> {
+        Some(workspace::HideStatusItem::new(|settings| {
+            settings.hierarchy_panel.get_or_insert_default().button = Some(false);
+        }))
+    }
+}
@@ -5,7 +5,9 @@ pub mod markdown;
 mod markdown_writer;
 pub mod structure;
 
+use std::cell::RefCell;
 use std::io::Read;
+use std::rc::Rc;
 
 use anyhow::{Context as _, Result};
 use html5ever::driver::ParseOpts;
@@ -16,6 +18,7 @@ use markup5ever_rcdom::RcDom;
 
 pub use crate::html_element::*;
 pub use crate::markdown_writer::*;
+use crate::structure::Site;
 
 /// Converts the provided HTML to Markdown.
 pub fn convert_html_to_markdown(html: impl Read, handlers: &mut [TagHandler]) -> Result<String> {
@@ -29,6 +32,78 @@ pub fn convert_html_to_markdown(html: impl Read, handlers: &mut [TagHandler]) ->
     Ok(markdown)
 }
 
+/// Converts a web page to Markdown, leaving out everything but its main content.
+///
+/// Pages of the sites in [`structure::Site`] are converted with the site's own handlers added to the given ones.
+/// The main content of other pages is found with [`structure::readability`], falling back to the whole page.
+pub fn convert_webpage_to_markdown(
+    html: impl Read,
+    url: &str,
+    mut handlers: Vec<TagHandler>,
+) -> Result<String> {
+    let dom = parse_html(html).context("failed to parse HTML")?;
+
+    let site = Site::detect(url, &dom.document);
+    let mut main_content = Vec::new();
+    match site {
+        Some(site) => {
+            handlers.splice(0..0, site.handlers());
+            handlers.push(site.code_handler());
+            main_content = site.main_content(&dom.document);
+        }
+        None => handlers.push(Rc::new(RefCell::new(markdown::FencedCodeHandler::new()))),
+    }
+    if main_content.is_empty() {
+        main_content.extend(structure::readability::main_content(&dom.document));
+    }
+    if main_content.is_empty() {
+        main_content.push(dom.document);
+    }
+
+    let mut sections = Vec::new();
+    for node in &main_content {
+        let markdown = MarkdownWriter::new()
+            .run(node, &mut handlers)
+            .context("failed to convert HTML to Markdown")?;
+        if !markdown.is_empty() {
+            sections.push(markdown);
+        }
+    }
+
+    Ok(tidy_whitespace(&sections.join("\n\n")))
+}
+
+/// Collapses the runs of spaces and blank lines left by the indentation of the HTML, outside of code blocks.
+fn tidy_whitespace(markdown: &str) -> String {
+    let mut tidy = String::with_capacity(markdown.len());
+    let mut in_code_block = false;
+    let mut blank_line = false;
+    for line in markdown.lines() {
+        if line.starts_with("```") {
+            in_code_block = !in_code_block;
+        }
+        let line = if in_code_block {
+            line.trim_end().to_string()
+        } else {
+            line.split(' ')
+                .filter(|word| !word.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        if line.is_empty() && !in_code_block {
+            blank_line = !tidy.is_empty();
+            continue;
+        }
+        if blank_line {
+            tidy.push('\n');
+            blank_line = false;
+        }
+        tidy.push_str(&line);
+        tidy.push('\n');
+    }
+    tidy.trim_end().to_string()
+}
+
 fn parse_html(mut html: impl Read) -> Result<RcDom> {
     let parse_options = ParseOpts {
         tree_builder: TreeBuilderOpts {
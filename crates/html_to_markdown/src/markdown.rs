@@ -274,3 +274,126 @@ impl HandleTag for CodeHandler {
         HandlerOutcome::NoOp
     }
 }
+
+/// Converts `<pre>` blocks to fenced code blocks, tagged with the language of the code when it can be found.
+///
+/// The language is looked up in the `language-*` and `lang-*` classes of the `<pre>` element and its `<code>` child,
+/// unless a site-specific rule is passed to [`FencedCodeHandler::with_language_detection`], which also sees the elements wrapping the `<pre>`.
+pub struct FencedCodeHandler {
+    site_language: Option<fn(&HtmlElement) -> Option<String>>,
+    /// The language found on an element wrapping the next `<pre>`, with the depth of that element.
+    wrapper_language: Option<(String, usize)>,
+    /// Where to insert the language into the opening fence of the current `<pre>`, if it's still unknown.
+    fence_language_offset: Option<usize>,
+}
+
+impl FencedCodeHandler {
+    pub fn new() -> Self {
+        Self {
+            site_language: None,
+            wrapper_language: None,
+            fence_language_offset: None,
+        }
+    }
+
+    pub fn with_language_detection(site_language: fn(&HtmlElement) -> Option<String>) -> Self {
+        Self {
+            site_language: Some(site_language),
+            ..Self::new()
+        }
+    }
+
+    fn language(&self, tag: &HtmlElement) -> Option<String> {
+        if let Some(site_language) = self.site_language {
+            return site_language(tag);
+        }
+        if !matches!(tag.tag(), "pre" | "code") {
+            return None;
+        }
+        tag.classes().iter().find_map(|class| {
+            class
+                .strip_prefix("language-")
+                .or_else(|| class.strip_prefix("lang-"))
+                .filter(|language| !language.is_empty())
+                .map(ToOwned::to_owned)
+        })
+    }
+}
+
+impl Default for FencedCodeHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HandleTag for FencedCodeHandler {
+    fn should_handle(&self, _tag: &str) -> bool {
+        true
+    }
+
+    fn handle_tag_start(
+        &mut self,
+        tag: &HtmlElement,
+        writer: &mut MarkdownWriter,
+    ) -> StartTagOutcome {
+        match tag.tag() {
+            "code" if writer.is_inside("pre") => {
+                if let Some(offset) = self.fence_language_offset.take()
+                    && let Some(language) = self.language(tag)
+                {
+                    writer.markdown.insert_str(offset, &language);
+                }
+            }
+            "code" => writer.push_str("`"),
+            "pre" => {
+                let language = self
+                    .language(tag)
+                    .or_else(|| self.wrapper_language.take().map(|(language, _)| language));
+                writer.push_str("\n\n```");
+                match language {
+                    Some(language) => writer.push_str(&language),
+                    None => self.fence_language_offset = Some(writer.markdown.len()),
+                }
+                writer.push_newline();
+            }
+            _ if !writer.is_inside("pre") => {
+                if let Some(language) = self.language(tag) {
+                    self.wrapper_language = Some((language, writer.current_element_stack().len()));
+                }
+            }
+            _ => {}
+        }
+
+        StartTagOutcome::Continue
+    }
+
+    fn handle_tag_end(&mut self, tag: &HtmlElement, writer: &mut MarkdownWriter) {
+        match tag.tag() {
+            "code" if !writer.is_inside("pre") => writer.push_str("`"),
+            "pre" => {
+                self.fence_language_offset = None;
+                if !writer.markdown.ends_with('\n') {
+                    writer.push_newline();
+                }
+                writer.push_str("```\n");
+            }
+            _ => {}
+        }
+        if self
+            .wrapper_language
+            .as_ref()
+            .is_some_and(|(_, depth)| *depth >= writer.current_element_stack().len())
+        {
+            self.wrapper_language = None;
+        }
+    }
+
+    fn handle_text(&mut self, text: &str, writer: &mut MarkdownWriter) -> HandlerOutcome {
+        if writer.is_inside("pre") {
+            writer.push_str(text);
+            return HandlerOutcome::Handled;
+        }
+
+        HandlerOutcome::NoOp
+    }
+}
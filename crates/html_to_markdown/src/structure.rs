@@ -1 +1,296 @@
+//! Site-specific handlers and main content extraction, to convert pages of well-known sites without their chrome.
+
+pub mod docs_rs;
+pub mod github;
+pub mod mdn;
+pub mod readability;
+pub mod sphinx;
+pub mod stack_overflow;
 pub mod wikipedia;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use markup5ever_rcdom::{Handle, NodeData};
+
+use crate::html_element::HtmlElement;
+use crate::markdown::FencedCodeHandler;
+use crate::markdown_writer::TagHandler;
+
+/// A site with handlers tailored to the structure of its pages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Site {
+    /// Rust API documentation generated by rustdoc, on docs.rs and elsewhere.
+    DocsRs,
+    /// GitHub issues, pull requests and READMEs.
+    GitHub,
+    /// MDN Web Docs.
+    Mdn,
+    /// Stack Overflow and the other Stack Exchange sites.
+    StackOverflow,
+    /// Documentation generated by Sphinx, on Read the Docs and elsewhere.
+    Sphinx,
+    Wikipedia,
+}
+
+impl Site {
+    /// Detects the site of a page from its URL or, for documentation generators whose pages can be hosted anywhere, from its markup.
+    pub fn detect(url: &str, document: &Handle) -> Option<Self> {
+        let host = host(url);
+        let is_domain = |domain: &str| {
+            host == domain
+                || host
+                    .strip_suffix(domain)
+                    .is_some_and(|subdomain| subdomain.ends_with('.'))
+        };
+
+        if is_domain("docs.rs") || is_domain("doc.rust-lang.org") {
+            Some(Self::DocsRs)
+        } else if is_domain("github.com") {
+            Some(Self::GitHub)
+        } else if is_domain("developer.mozilla.org") {
+            Some(Self::Mdn)
+        } else if [
+            "stackoverflow.com",
+            "stackexchange.com",
+            "superuser.com",
+            "serverfault.com",
+            "askubuntu.com",
+        ]
+        .into_iter()
+        .any(is_domain)
+        {
+            Some(Self::StackOverflow)
+        } else if is_domain("readthedocs.io") || is_domain("readthedocs.org") {
+            Some(Self::Sphinx)
+        } else if is_domain("wikipedia.org") {
+            Some(Self::Wikipedia)
+        } else if docs_rs::is_rustdoc_page(document) {
+            Some(Self::DocsRs)
+        } else if sphinx::is_sphinx_page(document) {
+            Some(Self::Sphinx)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the handlers that remove the chrome of the site's pages, to run before the generic handlers.
+    pub fn handlers(self) -> Vec<TagHandler> {
+        match self {
+            Self::DocsRs => vec![Rc::new(RefCell::new(docs_rs::DocsRsChromeRemover))],
+            Self::GitHub => vec![Rc::new(RefCell::new(github::GitHubChromeRemover))],
+            Self::Mdn => vec![Rc::new(RefCell::new(mdn::MdnChromeRemover))],
+            Self::StackOverflow => vec![
+                Rc::new(RefCell::new(stack_overflow::StackOverflowChromeRemover)),
+                Rc::new(RefCell::new(stack_overflow::StackOverflowPostHandler)),
+            ],
+            Self::Sphinx => vec![
+                Rc::new(RefCell::new(sphinx::SphinxChromeRemover)),
+                Rc::new(RefCell::new(sphinx::SphinxSignatureHandler)),
+            ],
+            Self::Wikipedia => vec![
+                Rc::new(RefCell::new(wikipedia::WikipediaChromeRemover)),
+                Rc::new(RefCell::new(wikipedia::WikipediaInfoboxHandler)),
+            ],
+        }
+    }
+
+    /// Returns the handler that converts the code blocks of the site's pages, to run after the generic handlers.
+    pub fn code_handler(self) -> TagHandler {
+        let language_detection = match self {
+            Self::DocsRs => docs_rs::code_language,
+            Self::GitHub => github::code_language,
+            Self::Mdn => mdn::code_language,
+            Self::StackOverflow => stack_overflow::code_language,
+            Self::Sphinx => sphinx::code_language,
+            Self::Wikipedia => {
+                return Rc::new(RefCell::new(wikipedia::WikipediaCodeHandler::new()));
+            }
+        };
+        Rc::new(RefCell::new(FencedCodeHandler::with_language_detection(
+            language_detection,
+        )))
+    }
+
+    /// Returns the elements holding the main content of the page, in document order.
+    ///
+    /// Returns nothing when the page doesn't have the expected structure.
+    pub fn main_content(self, document: &Handle) -> Vec<Handle> {
+        match self {
+            Self::DocsRs => docs_rs::main_content(document),
+            Self::GitHub => github::main_content(document),
+            Self::Mdn => mdn::main_content(document),
+            Self::StackOverflow => stack_overflow::main_content(document),
+            Self::Sphinx => sphinx::main_content(document),
+            Self::Wikipedia => wikipedia::main_content(document),
+        }
+    }
+}
+
+/// Returns the lowercased host of the URL, without the port.
+fn host(url: &str) -> String {
+    let url = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = url.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let host = host.split(':').next().unwrap_or_default();
+    host.to_lowercase()
+}
+
+/// Returns the [`HtmlElement`] of the node, if it's an element.
+pub(crate) fn html_element(node: &Handle) -> Option<HtmlElement> {
+    match &node.data {
+        NodeData::Element { name, attrs, .. } => {
+            Some(HtmlElement::new(name.local.to_string(), attrs.clone()))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the outermost elements under the node that match the predicate, in document order.
+pub fn find_elements(node: &Handle, predicate: impl Fn(&HtmlElement) -> bool) -> Vec<Handle> {
+    fn find(node: &Handle, predicate: &dyn Fn(&HtmlElement) -> bool, found: &mut Vec<Handle>) {
+        if html_element(node).is_some_and(|element| predicate(&element)) {
+            found.push(node.clone());
+            return;
+        }
+        for child in node.children.borrow().iter() {
+            find(child, predicate, found);
+        }
+    }
+
+    let mut found = Vec::new();
+    find(node, &predicate, &mut found);
+    found
+}
+
+/// Returns whether any element under the node matches the predicate.
+pub(crate) fn contains_element(node: &Handle, predicate: impl Fn(&HtmlElement) -> bool) -> bool {
+    fn contains(node: &Handle, predicate: &dyn Fn(&HtmlElement) -> bool) -> bool {
+        html_element(node).is_some_and(|element| predicate(&element))
+            || node
+                .children
+                .borrow()
+                .iter()
+                .any(|child| contains(child, predicate))
+    }
+
+    contains(node, &predicate)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{TagHandler, convert_webpage_to_markdown, markdown};
+
+    use super::*;
+
+    fn handlers() -> Vec<TagHandler> {
+        vec![
+            Rc::new(RefCell::new(markdown::WebpageChromeRemover)),
+            Rc::new(RefCell::new(markdown::ParagraphHandler)),
+            Rc::new(RefCell::new(markdown::HeadingHandler)),
+            Rc::new(RefCell::new(markdown::ListHandler)),
+            Rc::new(RefCell::new(markdown::TableHandler::new())),
+            Rc::new(RefCell::new(markdown::StyledTextHandler)),
+        ]
+    }
+
+    #[track_caller]
+    fn assert_converts_to(url: &str, html: &str, expected_markdown: &str) {
+        assert_eq!(
+            convert_webpage_to_markdown(html.as_bytes(), url, handlers()).unwrap(),
+            expected_markdown.trim()
+        );
+    }
+
+    #[test]
+    fn test_host() {
+        assert_eq!(host("https://Docs.rs/serde/latest/serde/"), "docs.rs");
+        assert_eq!(host("http://user@localhost:8000/index.html"), "localhost");
+        assert_eq!(host("github.com?tab=readme"), "github.com");
+    }
+
+    #[test]
+    fn test_detect_site() {
+        let blank_page = crate::parse_html("<html></html>".as_bytes()).unwrap();
+        let detect = |url| Site::detect(url, &blank_page.document);
+        assert_eq!(
+            detect("https://docs.rs/regex/latest/regex/"),
+            Some(Site::DocsRs)
+        );
+        assert_eq!(
+            detect("https://unix.stackexchange.com/questions/1"),
+            Some(Site::StackOverflow)
+        );
+        assert_eq!(
+            detect("https://pip.readthedocs.io/en/stable/"),
+            Some(Site::Sphinx)
+        );
+        assert_eq!(
+            detect("https://en.wikipedia.org/wiki/Rust"),
+            Some(Site::Wikipedia)
+        );
+        assert_eq!(detect("https://notgithub.com/zed-industries/zed"), None);
+    }
+
+    #[test]
+    fn test_docs_rs_page() {
+        assert_converts_to(
+            "https://docs.rs/anyhow/latest/anyhow/trait.Context.html",
+            include_str!("../test_data/docs_rs.html"),
+            include_str!("../test_data/docs_rs.md"),
+        );
+    }
+
+    #[test]
+    fn test_github_issue_page() {
+        assert_converts_to(
+            "https://github.com/zed-industries/zed/issues/1234",
+            include_str!("../test_data/github_issue.html"),
+            include_str!("../test_data/github_issue.md"),
+        );
+    }
+
+    #[test]
+    fn test_mdn_page() {
+        assert_converts_to(
+            "https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/at",
+            include_str!("../test_data/mdn.html"),
+            include_str!("../test_data/mdn.md"),
+        );
+    }
+
+    #[test]
+    fn test_stack_overflow_page() {
+        assert_converts_to(
+            "https://stackoverflow.com/questions/30177845/how-to-initialize-the-logger-for-integration-tests",
+            include_str!("../test_data/stack_overflow.html"),
+            include_str!("../test_data/stack_overflow.md"),
+        );
+    }
+
+    #[test]
+    fn test_sphinx_page() {
+        // Sphinx pages are detected from their markup, wherever they are hosted.
+        assert_converts_to(
+            "https://docs.example.org/en/stable/quickstart.html",
+            include_str!("../test_data/sphinx.html"),
+            include_str!("../test_data/sphinx.md"),
+        );
+    }
+
+    #[test]
+    fn test_readability_fallback() {
+        assert_converts_to(
+            "https://blog.example.com/2024/05/faster-builds",
+            include_str!("../test_data/blog_post.html"),
+            include_str!("../test_data/blog_post.md"),
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use markup5ever_rcdom::Handle;
+
+use crate::HandleTag;
+use crate::html_element::HtmlElement;
+use crate::markdown_writer::{MarkdownWriter, StartTagOutcome};
+use crate::structure::{contains_element, find_elements};
+
+/// Returns whether the page was generated by rustdoc, which also renders pages outside of docs.rs.
+pub fn is_rustdoc_page(document: &Handle) -> bool {
+    contains_element(document, |element| {
+        element.tag() == "meta"
+            && element.attr("name").as_deref() == Some("generator")
+            && element.attr("content").as_deref() == Some("rustdoc")
+    })
+}
+
+/// Returns the `#main-content` section of the page, leaving out the sidebar and the docs.rs top bar.
+pub fn main_content(document: &Handle) -> Vec<Handle> {
+    find_elements(document, |element| {
+        element.tag() == "section" && element.attr("id").as_deref() == Some("main-content")
+    })
+}
+
+/// Returns the language of a rustdoc code block, whose `<pre>` has a `rust` class for Rust code.
+pub fn code_language(tag: &HtmlElement) -> Option<String> {
+    (tag.tag() == "pre" && tag.has_class("rust")).then(|| "rust".to_string())
+}
+
+pub struct DocsRsChromeRemover;
+
+impl HandleTag for DocsRsChromeRemover {
+    fn should_handle(&self, _tag: &str) -> bool {
+        true
+    }
+
+    fn handle_tag_start(
+        &mut self,
+        tag: &HtmlElement,
+        _writer: &mut MarkdownWriter,
+    ) -> StartTagOutcome {
+        match tag.tag() {
+            "head" | "script" | "style" | "nav" | "button" | "rustdoc-search"
+            | "rustdoc-toolbar" => return StartTagOutcome::Skip,
+            "a" => {
+                // The "Source" links and the `§` anchors next to headings.
+                if tag.has_any_classes(&["src", "srclink", "anchor", "doc-anchor"]) {
+                    return StartTagOutcome::Skip;
+                }
+            }
+            "summary" => {
+                // The "Expand description" toggles.
+                if tag.has_class("hideme") {
+                    return StartTagOutcome::Skip;
+                }
+            }
+            "div" | "span" => {
+                let classes_to_skip = ["out-of-band", "rightside", "tooltip", "notable-traits"];
+                if tag.has_any_classes(&classes_to_skip) {
+                    return StartTagOutcome::Skip;
+                }
+            }
+            _ => {}
+        }
+
+        StartTagOutcome::Continue
+    }
+}
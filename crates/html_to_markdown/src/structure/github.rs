@@ -0,0 +1,86 @@
+use markup5ever_rcdom::Handle;
+
+use crate::HandleTag;
+use crate::html_element::HtmlElement;
+use crate::markdown_writer::{MarkdownWriter, StartTagOutcome};
+use crate::structure::{find_elements, html_element};
+
+/// Returns the title and comments of an issue or pull request, or the rendered Markdown of a README or file.
+///
+/// The comment bodies are picked out of the timeline on their own, as GitHub lays them out in table cells.
+pub fn main_content(document: &Handle) -> Vec<Handle> {
+    let discussion = find_elements(document, |element| {
+        (element.tag() == "h1" && element.has_class("gh-header-title"))
+            || element.has_any_classes(&[
+                "gh-header-meta",
+                "timeline-comment-header-text",
+                "comment-body",
+            ])
+    });
+    if !discussion.is_empty() {
+        return discussion
+            .into_iter()
+            .flat_map(|node| {
+                if html_element(&node).is_some_and(|element| element.tag() == "td") {
+                    node.children.borrow().clone()
+                } else {
+                    vec![node]
+                }
+            })
+            .collect();
+    }
+
+    find_elements(document, |element| {
+        element.tag() == "article" && element.has_class("markdown-body")
+    })
+}
+
+/// Returns the language of a code block, from the `highlight-source-*` class of the `<div>` wrapping its `<pre>`.
+pub fn code_language(tag: &HtmlElement) -> Option<String> {
+    match tag.tag() {
+        "div" => tag.classes().iter().find_map(|class| {
+            class
+                .strip_prefix("highlight-source-")
+                .map(ToOwned::to_owned)
+        }),
+        "pre" => tag.attr("lang"),
+        _ => None,
+    }
+}
+
+pub struct GitHubChromeRemover;
+
+impl HandleTag for GitHubChromeRemover {
+    fn should_handle(&self, _tag: &str) -> bool {
+        true
+    }
+
+    fn handle_tag_start(
+        &mut self,
+        tag: &HtmlElement,
+        _writer: &mut MarkdownWriter,
+    ) -> StartTagOutcome {
+        match tag.tag() {
+            "head" | "script" | "style" | "nav" | "form" | "button" | "svg" | "template"
+            | "clipboard-copy" | "tool-tip" | "include-fragment" => {
+                return StartTagOutcome::Skip;
+            }
+            "div" | "span" => {
+                let classes_to_skip = [
+                    "timeline-comment-actions",
+                    "comment-reactions",
+                    "js-comment-edit-history",
+                    "discussion-timeline-actions",
+                    "zeroclipboard-container",
+                    "sr-only",
+                ];
+                if tag.has_any_classes(&classes_to_skip) {
+                    return StartTagOutcome::Skip;
+                }
+            }
+            _ => {}
+        }
+
+        StartTagOutcome::Continue
+    }
+}
@@ -0,0 +1,71 @@
+use markup5ever_rcdom::Handle;
+
+use crate::HandleTag;
+use crate::html_element::HtmlElement;
+use crate::markdown_writer::{MarkdownWriter, StartTagOutcome};
+use crate::structure::find_elements;
+
+/// Returns the article of the page, leaving out the sidebar and the table of contents.
+pub fn main_content(document: &Handle) -> Vec<Handle> {
+    let article = find_elements(document, |element| {
+        element.tag() == "article" && element.has_class("main-page-content")
+    });
+    if !article.is_empty() {
+        return article;
+    }
+
+    find_elements(document, |element| element.tag() == "main")
+}
+
+/// Returns the language of a code block, from the `brush: <language>` classes of its `<pre>`.
+pub fn code_language(tag: &HtmlElement) -> Option<String> {
+    if tag.tag() != "pre" {
+        return None;
+    }
+    let classes = tag.classes();
+    let brush = classes.iter().position(|class| class == "brush:")?;
+    classes.get(brush + 1).cloned()
+}
+
+pub struct MdnChromeRemover;
+
+impl HandleTag for MdnChromeRemover {
+    fn should_handle(&self, _tag: &str) -> bool {
+        true
+    }
+
+    fn handle_tag_start(
+        &mut self,
+        tag: &HtmlElement,
+        _writer: &mut MarkdownWriter,
+    ) -> StartTagOutcome {
+        match tag.tag() {
+            "head"
+            | "script"
+            | "style"
+            | "nav"
+            | "aside"
+            | "button"
+            | "iframe"
+            | "interactive-example" => return StartTagOutcome::Skip,
+            "div" | "section" | "details" | "span" => {
+                let classes_to_skip = [
+                    "article-actions-container",
+                    "baseline-indicator",
+                    "document-toc-container",
+                    "example-header",
+                    "interactive-example",
+                    "metadata",
+                    "prev-next",
+                    "sidebar",
+                ];
+                if tag.has_any_classes(&classes_to_skip) {
+                    return StartTagOutcome::Skip;
+                }
+            }
+            _ => {}
+        }
+
+        StartTagOutcome::Continue
+    }
+}
@@ -0,0 +1,243 @@
+//! Finds the main content of pages from sites without dedicated handlers.
+//!
+//! This is a simplified take on the scoring of [Readability](https://github.com/mozilla/readability):
+//! every paragraph-like element scores points for its length and commas, which flow up to its nearest
+//! ancestors, and the ancestor with the best score, once weighed down by its density of links, is the
+//! main content.
+
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::LazyLock;
+
+use markup5ever_rcdom::{Handle, Node, NodeData};
+use regex::Regex;
+
+use crate::html_element::HtmlElement;
+use crate::structure::html_element;
+
+/// The minimum length of the text of an element for it to score points.
+const MIN_PARAGRAPH_LENGTH: usize = 25;
+
+/// The minimum length of the text of the main content, below which the page is deemed to have none.
+const MIN_CONTENT_LENGTH: usize = 140;
+
+/// How many levels of ancestors the score of a paragraph flows up to.
+const SCORED_ANCESTOR_LEVELS: usize = 3;
+
+fn unlikely_candidates_regex() -> &'static Regex {
+    static REGEX: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(
+            r"(?i)-ad-|banner|breadcrumbs|combx|comment|community|cookie|disqus|extra|footer|gdpr|header|menu|modal|newsletter|pager|pagination|popup|related|remark|replies|rss|share|shoutbox|sidebar|skyscraper|social|sponsor|subscribe|supplemental",
+        )
+        .unwrap()
+    });
+    &REGEX
+}
+
+fn maybe_candidate_regex() -> &'static Regex {
+    static REGEX: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"(?i)and|article|body|column|content|main|shadow").unwrap());
+    &REGEX
+}
+
+fn positive_regex() -> &'static Regex {
+    static REGEX: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"(?i)article|body|content|entry|hentry|h-entry|main|page|post|text|blog|story")
+            .unwrap()
+    });
+    &REGEX
+}
+
+fn negative_regex() -> &'static Regex {
+    static REGEX: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(
+            r"(?i)-ad-|hidden|banner|combx|comment|com-|contact|footer|gdpr|masthead|media|meta|outbrain|promo|related|scroll|share|shoutbox|sidebar|skyscraper|sponsor|shopping|tags|widget",
+        )
+        .unwrap()
+    });
+    &REGEX
+}
+
+/// Returns the element holding the main content of the page, if there's one with enough text.
+pub fn main_content(document: &Handle) -> Option<Handle> {
+    let mut candidates = HashMap::new();
+    score_paragraphs(document, &mut Vec::new(), &mut candidates);
+
+    let (node, _) = candidates
+        .into_values()
+        .map(|(node, score)| {
+            let score = score * (1. - link_density(&node));
+            (node, score)
+        })
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))?;
+
+    (text_content(&node).trim().chars().count() >= MIN_CONTENT_LENGTH).then_some(node)
+}
+
+/// Scores the paragraphs under the node, adding their scores to those of their ancestors in `candidates`.
+fn score_paragraphs(
+    node: &Handle,
+    ancestors: &mut Vec<Handle>,
+    candidates: &mut HashMap<*const Node, (Handle, f32)>,
+) {
+    let Some(element) = html_element(node) else {
+        for child in node.children.borrow().iter() {
+            score_paragraphs(child, ancestors, candidates);
+        }
+        return;
+    };
+    if is_unlikely_candidate(&element) {
+        return;
+    }
+
+    if is_paragraph(node, &element) {
+        let text = text_content(node);
+        let text = text.trim();
+        let length = text.chars().count();
+        if length >= MIN_PARAGRAPH_LENGTH {
+            let commas = text.matches(',').count();
+            let score = 1. + commas as f32 + (length / 100).min(3) as f32;
+            for (level, ancestor) in ancestors
+                .iter()
+                .rev()
+                .take(SCORED_ANCESTOR_LEVELS)
+                .enumerate()
+            {
+                let divider = match level {
+                    0 => 1.,
+                    1 => 2.,
+                    level => level as f32 * 3.,
+                };
+                let (_, ancestor_score) = candidates
+                    .entry(Rc::as_ptr(ancestor))
+                    .or_insert_with(|| (ancestor.clone(), initial_score(ancestor)));
+                *ancestor_score += score / divider;
+            }
+        }
+    }
+
+    ancestors.push(node.clone());
+    for child in node.children.borrow().iter() {
+        score_paragraphs(child, ancestors, candidates);
+    }
+    ancestors.pop();
+}
+
+/// Returns whether the element and everything under it can be left out, as it's most likely page chrome.
+fn is_unlikely_candidate(element: &HtmlElement) -> bool {
+    match element.tag() {
+        "head" | "script" | "style" | "noscript" | "nav" | "header" | "footer" | "aside"
+        | "form" | "iframe" | "svg" | "button" | "select" | "dialog" | "template" => true,
+        "html" | "body" | "article" | "main" => false,
+        _ => {
+            if matches!(
+                element.attr("role").as_deref(),
+                Some("navigation" | "banner" | "complementary" | "contentinfo" | "dialog")
+            ) {
+                return true;
+            }
+            let match_string = class_and_id(element);
+            unlikely_candidates_regex().is_match(&match_string)
+                && !maybe_candidate_regex().is_match(&match_string)
+        }
+    }
+}
+
+/// Returns whether the element is a paragraph of text, whose text is scored.
+fn is_paragraph(node: &Handle, element: &HtmlElement) -> bool {
+    match element.tag() {
+        "p" | "pre" | "td" | "blockquote" => true,
+        // Some pages lay out their text in `<div>`s, with inline elements only.
+        "div" => !node.children.borrow().iter().any(|child| {
+            html_element(child).is_some_and(|child| {
+                matches!(
+                    child.tag(),
+                    "blockquote"
+                        | "dl"
+                        | "div"
+                        | "img"
+                        | "ol"
+                        | "p"
+                        | "pre"
+                        | "table"
+                        | "ul"
+                        | "section"
+                        | "article"
+                )
+            })
+        }),
+        _ => false,
+    }
+}
+
+/// Returns the score an element starts with, from its tag, classes and id, before the scores of its paragraphs are added.
+fn initial_score(node: &Handle) -> f32 {
+    let Some(element) = html_element(node) else {
+        return 0.;
+    };
+    let tag_score = match element.tag() {
+        "article" | "main" => 10.,
+        "div" => 5.,
+        "pre" | "td" | "blockquote" => 3.,
+        "address" | "ol" | "ul" | "dl" | "dd" | "dt" | "li" | "form" => -3.,
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "th" => -5.,
+        _ => 0.,
+    };
+
+    let match_string = class_and_id(&element);
+    let mut class_weight = 0.;
+    if negative_regex().is_match(&match_string) {
+        class_weight -= 25.;
+    }
+    if positive_regex().is_match(&match_string) {
+        class_weight += 25.;
+    }
+    if element.attr("role").as_deref() == Some("main") {
+        class_weight += 25.;
+    }
+
+    tag_score + class_weight
+}
+
+fn class_and_id(element: &HtmlElement) -> String {
+    format!(
+        "{} {}",
+        element.attr("class").unwrap_or_default(),
+        element.attr("id").unwrap_or_default()
+    )
+}
+
+/// Returns the share of the text under the node that is inside links.
+fn link_density(node: &Handle) -> f32 {
+    fn link_text_length(node: &Handle) -> usize {
+        if html_element(node).is_some_and(|element| element.tag() == "a") {
+            return text_content(node).trim().chars().count();
+        }
+        node.children.borrow().iter().map(link_text_length).sum()
+    }
+
+    let length = text_content(node).trim().chars().count();
+    if length == 0 {
+        return 0.;
+    }
+    link_text_length(node) as f32 / length as f32
+}
+
+fn text_content(node: &Handle) -> String {
+    fn collect(node: &Handle, text: &mut String) {
+        match &node.data {
+            NodeData::Text { contents } => text.push_str(&contents.borrow()),
+            NodeData::Element { name, .. }
+                if matches!(&*name.local, "script" | "style" | "noscript") => {}
+            _ => {
+                for child in node.children.borrow().iter() {
+                    collect(child, text);
+                }
+            }
+        }
+    }
+
+    let mut text = String::new();
+    collect(node, &mut text);
+    text
+}
@@ -0,0 +1,127 @@
+use markup5ever_rcdom::Handle;
+
+use crate::HandleTag;
+use crate::html_element::HtmlElement;
+use crate::markdown_writer::{MarkdownWriter, StartTagOutcome};
+use crate::structure::{contains_element, find_elements};
+
+/// Returns whether the page was generated by Sphinx, whatever the theme and wherever it's hosted.
+pub fn is_sphinx_page(document: &Handle) -> bool {
+    contains_element(document, |element| match element.tag() {
+        "script" => element
+            .attr("src")
+            .is_some_and(|src| src.contains("documentation_options.js")),
+        "meta" => {
+            element.attr("name").as_deref() == Some("generator")
+                && element
+                    .attr("content")
+                    .is_some_and(|content| content.starts_with("Sphinx"))
+        }
+        _ => false,
+    })
+}
+
+/// Returns the body of the document, leaving out the navigation and the footer of the theme.
+pub fn main_content(document: &Handle) -> Vec<Handle> {
+    let main = find_elements(document, |element| {
+        element.attr("role").as_deref() == Some("main")
+    });
+    if !main.is_empty() {
+        return main;
+    }
+
+    find_elements(document, |element| {
+        element.tag() == "div" && element.has_class("body")
+    })
+}
+
+/// Returns the language of a code block, from the `highlight-*` class of the `<div>` wrapping its `<pre>`.
+pub fn code_language(tag: &HtmlElement) -> Option<String> {
+    if tag.tag() != "div" {
+        return None;
+    }
+    tag.classes().iter().find_map(|class| {
+        class
+            .strip_prefix("highlight-")
+            .filter(|language| *language != "default")
+            .map(ToOwned::to_owned)
+    })
+}
+
+pub struct SphinxChromeRemover;
+
+impl HandleTag for SphinxChromeRemover {
+    fn should_handle(&self, _tag: &str) -> bool {
+        true
+    }
+
+    fn handle_tag_start(
+        &mut self,
+        tag: &HtmlElement,
+        _writer: &mut MarkdownWriter,
+    ) -> StartTagOutcome {
+        match tag.tag() {
+            "head" | "script" | "style" | "nav" | "footer" | "button" => {
+                return StartTagOutcome::Skip;
+            }
+            "a" => {
+                // The `¶` permalinks next to headings and signatures.
+                if tag.has_class("headerlink") {
+                    return StartTagOutcome::Skip;
+                }
+            }
+            "div" | "span" | "ul" => {
+                let classes_to_skip = [
+                    "linenos",
+                    "related",
+                    "sphinxsidebar",
+                    "rst-footer-buttons",
+                    "wy-breadcrumbs",
+                    "prev-next-area",
+                    "copybtn",
+                    "viewcode-link",
+                ];
+                if tag.has_any_classes(&classes_to_skip) {
+                    return StartTagOutcome::Skip;
+                }
+            }
+            _ => {}
+        }
+
+        StartTagOutcome::Continue
+    }
+}
+
+/// Puts the signatures of API entries, which Sphinx renders as definition lists, on their own lines.
+pub struct SphinxSignatureHandler;
+
+impl HandleTag for SphinxSignatureHandler {
+    fn should_handle(&self, tag: &str) -> bool {
+        matches!(tag, "dt" | "dd")
+    }
+
+    fn handle_tag_start(
+        &mut self,
+        tag: &HtmlElement,
+        writer: &mut MarkdownWriter,
+    ) -> StartTagOutcome {
+        match tag.tag() {
+            "dt" => writer.push_str("\n\n**"),
+            "dd" => writer.push_blank_line(),
+            _ => {}
+        }
+
+        StartTagOutcome::Continue
+    }
+
+    fn handle_tag_end(&mut self, tag: &HtmlElement, writer: &mut MarkdownWriter) {
+        match tag.tag() {
+            "dt" => {
+                writer.push_str("**");
+                writer.push_blank_line();
+            }
+            "dd" => writer.push_blank_line(),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use markup5ever_rcdom::Handle;
+
+use crate::HandleTag;
+use crate::html_element::HtmlElement;
+use crate::markdown_writer::{MarkdownWriter, StartTagOutcome};
+use crate::structure::find_elements;
+
+/// Returns the title of the question and the column holding the question and its answers, leaving out the sidebars.
+pub fn main_content(document: &Handle) -> Vec<Handle> {
+    find_elements(document, |element| {
+        matches!(
+            element.attr("id").as_deref(),
+            Some("question-header" | "mainbar")
+        )
+    })
+}
+
+/// Returns the language of a code block, from the `lang-*` class of its `<pre>`.
+///
+/// Code blocks without a language hint get `lang-default`, which isn't a language.
+pub fn code_language(tag: &HtmlElement) -> Option<String> {
+    if tag.tag() != "pre" {
+        return None;
+    }
+    tag.classes().iter().find_map(|class| {
+        class
+            .strip_prefix("lang-")
+            .filter(|language| *language != "default")
+            .map(ToOwned::to_owned)
+    })
+}
+
+pub struct StackOverflowChromeRemover;
+
+impl HandleTag for StackOverflowChromeRemover {
+    fn should_handle(&self, _tag: &str) -> bool {
+        true
+    }
+
+    fn handle_tag_start(
+        &mut self,
+        tag: &HtmlElement,
+        _writer: &mut MarkdownWriter,
+    ) -> StartTagOutcome {
+        match tag.tag() {
+            "head" | "script" | "style" | "nav" | "form" | "select" | "label" | "button" => {
+                return StartTagOutcome::Skip;
+            }
+            "div" | "span" | "a" | "h2" => {
+                if let Some(id) = tag.attr("id")
+                    && (matches!(id.as_str(), "answers-header" | "post-form")
+                        || id.starts_with("comments-link-"))
+                {
+                    return StartTagOutcome::Skip;
+                }
+
+                let classes_to_skip = [
+                    "votecell",
+                    "js-voting-container",
+                    "js-post-menu",
+                    "comment-actions",
+                    "comment-date",
+                    "post-signature",
+                    "js-add-link",
+                    "user-gravatar32",
+                    "-flair",
+                    "bottom-notice",
+                    "aside-cta",
+                ];
+                if tag.has_any_classes(&classes_to_skip) {
+                    return StartTagOutcome::Skip;
+                }
+            }
+            _ => {}
+        }
+
+        StartTagOutcome::Continue
+    }
+}
+
+/// Puts a heading with the score in front of each answer, in place of the voting buttons.
+pub struct StackOverflowPostHandler;
+
+impl HandleTag for StackOverflowPostHandler {
+    fn should_handle(&self, tag: &str) -> bool {
+        tag == "div"
+    }
+
+    fn handle_tag_start(
+        &mut self,
+        tag: &HtmlElement,
+        writer: &mut MarkdownWriter,
+    ) -> StartTagOutcome {
+        if tag.has_class("answer") {
+            let heading = if tag.has_class("accepted-answer") {
+                "Accepted answer"
+            } else {
+                "Answer"
+            };
+            writer.push_str("\n\n## ");
+            writer.push_str(heading);
+            if let Some(score) = tag.attr("data-score") {
+                writer.push_str(&format!(" (score: {score})"));
+            }
+            writer.push_blank_line();
+        }
+
+        StartTagOutcome::Continue
+    }
+}
@@ -1,6 +1,19 @@
+use markup5ever_rcdom::Handle;
+
 use crate::HandleTag;
 use crate::html_element::HtmlElement;
 use crate::markdown_writer::{HandlerOutcome, MarkdownWriter, StartTagOutcome};
+use crate::structure::find_elements;
+
+/// Returns the title and the body of the article, leaving out the navigation and the page tools.
+pub fn main_content(document: &Handle) -> Vec<Handle> {
+    find_elements(document, |element| {
+        matches!(
+            element.attr("id").as_deref(),
+            Some("firstHeading" | "mw-content-text")
+        )
+    })
+}
 
 pub struct WikipediaChromeRemover;
 
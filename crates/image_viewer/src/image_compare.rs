@@ -0,0 +1,116 @@
+use image::{DynamicImage, GenericImageView as _, Rgba, RgbaImage};
+
+/// How an image is shown next to the one it's compared with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CompareMode {
+    /// Both images next to each other, zoomed and panned together.
+    #[default]
+    SideBySide,
+    /// The image the view is compared with, drawn translucently over the view's own.
+    OnionSkin,
+    /// The per-channel difference of both images, black where they match.
+    Difference,
+}
+
+impl CompareMode {
+    pub fn next(self) -> Self {
+        match self {
+            CompareMode::SideBySide => CompareMode::OnionSkin,
+            CompareMode::OnionSkin => CompareMode::Difference,
+            CompareMode::Difference => CompareMode::SideBySide,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CompareMode::SideBySide => "Side by Side",
+            CompareMode::OnionSkin => "Onion Skin",
+            CompareMode::Difference => "Difference",
+        }
+    }
+}
+
+/// The difference of two images, aligned on their top-left corner.
+pub struct ImageDifference {
+    /// The absolute difference of each channel, opaque, as large as both images together.
+    pub image: RgbaImage,
+    /// The number of pixels that differ, counting those only one of the images has.
+    pub differing_pixels: u64,
+}
+
+pub fn difference(a: &DynamicImage, b: &DynamicImage) -> ImageDifference {
+    let width = a.width().max(b.width());
+    let height = a.height().max(b.height());
+    let pixel = |image: &DynamicImage, x, y| {
+        if image.in_bounds(x, y) {
+            image.get_pixel(x, y)
+        } else {
+            Rgba([0, 0, 0, 0])
+        }
+    };
+
+    let mut differing_pixels = 0;
+    let image = RgbaImage::from_fn(width, height, |x, y| {
+        let (a, b) = (pixel(a, x, y), pixel(b, x, y));
+        if a != b {
+            differing_pixels += 1;
+        }
+        // Pixels that only differ in transparency still show up, as white.
+        let alpha_difference = a[3].abs_diff(b[3]);
+        Rgba([
+            a[0].abs_diff(b[0]).max(alpha_difference),
+            a[1].abs_diff(b[1]).max(alpha_difference),
+            a[2].abs_diff(b[2]).max(alpha_difference),
+            255,
+        ])
+    });
+
+    ImageDifference {
+        image,
+        differing_pixels,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_difference() {
+        let a = DynamicImage::ImageRgba8(RgbaImage::from_pixel(2, 2, Rgba([10, 20, 30, 255])));
+        let mut b = RgbaImage::from_pixel(3, 1, Rgba([10, 20, 30, 255]));
+        b.put_pixel(1, 0, Rgba([40, 0, 30, 255]));
+        let b = DynamicImage::ImageRgba8(b);
+
+        let difference = difference(&a, &b);
+        assert_eq!(difference.image.dimensions(), (3, 2));
+        assert_eq!(difference.image.get_pixel(0, 0), &Rgba([0, 0, 0, 255]));
+        assert_eq!(difference.image.get_pixel(1, 0), &Rgba([30, 20, 0, 255]));
+        // Only `b` has the pixel at (2, 0), and only `a` the ones on the second row.
+        assert_eq!(
+            difference.image.get_pixel(2, 0),
+            &Rgba([255, 255, 255, 255])
+        );
+        assert_eq!(
+            difference.image.get_pixel(0, 1),
+            &Rgba([255, 255, 255, 255])
+        );
+        assert_eq!(difference.differing_pixels, 4);
+    }
+
+    #[test]
+    fn test_difference_of_identical_images() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_fn(4, 4, |x, y| {
+            Rgba([x as u8, y as u8, 0, 128])
+        }));
+
+        let difference = difference(&image, &image);
+        assert_eq!(difference.differing_pixels, 0);
+        assert!(
+            difference
+                .image
+                .pixels()
+                .all(|pixel| *pixel == Rgba([0, 0, 0, 255]))
+        );
+    }
+}
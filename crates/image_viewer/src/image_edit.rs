@@ -0,0 +1,172 @@
+use std::io::Cursor;
+
+use anyhow::{Result, bail};
+use image::{DynamicImage, ImageFormat};
+
+/// A rectangle of an image, in pixels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PixelRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl PixelRect {
+    /// Returns the rectangle spanned by two pixels, both included, or `None` if it lies outside of the image.
+    pub fn from_corners(a: (u32, u32), b: (u32, u32), image_size: (u32, u32)) -> Option<Self> {
+        let (width, height) = image_size;
+        if width == 0 || height == 0 {
+            return None;
+        }
+        let (left, right) = (a.0.min(b.0), a.0.max(b.0).min(width - 1));
+        let (top, bottom) = (a.1.min(b.1), a.1.max(b.1).min(height - 1));
+        if left > right || top > bottom {
+            return None;
+        }
+        Some(Self {
+            x: left,
+            y: top,
+            width: right - left + 1,
+            height: bottom - top + 1,
+        })
+    }
+}
+
+/// An edit of the image shown by an image viewer, kept until the image is saved.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageTransform {
+    RotateClockwise,
+    RotateCounterclockwise,
+    FlipHorizontal,
+    FlipVertical,
+    Crop(PixelRect),
+}
+
+impl ImageTransform {
+    pub fn apply(self, image: &DynamicImage) -> DynamicImage {
+        match self {
+            ImageTransform::RotateClockwise => image.rotate90(),
+            ImageTransform::RotateCounterclockwise => image.rotate270(),
+            ImageTransform::FlipHorizontal => image.fliph(),
+            ImageTransform::FlipVertical => image.flipv(),
+            ImageTransform::Crop(rect) => image.crop_imm(rect.x, rect.y, rect.width, rect.height),
+        }
+    }
+}
+
+/// Applies the transforms to the image, in order.
+pub fn apply_transforms(image: &DynamicImage, transforms: &[ImageTransform]) -> DynamicImage {
+    let mut transformed = image.clone();
+    for transform in transforms {
+        transformed = transform.apply(&transformed);
+    }
+    transformed
+}
+
+/// Encodes the image in the given format, converting its pixels to the ones the format can hold.
+///
+/// Formats with several frames, such as GIF, aren't supported, as saving them would drop all frames but the first.
+pub fn encode_image(image: &DynamicImage, format: ImageFormat) -> Result<Vec<u8>> {
+    let image = match format {
+        ImageFormat::Jpeg => DynamicImage::ImageRgb8(image.to_rgb8()),
+        ImageFormat::WebP | ImageFormat::Ico => DynamicImage::ImageRgba8(image.to_rgba8()),
+        ImageFormat::Png | ImageFormat::Bmp | ImageFormat::Tiff | ImageFormat::Pnm => image.clone(),
+        format => bail!("Saving {format:?} images is not supported"),
+    };
+    let mut bytes = Cursor::new(Vec::new());
+    image.write_to(&mut bytes, format)?;
+    Ok(bytes.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{Rgba, RgbaImage};
+
+    use super::*;
+
+    fn test_image() -> DynamicImage {
+        // A 3x2 image whose red channel holds the index of the pixel.
+        DynamicImage::ImageRgba8(RgbaImage::from_fn(3, 2, |x, y| {
+            Rgba([(y * 3 + x) as u8, 0, 0, 255])
+        }))
+    }
+
+    fn red_channel(image: &DynamicImage) -> Vec<u8> {
+        image.to_rgba8().pixels().map(|pixel| pixel[0]).collect()
+    }
+
+    #[test]
+    fn test_apply_transforms() {
+        let image = test_image();
+
+        let rotated = apply_transforms(&image, &[ImageTransform::RotateClockwise]);
+        assert_eq!((rotated.width(), rotated.height()), (2, 3));
+        assert_eq!(red_channel(&rotated), [3, 0, 4, 1, 5, 2]);
+
+        let rotated = apply_transforms(&image, &[ImageTransform::RotateCounterclockwise]);
+        assert_eq!(red_channel(&rotated), [2, 5, 1, 4, 0, 3]);
+
+        let flipped = apply_transforms(&image, &[ImageTransform::FlipHorizontal]);
+        assert_eq!(red_channel(&flipped), [2, 1, 0, 5, 4, 3]);
+
+        let flipped = apply_transforms(&image, &[ImageTransform::FlipVertical]);
+        assert_eq!(red_channel(&flipped), [3, 4, 5, 0, 1, 2]);
+
+        let rect = PixelRect::from_corners((2, 1), (1, 0), (3, 2)).unwrap();
+        let cropped = apply_transforms(
+            &image,
+            &[ImageTransform::Crop(rect), ImageTransform::RotateClockwise],
+        );
+        assert_eq!((cropped.width(), cropped.height()), (2, 2));
+        assert_eq!(red_channel(&cropped), [4, 1, 5, 2]);
+
+        let round_trip = apply_transforms(
+            &image,
+            &[
+                ImageTransform::RotateClockwise,
+                ImageTransform::RotateCounterclockwise,
+            ],
+        );
+        assert_eq!(red_channel(&round_trip), red_channel(&image));
+    }
+
+    #[test]
+    fn test_crop_rect_from_corners() {
+        assert_eq!(
+            PixelRect::from_corners((1, 5), (3, 2), (10, 10)),
+            Some(PixelRect {
+                x: 1,
+                y: 2,
+                width: 3,
+                height: 4
+            })
+        );
+        assert_eq!(
+            PixelRect::from_corners((8, 8), (20, 30), (10, 10)),
+            Some(PixelRect {
+                x: 8,
+                y: 8,
+                width: 2,
+                height: 2
+            })
+        );
+        assert_eq!(PixelRect::from_corners((12, 0), (15, 3), (10, 10)), None);
+        assert_eq!(PixelRect::from_corners((0, 0), (1, 1), (0, 0)), None);
+    }
+
+    #[test]
+    fn test_encode_image() {
+        let image = test_image();
+
+        let png = encode_image(&image, ImageFormat::Png).unwrap();
+        let decoded = image::load_from_memory_with_format(&png, ImageFormat::Png).unwrap();
+        assert_eq!(decoded.to_rgba8(), image.to_rgba8());
+
+        let jpeg = encode_image(&image, ImageFormat::Jpeg).unwrap();
+        let decoded = image::load_from_memory_with_format(&jpeg, ImageFormat::Jpeg).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (3, 2));
+
+        assert!(encode_image(&image, ImageFormat::Gif).is_err());
+    }
+}
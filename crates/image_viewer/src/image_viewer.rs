@@ -1,34 +1,47 @@
+mod image_compare;
+mod image_edit;
 mod image_info;
 mod image_viewer_settings;
+mod pixel_inspector;
 
 use std::path::Path;
+use std::sync::Arc;
 
-use anyhow::Context as _;
+use anyhow::{Context as _, Result, anyhow};
 use editor::{EditorSettings, RevealInFileManager, items::entry_git_aware_label_color};
 use file_icons::FileIcons;
 use gpui::{
-    AnyElement, App, Bounds, Context, DispatchPhase, Element, ElementId, Entity, EventEmitter,
-    FocusHandle, Focusable, Font, GlobalElementId, InspectorElementId, InteractiveElement,
-    IntoElement, LayoutId, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent,
-    ParentElement, PinchEvent, Pixels, Point, Render, ScrollDelta, ScrollWheelEvent, Style, Styled,
-    Task, WeakEntity, Window, actions, checkerboard, div, img, point, px, size,
+    AnyElement, App, Bounds, ClipboardItem, Context, DispatchPhase, Div, Element, ElementId,
+    Entity, EventEmitter, FocusHandle, Focusable, Font, GlobalElementId, InspectorElementId,
+    InteractiveElement, IntoElement, LayoutId, MouseButton, MouseDownEvent, MouseMoveEvent,
+    MouseUpEvent, ParentElement, PathPromptOptions, PinchEvent, Pixels, Point, Render, ScrollDelta,
+    ScrollWheelEvent, Size, Style, Styled, Task, WeakEntity, Window, actions, checkerboard, div,
+    img, point, px, size,
 };
+use image::DynamicImage;
 use language::File as _;
 use persistence::ImageViewerDb;
 use project::{ImageItem, Project, ProjectPath, image_store::ImageItemEvent};
 use settings::Settings;
 use theme_settings::ThemeSettings;
-use ui::{Tooltip, prelude::*};
+use ui::{ContextMenu, Divider, DividerColor, PopoverMenu, Tooltip, prelude::*};
 use util::paths::PathExt;
 use workspace::{
     ItemId, ItemSettings, Pane, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView, Workspace,
     WorkspaceId, delete_unloaded_items,
     invalid_item_view::InvalidItemView,
-    item::{HighlightedText, Item, ItemHandle, ProjectItem, SerializableItem, TabContentParams},
+    item::{
+        HighlightedText, Item, ItemHandle, ProjectItem, SaveOptions, SerializableItem,
+        TabContentParams,
+    },
+    notifications::DetachAndPromptErr as _,
 };
 
+use crate::image_compare::CompareMode;
+use crate::image_edit::{ImageTransform, PixelRect};
 pub use crate::image_info::*;
 pub use crate::image_viewer_settings::*;
+use crate::pixel_inspector::InspectedPixel;
 
 actions!(
     image_viewer,
@@ -42,7 +55,33 @@ actions!(
         /// Fit the image to view.
         FitToView,
         /// Zoom to actual size (100%).
-        ZoomToActualSize
+        ZoomToActualSize,
+        /// Compare the image with its version in the git HEAD.
+        CompareWithHead,
+        /// Compare the image with another image file.
+        CompareWithFile,
+        /// Switch between showing the compared images side by side, as an onion skin, or as their difference.
+        CycleCompareMode,
+        /// Stop comparing the image.
+        StopComparing,
+        /// Toggle the pixel inspector, showing the coordinates and color of the pixel under the mouse.
+        TogglePixelInspector,
+        /// Rotate the image clockwise.
+        RotateClockwise,
+        /// Rotate the image counterclockwise.
+        RotateCounterclockwise,
+        /// Flip the image horizontally.
+        FlipHorizontal,
+        /// Flip the image vertically.
+        FlipVertical,
+        /// Toggle selecting the region to crop the image to.
+        ToggleCrop,
+        /// Crop the image to the selected region.
+        ApplyCrop,
+        /// Leave the crop selection or the pixel inspector.
+        DismissTool,
+        /// Discard the unsaved edits of the image.
+        DiscardEdits
     ]
 );
 
@@ -51,6 +90,46 @@ const MAX_ZOOM: f32 = 20.0;
 const ZOOM_STEP: f32 = 1.1;
 const SCROLL_LINE_MULTIPLIER: f32 = 20.0;
 const BASE_SQUARE_SIZE: f32 = 32.0;
+const SIDE_BY_SIDE_GAP: Pixels = px(16.0);
+const ONION_SKIN_OPACITY_STEP: f32 = 0.25;
+
+/// What dragging the left mouse button over the image does.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Tool {
+    #[default]
+    Pan,
+    PixelInspector,
+    Crop,
+}
+
+/// The image with the unsaved edits applied, and its rendering.
+struct EditedImage {
+    image: Arc<DynamicImage>,
+    preview: Arc<gpui::Image>,
+}
+
+struct Comparison {
+    /// What the image is compared with, such as `HEAD` or the name of another file.
+    label: SharedString,
+    mode: CompareMode,
+    onion_skin_opacity: f32,
+    image: Arc<DynamicImage>,
+    preview: Arc<gpui::Image>,
+    /// Recomputed in the background whenever the image is edited.
+    difference: Option<RenderedDifference>,
+}
+
+struct RenderedDifference {
+    size: (u32, u32),
+    differing_pixels: u64,
+    preview: Arc<gpui::Image>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct CropSelection {
+    start: (u32, u32),
+    end: (u32, u32),
+}
 
 pub struct ImageView {
     image_item: Entity<ImageItem>,
@@ -61,6 +140,16 @@ pub struct ImageView {
     last_mouse_position: Option<Point<Pixels>>,
     container_bounds: Option<Bounds<Pixels>>,
     image_size: Option<(u32, u32)>,
+    /// The image file decoded, once it was needed to edit, inspect or compare the image.
+    decoded_image: Option<Arc<DynamicImage>>,
+    edits: Vec<ImageTransform>,
+    edited_image: Option<EditedImage>,
+    comparison: Option<Comparison>,
+    tool: Tool,
+    inspected_pixel: Option<InspectedPixel>,
+    crop_selection: Option<CropSelection>,
+    /// Where the pixels of the image, and those of the image it's compared with side by side, were last painted.
+    image_layers: Vec<(Bounds<Pixels>, (u32, u32))>,
 }
 
 impl ImageView {
@@ -80,13 +169,17 @@ impl ImageView {
             image.image.clone().get_render_image(window, cx)
         });
 
-        cx.subscribe(&image_item, Self::on_image_event).detach();
+        cx.subscribe_in(&image_item, window, Self::on_image_event)
+            .detach();
         cx.on_release_in(window, |this, window, cx| {
             let image_data = this.image_item.read(cx).image.clone();
-            if let Some(image) = image_data.clone().get_render_image(window, cx) {
-                cx.drop_image(image, None);
+            drop_preview(image_data, window, cx);
+            if let Some(edited_image) = this.edited_image.take() {
+                drop_preview(edited_image.preview, window, cx);
+            }
+            if let Some(comparison) = this.comparison.take() {
+                comparison.drop_previews(window, cx);
             }
-            image_data.remove_asset(cx);
         })
         .detach();
 
@@ -104,24 +197,42 @@ impl ImageView {
             last_mouse_position: None,
             container_bounds: None,
             image_size,
+            decoded_image: None,
+            edits: Vec::new(),
+            edited_image: None,
+            comparison: None,
+            tool: Tool::default(),
+            inspected_pixel: None,
+            crop_selection: None,
+            image_layers: Vec::new(),
         }
     }
 
     fn on_image_event(
         &mut self,
-        _: Entity<ImageItem>,
+        _: &Entity<ImageItem>,
         event: &ImageItemEvent,
+        window: &mut Window,
         cx: &mut Context<Self>,
     ) {
         match event {
             ImageItemEvent::MetadataUpdated
             | ImageItemEvent::FileHandleChanged
             | ImageItemEvent::Reloaded => {
-                self.image_size = self
-                    .image_item
-                    .read(cx)
-                    .image_metadata
-                    .map(|m| (m.width, m.height));
+                if matches!(event, ImageItemEvent::Reloaded) {
+                    self.decoded_image = None;
+                    // Unsaved edits are kept over reloads, and applied to the new contents.
+                    if self.edits.is_empty() {
+                        self.set_edited_image(None, window, cx);
+                    }
+                }
+                if self.edited_image.is_none() {
+                    self.image_size = self
+                        .image_item
+                        .read(cx)
+                        .image_metadata
+                        .map(|m| (m.width, m.height));
+                }
                 cx.emit(ImageViewEvent::TitleChanged);
                 cx.notify();
             }
@@ -129,6 +240,405 @@ impl ImageView {
         }
     }
 
+    /// Returns the image with the edits applied, decoding it first if needed.
+    fn current_image(&mut self, cx: &mut Context<Self>) -> Task<Result<Arc<DynamicImage>>> {
+        if let Some(edited_image) = &self.edited_image {
+            return Task::ready(Ok(edited_image.image.clone()));
+        }
+        self.decode_image(cx)
+    }
+
+    fn decode_image(&mut self, cx: &mut Context<Self>) -> Task<Result<Arc<DynamicImage>>> {
+        if let Some(decoded_image) = &self.decoded_image {
+            return Task::ready(Ok(decoded_image.clone()));
+        }
+        let image = self.image_item.read(cx).image.clone();
+        cx.spawn(async move |this, cx| {
+            let decoded_image = cx
+                .background_spawn(async move { image::load_from_memory(&image.bytes) })
+                .await
+                .context("Failed to decode the image")?;
+            let decoded_image = Arc::new(decoded_image);
+            this.update(cx, |this, _| {
+                this.decoded_image = Some(decoded_image.clone());
+            })?;
+            Ok(decoded_image)
+        })
+    }
+
+    fn edit(&mut self, transform: ImageTransform, window: &mut Window, cx: &mut Context<Self>) {
+        self.edits.push(transform);
+        let edits = self.edits.clone();
+        let decoded_image = self.decode_image(cx);
+        cx.spawn_in(window, async move |this, cx| {
+            let edited_image = async {
+                let decoded_image = decoded_image.await?;
+                let edits = edits.clone();
+                cx.background_spawn(async move {
+                    let image = image_edit::apply_transforms(&decoded_image, &edits);
+                    let preview = png_preview(&image)?;
+                    anyhow::Ok(EditedImage {
+                        image: Arc::new(image),
+                        preview,
+                    })
+                })
+                .await
+            }
+            .await;
+            this.update_in(cx, |this, window, cx| {
+                // Edits made in the meantime are rendered by their own task.
+                if this.edits != edits {
+                    return Ok(());
+                }
+                match edited_image {
+                    Ok(edited_image) => {
+                        this.set_edited_image(Some(edited_image), window, cx);
+                        Ok(())
+                    }
+                    Err(error) => {
+                        this.edits.pop();
+                        cx.emit(ImageViewEvent::DirtyChanged);
+                        cx.notify();
+                        Err(error)
+                    }
+                }
+            })?
+        })
+        .detach_and_prompt_err("Failed to edit image", window, cx, |_, _, _| None);
+        cx.emit(ImageViewEvent::DirtyChanged);
+        cx.notify();
+    }
+
+    fn set_edited_image(
+        &mut self,
+        edited_image: Option<EditedImage>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(previous) = self.edited_image.take() {
+            drop_preview(previous.preview, window, cx);
+        }
+        self.image_size = match &edited_image {
+            Some(edited_image) => Some((edited_image.image.width(), edited_image.image.height())),
+            None => self
+                .image_item
+                .read(cx)
+                .image_metadata
+                .map(|m| (m.width, m.height)),
+        };
+        self.edited_image = edited_image;
+        self.crop_selection = None;
+        self.inspected_pixel = None;
+        self.refresh_difference(window, cx);
+        cx.notify();
+    }
+
+    fn rotate_clockwise(
+        &mut self,
+        _: &RotateClockwise,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.edit(ImageTransform::RotateClockwise, window, cx);
+    }
+
+    fn rotate_counterclockwise(
+        &mut self,
+        _: &RotateCounterclockwise,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.edit(ImageTransform::RotateCounterclockwise, window, cx);
+    }
+
+    fn flip_horizontal(&mut self, _: &FlipHorizontal, window: &mut Window, cx: &mut Context<Self>) {
+        self.edit(ImageTransform::FlipHorizontal, window, cx);
+    }
+
+    fn flip_vertical(&mut self, _: &FlipVertical, window: &mut Window, cx: &mut Context<Self>) {
+        self.edit(ImageTransform::FlipVertical, window, cx);
+    }
+
+    fn toggle_crop(&mut self, _: &ToggleCrop, _window: &mut Window, cx: &mut Context<Self>) {
+        self.set_tool(
+            if self.tool == Tool::Crop {
+                Tool::Pan
+            } else {
+                Tool::Crop
+            },
+            cx,
+        );
+    }
+
+    fn apply_crop(&mut self, _: &ApplyCrop, window: &mut Window, cx: &mut Context<Self>) {
+        let Some((selection, image_size)) = self.crop_selection.zip(self.image_size) else {
+            return;
+        };
+        if let Some(rect) = PixelRect::from_corners(selection.start, selection.end, image_size) {
+            self.set_tool(Tool::Pan, cx);
+            self.edit(ImageTransform::Crop(rect), window, cx);
+        }
+    }
+
+    fn discard_edits(&mut self, _: &DiscardEdits, window: &mut Window, cx: &mut Context<Self>) {
+        if self.edits.is_empty() {
+            return;
+        }
+        self.edits.clear();
+        self.set_edited_image(None, window, cx);
+        cx.emit(ImageViewEvent::DirtyChanged);
+    }
+
+    fn toggle_pixel_inspector(
+        &mut self,
+        _: &TogglePixelInspector,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.set_tool(
+            if self.tool == Tool::PixelInspector {
+                Tool::Pan
+            } else {
+                Tool::PixelInspector
+            },
+            cx,
+        );
+    }
+
+    fn dismiss_tool(&mut self, _: &DismissTool, _window: &mut Window, cx: &mut Context<Self>) {
+        if self.tool == Tool::Pan {
+            cx.propagate();
+            return;
+        }
+        self.set_tool(Tool::Pan, cx);
+    }
+
+    fn set_tool(&mut self, tool: Tool, cx: &mut Context<Self>) {
+        self.tool = tool;
+        self.crop_selection = None;
+        self.inspected_pixel = None;
+        if tool != Tool::Pan {
+            // Decode the image ahead of the first click, which needs its pixels.
+            self.current_image(cx).detach_and_log_err(cx);
+        }
+        cx.notify();
+    }
+
+    fn compare_with_head(
+        &mut self,
+        _: &CompareWithHead,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let project_path = self.image_item.read(cx).project_path(cx);
+        let repository_and_path = self
+            .project
+            .read(cx)
+            .git_store()
+            .read(cx)
+            .repository_and_path_for_project_path(&project_path, cx);
+        let committed_bytes = match repository_and_path {
+            Some((repository, repo_path)) => {
+                let committed_bytes = repository.update(cx, |repository, _| {
+                    repository.load_committed_bytes(repo_path)
+                });
+                cx.background_spawn(async move {
+                    committed_bytes
+                        .await??
+                        .context("The image is not in the HEAD commit")
+                })
+            }
+            None => Task::ready(Err(anyhow!("The image is not in a git repository"))),
+        };
+        self.compare_with("HEAD".into(), committed_bytes, window, cx);
+    }
+
+    fn compare_with_file(
+        &mut self,
+        _: &CompareWithFile,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some("Compare".into()),
+        });
+        let project = self.project.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let Some(path) = paths.await??.and_then(|paths| paths.into_iter().next()) else {
+                return Ok(());
+            };
+            let (worktree, relative_path) = project
+                .update(cx, |project, cx| {
+                    project.find_or_create_worktree(&path, false, cx)
+                })
+                .await?;
+            let worktree_id = worktree.update(cx, |worktree, _| worktree.id());
+            let image_item = project
+                .update(cx, |project, cx| {
+                    project.open_image(
+                        ProjectPath {
+                            worktree_id,
+                            path: relative_path,
+                        },
+                        cx,
+                    )
+                })
+                .await?;
+            this.update_in(cx, |this, window, cx| {
+                let image_item = image_item.read(cx);
+                let label = image_item.file.file_name(cx).to_string().into();
+                let bytes = image_item.image.bytes.clone();
+                this.compare_with(label, Task::ready(Ok(bytes)), window, cx);
+            })
+        })
+        .detach_and_prompt_err("Failed to open image", window, cx, |_, _, _| None);
+    }
+
+    fn compare_with(
+        &mut self,
+        label: SharedString,
+        bytes: Task<Result<Vec<u8>>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let current_image = self.current_image(cx);
+        cx.spawn_in(window, async move |this, cx| {
+            let bytes = bytes.await?;
+            let current_image = current_image.await?;
+            let (image, preview, difference) = cx
+                .background_spawn(async move {
+                    let image = image::load_from_memory(&bytes)
+                        .context("Failed to decode the image to compare with")?;
+                    let preview = png_preview(&image)?;
+                    let difference = render_difference(&current_image, &image)?;
+                    anyhow::Ok((Arc::new(image), preview, difference))
+                })
+                .await?;
+            this.update_in(cx, |this, window, cx| {
+                let previous = this.comparison.replace(Comparison {
+                    label,
+                    mode: CompareMode::default(),
+                    onion_skin_opacity: 0.5,
+                    image,
+                    preview,
+                    difference: Some(difference),
+                });
+                if let Some(previous) = previous {
+                    previous.drop_previews(window, cx);
+                }
+                this.inspected_pixel = None;
+                cx.notify();
+            })
+        })
+        .detach_and_prompt_err("Failed to compare images", window, cx, |_, _, _| None);
+    }
+
+    /// Recomputes the difference with the compared image, after the image was edited.
+    fn refresh_difference(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(comparison) = self.comparison.as_mut() else {
+            return;
+        };
+        if let Some(difference) = comparison.difference.take() {
+            drop_preview(difference.preview, window, cx);
+        }
+        let compared_image = comparison.image.clone();
+        let current_image = self.current_image(cx);
+        cx.spawn_in(window, async move |this, cx| {
+            let current_image = current_image.await?;
+            let difference = cx
+                .background_spawn({
+                    let compared_image = compared_image.clone();
+                    async move { render_difference(&current_image, &compared_image) }
+                })
+                .await?;
+            this.update(cx, |this, cx| {
+                if let Some(comparison) = this.comparison.as_mut()
+                    && Arc::ptr_eq(&comparison.image, &compared_image)
+                    && comparison.difference.is_none()
+                {
+                    comparison.difference = Some(difference);
+                    cx.notify();
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn cycle_compare_mode(
+        &mut self,
+        _: &CycleCompareMode,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(comparison) = self.comparison.as_mut() {
+            comparison.mode = comparison.mode.next();
+            cx.notify();
+        }
+    }
+
+    fn cycle_onion_skin_opacity(&mut self, cx: &mut Context<Self>) {
+        if let Some(comparison) = self.comparison.as_mut() {
+            comparison.onion_skin_opacity += ONION_SKIN_OPACITY_STEP;
+            if comparison.onion_skin_opacity > 1.0 {
+                comparison.onion_skin_opacity = ONION_SKIN_OPACITY_STEP;
+            }
+            cx.notify();
+        }
+    }
+
+    fn stop_comparing(&mut self, _: &StopComparing, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(comparison) = self.comparison.take() {
+            comparison.drop_previews(window, cx);
+            self.inspected_pixel = None;
+            cx.notify();
+        }
+    }
+
+    /// Returns the pixel of the image, or of the image it's compared with side by side, under the mouse.
+    fn pixel_at_position(&self, position: Point<Pixels>) -> Option<(u32, u32)> {
+        self.image_layers.iter().find_map(|(bounds, image_size)| {
+            let offset = position - bounds.origin;
+            pixel_inspector::pixel_at(
+                (offset.x.into(), offset.y.into()),
+                self.zoom_level,
+                *image_size,
+            )
+        })
+    }
+
+    /// Returns the pixel of the image under the mouse, or the one at the nearest edge when the mouse is outside of it.
+    fn clamped_pixel_at_position(&self, position: Point<Pixels>) -> Option<(u32, u32)> {
+        let (bounds, (width, height)) = self.image_layers.first()?;
+        let offset = position - bounds.origin;
+        let clamp = |offset: Pixels, size: u32| {
+            let pixel = (f32::from(offset) / self.zoom_level).floor();
+            pixel.clamp(0.0, size.saturating_sub(1) as f32) as u32
+        };
+        Some((clamp(offset.x, *width), clamp(offset.y, *height)))
+    }
+
+    fn inspect_pixel(&mut self, position: Point<Pixels>, cx: &mut Context<Self>) {
+        let image = match (&self.edited_image, &self.decoded_image) {
+            (Some(edited_image), _) => &edited_image.image,
+            (None, Some(decoded_image)) => decoded_image,
+            (None, None) => return,
+        };
+        let inspected_pixel = self.pixel_at_position(position).and_then(|pixel| {
+            let compared_image = self
+                .comparison
+                .as_ref()
+                .map(|comparison| &*comparison.image);
+            InspectedPixel::new(image, compared_image, pixel)
+        });
+        if inspected_pixel != self.inspected_pixel {
+            self.inspected_pixel = inspected_pixel;
+            cx.notify();
+        }
+    }
+
     fn zoom_in(&mut self, _: &ZoomIn, _window: &mut Window, cx: &mut Context<Self>) {
         self.set_zoom(self.zoom_level * ZOOM_STEP, None, cx);
     }
@@ -241,9 +751,29 @@ impl ImageView {
         _window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        if event.button == MouseButton::Left || event.button == MouseButton::Middle {
-            self.last_mouse_position = Some(event.position);
-            cx.notify();
+        match (event.button, self.tool) {
+            (MouseButton::Left, Tool::Crop) => {
+                self.crop_selection =
+                    self.clamped_pixel_at_position(event.position)
+                        .map(|pixel| CropSelection {
+                            start: pixel,
+                            end: pixel,
+                        });
+                cx.notify();
+            }
+            (MouseButton::Left, Tool::PixelInspector) => {
+                self.inspect_pixel(event.position, cx);
+                if let Some(inspected_pixel) = self.inspected_pixel {
+                    cx.write_to_clipboard(ClipboardItem::new_string(pixel_inspector::hex_color(
+                        inspected_pixel.color,
+                    )));
+                }
+            }
+            (MouseButton::Left | MouseButton::Middle, _) => {
+                self.last_mouse_position = Some(event.position);
+                cx.notify();
+            }
+            _ => {}
         }
     }
 
@@ -271,6 +801,20 @@ impl ImageView {
             self.last_mouse_position = Some(event.position);
             cx.notify();
         }
+
+        match self.tool {
+            Tool::Pan => {}
+            Tool::PixelInspector => self.inspect_pixel(event.position, cx),
+            Tool::Crop => {
+                if event.pressed_button == Some(MouseButton::Left)
+                    && let Some(pixel) = self.clamped_pixel_at_position(event.position)
+                    && let Some(selection) = self.crop_selection.as_mut()
+                {
+                    selection.end = pixel;
+                    cx.notify();
+                }
+            }
+        }
     }
 
     fn handle_pinch(&mut self, event: &PinchEvent, _window: &mut Window, cx: &mut Context<Self>) {
@@ -339,7 +883,11 @@ impl Element for ImageContentElement {
         cx: &mut App,
     ) -> Self::PrepaintState {
         let image_view = self.image_view.read(cx);
-        let image = image_view.image_item.read(cx).image.clone();
+        let image = image_view
+            .edited_image
+            .as_ref()
+            .map(|edited_image| edited_image.preview.clone())
+            .unwrap_or_else(|| image_view.image_item.read(cx).image.clone());
 
         let first_layout = image_view.container_bounds.is_none();
 
@@ -354,27 +902,140 @@ impl Element for ImageContentElement {
         let zoom_level = initial_zoom_level.unwrap_or(image_view.zoom_level);
 
         let pan_offset = image_view.pan_offset;
-        let border_color = cx.theme().colors().border;
 
         let is_dragging = image_view.is_dragging();
 
-        let scaled_size = image_view
-            .image_size
-            .map(|(w, h)| (px(w as f32 * zoom_level), px(h as f32 * zoom_level)));
-
-        let (mut left, mut top) = (px(0.0), px(0.0));
-        let mut scaled_width = px(0.0);
-        let mut scaled_height = px(0.0);
+        let scale = |(width, height): (u32, u32)| {
+            size(
+                px(width as f32 * zoom_level),
+                px(height as f32 * zoom_level),
+            )
+        };
+        let image_size = image_view.image_size.unwrap_or_default();
+        let scaled_size = scale(image_size);
 
-        if let Some((width, height)) = scaled_size {
-            scaled_width = width;
-            scaled_height = height;
+        let center = point(bounds.size.width / 2.0, bounds.size.height / 2.0) + pan_offset;
+        let centered = |size: Size<Pixels>| {
+            Bounds::new(center - point(size.width / 2.0, size.height / 2.0), size)
+        };
 
-            let center_x = bounds.size.width / 2.0;
-            let center_y = bounds.size.height / 2.0;
+        let entity_id = self.image_view.entity_id();
+        let mut layers = Vec::new();
+        // The pixels of the image come first, as cropping only applies to them.
+        let mut image_layers = Vec::new();
+        match image_view.comparison.as_ref() {
+            None => {
+                let image_bounds = centered(scaled_size);
+                layers.push(image_layer(
+                    ("image-viewer-image", entity_id),
+                    image,
+                    image_bounds,
+                    zoom_level,
+                    cx,
+                ));
+                image_layers.push((image_bounds, image_size));
+            }
+            Some(comparison) => {
+                let compared_size = (comparison.image.width(), comparison.image.height());
+                let compared_scaled_size = scale(compared_size);
+                match (comparison.mode, &comparison.difference) {
+                    (CompareMode::SideBySide, _) => {
+                        let both_bounds = centered(size(
+                            compared_scaled_size.width + SIDE_BY_SIDE_GAP + scaled_size.width,
+                            compared_scaled_size.height.max(scaled_size.height),
+                        ));
+                        let compared_bounds = Bounds::new(both_bounds.origin, compared_scaled_size);
+                        let image_bounds = Bounds::new(
+                            point(
+                                compared_bounds.right() + SIDE_BY_SIDE_GAP,
+                                both_bounds.origin.y,
+                            ),
+                            scaled_size,
+                        );
+                        layers.push(image_layer(
+                            ("image-viewer-compared-image", entity_id),
+                            comparison.preview.clone(),
+                            compared_bounds,
+                            zoom_level,
+                            cx,
+                        ));
+                        layers.push(image_layer(
+                            ("image-viewer-image", entity_id),
+                            image,
+                            image_bounds,
+                            zoom_level,
+                            cx,
+                        ));
+                        image_layers.push((image_bounds, image_size));
+                        image_layers.push((compared_bounds, compared_size));
+                    }
+                    (CompareMode::OnionSkin, _) | (CompareMode::Difference, None) => {
+                        let origin = centered(size(
+                            compared_scaled_size.width.max(scaled_size.width),
+                            compared_scaled_size.height.max(scaled_size.height),
+                        ))
+                        .origin;
+                        let image_bounds = Bounds::new(origin, scaled_size);
+                        layers.push(image_layer(
+                            ("image-viewer-image", entity_id),
+                            image,
+                            image_bounds,
+                            zoom_level,
+                            cx,
+                        ));
+                        if comparison.mode == CompareMode::OnionSkin {
+                            layers.push(
+                                div()
+                                    .absolute()
+                                    .left(origin.x)
+                                    .top(origin.y)
+                                    .w(compared_scaled_size.width)
+                                    .h(compared_scaled_size.height)
+                                    .opacity(comparison.onion_skin_opacity)
+                                    .child(
+                                        img(comparison.preview.clone())
+                                            .id(("image-viewer-compared-image", entity_id))
+                                            .size_full(),
+                                    ),
+                            );
+                        }
+                        image_layers.push((image_bounds, image_size));
+                    }
+                    (CompareMode::Difference, Some(difference)) => {
+                        let difference_bounds = centered(scale(difference.size));
+                        layers.push(image_layer(
+                            ("image-viewer-difference", entity_id),
+                            difference.preview.clone(),
+                            difference_bounds,
+                            zoom_level,
+                            cx,
+                        ));
+                        image_layers.push((
+                            Bounds::new(difference_bounds.origin, scaled_size),
+                            image_size,
+                        ));
+                    }
+                }
+            }
+        }
 
-            left = center_x - (scaled_width / 2.0) + pan_offset.x;
-            top = center_y - (scaled_height / 2.0) + pan_offset.y;
+        if image_view.tool == Tool::Crop
+            && let Some(selection) = image_view.crop_selection
+            && let Some(rect) = PixelRect::from_corners(selection.start, selection.end, image_size)
+            && let Some((image_bounds, _)) = image_layers.first()
+        {
+            let player_colors = cx.theme().players().local();
+            layers.push(
+                div()
+                    .absolute()
+                    .left(image_bounds.origin.x + px(rect.x as f32 * zoom_level))
+                    .top(image_bounds.origin.y + px(rect.y as f32 * zoom_level))
+                    .w(px(rect.width as f32 * zoom_level))
+                    .h(px(rect.height as f32 * zoom_level))
+                    .border_1()
+                    .border_color(player_colors.cursor)
+                    .bg(player_colors.selection),
+            );
         }
 
         self.image_view.update(cx, |this, _| {
@@ -382,37 +1043,21 @@ impl Element for ImageContentElement {
             if let Some(initial_zoom_level) = initial_zoom_level {
                 this.zoom_level = initial_zoom_level;
             }
+            this.image_layers = image_layers
+                .into_iter()
+                .map(|(image_bounds, image_size)| {
+                    (
+                        Bounds::new(bounds.origin + image_bounds.origin, image_bounds.size),
+                        image_size,
+                    )
+                })
+                .collect();
         });
 
         let mut image_content = div()
             .relative()
             .size_full()
-            .child(
-                div()
-                    .absolute()
-                    .left(left)
-                    .top(top)
-                    .w(scaled_width)
-                    .h(scaled_height)
-                    .child(
-                        div()
-                            .size_full()
-                            .absolute()
-                            .top_0()
-                            .left_0()
-                            .child(div().size_full().bg(checkerboard(
-                                cx.theme().colors().panel_background,
-                                BASE_SQUARE_SIZE * zoom_level,
-                            )))
-                            .border_1()
-                            .border_color(border_color),
-                    )
-                    .child({
-                        img(image)
-                            .id(("image-viewer-image", self.image_view.entity_id()))
-                            .size_full()
-                    }),
-            )
+            .children(layers)
             .into_any_element();
 
         image_content.prepaint_as_root(bounds.origin, bounds.size.into(), window, cx);
@@ -451,8 +1096,75 @@ impl Element for ImageContentElement {
     }
 }
 
+/// Returns the element drawing an image over a checkerboard, at the given bounds of the image viewer.
+fn image_layer(
+    id: impl Into<ElementId>,
+    image: Arc<gpui::Image>,
+    bounds: Bounds<Pixels>,
+    zoom_level: f32,
+    cx: &App,
+) -> Div {
+    div()
+        .absolute()
+        .left(bounds.origin.x)
+        .top(bounds.origin.y)
+        .w(bounds.size.width)
+        .h(bounds.size.height)
+        .child(
+            div()
+                .size_full()
+                .absolute()
+                .top_0()
+                .left_0()
+                .child(div().size_full().bg(checkerboard(
+                    cx.theme().colors().panel_background,
+                    BASE_SQUARE_SIZE * zoom_level,
+                )))
+                .border_1()
+                .border_color(cx.theme().colors().border),
+        )
+        .child(img(image).id(id).size_full())
+}
+
+fn png_preview(image: &DynamicImage) -> Result<Arc<gpui::Image>> {
+    let bytes = image_edit::encode_image(image, image::ImageFormat::Png)?;
+    Ok(Arc::new(gpui::Image::from_bytes(
+        gpui::ImageFormat::Png,
+        bytes,
+    )))
+}
+
+fn render_difference(
+    image: &DynamicImage,
+    compared_image: &DynamicImage,
+) -> Result<RenderedDifference> {
+    let difference = image_compare::difference(image, compared_image);
+    Ok(RenderedDifference {
+        size: difference.image.dimensions(),
+        differing_pixels: difference.differing_pixels,
+        preview: png_preview(&DynamicImage::ImageRgba8(difference.image))?,
+    })
+}
+
+fn drop_preview(image: Arc<gpui::Image>, window: &mut Window, cx: &mut App) {
+    if let Some(render_image) = image.clone().get_render_image(window, cx) {
+        cx.drop_image(render_image, None);
+    }
+    image.remove_asset(cx);
+}
+
+impl Comparison {
+    fn drop_previews(self, window: &mut Window, cx: &mut App) {
+        drop_preview(self.preview, window, cx);
+        if let Some(difference) = self.difference {
+            drop_preview(difference.preview, window, cx);
+        }
+    }
+}
+
 pub enum ImageViewEvent {
     TitleChanged,
+    DirtyChanged,
 }
 
 impl EventEmitter<ImageViewEvent> for ImageView {}
@@ -466,6 +1178,7 @@ impl Item for ImageView {
                 f(workspace::item::ItemEvent::UpdateTab);
                 f(workspace::item::ItemEvent::UpdateBreadcrumbs);
             }
+            ImageViewEvent::DirtyChanged => f(workspace::item::ItemEvent::UpdateTab),
         }
     }
 
@@ -565,21 +1278,98 @@ impl Item for ImageView {
     where
         Self: Sized,
     {
-        Task::ready(Some(cx.new(|cx| Self {
-            image_item: self.image_item.clone(),
-            project: self.project.clone(),
-            focus_handle: cx.focus_handle(),
-            zoom_level: self.zoom_level,
-            pan_offset: self.pan_offset,
-            last_mouse_position: None,
-            container_bounds: None,
-            image_size: self.image_size,
+        Task::ready(Some(cx.new(|cx| {
+            Self {
+                image_item: self.image_item.clone(),
+                project: self.project.clone(),
+                focus_handle: cx.focus_handle(),
+                zoom_level: self.zoom_level,
+                pan_offset: self.pan_offset,
+                last_mouse_position: None,
+                container_bounds: None,
+                image_size: self
+                    .image_item
+                    .read(cx)
+                    .image_metadata
+                    .map(|m| (m.width, m.height)),
+                decoded_image: self.decoded_image.clone(),
+                edits: Vec::new(),
+                edited_image: None,
+                comparison: None,
+                tool: Tool::default(),
+                inspected_pixel: None,
+                crop_selection: None,
+                image_layers: Vec::new(),
+            }
         })))
     }
 
     fn has_deleted_file(&self, cx: &App) -> bool {
         self.image_item.read(cx).file.disk_state().is_deleted()
     }
+
+    fn is_dirty(&self, _: &App) -> bool {
+        !self.edits.is_empty()
+    }
+
+    fn can_save(&self, _: &App) -> bool {
+        !self.edits.is_empty()
+    }
+
+    fn save(
+        &mut self,
+        _options: SaveOptions,
+        _project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let Some(edited_image) = self
+            .edited_image
+            .as_ref()
+            .map(|edited| edited.image.clone())
+        else {
+            // The edits are still being applied.
+            return Task::ready(Err(anyhow!("The image is still being edited")));
+        };
+        let edits = self.edits.clone();
+        let image_item = self.image_item.read(cx);
+        let original = image_item.image.clone();
+        let project_path = image_item.project_path(cx);
+        let Some(worktree) = self
+            .project
+            .read(cx)
+            .worktree_for_id(project_path.worktree_id, cx)
+        else {
+            return Task::ready(Err(anyhow!("The image's worktree was closed")));
+        };
+
+        cx.spawn_in(window, async move |this, cx| {
+            let bytes = cx
+                .background_spawn({
+                    let edited_image = edited_image.clone();
+                    async move {
+                        let format = image::guess_format(&original.bytes)?;
+                        image_edit::encode_image(&edited_image, format)
+                    }
+                })
+                .await?;
+            worktree
+                .update(cx, |worktree, cx| {
+                    worktree.create_entry(project_path.path, false, Some(bytes), cx)
+                })
+                .await?;
+            this.update(cx, |this, cx| {
+                // Keep showing the edited image until the file is reloaded, and apply
+                // the edits made while saving to the saved image from now on.
+                if this.edits.starts_with(&edits) {
+                    this.edits.drain(..edits.len());
+                    this.decoded_image = Some(edited_image);
+                    cx.emit(ImageViewEvent::DirtyChanged);
+                    cx.notify();
+                }
+            })
+        })
+    }
     fn buffer_kind(&self, _: &App) -> workspace::item::ItemBufferKind {
         workspace::item::ItemBufferKind::Singleton
     }
@@ -691,6 +1481,19 @@ impl Render for ImageView {
             .on_action(cx.listener(Self::fit_to_view))
             .on_action(cx.listener(Self::zoom_to_actual_size))
             .on_action(cx.listener(Self::reveal_in_file_manager))
+            .on_action(cx.listener(Self::compare_with_head))
+            .on_action(cx.listener(Self::compare_with_file))
+            .on_action(cx.listener(Self::cycle_compare_mode))
+            .on_action(cx.listener(Self::stop_comparing))
+            .on_action(cx.listener(Self::toggle_pixel_inspector))
+            .on_action(cx.listener(Self::rotate_clockwise))
+            .on_action(cx.listener(Self::rotate_counterclockwise))
+            .on_action(cx.listener(Self::flip_horizontal))
+            .on_action(cx.listener(Self::flip_vertical))
+            .on_action(cx.listener(Self::toggle_crop))
+            .on_action(cx.listener(Self::apply_crop))
+            .on_action(cx.listener(Self::dismiss_tool))
+            .on_action(cx.listener(Self::discard_edits))
             .size_full()
             .relative()
             .bg(cx.theme().colors().editor_background)
@@ -701,6 +1504,8 @@ impl Render for ImageView {
                     .overflow_hidden()
                     .cursor(if self.is_dragging() {
                         gpui::CursorStyle::ClosedHand
+                    } else if self.tool != Tool::Pan {
+                        gpui::CursorStyle::Crosshair
                     } else {
                         gpui::CursorStyle::OpenHand
                     })
@@ -715,9 +1520,136 @@ impl Render for ImageView {
 
                 container
             })
+            .children(self.render_status(cx))
+    }
+}
+
+impl ImageView {
+    /// Renders what the pixel inspector, the crop selection and the comparison have to tell, in the bottom-left corner.
+    fn render_status(&self, cx: &App) -> Option<impl IntoElement> {
+        let mut lines = Vec::new();
+
+        if let Some(comparison) = &self.comparison {
+            let summary = match (&comparison.difference, comparison.mode) {
+                (None, _) => "computing the difference…".to_string(),
+                (Some(difference), _) if difference.differing_pixels == 0 => {
+                    "identical".to_string()
+                }
+                (Some(difference), CompareMode::OnionSkin) => format!(
+                    "{} pixels differ, {}% opacity",
+                    difference.differing_pixels,
+                    (comparison.onion_skin_opacity * 100.0).round()
+                ),
+                (Some(difference), _) => format!("{} pixels differ", difference.differing_pixels),
+            };
+            lines.push(
+                Label::new(format!(
+                    "{} with {}: {summary}",
+                    comparison.mode.label(),
+                    comparison.label
+                ))
+                .size(LabelSize::Small)
+                .into_any_element(),
+            );
+        }
+
+        match self.tool {
+            Tool::Pan => {}
+            Tool::Crop => {
+                let selection =
+                    self.crop_selection
+                        .zip(self.image_size)
+                        .and_then(|(selection, image_size)| {
+                            PixelRect::from_corners(selection.start, selection.end, image_size)
+                        });
+                let text = match selection {
+                    Some(rect) => format!(
+                        "Crop to {}×{} at {}, {}",
+                        rect.width, rect.height, rect.x, rect.y
+                    ),
+                    None => "Drag over the image to select the region to crop it to".to_string(),
+                };
+                lines.push(Label::new(text).size(LabelSize::Small).into_any_element());
+            }
+            Tool::PixelInspector => {
+                let Some(pixel) = self.inspected_pixel else {
+                    lines.push(
+                        Label::new(
+                            "Hover over the image to inspect its pixels, click to copy a color",
+                        )
+                        .size(LabelSize::Small)
+                        .into_any_element(),
+                    );
+                    return Some(status_panel(lines, cx));
+                };
+                let swatch = |color: image::Rgba<u8>| {
+                    let [r, g, b, a] = color.0;
+                    div()
+                        .size_3()
+                        .border_1()
+                        .border_color(cx.theme().colors().border)
+                        .bg(gpui::Rgba {
+                            r: r as f32 / 255.0,
+                            g: g as f32 / 255.0,
+                            b: b as f32 / 255.0,
+                            a: a as f32 / 255.0,
+                        })
+                };
+                let color_line = |label: String, color: image::Rgba<u8>| {
+                    h_flex()
+                        .gap_1()
+                        .child(swatch(color))
+                        .child(
+                            Label::new(format!(
+                                "{label}{}  {}",
+                                pixel_inspector::hex_color(color),
+                                pixel_inspector::rgba_color(color)
+                            ))
+                            .size(LabelSize::Small)
+                            .buffer_font(cx),
+                        )
+                        .into_any_element()
+                };
+                lines.push(
+                    Label::new(format!("{}, {}", pixel.x, pixel.y))
+                        .size(LabelSize::Small)
+                        .buffer_font(cx)
+                        .into_any_element(),
+                );
+                match (&self.comparison, pixel.compared_color) {
+                    (Some(comparison), compared_color) => {
+                        lines.push(color_line("This image: ".to_string(), pixel.color));
+                        if let Some(compared_color) = compared_color {
+                            lines.push(color_line(
+                                format!("{}: ", comparison.label),
+                                compared_color,
+                            ));
+                        }
+                    }
+                    (None, _) => lines.push(color_line(String::new(), pixel.color)),
+                }
+            }
+        }
+
+        (!lines.is_empty()).then(|| status_panel(lines, cx))
     }
 }
 
+fn status_panel(lines: Vec<AnyElement>, cx: &App) -> Div {
+    v_flex()
+        .absolute()
+        .bottom_2()
+        .left_2()
+        .gap_0p5()
+        .px_2()
+        .py_1()
+        .rounded_sm()
+        .border_1()
+        .border_color(cx.theme().colors().border)
+        .bg(cx.theme().colors().elevated_surface_background)
+        .children(lines)
+}
+
 impl ProjectItem for ImageView {
     type Item = ImageItem;
 
@@ -833,10 +1765,201 @@ impl Render for ImageViewToolbarControls {
                         }
                     }),
             )
+            .child(Divider::vertical().color(DividerColor::Border))
+            .child(self.render_edit_controls(&image_view, cx))
+            .child(Divider::vertical().color(DividerColor::Border))
+            .child(self.render_compare_controls(&image_view, cx))
             .into_any_element()
     }
 }
 
+impl ImageViewToolbarControls {
+    fn render_edit_controls(&self, image_view: &Entity<ImageView>, cx: &App) -> impl IntoElement {
+        let view = image_view.read(cx);
+        let tool = view.tool;
+        let has_edits = !view.edits.is_empty();
+        let has_crop_selection = view.crop_selection.is_some();
+
+        h_flex()
+            .gap_1()
+            .child(
+                IconButton::new("pixel-inspector", IconName::Crosshair)
+                    .icon_size(IconSize::Small)
+                    .toggle_state(tool == Tool::PixelInspector)
+                    .tooltip(|_window, cx| {
+                        Tooltip::for_action("Inspect Pixels", &TogglePixelInspector, cx)
+                    })
+                    .on_click(image_view_listener(image_view, |this, window, cx| {
+                        this.toggle_pixel_inspector(&TogglePixelInspector, window, cx)
+                    })),
+            )
+            .child(
+                IconButton::new("rotate-counterclockwise", IconName::RotateCcw)
+                    .icon_size(IconSize::Small)
+                    .tooltip(|_window, cx| {
+                        Tooltip::for_action("Rotate Counterclockwise", &RotateCounterclockwise, cx)
+                    })
+                    .on_click(image_view_listener(image_view, |this, window, cx| {
+                        this.rotate_counterclockwise(&RotateCounterclockwise, window, cx)
+                    })),
+            )
+            .child(
+                IconButton::new("rotate-clockwise", IconName::RotateCw)
+                    .icon_size(IconSize::Small)
+                    .tooltip(|_window, cx| {
+                        Tooltip::for_action("Rotate Clockwise", &RotateClockwise, cx)
+                    })
+                    .on_click(image_view_listener(image_view, |this, window, cx| {
+                        this.rotate_clockwise(&RotateClockwise, window, cx)
+                    })),
+            )
+            .child(
+                IconButton::new("flip-horizontal", IconName::ArrowRightLeft)
+                    .icon_size(IconSize::Small)
+                    .tooltip(|_window, cx| {
+                        Tooltip::for_action("Flip Horizontally", &FlipHorizontal, cx)
+                    })
+                    .on_click(image_view_listener(image_view, |this, window, cx| {
+                        this.flip_horizontal(&FlipHorizontal, window, cx)
+                    })),
+            )
+            .child(
+                IconButton::new("flip-vertical", IconName::ExpandVertical)
+                    .icon_size(IconSize::Small)
+                    .tooltip(|_window, cx| {
+                        Tooltip::for_action("Flip Vertically", &FlipVertical, cx)
+                    })
+                    .on_click(image_view_listener(image_view, |this, window, cx| {
+                        this.flip_vertical(&FlipVertical, window, cx)
+                    })),
+            )
+            .child(
+                IconButton::new("crop", IconName::Scissors)
+                    .icon_size(IconSize::Small)
+                    .toggle_state(tool == Tool::Crop)
+                    .tooltip(|_window, cx| Tooltip::for_action("Crop", &ToggleCrop, cx))
+                    .on_click(image_view_listener(image_view, |this, window, cx| {
+                        this.toggle_crop(&ToggleCrop, window, cx)
+                    })),
+            )
+            .when(tool == Tool::Crop && has_crop_selection, |this| {
+                this.child(
+                    IconButton::new("apply-crop", IconName::Check)
+                        .icon_size(IconSize::Small)
+                        .tooltip(|_window, cx| Tooltip::for_action("Apply Crop", &ApplyCrop, cx))
+                        .on_click(image_view_listener(image_view, |this, window, cx| {
+                            this.apply_crop(&ApplyCrop, window, cx)
+                        })),
+                )
+            })
+            .when(has_edits, |this| {
+                this.child(
+                    IconButton::new("discard-edits", IconName::Undo)
+                        .icon_size(IconSize::Small)
+                        .tooltip(|_window, cx| {
+                            Tooltip::for_action("Discard Edits", &DiscardEdits, cx)
+                        })
+                        .on_click(image_view_listener(image_view, |this, window, cx| {
+                            this.discard_edits(&DiscardEdits, window, cx)
+                        })),
+                )
+            })
+    }
+
+    fn render_compare_controls(
+        &self,
+        image_view: &Entity<ImageView>,
+        cx: &App,
+    ) -> impl IntoElement {
+        let Some(comparison) = image_view.read(cx).comparison.as_ref() else {
+            let image_view = image_view.downgrade();
+            return PopoverMenu::new("compare-menu")
+                .trigger_with_tooltip(
+                    IconButton::new("compare", IconName::Diff).icon_size(IconSize::Small),
+                    Tooltip::text("Compare With…"),
+                )
+                .menu(move |window, cx| {
+                    let image_view = image_view.clone();
+                    Some(ContextMenu::build(window, cx, move |menu, _, _| {
+                        let compare_with_head = image_view.clone();
+                        let compare_with_file = image_view.clone();
+                        menu.entry(
+                            "Compare with HEAD",
+                            Some(Box::new(CompareWithHead)),
+                            move |window, cx| {
+                                compare_with_head
+                                    .update(cx, |this, cx| {
+                                        this.compare_with_head(&CompareWithHead, window, cx)
+                                    })
+                                    .ok();
+                            },
+                        )
+                        .entry(
+                            "Compare with File…",
+                            Some(Box::new(CompareWithFile)),
+                            move |window, cx| {
+                                compare_with_file
+                                    .update(cx, |this, cx| {
+                                        this.compare_with_file(&CompareWithFile, window, cx)
+                                    })
+                                    .ok();
+                            },
+                        )
+                    }))
+                })
+                .into_any_element();
+        };
+
+        let is_onion_skin = comparison.mode == CompareMode::OnionSkin;
+        let opacity_percentage = format!("{}%", (comparison.onion_skin_opacity * 100.0).round());
+        h_flex()
+            .gap_1()
+            .child(
+                Button::new("compare-mode", comparison.mode.label())
+                    .label_size(LabelSize::Small)
+                    .tooltip(|_window, cx| {
+                        Tooltip::for_action("Switch Compare Mode", &CycleCompareMode, cx)
+                    })
+                    .on_click(image_view_listener(image_view, |this, window, cx| {
+                        this.cycle_compare_mode(&CycleCompareMode, window, cx)
+                    })),
+            )
+            .when(is_onion_skin, |this| {
+                this.child(
+                    Button::new("onion-skin-opacity", opacity_percentage)
+                        .label_size(LabelSize::Small)
+                        .tooltip(Tooltip::text("Opacity of the Compared Image"))
+                        .on_click(image_view_listener(image_view, |this, _window, cx| {
+                            this.cycle_onion_skin_opacity(cx)
+                        })),
+                )
+            })
+            .child(
+                IconButton::new("stop-comparing", IconName::Close)
+                    .icon_size(IconSize::Small)
+                    .tooltip(|_window, cx| {
+                        Tooltip::for_action("Stop Comparing", &StopComparing, cx)
+                    })
+                    .on_click(image_view_listener(image_view, |this, window, cx| {
+                        this.stop_comparing(&StopComparing, window, cx)
+                    })),
+            )
+            .into_any_element()
+    }
+}
+
+fn image_view_listener(
+    image_view: &Entity<ImageView>,
+    f: impl Fn(&mut ImageView, &mut Window, &mut Context<ImageView>) + 'static,
+) -> impl Fn(&gpui::ClickEvent, &mut Window, &mut App) + 'static {
+    let image_view = image_view.downgrade();
+    move |_, window, cx| {
+        if let Some(view) = image_view.upgrade() {
+            view.update(cx, |this, cx| f(this, window, cx));
+        }
+    }
+}
+
 impl EventEmitter<ToolbarItemEvent> for ImageViewToolbarControls {}
 
 impl ToolbarItemView for ImageViewToolbarControls {
@@ -0,0 +1,98 @@
+use image::{DynamicImage, GenericImageView as _, Rgba};
+
+/// The pixel under the mouse, as shown by the pixel inspector.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InspectedPixel {
+    pub x: u32,
+    pub y: u32,
+    pub color: Rgba<u8>,
+    /// The color of the same pixel in the image the view is compared with, if it has one.
+    pub compared_color: Option<Rgba<u8>>,
+}
+
+impl InspectedPixel {
+    pub fn new(
+        image: &DynamicImage,
+        compared_image: Option<&DynamicImage>,
+        (x, y): (u32, u32),
+    ) -> Option<Self> {
+        if !image.in_bounds(x, y) {
+            return None;
+        }
+        Some(Self {
+            x,
+            y,
+            color: image.get_pixel(x, y),
+            compared_color: compared_image
+                .filter(|compared| compared.in_bounds(x, y))
+                .map(|compared| compared.get_pixel(x, y)),
+        })
+    }
+}
+
+/// Returns the pixel of an image drawn at `zoom_level`, at `offset` from its top-left corner.
+pub fn pixel_at(offset: (f32, f32), zoom_level: f32, image_size: (u32, u32)) -> Option<(u32, u32)> {
+    let x = (offset.0 / zoom_level).floor();
+    let y = (offset.1 / zoom_level).floor();
+    if x < 0.0 || y < 0.0 || x >= image_size.0 as f32 || y >= image_size.1 as f32 {
+        return None;
+    }
+    Some((x as u32, y as u32))
+}
+
+/// Formats a color as `#rrggbb`, or `#rrggbbaa` when it isn't opaque.
+pub fn hex_color(Rgba([r, g, b, a]): Rgba<u8>) -> String {
+    if a == u8::MAX {
+        format!("#{r:02x}{g:02x}{b:02x}")
+    } else {
+        format!("#{r:02x}{g:02x}{b:02x}{a:02x}")
+    }
+}
+
+/// Formats a color the way CSS's `rgba()` takes it.
+pub fn rgba_color(Rgba([r, g, b, a]): Rgba<u8>) -> String {
+    let alpha = format!("{:.2}", a as f32 / u8::MAX as f32);
+    let alpha = alpha.trim_end_matches('0').trim_end_matches('.');
+    format!("rgba({r}, {g}, {b}, {alpha})")
+}
+
+#[cfg(test)]
+mod tests {
+    use image::RgbaImage;
+
+    use super::*;
+
+    #[test]
+    fn test_pixel_at() {
+        assert_eq!(pixel_at((0.0, 0.0), 1.0, (10, 10)), Some((0, 0)));
+        assert_eq!(pixel_at((39.9, 8.0), 4.0, (10, 10)), Some((9, 2)));
+        assert_eq!(pixel_at((40.0, 8.0), 4.0, (10, 10)), None);
+        assert_eq!(pixel_at((-0.5, 8.0), 4.0, (10, 10)), None);
+        assert_eq!(pixel_at((15.0, 5.0), 0.5, (10, 10)), None);
+        assert_eq!(pixel_at((4.0, 3.0), 0.5, (10, 10)), Some((8, 6)));
+    }
+
+    #[test]
+    fn test_color_formatting() {
+        assert_eq!(hex_color(Rgba([255, 128, 0, 255])), "#ff8000");
+        assert_eq!(hex_color(Rgba([1, 2, 3, 0])), "#01020300");
+        assert_eq!(rgba_color(Rgba([255, 128, 0, 255])), "rgba(255, 128, 0, 1)");
+        assert_eq!(rgba_color(Rgba([1, 2, 3, 128])), "rgba(1, 2, 3, 0.5)");
+        assert_eq!(rgba_color(Rgba([1, 2, 3, 0])), "rgba(1, 2, 3, 0)");
+    }
+
+    #[test]
+    fn test_inspected_pixel() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(2, 2, Rgba([1, 2, 3, 255])));
+        let compared = DynamicImage::ImageRgba8(RgbaImage::from_pixel(1, 1, Rgba([4, 5, 6, 255])));
+
+        let pixel = InspectedPixel::new(&image, Some(&compared), (0, 0)).unwrap();
+        assert_eq!(pixel.color, Rgba([1, 2, 3, 255]));
+        assert_eq!(pixel.compared_color, Some(Rgba([4, 5, 6, 255])));
+
+        let pixel = InspectedPixel::new(&image, Some(&compared), (1, 1)).unwrap();
+        assert_eq!(pixel.compared_color, None);
+
+        assert_eq!(InspectedPixel::new(&image, None, (2, 0)), None);
+    }
+}
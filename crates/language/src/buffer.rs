@@ -61,8 +61,9 @@ use text::*;
 pub use text::{
     Anchor, Bias, Buffer as TextBuffer, BufferId, BufferSnapshot as TextBufferSnapshot, Edit,
     LineIndent, OffsetRangeExt, OffsetUtf16, Patch, Point, PointUtf16, Rope, Selection,
-    SelectionGoal, Subscription, TextDimension, TextSummary, ToOffset, ToOffsetUtf16, ToPoint,
-    ToPointUtf16, Transaction, TransactionId, Unclipped,
+    SelectionGoal, SerializedUndoHistory, Subscription, TextDimension, TextSummary, ToOffset,
+    ToOffsetUtf16, ToPoint, ToPointUtf16, Transaction, TransactionId, Unclipped, UndoTreeNode,
+    UndoTreeTarget, UndoTreeTravel,
 };
 use theme::{ActiveTheme as _, SyntaxTheme};
 #[cfg(any(test, feature = "test-support"))]
@@ -3255,6 +3256,55 @@ impl Buffer {
         }
     }
 
+    /// Moves to a different state in the buffer's undo tree, undoing and
+    /// redoing transactions as needed.
+    pub fn travel_undo_tree(
+        &mut self,
+        target: UndoTreeTarget,
+        cx: &mut Context<Self>,
+    ) -> Option<UndoTreeTravel> {
+        let was_dirty = self.is_dirty();
+        let old_version = self.version.clone();
+
+        let (travel, operations) = self.text.travel_undo_tree(target)?;
+        let operations_is_empty = operations.is_empty();
+        for operation in operations {
+            self.send_operation(Operation::Buffer(operation), true, cx);
+        }
+        if !operations_is_empty {
+            self.did_edit(&old_version, was_dirty, BufferEditSource::User, cx);
+        }
+        Some(travel)
+    }
+
+    /// Replays a persisted undo history onto a buffer that hasn't been edited
+    /// since it was loaded. Returns whether the history matched the buffer's
+    /// text and was restored.
+    ///
+    /// The replayed operations aren't broadcast, so this must only be called
+    /// before the buffer is shared. Collaborators who join later receive them
+    /// along with the rest of the buffer's state.
+    pub fn restore_undo_history(
+        &mut self,
+        history: &SerializedUndoHistory,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if self.is_dirty() {
+            return false;
+        }
+        let old_version = self.version.clone();
+        if self.text.restore_undo_history(history).is_none() {
+            return false;
+        }
+        self.was_changed();
+
+        // Replaying the history leaves the text as it was on disk.
+        self.saved_version = self.version.clone();
+        self.has_unsaved_edits.set((self.version.clone(), false));
+        self.did_edit(&old_version, false, BufferEditSource::User, cx);
+        true
+    }
+
     fn restore_encoding_for_transaction(&mut self, transaction_id: TransactionId, was_dirty: bool) {
         if let Some((old_encoding, old_has_bom)) =
             self.reload_with_encoding_txns.get(&transaction_id)
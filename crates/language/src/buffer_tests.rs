@@ -512,6 +512,40 @@ fn test_edit_events(cx: &mut gpui::App) {
     );
 }
 
+#[gpui::test]
+fn test_restore_undo_history_is_not_broadcast(cx: &mut gpui::App) {
+    let buffer = cx.new(|cx| {
+        let mut buffer = Buffer::local("abc", cx);
+        buffer.edit([(3..3, "d")], None, cx);
+        buffer
+    });
+    let history = buffer.read(cx).serialize_undo_history().unwrap();
+
+    let restored = cx.new(|cx| Buffer::local("abcd", cx));
+    let operations = Arc::new(Mutex::new(Vec::new()));
+    cx.subscribe(&restored, {
+        let operations = operations.clone();
+        move |_, event, _| {
+            if let BufferEvent::Operation { operation, .. } = event {
+                operations.lock().push(operation.clone());
+            }
+        }
+    })
+    .detach();
+    restored.update(cx, |buffer, cx| {
+        assert!(buffer.restore_undo_history(&history, cx));
+        assert!(!buffer.is_dirty());
+    });
+    assert!(operations.lock().is_empty());
+
+    // Undoing the restored history is broadcast like any other edit.
+    restored.update(cx, |buffer, cx| {
+        buffer.undo(cx);
+        assert_eq!(buffer.text(), "abc");
+    });
+    assert_eq!(operations.lock().len(), 1);
+}
+
 #[gpui::test]
 async fn test_apply_diff(cx: &mut TestAppContext) {
     let (text, offsets) = marked_text_offsets(
@@ -0,0 +1,333 @@
+//! Local history keeps a snapshot of each file every time it is saved, so that
+//! earlier versions can be compared and restored independently of git.
+
+mod timeline;
+
+use std::{
+    cmp::Reverse,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Result;
+use fs::{Fs, RemoveOptions};
+use futures::StreamExt as _;
+use gpui::{App, AppContext as _, Context, Entity, actions};
+use language::{Buffer, BufferEvent};
+use project::buffer_store::BufferStoreEvent;
+use settings::{RegisterSetting, Settings};
+use sha2::{Digest as _, Sha256};
+use util::{ResultExt as _, rel_path::RelPath};
+use workspace::Workspace;
+
+actions!(
+    local_history,
+    [
+        /// Shows the snapshots of the active file in the local history.
+        ShowTimeline,
+    ]
+);
+
+#[derive(Clone, Copy, Debug, PartialEq, RegisterSetting)]
+pub struct LocalHistorySettings {
+    pub enabled: bool,
+    pub max_file_size: u64,
+    pub max_entries: usize,
+    pub max_age: Duration,
+}
+
+impl Settings for LocalHistorySettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let local_history = content.local_history.as_ref().unwrap();
+        Self {
+            enabled: local_history.enabled.unwrap(),
+            max_file_size: local_history.max_file_size_kb.unwrap() * 1024,
+            max_entries: local_history.max_entries.unwrap(),
+            max_age: Duration::from_secs(local_history.max_age_days.unwrap() * 24 * 60 * 60),
+        }
+    }
+}
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, cx| {
+        workspace.register_action(timeline::toggle);
+
+        let project = workspace.project().read(cx);
+        if !project.is_local() {
+            return;
+        }
+        let store = LocalHistoryStore::new(
+            workspace.app_state().fs.clone(),
+            paths::local_history_dir().clone(),
+        );
+        let buffer_store = project.buffer_store().clone();
+        let buffers = buffer_store.read(cx).buffers().collect::<Vec<_>>();
+        for buffer in buffers {
+            track_buffer(&buffer, store.clone(), cx);
+        }
+        cx.subscribe(&buffer_store, move |_, _, event, cx| {
+            if let BufferStoreEvent::BufferAdded(buffer) = event {
+                track_buffer(buffer, store.clone(), cx);
+            }
+        })
+        .detach();
+    })
+    .detach();
+}
+
+fn track_buffer(buffer: &Entity<Buffer>, store: LocalHistoryStore, cx: &mut Context<Workspace>) {
+    cx.subscribe(buffer, move |_, buffer, event, cx| {
+        if let BufferEvent::Saved = event {
+            snapshot_buffer(&buffer, &store, cx);
+        }
+    })
+    .detach();
+}
+
+fn snapshot_buffer(buffer: &Entity<Buffer>, store: &LocalHistoryStore, cx: &mut App) {
+    let settings = *LocalHistorySettings::get_global(cx);
+    if !settings.enabled {
+        return;
+    }
+    let buffer = buffer.read(cx);
+    let Some(file) = project::File::from_dyn(buffer.file()).filter(|file| file.is_local) else {
+        return;
+    };
+    if buffer.len() as u64 > settings.max_file_size {
+        return;
+    }
+
+    let worktree_abs_path = file.worktree.read(cx).abs_path();
+    let path = file.path.clone();
+    let text = buffer.as_rope().clone();
+    let store = store.clone();
+    cx.background_spawn(async move {
+        store
+            .snapshot(
+                &worktree_abs_path,
+                &path,
+                text.to_string(),
+                SystemTime::now(),
+                &settings,
+            )
+            .await
+            .log_err();
+    })
+    .detach();
+}
+
+/// A saved version of a file in the local history.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LocalHistoryEntry {
+    /// Where the snapshot's contents are stored.
+    pub abs_path: PathBuf,
+    pub timestamp: SystemTime,
+}
+
+/// Stores snapshots of files under a root directory, grouped by worktree and
+/// by the file's path within the worktree.
+#[derive(Clone)]
+pub struct LocalHistoryStore {
+    fs: Arc<dyn Fs>,
+    root: PathBuf,
+}
+
+impl LocalHistoryStore {
+    pub fn new(fs: Arc<dyn Fs>, root: PathBuf) -> Self {
+        Self { fs, root }
+    }
+
+    fn entries_dir(&self, worktree_abs_path: &Path, path: &RelPath) -> PathBuf {
+        let mut hasher = Sha256::new();
+        hasher.update(worktree_abs_path.to_string_lossy().as_bytes());
+        let hash = format!("{:x}", hasher.finalize());
+        let worktree_name = worktree_abs_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        self.root
+            .join(format!("{worktree_name}-{}", &hash[..16]))
+            .join(path.as_std_path())
+    }
+
+    /// Returns the snapshots of the given file, newest first.
+    pub async fn entries(
+        &self,
+        worktree_abs_path: &Path,
+        path: &RelPath,
+    ) -> Result<Vec<LocalHistoryEntry>> {
+        let dir = self.entries_dir(worktree_abs_path, path);
+        if !self.fs.is_dir(&dir).await {
+            return Ok(Vec::new());
+        }
+
+        let mut entries = Vec::new();
+        let mut paths = self.fs.read_dir(&dir).await?;
+        while let Some(abs_path) = paths.next().await {
+            let abs_path = abs_path?;
+            if let Some(timestamp) = parse_timestamp(&abs_path) {
+                entries.push(LocalHistoryEntry {
+                    abs_path,
+                    timestamp,
+                });
+            }
+        }
+        entries.sort_by_key(|entry| Reverse(entry.timestamp));
+        Ok(entries)
+    }
+
+    /// Loads the contents of the given snapshot.
+    pub async fn load(&self, entry: &LocalHistoryEntry) -> Result<String> {
+        self.fs.load(&entry.abs_path).await
+    }
+
+    /// Records a new snapshot of the given file unless its contents match the
+    /// latest one, and prunes the snapshots that exceed the configured limits.
+    pub async fn snapshot(
+        &self,
+        worktree_abs_path: &Path,
+        path: &RelPath,
+        content: String,
+        now: SystemTime,
+        settings: &LocalHistorySettings,
+    ) -> Result<Option<LocalHistoryEntry>> {
+        let mut entries = self.entries(worktree_abs_path, path).await?;
+        let latest_content = match entries.first() {
+            Some(latest) => self.fs.load(&latest.abs_path).await.ok(),
+            None => None,
+        };
+        if latest_content.as_ref() == Some(&content) {
+            self.prune(&entries, now, settings).await?;
+            return Ok(None);
+        }
+
+        let dir = self.entries_dir(worktree_abs_path, path);
+        self.fs.create_dir(&dir).await?;
+        let millis = now.duration_since(UNIX_EPOCH)?.as_millis();
+        let file_name = path.file_name().unwrap_or("snapshot");
+        let entry = LocalHistoryEntry {
+            abs_path: dir.join(format!("{millis}-{file_name}")),
+            timestamp: UNIX_EPOCH + Duration::from_millis(millis as u64),
+        };
+        self.fs.write(&entry.abs_path, content.as_bytes()).await?;
+
+        entries.insert(0, entry.clone());
+        self.prune(&entries, now, settings).await?;
+        Ok(Some(entry))
+    }
+
+    async fn prune(
+        &self,
+        entries: &[LocalHistoryEntry],
+        now: SystemTime,
+        settings: &LocalHistorySettings,
+    ) -> Result<()> {
+        for (ix, entry) in entries.iter().enumerate() {
+            let is_expired = now
+                .duration_since(entry.timestamp)
+                .is_ok_and(|age| age > settings.max_age);
+            if ix >= settings.max_entries.max(1) || is_expired {
+                self.fs
+                    .remove_file(
+                        &entry.abs_path,
+                        RemoveOptions {
+                            ignore_if_not_exists: true,
+                            ..Default::default()
+                        },
+                    )
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_timestamp(abs_path: &Path) -> Option<SystemTime> {
+    let file_name = abs_path.file_name()?.to_str()?;
+    let (millis, _) = file_name.split_once('-')?;
+    let millis = millis.parse().ok()?;
+    Some(UNIX_EPOCH + Duration::from_millis(millis))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use util::{path, rel_path::rel_path};
+
+    #[gpui::test]
+    async fn test_local_history_store(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        let store = LocalHistoryStore::new(fs.clone(), PathBuf::from(path!("/history")));
+        let worktree = Path::new(path!("/project"));
+        let file = rel_path("src/main.rs");
+        let settings = LocalHistorySettings {
+            enabled: true,
+            max_file_size: 1024,
+            max_entries: 2,
+            max_age: Duration::from_secs(60 * 60),
+        };
+        let start = UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let at = |seconds: u64| start + Duration::from_secs(seconds);
+
+        assert!(store.entries(worktree, file).await.unwrap().is_empty());
+
+        let first = store
+            .snapshot(worktree, file, "one".into(), at(0), &settings)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(first.abs_path.ends_with("1000000000-main.rs"));
+        assert_eq!(fs.load(&first.abs_path).await.unwrap(), "one");
+
+        // Saving the same contents again doesn't create another snapshot.
+        assert_eq!(
+            store
+                .snapshot(worktree, file, "one".into(), at(1), &settings)
+                .await
+                .unwrap(),
+            None
+        );
+
+        let second = store
+            .snapshot(worktree, file, "two".into(), at(2), &settings)
+            .await
+            .unwrap()
+            .unwrap();
+        let third = store
+            .snapshot(worktree, file, "three".into(), at(3), &settings)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            store.entries(worktree, file).await.unwrap(),
+            [third.clone(), second]
+        );
+
+        // Other files and worktrees have their own history.
+        assert!(
+            store
+                .entries(worktree, rel_path("src/lib.rs"))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            store
+                .entries(Path::new(path!("/other")), file)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        // Snapshots older than the maximum age are pruned.
+        let fourth = store
+            .snapshot(worktree, file, "four".into(), at(60 * 60 + 4), &settings)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(store.entries(worktree, file).await.unwrap(), [fourth]);
+    }
+}
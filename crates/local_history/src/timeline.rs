@@ -0,0 +1,363 @@
+use std::sync::Arc;
+
+use editor::Editor;
+use git_ui::file_diff_view::FileDiffView;
+use gpui::{
+    AnyElement, App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
+    IntoElement, ParentElement, Render, SharedString, Styled, Subscription, Task, TaskExt,
+    WeakEntity, Window, rems,
+};
+use language::{Buffer, Capability};
+use picker::{Picker, PickerDelegate};
+use time::{OffsetDateTime, UtcOffset};
+use ui::{KeyBinding, ListItem, ListItemSpacing, prelude::*};
+use workspace::{ModalView, Workspace, notifications::DetachAndPromptErr};
+
+use crate::{LocalHistoryEntry, LocalHistoryStore, ShowTimeline};
+
+pub(crate) fn toggle(
+    workspace: &mut Workspace,
+    _: &ShowTimeline,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+        return;
+    };
+    let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() else {
+        return;
+    };
+    let Some(file) = project::File::from_dyn(buffer.read(cx).file())
+        .filter(|file| file.is_local)
+        .cloned()
+    else {
+        return;
+    };
+
+    let store = LocalHistoryStore::new(
+        workspace.app_state().fs.clone(),
+        paths::local_history_dir().clone(),
+    );
+    let workspace_handle = workspace.weak_handle();
+    workspace.toggle_modal(window, cx, |window, cx| {
+        Timeline::new(store, file, buffer, workspace_handle, window, cx)
+    });
+}
+
+/// A modal listing the local history snapshots of a file, which can be
+/// compared against the file's current contents or restored.
+pub struct Timeline {
+    picker: Entity<Picker<TimelineDelegate>>,
+    _subscription: Subscription,
+}
+
+impl Timeline {
+    fn new(
+        store: LocalHistoryStore,
+        file: project::File,
+        buffer: Entity<Buffer>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let worktree_abs_path = file.worktree.read(cx).abs_path();
+        let delegate = TimelineDelegate {
+            store: store.clone(),
+            workspace,
+            buffer,
+            entries: None,
+            matches: Vec::new(),
+            selected_index: 0,
+            timezone: UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC),
+        };
+        let picker =
+            cx.new(|cx| Picker::uniform_list(delegate, window, cx).initial_width(rems(34.)));
+        let subscription = cx.subscribe(&picker, |_, _, _, cx| cx.emit(DismissEvent));
+
+        let picker_handle = picker.downgrade();
+        cx.spawn_in(window, async move |_, cx| {
+            let entries = store.entries(&worktree_abs_path, &file.path).await?;
+            picker_handle.update_in(cx, |picker, window, cx| {
+                picker.delegate.entries = Some(entries);
+                picker.refresh(window, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+
+        Self {
+            picker,
+            _subscription: subscription,
+        }
+    }
+}
+
+impl ModalView for Timeline {}
+
+impl EventEmitter<DismissEvent> for Timeline {}
+
+impl Focusable for Timeline {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for Timeline {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("LocalHistoryTimeline")
+            .w(rems(34.))
+            .child(self.picker.clone())
+    }
+}
+
+struct TimelineMatch {
+    entry: LocalHistoryEntry,
+    relative_timestamp: SharedString,
+    absolute_timestamp: SharedString,
+}
+
+pub struct TimelineDelegate {
+    store: LocalHistoryStore,
+    workspace: WeakEntity<Workspace>,
+    buffer: Entity<Buffer>,
+    entries: Option<Vec<LocalHistoryEntry>>,
+    matches: Vec<TimelineMatch>,
+    selected_index: usize,
+    timezone: UtcOffset,
+}
+
+impl TimelineDelegate {
+    fn format_timestamp(
+        entry: &LocalHistoryEntry,
+        timezone: UtcOffset,
+        format: time_format::TimestampFormat,
+    ) -> SharedString {
+        time_format::format_localized_timestamp(
+            OffsetDateTime::from(entry.timestamp),
+            OffsetDateTime::now_utc(),
+            timezone,
+            format,
+        )
+        .into()
+    }
+
+    fn compare(&self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(entry_match) = self.matches.get(ix) else {
+            return;
+        };
+        let entry = entry_match.entry.clone();
+        let store = self.store.clone();
+        let buffer = self.buffer.clone();
+        let workspace = self.workspace.clone();
+        cx.spawn_in(window, async move |_, cx| {
+            let text = store.load(&entry).await?;
+            // The snapshot is kept out of the buffer store, so that it isn't
+            // opened as a file of the project.
+            let snapshot_buffer = cx.update(|_, cx| {
+                let language = buffer.read(cx).language().cloned();
+                cx.new(|cx| {
+                    let mut snapshot_buffer = Buffer::local(text, cx);
+                    snapshot_buffer.set_language(language, cx);
+                    snapshot_buffer.set_capability(Capability::ReadOnly, cx);
+                    snapshot_buffer
+                })
+            })?;
+            cx.update(|window, cx| {
+                FileDiffView::open_buffers(snapshot_buffer, buffer, workspace, window, cx)
+            })?
+            .await
+        })
+        .detach_and_prompt_err("Failed to open snapshot", window, cx, |error, _, _| {
+            Some(error.to_string())
+        });
+        cx.emit(DismissEvent);
+    }
+
+    fn restore(&self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(entry_match) = self.matches.get(ix) else {
+            return;
+        };
+        let entry = entry_match.entry.clone();
+        let store = self.store.clone();
+        let buffer = self.buffer.clone();
+        cx.spawn_in(window, async move |_, cx| {
+            let text = store.load(&entry).await?;
+            let diff = buffer.update(cx, |buffer, cx| buffer.diff(text, cx)).await;
+            buffer.update(cx, |buffer, cx| {
+                buffer.finalize_last_transaction();
+                buffer.apply_diff(diff, cx);
+                buffer.finalize_last_transaction();
+            });
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to restore snapshot", window, cx, |error, _, _| {
+            Some(error.to_string())
+        });
+        cx.emit(DismissEvent);
+    }
+}
+
+impl PickerDelegate for TimelineDelegate {
+    type ListItem = ListItem;
+
+    fn name() -> &'static str {
+        "local history timeline"
+    }
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Filter snapshots…".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let query = query.to_lowercase();
+        self.matches = self
+            .entries
+            .iter()
+            .flatten()
+            .map(|entry| TimelineMatch {
+                entry: entry.clone(),
+                relative_timestamp: Self::format_timestamp(
+                    entry,
+                    self.timezone,
+                    time_format::TimestampFormat::Relative,
+                ),
+                absolute_timestamp: Self::format_timestamp(
+                    entry,
+                    self.timezone,
+                    time_format::TimestampFormat::EnhancedAbsolute,
+                ),
+            })
+            .filter(|entry_match| {
+                query.is_empty()
+                    || entry_match
+                        .relative_timestamp
+                        .to_lowercase()
+                        .contains(&query)
+                    || entry_match
+                        .absolute_timestamp
+                        .to_lowercase()
+                        .contains(&query)
+            })
+            .collect();
+        self.selected_index = self
+            .selected_index
+            .min(self.matches.len().saturating_sub(1));
+        Task::ready(())
+    }
+
+    fn confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        if secondary {
+            self.restore(self.selected_index, window, cx);
+        } else {
+            self.compare(self.selected_index, window, cx);
+        }
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let entry_match = self.matches.get(ix)?;
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .start_slot(
+                    Icon::new(IconName::HistoryRerun)
+                        .size(IconSize::Small)
+                        .color(Color::Muted),
+                )
+                .child(
+                    h_flex()
+                        .w_full()
+                        .gap_2()
+                        .justify_between()
+                        .child(Label::new(entry_match.relative_timestamp.clone()))
+                        .child(
+                            Label::new(entry_match.absolute_timestamp.clone())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                ),
+        )
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some(match self.entries {
+            Some(_) => "No local history for this file".into(),
+            None => "Loading snapshots…".into(),
+        })
+    }
+
+    fn render_footer(
+        &self,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<AnyElement> {
+        if self.matches.is_empty() {
+            return None;
+        }
+
+        let focus_handle = self.focus_handle(window, cx);
+        Some(
+            h_flex()
+                .w_full()
+                .p_1p5()
+                .gap_0p5()
+                .justify_end()
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .child(
+                    Button::new("restore-snapshot", "Restore")
+                        .key_binding(
+                            KeyBinding::for_action_in(&menu::SecondaryConfirm, &focus_handle, cx)
+                                .map(|kb| kb.size(rems_from_px(12.))),
+                        )
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(menu::SecondaryConfirm.boxed_clone(), cx)
+                        }),
+                )
+                .child(
+                    Button::new("compare-snapshot", "Compare")
+                        .key_binding(
+                            KeyBinding::for_action_in(&menu::Confirm, &focus_handle, cx)
+                                .map(|kb| kb.size(rems_from_px(12.))),
+                        )
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(menu::Confirm.boxed_clone(), cx)
+                        }),
+                )
+                .into_any(),
+        )
+    }
+}
@@ -14,6 +14,10 @@ use log::Level;
 use mermaid::{
     MermaidState, ParsedMarkdownMermaidDiagram, extract_mermaid_diagrams, render_mermaid_diagram,
 };
+pub use mermaid::{
+    MermaidExportFormat, MermaidExportTheme, copy_mermaid_diagram, export_mermaid_diagram,
+    render_mermaid_diagram_for_export,
+};
 pub use path_range::{LineCol, PathWithRange};
 use settings::Settings as _;
 use smallvec::SmallVec;
@@ -395,6 +399,7 @@ pub struct Markdown {
     mermaid_state: MermaidState,
     _mermaid_theme_subscription: Option<Subscription>,
     mermaid_showing_code: HashSet<usize>,
+    mermaid_export_theme: MermaidExportTheme,
     copied_code_blocks: HashSet<ElementId>,
     wrapped_code_blocks: HashSet<usize>,
     code_block_scroll_handles: BTreeMap<usize, ScrollHandle>,
@@ -588,6 +593,7 @@ impl Markdown {
             mermaid_state: MermaidState::default(),
             _mermaid_theme_subscription: theme_subscription,
             mermaid_showing_code: HashSet::default(),
+            mermaid_export_theme: MermaidExportTheme::default(),
             copied_code_blocks: HashSet::default(),
             wrapped_code_blocks: HashSet::default(),
             code_block_scroll_handles: BTreeMap::default(),
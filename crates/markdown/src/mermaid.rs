@@ -1,14 +1,15 @@
+use anyhow::Context as _;
 use collections::HashMap;
 use gpui::{
-    Animation, AnimationExt, AnyElement, ClipboardItem, Context, Entity, ImageSource, RenderImage,
-    StyledText, Task, img, pulsating_between,
+    Anchor, Animation, AnimationExt, AnyElement, ClipboardItem, Context, Entity, Image,
+    ImageFormat, ImageSource, RenderImage, StyledText, Task, img, point, pulsating_between,
 };
 use std::collections::BTreeMap;
 use std::ops::Range;
 use std::path::Path;
 use std::sync::{Arc, OnceLock};
 use std::time::Duration;
-use ui::{CopyButton, TintColor, prelude::*};
+use ui::{ContextMenu, CopyButton, PopoverMenu, TintColor, prelude::*};
 
 use crate::parser::{CodeBlockKind, MarkdownEvent, MarkdownTag};
 use settings::Settings as _;
@@ -18,6 +19,45 @@ use super::{CopyButtonVisibility, Markdown, MarkdownStyle, ParsedMarkdown};
 
 type MermaidDiagramCache = HashMap<ParsedMarkdownMermaidDiagramContents, Arc<CachedMermaidDiagram>>;
 
+/// Diagrams are rasterized at twice their size when copied or exported as PNG, so they stay sharp on
+/// high density displays.
+const PNG_EXPORT_SCALE: f32 = 2.0;
+
+/// The theme of Mermaid diagrams that are copied or exported out of Zed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MermaidExportTheme {
+    /// The current Zed theme, as the diagram is displayed.
+    #[default]
+    Current,
+    /// A light theme that doesn't depend on the Zed theme, for documentation.
+    NeutralLight,
+}
+
+impl MermaidExportTheme {
+    pub fn mermaid_theme(self, cx: &App) -> mermaid_render::MermaidTheme {
+        match self {
+            Self::Current => build_mermaid_theme(cx),
+            Self::NeutralLight => mermaid_render::MermaidTheme::neutral_light(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MermaidExportFormat {
+    Svg,
+    Png,
+}
+
+impl MermaidExportFormat {
+    /// Picks the format from the extension of the path, defaulting to SVG.
+    pub fn for_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("png") => Self::Png,
+            _ => Self::Svg,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct ParsedMarkdownMermaidDiagram {
     pub(crate) content_range: Range<usize>,
@@ -167,7 +207,7 @@ fn mermaid_font_family(font_family: &str) -> String {
     }
 }
 
-fn build_mermaid_theme(cx: &Context<Markdown>) -> mermaid_render::MermaidTheme {
+fn build_mermaid_theme(cx: &App) -> mermaid_render::MermaidTheme {
     let colors = cx.theme().colors();
     let theme_settings = ThemeSettings::get_global(cx);
     let is_dark = !cx.theme().appearance.is_light();
@@ -213,6 +253,70 @@ fn build_mermaid_theme(cx: &Context<Markdown>) -> mermaid_render::MermaidTheme {
     }
 }
 
+/// Renders the diagram with the theme background painted in, so it looks the same outside of Zed.
+pub fn render_mermaid_diagram_for_export(
+    source: SharedString,
+    format: MermaidExportFormat,
+    theme: MermaidExportTheme,
+    cx: &App,
+) -> Task<anyhow::Result<Vec<u8>>> {
+    let mermaid_theme = theme.mermaid_theme(cx);
+    let svg_renderer = cx.svg_renderer();
+    cx.background_spawn(async move {
+        let svg = mermaid_render::render_to_standalone_svg(&source, &mermaid_theme)?;
+        match format {
+            MermaidExportFormat::Svg => Ok(svg.into_bytes()),
+            MermaidExportFormat::Png => svg_renderer.render_png(svg.as_bytes(), PNG_EXPORT_SCALE),
+        }
+    })
+}
+
+/// Copies the diagram to the clipboard, as SVG markup or as a PNG image.
+pub fn copy_mermaid_diagram(
+    source: SharedString,
+    format: MermaidExportFormat,
+    theme: MermaidExportTheme,
+    cx: &mut App,
+) -> Task<anyhow::Result<()>> {
+    let render = render_mermaid_diagram_for_export(source, format, theme, cx);
+    cx.spawn(async move |cx| {
+        let bytes = render.await?;
+        let item = match format {
+            MermaidExportFormat::Svg => ClipboardItem::new_string(String::from_utf8(bytes)?),
+            MermaidExportFormat::Png => {
+                ClipboardItem::new_image(&Image::from_bytes(ImageFormat::Png, bytes))
+            }
+        };
+        cx.update(|cx| cx.write_to_clipboard(item));
+        Ok(())
+    })
+}
+
+/// Prompts for a path and saves the diagram there, as PNG for `.png` paths and as SVG otherwise.
+pub fn export_mermaid_diagram(
+    source: SharedString,
+    theme: MermaidExportTheme,
+    directory: &Path,
+    suggested_name: &str,
+    cx: &mut App,
+) -> Task<anyhow::Result<()>> {
+    let save_dialog = cx.prompt_for_new_path(directory, Some(suggested_name));
+    cx.spawn(async move |cx| {
+        let Some(path) = save_dialog.await?? else {
+            return Ok(());
+        };
+        let format = MermaidExportFormat::for_path(&path);
+        let bytes = cx
+            .update(|cx| render_mermaid_diagram_for_export(source, format, theme, cx))
+            .await?;
+        cx.background_spawn(async move {
+            std::fs::write(&path, bytes)
+                .with_context(|| format!("Failed to write diagram to {}", path.display()))
+        })
+        .await
+    })
+}
+
 fn parse_mermaid_info(info: &str) -> Option<u32> {
     let mut parts = info.split_whitespace();
     if parts.next()? != "mermaid" {
@@ -355,11 +459,7 @@ pub(crate) fn render_mermaid_diagram(
                 })
                 .child(body)
                 .when(show_interactive, |container| {
-                    container.child(render_mermaid_copy_button(
-                        source_offset,
-                        code.to_string(),
-                        markdown,
-                    ))
+                    container.child(render_mermaid_buttons(source_offset, code, markdown, true))
                 })
                 .into_any_element()
         }
@@ -368,11 +468,7 @@ pub(crate) fn render_mermaid_diagram(
             container
                 .child(render_mermaid_code_view(&parsed.contents.contents))
                 .when(show_interactive, |container| {
-                    container.child(render_mermaid_copy_button(
-                        source_offset,
-                        code.to_string(),
-                        markdown,
-                    ))
+                    container.child(render_mermaid_buttons(source_offset, code, markdown, false))
                 })
                 .into_any_element()
         }
@@ -401,10 +497,11 @@ pub(crate) fn render_mermaid_diagram(
                             ),
                     )
                     .when(show_interactive, |container| {
-                        container.child(render_mermaid_copy_button(
+                        container.child(render_mermaid_buttons(
                             source_offset,
-                            code.to_string(),
+                            code,
                             markdown,
+                            false,
                         ))
                     })
                     .into_any_element()
@@ -427,10 +524,11 @@ pub(crate) fn render_mermaid_diagram(
                         ),
                     )
                     .when(show_interactive, |container| {
-                        container.child(render_mermaid_copy_button(
+                        container.child(render_mermaid_buttons(
                             source_offset,
-                            code.to_string(),
+                            code,
                             markdown,
+                            false,
                         ))
                     })
                     .into_any_element()
@@ -491,6 +589,107 @@ fn render_mermaid_tab_header(
         )
 }
 
+fn render_mermaid_buttons(
+    source_offset: usize,
+    code: SharedString,
+    markdown: Entity<Markdown>,
+    can_export: bool,
+) -> impl IntoElement {
+    h_flex()
+        .absolute()
+        .top_1()
+        .right_1()
+        .justify_end()
+        .gap_0p5()
+        .when(can_export, |this| {
+            this.child(render_mermaid_export_menu(
+                source_offset,
+                code.clone(),
+                markdown.clone(),
+            ))
+        })
+        .child(render_mermaid_copy_button(
+            source_offset,
+            code.to_string(),
+            markdown,
+        ))
+}
+
+fn render_mermaid_export_menu(
+    source_offset: usize,
+    code: SharedString,
+    markdown: Entity<Markdown>,
+) -> impl IntoElement {
+    let id = ElementId::NamedChild(
+        Arc::new(ElementId::from((
+            "mermaid-export-menu",
+            markdown.entity_id(),
+        ))),
+        source_offset.to_string().into(),
+    );
+
+    PopoverMenu::new(id.clone())
+        .trigger(
+            IconButton::new(id, IconName::Ellipsis)
+                .icon_size(IconSize::Small)
+                .visible_on_hover("code_block"),
+        )
+        .anchor(Anchor::TopRight)
+        .offset(point(px(0.), px(2.)))
+        .menu(move |window, cx| {
+            let code = code.clone();
+            let markdown = markdown.clone();
+            let theme = markdown.read(cx).mermaid_export_theme;
+            Some(ContextMenu::build(window, cx, move |menu, _, _| {
+                let copy = |format| {
+                    let code = code.clone();
+                    move |_: &mut Window, cx: &mut App| {
+                        copy_mermaid_diagram(code.clone(), format, theme, cx).detach_and_log_err(cx)
+                    }
+                };
+                let set_theme = |export_theme| {
+                    let markdown = markdown.clone();
+                    move |_: &mut Window, cx: &mut App| {
+                        markdown.update(cx, |markdown, _| {
+                            markdown.mermaid_export_theme = export_theme
+                        })
+                    }
+                };
+                menu.entry("Copy as SVG", None, copy(MermaidExportFormat::Svg))
+                    .entry("Copy as PNG", None, copy(MermaidExportFormat::Png))
+                    .entry("Export Diagram…", None, {
+                        let code = code.clone();
+                        move |_, cx| {
+                            export_mermaid_diagram(
+                                code.clone(),
+                                theme,
+                                Path::new(""),
+                                "diagram.svg",
+                                cx,
+                            )
+                            .detach_and_log_err(cx)
+                        }
+                    })
+                    .separator()
+                    .header("Theme")
+                    .toggleable_entry(
+                        "Current Theme",
+                        theme == MermaidExportTheme::Current,
+                        IconPosition::Start,
+                        None,
+                        set_theme(MermaidExportTheme::Current),
+                    )
+                    .toggleable_entry(
+                        "Neutral Light",
+                        theme == MermaidExportTheme::NeutralLight,
+                        IconPosition::Start,
+                        None,
+                        set_theme(MermaidExportTheme::NeutralLight),
+                    )
+            }))
+        })
+}
+
 fn render_mermaid_copy_button(
     source_offset: usize,
     code: String,
@@ -501,7 +700,7 @@ fn render_mermaid_copy_button(
         source_offset.to_string().into(),
     );
 
-    div().absolute().top_1().right_1().justify_end().child(
+    div().child(
         CopyButton::new(id.clone(), code.clone())
             .visible_on_hover("code_block")
             .custom_on_click({
@@ -537,7 +736,7 @@ fn render_mermaid_code_view(contents: &SharedString) -> AnyElement {
 #[cfg(test)]
 mod tests {
     use super::{
-        CachedMermaidDiagram, MermaidDiagramCache, MermaidState,
+        CachedMermaidDiagram, MermaidDiagramCache, MermaidExportFormat, MermaidState,
         ParsedMarkdownMermaidDiagramContents, extract_mermaid_diagrams, parse_mermaid_info,
     };
     use crate::{
@@ -546,6 +745,7 @@ mod tests {
     };
     use collections::HashMap;
     use gpui::{Context, IntoElement, Render, RenderImage, TestAppContext, Window, size};
+    use std::path::Path;
     use std::sync::Arc;
     use ui::prelude::*;
 
@@ -668,6 +868,22 @@ mod tests {
         assert_eq!(parse_mermaid_info("rust"), None);
     }
 
+    #[test]
+    fn test_export_format_for_path() {
+        assert_eq!(
+            MermaidExportFormat::for_path(Path::new("docs/flow.PNG")),
+            MermaidExportFormat::Png
+        );
+        assert_eq!(
+            MermaidExportFormat::for_path(Path::new("docs/flow.svg")),
+            MermaidExportFormat::Svg
+        );
+        assert_eq!(
+            MermaidExportFormat::for_path(Path::new("docs/flow")),
+            MermaidExportFormat::Svg
+        );
+    }
+
     #[test]
     fn test_extract_mermaid_diagrams_parses_scale() {
         let markdown = "```mermaid 150\ngraph TD;\n```\n\n```rust\nfn main() {}\n```";
@@ -3,11 +3,32 @@ use workspace::Workspace;
 
 pub mod markdown_preview_settings;
 pub mod markdown_preview_view;
+pub mod mermaid_preview_view;
 
 pub use zed_actions::preview::markdown::{OpenPreview, OpenPreviewToTheSide};
 
 use crate::markdown_preview_view::MarkdownPreviewView;
 
+pub mod mermaid {
+    use gpui::actions;
+
+    pub use zed_actions::preview::mermaid::{OpenPreview, OpenPreviewToTheSide};
+
+    actions!(
+        mermaid,
+        [
+            /// Copies the previewed Mermaid diagram to the clipboard as SVG.
+            CopyAsSvg,
+            /// Copies the previewed Mermaid diagram to the clipboard as a PNG image.
+            CopyAsPng,
+            /// Saves the previewed Mermaid diagram to an SVG or PNG file.
+            ExportDiagram,
+            /// Switches the Mermaid diagram preview between the current theme and a neutral light theme.
+            ToggleNeutralLightTheme,
+        ]
+    );
+}
+
 actions!(
     markdown,
     [
@@ -42,6 +63,7 @@ pub fn init(cx: &mut App) {
             return;
         };
         markdown_preview_view::MarkdownPreviewView::register(workspace, window, cx);
+        mermaid_preview_view::MermaidPreviewView::register(workspace, window, cx);
     })
     .detach();
 }
@@ -0,0 +1,369 @@
+use std::mem;
+use std::path::Path;
+use std::sync::Arc;
+
+use editor::Editor;
+use gpui::{
+    App, Context, Entity, EventEmitter, FocusHandle, Focusable, IntoElement, ParentElement, Render,
+    RenderImage, Styled, Subscription, Task, WeakEntity, Window, div, img,
+};
+use language::{Buffer, BufferEvent};
+use markdown::{
+    MermaidExportFormat, MermaidExportTheme, copy_mermaid_diagram, export_mermaid_diagram,
+    render_mermaid_diagram_for_export,
+};
+use ui::{Tooltip, prelude::*};
+use workspace::item::Item;
+use workspace::notifications::NotifyTaskExt as _;
+use workspace::{Pane, Workspace};
+
+use crate::mermaid::{
+    CopyAsPng, CopyAsSvg, ExportDiagram, OpenPreview, OpenPreviewToTheSide, ToggleNeutralLightTheme,
+};
+
+/// Previews a standalone `.mmd`/`.mermaid` file, re-rendering the diagram as the file is edited.
+pub struct MermaidPreviewView {
+    workspace: WeakEntity<Workspace>,
+    focus_handle: FocusHandle,
+    buffer: Entity<Buffer>,
+    export_theme: MermaidExportTheme,
+    current_diagram: Option<Arc<RenderImage>>,
+    /// The error of the latest render, shown above the last successfully rendered diagram.
+    error: Option<SharedString>,
+    _refresh: Task<()>,
+    _buffer_subscription: Subscription,
+    _theme_subscription: Subscription,
+}
+
+impl MermaidPreviewView {
+    pub fn new(
+        buffer: Entity<Buffer>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        cx.new(|cx| {
+            let buffer_subscription = cx.subscribe_in(
+                &buffer,
+                window,
+                |this, _buffer, event: &BufferEvent, window, cx| match event {
+                    BufferEvent::Edited { .. } | BufferEvent::Saved => {
+                        this.render_diagram(window, cx);
+                    }
+                    _ => {}
+                },
+            );
+            let theme_subscription =
+                cx.observe_global_in::<theme::GlobalTheme>(window, |this, window, cx| {
+                    if this.export_theme == MermaidExportTheme::Current {
+                        this.render_diagram(window, cx);
+                    }
+                });
+
+            let mut this = Self {
+                workspace,
+                focus_handle: cx.focus_handle(),
+                buffer,
+                export_theme: MermaidExportTheme::default(),
+                current_diagram: None,
+                error: None,
+                _refresh: Task::ready(()),
+                _buffer_subscription: buffer_subscription,
+                _theme_subscription: theme_subscription,
+            };
+            this.render_diagram(window, cx);
+            this
+        })
+    }
+
+    /// Renders the diagram the way it is copied and exported, so the preview shows what will be shared.
+    fn render_diagram(&mut self, window: &Window, cx: &mut Context<Self>) {
+        const SCALE_FACTOR: f32 = 1.0;
+
+        let svg = render_mermaid_diagram_for_export(
+            self.source(cx),
+            MermaidExportFormat::Svg,
+            self.export_theme,
+            cx,
+        );
+        let renderer = cx.svg_renderer();
+        self._refresh = cx.spawn_in(window, async move |this, cx| {
+            let result = match svg.await {
+                Ok(svg) => {
+                    cx.background_spawn(async move {
+                        renderer
+                            .render_single_frame(&svg, SCALE_FACTOR)
+                            .map_err(|error| anyhow::anyhow!("{error}"))
+                    })
+                    .await
+                }
+                Err(error) => Err(error),
+            };
+
+            this.update_in(cx, |this, window, cx| {
+                match result {
+                    Ok(image) => {
+                        this.error = None;
+                        if let Some(image) = mem::replace(&mut this.current_diagram, Some(image)) {
+                            window.drop_image(image).ok();
+                        }
+                    }
+                    Err(error) => this.error = Some(format!("{error:#}").into()),
+                }
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn source(&self, cx: &App) -> SharedString {
+        self.buffer.read(cx).text().into()
+    }
+
+    fn copy_as_svg(&mut self, _: &CopyAsSvg, window: &mut Window, cx: &mut Context<Self>) {
+        self.copy(MermaidExportFormat::Svg, window, cx);
+    }
+
+    fn copy_as_png(&mut self, _: &CopyAsPng, window: &mut Window, cx: &mut Context<Self>) {
+        self.copy(MermaidExportFormat::Png, window, cx);
+    }
+
+    fn copy(&mut self, format: MermaidExportFormat, window: &mut Window, cx: &mut Context<Self>) {
+        copy_mermaid_diagram(self.source(cx), format, self.export_theme, cx).detach_and_notify_err(
+            self.workspace.clone(),
+            window,
+            cx,
+        );
+    }
+
+    fn export_diagram(&mut self, _: &ExportDiagram, window: &mut Window, cx: &mut Context<Self>) {
+        let file = self.buffer.read(cx).file();
+        let directory = file
+            .and_then(|file| file.as_local())
+            .and_then(|file| file.abs_path(cx).parent().map(Path::to_path_buf))
+            .unwrap_or_default();
+        let suggested_name = file
+            .and_then(|file| file.path().file_stem())
+            .map(|stem| format!("{stem}.svg"))
+            .unwrap_or_else(|| "diagram.svg".to_string());
+        export_mermaid_diagram(
+            self.source(cx),
+            self.export_theme,
+            &directory,
+            &suggested_name,
+            cx,
+        )
+        .detach_and_notify_err(self.workspace.clone(), window, cx);
+    }
+
+    fn toggle_neutral_light_theme(
+        &mut self,
+        _: &ToggleNeutralLightTheme,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.export_theme = match self.export_theme {
+            MermaidExportTheme::Current => MermaidExportTheme::NeutralLight,
+            MermaidExportTheme::NeutralLight => MermaidExportTheme::Current,
+        };
+        self.render_diagram(window, cx);
+        cx.notify();
+    }
+
+    fn find_existing_preview_item_idx(
+        pane: &Pane,
+        buffer: &Entity<Buffer>,
+        cx: &App,
+    ) -> Option<usize> {
+        pane.items_of_type::<MermaidPreviewView>()
+            .find(|view| view.read(cx).buffer == *buffer)
+            .and_then(|view| pane.index_for_item(&view))
+    }
+
+    pub fn resolve_active_item_as_mermaid_buffer(
+        workspace: &Workspace,
+        cx: &mut Context<Workspace>,
+    ) -> Option<Entity<Buffer>> {
+        let editor = workspace.active_item(cx)?.act_as::<Editor>(cx)?;
+        let buffer = editor.read(cx).buffer().read(cx).as_singleton()?;
+        Self::is_mermaid_file(&buffer, cx).then_some(buffer)
+    }
+
+    pub fn is_mermaid_file(buffer: &Entity<Buffer>, cx: &App) -> bool {
+        buffer.read(cx).file().is_some_and(|file| {
+            Path::new(file.file_name(cx))
+                .extension()
+                .is_some_and(|ext| {
+                    ext.eq_ignore_ascii_case("mmd") || ext.eq_ignore_ascii_case("mermaid")
+                })
+        })
+    }
+
+    fn open_preview(
+        workspace: &mut Workspace,
+        buffer: Entity<Buffer>,
+        pane: Entity<Pane>,
+        focus: bool,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        if let Some(existing_view_idx) =
+            Self::find_existing_preview_item_idx(pane.read(cx), &buffer, cx)
+        {
+            pane.update(cx, |pane, cx| {
+                pane.activate_item(existing_view_idx, true, true, window, cx);
+            });
+        } else {
+            let view = Self::new(buffer, workspace.weak_handle(), window, cx);
+            pane.update(cx, |pane, cx| {
+                pane.add_item(Box::new(view), focus, focus, None, window, cx)
+            });
+        }
+        cx.notify();
+    }
+
+    pub fn register(workspace: &mut Workspace, _window: &mut Window, _cx: &mut Context<Workspace>) {
+        workspace.register_action(move |workspace, _: &OpenPreview, window, cx| {
+            if let Some(buffer) = Self::resolve_active_item_as_mermaid_buffer(workspace, cx) {
+                let pane = workspace.active_pane().clone();
+                Self::open_preview(workspace, buffer, pane, true, window, cx);
+            }
+        });
+
+        workspace.register_action(move |workspace, _: &OpenPreviewToTheSide, window, cx| {
+            let Some(buffer) = Self::resolve_active_item_as_mermaid_buffer(workspace, cx) else {
+                return;
+            };
+            let pane = workspace
+                .find_pane_in_direction(workspace::SplitDirection::Right, cx)
+                .unwrap_or_else(|| {
+                    workspace.split_pane(
+                        workspace.active_pane().clone(),
+                        workspace::SplitDirection::Right,
+                        window,
+                        cx,
+                    )
+                });
+            Self::open_preview(workspace, buffer, pane, false, window, cx);
+        });
+    }
+
+    fn render_toolbar(&self, cx: &App) -> impl IntoElement {
+        let focus_handle = self.focus_handle.clone();
+        let button = move |id: &'static str, label: &'static str, action: Box<dyn gpui::Action>| {
+            let focus_handle = focus_handle.clone();
+            Button::new(id, label)
+                .label_size(LabelSize::Small)
+                .on_click(move |_, window, cx| {
+                    focus_handle.dispatch_action(action.as_ref(), window, cx)
+                })
+        };
+
+        h_flex()
+            .w_full()
+            .p_1()
+            .gap_1()
+            .justify_end()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(button("copy-as-svg", "Copy as SVG", Box::new(CopyAsSvg)))
+            .child(button("copy-as-png", "Copy as PNG", Box::new(CopyAsPng)))
+            .child(button(
+                "export-diagram",
+                "Export Diagram…",
+                Box::new(ExportDiagram),
+            ))
+            .child(
+                button(
+                    "neutral-light-theme",
+                    "Neutral Light Theme",
+                    Box::new(ToggleNeutralLightTheme),
+                )
+                .selected_style(ButtonStyle::Tinted(ui::TintColor::Accent))
+                .toggle_state(self.export_theme == MermaidExportTheme::NeutralLight)
+                .tooltip(Tooltip::text(
+                    "Use a neutral light theme instead of the current one, e.g. for documentation",
+                )),
+            )
+    }
+}
+
+impl Render for MermaidPreviewView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .id("MermaidPreview")
+            .key_context("MermaidPreview")
+            .track_focus(&self.focus_handle(cx))
+            .on_action(cx.listener(Self::copy_as_svg))
+            .on_action(cx.listener(Self::copy_as_png))
+            .on_action(cx.listener(Self::export_diagram))
+            .on_action(cx.listener(Self::toggle_neutral_light_theme))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_toolbar(cx))
+            .when_some(self.error.clone(), |this, error| {
+                this.child(
+                    h_flex()
+                        .p_2()
+                        .gap_2()
+                        .child(Icon::new(IconName::Warning).color(Color::Warning))
+                        .child(Label::new(error).size(LabelSize::Small)),
+                )
+            })
+            .child(
+                div()
+                    .id("mermaid-diagram")
+                    .flex_1()
+                    .p_4()
+                    .flex()
+                    .justify_center()
+                    .items_center()
+                    .overflow_scroll()
+                    .map(|this| match self.current_diagram.clone() {
+                        Some(image) => this.child(img(image).max_w_full().with_fallback(|| {
+                            h_flex()
+                                .gap_2()
+                                .child(Icon::new(IconName::Warning))
+                                .child("Failed to load Mermaid diagram")
+                                .into_any_element()
+                        })),
+                        None if self.error.is_none() => this.child(
+                            Label::new("Rendering…")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                        None => this,
+                    }),
+            )
+    }
+}
+
+impl Focusable for MermaidPreviewView {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<()> for MermaidPreviewView {}
+
+impl Item for MermaidPreviewView {
+    type Event = ();
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::Image))
+    }
+
+    fn tab_content_text(&self, _detail: usize, cx: &App) -> SharedString {
+        self.buffer
+            .read(cx)
+            .file()
+            .map(|file| format!("Preview {}", file.file_name(cx)).into())
+            .unwrap_or_else(|| "Mermaid Preview".into())
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("mermaid preview: open")
+    }
+
+    fn to_item_events(_event: &Self::Event, _f: &mut dyn FnMut(workspace::item::ItemEvent)) {}
+}
@@ -0,0 +1,224 @@
+//! Serves Zed's editor and project tools over MCP, so that agents running
+//! outside of Zed can use its language intelligence. Agents connect by running
+//! `zed --mcp`, which forwards its stdio to the socket served here.
+
+mod tools;
+
+use std::mem;
+
+use anyhow::{Context as _, Result, anyhow};
+use collections::HashMap;
+use context_server::listener::McpServer;
+use gpui::{App, AsyncApp, Entity, Global, PromptLevel, Task, WindowHandle};
+use settings::{RegisterSetting, Settings, SettingsStore, ToolPermissionMode};
+use workspace::{MultiWorkspace, Workspace};
+
+#[derive(Clone, Debug, PartialEq, RegisterSetting)]
+pub struct McpServerSettings {
+    pub enabled: bool,
+    pub default: ToolPermissionMode,
+    pub tools: HashMap<String, ToolPermissionMode>,
+}
+
+impl McpServerSettings {
+    /// Returns the permission configured for the tool with the given name.
+    pub fn permission(&self, tool_name: &str) -> ToolPermissionMode {
+        self.tools.get(tool_name).copied().unwrap_or(self.default)
+    }
+}
+
+impl Settings for McpServerSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let mcp_server = content.mcp_server.as_ref().unwrap();
+        Self {
+            enabled: mcp_server.enabled.unwrap(),
+            default: mcp_server.default.unwrap(),
+            tools: mcp_server.tools.clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// The running server, which is dropped when the setting is turned off.
+#[derive(Default)]
+struct GlobalMcpServer {
+    server: Option<McpServer>,
+    _start: Option<Task<()>>,
+}
+
+impl Global for GlobalMcpServer {}
+
+pub fn init(cx: &mut App) {
+    cx.set_global(GlobalMcpServer::default());
+
+    let mut enabled = false;
+    let mut update_server = move |cx: &mut App| {
+        let was_enabled = mem::replace(&mut enabled, McpServerSettings::get_global(cx).enabled);
+        if enabled != was_enabled {
+            let start = enabled.then(|| start_server(cx));
+            cx.set_global(GlobalMcpServer {
+                server: None,
+                _start: start,
+            });
+        }
+    };
+    update_server(cx);
+    cx.observe_global::<SettingsStore>(update_server).detach();
+}
+
+fn start_server(cx: &mut App) -> Task<()> {
+    cx.spawn(async move |cx| {
+        match McpServer::new_at(paths::mcp_server_socket().clone(), cx).await {
+            Ok(mut server) => {
+                tools::add_tools(&mut server);
+                log::info!("serving MCP on {:?}", server.socket_path());
+                cx.update(|cx| cx.global_mut::<GlobalMcpServer>().server = Some(server));
+            }
+            Err(error) => log::error!("failed to start the MCP server: {error:#}"),
+        }
+    })
+}
+
+/// Returns the workspace the tools act on: the one in the active window, or in
+/// any window while Zed isn't focused.
+fn active_workspace(
+    cx: &mut AsyncApp,
+) -> Result<(WindowHandle<MultiWorkspace>, Entity<Workspace>)> {
+    cx.update(|cx| {
+        let window = cx
+            .active_window()
+            .and_then(|window| window.downcast::<MultiWorkspace>())
+            .or_else(|| {
+                cx.windows()
+                    .into_iter()
+                    .find_map(|window| window.downcast::<MultiWorkspace>())
+            })
+            .context("no Zed window is open")?;
+        let workspace = window.read(cx)?.workspace().clone();
+        Ok((window, workspace))
+    })
+}
+
+/// Checks the tool's permission, asking the user when it is set to `confirm`.
+/// `action` completes the sentence "An external agent wants to …".
+async fn authorize(tool_name: &str, action: String, cx: &mut AsyncApp) -> Result<()> {
+    let is_enabled = |cx: &mut AsyncApp| cx.update(|cx| McpServerSettings::get_global(cx).enabled);
+    // Tool calls already in flight when the server is turned off must not go
+    // through.
+    anyhow::ensure!(is_enabled(cx), "Zed's MCP server is disabled");
+    match cx.update(|cx| McpServerSettings::get_global(cx).permission(tool_name)) {
+        ToolPermissionMode::Allow => Ok(()),
+        ToolPermissionMode::Deny => Err(anyhow!(
+            "the `{tool_name}` tool is disabled in Zed's settings"
+        )),
+        ToolPermissionMode::Confirm => {
+            let (window, _) = active_workspace(cx)?;
+            let answer = window.update(cx, |_, window, cx| {
+                window.prompt(
+                    PromptLevel::Info,
+                    &format!("An external agent wants to {action}"),
+                    None,
+                    &["Allow", "Deny"],
+                    cx,
+                )
+            })?;
+            match answer.await {
+                Ok(0) if is_enabled(cx) => Ok(()),
+                Ok(0) => Err(anyhow!("Zed's MCP server is disabled")),
+                _ => Err(anyhow!("the user denied the `{tool_name}` tool call")),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::{FakeFs, Project, task_store::TaskStore};
+    use serde_json::json;
+    use util::path;
+    use workspace::AppState;
+
+    /// Opens a window on a project in `/dir`, with the given `mcp_server` settings.
+    pub(crate) async fn init_test(
+        mcp_server_settings: serde_json::Value,
+        files: serde_json::Value,
+        cx: &mut TestAppContext,
+    ) -> WindowHandle<MultiWorkspace> {
+        cx.update(|cx| {
+            AppState::test(cx);
+            editor::init(cx);
+            TaskStore::init(None);
+        });
+        set_settings(mcp_server_settings, cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), files).await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        cx.add_window(|window, cx| MultiWorkspace::test_new(project, window, cx))
+    }
+
+    pub(crate) fn set_settings(mcp_server_settings: serde_json::Value, cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store
+                    .set_user_settings(
+                        &json!({ "mcp_server": mcp_server_settings }).to_string(),
+                        cx,
+                    )
+                    .unwrap();
+            });
+        });
+    }
+
+    #[gpui::test]
+    async fn test_authorize(cx: &mut TestAppContext) {
+        init_test(
+            json!({
+                "enabled": true,
+                "default": "allow",
+                "tools": { "denied": "deny", "confirmed": "confirm" },
+            }),
+            json!({}),
+            cx,
+        )
+        .await;
+        let spawn_authorize = |tool_name: &'static str, cx: &mut TestAppContext| {
+            cx.spawn(move |mut cx| async move {
+                authorize(tool_name, "do something".into(), &mut cx).await
+            })
+        };
+
+        spawn_authorize("allowed", cx).await.unwrap();
+        spawn_authorize("denied", cx).await.unwrap_err();
+        assert!(!cx.has_pending_prompt());
+
+        // Confirmed tools only run if the user allows them.
+        let task = spawn_authorize("confirmed", cx);
+        cx.run_until_parked();
+        assert!(cx.has_pending_prompt());
+        cx.simulate_prompt_answer("Allow");
+        task.await.unwrap();
+
+        let task = spawn_authorize("confirmed", cx);
+        cx.run_until_parked();
+        cx.simulate_prompt_answer("Deny");
+        task.await.unwrap_err();
+
+        // Calls made while the server is off are refused, even when confirmed
+        // while it is being turned off.
+        let task = spawn_authorize("confirmed", cx);
+        cx.run_until_parked();
+        set_settings(
+            json!({
+                "enabled": false,
+                "default": "allow",
+                "tools": { "confirmed": "confirm" },
+            }),
+            cx,
+        );
+        cx.simulate_prompt_answer("Allow");
+        task.await.unwrap_err();
+        spawn_authorize("allowed", cx).await.unwrap_err();
+    }
+}
@@ -0,0 +1,718 @@
+use std::{collections::BTreeMap, ops::Range};
+
+use anyhow::{Context as _, Result, anyhow};
+use collections::HashSet;
+use context_server::{
+    listener::{McpServer, McpServerTool, ToolResponse},
+    types::{ToolAnnotations, ToolResponseContent},
+};
+use editor::Editor;
+use gpui::{App, AsyncApp, Entity, Task};
+use language::{Buffer, BufferSnapshot, Location, OffsetRangeExt as _, Point};
+use lsp::DiagnosticSeverity;
+use project::{Project, lsp_store::SymbolLocation};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use task::TaskContext;
+
+use crate::{active_workspace, authorize};
+
+const MAX_SYMBOLS: usize = 100;
+
+pub(crate) fn add_tools(server: &mut McpServer) {
+    server.add_tool(OpenBuffersTool);
+    server.add_tool(DiagnosticsTool);
+    server.add_tool(SearchSymbolsTool);
+    server.add_tool(GoToDefinitionTool);
+    server.add_tool(CurrentSelectionTool);
+    server.add_tool(RunTaskTool);
+}
+
+/// Lists the files open in Zed's editor, marking the focused one and those
+/// with unsaved changes.
+#[derive(Deserialize, JsonSchema)]
+struct OpenBuffersInput {
+    /// Whether to include the contents of each file, including unsaved changes.
+    #[serde(default)]
+    include_contents: bool,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct OpenBuffersOutput {
+    buffers: Vec<OpenBuffer>,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct OpenBuffer {
+    path: String,
+    language: Option<String>,
+    is_focused: bool,
+    has_unsaved_changes: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    contents: Option<String>,
+}
+
+#[derive(Clone)]
+struct OpenBuffersTool;
+
+impl McpServerTool for OpenBuffersTool {
+    type Input = OpenBuffersInput;
+    type Output = OpenBuffersOutput;
+
+    const NAME: &'static str = "open_buffers";
+
+    fn annotations(&self) -> ToolAnnotations {
+        read_only_annotations("Open Buffers")
+    }
+
+    async fn run(
+        &self,
+        input: Self::Input,
+        cx: &mut AsyncApp,
+    ) -> Result<ToolResponse<Self::Output>> {
+        authorize(Self::NAME, "list the files open in Zed".into(), cx).await?;
+        let (_, workspace) = active_workspace(cx)?;
+        let buffers = workspace.read_with(cx, |workspace, cx| {
+            let focused_buffer = workspace
+                .active_item_as::<Editor>(cx)
+                .and_then(|editor| editor.read(cx).buffer().read(cx).as_singleton());
+            let mut seen_buffers = HashSet::default();
+            workspace
+                .items_of_type::<Editor>(cx)
+                .filter_map(|editor| editor.read(cx).buffer().read(cx).as_singleton())
+                .filter(|buffer| seen_buffers.insert(buffer.entity_id()))
+                .filter_map(|buffer| {
+                    let path = buffer_path(&buffer, cx)?;
+                    let is_focused = focused_buffer.as_ref() == Some(&buffer);
+                    let buffer = buffer.read(cx);
+                    Some(OpenBuffer {
+                        path,
+                        language: buffer
+                            .language()
+                            .map(|language| language.name().to_string()),
+                        is_focused,
+                        has_unsaved_changes: buffer.is_dirty(),
+                        contents: input.include_contents.then(|| buffer.text()),
+                    })
+                })
+                .collect()
+        });
+
+        response(OpenBuffersOutput { buffers })
+    }
+}
+
+/// Returns the errors, warnings and hints that language servers reported for
+/// a file. Without a path, returns how many errors and warnings each file in
+/// the project has.
+#[derive(Deserialize, JsonSchema)]
+struct DiagnosticsInput {
+    /// The file to get diagnostics for, either absolute or relative to a
+    /// project root.
+    path: Option<String>,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct DiagnosticsOutput {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    diagnostics: Vec<Diagnostic>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    summaries: Vec<DiagnosticSummary>,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct Diagnostic {
+    severity: String,
+    message: String,
+    source: Option<String>,
+    range: TextRange,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct DiagnosticSummary {
+    path: String,
+    error_count: usize,
+    warning_count: usize,
+}
+
+#[derive(Clone)]
+struct DiagnosticsTool;
+
+impl McpServerTool for DiagnosticsTool {
+    type Input = DiagnosticsInput;
+    type Output = DiagnosticsOutput;
+
+    const NAME: &'static str = "diagnostics";
+
+    fn annotations(&self) -> ToolAnnotations {
+        read_only_annotations("Diagnostics")
+    }
+
+    async fn run(
+        &self,
+        input: Self::Input,
+        cx: &mut AsyncApp,
+    ) -> Result<ToolResponse<Self::Output>> {
+        authorize(Self::NAME, "read the project's diagnostics".into(), cx).await?;
+        let (_, workspace) = active_workspace(cx)?;
+        let project = workspace.read_with(cx, |workspace, _| workspace.project().clone());
+
+        let Some(path) = input.path.filter(|path| !path.is_empty()) else {
+            let summaries = project.read_with(cx, |project, cx| {
+                let mut counts = BTreeMap::<String, (usize, usize)>::new();
+                for (project_path, _, summary) in project.diagnostic_summaries(false, cx) {
+                    let Some(path) = project.absolute_path(&project_path, cx) else {
+                        continue;
+                    };
+                    let count = counts
+                        .entry(path.to_string_lossy().into_owned())
+                        .or_default();
+                    count.0 += summary.error_count;
+                    count.1 += summary.warning_count;
+                }
+                counts
+                    .into_iter()
+                    .filter(|(_, (error_count, warning_count))| {
+                        *error_count > 0 || *warning_count > 0
+                    })
+                    .map(|(path, (error_count, warning_count))| DiagnosticSummary {
+                        path,
+                        error_count,
+                        warning_count,
+                    })
+                    .collect()
+            });
+            return response(DiagnosticsOutput {
+                diagnostics: Vec::new(),
+                summaries,
+            });
+        };
+
+        let buffer = open_buffer(&project, &path, cx).await?;
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+        let diagnostics = snapshot
+            .diagnostic_groups(None)
+            .into_iter()
+            .map(|(_, group)| {
+                let entry = &group.entries[group.primary_ix];
+                Diagnostic {
+                    severity: severity_name(entry.diagnostic.severity).into(),
+                    message: entry.diagnostic.message.clone(),
+                    source: entry.diagnostic.source.clone(),
+                    range: text_range(&snapshot, entry.range.to_point(&snapshot)),
+                }
+            })
+            .collect();
+
+        response(DiagnosticsOutput {
+            diagnostics,
+            summaries: Vec::new(),
+        })
+    }
+}
+
+/// Searches the project for symbols, such as functions and types, whose names
+/// match a query, using the language servers running in Zed.
+#[derive(Deserialize, JsonSchema)]
+struct SearchSymbolsInput {
+    /// The name, or part of the name, of the symbols to find.
+    query: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct SearchSymbolsOutput {
+    symbols: Vec<Symbol>,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct Symbol {
+    name: String,
+    kind: String,
+    container_name: Option<String>,
+    path: String,
+    /// The 1-based line the symbol starts on.
+    line: u32,
+}
+
+#[derive(Clone)]
+struct SearchSymbolsTool;
+
+impl McpServerTool for SearchSymbolsTool {
+    type Input = SearchSymbolsInput;
+    type Output = SearchSymbolsOutput;
+
+    const NAME: &'static str = "search_symbols";
+
+    fn annotations(&self) -> ToolAnnotations {
+        read_only_annotations("Search Symbols")
+    }
+
+    async fn run(
+        &self,
+        input: Self::Input,
+        cx: &mut AsyncApp,
+    ) -> Result<ToolResponse<Self::Output>> {
+        authorize(
+            Self::NAME,
+            format!(
+                "search the project for symbols matching \"{}\"",
+                input.query
+            ),
+            cx,
+        )
+        .await?;
+        let (_, workspace) = active_workspace(cx)?;
+        let project = workspace.read_with(cx, |workspace, _| workspace.project().clone());
+        let symbols = project
+            .update(cx, |project, cx| project.symbols(&input.query, cx))
+            .await?;
+
+        let symbols = project.read_with(cx, |project, cx| {
+            symbols
+                .into_iter()
+                .filter_map(|symbol| {
+                    let path = match &symbol.path {
+                        SymbolLocation::InProject(project_path) => {
+                            project.absolute_path(project_path, cx)?
+                        }
+                        SymbolLocation::OutsideProject { abs_path, .. } => abs_path.to_path_buf(),
+                    };
+                    Some(Symbol {
+                        kind: format!("{:?}", symbol.kind),
+                        path: path.to_string_lossy().into_owned(),
+                        line: symbol.range.start.0.row + 1,
+                        name: symbol.name,
+                        container_name: symbol.container_name,
+                    })
+                })
+                .take(MAX_SYMBOLS)
+                .collect()
+        });
+
+        response(SearchSymbolsOutput { symbols })
+    }
+}
+
+/// Finds where the symbol at a position in a file is defined, using the
+/// language servers running in Zed.
+#[derive(Deserialize, JsonSchema)]
+struct GoToDefinitionInput {
+    /// The file containing the symbol, either absolute or relative to a
+    /// project root.
+    path: String,
+    /// The 1-based line of the symbol.
+    line: u32,
+    /// The 1-based column of the symbol, counted in characters.
+    column: u32,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct GoToDefinitionOutput {
+    definitions: Vec<FileLocation>,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct FileLocation {
+    path: String,
+    range: TextRange,
+}
+
+#[derive(Clone)]
+struct GoToDefinitionTool;
+
+impl McpServerTool for GoToDefinitionTool {
+    type Input = GoToDefinitionInput;
+    type Output = GoToDefinitionOutput;
+
+    const NAME: &'static str = "go_to_definition";
+
+    fn annotations(&self) -> ToolAnnotations {
+        read_only_annotations("Go to Definition")
+    }
+
+    async fn run(
+        &self,
+        input: Self::Input,
+        cx: &mut AsyncApp,
+    ) -> Result<ToolResponse<Self::Output>> {
+        authorize(
+            Self::NAME,
+            format!("find a definition in {}", input.path),
+            cx,
+        )
+        .await?;
+        let (_, workspace) = active_workspace(cx)?;
+        let project = workspace.read_with(cx, |workspace, _| workspace.project().clone());
+        let buffer = open_buffer(&project, &input.path, cx).await?;
+        let position = buffer.read_with(cx, |buffer, _| {
+            point_for_position(&buffer.snapshot(), input.line, input.column)
+        });
+        let links = project
+            .update(cx, |project, cx| project.definitions(&buffer, position, cx))
+            .await?
+            .unwrap_or_default();
+
+        let definitions = cx.update(|cx| {
+            links
+                .iter()
+                .filter_map(|link| file_location(&link.target, cx))
+                .collect()
+        });
+
+        response(GoToDefinitionOutput { definitions })
+    }
+}
+
+/// Returns the file focused in Zed's editor and the text selected in it.
+#[derive(Deserialize, JsonSchema)]
+struct CurrentSelectionInput {}
+
+#[derive(Serialize, JsonSchema)]
+struct CurrentSelectionOutput {
+    path: String,
+    selections: Vec<Selection>,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct Selection {
+    range: TextRange,
+    text: String,
+}
+
+#[derive(Clone)]
+struct CurrentSelectionTool;
+
+impl McpServerTool for CurrentSelectionTool {
+    type Input = CurrentSelectionInput;
+    type Output = CurrentSelectionOutput;
+
+    const NAME: &'static str = "current_selection";
+
+    fn annotations(&self) -> ToolAnnotations {
+        read_only_annotations("Current Selection")
+    }
+
+    async fn run(
+        &self,
+        _input: Self::Input,
+        cx: &mut AsyncApp,
+    ) -> Result<ToolResponse<Self::Output>> {
+        authorize(Self::NAME, "read your selection in Zed".into(), cx).await?;
+        let (_, workspace) = active_workspace(cx)?;
+        let output = cx.update(|cx| {
+            let editor = workspace
+                .read(cx)
+                .active_item_as::<Editor>(cx)
+                .context("no editor is focused in Zed")?;
+            let buffer = editor
+                .read(cx)
+                .buffer()
+                .read(cx)
+                .as_singleton()
+                .context("the focused editor shows more than one file")?;
+            let path = buffer_path(&buffer, cx).context("the focused file has no path")?;
+            let snapshot = buffer.read(cx).snapshot();
+            let selections = editor.update(cx, |editor, cx| {
+                let display_snapshot = editor.display_snapshot(cx);
+                editor.selections.all::<Point>(&display_snapshot)
+            });
+
+            anyhow::Ok(CurrentSelectionOutput {
+                path,
+                selections: selections
+                    .into_iter()
+                    .map(|selection| Selection {
+                        text: snapshot.text_for_range(selection.range()).collect(),
+                        range: text_range(&snapshot, selection.range()),
+                    })
+                    .collect(),
+            })
+        })?;
+
+        response(output)
+    }
+}
+
+/// Runs one of the project's tasks in a terminal in Zed, such as those defined
+/// in `.zed/tasks.json`. The task's output is shown to the user in Zed and is
+/// not returned.
+#[derive(Deserialize, JsonSchema)]
+struct RunTaskInput {
+    /// The label of the task to run.
+    label: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct RunTaskOutput {
+    label: String,
+}
+
+#[derive(Clone)]
+struct RunTaskTool;
+
+impl McpServerTool for RunTaskTool {
+    type Input = RunTaskInput;
+    type Output = RunTaskOutput;
+
+    const NAME: &'static str = "run_task";
+
+    fn annotations(&self) -> ToolAnnotations {
+        ToolAnnotations {
+            title: Some("Run Task".into()),
+            read_only_hint: Some(false),
+            destructive_hint: Some(true),
+            idempotent_hint: Some(false),
+            open_world_hint: Some(true),
+        }
+    }
+
+    async fn run(
+        &self,
+        input: Self::Input,
+        cx: &mut AsyncApp,
+    ) -> Result<ToolResponse<Self::Output>> {
+        authorize(Self::NAME, format!("run the task \"{}\"", input.label), cx).await?;
+        let (window, workspace) = active_workspace(cx)?;
+        let task_contexts = window
+            .update(cx, |_, window, cx| {
+                workspace.update(cx, |workspace, cx| {
+                    tasks_ui::task_contexts(workspace, window, cx)
+                })
+            })?
+            .await;
+
+        let tasks = workspace
+            .update(cx, |workspace, cx| {
+                let Some(task_inventory) = workspace
+                    .project()
+                    .read(cx)
+                    .task_store()
+                    .read(cx)
+                    .task_inventory()
+                    .cloned()
+                else {
+                    return Task::ready(Vec::new());
+                };
+                let (language, buffer) = task_contexts
+                    .location()
+                    .map(|location| {
+                        let buffer = location.buffer.clone();
+                        (
+                            buffer.read(cx).language_at(location.range.start),
+                            Some(buffer),
+                        )
+                    })
+                    .unwrap_or_default();
+                task_inventory
+                    .read(cx)
+                    .list_tasks(buffer, language, task_contexts.worktree(), cx)
+            })
+            .await;
+
+        let Some((task_source_kind, task)) = tasks
+            .iter()
+            .find(|(_, task)| task.label == input.label)
+            .cloned()
+        else {
+            let labels = tasks
+                .iter()
+                .map(|(_, task)| format!("\"{}\"", task.label))
+                .collect::<Vec<_>>();
+            return Err(anyhow!(
+                "no task is labeled \"{}\". The available tasks are: {}",
+                input.label,
+                labels.join(", ")
+            ));
+        };
+
+        window.update(cx, |_, window, cx| {
+            workspace.update(cx, |workspace, cx| {
+                let default_context = TaskContext::default();
+                workspace.schedule_task(
+                    task_source_kind,
+                    &task,
+                    task_contexts.active_context().unwrap_or(&default_context),
+                    false,
+                    window,
+                    cx,
+                );
+            })
+        })?;
+
+        response(RunTaskOutput { label: input.label })
+    }
+}
+
+/// A range in a file, with 1-based lines and columns counted in characters.
+#[derive(Debug, PartialEq, Serialize, JsonSchema)]
+struct TextRange {
+    start: Position,
+    end: Position,
+}
+
+#[derive(Debug, PartialEq, Serialize, JsonSchema)]
+struct Position {
+    line: u32,
+    column: u32,
+}
+
+fn read_only_annotations(title: &str) -> ToolAnnotations {
+    ToolAnnotations {
+        title: Some(title.into()),
+        read_only_hint: Some(true),
+        destructive_hint: None,
+        idempotent_hint: None,
+        open_world_hint: Some(false),
+    }
+}
+
+/// Returns the output both as structured content and as JSON text, for
+/// clients that don't read structured content.
+fn response<T: Serialize>(output: T) -> Result<ToolResponse<T>> {
+    Ok(ToolResponse {
+        content: vec![ToolResponseContent::Text {
+            text: serde_json::to_string_pretty(&output)?,
+        }],
+        structured_content: output,
+    })
+}
+
+async fn open_buffer(
+    project: &Entity<Project>,
+    path: &str,
+    cx: &mut AsyncApp,
+) -> Result<Entity<Buffer>> {
+    project
+        .update(cx, |project, cx| {
+            let project_path = project
+                .find_project_path(path, cx)
+                .with_context(|| format!("{path} is not in the project"))?;
+            anyhow::Ok(project.open_buffer(project_path, cx))
+        })?
+        .await
+}
+
+fn buffer_path(buffer: &Entity<Buffer>, cx: &App) -> Option<String> {
+    let file = project::File::from_dyn(buffer.read(cx).file())?;
+    let abs_path = file.worktree.read(cx).absolutize(&file.path);
+    Some(abs_path.to_string_lossy().into_owned())
+}
+
+fn file_location(location: &Location, cx: &App) -> Option<FileLocation> {
+    let path = buffer_path(&location.buffer, cx)?;
+    let snapshot = location.buffer.read(cx).snapshot();
+    Some(FileLocation {
+        path,
+        range: text_range(&snapshot, location.range.to_point(&snapshot)),
+    })
+}
+
+fn severity_name(severity: DiagnosticSeverity) -> &'static str {
+    match severity {
+        DiagnosticSeverity::ERROR => "error",
+        DiagnosticSeverity::WARNING => "warning",
+        DiagnosticSeverity::INFORMATION => "information",
+        _ => "hint",
+    }
+}
+
+/// Converts a 1-based line and character column into a point, clipping it to
+/// the buffer.
+fn point_for_position(snapshot: &BufferSnapshot, line: u32, column: u32) -> Point {
+    let row = line.saturating_sub(1).min(snapshot.max_point().row);
+    let line_text = snapshot
+        .text_for_range(Point::new(row, 0)..Point::new(row, snapshot.line_len(row)))
+        .collect::<String>();
+    let byte_column = line_text
+        .char_indices()
+        .nth(column.saturating_sub(1) as usize)
+        .map_or(line_text.len(), |(ix, _)| ix);
+    Point::new(row, byte_column as u32)
+}
+
+fn position_for_point(snapshot: &BufferSnapshot, point: Point) -> Position {
+    let column = snapshot
+        .text_for_range(Point::new(point.row, 0)..point)
+        .map(|chunk| chunk.chars().count())
+        .sum::<usize>();
+    Position {
+        line: point.row + 1,
+        column: column as u32 + 1,
+    }
+}
+
+fn text_range(snapshot: &BufferSnapshot, range: Range<Point>) -> TextRange {
+    TextRange {
+        start: position_for_point(snapshot, range.start),
+        end: position_for_point(snapshot, range.end),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::init_test;
+    use gpui::{AppContext as _, TestAppContext};
+    use serde_json::json;
+
+    #[gpui::test]
+    fn test_positions_count_characters(cx: &mut TestAppContext) {
+        let buffer = cx.new(|cx| Buffer::local("fn main() {\n    let café = \"☕\";\n}", cx));
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+
+        // `é` takes two bytes, so `=`, the 14th character, starts at byte 14.
+        let point = point_for_position(&snapshot, 2, 14);
+        assert_eq!(point, Point::new(1, 14));
+        assert_eq!(
+            position_for_point(&snapshot, point),
+            Position {
+                line: 2,
+                column: 14
+            }
+        );
+
+        // Positions past the end of a line or of the buffer are clipped.
+        assert_eq!(point_for_position(&snapshot, 1, 100), Point::new(0, 11));
+        assert_eq!(point_for_position(&snapshot, 10, 1), Point::new(2, 0));
+        assert_eq!(point_for_position(&snapshot, 0, 0), Point::new(0, 0));
+    }
+
+    #[gpui::test]
+    async fn test_run_task_with_unknown_label(cx: &mut TestAppContext) {
+        init_test(
+            json!({
+                "enabled": true,
+                "tools": { "run_task": "allow" },
+            }),
+            json!({
+                ".zed": {
+                    "tasks.json": r#"[{ "label": "build", "command": "make" }]"#,
+                },
+            }),
+            cx,
+        )
+        .await;
+        cx.run_until_parked();
+
+        let error = cx
+            .spawn(|mut cx| async move {
+                RunTaskTool
+                    .run(
+                        RunTaskInput {
+                            label: "missing".into(),
+                        },
+                        &mut cx,
+                    )
+                    .await
+            })
+            .await
+            .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .starts_with("no task is labeled \"missing\""),
+            "unexpected error: {error}"
+        );
+        assert!(!cx.has_pending_prompt());
+    }
+}
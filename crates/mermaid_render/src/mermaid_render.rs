@@ -3,7 +3,8 @@
 
 //! Crate for rendering Mermaid diagram strings to SVG strings.
 //!
-//! The entrypoint to this crate is [`render_to_svg`].
+//! The entrypoint to this crate is [`render_to_svg`], with
+//! [`render_to_standalone_svg`] producing SVGs meant to be exported out of Zed.
 //!
 //! It takes a `&str` and a [`MermaidTheme`]. The output is an SVG with the
 //! following properties:
@@ -105,10 +106,10 @@ pub struct MermaidTheme {
     pub accent_colors: Vec<AccentColor>,
 }
 
-/// Default theme for testing.
-#[cfg(any(test, feature = "test-support"))]
-impl Default for MermaidTheme {
-    fn default() -> Self {
+impl MermaidTheme {
+    /// A light theme with a neutral slate palette that doesn't depend on the
+    /// Zed theme, for diagrams exported into documentation.
+    pub fn neutral_light() -> Self {
         use gpui::{hsla, rgb};
         let git_branch_colors: [Hsla; 8] = [
             hsla(240.0 / 360.0, 1.0, 0.462_745_1, 1.0),
@@ -154,6 +155,14 @@ impl Default for MermaidTheme {
     }
 }
 
+/// Default theme for testing.
+#[cfg(any(test, feature = "test-support"))]
+impl Default for MermaidTheme {
+    fn default() -> Self {
+        Self::neutral_light()
+    }
+}
+
 /// Formats a color as a CSS hex color for embedding in SVG/CSS.
 ///
 /// Emits `#rrggbb` for fully opaque colors and `#rrggbbaa` when the input
@@ -177,7 +186,15 @@ pub use postprocess::util::text_color_for_background;
 /// See the [module-level docs][crate] for more info.
 pub fn render_to_svg(source: &str, theme: &MermaidTheme) -> Result<String> {
     let svg = render::render_mermaid(source, theme)?;
-    let svg = postprocess::postprocess(&svg, theme)?;
+    let svg = postprocess::postprocess(&svg, theme, false)?;
+    Ok(svg)
+}
+
+/// Like [`render_to_svg`], but the theme background is painted into the SVG,
+/// so it can be copied or exported and still look the same outside of Zed.
+pub fn render_to_standalone_svg(source: &str, theme: &MermaidTheme) -> Result<String> {
+    let svg = render::render_mermaid(source, theme)?;
+    let svg = postprocess::postprocess(&svg, theme, true)?;
     Ok(svg)
 }
 
@@ -12,6 +12,7 @@
 //! details.
 
 mod accent_colors;
+mod background;
 mod element_fixup;
 mod inject_css;
 mod strip_foreignobject;
@@ -23,7 +24,9 @@ use quick_xml::events::Event;
 
 use crate::MermaidTheme;
 
-pub(super) fn postprocess(svg: &str, theme: &MermaidTheme) -> Result<String> {
+/// With `standalone`, the theme background is painted into the SVG itself,
+/// so that it looks the same outside of Zed.
+pub(super) fn postprocess(svg: &str, theme: &MermaidTheme, standalone: bool) -> Result<String> {
     // merman 0.6 already applies the generic resvg-safe cleanup before this point.
     // The remaining passes are Zed-specific theme and accent adjustments.
     let svg_id = extract_svg_id(svg);
@@ -40,6 +43,7 @@ pub(super) fn postprocess(svg: &str, theme: &MermaidTheme) -> Result<String> {
 
     let events = accent_colors::process(events, theme);
     let events = inject_css::process(events, theme, &svg_id);
+    let events = background::process(events, theme, standalone);
 
     let mut writer = quick_xml::Writer::new(Vec::with_capacity(svg.len()));
     for event in events {
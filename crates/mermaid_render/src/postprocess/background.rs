@@ -0,0 +1,120 @@
+//! Paints the theme background behind the diagram, for SVGs that leave Zed.
+//!
+//! The root `<svg>` only carries a `background-color` style, which rasterizers
+//! ignore and which is lost when the SVG is embedded elsewhere, so exported
+//! diagrams would otherwise be transparent. This pass inserts a `<rect>` that
+//! covers the `viewBox` as the first child of the root `<svg>`:
+//!
+//! ```xml
+//! <!-- before --> <svg viewBox="-8 -8 200 100"><g>...</g></svg>
+//! <!-- after  --> <svg viewBox="-8 -8 200 100"><rect x="-8" y="-8" width="200" height="100" fill="#ffffff"/><g>...</g></svg>
+//! ```
+
+use std::collections::VecDeque;
+
+use anyhow::Result;
+use quick_xml::events::{BytesStart, Event};
+
+use crate::MermaidTheme;
+
+struct Background<'a, I> {
+    inner: I,
+    fill: String,
+    svg_seen: bool,
+    pending: VecDeque<Event<'a>>,
+}
+
+impl<'a, I: Iterator<Item = Result<Event<'a>>>> Iterator for Background<'a, I> {
+    type Item = Result<Event<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(event) = self.pending.pop_front() {
+            return Some(Ok(event));
+        }
+
+        let event = match self.inner.next()? {
+            Ok(ev) => ev,
+            Err(e) => return Some(Err(e)),
+        };
+
+        if self.svg_seen {
+            return Some(Ok(event));
+        }
+        if let Event::Start(e) = &event
+            && e.name().as_ref() == b"svg"
+        {
+            self.svg_seen = true;
+            let rect = match background_rect(e, &self.fill) {
+                Ok(rect) => rect,
+                Err(e) => return Some(Err(e)),
+            };
+            self.pending.push_back(Event::Empty(rect));
+        }
+        Some(Ok(event))
+    }
+}
+
+fn background_rect<'a>(svg: &BytesStart<'_>, fill: &str) -> Result<BytesStart<'a>> {
+    let view_box = svg
+        .try_get_attribute("viewBox")?
+        .map(|attr| attr.unescape_value().map(|value| value.into_owned()))
+        .transpose()?;
+    let view_box = view_box.as_deref().and_then(|view_box| {
+        let mut values = view_box
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|value| !value.is_empty());
+        let [x, y, width, height] = std::array::from_fn(|_| values.next());
+        Some([x?, y?, width?, height?])
+    });
+    let [x, y, width, height] = view_box.unwrap_or(["0", "0", "100%", "100%"]);
+
+    let mut rect = BytesStart::new("rect");
+    rect.push_attribute(("x", x));
+    rect.push_attribute(("y", y));
+    rect.push_attribute(("width", width));
+    rect.push_attribute(("height", height));
+    rect.push_attribute(("fill", fill));
+    Ok(rect)
+}
+
+/// Passes the events through unchanged, unless `enabled`.
+pub(super) fn process<'a>(
+    events: impl Iterator<Item = Result<Event<'a>>>,
+    theme: &MermaidTheme,
+    enabled: bool,
+) -> impl Iterator<Item = Result<Event<'a>>> {
+    Background {
+        inner: events,
+        fill: crate::css_color(theme.background),
+        svg_seen: !enabled,
+        pending: VecDeque::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quick_xml::Reader;
+
+    use super::*;
+    use crate::postprocess::ReaderIter;
+
+    fn run(svg: &str) -> String {
+        let mut reader = Reader::from_str(svg);
+        reader.config_mut().check_end_names = false;
+        let theme = MermaidTheme::neutral_light();
+        let events = process(ReaderIter::new(reader), &theme, true);
+        let mut writer = quick_xml::Writer::new(Vec::new());
+        for event in events {
+            writer.write_event(event.unwrap()).unwrap();
+        }
+        String::from_utf8(writer.into_inner()).unwrap()
+    }
+
+    #[test]
+    fn covers_the_view_box() {
+        assert_eq!(
+            run(r#"<svg id="m" viewBox="-8 -8 200 100"><g><rect width="1" height="1"/></g></svg>"#),
+            r##"<svg id="m" viewBox="-8 -8 200 100"><rect x="-8" y="-8" width="200" height="100" fill="#ffffff"/><g><rect width="1" height="1"/></g></svg>"##
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use gpui::{App, Context, Entity};
-use language::{self, Buffer, BufferEditSource, TransactionId};
+use language::{self, Buffer, BufferEditSource, TransactionId, UndoTreeTarget, UndoTreeTravel};
 use std::{
     collections::HashMap,
     ops::Range,
@@ -514,6 +514,23 @@ impl MultiBuffer {
         None
     }
 
+    /// Moves to a different state in the undo tree of a singleton buffer.
+    /// Multi-buffers spanning several buffers only have a linear history.
+    pub fn travel_undo_tree(
+        &mut self,
+        target: UndoTreeTarget,
+        cx: &mut Context<Self>,
+    ) -> Option<UndoTreeTravel> {
+        let buffer = self.as_singleton()?;
+        let travel = buffer.update(cx, |buffer, cx| buffer.travel_undo_tree(target, cx))?;
+        for transaction_id in &travel.undone {
+            cx.emit(Event::TransactionUndone {
+                transaction_id: *transaction_id,
+            });
+        }
+        Some(travel)
+    }
+
     pub fn undo_transaction(&mut self, transaction_id: TransactionId, cx: &mut Context<Self>) {
         if let Some(buffer) = self.as_singleton() {
             buffer.update(cx, |buffer, cx| buffer.undo_transaction(transaction_id, cx));
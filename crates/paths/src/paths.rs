@@ -380,6 +380,21 @@ pub fn snippets_dir() -> &'static PathBuf {
     SNIPPETS_DIR.get_or_init(|| config_dir().join("snippets"))
 }
 
+/// Returns the path to the local history directory.
+///
+/// This is where snapshots of files are stored each time they are saved.
+pub fn local_history_dir() -> &'static PathBuf {
+    static LOCAL_HISTORY_DIR: OnceLock<PathBuf> = OnceLock::new();
+    LOCAL_HISTORY_DIR.get_or_init(|| data_dir().join("local_history"))
+}
+
+/// Returns the path to the socket on which Zed serves its editor and project
+/// tools over MCP, when enabled in the settings.
+pub fn mcp_server_socket() -> &'static PathBuf {
+    static MCP_SERVER_SOCKET: OnceLock<PathBuf> = OnceLock::new();
+    MCP_SERVER_SOCKET.get_or_init(|| data_dir().join("mcp.sock"))
+}
+
 /// Returns the path to the contexts directory.
 ///
 /// This is where the prompts for use with the Assistant are stored.
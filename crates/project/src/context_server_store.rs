@@ -9,8 +9,12 @@ use anyhow::{Context as _, Result};
 use collections::{HashMap, HashSet};
 use context_server::oauth::{self, McpOAuthTokenProvider, OAuthDiscovery, OAuthSession};
 use context_server::transport::{HttpTransport, TransportError};
-use context_server::{ContextServer, ContextServerCommand, ContextServerId};
+use context_server::types::{
+    CreateMessageRequest, CreateMessageResult, ElicitParams, ElicitResult,
+};
+use context_server::{ContextServer, ContextServerCommand, ContextServerDelegate, ContextServerId};
 use credentials_provider::CredentialsProvider;
+use futures::channel::mpsc;
 use futures::future::Either;
 use futures::{FutureExt as _, StreamExt as _, future::join_all};
 use gpui::{
@@ -299,6 +303,85 @@ pub struct ServerStatusChangedEvent {
 
 impl EventEmitter<ServerStatusChangedEvent> for ContextServerStore {}
 
+/// A request from a running context server that has to be answered on the
+/// user's behalf. If no subscriber responds through `sender`, the server
+/// receives an error.
+pub enum ContextServerRequestEvent {
+    /// The server asks for a completion from the user's language model.
+    CreateMessage {
+        server_id: ContextServerId,
+        params: Arc<CreateMessageRequest>,
+        sender: mpsc::Sender<Result<CreateMessageResult>>,
+    },
+    /// The server asks the user to fill in a form.
+    Elicit {
+        server_id: ContextServerId,
+        params: Arc<ElicitParams>,
+        sender: mpsc::Sender<Result<ElicitResult>>,
+    },
+}
+
+impl EventEmitter<ContextServerRequestEvent> for ContextServerStore {}
+
+/// Forwards the requests of a context server to the store's subscribers.
+struct StoreDelegate {
+    store: WeakEntity<ContextServerStore>,
+}
+
+impl StoreDelegate {
+    fn forward<T: 'static + Send>(
+        &self,
+        event: impl FnOnce(mpsc::Sender<Result<T>>) -> ContextServerRequestEvent,
+        mut cx: AsyncApp,
+    ) -> Task<Result<T>> {
+        let (sender, mut receiver) = mpsc::channel(1);
+        let emitted = self.store.update(&mut cx, |_, cx| cx.emit(event(sender)));
+        cx.background_spawn(async move {
+            emitted?;
+            receiver
+                .next()
+                .await
+                .context("the request was not handled")?
+        })
+    }
+}
+
+impl ContextServerDelegate for StoreDelegate {
+    fn create_message(
+        &self,
+        server_id: ContextServerId,
+        params: CreateMessageRequest,
+        cx: AsyncApp,
+    ) -> Task<Result<CreateMessageResult>> {
+        let params = Arc::new(params);
+        self.forward(
+            |sender| ContextServerRequestEvent::CreateMessage {
+                server_id,
+                params,
+                sender,
+            },
+            cx,
+        )
+    }
+
+    fn elicit(
+        &self,
+        server_id: ContextServerId,
+        params: ElicitParams,
+        cx: AsyncApp,
+    ) -> Task<Result<ElicitResult>> {
+        let params = Arc::new(params);
+        self.forward(
+            |sender| ContextServerRequestEvent::Elicit {
+                server_id,
+                params,
+                sender,
+            },
+            cx,
+        )
+    }
+}
+
 impl ContextServerStore {
     pub fn local(
         worktree_store: Entity<WorktreeStore>,
@@ -699,6 +782,18 @@ impl ContextServerStore {
         ) {
             self.stop_server(&id, cx).log_err();
         }
+        // A headless store has nobody to answer sampling or elicitation requests.
+        if !matches!(
+            self.state,
+            ContextServerStoreState::Local {
+                is_headless: true,
+                ..
+            }
+        ) {
+            server.set_delegate(Arc::new(StoreDelegate {
+                store: cx.weak_entity(),
+            }));
+        }
         let task = cx.spawn({
             let id = server.id();
             let server = server.clone();
@@ -2,17 +2,21 @@ use super::breakpoint_store::{
     BreakpointStore, BreakpointStoreEvent, BreakpointUpdatedReason, SourceBreakpoint,
 };
 use super::dap_command::{
-    self, Attach, ConfigurationDone, ContinueCommand, DataBreakpointInfoCommand, DisconnectCommand,
-    EvaluateCommand, Initialize, Launch, LoadedSourcesCommand, LocalDapCommand, LocationsCommand,
-    ModulesCommand, NextCommand, PauseCommand, RestartCommand, RestartStackFrameCommand,
-    ScopesCommand, SetDataBreakpointsCommand, SetExceptionBreakpoints, SetVariableValueCommand,
-    StackTraceCommand, StepBackCommand, StepCommand, StepInCommand, StepOutCommand,
-    TerminateCommand, TerminateThreadsCommand, ThreadsCommand, VariablesCommand,
+    self, Attach, ConfigurationDone, ContinueCommand, DataBreakpointInfoCommand,
+    DisassembleCommand, DisconnectCommand, EvaluateCommand, ExceptionInfoCommand, GotoCommand,
+    GotoTargetsCommand, Initialize, Launch, LoadedSourcesCommand, LocalDapCommand,
+    LocationsCommand, ModulesCommand, NextCommand, PauseCommand, RestartCommand,
+    RestartStackFrameCommand, ScopesCommand, SetDataBreakpointsCommand, SetExceptionBreakpoints,
+    SetInstructionBreakpointsCommand, SetVariableValueCommand, StackTraceCommand, StepBackCommand,
+    StepCommand, StepInCommand, StepInTargetsCommand, StepOutCommand, TerminateCommand,
+    TerminateThreadsCommand, ThreadsCommand, VariablesCommand,
 };
 use super::dap_store::DapStore;
 use crate::debugger::breakpoint_store::BreakpointSessionState;
 use crate::debugger::dap_command::{DataBreakpointContext, ReadMemory};
-use crate::debugger::memory::{self, Memory, MemoryIterator, MemoryPageBuilder, PageAddress};
+use crate::debugger::memory::{
+    self, Memory, MemoryCell, MemoryIterator, MemoryPageBuilder, PageAddress,
+};
 use anyhow::{Context as _, Result, anyhow, bail};
 use base64::Engine;
 use collections::{HashMap, HashSet, IndexMap, TypeIdHashMap};
@@ -178,7 +182,7 @@ pub struct SessionQuirks {
     pub prefer_thread_name: bool,
 }
 
-fn client_source(abs_path: &Path) -> dap::Source {
+pub(super) fn client_source(abs_path: &Path) -> dap::Source {
     dap::Source {
         name: abs_path
             .file_name()
@@ -709,6 +713,10 @@ pub struct Session {
     ignore_breakpoints: bool,
     exception_breakpoints: BTreeMap<String, (ExceptionBreakpointsFilter, IsEnabled)>,
     data_breakpoints: BTreeMap<String, DataBreakpointState>,
+    /// Instruction breakpoints, keyed by the address of the instruction.
+    instruction_breakpoints: BTreeMap<u64, dap::InstructionBreakpoint>,
+    /// The thread that was stopped by an exception, when that's why the session last stopped.
+    exception_thread: Option<ThreadId>,
     background_tasks: Vec<Task<()>>,
     restart_task: Option<Task<()>>,
     task_context: SharedTaskContext,
@@ -883,6 +891,8 @@ impl Session {
                 ignore_breakpoints: false,
                 breakpoint_store,
                 data_breakpoints: Default::default(),
+                instruction_breakpoints: Default::default(),
+                exception_thread: None,
                 exception_breakpoints: Default::default(),
                 label,
                 adapter,
@@ -1465,6 +1475,10 @@ impl Session {
         self.push_to_history();
 
         self.state.stopped();
+        self.exception_thread = event
+            .thread_id
+            .filter(|_| matches!(event.reason, dap::StoppedEventReason::Exception))
+            .map(ThreadId);
         // todo(debugger): Find a clean way to get around the clone
         let breakpoint_store = self.breakpoint_store.clone();
         if let Some((local, path)) = self.as_running_mut().and_then(|local| {
@@ -1528,11 +1542,15 @@ impl Session {
             Events::Stopped(event) => self.handle_stopped_event(event, cx),
             Events::Continued(event) => {
                 if event.all_threads_continued.unwrap_or_default() {
+                    self.exception_thread = None;
                     self.active_snapshot.thread_states.continue_all_threads();
                     self.breakpoint_store.update(cx, |store, cx| {
                         store.remove_active_position(Some(self.session_id()), cx)
                     });
                 } else {
+                    if self.exception_thread == Some(ThreadId(event.thread_id)) {
+                        self.exception_thread = None;
+                    }
                     self.active_snapshot
                         .thread_states
                         .continue_thread(ThreadId(event.thread_id));
@@ -1898,25 +1916,30 @@ impl Session {
         })
     }
 
-    pub fn write_memory(&mut self, address: u64, data: &[u8], cx: &mut Context<Self>) {
+    pub fn write_memory(
+        &mut self,
+        address: u64,
+        data: &[u8],
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
         let data = base64::engine::general_purpose::STANDARD.encode(data);
-        self.request(
-            WriteMemoryArguments {
-                memory_reference: address.to_string(),
-                data,
-                allow_partial: None,
-                offset: None,
-            },
-            |this, response, cx| {
+        let request = self.state.request_dap(WriteMemoryArguments {
+            memory_reference: address.to_string(),
+            data,
+            allow_partial: None,
+            offset: None,
+        });
+        cx.spawn(async move |this, cx| {
+            let response = request.await;
+            this.update(cx, |this, cx| {
                 this.memory.clear(cx.background_executor());
                 this.invalidate_command_type::<ReadMemory>();
                 this.invalidate_command_type::<VariablesCommand>();
                 cx.emit(SessionEvent::Variables);
-                response.ok()
-            },
-            cx,
-        )
-        .detach();
+            })?;
+            response.with_context(|| format!("failed to write memory at {address:#x}"))?;
+            Ok(())
+        })
     }
     pub fn read_memory(
         &mut self,
@@ -1934,6 +1957,60 @@ impl Session {
         self.memory.memory_range(range)
     }
 
+    /// Disassembles `instruction_count` instructions, starting `instruction_offset` instructions
+    /// away from the one at `memory_reference`.
+    pub fn disassemble(
+        &mut self,
+        memory_reference: String,
+        instruction_offset: i64,
+        instruction_count: u64,
+        cx: &mut Context<Self>,
+    ) -> Task<Option<Vec<dap::DisassembledInstruction>>> {
+        self.request(
+            DisassembleCommand {
+                memory_reference,
+                instruction_offset,
+                instruction_count,
+            },
+            |_, response, _| response.log_err(),
+            cx,
+        )
+    }
+
+    /// Reads `count` bytes at `address` with a single request, bypassing the page cache that
+    /// [`Self::read_memory`] fills. Bytes the adapter couldn't read are `None`.
+    pub fn read_memory_bytes(
+        &mut self,
+        address: u64,
+        count: u64,
+        cx: &mut Context<Self>,
+    ) -> Task<Vec<MemoryCell>> {
+        let request = self.request(
+            ReadMemory {
+                memory_reference: format!("0x{address:X}"),
+                offset: Some(0),
+                count,
+            },
+            |_, response, _| response.log_err(),
+            cx,
+        );
+        cx.background_spawn(async move {
+            let mut cells = request
+                .await
+                .map(|response| {
+                    response
+                        .content
+                        .iter()
+                        .take(count as usize)
+                        .map(|byte| MemoryCell(Some(*byte)))
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            cells.resize(count as usize, MemoryCell(None));
+            cells
+        })
+    }
+
     fn read_single_page_memory(&mut self, page_start: PageAddress, cx: &mut Context<Self>) {
         _ = maybe!({
             let builder = self.memory.build_page(page_start)?;
@@ -2086,6 +2163,40 @@ impl Session {
         self.send_data_breakpoints(cx);
     }
 
+    pub fn instruction_breakpoints(&self) -> impl Iterator<Item = u64> + '_ {
+        self.instruction_breakpoints.keys().copied()
+    }
+
+    pub fn has_instruction_breakpoint(&self, address: u64) -> bool {
+        self.instruction_breakpoints.contains_key(&address)
+    }
+
+    /// Sets a breakpoint on the instruction at `address`, or removes the one there.
+    pub fn toggle_instruction_breakpoint(&mut self, address: u64, cx: &mut Context<Self>) {
+        if self.instruction_breakpoints.remove(&address).is_none() {
+            self.instruction_breakpoints.insert(
+                address,
+                dap::InstructionBreakpoint {
+                    instruction_reference: format!("0x{address:X}"),
+                    offset: None,
+                    condition: None,
+                    hit_condition: None,
+                    mode: None,
+                },
+            );
+        }
+        self.send_instruction_breakpoints(cx);
+        cx.notify();
+    }
+
+    fn send_instruction_breakpoints(&mut self, cx: &mut Context<Self>) {
+        if let Some(mode) = self.as_running() {
+            let breakpoints = self.instruction_breakpoints.values().cloned().collect();
+            let command = SetInstructionBreakpointsCommand { breakpoints };
+            mode.request(command).detach_and_log_err(cx);
+        }
+    }
+
     pub fn breakpoints_enabled(&self) -> bool {
         self.ignore_breakpoints
     }
@@ -2170,6 +2281,7 @@ impl Session {
     }
 
     pub fn restart_stack_frame(&mut self, stack_frame_id: u64, cx: &mut Context<Self>) {
+        self.exception_thread = None;
         self.request(
             RestartStackFrameCommand { stack_frame_id },
             Self::empty_response,
@@ -2183,6 +2295,7 @@ impl Session {
             return;
         }
 
+        self.exception_thread = None;
         let supports_dap_restart =
             self.capabilities.supports_restart_request.unwrap_or(false) && !self.is_terminated();
 
@@ -2285,6 +2398,7 @@ impl Session {
 
         let supports_single_thread_execution_requests =
             self.capabilities.supports_single_thread_execution_requests;
+        self.clear_exception_thread_on_resume(thread_id);
         self.active_snapshot
             .thread_states
             .continue_thread(thread_id);
@@ -2335,6 +2449,7 @@ impl Session {
             },
         };
 
+        self.clear_exception_thread_on_resume(thread_id);
         self.active_snapshot.thread_states.process_step(thread_id);
         self.request(
             command,
@@ -2365,8 +2480,46 @@ impl Session {
                 granularity: supports_stepping_granularity.then(|| granularity),
                 single_thread: supports_single_thread_execution_requests,
             },
+            target_id: None,
+        };
+
+        self.clear_exception_thread_on_resume(thread_id);
+        self.active_snapshot.thread_states.process_step(thread_id);
+        self.request(
+            command,
+            Self::on_step_response::<StepInCommand>(thread_id),
+            cx,
+        )
+        .detach();
+    }
+
+    /// The calls on the current line of `frame_id` that can be stepped into.
+    pub fn step_in_targets(
+        &mut self,
+        frame_id: StackFrameId,
+        cx: &mut Context<Self>,
+    ) -> Task<Option<Vec<dap::StepInTarget>>> {
+        self.request(
+            StepInTargetsCommand { frame_id },
+            |_, response, _| response.log_err(),
+            cx,
+        )
+    }
+
+    /// Steps into the call `target_id`, one of the [`Self::step_in_targets`] of the current line.
+    pub fn step_in_target(&mut self, thread_id: ThreadId, target_id: u64, cx: &mut Context<Self>) {
+        self.select_historic_snapshot(None, cx);
+
+        let command = StepInCommand {
+            inner: StepCommand {
+                thread_id: thread_id.0,
+                granularity: None,
+                single_thread: self.capabilities.supports_single_thread_execution_requests,
+            },
+            target_id: Some(target_id),
         };
 
+        self.clear_exception_thread_on_resume(thread_id);
         self.active_snapshot.thread_states.process_step(thread_id);
         self.request(
             command,
@@ -2376,6 +2529,73 @@ impl Session {
         .detach();
     }
 
+    /// The locations on the zero-based `row` of `path` that execution can jump to.
+    pub fn goto_targets(
+        &mut self,
+        path: Arc<Path>,
+        row: u32,
+        cx: &mut Context<Self>,
+    ) -> Task<Option<Vec<dap::GotoTarget>>> {
+        self.request(
+            GotoTargetsCommand {
+                path,
+                line: row as u64 + 1,
+            },
+            |_, response, _| response.log_err(),
+            cx,
+        )
+    }
+
+    /// Moves execution of the thread to `target_id` without running the code in between.
+    pub fn goto(&mut self, thread_id: ThreadId, target_id: u64, cx: &mut Context<Self>) {
+        self.select_historic_snapshot(None, cx);
+
+        if self.exception_thread == Some(thread_id) {
+            self.exception_thread = None;
+        }
+        self.active_snapshot.thread_states.process_step(thread_id);
+        self.request(
+            GotoCommand {
+                thread_id: thread_id.0,
+                target_id,
+            },
+            Self::on_step_response::<GotoCommand>(thread_id),
+            cx,
+        )
+        .detach();
+    }
+
+    /// The thread that was stopped by an exception, when that's why the session last stopped.
+    pub fn exception_thread(&self) -> Option<ThreadId> {
+        self.exception_thread
+    }
+
+    /// Forgets the exception once the thread it stopped runs again. Unless the
+    /// adapter supports single-thread execution, every thread resumes with `thread_id`.
+    fn clear_exception_thread_on_resume(&mut self, thread_id: ThreadId) {
+        let single_thread = self
+            .capabilities
+            .supports_single_thread_execution_requests
+            .unwrap_or_default();
+        if !single_thread || self.exception_thread == Some(thread_id) {
+            self.exception_thread = None;
+        }
+    }
+
+    pub fn exception_info(
+        &mut self,
+        thread_id: ThreadId,
+        cx: &mut Context<Self>,
+    ) -> Task<Option<dap::ExceptionInfoResponse>> {
+        self.request(
+            ExceptionInfoCommand {
+                thread_id: thread_id.0,
+            },
+            |_, response, _| response.log_err(),
+            cx,
+        )
+    }
+
     pub fn step_out(
         &mut self,
         thread_id: ThreadId,
@@ -2399,6 +2619,7 @@ impl Session {
             },
         };
 
+        self.clear_exception_thread_on_resume(thread_id);
         self.active_snapshot.thread_states.process_step(thread_id);
         self.request(
             command,
@@ -2431,6 +2652,7 @@ impl Session {
             },
         };
 
+        self.clear_exception_thread_on_resume(thread_id);
         self.active_snapshot.thread_states.process_step(thread_id);
 
         self.request(
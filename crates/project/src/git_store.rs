@@ -34,11 +34,13 @@ use git::{
     blame::Blame,
     parse_git_remote_url,
     repository::{
-        Branch, BranchesScanResult, CommitData, CommitDetails, CommitDiff, CommitFile,
-        CommitOptions, CreateWorktreeTarget, DiffType, FetchOptions, FileHistoryChangedFileSets,
-        GitCommitTemplate, GitRepository, GitRepositoryCheckpoint, InitialGraphCommitData,
-        LogOrder, LogSource, PushOptions, Remote, RemoteCommandOutput, RepoPath, ResetMode,
-        SearchCommitArgs, UpstreamTrackingStatus, Worktree as GitWorktree, delete_branch_flag,
+        BisectMark, BisectState, Branch, BranchesScanResult, CommitData, CommitDetails, CommitDiff,
+        CommitFile, CommitOptions, CreateWorktreeTarget, DiffType, FetchOptions,
+        FileHistoryChangedFileSets, GitCommitTemplate, GitRepository, GitRepositoryCheckpoint,
+        InitialGraphCommitData, LogOrder, LogSource, MergeStages, PushOptions, RebaseAction,
+        RebaseControl, RebaseProgress, RebaseTodoEntry, Remote, RemoteCommandOutput, RepoPath,
+        ResetMode, SearchCommitArgs, SequencerOperation, UpstreamTrackingStatus,
+        Worktree as GitWorktree, delete_branch_flag, validate_rev,
     },
     stash::{GitStash, StashEntry},
     status::{
@@ -60,7 +62,10 @@ use pending_op::{PendingOp, PendingOpId, PendingOps, PendingOpsSummary};
 use postage::stream::Stream as _;
 use rpc::{
     AnyProtoClient, TypedEnvelope,
-    proto::{self, git_reset, split_repository_update},
+    proto::{
+        self, git_bisect_mark, git_rebase_control, git_reset, rebase_todo_entry,
+        split_repository_update,
+    },
 };
 use serde::Deserialize;
 use settings::{Settings, WorktreeId};
@@ -294,6 +299,12 @@ pub struct RepositoryId(pub u64);
 pub struct MergeDetails {
     pub merge_heads_by_conflicted_path: TreeMap<RepoPath, Vec<Option<SharedString>>>,
     pub message: Option<SharedString>,
+    /// The progress of the interactive rebase in progress, if any.
+    pub rebase: Option<RebaseProgress>,
+    /// The cherry-pick or revert that stopped because of conflicts, if any.
+    pub sequencer: Option<SequencerOperation>,
+    /// The bisect in progress, if any.
+    pub bisect: Option<BisectState>,
 }
 
 #[derive(Clone)]
@@ -674,6 +685,15 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_commit);
         client.add_entity_request_handler(Self::handle_run_hook);
         client.add_entity_request_handler(Self::handle_reset);
+        client.add_entity_request_handler(Self::handle_rebase_todo);
+        client.add_entity_request_handler(Self::handle_rebase);
+        client.add_entity_request_handler(Self::handle_rebase_control);
+        client.add_entity_request_handler(Self::handle_cherry_pick);
+        client.add_entity_request_handler(Self::handle_revert);
+        client.add_entity_request_handler(Self::handle_sequencer_control);
+        client.add_entity_request_handler(Self::handle_bisect_start);
+        client.add_entity_request_handler(Self::handle_bisect_mark);
+        client.add_entity_request_handler(Self::handle_bisect_reset);
         client.add_entity_request_handler(Self::handle_show);
         client.add_entity_request_handler(Self::handle_create_checkpoint);
         client.add_entity_request_handler(Self::handle_create_archive_checkpoint);
@@ -682,6 +702,8 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_compare_checkpoints);
         client.add_entity_request_handler(Self::handle_diff_checkpoints);
         client.add_entity_request_handler(Self::handle_load_commit_diff);
+        client.add_entity_request_handler(Self::handle_load_merge_stages);
+        client.add_entity_request_handler(Self::handle_load_committed_bytes);
         client.add_entity_request_handler(Self::handle_checkout_files);
         client.add_entity_request_handler(Self::handle_open_commit_message_buffer);
         client.add_entity_request_handler(Self::handle_set_index_text);
@@ -3459,6 +3481,44 @@ impl GitStore {
         })
     }
 
+    async fn handle_load_merge_stages(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitLoadMergeStages>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitLoadMergeStagesResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let path = RepoPath::from_proto(&envelope.payload.path)?;
+
+        let stages = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.load_merge_stages(path)
+            })
+            .await??;
+        Ok(proto::GitLoadMergeStagesResponse {
+            base: stages.base,
+            ours: stages.ours,
+            theirs: stages.theirs,
+        })
+    }
+
+    async fn handle_load_committed_bytes(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitLoadCommittedBytes>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitLoadCommittedBytesResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let path = RepoPath::from_proto(&envelope.payload.path)?;
+
+        let content = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.load_committed_bytes(path)
+            })
+            .await??;
+        Ok(proto::GitLoadCommittedBytesResponse { content })
+    }
+
     async fn handle_reset(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::GitReset>,
@@ -3470,6 +3530,7 @@ impl GitStore {
         let mode = match envelope.payload.mode() {
             git_reset::ResetMode::Soft => ResetMode::Soft,
             git_reset::ResetMode::Mixed => ResetMode::Mixed,
+            git_reset::ResetMode::Hard => ResetMode::Hard,
         };
 
         repository_handle
@@ -3480,6 +3541,175 @@ impl GitStore {
         Ok(proto::Ack {})
     }
 
+    async fn handle_rebase_todo(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRebaseTodo>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitRebaseTodoResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let entries = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.rebase_todo(envelope.payload.base)
+            })
+            .await??;
+        Ok(proto::GitRebaseTodoResponse {
+            entries: entries.iter().map(rebase_todo_entry_to_proto).collect(),
+        })
+    }
+
+    async fn handle_rebase(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRebase>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let todo = envelope
+            .payload
+            .todo
+            .into_iter()
+            .map(proto_to_rebase_todo_entry)
+            .collect::<Result<_>>()?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.rebase(envelope.payload.base, todo, cx)
+            })
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_rebase_control(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRebaseControl>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let control = proto_to_rebase_control(envelope.payload.control());
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.rebase_control(control, cx)
+            })
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_cherry_pick(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitCherryPick>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        for commit in &envelope.payload.commits {
+            validate_rev(commit)?;
+        }
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.cherry_pick(envelope.payload.commits, cx)
+            })
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_revert(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRevert>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        validate_rev(&envelope.payload.commit)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.revert(envelope.payload.commit, cx)
+            })
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_sequencer_control(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitSequencerControl>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let operation = proto_to_sequencer_operation(envelope.payload.operation());
+        let control = proto_to_rebase_control(envelope.payload.control());
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.sequencer_control(operation, control, cx)
+            })
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_bisect_start(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitBisectStart>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        validate_rev(&envelope.payload.bad)?;
+        for good in &envelope.payload.good {
+            validate_rev(good)?;
+        }
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.bisect_start(envelope.payload.bad, envelope.payload.good, cx)
+            })
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_bisect_mark(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitBisectMark>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let mark = proto_to_bisect_mark(envelope.payload.mark());
+        if let Some(commit) = &envelope.payload.commit {
+            validate_rev(commit)?;
+        }
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.bisect_mark(mark, envelope.payload.commit, cx)
+            })
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_bisect_reset(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitBisectReset>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.bisect_reset(cx)
+            })
+            .await??;
+        Ok(proto::Ack {})
+    }
+
     async fn handle_checkout_files(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::GitCheckoutFiles>,
@@ -4620,6 +4850,12 @@ impl RepositorySnapshot {
                 .map(|(repo_path, _)| repo_path.to_proto())
                 .collect(),
             merge_message: self.merge.message.as_ref().map(|msg| msg.to_string()),
+            rebase_progress: self.merge.rebase.map(rebase_progress_to_proto),
+            sequencer_operation: self
+                .merge
+                .sequencer
+                .map(|operation| sequencer_operation_to_proto(operation).into()),
+            bisect_state: self.merge.bisect.as_ref().map(bisect_state_to_proto),
             project_id,
             id: self.id.to_proto(),
             abs_path: self.work_directory_abs_path.to_string_lossy().into_owned(),
@@ -4707,6 +4943,12 @@ impl RepositorySnapshot {
                 .map(|(path, _)| path.to_proto())
                 .collect(),
             merge_message: self.merge.message.as_ref().map(|msg| msg.to_string()),
+            rebase_progress: self.merge.rebase.map(rebase_progress_to_proto),
+            sequencer_operation: self
+                .merge
+                .sequencer
+                .map(|operation| sequencer_operation_to_proto(operation).into()),
+            bisect_state: self.merge.bisect.as_ref().map(bisect_state_to_proto),
             project_id,
             id: self.id.to_proto(),
             abs_path: self.work_directory_abs_path.to_string_lossy().into_owned(),
@@ -4864,6 +5106,130 @@ pub fn proto_to_stash(entry: &proto::StashEntry) -> Result<StashEntry> {
     })
 }
 
+fn rebase_todo_entry_to_proto(entry: &RebaseTodoEntry) -> proto::RebaseTodoEntry {
+    let action = match entry.action {
+        RebaseAction::Pick => rebase_todo_entry::RebaseAction::Pick,
+        RebaseAction::Reword => rebase_todo_entry::RebaseAction::Reword,
+        RebaseAction::Squash => rebase_todo_entry::RebaseAction::Squash,
+        RebaseAction::Fixup => rebase_todo_entry::RebaseAction::Fixup,
+        RebaseAction::Drop => rebase_todo_entry::RebaseAction::Drop,
+    };
+    proto::RebaseTodoEntry {
+        action: action.into(),
+        sha: entry.sha.to_string(),
+        subject: entry.subject.to_string(),
+        original_message: entry.original_message.to_string(),
+        message: entry.message.as_ref().map(|message| message.to_string()),
+    }
+}
+
+fn proto_to_rebase_todo_entry(entry: proto::RebaseTodoEntry) -> Result<RebaseTodoEntry> {
+    entry
+        .sha
+        .parse::<Oid>()
+        .with_context(|| format!("invalid commit `{}` in rebase todo", entry.sha))?;
+    let action = match entry.action() {
+        rebase_todo_entry::RebaseAction::Pick => RebaseAction::Pick,
+        rebase_todo_entry::RebaseAction::Reword => RebaseAction::Reword,
+        rebase_todo_entry::RebaseAction::Squash => RebaseAction::Squash,
+        rebase_todo_entry::RebaseAction::Fixup => RebaseAction::Fixup,
+        rebase_todo_entry::RebaseAction::Drop => RebaseAction::Drop,
+    };
+    Ok(RebaseTodoEntry {
+        action,
+        sha: entry.sha.into(),
+        subject: entry.subject.into(),
+        original_message: entry.original_message.into(),
+        message: entry.message.map(SharedString::from),
+    })
+}
+
+fn rebase_progress_to_proto(progress: RebaseProgress) -> proto::RebaseProgress {
+    proto::RebaseProgress {
+        current: progress.current as u64,
+        total: progress.total as u64,
+    }
+}
+
+fn proto_to_rebase_progress(progress: proto::RebaseProgress) -> RebaseProgress {
+    RebaseProgress {
+        current: progress.current as usize,
+        total: progress.total as usize,
+    }
+}
+
+fn rebase_control_to_proto(control: RebaseControl) -> git_rebase_control::Control {
+    match control {
+        RebaseControl::Continue => git_rebase_control::Control::Continue,
+        RebaseControl::Skip => git_rebase_control::Control::Skip,
+        RebaseControl::Abort => git_rebase_control::Control::Abort,
+    }
+}
+
+fn proto_to_rebase_control(control: git_rebase_control::Control) -> RebaseControl {
+    match control {
+        git_rebase_control::Control::Continue => RebaseControl::Continue,
+        git_rebase_control::Control::Skip => RebaseControl::Skip,
+        git_rebase_control::Control::Abort => RebaseControl::Abort,
+    }
+}
+
+fn sequencer_operation_to_proto(operation: SequencerOperation) -> proto::SequencerOperation {
+    match operation {
+        SequencerOperation::CherryPick => proto::SequencerOperation::CherryPick,
+        SequencerOperation::Revert => proto::SequencerOperation::Revert,
+    }
+}
+
+fn proto_to_sequencer_operation(operation: proto::SequencerOperation) -> SequencerOperation {
+    match operation {
+        proto::SequencerOperation::CherryPick => SequencerOperation::CherryPick,
+        proto::SequencerOperation::Revert => SequencerOperation::Revert,
+    }
+}
+
+fn bisect_state_to_proto(state: &BisectState) -> proto::BisectState {
+    proto::BisectState {
+        bad: state.bad.map(|sha| sha.to_string()),
+        good: state.good.iter().map(ToString::to_string).collect(),
+        skipped: state.skipped.iter().map(ToString::to_string).collect(),
+        first_bad: state.first_bad.map(|sha| sha.to_string()),
+    }
+}
+
+fn proto_to_bisect_state(state: proto::BisectState) -> BisectState {
+    BisectState {
+        bad: state.bad.and_then(|sha| sha.parse().log_err()),
+        good: state
+            .good
+            .iter()
+            .filter_map(|sha| sha.parse().log_err())
+            .collect(),
+        skipped: state
+            .skipped
+            .iter()
+            .filter_map(|sha| sha.parse().log_err())
+            .collect(),
+        first_bad: state.first_bad.and_then(|sha| sha.parse().log_err()),
+    }
+}
+
+fn bisect_mark_to_proto(mark: BisectMark) -> git_bisect_mark::Mark {
+    match mark {
+        BisectMark::Good => git_bisect_mark::Mark::Good,
+        BisectMark::Bad => git_bisect_mark::Mark::Bad,
+        BisectMark::Skip => git_bisect_mark::Mark::Skip,
+    }
+}
+
+fn proto_to_bisect_mark(mark: git_bisect_mark::Mark) -> BisectMark {
+    match mark {
+        git_bisect_mark::Mark::Good => BisectMark::Good,
+        git_bisect_mark::Mark::Bad => BisectMark::Bad,
+        git_bisect_mark::Mark::Skip => BisectMark::Skip,
+    }
+}
+
 impl MergeDetails {
     async fn update(
         &mut self,
@@ -4872,6 +5238,12 @@ impl MergeDetails {
     ) -> bool {
         log::debug!("load merge details");
         self.message = backend.merge_message().await.map(SharedString::from);
+        let rebase = backend.rebase_progress().await;
+        let rebase_changed = self.rebase != rebase;
+        self.rebase = rebase;
+        let bisect = backend.bisect_state().await;
+        let bisect_changed = self.bisect != bisect;
+        self.bisect = bisect;
         let heads = backend
             .revparse_batch(vec![
                 "MERGE_HEAD".into(),
@@ -4886,6 +5258,15 @@ impl MergeDetails {
             .into_iter()
             .map(|opt| opt.map(SharedString::from))
             .collect::<Vec<_>>();
+        let sequencer = if heads[1].is_some() {
+            Some(SequencerOperation::CherryPick)
+        } else if heads[3].is_some() {
+            Some(SequencerOperation::Revert)
+        } else {
+            None
+        };
+        let sequencer_changed = self.sequencer != sequencer;
+        self.sequencer = sequencer;
 
         let mut conflicts_changed = false;
 
@@ -4910,7 +5291,7 @@ impl MergeDetails {
                 keep
             });
 
-        conflicts_changed
+        conflicts_changed || rebase_changed || sequencer_changed || bisect_changed
     }
 }
 
@@ -5587,6 +5968,7 @@ impl Repository {
                             mode: match reset_mode {
                                 ResetMode::Soft => git_reset::ResetMode::Soft.into(),
                                 ResetMode::Mixed => git_reset::ResetMode::Mixed.into(),
+                                ResetMode::Hard => git_reset::ResetMode::Hard.into(),
                             },
                         })
                         .await?;
@@ -5597,6 +5979,295 @@ impl Repository {
         })
     }
 
+    pub fn rebase_todo(&mut self, base: String) -> oneshot::Receiver<Result<Vec<RebaseTodoEntry>>> {
+        let id = self.id;
+        self.send_job("rebase_todo", None, move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.rebase_todo(base).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    let response = client
+                        .request(proto::GitRebaseTodo {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            base,
+                        })
+                        .await?;
+                    response
+                        .entries
+                        .into_iter()
+                        .map(proto_to_rebase_todo_entry)
+                        .collect()
+                }
+            }
+        })
+    }
+
+    pub fn rebase(
+        &mut self,
+        base: String,
+        todo: Vec<RebaseTodoEntry>,
+        _cx: &mut App,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            "rebase",
+            Some("git rebase --interactive".into()),
+            move |git_repo, _| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.rebase(base, todo, environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitRebase {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                base,
+                                todo: todo.iter().map(rebase_todo_entry_to_proto).collect(),
+                            })
+                            .await?;
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn rebase_control(
+        &mut self,
+        control: RebaseControl,
+        _cx: &mut App,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        let status = match control {
+            RebaseControl::Continue => "git rebase --continue",
+            RebaseControl::Skip => "git rebase --skip",
+            RebaseControl::Abort => "git rebase --abort",
+        };
+        self.send_job(
+            "rebase_control",
+            Some(status.into()),
+            move |git_repo, _| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.rebase_control(control, environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitRebaseControl {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                control: rebase_control_to_proto(control).into(),
+                            })
+                            .await?;
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn cherry_pick(
+        &mut self,
+        commits: Vec<String>,
+        _cx: &mut App,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            "cherry_pick",
+            Some("git cherry-pick".into()),
+            move |git_repo, _| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.cherry_pick(commits, environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitCherryPick {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                commits,
+                            })
+                            .await?;
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn revert(&mut self, commit: String, _cx: &mut App) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            "revert",
+            Some("git revert".into()),
+            move |git_repo, _| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.revert(commit, environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitRevert {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                commit,
+                            })
+                            .await?;
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn sequencer_control(
+        &mut self,
+        operation: SequencerOperation,
+        control: RebaseControl,
+        _cx: &mut App,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        let flag = match control {
+            RebaseControl::Continue => "--continue",
+            RebaseControl::Skip => "--skip",
+            RebaseControl::Abort => "--abort",
+        };
+        let status = format!("git {} {flag}", operation.command());
+        self.send_job(
+            "sequencer_control",
+            Some(status.into()),
+            move |git_repo, _| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => {
+                        backend
+                            .sequencer_control(operation, control, environment)
+                            .await
+                    }
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitSequencerControl {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                operation: sequencer_operation_to_proto(operation).into(),
+                                control: rebase_control_to_proto(control).into(),
+                            })
+                            .await?;
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn bisect_start(
+        &mut self,
+        bad: String,
+        good: Vec<String>,
+        _cx: &mut App,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            "bisect_start",
+            Some("git bisect start".into()),
+            move |git_repo, _| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.bisect_start(bad, good, environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitBisectStart {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                bad,
+                                good,
+                            })
+                            .await?;
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    /// Marks the given commit, or the current checkout if none is given.
+    pub fn bisect_mark(
+        &mut self,
+        mark: BisectMark,
+        commit: Option<String>,
+        _cx: &mut App,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        let status = format!("git bisect {}", mark.as_str());
+        self.send_job(
+            "bisect_mark",
+            Some(status.into()),
+            move |git_repo, _| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.bisect_mark(mark, commit, environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitBisectMark {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                mark: bisect_mark_to_proto(mark).into(),
+                                commit,
+                            })
+                            .await?;
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn bisect_reset(&mut self, _cx: &mut App) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            "bisect_reset",
+            Some("git bisect reset".into()),
+            move |git_repo, _| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.bisect_reset(environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitBisectReset {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                            })
+                            .await?;
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
     pub fn show(&mut self, commit: String) -> oneshot::Receiver<Result<CommitDetails>> {
         let id = self.id;
         self.send_job("show", None, move |git_repo, _cx| async move {
@@ -5661,6 +6332,61 @@ impl Repository {
         })
     }
 
+    /// Loads the base, ours and theirs versions of a conflicted file.
+    pub fn load_merge_stages(&mut self, path: RepoPath) -> oneshot::Receiver<Result<MergeStages>> {
+        let id = self.id;
+        self.send_job("load_merge_stages", None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.load_merge_stages(path).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    let response = client
+                        .request(proto::GitLoadMergeStages {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            path: path.to_proto(),
+                        })
+                        .await?;
+                    Ok(MergeStages {
+                        base: response.base,
+                        ours: response.ours,
+                        theirs: response.theirs,
+                    })
+                }
+            }
+        })
+    }
+
+    /// Loads the raw contents of a file in HEAD, or `None` if HEAD has no such file.
+    pub fn load_committed_bytes(
+        &mut self,
+        path: RepoPath,
+    ) -> oneshot::Receiver<Result<Option<Vec<u8>>>> {
+        let id = self.id;
+        self.send_job(
+            "load_committed_bytes",
+            None,
+            move |git_repo, _cx| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                        Ok(backend.load_committed_bytes(path).await)
+                    }
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        let response = client
+                            .request(proto::GitLoadCommittedBytes {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                path: path.to_proto(),
+                            })
+                            .await?;
+                        Ok(response.content)
+                    }
+                }
+            },
+        )
+    }
+
     pub fn file_history_changed_files(
         &mut self,
         paths: Vec<RepoPath>,
@@ -8226,6 +8952,24 @@ impl Repository {
             self.snapshot.merge.merge_heads_by_conflicted_path != new_merge_heads;
         self.snapshot.merge.merge_heads_by_conflicted_path = new_merge_heads;
         self.snapshot.merge.message = update.merge_message.map(SharedString::from);
+        let new_rebase = update.rebase_progress.map(proto_to_rebase_progress);
+        if self.snapshot.merge.rebase != new_rebase {
+            cx.emit(RepositoryEvent::StatusesChanged);
+        }
+        self.snapshot.merge.rebase = new_rebase;
+        let new_sequencer = update
+            .sequencer_operation
+            .and_then(proto::SequencerOperation::from_i32)
+            .map(proto_to_sequencer_operation);
+        if self.snapshot.merge.sequencer != new_sequencer {
+            cx.emit(RepositoryEvent::StatusesChanged);
+        }
+        self.snapshot.merge.sequencer = new_sequencer;
+        let new_bisect = update.bisect_state.map(proto_to_bisect_state);
+        if self.snapshot.merge.bisect != new_bisect {
+            cx.emit(RepositoryEvent::StatusesChanged);
+        }
+        self.snapshot.merge.bisect = new_bisect;
         let new_stash_entries = GitStash {
             entries: update
                 .stash_entries
@@ -4233,6 +4233,12 @@ impl LspStore {
         client.add_entity_request_handler(Self::handle_lsp_get_completions);
         client.add_entity_request_handler(Self::handle_lsp_command::<GetDocumentHighlights>);
         client.add_entity_request_handler(Self::handle_lsp_command::<GetDocumentSymbols>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<PrepareCallHierarchy>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetIncomingCalls>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetOutgoingCalls>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<PrepareTypeHierarchy>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSupertypes>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSubtypes>);
         client.add_entity_request_handler(Self::handle_lsp_command::<PrepareRename>);
         client.add_entity_request_handler(Self::handle_lsp_command::<PerformRename>);
         client.add_entity_request_handler(Self::handle_lsp_command::<LinkedEditingRange>);
@@ -8814,6 +8820,34 @@ impl LspStore {
         Ok(())
     }
 
+    /// Replaces the diagnostics of the given files, that were produced by a source other than a language server,
+    /// such as the problem matchers of a task.
+    /// `server_id` is a pseudo id, reserved by the caller to group these diagnostics.
+    pub fn update_external_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        diagnostics: Vec<(PathBuf, Vec<DiagnosticEntry<Unclipped<PointUtf16>>>)>,
+        cx: &mut Context<Self>,
+    ) -> anyhow::Result<()> {
+        let worktree_store = self.worktree_store.read(cx);
+        let updates = diagnostics
+            .into_iter()
+            .filter(|(abs_path, _)| worktree_store.find_worktree(abs_path, cx).is_some())
+            .map(|(abs_path, diagnostics)| DocumentDiagnosticsUpdate {
+                diagnostics: DocumentDiagnostics {
+                    diagnostics,
+                    document_abs_path: abs_path,
+                    version: None,
+                },
+                result_id: None,
+                registration_id: None,
+                server_id,
+                disk_based_sources: Cow::Borrowed(&[]),
+            })
+            .collect();
+        self.merge_diagnostic_entries(updates, |_, _, _| false, cx)
+    }
+
     pub fn merge_diagnostic_entries<'a>(
         &mut self,
         diagnostic_updates: Vec<DocumentDiagnosticsUpdate<'a, DocumentDiagnostics>>,
@@ -9344,14 +9378,10 @@ impl LspStore {
             cx.clone(),
         )
         .await?;
+        let server_to_query = request.language_server_to_query();
         let response = this
             .update(&mut cx, |this, cx| {
-                this.request_lsp(
-                    buffer_handle.clone(),
-                    LanguageServerToQuery::FirstCapable,
-                    request,
-                    cx,
-                )
+                this.request_lsp(buffer_handle.clone(), server_to_query, request, cx)
             })
             .await?;
         this.update(&mut cx, |this, cx| {
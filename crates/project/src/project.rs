@@ -25,6 +25,7 @@ pub mod trusted_worktrees;
 pub mod worktree_store;
 
 mod environment;
+mod task_diagnostics;
 use buffer_diff::BufferDiff;
 use context_server_store::ContextServerStore;
 pub use environment::ProjectEnvironmentEvent;
@@ -158,7 +159,7 @@ pub use prettier::FORMAT_SUFFIX as TEST_PRETTIER_FORMAT_SUFFIX;
 pub use prettier::RANGE_FORMAT_SUFFIX as TEST_PRETTIER_RANGE_FORMAT_SUFFIX;
 pub use task_inventory::{
     BasicContextProvider, ContextProviderWithTasks, DebugScenarioContext, GIT_COMMAND_TASK_TAG,
-    Inventory, TaskContexts, TaskSourceKind,
+    Inventory, TaskContexts, TaskDependencyTree, TaskSourceKind,
 };
 
 pub use buffer_store::ProjectTransaction;
@@ -878,6 +879,37 @@ pub struct DocumentSymbol {
     pub children: Vec<DocumentSymbol>,
 }
 
+/// A symbol in a call or type hierarchy.
+#[derive(Clone, Debug)]
+pub struct HierarchyItem {
+    pub name: String,
+    pub kind: lsp::SymbolKind,
+    pub detail: Option<String>,
+    /// The range of the symbol's name.
+    pub location: Location,
+    /// The item as sent by the language server, which expects it back when
+    /// asked for the item's calls or related types.
+    pub lsp_item: LspHierarchyItem,
+    /// The language server that produced the item, and that must be asked
+    /// about it.
+    pub language_server_id: LanguageServerId,
+}
+
+#[derive(Clone, Debug)]
+pub enum LspHierarchyItem {
+    Call(lsp::CallHierarchyItem),
+    Type(lsp::TypeHierarchyItem),
+}
+
+/// An incoming or outgoing call of a [`HierarchyItem`].
+#[derive(Clone, Debug)]
+pub struct HierarchyCall {
+    /// The caller for incoming calls, or the callee for outgoing calls.
+    pub item: HierarchyItem,
+    /// Where the calls are made, within the caller.
+    pub call_sites: Vec<Location>,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct HoverBlock {
     pub text: String,
@@ -1358,6 +1390,7 @@ impl Project {
                 git_diff_debouncer: DebouncedDelay::new(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
+                    task_diagnostics: HashMap::default(),
                 },
                 node: Some(node),
                 search_history: Self::new_search_history(),
@@ -1601,6 +1634,7 @@ impl Project {
                 git_diff_debouncer: DebouncedDelay::new(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
+                    task_diagnostics: HashMap::default(),
                 },
                 node: Some(node),
                 search_history: Self::new_search_history(),
@@ -1891,6 +1925,7 @@ impl Project {
                 git_diff_debouncer: DebouncedDelay::new(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
+                    task_diagnostics: HashMap::default(),
                 },
                 node: None,
                 search_history: Self::new_search_history(),
@@ -4341,6 +4376,114 @@ impl Project {
         )
     }
 
+    /// Returns the symbols at the given position to show the calls of.
+    pub fn prepare_call_hierarchy<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: T,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            PrepareCallHierarchy { position },
+            cx,
+        )
+    }
+
+    pub fn incoming_calls(
+        &mut self,
+        item: &HierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyCall>>> {
+        let LspHierarchyItem::Call(lsp_item) = &item.lsp_item else {
+            return Task::ready(Err(anyhow!("not a call hierarchy item")));
+        };
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.language_server_id),
+            GetIncomingCalls {
+                item: lsp_item.clone(),
+                server_id: item.language_server_id,
+            },
+            cx,
+        )
+    }
+
+    pub fn outgoing_calls(
+        &mut self,
+        item: &HierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyCall>>> {
+        let LspHierarchyItem::Call(lsp_item) = &item.lsp_item else {
+            return Task::ready(Err(anyhow!("not a call hierarchy item")));
+        };
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.language_server_id),
+            GetOutgoingCalls {
+                item: lsp_item.clone(),
+                server_id: item.language_server_id,
+            },
+            cx,
+        )
+    }
+
+    /// Returns the types at the given position to show the supertypes and subtypes of.
+    pub fn prepare_type_hierarchy<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: T,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            PrepareTypeHierarchy { position },
+            cx,
+        )
+    }
+
+    pub fn supertypes(
+        &mut self,
+        item: &HierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyItem>>> {
+        let LspHierarchyItem::Type(lsp_item) = &item.lsp_item else {
+            return Task::ready(Err(anyhow!("not a type hierarchy item")));
+        };
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.language_server_id),
+            GetSupertypes {
+                item: lsp_item.clone(),
+                server_id: item.language_server_id,
+            },
+            cx,
+        )
+    }
+
+    pub fn subtypes(
+        &mut self,
+        item: &HierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyItem>>> {
+        let LspHierarchyItem::Type(lsp_item) = &item.lsp_item else {
+            return Task::ready(Err(anyhow!("not a type hierarchy item")));
+        };
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.language_server_id),
+            GetSubtypes {
+                item: lsp_item.clone(),
+                server_id: item.language_server_id,
+            },
+            cx,
+        )
+    }
+
     pub fn symbols(&self, query: &str, cx: &mut Context<Self>) -> Task<Result<Vec<Symbol>>> {
         self.lsp_store
             .update(cx, |lsp_store, cx| lsp_store.symbols(query, cx))
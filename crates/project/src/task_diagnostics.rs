@@ -0,0 +1,210 @@
+//! Reports the problems found in the output of running tasks as project diagnostics.
+//!
+//! Each task gets its own pseudo language server id, so that the diagnostics of a task are replaced
+//! on its next run, without touching the diagnostics of the language servers and other tasks.
+
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use collections::{HashMap, HashSet};
+use gpui::{AppContext as _, Context, Entity, WeakEntity};
+use language::{Diagnostic, DiagnosticEntry, PointUtf16, Unclipped};
+use lsp::{DiagnosticSeverity, LanguageServerId, NumberOrString};
+use task::{Problem, ProblemMatcher, ProblemSeverity, SpawnInTerminal, TaskId};
+use terminal::Terminal;
+use util::ResultExt as _;
+
+use crate::Project;
+
+/// How often the output of a running task is matched again, while it keeps printing.
+const PROBLEM_SCAN_INTERVAL: Duration = Duration::from_secs(1);
+
+pub(crate) struct TaskDiagnostics {
+    server_id: LanguageServerId,
+    /// The terminal of the latest run of the task, the only one to report problems.
+    terminal: WeakEntity<Terminal>,
+    matchers: Arc<Vec<ProblemMatcher>>,
+    cwd: Option<PathBuf>,
+    source: String,
+    problems: Vec<Problem>,
+    paths: HashSet<PathBuf>,
+    scan_pending: bool,
+}
+
+impl Project {
+    /// Starts matching the output of the task, spawned in `terminal`, against the task's problem matchers.
+    /// Diagnostics from the previous run of the same task are cleared.
+    pub(crate) fn report_task_problems(
+        &mut self,
+        spawn_task: &SpawnInTerminal,
+        cwd: Option<PathBuf>,
+        terminal: &Entity<Terminal>,
+        cx: &mut Context<Self>,
+    ) {
+        let matchers = spawn_task
+            .problem_matchers
+            .iter()
+            .filter_map(|definition| {
+                ProblemMatcher::new(definition)
+                    .with_context(|| format!("Task `{}`", spawn_task.label))
+                    .log_err()
+            })
+            .collect::<Vec<_>>();
+        let previous_run = self.terminals.task_diagnostics.remove(&spawn_task.id);
+        let server_id = match &previous_run {
+            Some(previous_run) => previous_run.server_id,
+            None => self.languages.next_language_server_id(),
+        };
+        if let Some(previous_run) = previous_run {
+            self.publish_task_diagnostics(server_id, previous_run.paths, Vec::new(), "", cx);
+        }
+        if matchers.is_empty() {
+            return;
+        }
+
+        self.terminals.task_diagnostics.insert(
+            spawn_task.id.clone(),
+            TaskDiagnostics {
+                server_id,
+                terminal: terminal.downgrade(),
+                matchers: Arc::new(matchers),
+                cwd,
+                source: spawn_task.label.clone(),
+                problems: Vec::new(),
+                paths: HashSet::default(),
+                scan_pending: false,
+            },
+        );
+        let task_id = spawn_task.id.clone();
+        cx.subscribe(terminal, move |project, terminal, event, cx| {
+            if let terminal::Event::Wakeup = event {
+                project.schedule_task_problems_scan(&task_id, &terminal, cx);
+            }
+        })
+        .detach();
+    }
+
+    fn schedule_task_problems_scan(
+        &mut self,
+        task_id: &TaskId,
+        terminal: &Entity<Terminal>,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(task_diagnostics) = self.terminals.task_diagnostics.get_mut(task_id) else {
+            return;
+        };
+        if task_diagnostics.scan_pending || task_diagnostics.terminal != *terminal {
+            return;
+        }
+        task_diagnostics.scan_pending = true;
+
+        let task_id = task_id.clone();
+        let terminal = terminal.downgrade();
+        let matchers = task_diagnostics.matchers.clone();
+        let cwd = task_diagnostics.cwd.clone();
+        cx.spawn(async move |project, cx| {
+            cx.background_executor().timer(PROBLEM_SCAN_INTERVAL).await;
+            let output = project.update(cx, |project, cx| {
+                if let Some(task_diagnostics) = project.terminals.task_diagnostics.get_mut(&task_id)
+                {
+                    task_diagnostics.scan_pending = false;
+                }
+                terminal.read_with(cx, |terminal, _| terminal.get_content())
+            })??;
+            let problems = cx
+                .background_spawn(async move {
+                    matchers
+                        .iter()
+                        .flat_map(|matcher| matcher.match_output(&output, cwd.as_deref()))
+                        .collect::<Vec<_>>()
+                })
+                .await;
+            project.update(cx, |project, cx| {
+                let Some(task_diagnostics) = project.terminals.task_diagnostics.get_mut(&task_id)
+                else {
+                    return;
+                };
+                if task_diagnostics.terminal != terminal || task_diagnostics.problems == problems {
+                    return;
+                }
+                let stale_paths = std::mem::replace(
+                    &mut task_diagnostics.paths,
+                    problems
+                        .iter()
+                        .map(|problem| problem.path.clone())
+                        .collect(),
+                );
+                task_diagnostics.problems = problems.clone();
+                let server_id = task_diagnostics.server_id;
+                let source = task_diagnostics.source.clone();
+                project.publish_task_diagnostics(server_id, stale_paths, problems, &source, cx);
+            })
+        })
+        .detach();
+    }
+
+    /// Replaces the task's diagnostics with `problems`, clearing the ones in `stale_paths` that have no problems anymore.
+    fn publish_task_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        stale_paths: HashSet<PathBuf>,
+        problems: Vec<Problem>,
+        source: &str,
+        cx: &mut Context<Self>,
+    ) {
+        let mut diagnostics = stale_paths
+            .into_iter()
+            .map(|path| (path, Vec::new()))
+            .collect::<HashMap<_, _>>();
+        for (group_id, problem) in problems.into_iter().enumerate() {
+            diagnostics
+                .entry(problem.path.clone())
+                .or_default()
+                .push(problem_to_diagnostic(problem, group_id, source));
+        }
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store
+                .update_external_diagnostics(server_id, diagnostics.into_iter().collect(), cx)
+                .log_err();
+        });
+    }
+}
+
+fn problem_to_diagnostic(
+    problem: Problem,
+    group_id: usize,
+    source: &str,
+) -> DiagnosticEntry<Unclipped<PointUtf16>> {
+    let start = PointUtf16::new(
+        problem.line.saturating_sub(1),
+        problem.column.unwrap_or(1).saturating_sub(1),
+    );
+    let end = match problem.end_line {
+        Some(end_line) => PointUtf16::new(
+            end_line.saturating_sub(1),
+            problem.end_column.unwrap_or(1).saturating_sub(1),
+        ),
+        None => match problem.end_column {
+            Some(end_column) => PointUtf16::new(start.row, end_column.saturating_sub(1)),
+            None => start,
+        },
+    };
+    DiagnosticEntry {
+        range: Unclipped(start)..Unclipped(end.max(start)),
+        diagnostic: Diagnostic {
+            source: Some(problem.source.unwrap_or_else(|| source.to_string())),
+            code: problem.code.map(NumberOrString::String),
+            severity: match problem.severity {
+                ProblemSeverity::Error => DiagnosticSeverity::ERROR,
+                ProblemSeverity::Warning => DiagnosticSeverity::WARNING,
+                ProblemSeverity::Info => DiagnosticSeverity::INFORMATION,
+                ProblemSeverity::Hint => DiagnosticSeverity::HINT,
+            },
+            message: problem.message,
+            group_id,
+            is_primary: true,
+            is_disk_based: true,
+            ..Diagnostic::default()
+        },
+    }
+}
@@ -8,7 +8,7 @@ use std::{
     sync::Arc,
 };
 
-use anyhow::Result;
+use anyhow::{Context as _, Result};
 use collections::{HashMap, HashSet, VecDeque};
 use dap::DapRegistry;
 use gpui::{App, AppContext as _, Context, Entity, SharedString, Task, WeakEntity};
@@ -167,6 +167,15 @@ pub enum TaskSourceKind {
     },
 }
 
+/// A task template together with the templates of the tasks it depends on, see [`Inventory::task_dependencies`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskDependencyTree {
+    pub source_kind: TaskSourceKind,
+    pub template: TaskTemplate,
+    /// Trees of the tasks from the template's `depends_on`, in the same order.
+    pub dependencies: Vec<TaskDependencyTree>,
+}
+
 /// A collection of task contexts, derived from the current state of the workspace.
 /// Only contains worktrees that are visible and with their root being a directory.
 #[derive(Debug, Default)]
@@ -729,6 +738,30 @@ impl Inventory {
             .collect()
     }
 
+    /// Looks up the templates of all tasks the given template depends on, recursively.
+    ///
+    /// Dependencies are matched by label against the worktree and global tasks, preferring the ones
+    /// from the same source as the dependent task.
+    /// Fails if a dependency cannot be found or if the dependencies form a cycle.
+    pub fn task_dependencies(
+        &self,
+        source_kind: &TaskSourceKind,
+        template: &TaskTemplate,
+        worktree: Option<WorktreeId>,
+    ) -> Result<TaskDependencyTree> {
+        let candidates = worktree
+            .into_iter()
+            .flat_map(|worktree| self.worktree_templates_from_settings(worktree))
+            .chain(self.global_templates_from_settings())
+            .collect::<Vec<_>>();
+        task_dependency_tree(
+            source_kind.clone(),
+            template.clone(),
+            &candidates,
+            &mut Vec::new(),
+        )
+    }
+
     fn global_templates_from_settings(
         &self,
     ) -> impl '_ + Iterator<Item = (TaskSourceKind, TaskTemplate)> {
@@ -980,6 +1013,53 @@ fn task_lru_comparator(
         })
 }
 
+fn task_dependency_tree(
+    source_kind: TaskSourceKind,
+    template: TaskTemplate,
+    candidates: &[(TaskSourceKind, TaskTemplate)],
+    dependents: &mut Vec<String>,
+) -> Result<TaskDependencyTree> {
+    if dependents.contains(&template.label) {
+        anyhow::bail!(
+            "Task `{}` depends on itself: {} -> {}",
+            template.label,
+            dependents.join(" -> "),
+            template.label
+        );
+    }
+
+    dependents.push(template.label.clone());
+    let dependencies = template
+        .depends_on
+        .iter()
+        .map(|label| {
+            let (dependency_kind, dependency) = candidates
+                .iter()
+                .filter(|(_, candidate)| &candidate.label == label)
+                .min_by_key(|(candidate_kind, _)| candidate_kind != &source_kind)
+                .with_context(|| {
+                    format!(
+                        "Task `{}` depends on unknown task `{label}`",
+                        template.label
+                    )
+                })?;
+            task_dependency_tree(
+                dependency_kind.clone(),
+                dependency.clone(),
+                candidates,
+                dependents,
+            )
+        })
+        .collect::<Result<Vec<_>>>();
+    dependents.pop();
+
+    Ok(TaskDependencyTree {
+        source_kind,
+        template,
+        dependencies: dependencies?,
+    })
+}
+
 pub fn task_source_kind_preference(kind: &TaskSourceKind) -> u32 {
     match kind {
         TaskSourceKind::Lsp { .. } => 0,
@@ -1259,4 +1339,100 @@ mod tests {
         let reresolved = task_contexts.reresolve_task(&TaskSourceKind::UserInput, &stale);
         assert_eq!(reresolved, stale);
     }
+
+    fn template_with_dependencies(label: &str, depends_on: &[&str]) -> TaskTemplate {
+        TaskTemplate {
+            label: label.to_string(),
+            command: format!("echo {label}"),
+            depends_on: depends_on.iter().map(|label| label.to_string()).collect(),
+            ..TaskTemplate::default()
+        }
+    }
+
+    #[test]
+    fn task_dependencies_prefer_the_dependent_task_source() {
+        let worktree_kind = TaskSourceKind::Worktree {
+            id: WorktreeId::from_usize(1),
+            directory_in_worktree: RelPath::empty_arc(),
+            id_base: Cow::Borrowed("worktree"),
+        };
+        let global_kind = TaskSourceKind::AbsPath {
+            id_base: Cow::Borrowed("global"),
+            abs_path: PathBuf::from("/tasks.json"),
+        };
+        let build = template_with_dependencies("build", &[]);
+        let global_build = TaskTemplate {
+            command: "make".to_string(),
+            ..build.clone()
+        };
+        let test = template_with_dependencies("test", &["build"]);
+        let candidates = vec![
+            (global_kind.clone(), global_build),
+            (worktree_kind.clone(), build.clone()),
+            (worktree_kind.clone(), test.clone()),
+        ];
+
+        let tree = task_dependency_tree(
+            worktree_kind.clone(),
+            test.clone(),
+            &candidates,
+            &mut Vec::new(),
+        )
+        .unwrap();
+        assert_eq!(
+            tree,
+            TaskDependencyTree {
+                source_kind: worktree_kind.clone(),
+                template: test,
+                dependencies: vec![TaskDependencyTree {
+                    source_kind: worktree_kind,
+                    template: build,
+                    dependencies: Vec::new(),
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn task_dependencies_reject_cycles_and_unknown_tasks() {
+        let kind = TaskSourceKind::UserInput;
+        let candidates = vec![
+            (kind.clone(), template_with_dependencies("a", &["b"])),
+            (kind.clone(), template_with_dependencies("b", &["c"])),
+            (kind.clone(), template_with_dependencies("c", &["a"])),
+            (kind.clone(), template_with_dependencies("d", &["missing"])),
+            (kind.clone(), template_with_dependencies("e", &["f", "f"])),
+            (kind.clone(), template_with_dependencies("f", &[])),
+        ];
+
+        let error = task_dependency_tree(
+            kind.clone(),
+            candidates[0].1.clone(),
+            &candidates,
+            &mut Vec::new(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Task `a` depends on itself: a -> b -> c -> a"
+        );
+
+        let error = task_dependency_tree(
+            kind.clone(),
+            candidates[3].1.clone(),
+            &candidates,
+            &mut Vec::new(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Task `d` depends on unknown task `missing`"
+        );
+
+        // Depending on the same task twice is not a cycle.
+        let tree =
+            task_dependency_tree(kind, candidates[4].1.clone(), &candidates, &mut Vec::new())
+                .unwrap();
+        assert_eq!(tree.dependencies.len(), 2);
+    }
 }
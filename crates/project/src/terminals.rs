@@ -22,10 +22,11 @@ use util::{
     command::new_std_command, get_default_system_shell, get_system_shell, maybe, rel_path::RelPath,
 };
 
-use crate::{Project, ProjectPath};
+use crate::{Project, ProjectPath, task_diagnostics::TaskDiagnostics};
 
 pub struct Terminals {
     pub(crate) local_handles: Vec<WeakEntity<terminal::Terminal>>,
+    pub(crate) task_diagnostics: HashMap<task::TaskId, TaskDiagnostics>,
 }
 
 impl Project {
@@ -95,6 +96,13 @@ impl Project {
         let (completion_tx, completion_rx) = bounded(1);
 
         let local_path = if is_via_remote { None } else { path.clone() };
+        // Paths in the output of remote tasks can't be matched against the local worktrees.
+        let problems_to_report = (!is_via_remote).then(|| {
+            (
+                spawn_task.clone(),
+                local_path.as_deref().map(Path::to_path_buf),
+            )
+        });
         let task_state = Some(TaskState {
             spawned_task: spawn_task.clone(),
             status: TaskStatus::Running,
@@ -252,6 +260,8 @@ impl Project {
                         settings.max_scroll_history_lines,
                         settings.path_hyperlink_regexes,
                         settings.path_hyperlink_timeout_ms,
+                        settings.shell_integration,
+                        None,
                         is_via_remote,
                         cx.entity_id().as_u64(),
                         Some(completion_tx),
@@ -267,6 +277,9 @@ impl Project {
                 this.terminals
                     .local_handles
                     .push(terminal_handle.downgrade());
+                if let Some((spawn_task, cwd)) = problems_to_report {
+                    this.report_task_problems(&spawn_task, cwd, &terminal_handle, cx);
+                }
 
                 let id = terminal_handle.entity_id();
                 cx.observe_release(&terminal_handle, move |project, _terminal, cx| {
@@ -292,7 +305,18 @@ impl Project {
         cwd: Option<PathBuf>,
         cx: &mut Context<Self>,
     ) -> Task<Result<Entity<Terminal>>> {
-        self.create_terminal_shell_internal(cwd, false, cx)
+        self.create_terminal_shell_internal(cwd, false, None, cx)
+    }
+
+    /// Creates a terminal shell that shows `scrollback`, as captured by
+    /// [`Terminal::scrollback_snapshot`], as history above its first prompt.
+    pub fn create_terminal_shell_with_scrollback(
+        &mut self,
+        cwd: Option<PathBuf>,
+        scrollback: Option<String>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Entity<Terminal>>> {
+        self.create_terminal_shell_internal(cwd, false, scrollback, cx)
     }
 
     /// Creates a local terminal even if the project is remote.
@@ -309,7 +333,7 @@ impl Project {
             // Local project: use project directory like normal terminals
             self.active_project_directory(cx).map(|p| p.to_path_buf())
         };
-        self.create_terminal_shell_internal(working_directory, true, cx)
+        self.create_terminal_shell_internal(working_directory, true, None, cx)
     }
 
     /// Internal method for creating terminal shells.
@@ -319,6 +343,7 @@ impl Project {
         &mut self,
         cwd: Option<PathBuf>,
         force_local: bool,
+        restored_scrollback: Option<String>,
         cx: &mut Context<Self>,
     ) -> Task<Result<Entity<Terminal>>> {
         let path = cwd.map(|p| Arc::from(&*p));
@@ -422,6 +447,8 @@ impl Project {
                         settings.max_scroll_history_lines,
                         settings.path_hyperlink_regexes,
                         settings.path_hyperlink_timeout_ms,
+                        settings.shell_integration,
+                        restored_scrollback,
                         is_via_remote,
                         cx.entity_id().as_u64(),
                         None,
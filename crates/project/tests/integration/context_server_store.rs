@@ -1,5 +1,8 @@
 use anyhow::Result;
 use context_server::test::create_fake_transport;
+use context_server::types::{
+    ElicitAction, ElicitParams, ElicitResult, ElicitationSchema, requests,
+};
 use context_server::{ContextServer, ContextServerId};
 use gpui::{AppContext, AsyncApp, Entity, Subscription, Task, TestAppContext, UpdateGlobal as _};
 use http_client::{FakeHttpClient, Response};
@@ -148,6 +151,85 @@ async fn test_context_server_status_events(cx: &mut TestAppContext) {
         .unwrap();
 }
 
+#[gpui::test]
+async fn test_context_server_requests_are_forwarded(cx: &mut TestAppContext) {
+    const SERVER_ID: &str = "mcp-1";
+
+    let (_fs, project) = setup_context_server_test(cx, json!({"code.rs": ""}), vec![]).await;
+
+    let registry = cx.new(|_| ContextServerDescriptorRegistry::new());
+    let store = cx.new(|cx| {
+        ContextServerStore::test(
+            registry.clone(),
+            project.read(cx).worktree_store(),
+            Some(project.downgrade()),
+            cx,
+        )
+    });
+
+    let transport = Arc::new(create_fake_transport(SERVER_ID, cx.executor()));
+    let server = Arc::new(ContextServer::new(
+        ContextServerId(SERVER_ID.into()),
+        transport.clone(),
+    ));
+    store.update(cx, |store, cx| store.test_start_server(server, cx));
+    cx.run_until_parked();
+
+    let elicit = || {
+        transport.request::<requests::Elicit>(ElicitParams {
+            message: "Which branch?".into(),
+            requested_schema: ElicitationSchema {
+                properties: Default::default(),
+                required: Vec::new(),
+            },
+        })
+    };
+
+    let error = elicit().await.unwrap_err();
+    assert!(
+        error.to_string().contains("not handled"),
+        "Requests nobody answers should fail instead of hanging"
+    );
+
+    let elicited = Rc::new(RefCell::new(Vec::new()));
+    let _subscription = cx.update(|cx| {
+        cx.subscribe(&store, {
+            let elicited = elicited.clone();
+            move |_, event: &ContextServerRequestEvent, _| {
+                if let ContextServerRequestEvent::Elicit {
+                    server_id,
+                    params,
+                    sender,
+                } = event
+                {
+                    elicited
+                        .borrow_mut()
+                        .push((server_id.clone(), params.message.clone()));
+                    let content = json!({ "branch": "main" }).as_object().cloned();
+                    sender
+                        .clone()
+                        .try_send(Ok(ElicitResult {
+                            action: ElicitAction::Accept,
+                            content,
+                        }))
+                        .unwrap();
+                }
+            }
+        })
+    });
+
+    let result = elicit().await.unwrap();
+    assert_eq!(
+        *elicited.borrow(),
+        vec![(
+            ContextServerId(SERVER_ID.into()),
+            "Which branch?".to_string()
+        )]
+    );
+    assert_eq!(result.action, ElicitAction::Accept);
+    assert_eq!(result.content.unwrap()["branch"], "main");
+}
+
 #[gpui::test(iterations = 25)]
 async fn test_context_server_concurrent_starts(cx: &mut TestAppContext) {
     const SERVER_1_ID: &str = "mcp-1";
@@ -24,6 +24,9 @@ mod go_locator {
             show_command: true,
             save: SaveStrategy::default(),
             hooks: Default::default(),
+            depends_on: Vec::new(),
+            depends_order: Default::default(),
+            problem_matchers: Vec::new(),
         };
 
         let scenario = locator
@@ -53,6 +56,9 @@ mod go_locator {
             show_command: true,
             save: SaveStrategy::default(),
             hooks: Default::default(),
+            depends_on: Vec::new(),
+            depends_order: Default::default(),
+            problem_matchers: Vec::new(),
         };
 
         let scenario = locator
@@ -231,6 +237,9 @@ mod go_locator {
             show_command: true,
             save: SaveStrategy::default(),
             hooks: Default::default(),
+            depends_on: Vec::new(),
+            depends_order: Default::default(),
+            problem_matchers: Vec::new(),
         };
 
         let scenario = locator
@@ -267,6 +276,9 @@ mod python_locator {
             show_command: false,
             save: task::SaveStrategy::default(),
             hooks: Default::default(),
+            depends_on: Vec::new(),
+            depends_order: Default::default(),
+            problem_matchers: Vec::new(),
         };
 
         let expected_scenario = DebugScenario {
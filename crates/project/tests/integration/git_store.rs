@@ -1792,3 +1792,364 @@ mod resolve_worktree_tests {
         }
     }
 }
+
+mod rebase_tests {
+    use std::sync::Arc;
+
+    use crate::Project;
+
+    use fs::FakeFs;
+    use git::{
+        Oid,
+        repository::{
+            BisectMark, CommitData, InitialGraphCommitData, RebaseAction, RebaseControl,
+            RebaseProgress, RebaseTodoEntry, SequencerOperation, repo_path,
+        },
+        status::{UnmergedStatus, UnmergedStatusCode},
+    };
+    use gpui::TestAppContext;
+    use serde_json::json;
+    use smallvec::smallvec;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_interactive_rebase_with_conflicts(cx: &mut TestAppContext) {
+        zlog::init_test();
+        cx.update(|cx| {
+            settings::init(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                ".git": {},
+                "a.txt": "a",
+            }),
+        )
+        .await;
+        let dot_git = path!("/project/.git").as_ref();
+
+        let shas = (0..4u8)
+            .map(|n| Oid::from_bytes(&[n + 1; 20]).unwrap())
+            .collect::<Vec<_>>();
+        fs.set_graph_commits(
+            dot_git,
+            shas.iter()
+                .enumerate()
+                .rev()
+                .map(|(ix, sha)| {
+                    Arc::new(InitialGraphCommitData {
+                        sha: *sha,
+                        parents: ix
+                            .checked_sub(1)
+                            .map(|parent| smallvec![shas[parent]])
+                            .unwrap_or_default(),
+                        ref_names: Vec::new(),
+                    })
+                })
+                .collect(),
+        );
+        fs.set_commit_data(
+            dot_git,
+            shas.iter().enumerate().map(|(ix, sha)| {
+                (
+                    CommitData {
+                        sha: *sha,
+                        parents: Default::default(),
+                        author_name: "Author".into(),
+                        author_email: "author@example.com".into(),
+                        commit_timestamp: 0,
+                        subject: format!("Commit {ix}").into(),
+                        message: format!("Commit {ix}").into(),
+                    },
+                    false,
+                )
+            }),
+        );
+        fs.with_git_state(dot_git, true, |state| {
+            state.refs.insert("HEAD".into(), shas[3].to_string());
+        })
+        .unwrap();
+        fs.set_conflicting_commits(dot_git, [shas[1].to_string()]);
+
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        cx.run_until_parked();
+        let repository =
+            project.read_with(cx, |project, cx| project.active_repository(cx).unwrap());
+
+        let base = shas[0].to_string();
+        let todo = repository
+            .update(cx, |repository, _| repository.rebase_todo(base.clone()))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            todo.iter()
+                .map(|entry| (entry.action, entry.subject.as_ref()))
+                .collect::<Vec<_>>(),
+            [
+                (RebaseAction::Pick, "Commit 1"),
+                (RebaseAction::Pick, "Commit 2"),
+                (RebaseAction::Pick, "Commit 3"),
+            ]
+        );
+
+        // The second commit is dropped, and the first one stops with conflicts.
+        let todo = todo
+            .into_iter()
+            .enumerate()
+            .map(|(ix, entry)| RebaseTodoEntry {
+                action: if ix == 1 {
+                    RebaseAction::Drop
+                } else {
+                    RebaseAction::Pick
+                },
+                ..entry
+            })
+            .collect();
+        fs.with_git_state(dot_git, true, |state| {
+            state.unmerged_paths.insert(
+                repo_path("a.txt"),
+                UnmergedStatus {
+                    first_head: UnmergedStatusCode::Updated,
+                    second_head: UnmergedStatusCode::Updated,
+                },
+            );
+        })
+        .unwrap();
+        repository
+            .update(cx, |repository, cx| repository.rebase(base, todo, cx))
+            .await
+            .unwrap()
+            .unwrap();
+        cx.run_until_parked();
+        repository.read_with(cx, |repository, _| {
+            assert_eq!(
+                repository.snapshot().merge.rebase,
+                Some(RebaseProgress {
+                    current: 1,
+                    total: 3
+                })
+            );
+        });
+
+        // Continuing fails until the conflicts are resolved.
+        assert!(
+            repository
+                .update(cx, |repository, cx| {
+                    repository.rebase_control(RebaseControl::Continue, cx)
+                })
+                .await
+                .unwrap()
+                .is_err()
+        );
+        fs.with_git_state(dot_git, true, |state| state.unmerged_paths.clear())
+            .unwrap();
+        repository
+            .update(cx, |repository, cx| {
+                repository.rebase_control(RebaseControl::Continue, cx)
+            })
+            .await
+            .unwrap()
+            .unwrap();
+        cx.run_until_parked();
+        repository.read_with(cx, |repository, _| {
+            assert_eq!(repository.snapshot().merge.rebase, None);
+        });
+        fs.with_git_state(dot_git, false, |state| {
+            assert_eq!(
+                state.refs.get("HEAD"),
+                Some(&format!("rebased-{}", shas[3]))
+            );
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    async fn test_cherry_pick_with_conflicts(cx: &mut TestAppContext) {
+        zlog::init_test();
+        cx.update(|cx| {
+            settings::init(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                ".git": {},
+                "a.txt": "a",
+            }),
+        )
+        .await;
+        let dot_git = path!("/project/.git").as_ref();
+        fs.with_git_state(dot_git, true, |state| {
+            state.refs.insert("HEAD".into(), "main".into());
+        })
+        .unwrap();
+        fs.set_conflicting_commits(dot_git, ["b".to_string()]);
+
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        cx.run_until_parked();
+        let repository =
+            project.read_with(cx, |project, cx| project.active_repository(cx).unwrap());
+
+        // The second commit stops with conflicts.
+        fs.with_git_state(dot_git, true, |state| {
+            state.unmerged_paths.insert(
+                repo_path("a.txt"),
+                UnmergedStatus {
+                    first_head: UnmergedStatusCode::Updated,
+                    second_head: UnmergedStatusCode::Updated,
+                },
+            );
+        })
+        .unwrap();
+        repository
+            .update(cx, |repository, cx| {
+                repository.cherry_pick(vec!["a".into(), "b".into(), "c".into()], cx)
+            })
+            .await
+            .unwrap()
+            .unwrap();
+        cx.run_until_parked();
+        repository.read_with(cx, |repository, _| {
+            assert_eq!(
+                repository.snapshot().merge.sequencer,
+                Some(SequencerOperation::CherryPick)
+            );
+        });
+        fs.with_git_state(dot_git, false, |state| {
+            assert_eq!(state.refs.get("HEAD"), Some(&"cherry-pick-a".to_string()));
+        })
+        .unwrap();
+
+        // A revert can't be continued while a cherry-pick is in progress.
+        assert!(
+            repository
+                .update(cx, |repository, cx| {
+                    repository.sequencer_control(
+                        SequencerOperation::Revert,
+                        RebaseControl::Continue,
+                        cx,
+                    )
+                })
+                .await
+                .unwrap()
+                .is_err()
+        );
+
+        fs.with_git_state(dot_git, true, |state| state.unmerged_paths.clear())
+            .unwrap();
+        repository
+            .update(cx, |repository, cx| {
+                repository.sequencer_control(
+                    SequencerOperation::CherryPick,
+                    RebaseControl::Continue,
+                    cx,
+                )
+            })
+            .await
+            .unwrap()
+            .unwrap();
+        cx.run_until_parked();
+        repository.read_with(cx, |repository, _| {
+            assert_eq!(repository.snapshot().merge.sequencer, None);
+        });
+        fs.with_git_state(dot_git, false, |state| {
+            assert_eq!(state.refs.get("HEAD"), Some(&"cherry-pick-c".to_string()));
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    async fn test_bisect(cx: &mut TestAppContext) {
+        zlog::init_test();
+        cx.update(|cx| {
+            settings::init(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                ".git": {},
+                "a.txt": "a",
+            }),
+        )
+        .await;
+        let dot_git = path!("/project/.git").as_ref();
+
+        let shas = (0..5u8)
+            .map(|n| Oid::from_bytes(&[n + 1; 20]).unwrap())
+            .collect::<Vec<_>>();
+        fs.set_graph_commits(
+            dot_git,
+            shas.iter()
+                .enumerate()
+                .rev()
+                .map(|(ix, sha)| {
+                    Arc::new(InitialGraphCommitData {
+                        sha: *sha,
+                        parents: ix
+                            .checked_sub(1)
+                            .map(|parent| smallvec![shas[parent]])
+                            .unwrap_or_default(),
+                        ref_names: Vec::new(),
+                    })
+                })
+                .collect(),
+        );
+        fs.with_git_state(dot_git, true, |state| {
+            state.refs.insert("HEAD".into(), shas[4].to_string());
+        })
+        .unwrap();
+
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        cx.run_until_parked();
+        let repository =
+            project.read_with(cx, |project, cx| project.active_repository(cx).unwrap());
+        let head = || {
+            fs.with_git_state(dot_git, false, |state| state.refs.get("HEAD").cloned())
+                .unwrap()
+        };
+
+        repository
+            .update(cx, |repository, cx| {
+                repository.bisect_start(shas[4].to_string(), vec![shas[0].to_string()], cx)
+            })
+            .await
+            .unwrap()
+            .unwrap();
+        cx.run_until_parked();
+        assert_eq!(head(), Some(shas[2].to_string()));
+        repository.read_with(cx, |repository, _| {
+            let bisect = repository.snapshot().merge.bisect.unwrap();
+            assert_eq!(bisect.bad, Some(shas[4]));
+            assert_eq!(bisect.good, vec![shas[0]]);
+            assert_eq!(bisect.first_bad, None);
+        });
+
+        for (mark, expected_head) in [(BisectMark::Bad, shas[1]), (BisectMark::Good, shas[2])] {
+            repository
+                .update(cx, |repository, cx| repository.bisect_mark(mark, None, cx))
+                .await
+                .unwrap()
+                .unwrap();
+            cx.run_until_parked();
+            assert_eq!(head(), Some(expected_head.to_string()));
+        }
+        repository.read_with(cx, |repository, _| {
+            let bisect = repository.snapshot().merge.bisect.unwrap();
+            assert_eq!(bisect.first_bad, Some(shas[2]));
+        });
+
+        repository
+            .update(cx, |repository, cx| repository.bisect_reset(cx))
+            .await
+            .unwrap()
+            .unwrap();
+        cx.run_until_parked();
+        assert_eq!(head(), Some(shas[4].to_string()));
+        repository.read_with(cx, |repository, _| {
+            assert_eq!(repository.snapshot().merge.bisect, None);
+        });
+    }
+}
@@ -4986,6 +4986,126 @@ async fn test_definition(cx: &mut gpui::TestAppContext) {
     }
 }
 
+#[gpui::test]
+async fn test_call_hierarchy(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "fn a() {}\nfn b() { a() }",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                call_hierarchy_provider: Some(lsp::CallHierarchyServerCapability::Simple(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    cx.executor().run_until_parked();
+
+    let uri = lsp::Uri::from_file_path(path!("/dir/a.rs")).unwrap();
+    let lsp_item = |name: &str, line: u32| lsp::CallHierarchyItem {
+        name: name.to_string(),
+        kind: lsp::SymbolKind::FUNCTION,
+        tags: None,
+        detail: None,
+        uri: uri.clone(),
+        range: lsp::Range::new(lsp::Position::new(line, 0), lsp::Position::new(line, 9)),
+        selection_range: lsp::Range::new(lsp::Position::new(line, 3), lsp::Position::new(line, 4)),
+        data: None,
+    };
+
+    fake_server.set_request_handler::<lsp::request::CallHierarchyPrepare, _, _>({
+        let item = lsp_item("a", 0);
+        // Items that can't be opened are skipped rather than failing the request.
+        let unopenable_item = lsp::CallHierarchyItem {
+            uri: lsp::Uri::from_str("untitled:Untitled-1").unwrap(),
+            ..lsp_item("c", 0)
+        };
+        move |params, _| {
+            let items = vec![item.clone(), unopenable_item.clone()];
+            async move {
+                assert_eq!(
+                    params.text_document_position_params.position,
+                    lsp::Position::new(0, 3)
+                );
+                Ok(Some(items))
+            }
+        }
+    });
+    fake_server.set_request_handler::<lsp::request::CallHierarchyIncomingCalls, _, _>({
+        let caller = lsp_item("b", 1);
+        move |params, _| {
+            let caller = caller.clone();
+            async move {
+                assert_eq!(params.item.name, "a");
+                Ok(Some(vec![lsp::CallHierarchyIncomingCall {
+                    from: caller,
+                    from_ranges: vec![lsp::Range::new(
+                        lsp::Position::new(1, 9),
+                        lsp::Position::new(1, 10),
+                    )],
+                }]))
+            }
+        }
+    });
+
+    let items = project
+        .update(cx, |project, cx| {
+            project.prepare_call_hierarchy(&buffer, Point::new(0, 3), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].name, "a");
+    assert_eq!(items[0].language_server_id, fake_server.server.server_id());
+    cx.update(|cx| {
+        assert_eq!(
+            items[0]
+                .location
+                .range
+                .to_offset(items[0].location.buffer.read(cx)),
+            3..4
+        );
+    });
+
+    let calls = project
+        .update(cx, |project, cx| project.incoming_calls(&items[0], cx))
+        .await
+        .unwrap();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].item.name, "b");
+    cx.update(|cx| {
+        let call_sites = calls[0]
+            .call_sites
+            .iter()
+            .map(|location| location.range.to_offset(location.buffer.read(cx)))
+            .collect::<Vec<_>>();
+        assert_eq!(call_sites, [19..20]);
+    });
+}
+
 #[gpui::test]
 async fn test_completions_with_text_edit(cx: &mut gpui::TestAppContext) {
     init_test(cx);
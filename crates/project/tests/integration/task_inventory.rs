@@ -608,6 +608,87 @@ async fn test_zed_tasks_take_precedence_over_vscode(cx: &mut TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_task_dependencies_across_task_sources(cx: &mut TestAppContext) {
+    init_test(cx);
+    let inventory = cx.update(|cx| Inventory::new(cx));
+    let worktree_id = WorktreeId::from_usize(0);
+    let worktree_location = TaskSettingsLocation::Worktree(SettingsLocation {
+        worktree_id,
+        path: rel_path(".zed"),
+    });
+
+    inventory.update(cx, |inventory, _| {
+        inventory
+            .update_file_based_tasks(
+                TaskSettingsLocation::Global(tasks_file()),
+                Some(&mock_tasks_from_names(["lint"])),
+            )
+            .unwrap();
+        inventory
+            .update_file_based_tasks(
+                worktree_location,
+                Some(
+                    &json!([
+                        { "label": "build", "command": "cargo", "args": ["build"] },
+                        {
+                            "label": "check",
+                            "depends_on": ["lint", "build"],
+                            "depends_order": "sequence",
+                        },
+                        { "label": "broken", "depends_on": ["missing"] },
+                    ])
+                    .to_string(),
+                ),
+            )
+            .unwrap();
+    });
+
+    let templates = cx
+        .update(|cx| {
+            inventory
+                .read(cx)
+                .list_tasks(None, None, Some(worktree_id), cx)
+        })
+        .await;
+    let template = |label: &str| {
+        templates
+            .iter()
+            .find(|(_, template)| template.label == label)
+            .cloned()
+            .unwrap()
+    };
+
+    let (kind, check) = template("check");
+    assert!(check.is_compound());
+    let tree = inventory
+        .read_with(cx, |inventory, _| {
+            inventory.task_dependencies(&kind, &check, Some(worktree_id))
+        })
+        .unwrap();
+    assert_eq!(
+        tree.dependencies
+            .iter()
+            .map(|dependency| (
+                dependency.source_kind.clone(),
+                dependency.template.label.as_str()
+            ))
+            .collect::<Vec<_>>(),
+        vec![(template("lint").0, "lint"), (kind.clone(), "build")]
+    );
+
+    let (kind, broken) = template("broken");
+    let error = inventory
+        .read_with(cx, |inventory, _| {
+            inventory.task_dependencies(&kind, &broken, Some(worktree_id))
+        })
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Task `broken` depends on unknown task `missing`"
+    );
+}
+
 fn init_test(_cx: &mut TestAppContext) {
     zlog::init_test();
     TaskStore::init(None);
@@ -150,6 +150,7 @@ mod tests {
             skill_file_path: PathBuf::from("/skills/oversized/SKILL.md"),
             load_warnings: Vec::new(),
             disable_model_invocation: false,
+            allowed_tools: Vec::new(),
             embedded_body: None,
         };
         let summary = SkillSummary::from(&skill);
@@ -98,6 +98,18 @@ messages!(
     (GetDocumentHighlightsResponse, Background),
     (GetDocumentSymbols, Background),
     (GetDocumentSymbolsResponse, Background),
+    (PrepareCallHierarchy, Background),
+    (PrepareCallHierarchyResponse, Background),
+    (GetIncomingCalls, Background),
+    (GetIncomingCallsResponse, Background),
+    (GetOutgoingCalls, Background),
+    (GetOutgoingCallsResponse, Background),
+    (PrepareTypeHierarchy, Background),
+    (PrepareTypeHierarchyResponse, Background),
+    (GetSupertypes, Background),
+    (GetSupertypesResponse, Background),
+    (GetSubtypes, Background),
+    (GetSubtypesResponse, Background),
     (GetHover, Background),
     (GetHoverResponse, Background),
     (GetNotifications, Foreground),
@@ -375,6 +387,20 @@ messages!(
     (GitRenameWorktree, Background),
     (GitWorktreeCreatedAt, Background),
     (GitWorktreeCreatedAtResponse, Background),
+    (GitRebaseTodo, Background),
+    (GitRebaseTodoResponse, Background),
+    (GitRebase, Background),
+    (GitRebaseControl, Background),
+    (GitCherryPick, Background),
+    (GitRevert, Background),
+    (GitSequencerControl, Background),
+    (GitBisectStart, Background),
+    (GitBisectMark, Background),
+    (GitBisectReset, Background),
+    (GitLoadMergeStages, Background),
+    (GitLoadMergeStagesResponse, Background),
+    (GitLoadCommittedBytes, Background),
+    (GitLoadCommittedBytesResponse, Background),
     (FindSearchCandidatesChunk, Background),
     (FindSearchCandidatesCancelled, Background),
     (SpawnKernel, Background),
@@ -419,6 +445,12 @@ request_messages!(
     (GetImplementation, GetImplementationResponse),
     (GetDocumentHighlights, GetDocumentHighlightsResponse),
     (GetDocumentSymbols, GetDocumentSymbolsResponse),
+    (PrepareCallHierarchy, PrepareCallHierarchyResponse),
+    (GetIncomingCalls, GetIncomingCallsResponse),
+    (GetOutgoingCalls, GetOutgoingCallsResponse),
+    (PrepareTypeHierarchy, PrepareTypeHierarchyResponse),
+    (GetSupertypes, GetSupertypesResponse),
+    (GetSubtypes, GetSubtypesResponse),
     (GetHover, GetHoverResponse),
     (GetNotifications, GetNotificationsResponse),
     (GetProjectSymbols, GetProjectSymbolsResponse),
@@ -590,6 +622,17 @@ request_messages!(
     (GitRemoveWorktree, Ack),
     (GitRenameWorktree, Ack),
     (GitWorktreeCreatedAt, GitWorktreeCreatedAtResponse),
+    (GitRebaseTodo, GitRebaseTodoResponse),
+    (GitRebase, Ack),
+    (GitRebaseControl, Ack),
+    (GitCherryPick, Ack),
+    (GitRevert, Ack),
+    (GitSequencerControl, Ack),
+    (GitBisectStart, Ack),
+    (GitBisectMark, Ack),
+    (GitBisectReset, Ack),
+    (GitLoadMergeStages, GitLoadMergeStagesResponse),
+    (GitLoadCommittedBytes, GitLoadCommittedBytesResponse),
     (TrustWorktrees, Ack),
     (RestrictWorktrees, Ack),
     (FindSearchCandidatesChunk, Ack),
@@ -654,6 +697,12 @@ entity_messages!(
     GetImplementation,
     GetDocumentHighlights,
     GetDocumentSymbols,
+    PrepareCallHierarchy,
+    GetIncomingCalls,
+    GetOutgoingCalls,
+    PrepareTypeHierarchy,
+    GetSupertypes,
+    GetSubtypes,
     GetHover,
     GetProjectSymbols,
     GetReferences,
@@ -794,6 +843,17 @@ entity_messages!(
     GitRemoveWorktree,
     GitRenameWorktree,
     GitWorktreeCreatedAt,
+    GitRebaseTodo,
+    GitRebase,
+    GitRebaseControl,
+    GitCherryPick,
+    GitRevert,
+    GitSequencerControl,
+    GitBisectStart,
+    GitBisectMark,
+    GitBisectReset,
+    GitLoadMergeStages,
+    GitLoadCommittedBytes,
     TrustWorktrees,
     RestrictWorktrees,
     FindSearchCandidatesChunk,
@@ -193,12 +193,15 @@ impl VsCodeSettings {
             }),
             helix_mode: None,
             hide_mouse: None,
+            hierarchy_panel: None,
             image_viewer: None,
             markdown_preview: None,
             journal: None,
             language_models: None,
             line_indicator_format: None,
+            local_history: None,
             log: None,
+            mcp_server: None,
             message_editor: None,
             node: self.node_binary_settings(),
 
@@ -218,6 +221,7 @@ impl VsCodeSettings {
             terminal: self.terminal_settings_content(),
             theme: Box::new(self.theme_settings_content()),
             title_bar: None,
+            undo_tree_panel: None,
             vim: None,
             vim_mode: None,
             workspace: self.workspace_settings_content(),
@@ -927,6 +931,7 @@ impl VsCodeSettings {
             toolbar: None,
             show_count_badge: None,
             flexible: None,
+            allow_image_file_transmission: None,
         })
     }
 
@@ -960,6 +965,10 @@ impl VsCodeSettings {
             detect_venv: None,
             path_hyperlink_regexes: None,
             path_hyperlink_timeout_ms: None,
+            shell_integration: self.read_bool("terminal.integrated.shellIntegration.enabled"),
+            persisted_scrollback_lines: self
+                .read_bool("terminal.integrated.enablePersistentSessions")
+                .and_then(|enabled| (!enabled).then_some(0)),
         }
     }
 
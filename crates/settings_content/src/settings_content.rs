@@ -197,6 +197,9 @@ pub struct SettingsContent {
 
     pub journal: Option<JournalSettingsContent>,
 
+    /// Settings for the local history of saved files.
+    pub local_history: Option<LocalHistorySettingsContent>,
+
     /// A map of log scopes to the desired log level.
     /// Useful for filtering out noisy logs or enabling more verbose logging.
     ///
@@ -207,10 +210,19 @@ pub struct SettingsContent {
 
     pub language_models: Option<AllLanguageModelSettingsContent>,
 
+    /// Settings for serving Zed's editor and project tools to external agents over MCP.
+    pub mcp_server: Option<McpServerSettingsContent>,
+
     pub outline_panel: Option<OutlinePanelSettingsContent>,
 
     pub project_panel: Option<ProjectPanelSettingsContent>,
 
+    /// Configuration for the call and type hierarchy panel.
+    pub hierarchy_panel: Option<PanelSettingsContent>,
+
+    /// Configuration for the undo tree panel.
+    pub undo_tree_panel: Option<PanelSettingsContent>,
+
     /// Configuration for the Message Editor
     pub message_editor: Option<MessageEditorSettings>,
 
@@ -1061,6 +1073,47 @@ pub enum HourFormat {
     Hour24,
 }
 
+/// Settings for the local history of saved files.
+#[with_fallible_options]
+#[derive(Clone, Default, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq)]
+pub struct LocalHistorySettingsContent {
+    /// Whether to snapshot files into the local history when they are saved.
+    ///
+    /// Default: true
+    pub enabled: Option<bool>,
+    /// Files larger than this many kilobytes are not snapshotted.
+    ///
+    /// Default: 1024
+    pub max_file_size_kb: Option<u64>,
+    /// The maximum number of snapshots to keep for each file.
+    ///
+    /// Default: 50
+    pub max_entries: Option<usize>,
+    /// Snapshots older than this many days are deleted.
+    ///
+    /// Default: 30
+    pub max_age_days: Option<u64>,
+}
+
+/// Settings for serving Zed's editor and project tools to external agents over MCP.
+#[with_fallible_options]
+#[derive(Clone, Default, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq)]
+pub struct McpServerSettingsContent {
+    /// Whether to serve the tools on a local socket that agents can connect
+    /// to by running `zed --mcp`.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// The permission for tools that have no entry in `tools`.
+    ///
+    /// Default: allow
+    pub default: Option<ToolPermissionMode>,
+    /// Per-tool permissions, keyed by tool name (e.g. `run_task`).
+    ///
+    /// Default: {"run_task": "confirm"}
+    pub tools: Option<HashMap<String, ToolPermissionMode>>,
+}
+
 #[with_fallible_options]
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema, MergeFrom, Debug, PartialEq)]
 pub struct OutlinePanelSettingsContent {
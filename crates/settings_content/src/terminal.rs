@@ -63,6 +63,18 @@ pub struct ProjectTerminalSettingsContent {
     ///
     /// Default: 1
     pub path_hyperlink_timeout_ms: Option<u64>,
+    /// Whether to set up bash, zsh and fish to report their prompts and
+    /// commands, which enables jumping between prompts and marks failed commands.
+    ///
+    /// Default: true
+    pub shell_integration: Option<bool>,
+    /// How many lines of output to keep for each terminal when the workspace
+    /// is closed, to show as history above a new shell when it's restored.
+    /// Maximum allowed value is 100_000, all values above that will be treated as 100_000.
+    /// 0 disables restoring scrollback.
+    ///
+    /// Default: 1000
+    pub persisted_scrollback_lines: Option<usize>,
 }
 
 #[with_fallible_options]
@@ -183,6 +195,13 @@ pub struct TerminalSettingsContent {
     ///
     /// Default: "system"
     pub bell: Option<TerminalBell>,
+    /// Whether programs may show images in the terminal by sending the path of
+    /// an image file through the Kitty graphics protocol, rather than its data.
+    /// This lets any program running in the terminal make Zed read files for it,
+    /// so it can only be enabled in the user settings.
+    ///
+    /// Default: false
+    pub allow_image_file_transmission: Option<bool>,
 }
 
 /// Shell configuration to open the terminal with.
@@ -6520,7 +6520,7 @@ fn debugger_page() -> SettingsPage {
 }
 
 fn terminal_page() -> SettingsPage {
-    fn environment_section() -> [SettingsPageItem; 5] {
+    fn environment_section() -> [SettingsPageItem; 8] {
         [
                 SettingsPageItem::SectionHeader("Environment"),
                 SettingsPageItem::DynamicItem(DynamicItem {
@@ -6885,6 +6885,79 @@ fn terminal_page() -> SettingsPage {
                     metadata: None,
                     files: USER | PROJECT,
                 }),
+                SettingsPageItem::SettingItem(SettingItem {
+                    title: "Shell Integration",
+                    description: "Set up bash, zsh and fish to report their prompts and commands to Zed.",
+                    field: Box::new(SettingField {
+                        organization_override: None,
+                        json_path: Some("terminal.shell_integration"),
+                        pick: |settings_content| {
+                            settings_content
+                                .terminal
+                                .as_ref()?
+                                .project
+                                .shell_integration
+                                .as_ref()
+                        },
+                        write: |settings_content, value, _| {
+                            settings_content
+                                .terminal
+                                .get_or_insert_default()
+                                .project
+                                .shell_integration = value;
+                        },
+                    }),
+                    metadata: None,
+                    files: USER | PROJECT,
+                }),
+                SettingsPageItem::SettingItem(SettingItem {
+                    title: "Persisted Scrollback Lines",
+                    description: "Lines of output to restore above a new shell when reopening the workspace (max: 100,000; 0 disables restoring).",
+                    field: Box::new(SettingField {
+                        organization_override: None,
+                        json_path: Some("terminal.persisted_scrollback_lines"),
+                        pick: |settings_content| {
+                            settings_content
+                                .terminal
+                                .as_ref()?
+                                .project
+                                .persisted_scrollback_lines
+                                .as_ref()
+                        },
+                        write: |settings_content, value, _| {
+                            settings_content
+                                .terminal
+                                .get_or_insert_default()
+                                .project
+                                .persisted_scrollback_lines = value;
+                        },
+                    }),
+                    metadata: None,
+                    files: USER | PROJECT,
+                }),
+                SettingsPageItem::SettingItem(SettingItem {
+                    title: "Allow Image File Transmission",
+                    description: "Let programs show images by sending the path of an image file through the Kitty graphics protocol.",
+                    field: Box::new(SettingField {
+                        organization_override: None,
+                        json_path: Some("terminal.allow_image_file_transmission"),
+                        pick: |settings_content| {
+                            settings_content
+                                .terminal
+                                .as_ref()?
+                                .allow_image_file_transmission
+                                .as_ref()
+                        },
+                        write: |settings_content, value, _| {
+                            settings_content
+                                .terminal
+                                .get_or_insert_default()
+                                .allow_image_file_transmission = value;
+                        },
+                    }),
+                    metadata: None,
+                    files: USER,
+                }),
             ]
     }
 
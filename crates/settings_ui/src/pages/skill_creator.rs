@@ -1256,6 +1256,7 @@ fn format_skill_file(
         name: name.to_string(),
         description: description.to_string(),
         disable_model_invocation,
+        allowed_tools: Vec::new(),
     };
     let frontmatter = serde_yaml_ng::to_string(&metadata)
         .context("failed to serialize skill frontmatter as YAML")?;
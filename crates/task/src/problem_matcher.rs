@@ -0,0 +1,684 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context as _, bail};
+use regex::{Captures, Regex};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A problem matcher of a task, turning the task's output into diagnostics.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ProblemMatcherDefinition {
+    /// Name of a built-in problem matcher:
+    /// * `$rustc` — rustc and cargo output
+    /// * `$tsc` — TypeScript compiler output
+    /// * `$gcc` — gcc and clang output
+    /// * `$go` — go compiler and go vet output
+    /// * `$eslint-stylish` — ESLint output in the default, stylish format
+    /// * `$eslint-compact` — ESLint output in the compact format
+    Named(String),
+    /// A problem matcher defined by regular expressions.
+    Custom(CustomProblemMatcher),
+}
+
+/// A problem matcher defined by regular expressions matching the output lines.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct CustomProblemMatcher {
+    /// Name of a built-in problem matcher whose patterns are used when `pattern` is empty.
+    #[serde(default)]
+    pub base: Option<String>,
+    /// Patterns matching consecutive lines of output, which together describe a problem.
+    #[serde(default)]
+    pub pattern: Vec<ProblemPattern>,
+    /// Name of the tool reporting the problems, shown next to the diagnostics.
+    /// Defaults to the task's label.
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Severity of the problems whose patterns don't capture one, defaults to `error`.
+    #[serde(default)]
+    pub severity: Option<ProblemSeverity>,
+    /// Directory to resolve relative file paths in the output against, defaults to the task's `cwd`.
+    #[serde(default)]
+    pub relative_to: Option<String>,
+}
+
+/// A regular expression matching one line of output, and the indices of its capture groups holding the problem's details.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ProblemPattern {
+    /// The regular expression to match the line with.
+    pub regexp: String,
+    /// The group with the file path.
+    #[serde(default)]
+    pub file: Option<usize>,
+    /// The group with the location, either `line`, `line,column` or `line,column,end_line,end_column`.
+    #[serde(default)]
+    pub location: Option<usize>,
+    /// The group with the 1-based line number.
+    #[serde(default)]
+    pub line: Option<usize>,
+    /// The group with the 1-based column number.
+    #[serde(default)]
+    pub column: Option<usize>,
+    /// The group with the 1-based line number the problem ends at.
+    #[serde(default, alias = "endLine")]
+    pub end_line: Option<usize>,
+    /// The group with the 1-based column number the problem ends at.
+    #[serde(default, alias = "endColumn")]
+    pub end_column: Option<usize>,
+    /// The group with the severity, such as `error`, `warning`, `info` or `hint`.
+    #[serde(default)]
+    pub severity: Option<usize>,
+    /// The group with the error code.
+    #[serde(default)]
+    pub code: Option<usize>,
+    /// The group with the message.
+    #[serde(default)]
+    pub message: Option<usize>,
+    /// Whether the last pattern keeps matching the following lines, reporting a problem for each of them.
+    #[serde(default)]
+    pub r#loop: bool,
+}
+
+/// How severe a problem found in the task output is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProblemSeverity {
+    Error,
+    Warning,
+    Info,
+    Hint,
+}
+
+impl ProblemSeverity {
+    fn parse(severity: &str) -> Option<Self> {
+        match severity.to_ascii_lowercase().as_str() {
+            "error" | "fatal" | "fatal error" => Some(Self::Error),
+            "warning" | "warn" => Some(Self::Warning),
+            "info" | "information" | "note" => Some(Self::Info),
+            "hint" => Some(Self::Hint),
+            _ => None,
+        }
+    }
+}
+
+/// A problem found in the task output.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Problem {
+    pub path: PathBuf,
+    /// 1-based line number.
+    pub line: u32,
+    /// 1-based column number.
+    pub column: Option<u32>,
+    pub end_line: Option<u32>,
+    pub end_column: Option<u32>,
+    pub severity: ProblemSeverity,
+    pub code: Option<String>,
+    pub message: String,
+    pub source: Option<String>,
+}
+
+/// A compiled [`ProblemMatcherDefinition`].
+#[derive(Debug)]
+pub struct ProblemMatcher {
+    patterns: Vec<(Regex, ProblemPattern)>,
+    source: Option<String>,
+    severity: ProblemSeverity,
+    relative_to: Option<PathBuf>,
+}
+
+impl ProblemMatcher {
+    pub fn new(definition: &ProblemMatcherDefinition) -> anyhow::Result<Self> {
+        let definition = match definition {
+            ProblemMatcherDefinition::Named(name) => {
+                built_in(name).with_context(|| format!("Unknown problem matcher `{name}`"))?
+            }
+            ProblemMatcherDefinition::Custom(custom) => match &custom.base {
+                Some(base) => {
+                    let base = built_in(base)
+                        .with_context(|| format!("Unknown base problem matcher `{base}`"))?;
+                    CustomProblemMatcher {
+                        base: None,
+                        pattern: if custom.pattern.is_empty() {
+                            base.pattern
+                        } else {
+                            custom.pattern.clone()
+                        },
+                        source: custom.source.clone().or(base.source),
+                        severity: custom.severity.or(base.severity),
+                        relative_to: custom.relative_to.clone(),
+                    }
+                }
+                None => custom.clone(),
+            },
+        };
+
+        if definition.pattern.is_empty() {
+            bail!("Problem matcher has no patterns");
+        }
+        if !definition
+            .pattern
+            .iter()
+            .any(|pattern| pattern.file.is_some())
+        {
+            bail!("Problem matcher patterns don't capture a file");
+        }
+        if !definition
+            .pattern
+            .iter()
+            .any(|pattern| pattern.message.is_some())
+        {
+            bail!("Problem matcher patterns don't capture a message");
+        }
+
+        let patterns = definition
+            .pattern
+            .into_iter()
+            .map(|pattern| {
+                let regex = Regex::new(&pattern.regexp)
+                    .with_context(|| format!("Invalid problem pattern `{}`", pattern.regexp))?;
+                Ok((regex, pattern))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Self {
+            patterns,
+            source: definition.source,
+            severity: definition.severity.unwrap_or(ProblemSeverity::Error),
+            relative_to: definition.relative_to.map(PathBuf::from),
+        })
+    }
+
+    /// Finds all problems in the output, resolving relative file paths against `cwd`.
+    pub fn match_output(&self, output: &str, cwd: Option<&Path>) -> Vec<Problem> {
+        let last_pattern = self.patterns.len() - 1;
+        let mut problems = Vec::new();
+        let mut matched = MatchedProblem::default();
+        let mut next_pattern = 0;
+        for line in output.lines() {
+            let line = line.trim_end();
+            if next_pattern > 0 {
+                let (regex, pattern) = &self.patterns[next_pattern];
+                if let Some(captures) = regex.captures(line) {
+                    let mut problem = matched.clone();
+                    problem.fill(pattern, &captures);
+                    if next_pattern < last_pattern {
+                        matched = problem;
+                        next_pattern += 1;
+                    } else {
+                        problems.extend(self.problem(problem, cwd));
+                        if !pattern.r#loop {
+                            next_pattern = 0;
+                        }
+                    }
+                    continue;
+                }
+                // The sequence is broken, the line may start a new one.
+                next_pattern = 0;
+            }
+
+            let (regex, pattern) = &self.patterns[0];
+            if let Some(captures) = regex.captures(line) {
+                let mut problem = MatchedProblem::default();
+                problem.fill(pattern, &captures);
+                if last_pattern == 0 {
+                    problems.extend(self.problem(problem, cwd));
+                } else {
+                    matched = problem;
+                    next_pattern = 1;
+                }
+            }
+        }
+        problems
+    }
+
+    fn problem(&self, matched: MatchedProblem, cwd: Option<&Path>) -> Option<Problem> {
+        let file = matched.file?;
+        let message = matched.message?;
+        let mut path = PathBuf::from(file);
+        if let Some(relative_to) = &self.relative_to {
+            path = relative_to.join(path);
+        }
+        if let Some(cwd) = cwd {
+            path = cwd.join(path);
+        }
+        Some(Problem {
+            path: util::normalize_path(&path),
+            line: matched.line.unwrap_or(1).max(1),
+            column: matched.column.filter(|column| *column > 0),
+            end_line: matched.end_line,
+            end_column: matched.end_column,
+            severity: matched
+                .severity
+                .as_deref()
+                .and_then(ProblemSeverity::parse)
+                .unwrap_or(self.severity),
+            code: matched.code,
+            message,
+            source: self.source.clone(),
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct MatchedProblem {
+    file: Option<String>,
+    line: Option<u32>,
+    column: Option<u32>,
+    end_line: Option<u32>,
+    end_column: Option<u32>,
+    severity: Option<String>,
+    code: Option<String>,
+    message: Option<String>,
+}
+
+impl MatchedProblem {
+    fn fill(&mut self, pattern: &ProblemPattern, captures: &Captures) {
+        let group = |index: Option<usize>| {
+            index
+                .and_then(|index| captures.get(index))
+                .map(|group| group.as_str().trim())
+                .filter(|group| !group.is_empty())
+        };
+        let number = |index: Option<usize>| group(index).and_then(|group| group.parse().ok());
+
+        if let Some(file) = group(pattern.file) {
+            self.file = Some(file.to_string());
+        }
+        if let Some(location) = group(pattern.location) {
+            let mut numbers = location.split(',').map(|number| number.trim().parse().ok());
+            self.line = numbers.next().flatten();
+            self.column = numbers.next().flatten();
+            self.end_line = numbers.next().flatten();
+            self.end_column = numbers.next().flatten();
+        }
+        for (index, field) in [
+            (pattern.line, &mut self.line),
+            (pattern.column, &mut self.column),
+            (pattern.end_line, &mut self.end_line),
+            (pattern.end_column, &mut self.end_column),
+        ] {
+            if let Some(number) = number(index) {
+                *field = Some(number);
+            }
+        }
+        for (index, field) in [
+            (pattern.severity, &mut self.severity),
+            (pattern.code, &mut self.code),
+            (pattern.message, &mut self.message),
+        ] {
+            if let Some(group) = group(index) {
+                *field = Some(group.to_string());
+            }
+        }
+    }
+}
+
+fn built_in(name: &str) -> Option<CustomProblemMatcher> {
+    // Watch mode prints problems in the same format, and the whole output is matched again on every change.
+    let name = name.strip_suffix("-watch").unwrap_or(name);
+    let (source, pattern) = match name {
+        "$rustc" => (
+            "rustc",
+            vec![
+                ProblemPattern {
+                    regexp: r"^(warning|error)(?:\[(\w+)\])?: (.*)$".to_string(),
+                    severity: Some(1),
+                    code: Some(2),
+                    message: Some(3),
+                    ..ProblemPattern::default()
+                },
+                ProblemPattern {
+                    regexp: r"^\s*--> (.*):(\d+):(\d+)$".to_string(),
+                    file: Some(1),
+                    line: Some(2),
+                    column: Some(3),
+                    ..ProblemPattern::default()
+                },
+            ],
+        ),
+        "$tsc" => (
+            "tsc",
+            vec![ProblemPattern {
+                regexp: r"^([^\s].*)[\(:](\d+)[,:](\d+)(?:\):\s+|\s+-\s+)(error|warning|info)\s+(TS\d+)\s*:\s*(.*)$"
+                    .to_string(),
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                code: Some(5),
+                message: Some(6),
+                ..ProblemPattern::default()
+            }],
+        ),
+        "$gcc" => (
+            "gcc",
+            vec![ProblemPattern {
+                regexp: r"^(.*?):(\d+):(\d*):?\s+(?:fatal\s+)?(warning|error):\s+(.*)$".to_string(),
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                message: Some(5),
+                ..ProblemPattern::default()
+            }],
+        ),
+        "$go" => (
+            "go",
+            vec![ProblemPattern {
+                regexp: r"^\s*(?:vet: )?(\S.*\.go):(\d+):(?:(\d+):)?\s*(.*)$".to_string(),
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                message: Some(4),
+                ..ProblemPattern::default()
+            }],
+        ),
+        "$eslint-stylish" => (
+            "eslint",
+            vec![
+                ProblemPattern {
+                    regexp: r"^([^\s].*)$".to_string(),
+                    file: Some(1),
+                    ..ProblemPattern::default()
+                },
+                ProblemPattern {
+                    regexp: r"^\s+(\d+):(\d+)\s+(error|warning|info)\s+(.*?)(?:\s\s+(\S+))?$"
+                        .to_string(),
+                    line: Some(1),
+                    column: Some(2),
+                    severity: Some(3),
+                    message: Some(4),
+                    code: Some(5),
+                    r#loop: true,
+                    ..ProblemPattern::default()
+                },
+            ],
+        ),
+        "$eslint-compact" => (
+            "eslint",
+            vec![ProblemPattern {
+                regexp: r"^(.+):\sline\s(\d+),\scol\s(\d+),\s(Error|Warning|Info)\s-\s(.+)\s\((.+)\)$"
+                    .to_string(),
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                message: Some(5),
+                code: Some(6),
+                ..ProblemPattern::default()
+            }],
+        ),
+        _ => return None,
+    };
+    Some(CustomProblemMatcher {
+        pattern,
+        source: Some(source.to_string()),
+        ..CustomProblemMatcher::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn match_output(name: &str, output: &str) -> Vec<Problem> {
+        ProblemMatcher::new(&ProblemMatcherDefinition::Named(name.to_string()))
+            .unwrap()
+            .match_output(output, Some(Path::new("/project")))
+    }
+
+    fn problem(
+        path: &str,
+        (line, column): (u32, Option<u32>),
+        severity: ProblemSeverity,
+        code: Option<&str>,
+        message: &str,
+        source: &str,
+    ) -> Problem {
+        Problem {
+            path: PathBuf::from(path),
+            line,
+            column,
+            end_line: None,
+            end_column: None,
+            severity,
+            code: code.map(str::to_string),
+            message: message.to_string(),
+            source: Some(source.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_rustc_problem_matcher() {
+        let output = "\
+   Compiling app v0.1.0 (/project)
+error[E0308]: mismatched types
+ --> src/main.rs:4:18
+  |
+4 |     let x: u32 = \"text\";
+  |            ---   ^^^^^^ expected `u32`, found `&str`
+
+warning: unused variable: `y`
+  --> src/lib.rs:10:9
+   |
+error: could not compile `app` (bin \"app\") due to 1 previous error
+";
+        assert_eq!(
+            match_output("$rustc", output),
+            vec![
+                problem(
+                    "/project/src/main.rs",
+                    (4, Some(18)),
+                    ProblemSeverity::Error,
+                    Some("E0308"),
+                    "mismatched types",
+                    "rustc"
+                ),
+                problem(
+                    "/project/src/lib.rs",
+                    (10, Some(9)),
+                    ProblemSeverity::Warning,
+                    None,
+                    "unused variable: `y`",
+                    "rustc"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_single_line_problem_matchers() {
+        assert_eq!(
+            match_output(
+                "$tsc",
+                "src/index.ts(3,7): error TS2322: Type 'string' is not assignable to type 'number'.\n\
+                 src/util.ts:12:1 - warning TS6133: 'x' is declared but its value is never read."
+            ),
+            vec![
+                problem(
+                    "/project/src/index.ts",
+                    (3, Some(7)),
+                    ProblemSeverity::Error,
+                    Some("TS2322"),
+                    "Type 'string' is not assignable to type 'number'.",
+                    "tsc"
+                ),
+                problem(
+                    "/project/src/util.ts",
+                    (12, Some(1)),
+                    ProblemSeverity::Warning,
+                    Some("TS6133"),
+                    "'x' is declared but its value is never read.",
+                    "tsc"
+                ),
+            ]
+        );
+        assert_eq!(
+            match_output(
+                "$gcc",
+                "main.c:5:3: warning: implicit declaration of function 'foo'\n\
+                 /usr/include/lib.h:1:10: fatal error: 'missing.h' file not found\n\
+                 main.c: In function 'main':"
+            ),
+            vec![
+                problem(
+                    "/project/main.c",
+                    (5, Some(3)),
+                    ProblemSeverity::Warning,
+                    None,
+                    "implicit declaration of function 'foo'",
+                    "gcc"
+                ),
+                problem(
+                    "/usr/include/lib.h",
+                    (1, Some(10)),
+                    ProblemSeverity::Error,
+                    None,
+                    "'missing.h' file not found",
+                    "gcc"
+                ),
+            ]
+        );
+        assert_eq!(
+            match_output(
+                "$go",
+                "# example.com/app\n./main.go:8:2: undefined: foo\nvet: util.go:3: unreachable code"
+            ),
+            vec![
+                problem(
+                    "/project/main.go",
+                    (8, Some(2)),
+                    ProblemSeverity::Error,
+                    None,
+                    "undefined: foo",
+                    "go"
+                ),
+                problem(
+                    "/project/util.go",
+                    (3, None),
+                    ProblemSeverity::Error,
+                    None,
+                    "unreachable code",
+                    "go"
+                ),
+            ]
+        );
+        assert_eq!(
+            match_output(
+                "$eslint-compact",
+                "/project/src/app.js: line 2, col 5, Warning - 'x' is assigned a value but never used. (no-unused-vars)"
+            ),
+            vec![problem(
+                "/project/src/app.js",
+                (2, Some(5)),
+                ProblemSeverity::Warning,
+                Some("no-unused-vars"),
+                "'x' is assigned a value but never used.",
+                "eslint"
+            )]
+        );
+    }
+
+    #[test]
+    fn test_looping_problem_matcher() {
+        let output = "\
+/project/src/app.js
+  1:10  error    'foo' is defined but never used  no-unused-vars
+  3:1   warning  Unexpected console statement     no-console
+
+/project/src/other.js
+  7:3  error  Missing semicolon  semi
+
+✖ 3 problems (2 errors, 1 warning)
+";
+        assert_eq!(
+            match_output("$eslint-stylish", output),
+            vec![
+                problem(
+                    "/project/src/app.js",
+                    (1, Some(10)),
+                    ProblemSeverity::Error,
+                    Some("no-unused-vars"),
+                    "'foo' is defined but never used",
+                    "eslint"
+                ),
+                problem(
+                    "/project/src/app.js",
+                    (3, Some(1)),
+                    ProblemSeverity::Warning,
+                    Some("no-console"),
+                    "Unexpected console statement",
+                    "eslint"
+                ),
+                problem(
+                    "/project/src/other.js",
+                    (7, Some(3)),
+                    ProblemSeverity::Error,
+                    Some("semi"),
+                    "Missing semicolon",
+                    "eslint"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_custom_problem_matcher() {
+        let definition: ProblemMatcherDefinition = serde_json::from_value(serde_json::json!({
+            "pattern": [{
+                "regexp": "^(.*)\\((\\d+,\\d+)\\): (.*)$",
+                "file": 1,
+                "location": 2,
+                "message": 3,
+            }],
+            "severity": "warning",
+            "relative_to": "packages/app",
+        }))
+        .unwrap();
+        let matcher = ProblemMatcher::new(&definition).unwrap();
+        assert_eq!(
+            matcher.match_output(
+                "lib/a.ts(4,2): something is off",
+                Some(Path::new("/project"))
+            ),
+            vec![Problem {
+                path: PathBuf::from("/project/packages/app/lib/a.ts"),
+                line: 4,
+                column: Some(2),
+                end_line: None,
+                end_column: None,
+                severity: ProblemSeverity::Warning,
+                code: None,
+                message: "something is off".to_string(),
+                source: None,
+            }]
+        );
+
+        let extended = ProblemMatcherDefinition::Custom(CustomProblemMatcher {
+            base: Some("$tsc-watch".to_string()),
+            source: Some("typescript".to_string()),
+            ..CustomProblemMatcher::default()
+        });
+        let problems = ProblemMatcher::new(&extended)
+            .unwrap()
+            .match_output("a.ts(1,1): error TS1005: ';' expected.", None);
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].path, PathBuf::from("a.ts"));
+        assert_eq!(problems[0].source.as_deref(), Some("typescript"));
+
+        assert!(ProblemMatcher::new(&ProblemMatcherDefinition::Named("$unknown".into())).is_err());
+        assert!(
+            ProblemMatcher::new(&ProblemMatcherDefinition::Custom(CustomProblemMatcher {
+                pattern: vec![ProblemPattern {
+                    regexp: "(.*)".to_string(),
+                    message: Some(1),
+                    ..ProblemPattern::default()
+                }],
+                ..CustomProblemMatcher::default()
+            }))
+            .is_err(),
+            "patterns without a file should be rejected"
+        );
+    }
+}
@@ -2,6 +2,7 @@
 
 mod adapter_schema;
 mod debug_format;
+mod problem_matcher;
 mod serde_helpers;
 pub mod static_source;
 mod task_template;
@@ -22,9 +23,13 @@ pub use debug_format::{
     AttachRequest, BuildTaskDefinition, DebugRequest, DebugScenario, DebugTaskFile, LaunchRequest,
     Request, TcpArgumentsTemplate, ZedDebugConfig,
 };
+pub use problem_matcher::{
+    CustomProblemMatcher, Problem, ProblemMatcher, ProblemMatcherDefinition, ProblemPattern,
+    ProblemSeverity,
+};
 pub use task_template::{
-    DebugArgsRequest, HideStrategy, RevealStrategy, SaveStrategy, TaskHook, TaskTemplate,
-    TaskTemplates, substitute_variables_in_map, substitute_variables_in_str,
+    DebugArgsRequest, DependsOrder, HideStrategy, RevealStrategy, SaveStrategy, TaskHook,
+    TaskTemplate, TaskTemplates, substitute_variables_in_map, substitute_variables_in_str,
 };
 pub use util::shell::{Shell, ShellKind};
 pub use util::shell_builder::ShellBuilder;
@@ -77,6 +82,8 @@ pub struct SpawnInTerminal {
     pub show_rerun: bool,
     /// Which edited buffers to save before running the task.
     pub save: SaveStrategy,
+    /// Problem matchers to turn the task output into project diagnostics with.
+    pub problem_matchers: Vec<ProblemMatcherDefinition>,
 }
 
 impl SpawnInTerminal {
@@ -9,8 +9,9 @@ use util::serde::default_true;
 use util::{ResultExt, truncate_and_remove_front};
 
 use crate::{
-    AttachRequest, ResolvedTask, RevealTarget, Shell, SpawnInTerminal, TaskContext, TaskId,
-    VariableName, ZED_VARIABLE_NAME_PREFIX, serde_helpers::non_empty_string_vec,
+    AttachRequest, ProblemMatcherDefinition, ResolvedTask, RevealTarget, Shell, SpawnInTerminal,
+    TaskContext, TaskId, VariableName, ZED_VARIABLE_NAME_PREFIX,
+    serde_helpers::non_empty_string_vec,
 };
 
 /// A template definition of a Zed task to run.
@@ -25,6 +26,8 @@ pub struct TaskTemplate {
     /// Human readable name of the task to display in the UI.
     pub label: String,
     /// Executable command to spawn.
+    /// May be omitted for compound tasks, which only run their `depends_on` tasks.
+    #[serde(default)]
     pub command: String,
     /// Arguments to the command.
     #[serde(default)]
@@ -78,6 +81,20 @@ pub struct TaskTemplate {
     /// Hooks that this task runs when emitted.
     #[serde(default)]
     pub hooks: HashSet<TaskHook>,
+    /// Labels of the tasks to run before this one.
+    /// If any of them fails, this task is not run.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// How to run the tasks listed in `depends_on`:
+    /// * `parallel` — start all of them at once (default)
+    /// * `sequence` — run them one after another, in the listed order
+    #[serde(default)]
+    pub depends_order: DependsOrder,
+    /// Problem matchers to find errors and warnings in the task output with, reported as project diagnostics.
+    /// Either names of the built-in matchers (`$rustc`, `$tsc`, `$gcc`, `$go`, `$eslint-stylish`, `$eslint-compact`),
+    /// or custom matchers, defined by regular expressions.
+    #[serde(default)]
+    pub problem_matchers: Vec<ProblemMatcherDefinition>,
 }
 
 #[derive(Deserialize, Eq, PartialEq, Clone, Debug)]
@@ -136,6 +153,17 @@ pub enum SaveStrategy {
     None,
 }
 
+/// How to run the dependencies of a task.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DependsOrder {
+    /// Start all dependencies at once.
+    #[default]
+    Parallel,
+    /// Run the dependencies one after another, in the listed order.
+    Sequence,
+}
+
 /// A group of Tasks defined in a JSON file.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct TaskTemplates(pub Vec<TaskTemplate>);
@@ -155,12 +183,17 @@ impl TaskTemplates {
 }
 
 impl TaskTemplate {
+    /// Whether this template has no command of its own, and only runs the tasks it depends on.
+    pub fn is_compound(&self) -> bool {
+        self.command.trim().is_empty() && !self.depends_on.is_empty()
+    }
+
     /// Replaces all `VariableName` task variables in the task template string fields.
     ///
     /// Every [`ResolvedTask`] gets a [`TaskId`], based on the `id_base` (to avoid collision with various task sources),
     /// and hashes of its template and [`TaskContext`], see [`ResolvedTask`] fields' documentation for more details.
     pub fn resolve_task(&self, id_base: &str, cx: &TaskContext) -> Option<ResolvedTask> {
-        if self.label.trim().is_empty() || self.command.trim().is_empty() {
+        if self.label.trim().is_empty() || (self.command.trim().is_empty() && !self.is_compound()) {
             return None;
         }
 
@@ -239,6 +272,25 @@ impl TaskTemplate {
             &variable_names,
             &mut substituted_variables,
         )?;
+        let problem_matchers = self
+            .problem_matchers
+            .iter()
+            .map(|problem_matcher| match problem_matcher {
+                ProblemMatcherDefinition::Custom(custom) => {
+                    let mut custom = custom.clone();
+                    if let Some(relative_to) = &custom.relative_to {
+                        custom.relative_to = Some(substitute_all_template_variables_in_str(
+                            relative_to,
+                            &task_variables,
+                            &variable_names,
+                            &mut substituted_variables,
+                        )?);
+                    }
+                    Some(ProblemMatcherDefinition::Custom(custom))
+                }
+                named => Some(named.clone()),
+            })
+            .collect::<Option<Vec<_>>>()?;
 
         let task_hash = to_hex_hash(self)
             .context("hashing task template")
@@ -278,15 +330,19 @@ impl TaskTemplate {
                 cwd,
                 full_label,
                 label: human_readable_label,
-                command_label: args_with_substitutions.iter().fold(
-                    command.clone(),
-                    |mut command_label, arg| {
-                        command_label.push(' ');
-                        command_label.push_str(arg);
-                        command_label
-                    },
-                ),
-                command: Some(command),
+                command_label: if self.is_compound() {
+                    self.depends_on.join(", ")
+                } else {
+                    args_with_substitutions.iter().fold(
+                        command.clone(),
+                        |mut command_label, arg| {
+                            command_label.push(' ');
+                            command_label.push_str(arg);
+                            command_label
+                        },
+                    )
+                },
+                command: (!self.is_compound()).then_some(command),
                 args: args_with_substitutions,
                 env,
                 use_new_terminal: self.use_new_terminal,
@@ -299,6 +355,7 @@ impl TaskTemplate {
                 show_command: self.show_command,
                 show_rerun: true,
                 save: self.save,
+                problem_matchers,
             },
         })
     }
@@ -327,6 +384,14 @@ impl TaskTemplate {
             Self::collect_unknown_variables(cwd, &mut variables);
         }
 
+        for problem_matcher in &self.problem_matchers {
+            if let ProblemMatcherDefinition::Custom(custom) = problem_matcher
+                && let Some(relative_to) = &custom.relative_to
+            {
+                Self::collect_unknown_variables(relative_to, &mut variables);
+            }
+        }
+
         variables.into_iter().collect()
     }
 
@@ -516,7 +581,7 @@ mod tests {
         path::{Path, PathBuf},
     };
 
-    use crate::{TaskVariables, VariableName};
+    use crate::{CustomProblemMatcher, TaskVariables, VariableName};
 
     use super::*;
 
@@ -555,6 +620,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_resolving_compound_templates() {
+        let compound_task = TaskTemplate {
+            label: "build all".to_string(),
+            depends_on: vec!["build server".to_string(), "build client".to_string()],
+            depends_order: DependsOrder::Sequence,
+            ..TaskTemplate::default()
+        };
+        assert!(compound_task.is_compound());
+        let resolved_task = compound_task
+            .resolve_task(TEST_ID_BASE, &TaskContext::default())
+            .expect("compound task should resolve without a command");
+        assert_eq!(resolved_task.resolved.command, None);
+        assert_eq!(
+            resolved_task.resolved.command_label,
+            "build server, build client"
+        );
+
+        let task_with_command = TaskTemplate {
+            command: "cargo".to_string(),
+            ..compound_task.clone()
+        };
+        assert!(!task_with_command.is_compound());
+        assert_eq!(
+            task_with_command
+                .resolve_task(TEST_ID_BASE, &TaskContext::default())
+                .unwrap()
+                .resolved
+                .command
+                .as_deref(),
+            Some("cargo")
+        );
+
+        let deserialized: TaskTemplate = serde_json::from_value(serde_json::json!({
+            "label": "build all",
+            "depends_on": ["build server", "build client"],
+            "depends_order": "sequence",
+        }))
+        .unwrap();
+        assert_eq!(deserialized, compound_task);
+    }
+
     #[test]
     fn test_template_cwd_resolution() {
         let task_without_cwd = TaskTemplate {
@@ -1106,6 +1213,48 @@ mod tests {
         assert!(task.unknown_variables().is_empty());
     }
 
+    #[test]
+    fn test_problem_matchers_resolution() {
+        let task = TaskTemplate {
+            label: "check".to_string(),
+            command: "cargo check".to_string(),
+            problem_matchers: vec![
+                ProblemMatcherDefinition::Named("$rustc".to_string()),
+                ProblemMatcherDefinition::Custom(CustomProblemMatcher {
+                    base: Some("$tsc".to_string()),
+                    relative_to: Some("$ZED_WORKTREE_ROOT/web".to_string()),
+                    ..CustomProblemMatcher::default()
+                }),
+            ],
+            ..TaskTemplate::default()
+        };
+        let context = TaskContext {
+            task_variables: TaskVariables::from_iter([(
+                VariableName::WorktreeRoot,
+                "/project".to_string(),
+            )]),
+            ..TaskContext::default()
+        };
+
+        let resolved_task = task.resolve_task(TEST_ID_BASE, &context).unwrap();
+        assert_eq!(
+            resolved_task.resolved.problem_matchers,
+            vec![
+                ProblemMatcherDefinition::Named("$rustc".to_string()),
+                ProblemMatcherDefinition::Custom(CustomProblemMatcher {
+                    base: Some("$tsc".to_string()),
+                    relative_to: Some("/project/web".to_string()),
+                    ..CustomProblemMatcher::default()
+                }),
+            ]
+        );
+        assert!(
+            resolved_task
+                .substituted_variables()
+                .contains(&VariableName::WorktreeRoot)
+        );
+    }
+
     #[test]
     fn test_git_variables_resolution() {
         let task = TaskTemplate {
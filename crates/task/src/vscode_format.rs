@@ -1,9 +1,12 @@
-use anyhow::bail;
+use anyhow::{Context as _, bail};
 use collections::HashMap;
 use serde::Deserialize;
 use util::ResultExt;
 
-use crate::{EnvVariableReplacer, TaskTemplate, TaskTemplates, VariableName};
+use crate::{
+    CustomProblemMatcher, DependsOrder, EnvVariableReplacer, ProblemMatcherDefinition,
+    ProblemPattern, ProblemSeverity, TaskTemplate, TaskTemplates, VariableName,
+};
 
 #[derive(Clone, Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -90,19 +93,28 @@ impl VsCodeTaskDefinition {
         self,
         replacer: &EnvVariableReplacer,
     ) -> anyhow::Result<Option<TaskTemplate>> {
-        if self.other_attributes.contains_key("dependsOn") {
-            log::warn!(
-                "Skipping deserializing of a task `{}` with the unsupported `dependsOn` key",
-                self.label
-            );
-            return Ok(None);
-        }
-        // `type` might not be set in e.g. tasks that use `dependsOn`; we still want to deserialize the whole object though (hence command is an Option),
+        let depends_on = match self.other_attributes.get("dependsOn") {
+            Some(depends_on) => parse_depends_on(depends_on)?,
+            None => Vec::new(),
+        };
+        let depends_order = match self.other_attributes.get("dependsOrder") {
+            Some(serde_json_lenient::Value::String(order)) if order == "sequence" => {
+                DependsOrder::Sequence
+            }
+            _ => DependsOrder::Parallel,
+        };
+        // `type` is not set in compound tasks that only run their `dependsOn` tasks; we still want to deserialize the whole object though (hence command is an Option),
         // as that way we can provide more specific description of why deserialization failed.
-        // E.g. if the command is missing due to `dependsOn` presence, we can check other_attributes first before doing this (and provide nice error message)
-        // catch-all if on value.command presence.
         let Some(command) = self.command else {
-            bail!("Missing `type` field in task");
+            if depends_on.is_empty() {
+                bail!("Missing `type` field in task");
+            }
+            return Ok(Some(TaskTemplate {
+                label: self.label,
+                depends_on,
+                depends_order,
+                ..TaskTemplate::default()
+            }));
         };
 
         let (command, args) = match command {
@@ -113,10 +125,17 @@ impl VsCodeTaskDefinition {
         // Per VSC docs, only `command`, `args` and `options` support variable substitution.
         let command = replacer.replace(&command);
         let args = args.into_iter().map(|arg| replacer.replace(&arg)).collect();
+        let problem_matchers = match self.other_attributes.get("problemMatcher") {
+            Some(problem_matchers) => parse_problem_matchers(problem_matchers, replacer),
+            None => Vec::new(),
+        };
         let mut template = TaskTemplate {
             label: self.label,
             command,
             args,
+            depends_on,
+            depends_order,
+            problem_matchers,
             ..TaskTemplate::default()
         };
         if let Some(options) = self.options {
@@ -127,6 +146,108 @@ impl VsCodeTaskDefinition {
     }
 }
 
+/// Collects the labels of the tasks referenced by `dependsOn`, which is either a single reference or an array of them.
+fn parse_depends_on(value: &serde_json_lenient::Value) -> anyhow::Result<Vec<String>> {
+    match value {
+        serde_json_lenient::Value::Array(dependencies) => {
+            dependencies.iter().map(dependency_label).collect()
+        }
+        dependency => Ok(vec![dependency_label(dependency)?]),
+    }
+}
+
+/// Tasks are referenced either by their label, or by an object identifying a task without one, e.g. `{ "type": "npm", "script": "build" }`.
+fn dependency_label(value: &serde_json_lenient::Value) -> anyhow::Result<String> {
+    match value {
+        serde_json_lenient::Value::String(label) => Ok(label.clone()),
+        serde_json_lenient::Value::Object(_) => {
+            let command = serde_json_lenient::from_value::<Command>(value.clone())
+                .with_context(|| format!("Unsupported task reference in `dependsOn`: {value}"))?;
+            Ok(generate_label(&Some(command)))
+        }
+        _ => bail!("Unsupported task reference in `dependsOn`: {value}"),
+    }
+}
+
+/// Converts `problemMatcher`, which is either a single matcher or an array of them.
+/// Matchers that can't be converted are skipped, so that the task itself is still imported.
+fn parse_problem_matchers(
+    value: &serde_json_lenient::Value,
+    replacer: &EnvVariableReplacer,
+) -> Vec<ProblemMatcherDefinition> {
+    let problem_matchers = match value {
+        serde_json_lenient::Value::Array(problem_matchers) => problem_matchers.as_slice(),
+        problem_matcher => std::slice::from_ref(problem_matcher),
+    };
+    problem_matchers
+        .iter()
+        .filter_map(|problem_matcher| {
+            parse_problem_matcher(problem_matcher, replacer)
+                .with_context(|| format!("Unsupported `problemMatcher`: {problem_matcher}"))
+                .log_err()
+        })
+        .collect()
+}
+
+/// Matchers are referenced either by name, e.g. `"$tsc"`, or defined inline with their `pattern`, extending a named `base` optionally.
+fn parse_problem_matcher(
+    value: &serde_json_lenient::Value,
+    replacer: &EnvVariableReplacer,
+) -> anyhow::Result<ProblemMatcherDefinition> {
+    let problem_matcher = match value {
+        serde_json_lenient::Value::String(name) => {
+            return Ok(ProblemMatcherDefinition::Named(name.clone()));
+        }
+        serde_json_lenient::Value::Object(problem_matcher) => problem_matcher,
+        _ => bail!("expected a name or an object"),
+    };
+    let string = |key: &str| {
+        problem_matcher
+            .get(key)
+            .and_then(|value| value.as_str())
+            .map(str::to_string)
+    };
+
+    let pattern = match problem_matcher.get("pattern") {
+        Some(pattern @ serde_json_lenient::Value::Object(_)) => {
+            vec![serde_json_lenient::from_value::<ProblemPattern>(
+                pattern.clone(),
+            )?]
+        }
+        Some(patterns @ serde_json_lenient::Value::Array(_)) => {
+            serde_json_lenient::from_value::<Vec<ProblemPattern>>(patterns.clone())?
+        }
+        Some(_) => bail!("named patterns are not supported"),
+        None => Vec::new(),
+    };
+    let base = string("base");
+    if base.is_none() && pattern.is_empty() {
+        bail!("neither `base` nor `pattern` are set");
+    }
+    let severity = match string("severity") {
+        Some(severity) => Some(serde_json_lenient::from_value::<ProblemSeverity>(
+            serde_json_lenient::Value::String(severity),
+        )?),
+        None => None,
+    };
+    // `fileLocation` is either `"absolute"`, `"relative"`, `"autoDetect"`, or an array with the directory
+    // to resolve relative paths against, e.g. `["relative", "${workspaceFolder}/src"]`.
+    let relative_to = problem_matcher
+        .get("fileLocation")
+        .and_then(|file_location| file_location.as_array())
+        .and_then(|file_location| file_location.get(1))
+        .and_then(|directory| directory.as_str())
+        .map(|directory| replacer.replace(directory));
+
+    Ok(ProblemMatcherDefinition::Custom(CustomProblemMatcher {
+        base,
+        pattern,
+        source: string("source"),
+        severity,
+        relative_to,
+    }))
+}
+
 /// [`VsCodeTaskFile`] is a superset of Code's task definition format.
 #[derive(Debug, Deserialize, PartialEq)]
 pub struct VsCodeTaskFile {
@@ -168,7 +289,8 @@ mod tests {
     use std::collections::HashMap;
 
     use crate::{
-        TaskTemplate, TaskTemplates, VsCodeTaskFile,
+        CustomProblemMatcher, DependsOrder, ProblemMatcherDefinition, ProblemPattern,
+        ProblemSeverity, TaskTemplate, TaskTemplates, VsCodeTaskFile,
         vscode_format::{Command, VsCodeTaskDefinition},
     };
 
@@ -271,6 +393,7 @@ mod tests {
                 label: "gulp: tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matchers: vec![ProblemMatcherDefinition::Named("$tsc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
@@ -282,18 +405,21 @@ mod tests {
                     "${ZED_WORKTREE_ROOT}/src".to_string(),
                     "--watch".to_string(),
                 ],
+                problem_matchers: vec![ProblemMatcherDefinition::Named("$tsc-watch".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:compiler".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:compiler".to_string()],
+                problem_matchers: vec![ProblemMatcherDefinition::Named("$tsc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matchers: vec![ProblemMatcherDefinition::Named("$tsc".to_string())],
                 ..Default::default()
             },
         ];
@@ -374,28 +500,58 @@ mod tests {
                 label: "Build Extension in Background".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "watch".to_string()],
+                problem_matchers: vec![ProblemMatcherDefinition::Custom(CustomProblemMatcher {
+                    base: Some("$tsc-watch".to_string()),
+                    relative_to: Some("${ZED_WORKTREE_ROOT}/editors/code/".to_string()),
+                    ..Default::default()
+                })],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Extension".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build".to_string()],
+                problem_matchers: vec![ProblemMatcherDefinition::Custom(CustomProblemMatcher {
+                    base: Some("$tsc".to_string()),
+                    relative_to: Some("${ZED_WORKTREE_ROOT}/editors/code/".to_string()),
+                    ..Default::default()
+                })],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server".to_string(),
                 command: "cargo build --package rust-analyzer".to_string(),
+                problem_matchers: vec![ProblemMatcherDefinition::Named("$rustc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server (Release)".to_string(),
                 command: "cargo build --release --package rust-analyzer".to_string(),
+                problem_matchers: vec![ProblemMatcherDefinition::Named("$rustc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Pretest".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "pretest".to_string()],
+                problem_matchers: vec![ProblemMatcherDefinition::Custom(CustomProblemMatcher {
+                    base: Some("$tsc".to_string()),
+                    relative_to: Some("${ZED_WORKTREE_ROOT}/editors/code/".to_string()),
+                    ..Default::default()
+                })],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server and Extension".to_string(),
+                depends_on: vec!["Build Server".to_string(), "Build Extension".to_string()],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server (Release) and Extension".to_string(),
+                depends_on: vec![
+                    "Build Server (Release)".to_string(),
+                    "Build Extension".to_string(),
+                ],
                 ..Default::default()
             },
         ];
@@ -416,6 +572,116 @@ mod tests {
         assert_eq!(vscode_definitions.tasks[3].label, "echo hello");
     }
 
+    #[test]
+    fn can_deserialize_task_dependencies() {
+        let vscode_definitions: VsCodeTaskFile = serde_json_lenient::from_str(
+            r#"{
+                "tasks": [
+                    {
+                        "label": "Test",
+                        "type": "shell",
+                        "command": "cargo test",
+                        "dependsOn": "Build"
+                    },
+                    {
+                        "label": "Release",
+                        "dependsOn": ["Test", { "type": "npm", "script": "publish" }],
+                        "dependsOrder": "sequence"
+                    },
+                    {
+                        "label": "Broken",
+                        "dependsOn": 42
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
+        assert_eq!(
+            tasks.0,
+            vec![
+                TaskTemplate {
+                    label: "Test".to_string(),
+                    command: "cargo test".to_string(),
+                    depends_on: vec!["Build".to_string()],
+                    ..Default::default()
+                },
+                TaskTemplate {
+                    label: "Release".to_string(),
+                    depends_on: vec!["Test".to_string(), "npm: publish".to_string()],
+                    depends_order: DependsOrder::Sequence,
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn can_deserialize_problem_matchers() {
+        let vscode_definitions: VsCodeTaskFile = serde_json_lenient::from_str(
+            r#"{
+                "tasks": [
+                    {
+                        "label": "lint",
+                        "type": "shell",
+                        "command": "lint",
+                        "problemMatcher": [
+                            "$eslint-stylish",
+                            {
+                                "owner": "lint",
+                                "source": "lint",
+                                "severity": "warning",
+                                "fileLocation": ["relative", "${workspaceFolder}/src"],
+                                "pattern": {
+                                    "regexp": "^(.*):(\\d+):(\\d+):(\\d+):(\\d+): (.*)$",
+                                    "file": 1,
+                                    "line": 2,
+                                    "column": 3,
+                                    "endLine": 4,
+                                    "endColumn": 5,
+                                    "message": 6
+                                }
+                            },
+                            { "owner": "broken", "pattern": "$named" },
+                            42
+                        ]
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
+        assert_eq!(
+            tasks.0,
+            vec![TaskTemplate {
+                label: "lint".to_string(),
+                command: "lint".to_string(),
+                problem_matchers: vec![
+                    ProblemMatcherDefinition::Named("$eslint-stylish".to_string()),
+                    ProblemMatcherDefinition::Custom(CustomProblemMatcher {
+                        base: None,
+                        pattern: vec![ProblemPattern {
+                            regexp: "^(.*):(\\d+):(\\d+):(\\d+):(\\d+): (.*)$".to_string(),
+                            file: Some(1),
+                            line: Some(2),
+                            column: Some(3),
+                            end_line: Some(4),
+                            end_column: Some(5),
+                            message: Some(6),
+                            ..Default::default()
+                        }],
+                        source: Some("lint".to_string()),
+                        severity: Some(ProblemSeverity::Warning),
+                        relative_to: Some("${ZED_WORKTREE_ROOT}/src".to_string()),
+                    }),
+                ],
+                ..Default::default()
+            }]
+        );
+    }
+
     #[test]
     fn test_generate_label() {
         assert_eq!(
@@ -0,0 +1,225 @@
+use std::{cell::RefCell, rc::Rc, sync::Arc};
+
+use anyhow::{Context as _, anyhow, bail};
+use collections::HashMap;
+use futures::{
+    FutureExt as _,
+    future::{self, Shared},
+};
+use gpui::{AsyncWindowContext, Context, Task, WeakEntity, Window};
+use project::{TaskContexts, TaskDependencyTree, TaskSourceKind};
+use task::{DependsOrder, ResolvedTask, TaskContext};
+use workspace::{TaskDependencyScheduler, Toast, Workspace, notifications::NotificationId};
+
+use crate::task_contexts;
+
+type DependencyRun = Shared<Task<Result<(), Arc<anyhow::Error>>>>;
+
+/// Schedules tasks after all tasks from their `depends_on` finished successfully.
+///
+/// Dependencies are looked up in the task inventory and spawned in terminals with the current task contexts,
+/// a dependency shared by several tasks is only run once.
+/// If any of them fails, the task is not scheduled and an error toast is shown instead.
+/// Compound tasks, that have no command of their own, are only recorded in the task history.
+pub(crate) struct DependencyScheduler;
+
+impl TaskDependencyScheduler for DependencyScheduler {
+    fn schedule(
+        &self,
+        workspace: &mut Workspace,
+        task_source_kind: TaskSourceKind,
+        resolved_task: ResolvedTask,
+        omit_history: bool,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        schedule_resolved_task_with_dependencies(
+            workspace,
+            task_source_kind,
+            resolved_task,
+            omit_history,
+            window,
+            cx,
+        );
+    }
+}
+
+fn schedule_resolved_task_with_dependencies(
+    workspace: &mut Workspace,
+    task_source_kind: TaskSourceKind,
+    resolved_task: ResolvedTask,
+    omit_history: bool,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(inventory) = workspace
+        .project()
+        .read(cx)
+        .task_store()
+        .read(cx)
+        .task_inventory()
+        .cloned()
+    else {
+        return;
+    };
+
+    let task_contexts = task_contexts(workspace, window, cx);
+    cx.spawn_in(window, async move |workspace, cx| {
+        let task_contexts = task_contexts.await;
+        let worktree = match &task_source_kind {
+            TaskSourceKind::Worktree { id, .. } => Some(*id),
+            _ => task_contexts.worktree(),
+        };
+        let dependency_tree = inventory.read_with(cx, |inventory, _| {
+            inventory.task_dependencies(&task_source_kind, resolved_task.original_task(), worktree)
+        });
+        let result = match dependency_tree {
+            Ok(dependency_tree) => {
+                if resolved_task.original_task().is_compound() && !omit_history {
+                    inventory.update(cx, |inventory, _| {
+                        inventory.task_scheduled(task_source_kind.clone(), resolved_task.clone());
+                    });
+                }
+                let runner = Rc::new(DependencyRunner {
+                    workspace: workspace.clone(),
+                    task_contexts,
+                    runs: RefCell::default(),
+                });
+                run_dependencies(&dependency_tree, &runner, cx).await
+            }
+            Err(error) => Err(error),
+        };
+
+        workspace
+            .update_in(cx, |workspace, window, cx| match result {
+                Ok(()) => {
+                    if !resolved_task.original_task().is_compound() {
+                        workspace.schedule_resolved_task_without_dependencies(
+                            task_source_kind,
+                            resolved_task,
+                            omit_history,
+                            window,
+                            cx,
+                        );
+                    }
+                }
+                Err(error) => {
+                    log::error!("{error:#}");
+                    workspace.show_toast(
+                        Toast::new(
+                            NotificationId::unique::<TaskDependencyTree>(),
+                            format!(
+                                "Task `{}` was not run: {error:#}",
+                                resolved_task.resolved_label
+                            ),
+                        ),
+                        cx,
+                    );
+                }
+            })
+            .ok();
+    })
+    .detach();
+}
+
+struct DependencyRunner {
+    workspace: WeakEntity<Workspace>,
+    task_contexts: TaskContexts,
+    runs: RefCell<HashMap<(TaskSourceKind, String), DependencyRun>>,
+}
+
+impl DependencyRunner {
+    fn task_context(&self, task_source_kind: &TaskSourceKind) -> TaskContext {
+        match task_source_kind {
+            TaskSourceKind::Worktree { id, .. } => {
+                self.task_contexts.task_context_for_worktree_id(*id)
+            }
+            _ => self.task_contexts.active_context(),
+        }
+        .cloned()
+        .unwrap_or_default()
+    }
+}
+
+async fn run_dependencies(
+    tree: &TaskDependencyTree,
+    runner: &Rc<DependencyRunner>,
+    cx: &mut AsyncWindowContext,
+) -> anyhow::Result<()> {
+    match tree.template.depends_order {
+        DependsOrder::Parallel => {
+            let runs = tree
+                .dependencies
+                .iter()
+                .map(|dependency| run_dependency(dependency.clone(), runner, cx))
+                .collect::<Vec<_>>();
+            for result in future::join_all(runs).await {
+                result.map_err(|error| anyhow!("{error:#}"))?;
+            }
+        }
+        DependsOrder::Sequence => {
+            for dependency in &tree.dependencies {
+                run_dependency(dependency.clone(), runner, cx)
+                    .await
+                    .map_err(|error| anyhow!("{error:#}"))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn run_dependency(
+    tree: TaskDependencyTree,
+    runner: &Rc<DependencyRunner>,
+    cx: &mut AsyncWindowContext,
+) -> DependencyRun {
+    let key = (tree.source_kind.clone(), tree.template.label.clone());
+    if let Some(run) = runner.runs.borrow().get(&key) {
+        return run.clone();
+    }
+
+    let run = cx
+        .spawn({
+            let runner = runner.clone();
+            async move |cx| run_task(tree, &runner, cx).await.map_err(Arc::new)
+        })
+        .shared();
+    runner.runs.borrow_mut().insert(key, run.clone());
+    run
+}
+
+async fn run_task(
+    tree: TaskDependencyTree,
+    runner: &Rc<DependencyRunner>,
+    cx: &mut AsyncWindowContext,
+) -> anyhow::Result<()> {
+    run_dependencies(&tree, runner, cx).await?;
+    if tree.template.is_compound() {
+        return Ok(());
+    }
+
+    let label = &tree.template.label;
+    let resolved_task = tree
+        .template
+        .resolve_task(
+            &tree.source_kind.to_id_base(),
+            &runner.task_context(&tree.source_kind),
+        )
+        .with_context(|| format!("Failed to resolve task `{label}`"))?;
+    Workspace::save_for_task(&runner.workspace, resolved_task.resolved.save, cx).await;
+    let status = runner
+        .workspace
+        .update_in(cx, |workspace, window, cx| {
+            workspace.spawn_in_terminal(resolved_task.resolved, window, cx)
+        })?
+        .await;
+    match status {
+        Some(Ok(status)) if status.success() => Ok(()),
+        Some(Ok(status)) => match status.code() {
+            Some(code) => bail!("Task `{label}` failed with exit code {code}"),
+            None => bail!("Task `{label}` was terminated"),
+        },
+        Some(Err(error)) => Err(error.context(format!("Failed to spawn task `{label}`"))),
+        None => bail!("Task `{label}` was cancelled"),
+    }
+}
@@ -4,7 +4,10 @@ use std::num::NonZeroU32;
 use std::os::fd::AsRawFd;
 use std::{borrow::Cow, io, ops::RangeInclusive, path::PathBuf, sync::Arc};
 
+#[cfg(unix)]
+mod graphics_pty;
 mod hyperlinks;
+mod scrollback;
 
 use alacritty_terminal::{
     event::{Event as AlacTermEvent, EventListener, Notify, WindowSize},
@@ -36,14 +39,16 @@ use vte::ansi::Handler;
 use windows::Win32::{Foundation::HANDLE, System::Threading::GetProcessId};
 
 use crate::{
-    Cell, Color, Content, Cursor, CursorShape, Hyperlink, HyperlinkData, IndexedCell, Modes, Point,
-    PtyEvent, Range, RenderableCells, Scroll, Search, Selection, SelectionRange, SelectionSide,
-    SelectionType, TerminalBackendEvent, TerminalBounds, ViMotion,
+    Cell, Color, CommandMark, Content, Cursor, CursorShape, Hyperlink, HyperlinkData, ImageAnchor,
+    IndexedCell, Modes, Point, PtyEvent, Range, RenderableCells, Scroll, Search, Selection,
+    SelectionRange, SelectionSide, SelectionType, TerminalBackendEvent, TerminalBounds,
+    TerminalCommands, TerminalImages, ViMotion,
     pty_info::ProcessIdGetter,
     terminal_settings::{AlternateScroll, CursorShape as SettingsCursorShape},
 };
 
 pub(super) use hyperlinks::{HyperlinkMatch, RegexSearches};
+pub(super) use scrollback::serialize_scrollback;
 
 pub(super) type AlacrittyPty = tty::Pty;
 pub(super) type AlacrittyTerm = Term<ZedListener>;
@@ -202,7 +207,21 @@ pub(super) fn spawn_event_loop(
     events_tx: UnboundedSender<PtyEvent>,
     pty: AlacrittyPty,
     drain_on_exit: bool,
+    images: TerminalImages,
+    commands: TerminalCommands,
 ) -> Result<PtySender> {
+    #[cfg(unix)]
+    let pty = graphics_pty::GraphicsPty::new(
+        pty,
+        crate::shell_integration::PromptMarkFilter::new(commands),
+        crate::graphics::GraphicsFilter::new(images, Some(events_tx.clone())),
+    )
+    .context("failed to set up pty reader")?;
+    // ConPTY doesn't pass Sixel or Kitty graphics sequences through to us, and
+    // we don't inject shell integration on Windows.
+    #[cfg(windows)]
+    drop((images, commands));
+
     let event_loop = EventLoop::new(term, ZedListener(events_tx), pty, drain_on_exit, false)
         .context("failed to create event loop")?;
     let pty_tx = event_loop.channel();
@@ -439,6 +458,12 @@ impl Hyperlink {
     }
 }
 
+/// Whether a hyperlink was put in the grid by Zed to anchor images or commands,
+/// rather than by the program.
+fn is_internal_hyperlink(uri: &str) -> bool {
+    ImageAnchor::from_uri(uri).is_some() || CommandMark::from_uri(uri).is_some()
+}
+
 fn terminal_hyperlink_from_alacritty(hyperlink: AlacHyperlink) -> Hyperlink {
     Hyperlink::from_alacritty(hyperlink)
 }
@@ -489,7 +514,26 @@ impl Cell {
 
     #[inline]
     pub fn hyperlink(&self) -> Option<Hyperlink> {
-        self.cell.hyperlink().map(terminal_hyperlink_from_alacritty)
+        self.cell
+            .hyperlink()
+            .filter(|hyperlink| !is_internal_hyperlink(hyperlink.uri()))
+            .map(terminal_hyperlink_from_alacritty)
+    }
+
+    /// The inline image row starting at this cell, if any.
+    #[inline]
+    pub fn image_anchor(&self) -> Option<ImageAnchor> {
+        self.cell
+            .hyperlink()
+            .and_then(|hyperlink| ImageAnchor::from_uri(hyperlink.uri()))
+    }
+
+    /// The shell integration mark on this cell, if any.
+    #[inline]
+    pub fn command_mark(&self) -> Option<CommandMark> {
+        self.cell
+            .hyperlink()
+            .and_then(|hyperlink| CommandMark::from_uri(hyperlink.uri()))
     }
 
     #[inline]
@@ -840,6 +884,50 @@ pub(super) fn content_text(term: &Term<ZedListener>) -> String {
     term.bounds_to_string(start, end)
 }
 
+pub(super) fn text_in_range(term: &Term<ZedListener>, range: Range) -> String {
+    term.bounds_to_string(range.start().to_alacritty(), range.end().to_alacritty())
+}
+
+pub(super) fn last_column(term: &Term<ZedListener>) -> usize {
+    term.last_column().0
+}
+
+/// Cells of the whole grid carrying the same [`CommandMark`], from the first
+/// such cell to the last one.
+pub(super) struct CommandMarkRun {
+    pub mark: CommandMark,
+    pub start: Point,
+    pub end: Point,
+}
+
+/// Finds the prompts and command lines marked by shell integration, top to
+/// bottom.
+pub(super) fn command_mark_runs(term: &Term<ZedListener>) -> Vec<CommandMarkRun> {
+    let grid = term.grid();
+    let mut runs = Vec::<CommandMarkRun>::new();
+    for line in grid.topmost_line().0..=grid.bottommost_line().0 {
+        let row = &grid[Line(line)];
+        for column in 0..grid.columns() {
+            let Some(mark) = row[Column(column)]
+                .hyperlink()
+                .and_then(|hyperlink| CommandMark::from_uri(hyperlink.uri()))
+            else {
+                continue;
+            };
+            let point = Point::new(line, column);
+            match runs.last_mut() {
+                Some(run) if run.mark == mark => run.end = point,
+                _ => runs.push(CommandMarkRun {
+                    mark,
+                    start: point,
+                    end: point,
+                }),
+            }
+        }
+    }
+    runs
+}
+
 pub(super) fn total_lines(term: &Term<ZedListener>) -> usize {
     term.total_lines()
 }
@@ -0,0 +1,128 @@
+use std::{
+    fs::File,
+    io::{self, Read},
+    sync::Arc,
+};
+
+use alacritty_terminal::{
+    event::{OnResize, WindowSize},
+    tty::{self, ChildEvent, EventedPty, EventedReadWrite},
+};
+use polling::{Event, PollMode, Poller};
+
+use crate::{graphics::GraphicsFilter, shell_integration::PromptMarkFilter};
+
+const READ_BUFFER_SIZE: usize = 0x10_0000;
+
+/// A PTY whose output goes through a [`PromptMarkFilter`] and a
+/// [`GraphicsFilter`] before alacritty's event loop parses it, since alacritty
+/// has no hook for escape sequences it doesn't understand.
+pub(super) struct GraphicsPty {
+    pty: tty::Pty,
+    reader: GraphicsReader,
+}
+
+impl GraphicsPty {
+    pub(super) fn new(
+        pty: tty::Pty,
+        prompt_marks: PromptMarkFilter,
+        filter: GraphicsFilter,
+    ) -> io::Result<Self> {
+        // The duplicated descriptor shares the PTY's open file description, so
+        // readiness registered on the PTY applies to reads through it too.
+        let file = pty.file().try_clone()?;
+        Ok(Self {
+            pty,
+            reader: GraphicsReader {
+                file,
+                prompt_marks,
+                filter,
+                buffer: vec![0; READ_BUFFER_SIZE].into_boxed_slice(),
+                marked: Vec::new(),
+                filtered: Vec::new(),
+                position: 0,
+            },
+        })
+    }
+}
+
+pub(super) struct GraphicsReader {
+    file: File,
+    prompt_marks: PromptMarkFilter,
+    filter: GraphicsFilter,
+    buffer: Box<[u8]>,
+    marked: Vec<u8>,
+    filtered: Vec<u8>,
+    position: usize,
+}
+
+impl Read for GraphicsReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // Keep reading while everything read so far belongs to an unfinished
+        // image or prompt mark sequence, as returning zero bytes would look like end of file.
+        while self.position == self.filtered.len() {
+            let count = self.file.read(&mut self.buffer)?;
+            if count == 0 {
+                return Ok(0);
+            }
+            self.marked.clear();
+            self.filtered.clear();
+            self.position = 0;
+            self.prompt_marks
+                .filter(&self.buffer[..count], &mut self.marked);
+            self.filter.filter(&self.marked, &mut self.filtered);
+        }
+
+        let count = buf.len().min(self.filtered.len() - self.position);
+        buf[..count].copy_from_slice(&self.filtered[self.position..self.position + count]);
+        self.position += count;
+        Ok(count)
+    }
+}
+
+impl EventedReadWrite for GraphicsPty {
+    type Reader = GraphicsReader;
+    type Writer = <tty::Pty as EventedReadWrite>::Writer;
+
+    unsafe fn register(
+        &mut self,
+        poller: &Arc<Poller>,
+        interest: Event,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        unsafe { self.pty.register(poller, interest, mode) }
+    }
+
+    fn reregister(
+        &mut self,
+        poller: &Arc<Poller>,
+        interest: Event,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        self.pty.reregister(poller, interest, mode)
+    }
+
+    fn deregister(&mut self, poller: &Arc<Poller>) -> io::Result<()> {
+        self.pty.deregister(poller)
+    }
+
+    fn reader(&mut self) -> &mut Self::Reader {
+        &mut self.reader
+    }
+
+    fn writer(&mut self) -> &mut Self::Writer {
+        self.pty.writer()
+    }
+}
+
+impl EventedPty for GraphicsPty {
+    fn next_child_event(&mut self) -> Option<ChildEvent> {
+        self.pty.next_child_event()
+    }
+}
+
+impl OnResize for GraphicsPty {
+    fn on_resize(&mut self, window_size: WindowSize) {
+        self.pty.on_resize(window_size);
+    }
+}
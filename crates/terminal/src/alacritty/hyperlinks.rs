@@ -94,7 +94,10 @@ pub(crate) fn find_from_grid_point<T: EventListener>(
     path_style: PathStyle,
 ) -> Option<HyperlinkMatch> {
     let grid = term.grid();
-    let link = grid.index(point).hyperlink();
+    let link = grid
+        .index(point)
+        .hyperlink()
+        .filter(|link| !super::is_internal_hyperlink(link.uri()));
     let found_word = if let Some(ref url) = link {
         let mut min_index = point;
         loop {
@@ -0,0 +1,231 @@
+use std::fmt::Write as _;
+
+use alacritty_terminal::{
+    Term,
+    grid::Dimensions,
+    index::{Column, Line},
+    term::{
+        TermMode,
+        cell::{Cell as AlacCell, Flags},
+    },
+    vte::ansi::{Color, NamedColor},
+};
+
+const SKIPPED_CELLS: Flags =
+    Flags::from_bits(Flags::LEADING_WIDE_CHAR_SPACER.bits() | Flags::WIDE_CHAR_SPACER.bits())
+        .unwrap();
+
+/// Serializes up to `max_lines` lines above the cursor as text with SGR
+/// sequences, so the content can be fed back into a terminal later.
+///
+/// The cursor line is left out, as it normally holds the live prompt. Returns
+/// `None` if there is nothing to keep or if an alternate screen application
+/// is running, since the primary screen isn't reachable then.
+pub(crate) fn serialize_scrollback<T>(term: &Term<T>, max_lines: usize) -> Option<String> {
+    if max_lines == 0 || term.mode().contains(TermMode::ALT_SCREEN) {
+        return None;
+    }
+
+    let grid = term.grid();
+    let columns = grid.columns();
+    let mut end = grid.cursor.point.line.0;
+    // Blank lines right above the prompt aren't worth keeping.
+    while end > grid.topmost_line().0
+        && grid[Line(end - 1)][..Column(columns)]
+            .iter()
+            .all(|cell| is_blank(cell))
+    {
+        end -= 1;
+    }
+    let start = end
+        .saturating_sub(max_lines as i32)
+        .max(grid.topmost_line().0);
+    if start >= end {
+        return None;
+    }
+
+    let mut output = String::new();
+    let mut style = CellStyle::default();
+    for line in start..end {
+        let row = &grid[Line(line)];
+        let wraps = row[Column(columns - 1)].flags.contains(Flags::WRAPLINE);
+        let cells = &row[..Column(columns)];
+        // Trailing blanks of a wrapped line are part of its content.
+        let len = if wraps {
+            columns
+        } else {
+            cells
+                .iter()
+                .rposition(|cell| !is_blank(cell))
+                .map_or(0, |ix| ix + 1)
+        };
+
+        for cell in &cells[..len] {
+            if cell.flags.intersects(SKIPPED_CELLS) {
+                continue;
+            }
+            let cell_style = CellStyle::from(cell);
+            if cell_style != style {
+                cell_style.write_sgr(&mut output);
+                style = cell_style;
+            }
+            output.push(cell.c);
+            if let Some(zerowidth) = cell.zerowidth() {
+                output.extend(zerowidth);
+            }
+        }
+
+        if !wraps {
+            if style.bg != Color::Named(NamedColor::Background) {
+                // Keep the background from bleeding into the next line.
+                style = CellStyle::default();
+                style.write_sgr(&mut output);
+            }
+            output.push_str("\r\n");
+        }
+    }
+    if style != CellStyle::default() {
+        CellStyle::default().write_sgr(&mut output);
+    }
+
+    Some(output)
+}
+
+fn is_blank(cell: &AlacCell) -> bool {
+    cell.c == ' '
+        && cell.bg == Color::Named(NamedColor::Background)
+        && !cell.flags.intersects(Flags::INVERSE)
+        && cell.zerowidth().is_none()
+}
+
+#[derive(Clone, Copy, PartialEq)]
+struct CellStyle {
+    fg: Color,
+    bg: Color,
+    flags: Flags,
+}
+
+impl Default for CellStyle {
+    fn default() -> Self {
+        Self {
+            fg: Color::Named(NamedColor::Foreground),
+            bg: Color::Named(NamedColor::Background),
+            flags: Flags::empty(),
+        }
+    }
+}
+
+impl From<&AlacCell> for CellStyle {
+    fn from(cell: &AlacCell) -> Self {
+        Self {
+            fg: cell.fg,
+            bg: cell.bg,
+            flags: cell.flags
+                & (Flags::BOLD
+                    | Flags::DIM
+                    | Flags::ITALIC
+                    | Flags::ALL_UNDERLINES
+                    | Flags::INVERSE
+                    | Flags::HIDDEN
+                    | Flags::STRIKEOUT),
+        }
+    }
+}
+
+impl CellStyle {
+    /// Writes a complete SGR sequence for this style, starting from a reset.
+    fn write_sgr(&self, output: &mut String) {
+        output.push_str("\x1b[0");
+        for (flag, code) in [
+            (Flags::BOLD, 1),
+            (Flags::DIM, 2),
+            (Flags::ITALIC, 3),
+            (Flags::INVERSE, 7),
+            (Flags::HIDDEN, 8),
+            (Flags::STRIKEOUT, 9),
+        ] {
+            if self.flags.contains(flag) {
+                write!(output, ";{code}").ok();
+            }
+        }
+        if self.flags.intersects(Flags::ALL_UNDERLINES) {
+            output.push_str(";4");
+        }
+        write_color(output, self.fg, 30, 90, 38);
+        write_color(output, self.bg, 40, 100, 48);
+        output.push('m');
+    }
+}
+
+fn write_color(output: &mut String, color: Color, base: u8, bright_base: u8, extended: u8) {
+    match color {
+        Color::Named(named) => match named as usize {
+            ix @ 0..=7 => write!(output, ";{}", base as usize + ix),
+            ix @ 8..=15 => write!(output, ";{}", bright_base as usize + ix - 8),
+            // Foreground, background and dim colors are the defaults.
+            _ => Ok(()),
+        },
+        Color::Spec(rgb) => write!(output, ";{extended};2;{};{};{}", rgb.r, rgb.g, rgb.b),
+        Color::Indexed(ix) => write!(output, ";{extended};5;{ix}"),
+    }
+    .ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use alacritty_terminal::{
+        event::VoidListener,
+        term::{Config, test::TermSize},
+        vte::ansi::{Processor, StdSyncHandler},
+    };
+
+    use super::*;
+
+    fn term_with_output(output: &str) -> Term<VoidListener> {
+        let mut term = Term::new(Config::default(), &TermSize::new(10, 5), VoidListener);
+        let mut processor = Processor::<StdSyncHandler>::new();
+        processor.advance(&mut term, output.as_bytes());
+        term
+    }
+
+    #[test]
+    fn test_serialize_scrollback_keeps_colors() {
+        let term = term_with_output("plain\r\n\x1b[1;31mred\x1b[0m text\r\n\r\n$ ");
+        assert_eq!(
+            serialize_scrollback(&term, 100).as_deref(),
+            Some("plain\r\n\x1b[0;1;31mred\x1b[0m text\r\n")
+        );
+
+        let round_trip = term_with_output(&serialize_scrollback(&term, 100).unwrap());
+        assert_eq!(
+            serialize_scrollback(&round_trip, 100),
+            serialize_scrollback(&term, 100)
+        );
+    }
+
+    #[test]
+    fn test_serialize_scrollback_is_bounded() {
+        let term = term_with_output("1\r\n2\r\n3\r\n4\r\n5\r\n6\r\n7\r\n$ ");
+        assert_eq!(
+            serialize_scrollback(&term, 3).as_deref(),
+            Some("5\r\n6\r\n7\r\n")
+        );
+        assert_eq!(serialize_scrollback(&term, 0), None);
+        assert_eq!(serialize_scrollback(&term_with_output("$ "), 3), None);
+    }
+
+    #[test]
+    fn test_serialize_scrollback_joins_wrapped_lines() {
+        let term = term_with_output("0123456789abc\r\n$ ");
+        assert_eq!(
+            serialize_scrollback(&term, 100).as_deref(),
+            Some("0123456789abc\r\n")
+        );
+    }
+
+    #[test]
+    fn test_serialize_scrollback_skips_alternate_screen() {
+        let term = term_with_output("output\r\n\x1b[?1049h");
+        assert_eq!(serialize_scrollback(&term, 100), None);
+    }
+}
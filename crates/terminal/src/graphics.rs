@@ -0,0 +1,1235 @@
+//! Inline images for the Sixel and Kitty graphics protocols.
+//!
+//! Alacritty drops both protocols on the floor, so [`GraphicsFilter`] cuts the
+//! image escape sequences out of the output stream before alacritty parses it.
+//! Each decoded image is stored in [`TerminalImages`] and replaced in the
+//! stream by one placeholder cell per image row, carrying an OSC 8 hyperlink
+//! whose URI names the image (see [`ImageAnchor`]). Because those cells live
+//! in alacritty's grid, images scroll, move into scrollback and get cleared
+//! together with the text around them, and the view only has to look for
+//! anchor cells among the visible ones to know where to paint.
+
+use std::{
+    fs,
+    io::{Cursor, Read as _},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{Context as _, Result, anyhow, bail};
+use base64::{
+    Engine as _, alphabet,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+};
+use collections::{HashMap, VecDeque};
+use futures::channel::mpsc::UnboundedSender;
+use gpui::{Pixels, RenderImage, Size, px, size};
+use image::{Frame, ImageFormat, ImageReader, Limits, RgbaImage};
+use parking_lot::Mutex;
+use util::ResultExt as _;
+
+use crate::{PtyEvent, TerminalBackendEvent, TerminalBounds};
+
+const ESC: u8 = 0x1b;
+const IMAGE_ANCHOR_SCHEME: &str = "zed-image:";
+/// Stored images are evicted oldest-first once they take up more than this,
+/// which is the same default quota kitty uses.
+const MAX_STORED_IMAGE_BYTES: usize = 320 * 1024 * 1024;
+/// Longest single image escape sequence (or chunked Kitty upload) we buffer.
+const MAX_SEQUENCE_BYTES: usize = 64 * 1024 * 1024;
+const MAX_IMAGE_DIMENSION: u32 = 10_000;
+/// Largest decoded image, as many pixels as the longest sequence can hold as
+/// raw RGBA data, so a small Sixel or compressed upload can't make us
+/// allocate far more than we'd buffer for it.
+const MAX_IMAGE_PIXELS: u64 = MAX_SEQUENCE_BYTES as u64 / 4;
+const MAX_PLACEMENT_CELLS: usize = 1_000;
+const MAX_DCS_PARAMETER_BYTES: usize = 64;
+
+const KITTY_BASE64: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// An image placed in the terminal grid.
+#[derive(Clone)]
+pub struct TerminalImage {
+    pub image: Arc<RenderImage>,
+    /// Number of grid columns the image was laid out over.
+    pub columns: usize,
+    /// Number of grid rows the image was laid out over.
+    pub rows: usize,
+    /// Whether the image should be stretched over its cells, which happens when
+    /// the program asked for an explicit size in cells. Otherwise the image is
+    /// drawn at its own pixel size.
+    pub fit_to_cells: bool,
+    kitty_image_id: Option<u32>,
+}
+
+impl TerminalImage {
+    /// The image's own size, in logical pixels.
+    pub fn pixel_size(&self) -> Size<Pixels> {
+        let image_size = self.image.size(0);
+        size(
+            px(i32::from(image_size.width) as f32),
+            px(i32::from(image_size.height) as f32),
+        )
+    }
+}
+
+/// Marks a grid cell as the first cell of one row of an inline image.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ImageAnchor {
+    pub image_id: u64,
+    /// Which row of the image this cell starts.
+    pub row: usize,
+}
+
+impl ImageAnchor {
+    pub fn from_uri(uri: &str) -> Option<Self> {
+        let (image_id, row) = uri.strip_prefix(IMAGE_ANCHOR_SCHEME)?.split_once(':')?;
+        Some(Self {
+            image_id: image_id.parse().ok()?,
+            row: row.parse().ok()?,
+        })
+    }
+
+    fn uri(&self) -> String {
+        format!("{IMAGE_ANCHOR_SCHEME}{}:{}", self.image_id, self.row)
+    }
+}
+
+/// Images shown by a terminal, shared between its output readers and the view.
+#[derive(Clone, Default)]
+pub struct TerminalImages(Arc<Mutex<ImageStore>>);
+
+struct ImageStore {
+    placements: HashMap<u64, TerminalImage>,
+    kitty_images: HashMap<u32, Arc<RenderImage>>,
+    order: VecDeque<StoredImage>,
+    total_bytes: usize,
+    next_image_id: u64,
+    cell_width: f32,
+    line_height: f32,
+    allow_file_transmission: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum StoredImage {
+    Placement(u64),
+    Kitty(u32),
+}
+
+impl Default for ImageStore {
+    fn default() -> Self {
+        Self {
+            placements: HashMap::default(),
+            kitty_images: HashMap::default(),
+            order: VecDeque::new(),
+            total_bytes: 0,
+            next_image_id: 0,
+            cell_width: 8.,
+            line_height: 16.,
+            allow_file_transmission: false,
+        }
+    }
+}
+
+impl TerminalImages {
+    pub fn get(&self, image_id: u64) -> Option<TerminalImage> {
+        self.0.lock().placements.get(&image_id).cloned()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.lock().placements.is_empty()
+    }
+
+    pub(crate) fn set_cell_size(&self, bounds: &TerminalBounds) {
+        let mut store = self.0.lock();
+        store.cell_width = f32::from(bounds.cell_width()).max(1.);
+        store.line_height = f32::from(bounds.line_height()).max(1.);
+    }
+
+    /// Whether Kitty uploads may name a file to read the image from, which
+    /// lets any program in the terminal make it read files on its behalf.
+    pub(crate) fn set_allow_file_transmission(&self, allow: bool) {
+        self.0.lock().allow_file_transmission = allow;
+    }
+
+    fn allows_file_transmission(&self) -> bool {
+        self.0.lock().allow_file_transmission
+    }
+
+    /// Returns the number of cells an image covers, and whether it has to be
+    /// scaled to cover them exactly.
+    fn placement_size(&self, image: &RenderImage, columns: u32, rows: u32) -> (usize, usize, bool) {
+        let store = self.0.lock();
+        let image_size = image.size(0);
+        let width = i32::from(image_size.width).max(1) as f32;
+        let height = i32::from(image_size.height).max(1) as f32;
+        let (columns, rows, fit_to_cells) = match (columns, rows) {
+            (0, 0) => (
+                (width / store.cell_width).ceil() as usize,
+                (height / store.line_height).ceil() as usize,
+                false,
+            ),
+            (0, rows) => {
+                let scale = rows as f32 * store.line_height / height;
+                let columns = (width * scale / store.cell_width).ceil() as usize;
+                (columns, rows as usize, true)
+            }
+            (columns, 0) => {
+                let scale = columns as f32 * store.cell_width / width;
+                let rows = (height * scale / store.line_height).ceil() as usize;
+                (columns as usize, rows, true)
+            }
+            (columns, rows) => (columns as usize, rows as usize, true),
+        };
+        (
+            columns.clamp(1, MAX_PLACEMENT_CELLS),
+            rows.clamp(1, MAX_PLACEMENT_CELLS),
+            fit_to_cells,
+        )
+    }
+
+    fn place(
+        &self,
+        image: Arc<RenderImage>,
+        columns: u32,
+        rows: u32,
+        kitty_image_id: Option<u32>,
+    ) -> (u64, TerminalImage) {
+        let (columns, rows, fit_to_cells) = self.placement_size(&image, columns, rows);
+        let placement = TerminalImage {
+            image,
+            columns,
+            rows,
+            fit_to_cells,
+            kitty_image_id,
+        };
+
+        let mut store = self.0.lock();
+        let image_id = store.next_image_id;
+        store.next_image_id += 1;
+        store.total_bytes += image_bytes(&placement.image);
+        store.placements.insert(image_id, placement.clone());
+        store.order.push_back(StoredImage::Placement(image_id));
+        store.evict();
+        (image_id, placement)
+    }
+
+    fn store_kitty_image(&self, kitty_image_id: u32, image: Arc<RenderImage>) {
+        let mut store = self.0.lock();
+        store.total_bytes += image_bytes(&image);
+        if let Some(previous) = store.kitty_images.insert(kitty_image_id, image) {
+            store.total_bytes -= image_bytes(&previous);
+            store
+                .order
+                .retain(|stored| *stored != StoredImage::Kitty(kitty_image_id));
+        }
+        store.order.push_back(StoredImage::Kitty(kitty_image_id));
+        store.evict();
+    }
+
+    fn kitty_image(&self, kitty_image_id: u32) -> Option<Arc<RenderImage>> {
+        self.0.lock().kitty_images.get(&kitty_image_id).cloned()
+    }
+
+    /// Deletes images as described by a Kitty `a=d` command. Upper-case
+    /// selectors also free the image data; we free it either way, since a
+    /// placement can't be brought back without re-sending the image.
+    fn delete_kitty_images(&self, selector: u8, kitty_image_id: u32) {
+        let mut store = self.0.lock();
+        match selector.to_ascii_lowercase() {
+            b'a' => {
+                store.placements.clear();
+                store.kitty_images.clear();
+                store.order.clear();
+                store.total_bytes = 0;
+            }
+            b'i' => {
+                let store = &mut *store;
+                if let Some(image) = store.kitty_images.remove(&kitty_image_id) {
+                    store.total_bytes -= image_bytes(&image);
+                }
+                let placements = &mut store.placements;
+                let total_bytes = &mut store.total_bytes;
+                placements.retain(|_, placement| {
+                    let keep = placement.kitty_image_id != Some(kitty_image_id);
+                    if !keep {
+                        *total_bytes -= image_bytes(&placement.image);
+                    }
+                    keep
+                });
+                store.order.retain(|stored| match stored {
+                    StoredImage::Placement(image_id) => placements.contains_key(image_id),
+                    StoredImage::Kitty(image_id) => *image_id != kitty_image_id,
+                });
+            }
+            _ => {}
+        }
+    }
+}
+
+impl ImageStore {
+    fn evict(&mut self) {
+        while self.total_bytes > MAX_STORED_IMAGE_BYTES {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+            let image = match oldest {
+                StoredImage::Placement(image_id) => self
+                    .placements
+                    .remove(&image_id)
+                    .map(|placement| placement.image),
+                StoredImage::Kitty(image_id) => self.kitty_images.remove(&image_id),
+            };
+            if let Some(image) = image {
+                self.total_bytes -= image_bytes(&image);
+            }
+        }
+    }
+}
+
+fn image_bytes(image: &RenderImage) -> usize {
+    image.as_bytes(0).map_or(0, |bytes| bytes.len())
+}
+
+fn render_image(mut image: RgbaImage) -> Arc<RenderImage> {
+    // Convert from RGBA to BGRA.
+    for pixel in image.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+    Arc::new(RenderImage::new(vec![Frame::new(image)]))
+}
+
+/// Where the cursor ends up once an image has been placed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CursorMovement {
+    /// On the line below the image, in the column the image started at.
+    BelowImage,
+    /// On the image's last line, just past its right edge.
+    AfterImage,
+    Unchanged,
+}
+
+/// Writes the cells standing in for an image into the output stream, starting
+/// at the cursor: one anchor cell per image row, stacked with IND so that the
+/// terminal scrolls when the image doesn't fit below the cursor.
+fn write_placeholder(
+    output: &mut Vec<u8>,
+    image_id: u64,
+    columns: usize,
+    rows: usize,
+    cursor: CursorMovement,
+) {
+    if cursor == CursorMovement::Unchanged {
+        output.extend_from_slice(b"\x1b7");
+    }
+    for row in 0..rows {
+        let anchor = ImageAnchor { image_id, row };
+        output.extend_from_slice(
+            format!("\x1b]8;;{}\x1b\\ \x1b]8;;\x1b\\\x1b[D", anchor.uri()).as_bytes(),
+        );
+        if row + 1 < rows {
+            output.extend_from_slice(b"\x1bD");
+        }
+    }
+    match cursor {
+        CursorMovement::BelowImage => output.extend_from_slice(b"\x1bD"),
+        CursorMovement::AfterImage => {
+            output.extend_from_slice(format!("\x1b[{columns}C").as_bytes())
+        }
+        CursorMovement::Unchanged => output.extend_from_slice(b"\x1b8"),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Protocol {
+    Sixel,
+    Kitty,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FilterState {
+    Ground,
+    Escape,
+    /// After `ESC P`, collecting parameters to see whether this is Sixel.
+    DcsStart,
+    /// After `ESC _`, expecting the `G` of a Kitty graphics command.
+    ApcStart,
+    Body(Protocol),
+    BodyEscape(Protocol),
+    /// Skipping the rest of a sequence that grew too large.
+    Discard {
+        escape: bool,
+    },
+}
+
+/// Extracts Sixel (`ESC P … q … ESC \`) and Kitty (`ESC _ G … ESC \`) image
+/// sequences from terminal output, passing everything else through untouched.
+pub(crate) struct GraphicsFilter {
+    images: TerminalImages,
+    /// Where to send replies to Kitty commands. `None` for terminals that have
+    /// no input stream to reply on.
+    responses: Option<UnboundedSender<PtyEvent>>,
+    state: FilterState,
+    parameters: Vec<u8>,
+    body: Vec<u8>,
+    kitty_upload: Option<(KittyCommand, Vec<u8>)>,
+}
+
+impl GraphicsFilter {
+    pub(crate) fn new(
+        images: TerminalImages,
+        responses: Option<UnboundedSender<PtyEvent>>,
+    ) -> Self {
+        Self {
+            images,
+            responses,
+            state: FilterState::Ground,
+            parameters: Vec::new(),
+            body: Vec::new(),
+            kitty_upload: None,
+        }
+    }
+
+    /// Appends `input` to `output` with image sequences replaced by
+    /// placeholder cells. Sequences may be split across calls.
+    pub(crate) fn filter(&mut self, input: &[u8], output: &mut Vec<u8>) {
+        let mut index = 0;
+        while index < input.len() {
+            let byte = input[index];
+            match self.state {
+                FilterState::Ground => {
+                    let run = input[index..]
+                        .iter()
+                        .position(|&byte| byte == ESC)
+                        .unwrap_or(input.len() - index);
+                    output.extend_from_slice(&input[index..index + run]);
+                    index += run;
+                    if index < input.len() {
+                        self.state = FilterState::Escape;
+                        index += 1;
+                    }
+                    continue;
+                }
+                FilterState::Escape => match byte {
+                    b'P' => {
+                        self.parameters.clear();
+                        self.state = FilterState::DcsStart;
+                    }
+                    b'_' => self.state = FilterState::ApcStart,
+                    ESC => output.push(ESC),
+                    _ => {
+                        output.extend_from_slice(&[ESC, byte]);
+                        self.state = FilterState::Ground;
+                    }
+                },
+                FilterState::DcsStart => match byte {
+                    b'q' => {
+                        self.body.clear();
+                        self.state = FilterState::Body(Protocol::Sixel);
+                    }
+                    0x20..=0x3f if self.parameters.len() < MAX_DCS_PARAMETER_BYTES => {
+                        self.parameters.push(byte);
+                    }
+                    _ => {
+                        // Some other DCS sequence; let alacritty deal with it.
+                        output.extend_from_slice(&[ESC, b'P']);
+                        output.extend_from_slice(&self.parameters);
+                        self.state = FilterState::Ground;
+                        continue;
+                    }
+                },
+                FilterState::ApcStart => {
+                    if byte == b'G' {
+                        self.body.clear();
+                        self.state = FilterState::Body(Protocol::Kitty);
+                    } else {
+                        output.extend_from_slice(&[ESC, b'_']);
+                        self.state = FilterState::Ground;
+                        continue;
+                    }
+                }
+                FilterState::Body(protocol) => {
+                    let run = input[index..]
+                        .iter()
+                        .position(|&byte| byte == ESC)
+                        .unwrap_or(input.len() - index);
+                    if self.body.len() + run > MAX_SEQUENCE_BYTES {
+                        log::warn!("discarding oversized terminal image sequence");
+                        self.body = Vec::new();
+                        self.state = FilterState::Discard { escape: false };
+                        continue;
+                    }
+                    self.body.extend_from_slice(&input[index..index + run]);
+                    index += run;
+                    if index < input.len() {
+                        self.state = FilterState::BodyEscape(protocol);
+                        index += 1;
+                    }
+                    continue;
+                }
+                FilterState::BodyEscape(protocol) => {
+                    let body = std::mem::take(&mut self.body);
+                    if byte == b'\\' {
+                        self.state = FilterState::Ground;
+                        match protocol {
+                            Protocol::Sixel => self.handle_sixel(&body, output),
+                            Protocol::Kitty => self.handle_kitty(&body, output),
+                        }
+                    } else {
+                        // The sequence was cut short by another escape sequence.
+                        self.state = FilterState::Escape;
+                        continue;
+                    }
+                }
+                FilterState::Discard { escape } => {
+                    if escape && byte == b'\\' {
+                        self.state = FilterState::Ground;
+                    } else {
+                        self.state = FilterState::Discard {
+                            escape: byte == ESC,
+                        };
+                    }
+                }
+            }
+            index += 1;
+        }
+    }
+
+    fn handle_sixel(&mut self, body: &[u8], output: &mut Vec<u8>) {
+        let Some(image) = decode_sixel(body) else {
+            return;
+        };
+        let (image_id, placement) = self.images.place(render_image(image), 0, 0, None);
+        write_placeholder(
+            output,
+            image_id,
+            placement.columns,
+            placement.rows,
+            CursorMovement::BelowImage,
+        );
+    }
+
+    fn handle_kitty(&mut self, body: &[u8], output: &mut Vec<u8>) {
+        let (control, payload) = match body.iter().position(|&byte| byte == b';') {
+            Some(separator) => (&body[..separator], &body[separator + 1..]),
+            None => (body, &[][..]),
+        };
+        let command = KittyCommand::parse(control);
+
+        let (command, payload) = match self.kitty_upload.take() {
+            Some((first_command, mut data)) => {
+                data.extend_from_slice(payload);
+                if command.more {
+                    if data.len() <= MAX_SEQUENCE_BYTES {
+                        self.kitty_upload = Some((first_command, data));
+                    }
+                    return;
+                }
+                (first_command, data)
+            }
+            None if command.more => {
+                self.kitty_upload = Some((command, payload.to_vec()));
+                return;
+            }
+            None => (command, payload.to_vec()),
+        };
+
+        let allow_files = self.images.allows_file_transmission();
+        let result = match command.action {
+            b'q' => decode_kitty_image(&command, &payload, allow_files).map(|_| ()),
+            b't' | b'T' => decode_kitty_image(&command, &payload, allow_files).map(|image| {
+                let image = render_image(image);
+                if command.image_id != 0 {
+                    self.images
+                        .store_kitty_image(command.image_id, image.clone());
+                }
+                if command.action == b'T' {
+                    self.place_kitty_image(&command, image, output);
+                }
+            }),
+            b'p' => match self.images.kitty_image(command.image_id) {
+                Some(image) => {
+                    self.place_kitty_image(&command, image, output);
+                    Ok(())
+                }
+                None => Err(anyhow!("ENOENT:image not found")),
+            },
+            b'd' => {
+                self.images
+                    .delete_kitty_images(command.delete, command.image_id);
+                return;
+            }
+            _ => Err(anyhow!("EINVAL:unsupported action")),
+        };
+        self.respond(&command, result);
+    }
+
+    fn place_kitty_image(
+        &mut self,
+        command: &KittyCommand,
+        image: Arc<RenderImage>,
+        output: &mut Vec<u8>,
+    ) {
+        let kitty_image_id = (command.image_id != 0).then_some(command.image_id);
+        let (image_id, placement) =
+            self.images
+                .place(image, command.columns, command.rows, kitty_image_id);
+        let cursor = if command.keep_cursor {
+            CursorMovement::Unchanged
+        } else {
+            CursorMovement::AfterImage
+        };
+        write_placeholder(output, image_id, placement.columns, placement.rows, cursor);
+    }
+
+    fn respond(&self, command: &KittyCommand, result: Result<()>) {
+        if command.image_id == 0 && command.image_number == 0 {
+            return;
+        }
+        let message = match &result {
+            Ok(()) if command.quiet >= 1 => return,
+            Err(_) if command.quiet >= 2 => return,
+            Ok(()) => "OK".to_string(),
+            Err(error) => {
+                let error = format!("{error:#}");
+                if error.contains(':') {
+                    error
+                } else {
+                    format!("EINVAL:{error}")
+                }
+            }
+        };
+        let Some(responses) = &self.responses else {
+            return;
+        };
+
+        let mut keys = Vec::new();
+        if command.image_id != 0 {
+            keys.push(format!("i={}", command.image_id));
+        }
+        if command.image_number != 0 {
+            keys.push(format!("I={}", command.image_number));
+        }
+        if command.placement_id != 0 {
+            keys.push(format!("p={}", command.placement_id));
+        }
+        let response = format!("\x1b_G{};{}\x1b\\", keys.join(","), message);
+        responses
+            .unbounded_send(PtyEvent::Event(TerminalBackendEvent::PtyWrite(response)))
+            .ok();
+    }
+}
+
+/// The control data of a Kitty graphics command.
+/// See <https://sw.kovidgoyal.net/kitty/graphics-protocol/#control-data-reference>.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct KittyCommand {
+    action: u8,
+    format: u32,
+    medium: u8,
+    compression: Option<u8>,
+    width: u32,
+    height: u32,
+    image_id: u32,
+    image_number: u32,
+    placement_id: u32,
+    columns: u32,
+    rows: u32,
+    more: bool,
+    quiet: u32,
+    keep_cursor: bool,
+    delete: u8,
+}
+
+impl Default for KittyCommand {
+    fn default() -> Self {
+        Self {
+            action: b't',
+            format: 32,
+            medium: b'd',
+            compression: None,
+            width: 0,
+            height: 0,
+            image_id: 0,
+            image_number: 0,
+            placement_id: 0,
+            columns: 0,
+            rows: 0,
+            more: false,
+            quiet: 0,
+            keep_cursor: false,
+            delete: b'a',
+        }
+    }
+}
+
+impl KittyCommand {
+    fn parse(control: &[u8]) -> Self {
+        let mut command = Self::default();
+        for pair in control.split(|&byte| byte == b',') {
+            let [key, b'=', value @ ..] = pair else {
+                continue;
+            };
+            let number = || {
+                std::str::from_utf8(value)
+                    .ok()
+                    .and_then(|value| value.parse::<u32>().ok())
+                    .unwrap_or(0)
+            };
+            let character = value.first().copied().unwrap_or(0);
+            match *key {
+                b'a' => command.action = character,
+                b'f' => command.format = number(),
+                b't' => command.medium = character,
+                b'o' => command.compression = Some(character),
+                b's' => command.width = number(),
+                b'v' => command.height = number(),
+                b'i' => command.image_id = number(),
+                b'I' => command.image_number = number(),
+                b'p' => command.placement_id = number(),
+                b'c' => command.columns = number(),
+                b'r' => command.rows = number(),
+                b'm' => command.more = number() == 1,
+                b'q' => command.quiet = number(),
+                b'C' => command.keep_cursor = number() == 1,
+                b'd' => command.delete = character,
+                _ => {}
+            }
+        }
+        command
+    }
+}
+
+fn decode_kitty_image(
+    command: &KittyCommand,
+    payload: &[u8],
+    allow_files: bool,
+) -> Result<RgbaImage> {
+    let payload = KITTY_BASE64
+        .decode(payload)
+        .context("EINVAL:invalid base64 payload")?;
+    let data = match command.medium {
+        b'd' => payload,
+        b'f' | b't' if !allow_files => bail!("EPERM:file transmission is disabled"),
+        b'f' | b't' => {
+            let path =
+                PathBuf::from(String::from_utf8(payload).context("EINVAL:invalid file name")?);
+            let data = read_image_file(&path)?;
+            if command.medium == b't' && is_temporary_image_file(&path) {
+                fs::remove_file(&path).log_err();
+            }
+            data
+        }
+        _ => bail!("EINVAL:unsupported transmission medium"),
+    };
+    let data = match command.compression {
+        None => data,
+        Some(b'z') => {
+            let mut decompressed = Vec::new();
+            flate2::read::ZlibDecoder::new(data.as_slice())
+                .take(MAX_SEQUENCE_BYTES as u64)
+                .read_to_end(&mut decompressed)
+                .context("EINVAL:invalid zlib data")?;
+            decompressed
+        }
+        Some(_) => bail!("EINVAL:unsupported compression"),
+    };
+
+    let image = match command.format {
+        24 | 32 => {
+            let (width, height) = (command.width, command.height);
+            if width == 0 || height == 0 {
+                bail!("EINVAL:missing image size");
+            }
+            if width > MAX_IMAGE_DIMENSION
+                || height > MAX_IMAGE_DIMENSION
+                || width as u64 * height as u64 > MAX_IMAGE_PIXELS
+            {
+                bail!("EINVAL:image too large");
+            }
+            let pixels = if command.format == 24 {
+                data.chunks_exact(3)
+                    .flat_map(|pixel| [pixel[0], pixel[1], pixel[2], 0xff])
+                    .collect()
+            } else {
+                data
+            };
+            RgbaImage::from_raw(width, height, pixels).context("ENODATA:insufficient image data")?
+        }
+        100 => {
+            let mut limits = Limits::default();
+            limits.max_image_width = Some(MAX_IMAGE_DIMENSION);
+            limits.max_image_height = Some(MAX_IMAGE_DIMENSION);
+            limits.max_alloc = Some(MAX_IMAGE_PIXELS * 4);
+            let mut reader = ImageReader::with_format(Cursor::new(data), ImageFormat::Png);
+            reader.limits(limits);
+            reader
+                .decode()
+                .context("EBADPNG:failed to decode PNG")?
+                .into_rgba8()
+        }
+        _ => bail!("EINVAL:unsupported format"),
+    };
+    if image.width() > MAX_IMAGE_DIMENSION
+        || image.height() > MAX_IMAGE_DIMENSION
+        || image.width() as u64 * image.height() as u64 > MAX_IMAGE_PIXELS
+    {
+        bail!("EINVAL:image too large");
+    }
+    Ok(image)
+}
+
+fn read_image_file(path: &Path) -> Result<Vec<u8>> {
+    if ["/proc", "/sys", "/dev"]
+        .iter()
+        .any(|prefix| path.starts_with(prefix) && !path.starts_with("/dev/shm"))
+    {
+        bail!("EPERM:refusing to read from a special file system");
+    }
+    let metadata = fs::metadata(path).context("EBADF:failed to read image file")?;
+    if !metadata.is_file() {
+        bail!("EBADF:not a regular file");
+    }
+    if metadata.len() > MAX_SEQUENCE_BYTES as u64 {
+        bail!("EFBIG:image file too large");
+    }
+    fs::read(path).context("EBADF:failed to read image file")
+}
+
+/// Kitty only deletes "temporary file" transmissions that are clearly meant
+/// for it, so a program can't use the protocol to delete arbitrary files.
+fn is_temporary_image_file(path: &Path) -> bool {
+    let in_temporary_directory = [
+        std::env::temp_dir(),
+        PathBuf::from("/tmp"),
+        PathBuf::from("/dev/shm"),
+    ]
+    .iter()
+    .any(|directory| path.starts_with(directory));
+    in_temporary_directory
+        && path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.contains("tty-graphics-protocol"))
+}
+
+/// The VT340's default palette, as RGB percentages.
+const SIXEL_DEFAULT_PALETTE: [[u32; 3]; 16] = [
+    [0, 0, 0],
+    [20, 20, 80],
+    [80, 13, 13],
+    [20, 80, 20],
+    [80, 20, 80],
+    [20, 80, 80],
+    [80, 80, 20],
+    [53, 53, 53],
+    [26, 26, 26],
+    [33, 33, 60],
+    [60, 26, 26],
+    [33, 60, 33],
+    [60, 33, 60],
+    [33, 60, 60],
+    [60, 60, 33],
+    [80, 80, 80],
+];
+
+fn percent_to_channel(percent: u32) -> u8 {
+    (percent.min(100) * 255 / 100) as u8
+}
+
+/// Converts a Sixel HLS color, whose hue wheel starts at blue, to RGB.
+fn sixel_hls_to_rgb(hue: u32, lightness: u32, saturation: u32) -> [u8; 3] {
+    let hue = ((hue + 240) % 360) as f32 / 360.;
+    let lightness = lightness.min(100) as f32 / 100.;
+    let saturation = saturation.min(100) as f32 / 100.;
+    if saturation == 0. {
+        let value = (lightness * 255.).round() as u8;
+        return [value; 3];
+    }
+
+    let q = if lightness < 0.5 {
+        lightness * (1. + saturation)
+    } else {
+        lightness + saturation - lightness * saturation
+    };
+    let p = 2. * lightness - q;
+    let channel = |mut t: f32| {
+        if t < 0. {
+            t += 1.;
+        }
+        if t > 1. {
+            t -= 1.;
+        }
+        let value = if t < 1. / 6. {
+            p + (q - p) * 6. * t
+        } else if t < 1. / 2. {
+            q
+        } else if t < 2. / 3. {
+            p + (q - p) * (2. / 3. - t) * 6.
+        } else {
+            p
+        };
+        (value * 255.).round() as u8
+    };
+    [channel(hue + 1. / 3.), channel(hue), channel(hue - 1. / 3.)]
+}
+
+struct SixelCanvas {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl SixelCanvas {
+    /// Grows the canvas to at least the given size, returning `false` if that
+    /// would exceed the maximum image size.
+    fn ensure(&mut self, width: u32, height: u32) -> bool {
+        let width = width.max(self.width);
+        let height = height.max(self.height);
+        if width > MAX_IMAGE_DIMENSION
+            || height > MAX_IMAGE_DIMENSION
+            || width as u64 * height as u64 > MAX_IMAGE_PIXELS
+        {
+            return false;
+        }
+        if width == self.width && height == self.height {
+            return true;
+        }
+        // Grow geometrically to avoid copying for every sixel, as long as the
+        // larger canvas still fits in the limits.
+        let mut new_width = width.max(self.width.saturating_mul(2).min(MAX_IMAGE_DIMENSION));
+        let mut new_height = height.max(self.height.saturating_mul(2).min(MAX_IMAGE_DIMENSION));
+        if new_width as u64 * new_height as u64 > MAX_IMAGE_PIXELS {
+            (new_width, new_height) = (width, height);
+        }
+        let mut pixels = vec![0; new_width as usize * new_height as usize * 4];
+        let row_bytes = self.width as usize * 4;
+        for row in 0..self.height as usize {
+            let new_row_start = row * new_width as usize * 4;
+            pixels[new_row_start..new_row_start + row_bytes]
+                .copy_from_slice(&self.pixels[row * row_bytes..(row + 1) * row_bytes]);
+        }
+        self.width = new_width;
+        self.height = new_height;
+        self.pixels = pixels;
+        true
+    }
+
+    fn set(&mut self, x: u32, y: u32, color: [u8; 4]) {
+        let offset = (y as usize * self.width as usize + x as usize) * 4;
+        self.pixels[offset..offset + 4].copy_from_slice(&color);
+    }
+
+    fn crop(self, width: u32, height: u32) -> Option<RgbaImage> {
+        let width = width.min(self.width);
+        let height = height.min(self.height);
+        if width == 0 || height == 0 {
+            return None;
+        }
+        let mut pixels = Vec::with_capacity(width as usize * height as usize * 4);
+        for row in 0..height as usize {
+            let start = row * self.width as usize * 4;
+            pixels.extend_from_slice(&self.pixels[start..start + width as usize * 4]);
+        }
+        RgbaImage::from_raw(width, height, pixels)
+    }
+}
+
+fn parse_sixel_parameters(data: &[u8], index: &mut usize) -> Vec<u32> {
+    let mut parameters = vec![0u32];
+    while let Some(&byte) = data.get(*index) {
+        match byte {
+            b'0'..=b'9' => {
+                let last = parameters.last_mut().unwrap();
+                *last = last
+                    .saturating_mul(10)
+                    .saturating_add(u32::from(byte - b'0'));
+            }
+            b';' => parameters.push(0),
+            _ => break,
+        }
+        *index += 1;
+    }
+    parameters
+}
+
+/// Decodes the data of a Sixel sequence, i.e. everything after the `q`.
+/// Pixels that are never drawn stay transparent.
+fn decode_sixel(data: &[u8]) -> Option<RgbaImage> {
+    let mut palette = [[0, 0, 0, 0xff]; 256];
+    for (color, [r, g, b]) in palette.iter_mut().zip(SIXEL_DEFAULT_PALETTE) {
+        *color = [
+            percent_to_channel(r),
+            percent_to_channel(g),
+            percent_to_channel(b),
+            0xff,
+        ];
+    }
+
+    let mut canvas = SixelCanvas {
+        width: 0,
+        height: 0,
+        pixels: Vec::new(),
+    };
+    let mut color = palette[0];
+    let (mut x, mut y) = (0u32, 0u32);
+    let (mut width, mut height) = (0u32, 0u32);
+    let mut repeat = 1;
+    let mut index = 0;
+
+    while let Some(&byte) = data.get(index) {
+        index += 1;
+        match byte {
+            b'"' => {
+                let parameters = parse_sixel_parameters(data, &mut index);
+                if let [_, _, raster_width, raster_height] = parameters[..] {
+                    if !canvas.ensure(raster_width, raster_height) {
+                        return None;
+                    }
+                    width = width.max(raster_width);
+                    height = height.max(raster_height);
+                }
+            }
+            b'#' => {
+                let parameters = parse_sixel_parameters(data, &mut index);
+                let register = parameters[0] as usize % palette.len();
+                match parameters[..] {
+                    [_, 1, hue, lightness, saturation] => {
+                        let [r, g, b] = sixel_hls_to_rgb(hue, lightness, saturation);
+                        palette[register] = [r, g, b, 0xff];
+                    }
+                    [_, 2, r, g, b] => {
+                        palette[register] = [
+                            percent_to_channel(r),
+                            percent_to_channel(g),
+                            percent_to_channel(b),
+                            0xff,
+                        ];
+                    }
+                    _ => {}
+                }
+                color = palette[register];
+            }
+            b'!' => {
+                repeat = parse_sixel_parameters(data, &mut index)[0].max(1);
+            }
+            b'$' => x = 0,
+            b'-' => {
+                x = 0;
+                y += 6;
+            }
+            b'?'..=b'~' => {
+                let bits = byte - b'?';
+                let count = std::mem::replace(&mut repeat, 1);
+                if bits != 0 {
+                    if !canvas.ensure(x.saturating_add(count), y + 6) {
+                        return None;
+                    }
+                    for column in x..x + count {
+                        for bit in 0..6 {
+                            if bits & (1 << bit) != 0 {
+                                canvas.set(column, y + bit, color);
+                            }
+                        }
+                    }
+                    height = height.max(y + 8 - bits.leading_zeros());
+                }
+                x = x.saturating_add(count);
+                width = width.max(x);
+            }
+            _ => {}
+        }
+    }
+
+    canvas.crop(width, height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::channel::mpsc::unbounded;
+
+    fn filter_chunks(filter: &mut GraphicsFilter, chunks: &[&[u8]]) -> Vec<u8> {
+        let mut output = Vec::new();
+        for chunk in chunks {
+            filter.filter(chunk, &mut output);
+        }
+        output
+    }
+
+    fn anchors(output: &[u8]) -> Vec<ImageAnchor> {
+        String::from_utf8_lossy(output)
+            .split("\x1b]8;;")
+            .filter_map(|part| ImageAnchor::from_uri(part.split('\x1b').next()?))
+            .collect()
+    }
+
+    #[test]
+    fn test_non_graphics_output_passes_through() {
+        let mut filter = GraphicsFilter::new(TerminalImages::default(), None);
+        let input: &[u8] =
+            b"plain \x1b[31mred\x1b[0m \x1bP=1s\x1b\\sync\x1bP=2s\x1b\\ \x1b_other\x1b\\ \x1b]0;title\x07";
+        for split in 0..input.len() {
+            let (first, second) = input.split_at(split);
+            assert_eq!(
+                filter_chunks(&mut filter, &[first, second]),
+                input,
+                "split at {split}"
+            );
+        }
+        assert!(filter.images.is_empty());
+    }
+
+    #[test]
+    fn test_sixel_image_is_replaced_by_anchors() {
+        let images = TerminalImages::default();
+        images.set_cell_size(&TerminalBounds::new(
+            px(4.),
+            px(10.),
+            gpui::Bounds::default(),
+        ));
+        let mut filter = GraphicsFilter::new(images.clone(), None);
+
+        // Two red columns, six pixels tall, then a third column with only its
+        // top pixel set, in blue. The sequence is split across reads.
+        let output = filter_chunks(
+            &mut filter,
+            &[
+                b"before\x1bPq#1;2;100;0;0~",
+                b"~#3;2;0;0;100@\x1b",
+                b"\\after",
+            ],
+        );
+        let output_text = String::from_utf8_lossy(&output);
+        assert!(output_text.starts_with("before"));
+        assert!(output_text.ends_with("\x1bDafter"));
+
+        let anchors = anchors(&output);
+        assert_eq!(anchors.len(), 2);
+        assert_eq!(anchors[0].row, 0);
+        assert_eq!(anchors[1].row, 1);
+        let image = images.get(anchors[0].image_id).unwrap();
+        assert_eq!(image.pixel_size(), size(px(3.), px(6.)));
+        assert_eq!(
+            (image.columns, image.rows, image.fit_to_cells),
+            (1, 2, false)
+        );
+
+        // Stored as BGRA.
+        let pixels = image.image.as_bytes(0).unwrap();
+        assert_eq!(&pixels[..4], &[0, 0, 0xff, 0xff]);
+        assert_eq!(&pixels[8..12], &[0xff, 0, 0, 0xff]);
+        assert_eq!(
+            pixels[(3 + 2) * 4 + 3],
+            0,
+            "pixel below the blue one is unset"
+        );
+    }
+
+    #[test]
+    fn test_kitty_chunked_transmission_and_placement() {
+        let images = TerminalImages::default();
+        let (responses_tx, mut responses_rx) = unbounded();
+        let mut filter = GraphicsFilter::new(images.clone(), Some(responses_tx));
+
+        let pixels = KITTY_BASE64.encode([0x10, 0x20, 0x30, 0x40].repeat(4));
+        let (first, second) = pixels.split_at(8);
+        let output = filter_chunks(
+            &mut filter,
+            &[
+                format!("\x1b_Ga=t,f=32,s=2,v=2,i=7,m=1;{first}\x1b\\").as_bytes(),
+                format!("\x1b_Gm=0;{second}\x1b\\").as_bytes(),
+                b"\x1b_Ga=p,i=7,c=4,r=3\x1b\\",
+                b"\x1b_Ga=p,i=8,q=1\x1b\\",
+            ],
+        );
+
+        let anchors = anchors(&output);
+        assert_eq!(anchors.len(), 3);
+        let image = images.get(anchors[0].image_id).unwrap();
+        assert_eq!(
+            (image.columns, image.rows, image.fit_to_cells),
+            (4, 3, true)
+        );
+        assert!(String::from_utf8_lossy(&output).ends_with("\x1b[4C"));
+
+        let mut responses = Vec::new();
+        while let Ok(Some(PtyEvent::Event(TerminalBackendEvent::PtyWrite(response)))) =
+            responses_rx.try_next()
+        {
+            responses.push(response);
+        }
+        assert_eq!(
+            responses,
+            [
+                "\x1b_Gi=7;OK\x1b\\",
+                "\x1b_Gi=7;OK\x1b\\",
+                "\x1b_Gi=8;ENOENT:image not found\x1b\\",
+            ]
+        );
+
+        filter_chunks(&mut filter, &[b"\x1b_Ga=d,d=I,i=7\x1b\\"]);
+        assert!(images.get(anchors[0].image_id).is_none());
+    }
+
+    #[test]
+    fn test_kitty_file_transmission_requires_permission() {
+        let path = std::env::temp_dir().join("zed-kitty-file-transmission.rgba");
+        fs::write(&path, [0xff; 4]).unwrap();
+        let upload = format!(
+            "\x1b_Ga=T,f=32,s=1,v=1,t=f,i=1;{}\x1b\\",
+            KITTY_BASE64.encode(path.to_string_lossy().as_bytes())
+        );
+
+        let images = TerminalImages::default();
+        let (responses_tx, mut responses_rx) = unbounded();
+        let mut filter = GraphicsFilter::new(images.clone(), Some(responses_tx));
+        assert!(anchors(&filter_chunks(&mut filter, &[upload.as_bytes()])).is_empty());
+        let Ok(Some(PtyEvent::Event(TerminalBackendEvent::PtyWrite(response)))) =
+            responses_rx.try_next()
+        else {
+            panic!("expected a response");
+        };
+        assert_eq!(
+            response,
+            "\x1b_Gi=1;EPERM:file transmission is disabled\x1b\\"
+        );
+
+        images.set_allow_file_transmission(true);
+        assert_eq!(
+            anchors(&filter_chunks(&mut filter, &[upload.as_bytes()])).len(),
+            1
+        );
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_image_size_is_limited() {
+        let mut canvas = SixelCanvas {
+            width: 0,
+            height: 0,
+            pixels: Vec::new(),
+        };
+        assert!(canvas.ensure(4_000, 4_000));
+        assert!(canvas.width as u64 * canvas.height as u64 <= MAX_IMAGE_PIXELS);
+        assert!(!canvas.ensure(MAX_IMAGE_DIMENSION, MAX_IMAGE_DIMENSION));
+        assert!(decode_sixel(b"\"1;1;10000;10000#0~").is_none());
+
+        let command = KittyCommand::parse(b"a=t,f=32,s=10000,v=10000");
+        assert!(decode_kitty_image(&command, b"", false).is_err());
+    }
+
+    #[test]
+    fn test_image_anchor_uri_round_trip() {
+        let anchor = ImageAnchor {
+            image_id: 42,
+            row: 3,
+        };
+        assert_eq!(ImageAnchor::from_uri(&anchor.uri()), Some(anchor));
+        assert_eq!(ImageAnchor::from_uri("https://zed.dev"), None);
+        assert_eq!(ImageAnchor::from_uri("zed-image:1"), None);
+    }
+}
@@ -0,0 +1,653 @@
+//! Shell integration through OSC 133 and OSC 633 semantic prompt marks.
+//!
+//! Shells that know about these sequences (ours are injected by
+//! [`insert_shell_integration_env`]) report where each prompt starts (`A`),
+//! where the command line starts (`B`), when the command starts running (`C`)
+//! and when it finished (`D`), along with the command line (`633;E`) and the
+//! working directory (`633;P;Cwd=`). Any program can print these sequences, so
+//! command lines are only taken from ones carrying the terminal's nonce.
+//!
+//! [`PromptMarkFilter`] takes these sequences out of the output stream before
+//! alacritty sees them. Each command gets a [`TerminalCommand`] record in
+//! [`TerminalCommands`], and its prompt and command line are wrapped in OSC 8
+//! hyperlinks whose URI names the command (see [`CommandMark`]), the same way
+//! inline images are anchored. Marked cells scroll and get cleared together
+//! with the text around them, so positions of commands are always looked up
+//! in the grid.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, OnceLock},
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context as _, Result};
+use collections::{HashMap, VecDeque};
+use parking_lot::Mutex;
+use util::ResultExt as _;
+
+const ESC: u8 = 0x1b;
+const BEL: u8 = 0x07;
+const COMMAND_MARK_SCHEME: &str = "zed-command:";
+/// Longest semantic prompt sequence we buffer; `633;E` carries a whole command line.
+const MAX_SEQUENCE_BYTES: usize = 64 * 1024;
+/// Oldest commands are forgotten once there are more than this.
+const MAX_COMMANDS: usize = 1_000;
+
+/// A command run in the terminal's shell.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TerminalCommand {
+    pub id: u64,
+    /// The command line, if the shell reported it. See
+    /// [`crate::Terminal::command_text`] for one read from the grid otherwise.
+    pub command: Option<String>,
+    /// The shell's working directory when the command started.
+    pub cwd: Option<String>,
+    pub exit_code: Option<i32>,
+    /// How long the command ran, once it has finished.
+    pub duration: Option<Duration>,
+    started_at: Option<Instant>,
+}
+
+impl TerminalCommand {
+    fn new(id: u64, cwd: Option<String>) -> Self {
+        Self {
+            id,
+            command: None,
+            cwd,
+            exit_code: None,
+            duration: None,
+            started_at: None,
+        }
+    }
+
+    /// Whether the command line was submitted, as opposed to the shell still
+    /// waiting at this command's prompt.
+    pub fn has_started(&self) -> bool {
+        self.started_at.is_some()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.started_at.is_some() && self.duration.is_none()
+    }
+
+    pub fn has_finished(&self) -> bool {
+        self.duration.is_some()
+    }
+
+    pub fn failed(&self) -> bool {
+        self.exit_code.is_some_and(|exit_code| exit_code != 0)
+    }
+}
+
+/// Which part of a command a marked cell belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CommandMarkKind {
+    Prompt,
+    Input,
+}
+
+/// Marks a grid cell as part of the prompt or command line of a command.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CommandMark {
+    pub command_id: u64,
+    pub kind: CommandMarkKind,
+}
+
+impl CommandMark {
+    pub fn from_uri(uri: &str) -> Option<Self> {
+        let (command_id, kind) = uri.strip_prefix(COMMAND_MARK_SCHEME)?.split_once(':')?;
+        let kind = match kind {
+            "prompt" => CommandMarkKind::Prompt,
+            "input" => CommandMarkKind::Input,
+            _ => return None,
+        };
+        Some(Self {
+            command_id: command_id.parse().ok()?,
+            kind,
+        })
+    }
+
+    fn uri(&self) -> String {
+        let kind = match self.kind {
+            CommandMarkKind::Prompt => "prompt",
+            CommandMarkKind::Input => "input",
+        };
+        format!("{COMMAND_MARK_SCHEME}{}:{kind}", self.command_id)
+    }
+}
+
+/// Commands reported by a terminal's shell integration, shared between its
+/// output reader and the view.
+#[derive(Clone, Default)]
+pub struct TerminalCommands(Arc<Mutex<CommandStore>>);
+
+#[derive(Default)]
+struct CommandStore {
+    commands: VecDeque<TerminalCommand>,
+    next_command_id: u64,
+    cwd: Option<String>,
+    /// What the shell integration passes along with command lines, see
+    /// [`new_nonce`].
+    nonce: Option<String>,
+}
+
+impl TerminalCommands {
+    /// Commands of a shell set up by [`insert_shell_integration_env`] with
+    /// the given nonce.
+    pub(crate) fn with_nonce(nonce: String) -> Self {
+        Self(Arc::new(Mutex::new(CommandStore {
+            nonce: Some(nonce),
+            ..CommandStore::default()
+        })))
+    }
+
+    pub fn get(&self, command_id: u64) -> Option<TerminalCommand> {
+        self.0
+            .lock()
+            .commands
+            .iter()
+            .find(|command| command.id == command_id)
+            .cloned()
+    }
+
+    /// All known commands, oldest first. The last one is still at its prompt
+    /// unless it [has started](TerminalCommand::has_started).
+    pub fn all(&self) -> Vec<TerminalCommand> {
+        self.0.lock().commands.iter().cloned().collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.lock().commands.is_empty()
+    }
+
+    fn start_prompt(&self) -> u64 {
+        let mut store = self.0.lock();
+        // A prompt that was left without running anything (an empty line,
+        // Ctrl-C) isn't worth a record.
+        if store
+            .commands
+            .back()
+            .is_some_and(|command| !command.has_started())
+        {
+            store.commands.pop_back();
+        }
+        let command_id = store.next_command_id;
+        store.next_command_id += 1;
+        let command = TerminalCommand::new(command_id, store.cwd.clone());
+        store.commands.push_back(command);
+        if store.commands.len() > MAX_COMMANDS {
+            store.commands.pop_front();
+        }
+        command_id
+    }
+
+    fn current_command_id(&self) -> Option<u64> {
+        self.0.lock().commands.back().map(|command| command.id)
+    }
+
+    fn update_current(&self, update: impl FnOnce(&mut TerminalCommand)) {
+        if let Some(command) = self.0.lock().commands.back_mut() {
+            update(command);
+        }
+    }
+
+    fn set_cwd(&self, cwd: String) {
+        let mut store = self.0.lock();
+        store.cwd = Some(cwd.clone());
+        if let Some(command) = store.commands.back_mut()
+            && !command.has_started()
+        {
+            command.cwd = Some(cwd);
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FilterState {
+    Ground,
+    Escape,
+    /// After `ESC ]`, collecting the first bytes to see whether this is a
+    /// semantic prompt sequence.
+    OscStart,
+    Body,
+    BodyEscape,
+    /// Skipping the rest of a sequence that grew too large.
+    Discard {
+        escape: bool,
+    },
+}
+
+/// Replaces OSC 133 and OSC 633 sequences in terminal output with
+/// [`CommandMark`] hyperlinks, passing everything else through untouched.
+pub(crate) struct PromptMarkFilter {
+    commands: TerminalCommands,
+    nonce: Option<String>,
+    state: FilterState,
+    prefix: Vec<u8>,
+    body: Vec<u8>,
+}
+
+impl PromptMarkFilter {
+    pub(crate) fn new(commands: TerminalCommands) -> Self {
+        let nonce = commands.0.lock().nonce.clone();
+        Self {
+            commands,
+            nonce,
+            state: FilterState::Ground,
+            prefix: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+
+    /// Appends `input` to `output` with semantic prompt sequences replaced.
+    /// Sequences may be split across calls.
+    pub(crate) fn filter(&mut self, input: &[u8], output: &mut Vec<u8>) {
+        let mut index = 0;
+        while index < input.len() {
+            let byte = input[index];
+            match self.state {
+                FilterState::Ground => {
+                    let run = input[index..]
+                        .iter()
+                        .position(|&byte| byte == ESC)
+                        .unwrap_or(input.len() - index);
+                    output.extend_from_slice(&input[index..index + run]);
+                    index += run;
+                    if index < input.len() {
+                        self.state = FilterState::Escape;
+                        index += 1;
+                    }
+                    continue;
+                }
+                FilterState::Escape => match byte {
+                    b']' => {
+                        self.prefix.clear();
+                        self.state = FilterState::OscStart;
+                    }
+                    ESC => output.push(ESC),
+                    _ => {
+                        output.extend_from_slice(&[ESC, byte]);
+                        self.state = FilterState::Ground;
+                    }
+                },
+                FilterState::OscStart => {
+                    self.prefix.push(byte);
+                    let is_prompt_mark = [b"133;", b"633;"]
+                        .iter()
+                        .any(|prefix| prefix.starts_with(&self.prefix));
+                    if !is_prompt_mark {
+                        // Some other OSC sequence; let alacritty deal with it.
+                        self.prefix.pop();
+                        output.extend_from_slice(&[ESC, b']']);
+                        output.extend_from_slice(&self.prefix);
+                        self.state = FilterState::Ground;
+                        continue;
+                    }
+                    if self.prefix.len() == 4 {
+                        self.body.clear();
+                        self.state = FilterState::Body;
+                    }
+                }
+                FilterState::Body => {
+                    let run = input[index..]
+                        .iter()
+                        .position(|&byte| byte == ESC || byte == BEL)
+                        .unwrap_or(input.len() - index);
+                    if self.body.len() + run > MAX_SEQUENCE_BYTES {
+                        log::warn!("discarding oversized terminal prompt mark");
+                        self.body = Vec::new();
+                        self.state = FilterState::Discard { escape: false };
+                        continue;
+                    }
+                    self.body.extend_from_slice(&input[index..index + run]);
+                    index += run;
+                    if index < input.len() {
+                        if input[index] == BEL {
+                            self.state = FilterState::Ground;
+                            let body = std::mem::take(&mut self.body);
+                            self.handle_mark(&body, output);
+                        } else {
+                            self.state = FilterState::BodyEscape;
+                        }
+                        index += 1;
+                    }
+                    continue;
+                }
+                FilterState::BodyEscape => {
+                    if byte == b'\\' {
+                        self.state = FilterState::Ground;
+                        let body = std::mem::take(&mut self.body);
+                        self.handle_mark(&body, output);
+                    } else {
+                        // The sequence was cut short by another escape sequence.
+                        self.body.clear();
+                        self.state = FilterState::Escape;
+                        continue;
+                    }
+                }
+                FilterState::Discard { escape } => {
+                    if byte == BEL || (escape && byte == b'\\') {
+                        self.state = FilterState::Ground;
+                    } else {
+                        self.state = FilterState::Discard {
+                            escape: byte == ESC,
+                        };
+                    }
+                }
+            }
+            index += 1;
+        }
+    }
+
+    fn handle_mark(&mut self, body: &[u8], output: &mut Vec<u8>) {
+        let body = String::from_utf8_lossy(body);
+        let (mark, arguments) = body.split_once(';').unwrap_or((body.as_ref(), ""));
+        match mark {
+            "A" => {
+                let command_id = self.commands.start_prompt();
+                write_mark(output, command_id, CommandMarkKind::Prompt);
+            }
+            "B" => {
+                if let Some(command_id) = self.commands.current_command_id() {
+                    write_mark(output, command_id, CommandMarkKind::Input);
+                }
+            }
+            "C" => {
+                output.extend_from_slice(b"\x1b]8;;\x1b\\");
+                let now = Instant::now();
+                self.commands.update_current(|command| {
+                    if !command.has_started() {
+                        command.started_at = Some(now);
+                    }
+                });
+            }
+            "D" => {
+                output.extend_from_slice(b"\x1b]8;;\x1b\\");
+                let exit_code = arguments
+                    .split(';')
+                    .next()
+                    .and_then(|exit_code| exit_code.parse().ok());
+                let now = Instant::now();
+                self.commands.update_current(|command| {
+                    if let Some(started_at) = command.started_at
+                        && command.duration.is_none()
+                    {
+                        command.duration = Some(now.saturating_duration_since(started_at));
+                        command.exit_code = exit_code;
+                    }
+                });
+            }
+            "E" => {
+                let mut arguments = arguments.split(';');
+                let command_line = arguments.next().unwrap_or_default();
+                // The command line is what gets run again, so it must come
+                // from our shell integration rather than a program's output.
+                if self.nonce.is_some() && arguments.next() == self.nonce.as_deref() {
+                    let command_line = unescape_value(command_line);
+                    self.commands.update_current(|command| {
+                        if !command.has_started() {
+                            command.command = Some(command_line);
+                        }
+                    });
+                }
+            }
+            "P" => {
+                if let Some(cwd) = arguments.strip_prefix("Cwd=") {
+                    self.commands.set_cwd(unescape_value(cwd));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn write_mark(output: &mut Vec<u8>, command_id: u64, kind: CommandMarkKind) {
+    let mark = CommandMark { command_id, kind };
+    output.extend_from_slice(format!("\x1b]8;;{}\x1b\\", mark.uri()).as_bytes());
+}
+
+/// Undoes the escaping of OSC 633 values: `\\` for a backslash and `\xAB`
+/// for any other byte.
+fn unescape_value(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut unescaped = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'\\' {
+            if bytes.get(index + 1) == Some(&b'\\') {
+                unescaped.push(b'\\');
+                index += 2;
+                continue;
+            }
+            if bytes.get(index + 1) == Some(&b'x')
+                && let Some(byte) = bytes
+                    .get(index + 2..index + 4)
+                    .and_then(|hex| std::str::from_utf8(hex).ok())
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                unescaped.push(byte);
+                index += 4;
+                continue;
+            }
+        }
+        unescaped.push(bytes[index]);
+        index += 1;
+    }
+    String::from_utf8_lossy(&unescaped).into_owned()
+}
+
+const BASH_SCRIPT: &str = include_str!("shell_integration/zed.bash");
+const ZSHENV_SCRIPT: &str = include_str!("shell_integration/zshenv.zsh");
+const ZPROFILE_SCRIPT: &str = include_str!("shell_integration/zprofile.zsh");
+const ZSHRC_SCRIPT: &str = include_str!("shell_integration/zshrc.zsh");
+const FISH_SCRIPT: &str = include_str!("shell_integration/zed.fish");
+
+/// A random value for [`insert_shell_integration_env`] and
+/// [`TerminalCommands::with_nonce`], which only the terminal's shell knows.
+pub(crate) fn new_nonce() -> String {
+    format!("{:032x}", rand::random::<u128>())
+}
+
+/// Sets up the environment of a local shell so that bash, zsh and fish load
+/// Zed's shell integration on top of the user's own configuration. Only the
+/// variables read by the given shell are changed, as they are inherited by
+/// everything it runs.
+pub(crate) fn insert_shell_integration_env(
+    env: &mut HashMap<String, String>,
+    shell_program: &str,
+    nonce: &str,
+) {
+    if cfg!(windows) {
+        return;
+    }
+    let shell = Path::new(shell_program)
+        .file_name()
+        .map(|name| name.to_string_lossy().trim_start_matches('-').to_string());
+    if !matches!(shell.as_deref(), Some("bash" | "zsh" | "fish")) {
+        return;
+    }
+    static SCRIPTS_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+    let Some(scripts_dir) = SCRIPTS_DIR.get_or_init(|| {
+        let scripts_dir = paths::data_dir().join("shell_integration");
+        write_scripts(&scripts_dir)
+            .context("writing shell integration scripts")
+            .log_err()?;
+        Some(scripts_dir)
+    }) else {
+        return;
+    };
+
+    let inherited = |env: &HashMap<String, String>, name: &str| {
+        env.get(name)
+            .cloned()
+            .or_else(|| std::env::var(name).ok())
+            .filter(|value| !value.is_empty())
+    };
+
+    // The scripts keep the nonce in a shell variable and remove it from the
+    // environment, so that the programs the shell runs can't read it.
+    env.insert("ZED_SHELL_INTEGRATION_NONCE".to_string(), nonce.to_string());
+    match shell.as_deref() {
+        // zsh reads its startup files from ZDOTDIR, ours source the user's ones.
+        Some("zsh") => {
+            if let Some(zdotdir) = inherited(env, "ZDOTDIR") {
+                env.insert("ZED_USER_ZDOTDIR".to_string(), zdotdir);
+            }
+            env.insert(
+                "ZDOTDIR".to_string(),
+                scripts_dir.join("zsh").to_string_lossy().into_owned(),
+            );
+        }
+        // bash has no such hook, so the script is sourced from the first prompt.
+        Some("bash") => {
+            let bash_script = scripts_dir.join("zed.bash");
+            let bootstrap = format!(
+                "{{ [ -n \"$__zed_shell_integration\" ] || . {}; }}",
+                quote_for_posix_shell(&bash_script)
+            );
+            let prompt_command = match inherited(env, "PROMPT_COMMAND") {
+                Some(prompt_command) => format!("{bootstrap};{prompt_command}"),
+                None => bootstrap,
+            };
+            env.insert("PROMPT_COMMAND".to_string(), prompt_command);
+        }
+        // fish loads `fish/vendor_conf.d` from every XDG data directory.
+        _ => {
+            let data_dirs = match inherited(env, "XDG_DATA_DIRS") {
+                Some(data_dirs) => {
+                    env.insert("ZED_ORIGINAL_XDG_DATA_DIRS".to_string(), data_dirs.clone());
+                    data_dirs
+                }
+                None => "/usr/local/share:/usr/share".to_string(),
+            };
+            env.insert(
+                "XDG_DATA_DIRS".to_string(),
+                format!("{}:{data_dirs}", scripts_dir.to_string_lossy()),
+            );
+        }
+    }
+}
+
+fn write_scripts(scripts_dir: &Path) -> Result<()> {
+    let zsh_dir = scripts_dir.join("zsh");
+    let fish_dir = scripts_dir.join("fish").join("vendor_conf.d");
+    fs::create_dir_all(&zsh_dir)?;
+    fs::create_dir_all(&fish_dir)?;
+    for (path, contents) in [
+        (scripts_dir.join("zed.bash"), BASH_SCRIPT),
+        (zsh_dir.join(".zshenv"), ZSHENV_SCRIPT),
+        (zsh_dir.join(".zprofile"), ZPROFILE_SCRIPT),
+        (zsh_dir.join(".zshrc"), ZSHRC_SCRIPT),
+        (fish_dir.join("zed.fish"), FISH_SCRIPT),
+    ] {
+        if fs::read_to_string(&path).ok().as_deref() != Some(contents) {
+            fs::write(&path, contents).with_context(|| format!("writing {path:?}"))?;
+        }
+    }
+    Ok(())
+}
+
+fn quote_for_posix_shell(path: &Path) -> String {
+    format!("'{}'", path.to_string_lossy().replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter_chunks(filter: &mut PromptMarkFilter, chunks: &[&[u8]]) -> Vec<u8> {
+        let mut output = Vec::new();
+        for chunk in chunks {
+            filter.filter(chunk, &mut output);
+        }
+        output
+    }
+
+    #[test]
+    fn test_other_output_passes_through() {
+        let mut filter = PromptMarkFilter::new(TerminalCommands::default());
+        let input: &[u8] = b"plain \x1b[31mred\x1b[0m \x1b]0;title\x07 \x1b]13;x\x1b\\ \x1b]8;;https://zed.dev\x1b\\link\x1b]8;;\x1b\\";
+        for split in 0..input.len() {
+            let (first, second) = input.split_at(split);
+            assert_eq!(
+                filter_chunks(&mut filter, &[first, second]),
+                input,
+                "split at {split}"
+            );
+        }
+        assert!(filter.commands.is_empty());
+    }
+
+    #[test]
+    fn test_command_records() {
+        let commands = TerminalCommands::with_nonce("nonce".to_string());
+        let mut filter = PromptMarkFilter::new(commands.clone());
+
+        let output = filter_chunks(
+            &mut filter,
+            &[
+                b"\x1b]633;P;Cwd=/tmp/a\\x3bb\x07\x1b]133;A\x07$ \x1b]133;B\x07",
+                b"ls\r\n\x1b]633;E;ls;nonce\x07\x1b]133;",
+                b"C\x07file\r\n\x1b]133;D;2\x1b\\",
+                b"\x1b]633;A\x07$ \x1b]633;B\x07\r\n\x1b]633;D\x07",
+                b"\x1b]633;A\x07$ \x1b]633;B\x07",
+            ],
+        );
+        let output = String::from_utf8_lossy(&output);
+        assert!(output.starts_with("\x1b]8;;zed-command:0:prompt\x1b\\$ "));
+        assert!(output.contains("\x1b]8;;zed-command:0:input\x1b\\ls\r\n\x1b]8;;\x1b\\file"));
+
+        // The empty prompt in between got dropped.
+        let commands = commands.all();
+        assert_eq!(commands.len(), 2);
+        assert_eq!(commands[0].id, 0);
+        assert_eq!(commands[0].command.as_deref(), Some("ls"));
+        assert_eq!(commands[0].cwd.as_deref(), Some("/tmp/a;b"));
+        assert_eq!(commands[0].exit_code, Some(2));
+        assert!(commands[0].has_finished() && commands[0].failed());
+        assert_eq!(commands[1].id, 2);
+        assert!(!commands[1].has_started());
+    }
+
+    #[test]
+    fn test_command_lines_need_nonce() {
+        let commands = TerminalCommands::with_nonce("nonce".to_string());
+        let mut filter = PromptMarkFilter::new(commands.clone());
+        filter_chunks(
+            &mut filter,
+            &[b"\x1b]633;A\x07$ \x1b]633;B\x07\x1b]633;E;rm -rf ~\x07\x1b]633;E;rm -rf ~;other\x07"],
+        );
+        assert_eq!(commands.all()[0].command, None);
+
+        // Without a nonce, no command line is trusted.
+        let commands = TerminalCommands::default();
+        let mut filter = PromptMarkFilter::new(commands.clone());
+        filter_chunks(
+            &mut filter,
+            &[b"\x1b]633;A\x07$ \x1b]633;B\x07\x1b]633;E;rm -rf ~;\x07"],
+        );
+        assert_eq!(commands.all()[0].command, None);
+    }
+
+    #[test]
+    fn test_command_mark_uri_round_trip() {
+        let mark = CommandMark {
+            command_id: 7,
+            kind: CommandMarkKind::Input,
+        };
+        assert_eq!(CommandMark::from_uri(&mark.uri()), Some(mark));
+        assert_eq!(CommandMark::from_uri("zed-command:7:output"), None);
+        assert_eq!(CommandMark::from_uri("zed-image:7:0"), None);
+    }
+
+    #[test]
+    fn test_unescape_value() {
+        assert_eq!(
+            unescape_value(r"echo a\x3b b \\x3b\x0a"),
+            "echo a; b \\x3b\n"
+        );
+        assert_eq!(unescape_value(r"trailing \x4"), r"trailing \x4");
+    }
+}
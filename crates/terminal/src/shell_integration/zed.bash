@@ -0,0 +1,62 @@
+# Zed shell integration for bash 4.4 and later.
+#
+# Sourced from PROMPT_COMMAND on the first prompt. Reports where prompts,
+# command lines and command output start using OSC 633 sequences.
+
+if [[ -n "$__zed_shell_integration" || $- != *i* ]]; then
+    return
+fi
+__zed_shell_integration=1
+# Command lines are reported with this, so Zed can tell them apart from
+# sequences printed by programs, which therefore mustn't see it.
+__zed_nonce=$ZED_SHELL_INTEGRATION_NONCE
+unset ZED_SHELL_INTEGRATION_NONCE
+
+__zed_escape() {
+    local value=${1//\\/\\\\}
+    value=${value//;/\\x3b}
+    value=${value//$'\n'/\\x0a}
+    printf '%s' "$value"
+}
+
+__zed_history() {
+    HISTTIMEFORMAT= builtin history 1
+}
+
+__zed_precmd() {
+    local status=$?
+    __zed_last_history=$(__zed_history)
+    printf '\e]633;D;%s\a' "$status"
+    printf '\e]633;P;Cwd=%s\a' "$(__zed_escape "$PWD")"
+    printf '\e]633;A\a'
+    # Prompt themes may rebuild PS1 before every prompt.
+    if [[ $PS1 != *'\[\e]633;B\a\]' ]]; then
+        PS1="$PS1"'\[\e]633;B\a\]'
+    fi
+    return $status
+}
+
+# Runs in a subshell while PS0 is expanded, after the command line was added
+# to the history. It isn't when it's ignored through HISTCONTROL.
+__zed_preexec() {
+    local command_line
+    command_line=$(__zed_history)
+    if [[ $command_line != "$__zed_last_history" ]]; then
+        if [[ $command_line =~ ^[[:space:]]*[0-9]+[*]?[[:space:]]+ ]]; then
+            command_line=${command_line:${#BASH_REMATCH[0]}}
+        fi
+        printf '\e]633;E;%s;%s\a' "$(__zed_escape "$command_line")" "$__zed_nonce"
+    fi
+    printf '\e]633;C\a'
+}
+
+PS0="$PS0"'$(__zed_preexec)'
+PROMPT_COMMAND="__zed_precmd;$PROMPT_COMMAND"
+
+# This prompt is already being drawn, so mark it now.
+__zed_last_history=$(__zed_history)
+printf '\e]633;P;Cwd=%s\a' "$(__zed_escape "$PWD")"
+printf '\e]633;A\a'
+if [[ $PS1 != *'\[\e]633;B\a\]' ]]; then
+    PS1="$PS1"'\[\e]633;B\a\]'
+fi
@@ -0,0 +1,49 @@
+# Zed shell integration for fish.
+#
+# Zed adds this file's data directory to XDG_DATA_DIRS so that fish loads it
+# as a vendor configuration snippet. Reports where prompts, command lines and
+# command output start using OSC 633 sequences.
+
+if set -q ZED_ORIGINAL_XDG_DATA_DIRS
+    set -gx XDG_DATA_DIRS $ZED_ORIGINAL_XDG_DATA_DIRS
+    set -e ZED_ORIGINAL_XDG_DATA_DIRS
+else
+    set -e XDG_DATA_DIRS
+end
+
+# Command lines are reported with this, so Zed can tell them apart from
+# sequences printed by programs, which therefore mustn't see it.
+set -g __zed_nonce "$ZED_SHELL_INTEGRATION_NONCE"
+set -e ZED_SHELL_INTEGRATION_NONCE
+
+if not status is-interactive; or set -q __zed_shell_integration
+    exit
+end
+set -g __zed_shell_integration 1
+
+function __zed_escape
+    string join '\x0a' (string replace --all -- '\\' '\\\\' $argv | string replace --all -- ';' '\\x3b')
+end
+
+function __zed_prompt_start --on-event fish_prompt
+    printf '\e]633;P;Cwd=%s\a' (__zed_escape $PWD)
+    printf '\e]633;A\a'
+    # The user's prompt is only defined once their config has run, so wrap it
+    # on the first prompt to mark where command input starts.
+    if not functions -q __zed_original_fish_prompt; and functions -q fish_prompt
+        functions -c fish_prompt __zed_original_fish_prompt
+        function fish_prompt
+            __zed_original_fish_prompt
+            printf '\e]633;B\a'
+        end
+    end
+end
+
+function __zed_preexec --on-event fish_preexec
+    printf '\e]633;E;%s;%s\a' (__zed_escape $argv) $__zed_nonce
+    printf '\e]633;C\a'
+end
+
+function __zed_postexec --on-event fish_postexec
+    printf '\e]633;D;%s\a' $status
+end
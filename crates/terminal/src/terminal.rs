@@ -1,7 +1,9 @@
 mod mappings;
 
 mod alacritty;
+mod graphics;
 mod pty_info;
+mod shell_integration;
 pub mod terminal_settings;
 
 #[cfg(not(windows))]
@@ -33,6 +35,8 @@ use theme::{ActiveTheme, Theme};
 use urlencoding;
 use util::{ResultExt as _, paths::PathStyle, truncate_and_trailoff};
 
+pub use graphics::{ImageAnchor, TerminalImage, TerminalImages};
+pub use shell_integration::{CommandMark, CommandMarkKind, TerminalCommand, TerminalCommands};
 #[cfg(unix)]
 use std::os::unix::process::ExitStatusExt;
 use std::{
@@ -62,15 +66,17 @@ use gpui::{
 use crate::alacritty::current_child_signal_mask;
 use crate::alacritty::{
     AlacrittyCell, AlacrittyGridIterator, AlacrittyHyperlink, AlacrittySearch, AlacrittyTerm,
-    AlacrittyTermConfig, AlacrittyTermLock, HyperlinkMatch, PtySender, RegexSearches,
-    append_text_to_term, apply_config, clear_saved_screen, content_text, display_offset,
-    display_only_term_config, find_from_terminal_point, full_content_range, last_non_empty_lines,
-    make_content, new_term, open_pty, pty_options, pty_term_config, resize, screen_lines,
-    scroll_display, scroll_to_point, search_matches, selection_text, set_default_cursor_style,
-    set_selection as set_term_selection, spawn_event_loop, toggle_vi_mode as toggle_term_vi_mode,
-    total_lines, update_selection as update_term_selection, update_selection_to_vi_cursor,
-    update_vi_cursor_for_scroll, vi_goto_point, vi_motion,
+    AlacrittyTermConfig, AlacrittyTermLock, CommandMarkRun, HyperlinkMatch, PtySender,
+    RegexSearches, append_text_to_term, apply_config, clear_saved_screen, command_mark_runs,
+    content_text, display_offset, display_only_term_config, find_from_terminal_point,
+    full_content_range, last_column, last_non_empty_lines, make_content, new_term, open_pty,
+    pty_options, pty_term_config, resize, screen_lines, scroll_display, scroll_to_point,
+    search_matches, selection_text, serialize_scrollback, set_default_cursor_style,
+    set_selection as set_term_selection, spawn_event_loop, text_in_range,
+    toggle_vi_mode as toggle_term_vi_mode, total_lines, update_selection as update_term_selection,
+    update_selection_to_vi_cursor, update_vi_cursor_for_scroll, vi_goto_point, vi_motion,
 };
+use crate::graphics::GraphicsFilter;
 use crate::mappings::colors::to_vte_rgb;
 use crate::mappings::keys::to_esc_str;
 
@@ -624,6 +630,14 @@ actions!(
         ToggleViMode,
         /// Selects all text in the terminal.
         SelectAll,
+        /// Scrolls up to the previous shell prompt.
+        ScrollToPreviousPrompt,
+        /// Scrolls down to the next shell prompt.
+        ScrollToNextPrompt,
+        /// Copies the output of the last finished command to the clipboard.
+        CopyLastCommandOutput,
+        /// Runs the last command again.
+        RerunLastCommand,
     ]
 );
 
@@ -870,6 +884,9 @@ impl Display for TerminalError {
 // https://github.com/alacritty/alacritty/blob/cb3a79dbf6472740daca8440d5166c1d4af5029e/extra/man/alacritty.5.scd?plain=1#L207-L213
 const DEFAULT_SCROLL_HISTORY_LINES: usize = 10_000;
 pub const MAX_SCROLL_HISTORY_LINES: usize = 100_000;
+/// Written below restored scrollback, so it reads as history rather than as
+/// output of the new shell.
+const RESTORED_SCROLLBACK_SEPARATOR: &str = "\x1b[0m\x1b[2m--- Restored session ---\x1b[0m\r\n";
 static NEXT_INIT_COMMAND_STARTUP_MARKER_ID: AtomicU64 = AtomicU64::new(1);
 
 const INIT_COMMAND_STARTUP_MARKER_PREFIX: &str = "__zed_init_command_ready_";
@@ -952,6 +969,8 @@ impl TerminalBuilder {
 
         let (events_tx, events_rx) = unbounded();
         let term = new_term(&config, terminal_bounds, events_tx, alternate_scroll);
+        let images = TerminalImages::default();
+        images.set_cell_size(&terminal_bounds);
 
         let terminal = Terminal {
             task: None,
@@ -961,6 +980,9 @@ impl TerminalBuilder {
             term,
             term_config: config,
             output_processor: Processor::<StdSyncHandler>::new(),
+            output_filter: GraphicsFilter::new(images.clone(), None),
+            images,
+            commands: TerminalCommands::default(),
             title_override: None,
             events: VecDeque::with_capacity(10),
             last_content: Content {
@@ -993,6 +1015,7 @@ impl TerminalBuilder {
                 max_scroll_history_lines,
                 path_hyperlink_regexes: Vec::default(),
                 path_hyperlink_timeout_ms: 0,
+                shell_integration: false,
                 window_id,
             },
             child_exited: None,
@@ -1022,6 +1045,8 @@ impl TerminalBuilder {
         max_scroll_history_lines: Option<usize>,
         path_hyperlink_regexes: Vec<String>,
         path_hyperlink_timeout_ms: u64,
+        shell_integration: bool,
+        restored_scrollback: Option<String>,
         is_remote_terminal: bool,
         window_id: u64,
         completion_tx: Option<Sender<Option<ExitStatus>>>,
@@ -1035,6 +1060,9 @@ impl TerminalBuilder {
         // allocation / acquiring a controlling terminal fails with `ENOTTY`.
         // When set, run the command as a plain subprocess instead.
         let no_pty = HeadlessTerminal::is_enabled(cx);
+        // Read from the user's settings only, so that a project can't enable it.
+        let allow_image_file_transmission =
+            TerminalSettings::get_global(cx).allow_image_file_transmission;
         #[cfg(not(windows))]
         let child_signal_mask = match current_child_signal_mask()
             .context("failed to capture terminal child signal mask")
@@ -1058,6 +1086,20 @@ impl TerminalBuilder {
 
             insert_zed_terminal_env(&mut env, &version);
 
+            // Tasks don't show prompts, and remote shells can't read our scripts.
+            let shell_integration_nonce =
+                (shell_integration && task.is_none() && !is_remote_terminal)
+                    .then(shell_integration::new_nonce);
+            if let Some(nonce) = &shell_integration_nonce {
+                let shell_program = match &shell {
+                    Shell::System => util::shell::get_system_shell(),
+                    Shell::Program(program) | Shell::WithArguments { program, .. } => {
+                        program.clone()
+                    }
+                };
+                shell_integration::insert_shell_integration_env(&mut env, &shell_program, nonce);
+            }
+
             #[derive(Default)]
             struct ShellParams {
                 program: String,
@@ -1140,6 +1182,20 @@ impl TerminalBuilder {
                 events_tx.clone(),
                 alternate_scroll,
             );
+            // Restore the history before the shell can print its first prompt.
+            let restored_scrollback = restored_scrollback.filter(|_| task.is_none());
+            if let Some(scrollback) = &restored_scrollback {
+                let mut processor = Processor::<StdSyncHandler>::new();
+                let mut term = term.lock();
+                processor.advance(&mut *term, scrollback.as_bytes());
+                processor.advance(&mut *term, RESTORED_SCROLLBACK_SEPARATOR.as_bytes());
+            }
+
+            let images = TerminalImages::default();
+            images.set_allow_file_transmission(allow_image_file_transmission);
+            let commands = shell_integration_nonce
+                .map(TerminalCommands::with_nonce)
+                .unwrap_or_default();
 
             // When `no_pty` is set (headless hosts), run the task as a plain
             // subprocess and pump its piped output into the same emulator the
@@ -1159,6 +1215,7 @@ impl TerminalBuilder {
                     working_directory.clone(),
                     term.clone(),
                     events_tx,
+                    images.clone(),
                     &background_executor,
                 ) {
                     Ok(subprocess) => subprocess,
@@ -1211,8 +1268,14 @@ impl TerminalBuilder {
                 let pty_info = PtyProcessInfo::new(ProcessIdGetter::from(&pty));
 
                 //And connect them together
-                let pty_tx =
-                    spawn_event_loop(term.clone(), events_tx, pty, pty_options.drain_on_exit)?;
+                let pty_tx = spawn_event_loop(
+                    term.clone(),
+                    events_tx,
+                    pty,
+                    pty_options.drain_on_exit,
+                    images.clone(),
+                    commands.clone(),
+                )?;
 
                 (
                     TerminalType::Pty {
@@ -1232,6 +1295,9 @@ impl TerminalBuilder {
                 term,
                 term_config: config,
                 output_processor: Processor::<StdSyncHandler>::new(),
+                output_filter: GraphicsFilter::new(images.clone(), None),
+                images,
+                commands,
                 title_override: terminal_title_override,
                 events: VecDeque::with_capacity(10), //Should never get this high.
                 last_content: Default::default(),
@@ -1264,6 +1330,7 @@ impl TerminalBuilder {
                     max_scroll_history_lines,
                     path_hyperlink_regexes,
                     path_hyperlink_timeout_ms,
+                    shell_integration,
                     window_id,
                 },
                 child_exited: None,
@@ -1293,9 +1360,12 @@ impl TerminalBuilder {
                 // and while we have sent the activation script to the pty, it will be executed asynchronously.
                 // Therefore, we somehow need to wait for the activation script to finish executing before we
                 // can proceed with clearing the screen.
-                terminal.write_to_pty(shell_kind.clear_screen_command().as_bytes());
-                // Simulate enter key press
-                terminal.write_to_pty(b"\x0d");
+                // Clearing would also wipe restored scrollback, so keep the activation output then.
+                if restored_scrollback.is_none() {
+                    terminal.write_to_pty(shell_kind.clear_screen_command().as_bytes());
+                    // Simulate enter key press
+                    terminal.write_to_pty(b"\x0d");
+                }
             }
 
             Ok(TerminalBuilder {
@@ -1407,6 +1477,10 @@ pub struct Terminal {
     term: Arc<AlacrittyTermLock>,
     term_config: AlacrittyTermConfig,
     output_processor: Processor<StdSyncHandler>,
+    /// Extracts inline images from output injected with [`Terminal::write_output`].
+    output_filter: GraphicsFilter,
+    images: TerminalImages,
+    commands: TerminalCommands,
     events: VecDeque<InternalEvent>,
     /// This is only used for mouse mode cell change detection
     last_mouse: Option<(Point, SelectionSide)>,
@@ -1452,6 +1526,7 @@ struct CopyTemplate {
     max_scroll_history_lines: Option<usize>,
     path_hyperlink_regexes: Vec<String>,
     path_hyperlink_timeout_ms: u64,
+    shell_integration: bool,
     window_id: u64,
 }
 
@@ -1599,6 +1674,7 @@ impl Terminal {
                 trace!("Resizing: new_bounds={new_bounds:?}");
 
                 self.last_content.terminal_bounds = new_bounds;
+                self.images.set_cell_size(&new_bounds);
 
                 if let TerminalType::Pty { pty_tx, .. } = &self.terminal_type {
                     pty_tx.resize(new_bounds);
@@ -1809,10 +1885,134 @@ impl Terminal {
         res
     }
 
+    /// Inline images placed by Sixel and Kitty graphics sequences, keyed by the
+    /// [`ImageAnchor`]s found in [`Content::cells`].
+    pub fn images(&self) -> &TerminalImages {
+        &self.images
+    }
+
+    /// Commands reported by the shell integration, keyed by the
+    /// [`CommandMark`]s found in [`Content::cells`].
+    pub fn commands(&self) -> &TerminalCommands {
+        &self.commands
+    }
+
+    /// The command line a command was run with. Falls back to the text typed
+    /// after its prompt when the shell didn't report it.
+    pub fn command_text(&self, command_id: u64) -> Option<String> {
+        if let Some(command) = self
+            .commands
+            .get(command_id)
+            .and_then(|command| command.command)
+        {
+            return Some(command);
+        }
+
+        let term = self.term.lock_unfair();
+        let input = command_mark_runs(&term).into_iter().find(|run| {
+            run.mark.command_id == command_id && run.mark.kind == CommandMarkKind::Input
+        })?;
+        let text = text_in_range(&term, Range::new(input.start, input.end));
+        let text = text.trim();
+        (!text.is_empty()).then(|| text.to_string())
+    }
+
+    /// The lines a command printed to, if they are still in the scrollback.
+    pub fn command_output_range(&self, command_id: u64) -> Option<Range> {
+        let term = self.term.lock_unfair();
+        command_output_range(
+            &command_mark_runs(&term),
+            command_id,
+            self.last_content.cursor.point,
+            last_column(&term),
+        )
+    }
+
+    pub fn scroll_to_previous_prompt(&mut self) {
+        self.scroll_to_prompt(true);
+    }
+
+    pub fn scroll_to_next_prompt(&mut self) {
+        self.scroll_to_prompt(false);
+    }
+
+    /// Scrolls the closest prompt above or below the top of the viewport to
+    /// the top.
+    fn scroll_to_prompt(&mut self, previous: bool) {
+        let term = self.term.lock_unfair();
+        let prompt_lines = prompt_lines(&command_mark_runs(&term));
+        let top_line = -(display_offset(&term) as i32);
+        drop(term);
+
+        let target_line = if previous {
+            prompt_lines.iter().rev().find(|&&line| line < top_line)
+        } else {
+            prompt_lines.iter().find(|&&line| line > top_line)
+        };
+        let scroll = match target_line {
+            Some(&line) => Scroll::Delta(top_line - line),
+            None if previous => return,
+            None => Scroll::Bottom,
+        };
+        self.events.push_back(InternalEvent::Scroll(scroll));
+    }
+
+    /// Copies what the last finished command printed. Returns whether there
+    /// was any such output.
+    pub fn copy_last_command_output(&self, cx: &mut App) -> bool {
+        let Some(range) = self
+            .commands
+            .all()
+            .iter()
+            .rev()
+            .filter(|command| command.has_finished())
+            .find_map(|command| self.command_output_range(command.id))
+        else {
+            return false;
+        };
+        let text = text_in_range(&self.term.lock_unfair(), range);
+        cx.write_to_clipboard(ClipboardItem::new_string(text.trim_end().to_string()));
+        true
+    }
+
+    /// Types the last command into the shell again and runs it. Does nothing
+    /// unless the shell is waiting at a prompt and reported that command's
+    /// line.
+    pub fn rerun_last_command(&mut self) -> bool {
+        let commands = self.commands.all();
+        if commands.last().is_none_or(|command| command.has_started()) {
+            return false;
+        }
+        // Unlike the command lines reported by the shell, prompt marks around
+        // text on the screen can be printed by any program.
+        let Some(command) = commands
+            .iter()
+            .rev()
+            .find(|command| command.has_started())
+            .and_then(|command| command.command.clone())
+        else {
+            return false;
+        };
+        self.paste(&command);
+        self.input(b"\r".as_slice());
+        true
+    }
+
     pub fn last_content(&self) -> &Content {
         &self.last_content
     }
 
+    /// Up to `max_lines` lines of output above the prompt, with their colors
+    /// encoded as SGR sequences. Pass the result to [`TerminalBuilder::new`]
+    /// to show it as history above a new shell.
+    ///
+    /// The lines are read and encoded on a background thread.
+    pub fn scrollback_snapshot(&self, max_lines: usize, cx: &App) -> Task<Option<String>> {
+        let term = self.term.clone();
+        let max_lines = max_lines.min(MAX_SCROLL_HISTORY_LINES);
+        cx.background_spawn(async move { serialize_scrollback(&term.lock_unfair(), max_lines) })
+    }
+
     pub fn set_cursor_shape(&mut self, cursor_shape: SettingsCursorShape) {
         set_default_cursor_style(&mut self.term_config, cursor_shape);
         apply_config(&self.term, &self.term_config);
@@ -1821,8 +2021,10 @@ impl Terminal {
     pub fn write_output(&mut self, bytes: &[u8], cx: &mut Context<Self>) {
         // Inject bytes directly into the terminal emulator and refresh the UI.
         // This bypasses the PTY/event loop for display-only terminals.
+        let mut filtered = Vec::with_capacity(bytes.len());
+        self.output_filter.filter(bytes, &mut filtered);
         let mut previous_byte_was_cr = false;
-        let converted = convert_lf_to_crlf(bytes, &mut previous_byte_was_cr);
+        let converted = convert_lf_to_crlf(&filtered, &mut previous_byte_was_cr);
 
         let mut term = self.term.lock();
         self.output_processor.advance(&mut *term, &converted);
@@ -2884,6 +3086,8 @@ impl Terminal {
             self.template.max_scroll_history_lines,
             self.template.path_hyperlink_regexes.clone(),
             self.template.path_hyperlink_timeout_ms,
+            self.template.shell_integration,
+            None,
             self.is_remote_terminal,
             self.template.window_id,
             None,
@@ -2894,6 +3098,44 @@ impl Terminal {
     }
 }
 
+/// First line of every command's prompt, top to bottom.
+fn prompt_lines(runs: &[CommandMarkRun]) -> Vec<i32> {
+    runs.iter()
+        .unique_by(|run| run.mark.command_id)
+        .map(|run| run.start.line)
+        .collect()
+}
+
+/// Output of a command spans from the line below its command line up to the
+/// next prompt, or up to the cursor while no other prompt follows.
+fn command_output_range(
+    runs: &[CommandMarkRun],
+    command_id: u64,
+    cursor: Point,
+    last_column: usize,
+) -> Option<Range> {
+    let start_line = runs
+        .iter()
+        .filter(|run| run.mark.command_id == command_id)
+        .map(|run| run.end.line)
+        .max()?
+        + 1;
+    let end = match runs
+        .iter()
+        .filter(|run| run.mark.command_id > command_id)
+        .map(|run| run.start)
+        .min()
+    {
+        // Output without a trailing newline ends on the next prompt's line.
+        Some(next_prompt) if next_prompt.column > 0 => {
+            Point::new(next_prompt.line, next_prompt.column - 1)
+        }
+        Some(next_prompt) => Point::new(next_prompt.line - 1, last_column),
+        None => Point::new(cursor.line, last_column),
+    };
+    (end.line >= start_line).then(|| Range::new(Point::new(start_line, 0), end))
+}
+
 const TASK_DELIMITER: &str = "⏵ ";
 fn task_summary(task: &TaskState, exit_status: Option<ExitStatus>) -> (bool, String, String) {
     let escaped_full_label = task
@@ -2978,6 +3220,7 @@ fn spawn_task_subprocess(
     working_directory: Option<PathBuf>,
     term: Arc<AlacrittyTermLock>,
     events_tx: futures::channel::mpsc::UnboundedSender<PtyEvent>,
+    images: TerminalImages,
     executor: &BackgroundExecutor,
 ) -> Result<SubprocessHandle> {
     use futures::io::AsyncReadExt as _;
@@ -3006,9 +3249,12 @@ fn spawn_task_subprocess(
             let pump = |reader: Option<BoxedReader>| {
                 let term = term.clone();
                 let events_tx = events_tx.clone();
+                let images = images.clone();
                 async move {
                     let Some(mut reader) = reader else { return };
                     let mut processor = Processor::<StdSyncHandler>::new();
+                    let mut filter = GraphicsFilter::new(images, None);
+                    let mut filtered = Vec::new();
                     let mut buffer = [0u8; 8192];
                     let mut previous_byte_was_cr = false;
                     loop {
@@ -3019,8 +3265,10 @@ fn spawn_task_subprocess(
                                 return;
                             }
                             Ok(count) => {
+                                filtered.clear();
+                                filter.filter(&buffer[..count], &mut filtered);
                                 let converted =
-                                    convert_lf_to_crlf(&buffer[..count], &mut previous_byte_was_cr);
+                                    convert_lf_to_crlf(&filtered, &mut previous_byte_was_cr);
                                 {
                                     let mut term = term.lock();
                                     processor.advance(&mut *term, &converted);
@@ -3436,6 +3684,8 @@ mod tests {
                     vec![],
                     0,
                     false,
+                    None,
+                    false,
                     0,
                     Some(completion_tx),
                     cx,
@@ -3487,6 +3737,8 @@ mod tests {
                     vec![],
                     0,
                     false,
+                    None,
+                    false,
                     0,
                     Some(completion_tx),
                     cx,
@@ -3769,6 +4021,8 @@ mod tests {
                     vec![],
                     0,
                     false,
+                    None,
+                    false,
                     0,
                     Some(completion_tx),
                     cx,
@@ -3837,6 +4091,8 @@ mod tests {
                     vec![],
                     0,
                     false,
+                    None,
+                    false,
                     0,
                     None,
                     cx,
@@ -3903,6 +4159,8 @@ mod tests {
                     Vec::new(),
                     0,
                     false,
+                    None,
+                    false,
                     0,
                     Some(completion_tx),
                     cx,
@@ -4614,6 +4872,8 @@ mod tests {
                         settings.path_hyperlink_regexes.clone(),
                         test_path_hyperlink_timeout_ms,
                         false,
+                        None,
+                        false,
                         window.window_handle().window_id().as_u64(),
                         None,
                         cx,
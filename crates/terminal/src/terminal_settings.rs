@@ -48,8 +48,11 @@ pub struct TerminalSettings {
     pub minimum_contrast: f32,
     pub path_hyperlink_regexes: Vec<String>,
     pub path_hyperlink_timeout_ms: u64,
+    pub shell_integration: bool,
+    pub persisted_scrollback_lines: usize,
     pub show_count_badge: bool,
     pub bell: TerminalBell,
+    pub allow_image_file_transmission: bool,
 }
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
@@ -130,8 +133,11 @@ impl settings::Settings for TerminalSettings {
                 })
                 .collect(),
             path_hyperlink_timeout_ms: project_content.path_hyperlink_timeout_ms.unwrap(),
+            shell_integration: project_content.shell_integration.unwrap(),
+            persisted_scrollback_lines: project_content.persisted_scrollback_lines.unwrap(),
             show_count_badge: user_content.show_count_badge.unwrap(),
             bell: user_content.bell.unwrap(),
+            allow_image_file_transmission: user_content.allow_image_file_transmission.unwrap(),
         }
     }
 }
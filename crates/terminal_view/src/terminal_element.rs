@@ -1,21 +1,23 @@
+use collections::HashSet;
 use editor::{CursorLayout, EditorSettings, HighlightedRange, HighlightedRangeLine};
 use gpui::{
-    AbsoluteLength, AnyElement, App, AvailableSpace, Bounds, ContentMask, Context, DispatchPhase,
-    Element, ElementId, Entity, FocusHandle, Font, FontFeatures, FontStyle, FontWeight,
-    GlobalElementId, HighlightStyle, Hitbox, Hsla, InputHandler, InteractiveElement, Interactivity,
-    IntoElement, LayoutId, Length, ModifiersChangedEvent, MouseButton, MouseMoveEvent, Pixels,
-    Point as GpuiPoint, StatefulInteractiveElement, StrikethroughStyle, Styled, TextRun, TextStyle,
-    UTF16Selection, UnderlineStyle, WeakEntity, WhiteSpace, Window, div, fill, point, px, relative,
-    size,
+    AbsoluteLength, AnyElement, App, AvailableSpace, Bounds, ContentMask, Context, Corners,
+    DispatchPhase, Element, ElementId, Entity, FocusHandle, Font, FontFeatures, FontStyle,
+    FontWeight, GlobalElementId, HighlightStyle, Hitbox, Hsla, InputHandler, InteractiveElement,
+    Interactivity, IntoElement, LayoutId, Length, ModifiersChangedEvent, MouseButton,
+    MouseMoveEvent, Pixels, Point as GpuiPoint, StatefulInteractiveElement, StrikethroughStyle,
+    Styled, TextRun, TextStyle, UTF16Selection, UnderlineStyle, WeakEntity, WhiteSpace, Window,
+    div, fill, point, px, relative, size,
 };
 use itertools::Itertools;
 use language::CursorShape as EditorCursorShape;
 use settings::Settings;
 use std::time::Instant;
 use terminal::{
-    Cell, Color, Content, CursorShape, IndexedCell, Modes, NamedColor, Point, Range, Terminal,
-    TerminalBounds, is_app_chosen_exact_color as terminal_is_app_chosen_exact_color,
-    is_default_background_color, terminal_settings::TerminalSettings,
+    Cell, Color, CommandMarkKind, Content, CursorShape, IndexedCell, Modes, NamedColor, Point,
+    Range, Terminal, TerminalBounds, TerminalCommands, TerminalImage, TerminalImages,
+    is_app_chosen_exact_color as terminal_is_app_chosen_exact_color, is_default_background_color,
+    terminal_settings::TerminalSettings,
 };
 use theme::{ActiveTheme, Theme};
 use theme_settings::ThemeSettings;
@@ -34,6 +36,8 @@ pub struct LayoutState {
     hitbox: Hitbox,
     batched_text_runs: Vec<BatchedTextRun>,
     rects: Vec<LayoutRect>,
+    images: Vec<LayoutImage>,
+    failed_command_lines: Vec<i32>,
     relative_highlighted_ranges: Vec<(Range, Hsla)>,
     cursor: Option<CursorLayout>,
     ime_cursor_bounds: Option<Bounds<Pixels>>,
@@ -216,6 +220,46 @@ impl LayoutRect {
     }
 }
 
+/// An inline image, positioned by the cell its top-left corner falls on.
+/// The corner may be above the viewport when only the image's lower rows are
+/// visible.
+pub struct LayoutImage {
+    point: LayoutPoint,
+    image: TerminalImage,
+}
+
+impl LayoutImage {
+    pub fn paint(
+        &self,
+        origin: GpuiPoint<Pixels>,
+        dimensions: &TerminalBounds,
+        window: &mut Window,
+    ) {
+        let position = point(
+            (origin.x + self.point.column as f32 * dimensions.cell_width).floor(),
+            origin.y + self.point.line as f32 * dimensions.line_height,
+        );
+        let size = if self.image.fit_to_cells {
+            size(
+                dimensions.cell_width * self.image.columns as f32,
+                dimensions.line_height * self.image.rows as f32,
+            )
+        } else {
+            self.image.pixel_size()
+        };
+
+        window
+            .paint_image(
+                Bounds::new(position, size),
+                Corners::default(),
+                self.image.image.clone(),
+                0,
+                false,
+            )
+            .log_err();
+    }
+}
+
 /// Represents a rectangular region with a specific background color
 #[derive(Debug, Clone)]
 struct BackgroundRegion {
@@ -369,6 +413,64 @@ impl TerminalElement {
         .track_focus(&focus)
     }
 
+    /// Finds the inline images that overlap the visible cells. Each row of an
+    /// image starts with an anchor cell, so an image is found as long as any
+    /// of its rows is on screen.
+    fn layout_images(cells: &[IndexedCell], images: &TerminalImages) -> Vec<LayoutImage> {
+        if images.is_empty() {
+            return Vec::new();
+        }
+
+        let mut seen_images = HashSet::default();
+        let mut layout_images = Vec::new();
+        let linegroups = cells.iter().chunk_by(|cell| cell.point.line);
+        for (line_index, (_, line)) in linegroups.into_iter().enumerate() {
+            for cell in line {
+                let Some(anchor) = cell.image_anchor() else {
+                    continue;
+                };
+                if !seen_images.insert(anchor.image_id) {
+                    continue;
+                }
+                if let Some(image) = images.get(anchor.image_id) {
+                    layout_images.push(LayoutImage {
+                        point: LayoutPoint::new(
+                            line_index as i32 - anchor.row as i32,
+                            cell.point.column as i32,
+                        ),
+                        image,
+                    });
+                }
+            }
+        }
+        layout_images
+    }
+
+    /// Finds the visible prompt lines of commands that exited with a non-zero
+    /// status, so they can be marked in the gutter.
+    fn layout_failed_commands(cells: &[IndexedCell], commands: &TerminalCommands) -> Vec<i32> {
+        if commands.is_empty() {
+            return Vec::new();
+        }
+
+        let linegroups = cells.iter().chunk_by(|cell| cell.point.line);
+        linegroups
+            .into_iter()
+            .enumerate()
+            .filter_map(|(line_index, (_, mut line))| {
+                line.any(|cell| {
+                    cell.command_mark().is_some_and(|mark| {
+                        mark.kind == CommandMarkKind::Prompt
+                            && commands
+                                .get(mark.command_id)
+                                .is_some_and(|command| command.failed())
+                    })
+                })
+                .then_some(line_index as i32)
+            })
+            .collect()
+    }
+
     pub fn layout_grid<T: TerminalLayoutCell>(
         grid: impl Iterator<Item = T>,
         start_line_offset: i32,
@@ -1208,6 +1310,12 @@ impl Element for TerminalElement {
                     )
                 };
 
+                let images = TerminalElement::layout_images(cells, self.terminal.read(cx).images());
+                let failed_command_lines = TerminalElement::layout_failed_commands(
+                    cells,
+                    self.terminal.read(cx).commands(),
+                );
+
                 // Layout cursor. Rectangle is used for IME, so we should lay it out even
                 // if we don't end up showing it.
                 let cursor_point = DisplayCursor::from(cursor.point, display_offset);
@@ -1309,6 +1417,8 @@ impl Element for TerminalElement {
                     background_color,
                     dimensions,
                     rects,
+                    images,
+                    failed_command_lines,
                     relative_highlighted_ranges,
                     mode,
                     display_offset,
@@ -1400,6 +1510,26 @@ impl Element for TerminalElement {
                         rect.paint(origin, &layout.dimensions, window);
                     }
 
+                    for image in &layout.images {
+                        image.paint(origin, &layout.dimensions, window);
+                    }
+
+                    let failed_command_color = cx.theme().status().error;
+                    for line in &layout.failed_command_lines {
+                        let marker_width = (layout.dimensions.cell_width / 4.).max(px(2.));
+                        let marker_origin = GpuiPoint::new(
+                            origin.x - layout.dimensions.cell_width / 2. - marker_width / 2.,
+                            origin.y + layout.dimensions.line_height * *line as f32,
+                        );
+                        window.paint_quad(fill(
+                            Bounds::new(
+                                marker_origin,
+                                size(marker_width, layout.dimensions.line_height),
+                            ),
+                            failed_command_color,
+                        ));
+                    }
+
                     for (relative_highlighted_range, color) in &layout.relative_highlighted_ranges {
                         if let Some((start_y, highlighted_range_lines)) =
                             to_highlighted_range_lines(relative_highlighted_range, layout, origin)
@@ -20,7 +20,8 @@ use project::{Project, ProjectEntryId, search::SearchQuery};
 use schemars::JsonSchema;
 use serde::Deserialize;
 use settings::{
-    SeedQuerySetting, Settings, SettingsStore, TerminalBell, TerminalBlink, WorkingDirectory,
+    SeedQuerySetting, Settings, SettingsLocation, SettingsStore, TerminalBell, TerminalBlink,
+    WorkingDirectory,
 };
 use std::{
     any::Any,
@@ -33,9 +34,10 @@ use std::{
 };
 use task::TaskId;
 use terminal::{
-    Clear, Copy, Event, HoveredWord, MaybeNavigationTarget, Modes, Paste, PasteText, Point, Range,
-    ScrollLineDown, ScrollLineUp, ScrollPageDown, ScrollPageUp, ScrollToBottom, ScrollToTop,
-    Search, ShowCharacterPalette, TaskState, TaskStatus, Terminal, TerminalBounds, ToggleViMode,
+    Clear, Copy, CopyLastCommandOutput, Event, HoveredWord, MaybeNavigationTarget, Modes, Paste,
+    PasteText, Point, Range, RerunLastCommand, ScrollLineDown, ScrollLineUp, ScrollPageDown,
+    ScrollPageUp, ScrollToBottom, ScrollToNextPrompt, ScrollToPreviousPrompt, ScrollToTop, Search,
+    ShowCharacterPalette, TaskState, TaskStatus, Terminal, TerminalBounds, ToggleViMode,
     terminal_settings::{CursorShape, TerminalSettings},
 };
 use terminal_element::TerminalElement;
@@ -47,7 +49,7 @@ use ui::{
     prelude::*,
     scrollbars::{self, ScrollbarVisibility},
 };
-use util::ResultExt;
+use util::{ResultExt, rel_path::RelPath};
 use workspace::{
     CloseActiveItem, DraggedSelection, DraggedTab, NewCenterTerminal, NewTerminal, Pane,
     ToolbarItemLocation, Workspace, WorkspaceId, delete_unloaded_items,
@@ -76,6 +78,7 @@ fn viewport_line_for_point(point: Point, display_offset: usize) -> Option<usize>
 }
 
 const CURSOR_BLINK_INTERVAL: Duration = Duration::from_millis(500);
+const SCROLLBACK_SERIALIZATION_INTERVAL: Duration = Duration::from_secs(5);
 
 /// Event to transmit the scroll from the element to the view
 #[derive(Clone, Debug, PartialEq)]
@@ -143,6 +146,8 @@ pub struct TerminalView {
     show_workspace_actions: Option<bool>,
     blinking_terminal_enabled: bool,
     needs_serialize: bool,
+    scrollback_changed: bool,
+    scrollback_serialization: Option<Task<()>>,
     custom_title: Option<String>,
     hover: Option<HoverTarget>,
     hover_tooltip_update: Task<()>,
@@ -297,6 +302,8 @@ impl TerminalView {
             scroll_top: Pixels::ZERO,
             scroll_handle,
             needs_serialize: false,
+            scrollback_changed: false,
+            scrollback_serialization: None,
             custom_title: None,
             ime_state: None,
             self_handle: cx.entity().downgrade(),
@@ -525,6 +532,7 @@ impl TerminalView {
             .upgrade()
             .and_then(|workspace| workspace.read(cx).panel::<TerminalPanel>(cx))
             .is_some_and(|terminal_panel| terminal_panel.read(cx).assistant_enabled());
+        let has_commands = !self.terminal.read(cx).commands().is_empty();
         let context_menu = ContextMenu::build(window, cx, |menu, _, _| {
             menu.context(self.focus_handle.clone())
                 .when(self.shows_workspace_actions(), |menu| {
@@ -548,6 +556,14 @@ impl TerminalView {
                     !matches!(self.mode, TerminalMode::Embedded { .. }),
                     |menu| menu.action("Clear", Box::new(Clear)),
                 )
+                .when(
+                    has_commands && !matches!(self.mode, TerminalMode::Embedded { .. }),
+                    |menu| {
+                        menu.separator()
+                            .action("Copy Last Command Output", Box::new(CopyLastCommandOutput))
+                            .action("Rerun Last Command", Box::new(RerunLastCommand))
+                    },
+                )
                 .when(
                     assistant_enabled && !matches!(self.mode, TerminalMode::Embedded { .. }),
                     |menu| {
@@ -709,6 +725,29 @@ impl TerminalView {
         });
     }
 
+    /// Saves the scrollback at most once per [`SCROLLBACK_SERIALIZATION_INTERVAL`]
+    /// while output keeps coming, and once more after it stops.
+    fn schedule_scrollback_serialization(&mut self, cx: &mut Context<Self>) {
+        if self.workspace_id.is_none() || self.terminal.read(cx).task().is_some() {
+            return;
+        }
+        self.scrollback_changed = true;
+        if self.scrollback_serialization.is_some() {
+            return;
+        }
+        self.scrollback_serialization = Some(cx.spawn(async move |this, cx| {
+            cx.background_executor()
+                .timer(SCROLLBACK_SERIALIZATION_INTERVAL)
+                .await;
+            this.update(cx, |this, cx| {
+                this.scrollback_serialization = None;
+                this.needs_serialize = true;
+                cx.emit(ItemEvent::UpdateTab);
+            })
+            .ok();
+        }));
+    }
+
     fn is_alt_screen(&self, cx: &App) -> bool {
         self.terminal
             .read(cx)
@@ -824,6 +863,64 @@ impl TerminalView {
         cx.notify();
     }
 
+    fn scroll_to_previous_prompt(
+        &mut self,
+        _: &ScrollToPreviousPrompt,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.is_alt_screen(cx) {
+            cx.propagate();
+            return;
+        }
+
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_previous_prompt());
+        cx.notify();
+    }
+
+    fn scroll_to_next_prompt(
+        &mut self,
+        _: &ScrollToNextPrompt,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.is_alt_screen(cx) {
+            cx.propagate();
+            return;
+        }
+
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_next_prompt());
+        cx.notify();
+    }
+
+    fn copy_last_command_output(
+        &mut self,
+        _: &CopyLastCommandOutput,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self
+            .terminal
+            .update(cx, |term, cx| term.copy_last_command_output(cx))
+        {
+            cx.propagate();
+        }
+    }
+
+    fn rerun_last_command(&mut self, _: &RerunLastCommand, _: &mut Window, cx: &mut Context<Self>) {
+        if self
+            .terminal
+            .update(cx, |term, _| term.rerun_last_command())
+        {
+            self.blink_manager.update(cx, BlinkManager::pause_blinking);
+            cx.notify();
+        } else {
+            cx.propagate();
+        }
+    }
+
     fn toggle_vi_mode(&mut self, _: &ToggleViMode, _: &mut Window, cx: &mut Context<Self>) {
         self.terminal.update(cx, |term, _| term.toggle_vi_mode());
         cx.notify();
@@ -1128,6 +1225,7 @@ fn subscribe_for_terminal_events(
 
             match event {
                 Event::Wakeup => {
+                    terminal_view.schedule_scrollback_serialization(cx);
                     cx.notify();
                     cx.emit(Event::Wakeup);
                     cx.emit(ItemEvent::UpdateTab);
@@ -1358,6 +1456,10 @@ impl Render for TerminalView {
             .on_action(cx.listener(TerminalView::scroll_page_down))
             .on_action(cx.listener(TerminalView::scroll_to_top))
             .on_action(cx.listener(TerminalView::scroll_to_bottom))
+            .on_action(cx.listener(TerminalView::scroll_to_previous_prompt))
+            .on_action(cx.listener(TerminalView::scroll_to_next_prompt))
+            .on_action(cx.listener(TerminalView::copy_last_command_output))
+            .on_action(cx.listener(TerminalView::rerun_last_command))
             .on_action(cx.listener(TerminalView::toggle_vi_mode))
             .on_action(cx.listener(TerminalView::show_character_palette))
             .on_action(cx.listener(TerminalView::select_all))
@@ -1855,9 +1957,9 @@ impl SerializableItem for TerminalView {
 
     fn serialize(
         &mut self,
-        _workspace: &mut Workspace,
+        workspace: &mut Workspace,
         item_id: workspace::ItemId,
-        _closing: bool,
+        closing: bool,
         _: &mut Window,
         cx: &mut Context<Self>,
     ) -> Option<Task<anyhow::Result<()>>> {
@@ -1866,14 +1968,24 @@ impl SerializableItem for TerminalView {
             return None;
         }
 
-        if !self.needs_serialize {
+        if !self.needs_serialize && !closing {
             return None;
         }
 
         let workspace_id = self.workspace_id?;
         let cwd = terminal.working_directory();
         let custom_title = self.custom_title.clone();
+        // Unchanged output was already saved. The primary screen can't be read
+        // while an alternate screen application runs, so keep what was saved
+        // before it started.
+        let scrollback = (self.scrollback_changed && !self.is_alt_screen(cx)).then(|| {
+            let max_lines = persisted_scrollback_lines(workspace.project(), cwd.as_deref(), cx);
+            terminal.scrollback_snapshot(max_lines, cx)
+        });
         self.needs_serialize = false;
+        if scrollback.is_some() {
+            self.scrollback_changed = false;
+        }
 
         let db = TerminalDb::global(cx);
         Some(cx.background_spawn(async move {
@@ -1883,6 +1995,10 @@ impl SerializableItem for TerminalView {
             }
             db.save_custom_title(item_id, workspace_id, custom_title)
                 .await?;
+            if let Some(scrollback) = scrollback {
+                db.save_scrollback(item_id, workspace_id, scrollback.await)
+                    .await?;
+            }
             Ok(())
         }))
     }
@@ -1900,7 +2016,7 @@ impl SerializableItem for TerminalView {
         cx: &mut App,
     ) -> Task<anyhow::Result<Entity<Self>>> {
         window.spawn(cx, async move |cx| {
-            let (cwd, custom_title) = cx
+            let (cwd, custom_title, scrollback) = cx
                 .update(|_window, cx| {
                     let db = TerminalDb::global(cx);
                     let from_db = db
@@ -1922,13 +2038,20 @@ impl SerializableItem for TerminalView {
                         .log_err()
                         .flatten()
                         .filter(|title| !title.trim().is_empty());
-                    (cwd, custom_title)
+                    let keep_scrollback =
+                        persisted_scrollback_lines(&project, cwd.as_deref(), cx) > 0;
+                    let scrollback = keep_scrollback
+                        .then(|| db.get_scrollback(item_id, workspace_id).log_err().flatten())
+                        .flatten();
+                    (cwd, custom_title, scrollback)
                 })
                 .ok()
-                .unwrap_or((None, None));
+                .unwrap_or((None, None, None));
 
             let terminal = project
-                .update(cx, |project, cx| project.create_terminal_shell(cwd, cx))
+                .update(cx, |project, cx| {
+                    project.create_terminal_shell_with_scrollback(cwd, scrollback, cx)
+                })
                 .await?;
             cx.update(|window, cx| {
                 cx.new(|cx| {
@@ -2160,6 +2283,18 @@ fn first_project_directory(workspace: &Workspace, cx: &App) -> Option<PathBuf> {
     }
 }
 
+/// The `persisted_scrollback_lines` setting for a terminal, which projects can
+/// override to keep their terminals' output off disk.
+fn persisted_scrollback_lines(project: &Entity<Project>, cwd: Option<&Path>, cx: &App) -> usize {
+    let settings_location = cwd
+        .and_then(|cwd| project.read(cx).find_worktree(cwd, cx))
+        .map(|(worktree, _)| SettingsLocation {
+            worktree_id: worktree.read(cx).id(),
+            path: RelPath::empty(),
+        });
+    TerminalSettings::get(settings_location, cx).persisted_scrollback_lines
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -648,6 +648,170 @@ fn test_history() {
     assert_eq!(buffer.text(), "X12cde6");
 }
 
+#[test]
+fn test_undo_tree() {
+    let mut now = Instant::now();
+    let mut buffer = Buffer::new(ReplicaId::LOCAL, BufferId::new(1).unwrap(), "abc");
+
+    let transaction_1 = buffer.start_transaction_at(now).unwrap();
+    buffer.edit([(3..3, "d")]);
+    buffer.end_transaction_at(now);
+    now += Duration::from_secs(60);
+    let transaction_2 = buffer.start_transaction_at(now).unwrap();
+    buffer.edit([(4..4, "e")]);
+    buffer.end_transaction_at(now);
+    assert_eq!(buffer.text(), "abcde");
+
+    // Editing after an undo starts a new branch instead of discarding the old one.
+    buffer.undo();
+    now += Duration::from_secs(60);
+    let transaction_3 = buffer.start_transaction_at(now).unwrap();
+    buffer.edit([(4..4, "X")]);
+    buffer.end_transaction_at(now);
+    assert_eq!(buffer.text(), "abcdX");
+    buffer.redo();
+    assert_eq!(buffer.text(), "abcdX");
+
+    let tree = buffer.undo_tree();
+    assert_eq!(
+        tree.iter()
+            .map(|node| (node.transaction_id, node.parent, node.is_applied))
+            .collect::<Vec<_>>(),
+        [
+            (transaction_1, None, true),
+            (transaction_2, Some(transaction_1), false),
+            (transaction_3, Some(transaction_1), true),
+        ]
+    );
+
+    // Switch between sibling branches.
+    buffer
+        .travel_undo_tree(UndoTreeTarget::PreviousBranch)
+        .unwrap();
+    assert_eq!(buffer.text(), "abcde");
+    buffer.travel_undo_tree(UndoTreeTarget::NextBranch).unwrap();
+    assert_eq!(buffer.text(), "abcdX");
+
+    // The linear undo stack follows the checked out branch.
+    buffer
+        .travel_undo_tree(UndoTreeTarget::Transaction(Some(transaction_2)))
+        .unwrap();
+    assert_eq!(buffer.text(), "abcde");
+    buffer.undo();
+    assert_eq!(buffer.text(), "abcd");
+    buffer.redo();
+    assert_eq!(buffer.text(), "abcde");
+
+    // Step through states in the order they were created.
+    buffer.travel_undo_tree(UndoTreeTarget::Steps(1)).unwrap();
+    assert_eq!(buffer.text(), "abcdX");
+    buffer.travel_undo_tree(UndoTreeTarget::Steps(-2)).unwrap();
+    assert_eq!(buffer.text(), "abcd");
+    buffer.travel_undo_tree(UndoTreeTarget::Steps(-5)).unwrap();
+    assert_eq!(buffer.text(), "abc");
+    assert!(buffer.travel_undo_tree(UndoTreeTarget::Steps(-1)).is_none());
+
+    // Travel by time.
+    buffer
+        .travel_undo_tree(UndoTreeTarget::Later(Duration::from_secs(90)))
+        .unwrap();
+    assert_eq!(buffer.text(), "abcde");
+    buffer
+        .travel_undo_tree(UndoTreeTarget::Later(Duration::from_secs(60)))
+        .unwrap();
+    assert_eq!(buffer.text(), "abcdX");
+    buffer
+        .travel_undo_tree(UndoTreeTarget::Earlier(Duration::from_secs(90)))
+        .unwrap();
+    assert_eq!(buffer.text(), "abcd");
+    buffer
+        .travel_undo_tree(UndoTreeTarget::Earlier(Duration::from_secs(60)))
+        .unwrap();
+    assert_eq!(buffer.text(), "abc");
+
+    // Durations and counts too large to represent travel as far as possible.
+    buffer
+        .travel_undo_tree(UndoTreeTarget::Later(Duration::MAX))
+        .unwrap();
+    assert_eq!(buffer.text(), "abcdX");
+    buffer
+        .travel_undo_tree(UndoTreeTarget::Steps(isize::MIN))
+        .unwrap();
+    assert_eq!(buffer.text(), "abc");
+}
+
+#[test]
+fn test_serialize_undo_history() {
+    let mut buffer = Buffer::new(ReplicaId::LOCAL, BufferId::new(1).unwrap(), "one two");
+    assert_eq!(buffer.serialize_undo_history(), None);
+
+    buffer.edit([(3..3, " and a half")]);
+    buffer.edit([(0..3, "ONE"), (18..18, "!")]);
+    buffer.undo();
+    buffer.edit([(15..15, "three ")]);
+    buffer.edit([(0..0, "> ")]);
+    buffer.undo();
+    assert_eq!(buffer.text(), "one and a half three two");
+
+    let history = buffer.serialize_undo_history().unwrap();
+    assert_eq!(history.transactions.len(), 4);
+    assert_eq!(history.current, Some(2));
+    assert_eq!(
+        history.transactions[1].edits,
+        [
+            SerializedUndoEdit {
+                old_range: 0..3,
+                old_text: "one".into(),
+                new_text: "ONE".into(),
+            },
+            SerializedUndoEdit {
+                old_range: 18..18,
+                old_text: "".into(),
+                new_text: "!".into(),
+            },
+        ]
+    );
+
+    let mut restored = Buffer::new(ReplicaId::LOCAL, BufferId::new(2).unwrap(), buffer.text());
+    restored.restore_undo_history(&history).unwrap();
+    assert_eq!(restored.text(), "one and a half three two");
+    assert_eq!(
+        restored
+            .undo_tree()
+            .iter()
+            .map(|node| node.is_applied)
+            .collect::<Vec<_>>(),
+        [true, false, true, false]
+    );
+    let parents_and_edits = |history: &SerializedUndoHistory| {
+        history
+            .transactions
+            .iter()
+            .map(|transaction| (transaction.parent, transaction.edits.clone()))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        parents_and_edits(&restored.serialize_undo_history().unwrap()),
+        parents_and_edits(&history)
+    );
+
+    restored.redo();
+    assert_eq!(restored.text(), "> one and a half three two");
+    restored
+        .travel_undo_tree(UndoTreeTarget::PreviousBranch)
+        .unwrap();
+    assert_eq!(restored.text(), "ONE and a half two!");
+    restored.undo();
+    restored.undo();
+    assert_eq!(restored.text(), "one two");
+
+    // Restoring fails without side effects if the text doesn't match.
+    let mut changed = Buffer::new(ReplicaId::LOCAL, BufferId::new(3).unwrap(), "one two");
+    assert!(changed.restore_undo_history(&history).is_none());
+    assert_eq!(changed.text(), "one two");
+    assert!(changed.undo_tree().is_empty());
+}
+
 #[test]
 fn test_finalize_last_transaction() {
     let now = Instant::now();
@@ -23,18 +23,19 @@ use postage::{oneshot, prelude::*};
 use regex::Regex;
 pub use rope::*;
 pub use selection::*;
+use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 use std::{
     borrow::Cow,
     cmp::{self, Ordering, Reverse},
     fmt::Display,
     future::Future,
-    iter::Iterator,
+    iter::{self, Iterator},
     num::NonZeroU64,
     ops::{self, Deref, Range, Sub},
     str,
     sync::{Arc, LazyLock},
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime},
 };
 pub use subscription::*;
 pub use sum_tree::Bias;
@@ -150,11 +151,105 @@ impl HistoryEntry {
     }
 }
 
+/// A node in a buffer's undo tree, as reported by [`Buffer::undo_tree`].
+#[derive(Clone, Debug)]
+pub struct UndoTreeNode {
+    pub transaction_id: TransactionId,
+    /// The transaction this one was applied on top of, or `None` if it was
+    /// applied to the buffer's base text.
+    pub parent: Option<TransactionId>,
+    pub first_edit_at: Instant,
+    pub last_edit_at: Instant,
+    /// Whether this transaction is currently applied to the buffer.
+    pub is_applied: bool,
+}
+
+/// A destination to travel to in a buffer's undo tree.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UndoTreeTarget {
+    /// The state right after the given transaction, or the base text if `None`.
+    Transaction(Option<TransactionId>),
+    /// The most recent state on the next sibling branch.
+    NextBranch,
+    /// The most recent state on the previous sibling branch.
+    PreviousBranch,
+    /// Moves the given number of states forward (or backward, if negative)
+    /// in the order they were created, regardless of branch.
+    Steps(isize),
+    /// The state the buffer was in the given amount of time before the current one.
+    Earlier(Duration),
+    /// The state the buffer was in the given amount of time after the current one.
+    Later(Duration),
+}
+
+/// The outcome of [`Buffer::travel_undo_tree`].
+#[derive(Clone, Debug)]
+pub struct UndoTreeTravel {
+    /// The most recently applied transaction after traveling, or `None` if
+    /// the buffer is back at its base text.
+    pub current: Option<TransactionId>,
+    /// The transactions that were undone, in order.
+    pub undone: Vec<TransactionId>,
+    /// The transactions that were redone after undoing, in order.
+    pub redone: Vec<TransactionId>,
+}
+
+/// A buffer's undo tree in a form that doesn't refer to any CRDT state, so that
+/// it can be persisted and replayed onto a buffer with the same text.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SerializedUndoHistory {
+    /// Transactions in the order they were created, so that parents always
+    /// come before their children.
+    pub transactions: Vec<SerializedUndoTransaction>,
+    /// The index of the most recently applied transaction, or `None` if none
+    /// of the transactions are applied.
+    pub current: Option<usize>,
+}
+
+/// A copy of a buffer taken by [`Buffer::undo_history_snapshot`], so that its
+/// undo history can be serialized off the main thread.
+pub struct UndoHistorySnapshot(Buffer);
+
+impl UndoHistorySnapshot {
+    pub fn serialize(&self) -> Option<SerializedUndoHistory> {
+        self.0.serialize_undo_history()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SerializedUndoTransaction {
+    /// The index of the transaction this one was applied on top of.
+    pub parent: Option<usize>,
+    /// The edits that turn the parent state into this one, ordered by their
+    /// position in the parent state.
+    pub edits: Vec<SerializedUndoEdit>,
+    pub first_edit_at: SystemTime,
+    pub last_edit_at: SystemTime,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SerializedUndoEdit {
+    pub old_range: Range<usize>,
+    pub old_text: String,
+    pub new_text: String,
+}
+
+/// A run of transactions that used to be on the redo stack but was abandoned
+/// when a new transaction was started on top of an earlier state.
+#[derive(Clone, Debug)]
+struct UndoBranch {
+    parent: Option<TransactionId>,
+    /// Entries in the order they were originally applied.
+    entries: Vec<HistoryEntry>,
+}
+
+#[derive(Clone)]
 struct History {
     base_text: Rope,
     operations: TreeMap<clock::Lamport, Operation>,
     undo_stack: Vec<HistoryEntry>,
     redo_stack: Vec<HistoryEntry>,
+    branches: Vec<UndoBranch>,
     transaction_depth: usize,
     group_interval: Duration,
 }
@@ -221,6 +316,7 @@ impl History {
             operations: Default::default(),
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
+            branches: Vec::new(),
             transaction_depth: 0,
             // Don't group transactions in tests unless we opt in, because it's a footgun.
             group_interval: if cfg!(any(test, feature = "test-support")) {
@@ -275,7 +371,7 @@ impl History {
                 self.undo_stack.pop();
                 None
             } else {
-                self.redo_stack.clear();
+                self.abandon_redo_stack();
                 let entry = self.undo_stack.last_mut().unwrap();
                 entry.last_edit_at = now;
                 Some(entry)
@@ -325,6 +421,11 @@ impl History {
                 for edit_id in &entry.transaction.edit_ids {
                     last_entry.transaction.edit_ids.push(*edit_id);
                 }
+                for branch in &mut self.branches {
+                    if branch.parent == Some(entry.transaction.id) {
+                        branch.parent = Some(last_entry.transaction.id);
+                    }
+                }
             }
 
             if let Some(entry) = entries_to_merge.last_mut() {
@@ -434,7 +535,8 @@ impl History {
 
     fn forget(&mut self, transaction_id: TransactionId) -> Option<Transaction> {
         assert_eq!(self.transaction_depth, 0);
-        if let Some(entry_ix) = self
+        let parent = self.parent_of(transaction_id);
+        let transaction = if let Some(entry_ix) = self
             .undo_stack
             .iter()
             .rposition(|entry| entry.transaction.id == transaction_id)
@@ -448,7 +550,14 @@ impl History {
             Some(self.redo_stack.remove(entry_ix).transaction)
         } else {
             None
+        }?;
+
+        for branch in &mut self.branches {
+            if branch.parent == Some(transaction_id) {
+                branch.parent = parent.flatten();
+            }
         }
+        Some(transaction)
     }
 
     fn transaction(&self, transaction_id: TransactionId) -> Option<&Transaction> {
@@ -460,6 +569,12 @@ impl History {
                 self.redo_stack
                     .iter()
                     .rfind(|entry| entry.transaction.id == transaction_id)
+            })
+            .or_else(|| {
+                self.branches
+                    .iter()
+                    .flat_map(|branch| &branch.entries)
+                    .find(|entry| entry.transaction.id == transaction_id)
             })?;
         Some(&entry.transaction)
     }
@@ -509,6 +624,226 @@ impl History {
         }
         &self.undo_stack[undo_stack_start_len..]
     }
+
+    /// Preserves the redo stack as a branch of the undo tree instead of
+    /// discarding it, rooted at the entry preceding the one that was just
+    /// pushed onto the undo stack.
+    fn abandon_redo_stack(&mut self) {
+        if self.redo_stack.is_empty() {
+            return;
+        }
+
+        let parent_ix = self.undo_stack.len().checked_sub(2);
+        let parent = parent_ix.map(|ix| {
+            // Prevent the new transaction from being grouped into the state
+            // the abandoned branch forks from.
+            let parent = &mut self.undo_stack[ix];
+            parent.suppress_grouping = true;
+            parent.transaction.id
+        });
+        let entries = self.redo_stack.drain(..).rev().collect();
+        self.branches.push(UndoBranch { parent, entries });
+    }
+
+    /// The entries of the undo tree's main line, in application order. The
+    /// first `undo_stack.len()` of them are currently applied.
+    fn main_line(&self) -> impl DoubleEndedIterator<Item = &HistoryEntry> {
+        self.undo_stack.iter().chain(self.redo_stack.iter().rev())
+    }
+
+    /// Returns `Some(parent)` if the transaction is part of the undo tree.
+    fn parent_of(&self, transaction_id: TransactionId) -> Option<Option<TransactionId>> {
+        let mut parent = None;
+        for entry in self.main_line() {
+            if entry.transaction.id == transaction_id {
+                return Some(parent);
+            }
+            parent = Some(entry.transaction.id);
+        }
+
+        for branch in &self.branches {
+            let mut parent = branch.parent;
+            for entry in &branch.entries {
+                if entry.transaction.id == transaction_id {
+                    return Some(parent);
+                }
+                parent = Some(entry.transaction.id);
+            }
+        }
+        None
+    }
+
+    fn undo_tree(&self) -> Vec<UndoTreeNode> {
+        let mut nodes = Vec::new();
+        let mut parent = None;
+        for (ix, entry) in self.main_line().enumerate() {
+            nodes.push(UndoTreeNode {
+                transaction_id: entry.transaction.id,
+                parent,
+                first_edit_at: entry.first_edit_at,
+                last_edit_at: entry.last_edit_at,
+                is_applied: ix < self.undo_stack.len(),
+            });
+            parent = Some(entry.transaction.id);
+        }
+
+        for branch in &self.branches {
+            let mut parent = branch.parent;
+            for entry in &branch.entries {
+                nodes.push(UndoTreeNode {
+                    transaction_id: entry.transaction.id,
+                    parent,
+                    first_edit_at: entry.first_edit_at,
+                    last_edit_at: entry.last_edit_at,
+                    is_applied: false,
+                });
+                parent = Some(entry.transaction.id);
+            }
+        }
+
+        nodes.sort_by_key(|node| node.transaction_id);
+        nodes
+    }
+
+    /// Restructures the tree so that the given transaction is part of the
+    /// main line, swapping branches in and out of it as needed.
+    fn move_into_main_line(&mut self, transaction_id: TransactionId, main: &mut Vec<HistoryEntry>) {
+        if main
+            .iter()
+            .any(|entry| entry.transaction.id == transaction_id)
+        {
+            return;
+        }
+
+        let Some(parent) = self.branches.iter().find_map(|branch| {
+            branch
+                .entries
+                .iter()
+                .any(|entry| entry.transaction.id == transaction_id)
+                .then_some(branch.parent)
+        }) else {
+            return;
+        };
+        if let Some(parent) = parent {
+            self.move_into_main_line(parent, main);
+        }
+
+        let branch_ix = self
+            .branches
+            .iter()
+            .position(|branch| {
+                branch
+                    .entries
+                    .iter()
+                    .any(|entry| entry.transaction.id == transaction_id)
+            })
+            .unwrap();
+        let branch = self.branches.remove(branch_ix);
+        let fork_len = branch
+            .parent
+            .and_then(|parent| main.iter().position(|entry| entry.transaction.id == parent))
+            .map_or(0, |ix| ix + 1);
+        let displaced = main.split_off(fork_len);
+        if !displaced.is_empty() {
+            self.branches.push(UndoBranch {
+                parent: branch.parent,
+                entries: displaced,
+            });
+        }
+        main.extend(branch.entries);
+    }
+
+    /// Moves the current state of the undo tree to just after the given
+    /// transaction, or to the base text if `None`. Returns the transactions
+    /// that must be undone and redone, in order, to get there.
+    fn checkout(
+        &mut self,
+        transaction_id: Option<TransactionId>,
+    ) -> Option<(Vec<Transaction>, Vec<Transaction>)> {
+        assert_eq!(self.transaction_depth, 0);
+        if let Some(transaction_id) = transaction_id
+            && self.parent_of(transaction_id).is_none()
+        {
+            return None;
+        }
+
+        let old_applied = self
+            .undo_stack
+            .iter()
+            .map(|entry| entry.transaction.id)
+            .collect::<Vec<_>>();
+        let mut main = self.undo_stack.drain(..).collect::<Vec<_>>();
+        main.extend(self.redo_stack.drain(..).rev());
+
+        let applied_len = match transaction_id {
+            Some(transaction_id) => {
+                self.move_into_main_line(transaction_id, &mut main);
+                main.iter()
+                    .position(|entry| entry.transaction.id == transaction_id)
+                    .map_or(0, |ix| ix + 1)
+            }
+            None => 0,
+        };
+        self.redo_stack = main.split_off(applied_len);
+        self.redo_stack.reverse();
+        self.undo_stack = main;
+
+        let common_len = old_applied
+            .iter()
+            .zip(&self.undo_stack)
+            .take_while(|(old_id, entry)| **old_id == entry.transaction.id)
+            .count();
+        let to_undo = old_applied[common_len..]
+            .iter()
+            .rev()
+            .filter_map(|transaction_id| self.transaction(*transaction_id).cloned())
+            .collect();
+        let to_redo = self.undo_stack[common_len..]
+            .iter()
+            .map(|entry| entry.transaction.clone())
+            .collect();
+        Some((to_undo, to_redo))
+    }
+
+    /// Returns the transaction that, when checked out, moves the current state
+    /// to a sibling branch of the undo tree. Siblings are searched for starting
+    /// at the most recently applied transaction and walking up the tree.
+    fn sibling_branch(&self, forward: bool) -> Option<TransactionId> {
+        let nodes = self.undo_tree();
+        let mut current = self.undo_stack.last()?.transaction.id;
+        loop {
+            let parent = nodes
+                .iter()
+                .find(|node| node.transaction_id == current)?
+                .parent;
+            let siblings = nodes
+                .iter()
+                .filter(|node| node.parent == parent)
+                .map(|node| node.transaction_id)
+                .collect::<Vec<_>>();
+            if siblings.len() > 1 {
+                let ix = siblings.iter().position(|id| *id == current)?;
+                let sibling = if forward {
+                    siblings[(ix + 1) % siblings.len()]
+                } else {
+                    siblings[(ix + siblings.len() - 1) % siblings.len()]
+                };
+
+                // Descend to the most recent leaf below the sibling.
+                let mut leaf = sibling;
+                while let Some(child) = nodes
+                    .iter()
+                    .filter(|node| node.parent == Some(leaf))
+                    .map(|node| node.transaction_id)
+                    .max()
+                {
+                    leaf = child;
+                }
+                return Some(leaf);
+            }
+            current = parent?;
+        }
+    }
 }
 
 struct Edits<'a, D: TextDimension, F: FnMut(&FragmentSummary) -> bool> {
@@ -1444,6 +1779,323 @@ impl Buffer {
         Operation::Undo(undo)
     }
 
+    /// Returns every transaction in the undo tree, including the ones on
+    /// branches abandoned by editing after an undo, ordered by creation.
+    pub fn undo_tree(&self) -> Vec<UndoTreeNode> {
+        self.history.undo_tree()
+    }
+
+    /// Resolves an [`UndoTreeTarget`] relative to the current state, returning
+    /// `None` if there is nowhere to travel to.
+    pub fn resolve_undo_tree_target(
+        &self,
+        target: UndoTreeTarget,
+    ) -> Option<Option<TransactionId>> {
+        let current = self
+            .history
+            .undo_stack
+            .last()
+            .map(|entry| entry.transaction.id);
+        let transaction_id = match target {
+            UndoTreeTarget::Transaction(transaction_id) => {
+                if let Some(transaction_id) = transaction_id {
+                    self.history.parent_of(transaction_id)?;
+                }
+                transaction_id
+            }
+            UndoTreeTarget::NextBranch => Some(self.history.sibling_branch(true)?),
+            UndoTreeTarget::PreviousBranch => Some(self.history.sibling_branch(false)?),
+            UndoTreeTarget::Steps(steps) => {
+                let states = iter::once(None)
+                    .chain(
+                        self.history
+                            .undo_tree()
+                            .into_iter()
+                            .map(|node| Some(node.transaction_id)),
+                    )
+                    .collect::<Vec<_>>();
+                let ix = states.iter().position(|state| *state == current)?;
+                let target_ix = ix.saturating_add_signed(steps).min(states.len() - 1);
+                states[target_ix]
+            }
+            UndoTreeTarget::Earlier(duration) => {
+                let nodes = self.history.undo_tree();
+                let current_node = nodes
+                    .iter()
+                    .find(|node| Some(node.transaction_id) == current)?;
+                let target_time = current_node.last_edit_at.checked_sub(duration);
+                target_time.and_then(|target_time| {
+                    nodes
+                        .iter()
+                        .filter(|node| node.last_edit_at <= target_time)
+                        .max_by_key(|node| (node.last_edit_at, node.transaction_id))
+                        .map(|node| node.transaction_id)
+                })
+            }
+            UndoTreeTarget::Later(duration) => {
+                let nodes = self.history.undo_tree();
+                let current_node = nodes
+                    .iter()
+                    .find(|node| Some(node.transaction_id) == current);
+                let current_time = match current_node {
+                    Some(node) => node.last_edit_at,
+                    None => nodes.iter().map(|node| node.first_edit_at).min()?,
+                };
+                // A duration past the end of time travels to the newest state.
+                let target_time = current_time.checked_add(duration);
+                let target = nodes
+                    .iter()
+                    .filter(|node| target_time.is_none_or(|time| node.last_edit_at <= time))
+                    .max_by_key(|node| (node.last_edit_at, node.transaction_id))?;
+                let is_later = current_node.is_none_or(|current_node| {
+                    (target.last_edit_at, target.transaction_id)
+                        > (current_node.last_edit_at, current_node.transaction_id)
+                });
+                if !is_later {
+                    return None;
+                }
+                Some(target.transaction_id)
+            }
+        };
+        (transaction_id != current).then_some(transaction_id)
+    }
+
+    /// Returns the text the buffer would have in the given state of its undo
+    /// tree, without changing the buffer itself.
+    pub fn text_for_undo_tree_state(&self, transaction_id: Option<TransactionId>) -> Option<Rope> {
+        if let Some(transaction_id) = transaction_id {
+            self.history.parent_of(transaction_id)?;
+        }
+
+        let mut buffer = self.detached();
+        buffer.travel_undo_tree(UndoTreeTarget::Transaction(transaction_id));
+        Some(buffer.snapshot.visible_text)
+    }
+
+    /// Returns a copy of this buffer that can be edited without notifying any
+    /// of this buffer's subscribers.
+    fn detached(&self) -> Self {
+        Self {
+            snapshot: self.snapshot.clone(),
+            history: self.history.clone(),
+            deferred_ops: OperationQueue::new(),
+            deferred_replicas: HashSet::default(),
+            lamport_clock: self.lamport_clock,
+            subscriptions: Default::default(),
+            edit_id_resolvers: Default::default(),
+            wait_for_version_txs: Default::default(),
+        }
+    }
+
+    /// Undoes and redoes transactions to move to a different state in the
+    /// undo tree, returning the operations that were applied.
+    pub fn travel_undo_tree(
+        &mut self,
+        target: UndoTreeTarget,
+    ) -> Option<(UndoTreeTravel, Vec<Operation>)> {
+        let transaction_id = self.resolve_undo_tree_target(target)?;
+        let (to_undo, to_redo) = self.history.checkout(transaction_id)?;
+        let undone = to_undo.iter().map(|transaction| transaction.id).collect();
+        let redone = to_redo.iter().map(|transaction| transaction.id).collect();
+        let operations = to_undo
+            .into_iter()
+            .chain(to_redo)
+            .map(|transaction| self.undo_or_redo(transaction))
+            .collect();
+        Some((
+            UndoTreeTravel {
+                current: transaction_id,
+                undone,
+                redone,
+            },
+            operations,
+        ))
+    }
+
+    /// Copies the buffer so that its undo history can be serialized later,
+    /// which takes time proportional to the size of the history.
+    pub fn undo_history_snapshot(&self) -> UndoHistorySnapshot {
+        UndoHistorySnapshot(self.detached())
+    }
+
+    /// Captures the buffer's undo tree as plain-text edits, or returns `None`
+    /// if there is no history or a transaction is in progress.
+    pub fn serialize_undo_history(&self) -> Option<SerializedUndoHistory> {
+        if self.history.transaction_depth > 0 {
+            return None;
+        }
+        let nodes = self.history.undo_tree();
+        if nodes.is_empty() {
+            return None;
+        }
+
+        let indices = nodes
+            .iter()
+            .enumerate()
+            .map(|(ix, node)| (node.transaction_id, ix))
+            .collect::<HashMap<_, _>>();
+        let now = Instant::now();
+        let system_now = SystemTime::now();
+        let system_time = |instant: Instant| {
+            system_now
+                .checked_sub(now.saturating_duration_since(instant))
+                .unwrap_or(system_now)
+        };
+
+        let mut buffer = self.detached();
+        let transactions = nodes
+            .iter()
+            .map(|node| {
+                buffer.travel_undo_tree(UndoTreeTarget::Transaction(node.parent));
+                let parent_version = buffer.version.clone();
+                let parent_text = buffer.visible_text.clone();
+                buffer.travel_undo_tree(UndoTreeTarget::Transaction(Some(node.transaction_id)));
+                let edits = buffer
+                    .edits_since::<usize>(&parent_version)
+                    .map(|edit| SerializedUndoEdit {
+                        old_text: parent_text.chunks_in_range(edit.old.clone()).collect(),
+                        new_text: buffer.text_for_range(edit.new).collect(),
+                        old_range: edit.old,
+                    })
+                    .collect();
+                SerializedUndoTransaction {
+                    parent: node.parent.map(|parent| indices[&parent]),
+                    edits,
+                    first_edit_at: system_time(node.first_edit_at),
+                    last_edit_at: system_time(node.last_edit_at),
+                }
+            })
+            .collect();
+
+        Some(SerializedUndoHistory {
+            transactions,
+            current: self
+                .history
+                .undo_stack
+                .last()
+                .map(|entry| indices[&entry.transaction_id()]),
+        })
+    }
+
+    /// Replays a serialized undo history onto this buffer, whose text must be
+    /// the history's current state. Returns `None` and leaves the buffer
+    /// untouched if it already has history or the history doesn't match its text.
+    pub fn restore_undo_history(
+        &mut self,
+        history: &SerializedUndoHistory,
+    ) -> Option<Vec<Operation>> {
+        if self.history.transaction_depth > 0
+            || !self.history.undo_stack.is_empty()
+            || !self.history.redo_stack.is_empty()
+            || !self.history.branches.is_empty()
+        {
+            return None;
+        }
+        let is_well_formed = history
+            .transactions
+            .iter()
+            .enumerate()
+            .all(|(ix, transaction)| transaction.parent.is_none_or(|parent| parent < ix))
+            && history
+                .current
+                .is_none_or(|current| current < history.transactions.len());
+        if !is_well_formed {
+            return None;
+        }
+
+        let now = Instant::now();
+        let system_now = SystemTime::now();
+        let instant = |system_time: SystemTime| {
+            now.checked_sub(system_now.duration_since(system_time).unwrap_or_default())
+                .unwrap_or(now)
+        };
+
+        let mut buffer = self.detached();
+        let mut operations = Vec::new();
+
+        // Revert to the state before any of the transactions, without recording
+        // it in the history.
+        let mut ancestor = history.current;
+        while let Some(ix) = ancestor {
+            let transaction = &history.transactions[ix];
+            let mut delta = 0_isize;
+            let mut edits = Vec::with_capacity(transaction.edits.len());
+            for edit in &transaction.edits {
+                let start = edit.old_range.start.checked_add_signed(delta)?;
+                if start + edit.new_text.len() > buffer.len()
+                    || !buffer.contains_str_at(start, &edit.new_text)
+                {
+                    return None;
+                }
+                delta += edit.new_text.len() as isize - edit.old_range.len() as isize;
+                edits.push((start..start + edit.new_text.len(), edit.old_text.as_str()));
+            }
+            let transaction_id = buffer.start_transaction_at(now)?;
+            operations.push(buffer.edit(edits));
+            buffer.end_transaction_at(now);
+            buffer.forget_transaction(transaction_id);
+            ancestor = transaction.parent;
+        }
+
+        // Then replay each transaction on top of its parent, which recreates the
+        // abandoned branches along the way.
+        let mut transaction_ids =
+            Vec::<Option<TransactionId>>::with_capacity(history.transactions.len());
+        for transaction in &history.transactions {
+            let parent = transaction
+                .parent
+                .and_then(|parent| transaction_ids[parent]);
+            if let Some((_, travel_operations)) =
+                buffer.travel_undo_tree(UndoTreeTarget::Transaction(parent))
+            {
+                operations.extend(travel_operations);
+            }
+            if transaction.edits.is_empty() {
+                transaction_ids.push(parent);
+                continue;
+            }
+            for edit in &transaction.edits {
+                if edit.old_range.end > buffer.len()
+                    || !buffer.contains_str_at(edit.old_range.start, &edit.old_text)
+                {
+                    return None;
+                }
+            }
+
+            let transaction_id = buffer.start_transaction_at(instant(transaction.first_edit_at))?;
+            operations.push(
+                buffer.edit(
+                    transaction
+                        .edits
+                        .iter()
+                        .map(|edit| (edit.old_range.clone(), edit.new_text.as_str())),
+                ),
+            );
+            buffer.end_transaction_at(instant(transaction.last_edit_at));
+            buffer.finalize_last_transaction();
+            transaction_ids.push(Some(transaction_id));
+        }
+
+        let current = history.current.and_then(|current| transaction_ids[current]);
+        if let Some((_, travel_operations)) =
+            buffer.travel_undo_tree(UndoTreeTarget::Transaction(current))
+        {
+            operations.extend(travel_operations);
+        }
+        if buffer.text() != self.text() {
+            return None;
+        }
+
+        let edits = buffer
+            .edits_since::<usize>(&self.version)
+            .collect::<Vec<_>>();
+        self.subscriptions.publish_mut(edits);
+        self.snapshot = buffer.snapshot;
+        self.history = buffer.history;
+        self.lamport_clock = buffer.lamport_clock;
+        Some(operations)
+    }
+
     pub fn push_transaction(&mut self, transaction: Transaction, now: Instant) {
         self.history.push_transaction(transaction, now);
     }
@@ -0,0 +1,651 @@
+mod undo_tree_panel_settings;
+
+use std::{
+    ops::Range,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use collections::HashMap;
+use editor::{Editor, EditorEvent};
+use gpui::{
+    Action, App, AsyncWindowContext, Context, Entity, EventEmitter, FocusHandle, Focusable,
+    KeyContext, Pixels, Render, ScrollStrategy, SharedString, Subscription, Task,
+    UniformListScrollHandle, WeakEntity, Window, actions, uniform_list,
+};
+use language::{Buffer, TransactionId, UndoTreeNode, UndoTreeTarget};
+use menu::{Confirm, SelectFirst, SelectLast, SelectNext, SelectPrevious};
+use project::Fs;
+use settings::Settings;
+use ui::{ListItem, WithScrollbar, prelude::*};
+use workspace::{
+    Workspace,
+    dock::{DockPosition, Panel, PanelEvent},
+};
+
+use crate::undo_tree_panel_settings::UndoTreePanelSettings;
+
+actions!(
+    undo_tree_panel,
+    [
+        /// Toggles focus on the undo tree panel.
+        ToggleFocus,
+    ]
+);
+
+const UNDO_TREE_PANEL_KEY: &str = "UndoTreePanel";
+const UPDATE_DEBOUNCE: Duration = Duration::from_millis(50);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
+            workspace.toggle_panel_focus::<UndoTreePanel>(window, cx);
+        });
+    })
+    .detach();
+}
+
+/// A panel that draws the undo tree of the active editor's buffer, including
+/// the branches that were abandoned by editing after an undo, and previews the
+/// changes made by each state.
+pub struct UndoTreePanel {
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    active_editor: Option<ActiveEditor>,
+    entries: Vec<UndoTreeEntry>,
+    selected_ix: Option<usize>,
+    preview: Option<Preview>,
+    /// Whether the panel is open. The undo tree is only laid out while it is.
+    active: bool,
+    entries_update_pending: bool,
+    entries_update_task: Task<()>,
+    _workspace_subscription: Subscription,
+}
+
+struct ActiveEditor {
+    editor: Entity<Editor>,
+    buffer: Entity<Buffer>,
+    _subscription: Subscription,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct UndoTreeEntry {
+    /// The transaction whose state this entry represents, or `None` for the
+    /// buffer's base text.
+    transaction_id: Option<TransactionId>,
+    parent: Option<TransactionId>,
+    /// The order in which the state was created, starting at 1.
+    sequence: usize,
+    depth: usize,
+    last_edit_at: Option<Instant>,
+    is_applied: bool,
+    is_current: bool,
+}
+
+struct Preview {
+    transaction_id: Option<TransactionId>,
+    diff: Option<SharedString>,
+    _task: Task<()>,
+}
+
+impl UndoTreePanel {
+    pub async fn load(
+        workspace: WeakEntity<Workspace>,
+        mut cx: AsyncWindowContext,
+    ) -> anyhow::Result<Entity<Self>> {
+        workspace.update_in(&mut cx, |workspace, window, cx| {
+            let fs = workspace.app_state().fs.clone();
+            let workspace_entity = cx.entity();
+            cx.new(|cx| {
+                let workspace_subscription = cx.subscribe_in(
+                    &workspace_entity,
+                    window,
+                    |this: &mut Self, workspace, event, window, cx| {
+                        if let workspace::Event::ActiveItemChanged = event {
+                            this.update_active_editor(workspace.read(cx), window, cx);
+                        }
+                    },
+                );
+
+                let mut this = Self {
+                    fs,
+                    focus_handle: cx.focus_handle(),
+                    scroll_handle: UniformListScrollHandle::new(),
+                    active_editor: None,
+                    entries: Vec::new(),
+                    selected_ix: None,
+                    preview: None,
+                    active: false,
+                    entries_update_pending: false,
+                    entries_update_task: Task::ready(()),
+                    _workspace_subscription: workspace_subscription,
+                };
+                this.update_active_editor(workspace, window, cx);
+                this
+            })
+        })
+    }
+
+    fn update_active_editor(
+        &mut self,
+        workspace: &Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(editor) = workspace
+            .active_item(cx)
+            .and_then(|item| item.act_as::<Editor>(cx))
+            .filter(|editor| editor.read(cx).mode().is_full())
+        else {
+            return;
+        };
+        if self
+            .active_editor
+            .as_ref()
+            .is_some_and(|active_editor| active_editor.editor == editor)
+        {
+            return;
+        }
+
+        // Only singleton buffers have an undo tree.
+        let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() else {
+            self.active_editor = None;
+            self.entries.clear();
+            self.selected_ix = None;
+            self.preview = None;
+            cx.notify();
+            return;
+        };
+
+        let subscription = cx.subscribe_in(
+            &editor,
+            window,
+            |this, _, event: &EditorEvent, _, cx| match event {
+                EditorEvent::Edited { .. }
+                | EditorEvent::BufferEdited
+                | EditorEvent::TransactionUndone { .. } => this.schedule_entries_update(cx),
+                _ => {}
+            },
+        );
+        self.active_editor = Some(ActiveEditor {
+            editor,
+            buffer,
+            _subscription: subscription,
+        });
+        self.selected_ix = None;
+        self.preview = None;
+        self.update_entries(cx);
+    }
+
+    /// Updates the entries once edits settle, rather than on every keystroke.
+    fn schedule_entries_update(&mut self, cx: &mut Context<Self>) {
+        // A pending update reads the latest undo tree when it fires.
+        if !self.active || self.entries_update_pending {
+            return;
+        }
+        self.entries_update_pending = true;
+        self.entries_update_task = cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(UPDATE_DEBOUNCE).await;
+            this.update(cx, |this, cx| this.update_entries(cx)).ok();
+        });
+    }
+
+    fn update_entries(&mut self, cx: &mut Context<Self>) {
+        self.entries_update_pending = false;
+        if !self.active {
+            return;
+        }
+        let Some(active_editor) = self.active_editor.as_ref() else {
+            return;
+        };
+        let nodes = active_editor.buffer.read(cx).undo_tree();
+        let entries = layout_undo_tree(&nodes);
+        if entries == self.entries {
+            // The preview is dropped while the panel is hidden.
+            self.update_preview(cx);
+            return;
+        }
+
+        let selected_transaction = self
+            .selected_ix
+            .and_then(|ix| self.entries.get(ix))
+            .map(|entry| entry.transaction_id);
+        self.entries = entries;
+        self.selected_ix = selected_transaction
+            .and_then(|transaction_id| {
+                self.entries
+                    .iter()
+                    .position(|entry| entry.transaction_id == transaction_id)
+            })
+            .or_else(|| self.entries.iter().position(|entry| entry.is_current));
+        self.preview = None;
+        self.update_preview(cx);
+        cx.notify();
+    }
+
+    fn select_ix(&mut self, ix: usize, cx: &mut Context<Self>) {
+        if ix >= self.entries.len() {
+            return;
+        }
+        self.selected_ix = Some(ix);
+        self.scroll_handle
+            .scroll_to_item(ix, ScrollStrategy::Nearest);
+        self.update_preview(cx);
+        cx.notify();
+    }
+
+    fn update_preview(&mut self, cx: &mut Context<Self>) {
+        let Some(active_editor) = self.active_editor.as_ref() else {
+            return;
+        };
+        let Some(entry) = self.selected_ix.and_then(|ix| self.entries.get(ix)) else {
+            self.preview = None;
+            return;
+        };
+        if self
+            .preview
+            .as_ref()
+            .is_some_and(|preview| preview.transaction_id == entry.transaction_id)
+        {
+            return;
+        }
+
+        let transaction_id = entry.transaction_id;
+        let (old_text, new_text) = {
+            let buffer = active_editor.buffer.read(cx);
+            match transaction_id {
+                Some(transaction_id) => (
+                    buffer.text_for_undo_tree_state(entry.parent),
+                    buffer.text_for_undo_tree_state(Some(transaction_id)),
+                ),
+                None => (None, None),
+            }
+        };
+        let task = cx.spawn(async move |this, cx| {
+            let diff = match (old_text, new_text) {
+                (Some(old_text), Some(new_text)) => Some(
+                    cx.background_spawn(async move {
+                        language::unified_diff(&old_text.to_string(), &new_text.to_string())
+                    })
+                    .await,
+                ),
+                _ => None,
+            };
+            this.update(cx, |this, cx| {
+                if let Some(preview) = this.preview.as_mut()
+                    && preview.transaction_id == transaction_id
+                {
+                    preview.diff = diff.map(SharedString::from);
+                    cx.notify();
+                }
+            })
+            .ok();
+        });
+        self.preview = Some(Preview {
+            transaction_id,
+            diff: None,
+            _task: task,
+        });
+    }
+
+    fn travel_to_selected(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(active_editor) = self.active_editor.as_ref() else {
+            return;
+        };
+        let Some(entry) = self.selected_ix.and_then(|ix| self.entries.get(ix)) else {
+            return;
+        };
+        let target = UndoTreeTarget::Transaction(entry.transaction_id);
+        active_editor.editor.update(cx, |editor, cx| {
+            editor.travel_undo_tree(target, window, cx);
+        });
+        self.update_entries(cx);
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        let ix = self.selected_ix.map_or(0, |ix| ix + 1);
+        self.select_ix(ix, cx);
+    }
+
+    fn select_previous(&mut self, _: &SelectPrevious, _: &mut Window, cx: &mut Context<Self>) {
+        let ix = self.selected_ix.map_or(0, |ix| ix.saturating_sub(1));
+        self.select_ix(ix, cx);
+    }
+
+    fn select_first(&mut self, _: &SelectFirst, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_ix(0, cx);
+    }
+
+    fn select_last(&mut self, _: &SelectLast, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.entries.len().checked_sub(1) {
+            self.select_ix(ix, cx);
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        self.travel_to_selected(window, cx);
+    }
+
+    fn dispatch_context(&self) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("UndoTreePanel");
+        dispatch_context.add("menu");
+        dispatch_context
+    }
+
+    fn render_entries(&self, range: Range<usize>, cx: &mut Context<Self>) -> Vec<AnyElement> {
+        let now = Instant::now();
+        range
+            .filter_map(|ix| {
+                let entry = self.entries.get(ix)?;
+                let (marker, marker_color) = if entry.is_current {
+                    ("◉", Color::Accent)
+                } else if entry.is_applied {
+                    ("●", Color::Default)
+                } else {
+                    ("○", Color::Muted)
+                };
+                let label = match entry.transaction_id {
+                    Some(_) => format!("#{}", entry.sequence),
+                    None => "Original".to_string(),
+                };
+                let age = entry
+                    .last_edit_at
+                    .map(|last_edit_at| format_age(now.saturating_duration_since(last_edit_at)));
+
+                Some(
+                    ListItem::new(ix)
+                        .indent_level(entry.depth)
+                        .indent_step_size(px(12.))
+                        .spacing(ui::ListItemSpacing::Sparse)
+                        .toggle_state(self.selected_ix == Some(ix))
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .child(Label::new(marker).color(marker_color))
+                                .child(
+                                    Label::new(label)
+                                        .when(!entry.is_applied, |label| label.color(Color::Muted)),
+                                ),
+                        )
+                        .end_slot::<Label>(
+                            age.map(|age| {
+                                Label::new(age).size(LabelSize::Small).color(Color::Muted)
+                            }),
+                        )
+                        .on_click(
+                            cx.listener(move |this, event: &gpui::ClickEvent, window, cx| {
+                                this.select_ix(ix, cx);
+                                if event.click_count() > 1 {
+                                    this.travel_to_selected(window, cx);
+                                }
+                            }),
+                        )
+                        .into_any_element(),
+                )
+            })
+            .collect()
+    }
+
+    fn render_preview(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let content = match self.preview.as_ref().map(|preview| &preview.diff) {
+            Some(Some(diff)) if diff.is_empty() => Label::new("No changes")
+                .color(Color::Muted)
+                .into_any_element(),
+            Some(Some(diff)) => v_flex()
+                .children(diff.lines().map(|line| {
+                    let color = if line.starts_with("@@") {
+                        Color::Muted
+                    } else if line.starts_with('+') {
+                        Color::Created
+                    } else if line.starts_with('-') {
+                        Color::Deleted
+                    } else {
+                        Color::Default
+                    };
+                    Label::new(SharedString::from(line.to_string()))
+                        .buffer_font(cx)
+                        .size(LabelSize::Small)
+                        .color(color)
+                }))
+                .into_any_element(),
+            Some(None) | None => Label::new("Select a state to preview its changes")
+                .color(Color::Muted)
+                .into_any_element(),
+        };
+
+        v_flex()
+            .id("undo-tree-preview")
+            .h_1_2()
+            .p_2()
+            .border_t_1()
+            .border_color(cx.theme().colors().border_variant)
+            .overflow_y_scroll()
+            .child(content)
+    }
+}
+
+/// Orders the nodes of an undo tree depth-first, with the base text first,
+/// indenting every branch but the oldest one under its parent.
+fn layout_undo_tree(nodes: &[UndoTreeNode]) -> Vec<UndoTreeEntry> {
+    let mut children = HashMap::<Option<TransactionId>, Vec<&UndoTreeNode>>::default();
+    let mut sequences = HashMap::default();
+    for (ix, node) in nodes.iter().enumerate() {
+        children.entry(node.parent).or_default().push(node);
+        sequences.insert(node.transaction_id, ix + 1);
+    }
+    let current = nodes
+        .iter()
+        .filter(|node| node.is_applied)
+        .max_by_key(|node| node.transaction_id)
+        .map(|node| node.transaction_id);
+
+    let mut entries = vec![UndoTreeEntry {
+        transaction_id: None,
+        parent: None,
+        sequence: 0,
+        depth: 0,
+        last_edit_at: None,
+        is_applied: true,
+        is_current: current.is_none(),
+    }];
+    let mut stack = Vec::new();
+    if let Some(roots) = children.get(&None) {
+        stack.extend(
+            roots
+                .iter()
+                .enumerate()
+                .rev()
+                .map(|(ix, node)| (*node, ix.min(1))),
+        );
+    }
+    while let Some((node, depth)) = stack.pop() {
+        let sequence = sequences[&node.transaction_id];
+        entries.push(UndoTreeEntry {
+            transaction_id: Some(node.transaction_id),
+            parent: node.parent,
+            sequence,
+            depth,
+            last_edit_at: Some(node.last_edit_at),
+            is_applied: node.is_applied,
+            is_current: current == Some(node.transaction_id),
+        });
+        if let Some(node_children) = children.get(&Some(node.transaction_id)) {
+            stack.extend(
+                node_children
+                    .iter()
+                    .enumerate()
+                    .rev()
+                    .map(|(ix, child)| (*child, depth + ix.min(1))),
+            );
+        }
+    }
+    entries
+}
+
+fn format_age(age: Duration) -> String {
+    let seconds = age.as_secs();
+    if seconds < 60 {
+        format!("{seconds}s ago")
+    } else if seconds < 60 * 60 {
+        format!("{}m ago", seconds / 60)
+    } else if seconds < 60 * 60 * 24 {
+        format!("{}h ago", seconds / (60 * 60))
+    } else {
+        format!("{}d ago", seconds / (60 * 60 * 24))
+    }
+}
+
+impl Render for UndoTreePanel {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let content = if self.active_editor.is_none() {
+            v_flex()
+                .size_full()
+                .items_center()
+                .justify_center()
+                .child(Label::new("Open a file to see its undo tree").color(Color::Muted))
+                .into_any_element()
+        } else {
+            v_flex()
+                .size_full()
+                .child(
+                    div()
+                        .flex_1()
+                        .min_h_0()
+                        .child(
+                            uniform_list(
+                                "undo-tree-entries",
+                                self.entries.len(),
+                                cx.processor(|this, range: Range<usize>, _, cx| {
+                                    this.render_entries(range, cx)
+                                }),
+                            )
+                            .size_full()
+                            .track_scroll(&self.scroll_handle),
+                        )
+                        .vertical_scrollbar_for(&self.scroll_handle, window, cx),
+                )
+                .child(self.render_preview(cx))
+                .into_any_element()
+        };
+
+        v_flex()
+            .id("undo-tree-panel")
+            .size_full()
+            .key_context(self.dispatch_context())
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::confirm))
+            .child(content)
+    }
+}
+
+impl Focusable for UndoTreePanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for UndoTreePanel {}
+
+impl Panel for UndoTreePanel {
+    fn persistent_name() -> &'static str {
+        "Undo Tree Panel"
+    }
+
+    fn panel_key() -> &'static str {
+        UNDO_TREE_PANEL_KEY
+    }
+
+    fn position(&self, _: &Window, cx: &App) -> DockPosition {
+        UndoTreePanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        settings::update_settings_file(self.fs.clone(), cx, move |settings, _| {
+            settings.undo_tree_panel.get_or_insert_default().dock = Some(position.into())
+        });
+    }
+
+    fn default_size(&self, _: &Window, cx: &App) -> Pixels {
+        UndoTreePanelSettings::get_global(cx).default_width
+    }
+
+    fn icon(&self, _: &Window, cx: &App) -> Option<IconName> {
+        UndoTreePanelSettings::get_global(cx)
+            .button
+            .then_some(IconName::HistoryRerun)
+    }
+
+    fn icon_tooltip(&self, _: &Window, _: &App) -> Option<&'static str> {
+        Some("Undo Tree Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn activation_priority(&self) -> u32 {
+        8
+    }
+
+    fn set_active(&mut self, active: bool, _: &mut Window, cx: &mut Context<Self>) {
+        self.active = active;
+        if active {
+            self.update_entries(cx);
+        } else {
+            self.entries_update_pending = false;
+            self.entries_update_task = Task::ready(());
+            self.preview = None;
+        }
+    }
+
+    fn hide_button_setting(&self, _: &App) -> Option<workspace::HideStatusItem> {
+        Some(workspace::HideStatusItem::new(|settings| {
+            settings.undo_tree_panel.get_or_insert_default().button = Some(false);
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use language::{BufferId, ReplicaId, TextBuffer};
+
+    #[test]
+    fn test_layout_undo_tree() {
+        let mut buffer = TextBuffer::new(ReplicaId::LOCAL, BufferId::new(1).unwrap(), "");
+        let transaction_1 = buffer.start_transaction().unwrap();
+        buffer.edit([(0..0, "a")]);
+        buffer.end_transaction();
+        let transaction_2 = buffer.start_transaction().unwrap();
+        buffer.edit([(1..1, "b")]);
+        buffer.end_transaction();
+        buffer.undo();
+        let transaction_3 = buffer.start_transaction().unwrap();
+        buffer.edit([(1..1, "c")]);
+        buffer.end_transaction();
+
+        let entries = layout_undo_tree(&buffer.undo_tree());
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| (entry.transaction_id, entry.depth, entry.is_current))
+                .collect::<Vec<_>>(),
+            [
+                (None, 0, false),
+                (Some(transaction_1), 0, false),
+                (Some(transaction_2), 0, false),
+                (Some(transaction_3), 1, true),
+            ]
+        );
+    }
+}
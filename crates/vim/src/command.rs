@@ -1760,6 +1760,28 @@ fn generate_commands(_: &App) -> Vec<VimCommand> {
         VimCommand::new(("delm", "arks"), ArgumentRequired)
             .bang(DeleteMarks::AllLocal)
             .args(|_, args| Some(DeleteMarks::Marks(args).boxed_clone())),
+        VimCommand::new(
+            ("ea", "rlier"),
+            editor::actions::UndoEarlier {
+                steps: None,
+                seconds: None,
+            },
+        )
+        .args(|_, args| {
+            let (steps, seconds) = parse_undo_tree_travel(&args)?;
+            Some(editor::actions::UndoEarlier { steps, seconds }.boxed_clone())
+        }),
+        VimCommand::new(
+            ("lat", "er"),
+            editor::actions::UndoLater {
+                steps: None,
+                seconds: None,
+            },
+        )
+        .args(|_, args| {
+            let (steps, seconds) = parse_undo_tree_travel(&args)?;
+            Some(editor::actions::UndoLater { steps, seconds }.boxed_clone())
+        }),
         VimCommand::new(("sor", "t"), SortLinesCaseSensitive)
             .range(select_range)
             .default_range(CommandRange::buffer()),
@@ -1824,6 +1846,26 @@ fn select_range(action: Box<dyn Action>, range: &CommandRange) -> Option<Box<dyn
     )
 }
 
+/// Parses the argument of `:earlier` and `:later`, which is either a count of
+/// undo states (`:earlier 3`) or a duration (`:earlier 10s`, `5m`, `2h`, `1d`).
+fn parse_undo_tree_travel(args: &str) -> Option<(Option<usize>, Option<u64>)> {
+    let args = args.trim();
+    if let Ok(steps) = args.parse::<usize>() {
+        return Some((Some(steps), None));
+    }
+
+    let (amount, unit) = args.split_at(args.find(|c: char| !c.is_ascii_digit())?);
+    let amount = amount.parse::<u64>().ok()?;
+    let seconds = match unit {
+        "s" => amount,
+        "m" => amount.saturating_mul(60),
+        "h" => amount.saturating_mul(60 * 60),
+        "d" => amount.saturating_mul(60 * 60 * 24),
+        _ => return None,
+    };
+    Some((None, Some(seconds)))
+}
+
 fn wrap_count(action: Box<dyn Action>, range: &CommandRange) -> Option<Box<dyn Action>> {
     range.as_count().map(|count| {
         WithCount {
@@ -2489,6 +2531,7 @@ impl ShellExec {
                     show_command: false,
                     show_rerun: false,
                     save: SaveStrategy::default(),
+                    problem_matchers: Vec::new(),
                 };
 
                 let task_status = workspace.spawn_in_terminal(spawn_in_terminal, window, cx);
@@ -2770,6 +2813,33 @@ mod test {
         assert_eq!(fs.load(path).await.unwrap().replace("\r\n", "\n"), "@@\n");
     }
 
+    #[gpui::test]
+    async fn test_command_earlier_later(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.set_state("ˇabc", Mode::Normal);
+
+        cx.simulate_keystrokes("i 1 escape");
+        cx.simulate_keystrokes("u");
+        cx.simulate_keystrokes("i 2 escape");
+        assert_eq!(cx.buffer_text(), "2abc");
+
+        // `u` only walks the current branch, while `g -` and `g +` visit
+        // every state in the order it was created.
+        cx.simulate_keystrokes("g -");
+        assert_eq!(cx.buffer_text(), "1abc");
+        cx.simulate_keystrokes("g -");
+        assert_eq!(cx.buffer_text(), "abc");
+        cx.simulate_keystrokes("2 g +");
+        assert_eq!(cx.buffer_text(), "2abc");
+
+        cx.simulate_keystrokes(": e a r l i e r enter");
+        assert_eq!(cx.buffer_text(), "1abc");
+        cx.simulate_keystrokes(": l a t e r space 1 enter");
+        assert_eq!(cx.buffer_text(), "2abc");
+        cx.simulate_keystrokes(": e a r l i e r space 1 h enter");
+        assert_eq!(cx.buffer_text(), "abc");
+    }
+
     #[gpui::test]
     async fn test_command_read(cx: &mut TestAppContext) {
         let mut cx = VimTestContext::new(cx, true).await;
@@ -98,6 +98,10 @@ actions!(
         Redo,
         /// Undoes all changes to the most recently changed line.
         UndoLastLine,
+        /// Moves to an earlier state in the undo tree, across branches.
+        UndoEarlier,
+        /// Moves to a later state in the undo tree, across branches.
+        UndoLater,
         /// Go to tab page (with count support).
         GoToTab,
         /// Go to previous tab page (with count support).
@@ -269,6 +273,34 @@ pub(crate) fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
             }
         });
     });
+    Vim::action(editor, cx, |vim, _: &UndoEarlier, window, cx| {
+        let steps = Vim::take_count(cx);
+        Vim::take_forced_motion(cx);
+        vim.update_editor(cx, |_, editor, cx| {
+            editor.undo_earlier(
+                &editor::actions::UndoEarlier {
+                    steps,
+                    seconds: None,
+                },
+                window,
+                cx,
+            );
+        });
+    });
+    Vim::action(editor, cx, |vim, _: &UndoLater, window, cx| {
+        let steps = Vim::take_count(cx);
+        Vim::take_forced_motion(cx);
+        vim.update_editor(cx, |_, editor, cx| {
+            editor.undo_later(
+                &editor::actions::UndoLater {
+                    steps,
+                    seconds: None,
+                },
+                window,
+                cx,
+            );
+        });
+    });
     Vim::action(editor, cx, |vim, _: &UndoLastLine, window, cx| {
         Vim::take_forced_motion(cx);
         vim.update_editor(cx, |vim, editor, cx| {
@@ -51,6 +51,7 @@ impl Workspace {
         }
     }
 
+    /// Schedules a task once all tasks from its `depends_on` finished successfully.
     pub fn schedule_resolved_task(
         self: &mut Workspace,
         task_source_kind: TaskSourceKind,
@@ -59,6 +60,48 @@ impl Workspace {
         window: &mut Window,
         cx: &mut Context<Workspace>,
     ) {
+        if resolved_task.original_task().depends_on.is_empty() {
+            self.schedule_resolved_task_without_dependencies(
+                task_source_kind,
+                resolved_task,
+                omit_history,
+                window,
+                cx,
+            );
+        } else if let Some(scheduler) = self.task_dependency_scheduler.clone() {
+            scheduler.schedule(
+                self,
+                task_source_kind,
+                resolved_task,
+                omit_history,
+                window,
+                cx,
+            );
+        } else {
+            log::warn!(
+                "Cannot schedule task `{}` without running its dependencies",
+                resolved_task.resolved_label
+            );
+        }
+    }
+
+    /// Schedules a task right away, even if it has dependencies that haven't run.
+    pub fn schedule_resolved_task_without_dependencies(
+        self: &mut Workspace,
+        task_source_kind: TaskSourceKind,
+        resolved_task: ResolvedTask,
+        omit_history: bool,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        if resolved_task.original_task().is_compound() {
+            log::warn!(
+                "Cannot schedule compound task `{}` without running its dependencies",
+                resolved_task.resolved_label
+            );
+            return;
+        }
+
         let spawn_in_terminal = resolved_task.resolved.clone();
         if !omit_history {
             if let Some(debugger_provider) = self.debugger_provider.as_ref() {
@@ -283,7 +326,7 @@ impl Workspace {
 mod tests {
     use super::*;
     use crate::{
-        TerminalProvider,
+        TaskDependencyScheduler, TerminalProvider,
         item::test::{TestItem, TestProjectItem},
         register_serializable_item,
     };
@@ -424,6 +467,44 @@ mod tests {
         item
     }
 
+    #[gpui::test]
+    async fn test_schedule_resolved_task_with_dependencies(cx: &mut TestAppContext) {
+        let (fixture, cx) = create_fixture(cx, SaveStrategy::None).await;
+        let template = TaskTemplate {
+            label: "test".to_string(),
+            command: "echo".to_string(),
+            depends_on: vec!["build".to_string()],
+            ..Default::default()
+        };
+        let task = template
+            .resolve_task("test", &task::TaskContext::default())
+            .unwrap();
+
+        // Without a way to run its dependencies, the task isn't run at all.
+        fixture.workspace.update_in(cx, |workspace, window, cx| {
+            workspace.schedule_resolved_task(
+                TaskSourceKind::UserInput,
+                task.clone(),
+                false,
+                window,
+                cx,
+            );
+        });
+        cx.executor().run_until_parked();
+        assert_eq!(*fixture.dirty_before_spawn.lock(), None);
+
+        let scheduled: Arc<Mutex<Vec<String>>> = Arc::default();
+        fixture.workspace.update_in(cx, |workspace, window, cx| {
+            workspace.set_task_dependency_scheduler(TestTaskDependencyScheduler {
+                scheduled: scheduled.clone(),
+            });
+            workspace.schedule_resolved_task(TaskSourceKind::UserInput, task, false, window, cx);
+        });
+        cx.executor().run_until_parked();
+        assert_eq!(*scheduled.lock(), ["test"]);
+        assert_eq!(*fixture.dirty_before_spawn.lock(), Some(true));
+    }
+
     #[gpui::test]
     async fn test_save_for_task_all(cx: &mut TestAppContext) {
         let (fixture, cx) = create_fixture(cx, SaveStrategy::All).await;
@@ -487,6 +568,33 @@ mod tests {
         assert!(cx.read(|cx| inactive.read(cx).is_dirty));
     }
 
+    struct TestTaskDependencyScheduler {
+        scheduled: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl TaskDependencyScheduler for TestTaskDependencyScheduler {
+        fn schedule(
+            &self,
+            workspace: &mut Workspace,
+            task_source_kind: TaskSourceKind,
+            resolved_task: ResolvedTask,
+            omit_history: bool,
+            window: &mut Window,
+            cx: &mut Context<Workspace>,
+        ) {
+            self.scheduled
+                .lock()
+                .push(resolved_task.resolved_label.clone());
+            workspace.schedule_resolved_task_without_dependencies(
+                task_source_kind,
+                resolved_task,
+                omit_history,
+                window,
+                cx,
+            );
+        }
+    }
+
     struct TestTerminalProvider {
         item: Entity<TestItem>,
         dirty_before_spawn: Arc<Mutex<Option<bool>>>,
@@ -7,6 +7,10 @@ use gpui::{AnyElement, Modifiers, WeakEntity};
 use markdown_preview::{
     OpenPreview as MarkdownOpenPreview, OpenPreviewToTheSide as MarkdownOpenPreviewToTheSide,
     markdown_preview_view::MarkdownPreviewView,
+    mermaid::{
+        OpenPreview as MermaidOpenPreview, OpenPreviewToTheSide as MermaidOpenPreviewToTheSide,
+    },
+    mermaid_preview_view::MermaidPreviewView,
 };
 use svg_preview::{
     OpenPreview as SvgOpenPreview, OpenPreviewToTheSide as SvgOpenPreviewToTheSide,
@@ -21,6 +25,7 @@ use super::QuickActionBar;
 enum PreviewType {
     Markdown,
     Svg,
+    Mermaid,
     Csv,
 }
 
@@ -41,6 +46,10 @@ impl QuickActionBar {
                 } else if SvgPreviewView::resolve_active_item_as_svg_buffer(workspace, cx).is_some()
                 {
                     preview_type = Some(PreviewType::Svg);
+                } else if MermaidPreviewView::resolve_active_item_as_mermaid_buffer(workspace, cx)
+                    .is_some()
+                {
+                    preview_type = Some(PreviewType::Mermaid);
                 } else if cx.has_flag::<TabularDataPreviewFeatureFlag>()
                     && CsvPreviewView::resolve_active_item_as_csv_editor(workspace, cx).is_some()
                 {
@@ -67,6 +76,13 @@ impl QuickActionBar {
                     Box::new(SvgOpenPreviewToTheSide) as Box<dyn gpui::Action>,
                     &svg_preview::OpenPreview as &dyn gpui::Action,
                 ),
+                PreviewType::Mermaid => (
+                    "toggle-mermaid-preview",
+                    "Preview Mermaid Diagram",
+                    Box::new(MermaidOpenPreview) as Box<dyn gpui::Action>,
+                    Box::new(MermaidOpenPreviewToTheSide) as Box<dyn gpui::Action>,
+                    &MermaidOpenPreview as &dyn gpui::Action,
+                ),
                 PreviewType::Csv => (
                     "toggle-csv-preview",
                     "Preview CSV",
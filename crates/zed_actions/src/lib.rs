@@ -372,6 +372,18 @@ pub mod command_palette {
     );
 }
 
+pub mod hex_editor {
+    use gpui::actions;
+
+    actions!(
+        hex_editor,
+        [
+            /// Opens the current file in a hex editor.
+            OpenInHexEditor,
+        ]
+    );
+}
+
 pub mod text_finder {
     use gpui::actions;
 
@@ -852,6 +864,20 @@ pub mod preview {
             ]
         );
     }
+
+    pub mod mermaid {
+        use gpui::actions;
+
+        actions!(
+            mermaid,
+            [
+                /// Opens a Mermaid diagram preview for the current file.
+                OpenPreview,
+                /// Opens a Mermaid diagram preview in a split pane.
+                OpenPreviewToTheSide,
+            ]
+        );
+    }
 }
 
 pub mod agents_sidebar {